@@ -0,0 +1,56 @@
+//! Frontier-heavy chain on a grid map whose units are numbered in random order,
+//! before and after `Map::reorder_hilbert`.
+//!
+//! Run with `cargo bench --bench hilbert_order`.  Set `GRID` to change the grid size.
+
+use std::time::{Duration, Instant};
+
+use geo::{MultiPolygon, polygon};
+use rand::seq::SliceRandom;
+
+use openmander::{Map, Plan};
+
+const DISTRICTS: u32 = 8;
+const ITERATIONS: usize = 20_000;
+const RUNS: usize = 3;
+
+/// An `n` x `n` grid of unit squares, listed in random order.
+fn shuffled_grid(n: usize) -> Map {
+    let mut cells = (0..n * n).collect::<Vec<_>>();
+    cells.shuffle(&mut rand::rng());
+
+    let geoms = cells.iter()
+        .map(|&cell| {
+            let (x, y) = ((cell % n) as f64, (cell / n) as f64);
+            MultiPolygon(vec![polygon![(x: x, y: y), (x: x + 1.0, y: y), (x: x + 1.0, y: y + 1.0), (x: x, y: y + 1.0)]])
+        })
+        .collect();
+    Map::from_parts(geoms, None, vec![("pop".into(), vec![1.0; n * n])], &[]).unwrap()
+}
+
+/// Best time over `RUNS` runs of a tabu balancing chain from a random start.
+fn time_chain(map: &Map) -> Duration {
+    (0..RUNS)
+        .map(|_| {
+            let mut plan = Plan::new(map.clone(), DISTRICTS).unwrap();
+            plan.randomize().unwrap();
+            let start = Instant::now();
+            plan.tabu_balance("pop", ITERATIONS, 10, 0.5, 16).unwrap();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    let n = std::env::var("GRID").ok().and_then(|s| s.parse().ok()).unwrap_or(150);
+    let mut map = shuffled_grid(n);
+
+    let shuffled = time_chain(&map);
+    map.reorder_hilbert().unwrap();
+    let ordered = time_chain(&map);
+
+    println!("{} units, {} districts, {} iterations (best of {})", n * n, DISTRICTS, ITERATIONS, RUNS);
+    println!("  input order:   {shuffled:>10.2?}");
+    println!("  hilbert order: {ordered:>10.2?}  ({:.2}x)", shuffled.as_secs_f64() / ordered.as_secs_f64());
+}
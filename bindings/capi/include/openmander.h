@@ -0,0 +1,142 @@
+#ifndef OPENMANDER_H
+#define OPENMANDER_H
+
+/* Generated by cbindgen from bindings/capi; do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Result of a fallible call.
+typedef enum OmStatus {
+  OM_STATUS_OK = 0,
+  // The call failed; see `om_last_error`.
+  OM_STATUS_ERROR = 1,
+} OmStatus;
+
+// A map loaded from a pack, shared by the plans created from it.
+typedef struct OmMap OmMap;
+
+// A districting plan over a map.
+typedef struct OmPlan OmPlan;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Message of the last failed call on this thread, or NULL if none has failed.
+// The string is owned by the library and valid until the next failing call on this thread.
+const char *om_last_error(void);
+
+// Load a map from a pack directory (format auto-detected). Returns NULL on failure.
+//
+// # Safety
+// `pack_dir` must be a NUL-terminated string.
+struct OmMap *om_map_load(const char *pack_dir);
+
+// Release a map. Plans created from it stay valid.
+//
+// # Safety
+// `map` must be NULL or a map that has not been freed yet.
+void om_map_free(struct OmMap *map);
+
+// Number of blocks in the map's base layer, which is the length of a plan's assignment array.
+//
+// # Safety
+// `map` must be a live map; `out` must be valid for a write.
+enum OmStatus om_map_num_blocks(const struct OmMap *map, size_t *out);
+
+// Create an empty plan with `num_districts` districts (all blocks unassigned). Returns NULL on failure.
+//
+// # Safety
+// `map` must be a live map.
+struct OmPlan *om_plan_new(const struct OmMap *map,
+                           uint32_t num_districts);
+
+// Copy a plan, e.g. to keep the current state of a chain. Returns NULL on failure.
+//
+// # Safety
+// `plan` must be a live plan.
+struct OmPlan *om_plan_clone(const struct OmPlan *plan);
+
+// Release a plan.
+//
+// # Safety
+// `plan` must be NULL or a plan that has not been freed yet.
+void om_plan_free(struct OmPlan *plan);
+
+// Number of districts in the plan (excluding unassigned 0), or 0 if `plan` is NULL.
+//
+// # Safety
+// `plan` must be NULL or a live plan.
+uint32_t om_plan_num_districts(const struct OmPlan *plan);
+
+// Copy the district of each block (0 = unassigned) into `out`, in base-layer block order.
+// `len` must equal the map's block count (see `om_map_num_blocks`).
+//
+// # Safety
+// `plan` must be a live plan; `out` must be valid for `len` writes.
+enum OmStatus om_plan_get_assignments(const struct OmPlan *plan, uint32_t *out, size_t len);
+
+// Set the district of each block from `assignments`, in base-layer block order.
+//
+// # Safety
+// `plan` must be a live plan; `assignments` must be valid for `len` reads.
+enum OmStatus om_plan_set_assignments(struct OmPlan *plan, const uint32_t *assignments, size_t len);
+
+// Randomize the plan into contiguous districts.
+//
+// # Safety
+// `plan` must be a live plan.
+enum OmStatus om_plan_randomize(struct OmPlan *plan);
+
+// Equalize a weight series (e.g. `"T_20_CENS_Total"`) across districts to within `tolerance`.
+//
+// # Safety
+// `plan` must be a live plan; `series` must be a NUL-terminated string.
+enum OmStatus om_plan_equalize(struct OmPlan *plan,
+                               const char *series,
+                               double tolerance,
+                               size_t max_iter);
+
+// Score the plan on a metric given as JSON, e.g. `{"type": "population_deviation", "pop_series": "T_20_CENS_Total"}`.
+//
+// # Safety
+// `plan` must be a live plan; `metric_json` must be a NUL-terminated string; `out` must be valid for a write.
+enum OmStatus om_plan_metric_score(const struct OmPlan *plan,
+                                   const char *metric_json,
+                                   double *out);
+
+// Score the plan on a weighted objective given as JSON (`{"metrics": [...], "weights": [...]}`).
+//
+// # Safety
+// `plan` must be a live plan; `objective_json` must be a NUL-terminated string; `out` must be valid for a write.
+enum OmStatus om_plan_objective_score(const struct OmPlan *plan,
+                                      const char *objective_json,
+                                      double *out);
+
+// Take one ReCom step: merge a random pair of adjacent districts and split them again,
+// balancing the halves by `pop_series`.
+// The recombined districts are written to `out_a` and `out_b` when those are not NULL.
+//
+// # Safety
+// `plan` must be a live plan; `pop_series` must be a NUL-terminated string;
+// `out_a` and `out_b` must each be NULL or valid for a write.
+enum OmStatus om_plan_recom_step(struct OmPlan *plan,
+                                 const char *pop_series,
+                                 uint32_t *out_a,
+                                 uint32_t *out_b);
+
+// Anneal the plan with a config given as JSON (the `AnnealConfig` format used by `Plan.anneal`).
+//
+// # Safety
+// `plan` must be a live plan; `config_json` must be a NUL-terminated string.
+enum OmStatus om_plan_anneal(struct OmPlan *plan, const char *config_json);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* OPENMANDER_H */
@@ -0,0 +1,160 @@
+use std::{
+    cell::RefCell,
+    ffi::{CStr, CString, c_char},
+    panic::{AssertUnwindSafe, catch_unwind},
+    ptr,
+};
+
+use anyhow::{Context, Result, anyhow};
+
+/// Result of a fallible call.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OmStatus {
+    Ok = 0,
+    /// The call failed; see `om_last_error`.
+    Error = 1,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Message of the last failed call on this thread, or NULL if none has failed.
+/// The string is owned by the library and valid until the next failing call on this thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn om_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr()))
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', " ")).expect("interior NULs were replaced");
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// Run `work`, recording its error (or panic) as the thread's last error.
+fn guard<T>(work: impl FnOnce() -> Result<T>) -> Option<T> {
+    match catch_unwind(AssertUnwindSafe(work)) {
+        Ok(Ok(value)) => Some(value),
+        Ok(Err(error)) => { set_last_error(format!("{error:#}")); None }
+        Err(panic) => {
+            let reason = panic.downcast_ref::<&str>().copied()
+                .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("unknown panic");
+            set_last_error(format!("[capi] Panicked: {reason}"));
+            None
+        }
+    }
+}
+
+/// Guard a call returning a status.
+pub(crate) fn status(work: impl FnOnce() -> Result<()>) -> OmStatus {
+    guard(work).map_or(OmStatus::Error, |()| OmStatus::Ok)
+}
+
+/// Guard a call returning a new handle, or NULL on failure.
+pub(crate) fn handle<T>(work: impl FnOnce() -> Result<T>) -> *mut T {
+    guard(work).map_or(ptr::null_mut(), |value| Box::into_raw(Box::new(value)))
+}
+
+/// Borrow a handle passed in by the caller.
+///
+/// # Safety
+/// `ptr` must be NULL or a live handle of type `T` created by this library.
+pub(crate) unsafe fn borrow<'a, T>(ptr: *const T, name: &str) -> Result<&'a T> {
+    unsafe { ptr.as_ref() }.ok_or_else(|| anyhow!("[capi] {name} is NULL"))
+}
+
+/// Mutably borrow a handle passed in by the caller.
+///
+/// # Safety
+/// `ptr` must be NULL or a live handle of type `T`, not in use by another call.
+pub(crate) unsafe fn borrow_mut<'a, T>(ptr: *mut T, name: &str) -> Result<&'a mut T> {
+    unsafe { ptr.as_mut() }.ok_or_else(|| anyhow!("[capi] {name} is NULL"))
+}
+
+/// Release a handle created by [`handle`].
+///
+/// # Safety
+/// `ptr` must be NULL or a handle of type `T` that has not been freed yet.
+pub(crate) unsafe fn free<T>(ptr: *mut T) {
+    if !ptr.is_null() { drop(unsafe { Box::from_raw(ptr) }) }
+}
+
+/// Read a NUL-terminated UTF-8 string argument.
+///
+/// # Safety
+/// `ptr` must be NULL or point to a NUL-terminated string.
+pub(crate) unsafe fn str_arg<'a>(ptr: *const c_char, name: &str) -> Result<&'a str> {
+    anyhow::ensure!(!ptr.is_null(), "[capi] {name} is NULL");
+    unsafe { CStr::from_ptr(ptr) }.to_str().with_context(|| format!("[capi] {name} is not valid UTF-8"))
+}
+
+/// Parse a JSON string argument.
+///
+/// # Safety
+/// `ptr` must be NULL or point to a NUL-terminated string.
+pub(crate) unsafe fn json_arg<T: serde::de::DeserializeOwned>(ptr: *const c_char, name: &str) -> Result<T> {
+    let json = unsafe { str_arg(ptr, name) }?;
+    serde_json::from_str(json).with_context(|| format!("[capi] Invalid {name}"))
+}
+
+/// Write `value` through an output pointer.
+///
+/// # Safety
+/// `out` must be NULL or valid for a write of `T`.
+pub(crate) unsafe fn write_out<T>(out: *mut T, value: T, name: &str) -> Result<()> {
+    anyhow::ensure!(!out.is_null(), "[capi] {name} is NULL");
+    unsafe { out.write(value) };
+    Ok(())
+}
+
+/// Message of the thread's last failed call.
+#[cfg(test)]
+pub(crate) fn last_error_message() -> String {
+    let message = om_last_error();
+    assert!(!message.is_null(), "no call has failed on this thread");
+    unsafe { CStr::from_ptr(message) }.to_str().unwrap().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_are_kept_per_thread() {
+        std::thread::spawn(|| assert!(om_last_error().is_null())).join().unwrap();
+
+        assert_eq!(status(|| anyhow::bail!("[capi] first")), OmStatus::Error);
+        assert_eq!(status(|| Ok(())), OmStatus::Ok);
+        assert_eq!(last_error_message(), "[capi] first");
+
+        std::thread::spawn(|| {
+            assert_eq!(status(|| anyhow::bail!("[capi] second")), OmStatus::Error);
+            assert_eq!(last_error_message(), "[capi] second");
+        }).join().unwrap();
+        assert_eq!(last_error_message(), "[capi] first");
+    }
+
+    #[test]
+    fn panics_become_errors() {
+        assert!(handle(|| -> Result<u32> { panic!("boom") }).is_null());
+        assert_eq!(last_error_message(), "[capi] Panicked: boom");
+
+        set_last_error("a\0b".to_string());
+        assert_eq!(last_error_message(), "a b");
+    }
+
+    #[test]
+    fn arguments_are_checked() {
+        let err = unsafe { str_arg(ptr::null(), "name") }.unwrap_err();
+        assert_eq!(err.to_string(), "[capi] name is NULL");
+        let err = unsafe { str_arg(c"\xff".as_ptr(), "name") }.unwrap_err();
+        assert_eq!(err.to_string(), "[capi] name is not valid UTF-8");
+        let err = unsafe { json_arg::<Vec<u32>>(c"[1,".as_ptr(), "list") }.unwrap_err();
+        assert_eq!(err.to_string(), "[capi] Invalid list");
+        let err = unsafe { write_out(ptr::null_mut(), 1u32, "out") }.unwrap_err();
+        assert_eq!(err.to_string(), "[capi] out is NULL");
+        unsafe { free(ptr::null_mut::<u32>()) };
+    }
+}
@@ -0,0 +1,14 @@
+//! C ABI for openmander, for wrappers in languages without a Rust binding (R, Julia, ...).
+//!
+//! Maps and plans are opaque handles created by `om_*_new`/`om_*_load` and released with the
+//! matching `om_*_free`. Fallible calls return an [`OmStatus`]; on failure the message is
+//! available from [`om_last_error`] on the same thread. Metrics, objectives, and annealing
+//! configs are passed as JSON, in the same format used by the Python bindings and server.
+
+mod common;
+mod map;
+mod plan;
+
+pub use common::{OmStatus, om_last_error};
+pub use map::{OmMap, om_map_free, om_map_load, om_map_num_blocks};
+pub use plan::*;
@@ -0,0 +1,89 @@
+use std::{ffi::c_char, path::Path, sync::Arc};
+
+use crate::common::*;
+
+/// A map loaded from a pack, shared by the plans created from it.
+pub struct OmMap(pub(crate) Arc<openmander_core::Map>);
+
+/// Load a map from a pack directory (format auto-detected). Returns NULL on failure.
+///
+/// # Safety
+/// `pack_dir` must be a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn om_map_load(pack_dir: *const c_char) -> *mut OmMap {
+    handle(|| {
+        let path = unsafe { str_arg(pack_dir, "pack_dir") }?;
+        Ok(OmMap(Arc::new(openmander_core::Map::read_from_pack(Path::new(path))?)))
+    })
+}
+
+/// Release a map. Plans created from it stay valid.
+///
+/// # Safety
+/// `map` must be NULL or a map that has not been freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn om_map_free(map: *mut OmMap) {
+    unsafe { free(map) }
+}
+
+/// Number of blocks in the map's base layer, which is the length of a plan's assignment array.
+///
+/// # Safety
+/// `map` must be a live map; `out` must be valid for a write.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn om_map_num_blocks(map: *const OmMap, out: *mut usize) -> OmStatus {
+    status(|| {
+        let map = unsafe { borrow(map, "map") }?;
+        unsafe { write_out(out, map.0.base()?.len(), "out") }
+    })
+}
+
+/// Load a pack of a row of `n` unit squares with one person in each.
+#[cfg(test)]
+pub(crate) fn load_row(n: usize) -> *mut OmMap {
+    let geoms = (0..n)
+        .map(|k| geo::MultiPolygon(vec![geo::Rect::new((k as f64, 0.), (k as f64 + 1., 1.)).to_polygon()]))
+        .collect();
+    let map = openmander_core::Map::from_parts(geoms, None, vec![("pop".into(), vec![1.0; n])], &[]).unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    map.write_to_pack(dir.path()).unwrap();
+    let path = std::ffi::CString::new(dir.path().to_str().unwrap()).unwrap();
+    let map = unsafe { om_map_load(path.as_ptr()) };
+    assert!(!map.is_null(), "{}", last_error_message());
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ptr;
+
+    use super::*;
+
+    #[test]
+    fn bad_packs_return_null() {
+        assert!(unsafe { om_map_load(ptr::null()) }.is_null());
+        assert_eq!(last_error_message(), "[capi] pack_dir is NULL");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = std::ffi::CString::new(dir.path().join("missing").to_str().unwrap()).unwrap();
+        assert!(unsafe { om_map_load(path.as_ptr()) }.is_null());
+        assert!(!last_error_message().is_empty());
+
+        unsafe { om_map_free(ptr::null_mut()) };
+    }
+
+    #[test]
+    fn block_counts_check_their_pointers() {
+        let mut count = 0;
+        assert_eq!(unsafe { om_map_num_blocks(ptr::null(), &mut count) }, OmStatus::Error);
+        assert_eq!(last_error_message(), "[capi] map is NULL");
+
+        let map = load_row(3);
+        assert_eq!(unsafe { om_map_num_blocks(map, ptr::null_mut()) }, OmStatus::Error);
+        assert_eq!(last_error_message(), "[capi] out is NULL");
+        assert_eq!(unsafe { om_map_num_blocks(map, &mut count) }, OmStatus::Ok);
+        assert_eq!(count, 3);
+        unsafe { om_map_free(map) };
+    }
+}
@@ -0,0 +1,215 @@
+use std::{ffi::c_char, sync::Arc};
+
+use openmander_core::{AnnealConfig, Metric, Objective};
+
+use crate::{OmMap, common::*};
+
+/// A districting plan over a map.
+pub struct OmPlan {
+    inner: openmander_core::Plan,
+    map: Arc<openmander_core::Map>,
+}
+
+/// Create an empty plan with `num_districts` districts (all blocks unassigned). Returns NULL on failure.
+///
+/// # Safety
+/// `map` must be a live map.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn om_plan_new(map: *const OmMap, num_districts: u32) -> *mut OmPlan {
+    handle(|| {
+        let map = unsafe { borrow(map, "map") }?;
+        Ok(OmPlan { inner: openmander_core::Plan::new(map.0.clone(), num_districts)?, map: map.0.clone() })
+    })
+}
+
+/// Copy a plan, e.g. to keep the current state of a chain. Returns NULL on failure.
+///
+/// # Safety
+/// `plan` must be a live plan.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn om_plan_clone(plan: *const OmPlan) -> *mut OmPlan {
+    handle(|| {
+        let plan = unsafe { borrow(plan, "plan") }?;
+        Ok(OmPlan { inner: plan.inner.clone(), map: plan.map.clone() })
+    })
+}
+
+/// Release a plan.
+///
+/// # Safety
+/// `plan` must be NULL or a plan that has not been freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn om_plan_free(plan: *mut OmPlan) {
+    unsafe { free(plan) }
+}
+
+/// Number of districts in the plan (excluding unassigned 0), or 0 if `plan` is NULL.
+///
+/// # Safety
+/// `plan` must be NULL or a live plan.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn om_plan_num_districts(plan: *const OmPlan) -> u32 {
+    unsafe { plan.as_ref() }.map_or(0, |plan| plan.inner.num_districts())
+}
+
+/// Copy the district of each block (0 = unassigned) into `out`, in base-layer block order.
+/// `len` must equal the map's block count (see `om_map_num_blocks`).
+///
+/// # Safety
+/// `plan` must be a live plan; `out` must be valid for `len` writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn om_plan_get_assignments(plan: *const OmPlan, out: *mut u32, len: usize) -> OmStatus {
+    status(|| {
+        let assignments = unsafe { borrow(plan, "plan") }?.inner.get_assignments_vec()?;
+        anyhow::ensure!(len == assignments.len(), "[capi] Expected {} assignments, got len {len}", assignments.len());
+        anyhow::ensure!(!out.is_null(), "[capi] out is NULL");
+        unsafe { std::slice::from_raw_parts_mut(out, len) }.copy_from_slice(&assignments);
+        Ok(())
+    })
+}
+
+/// Set the district of each block from `assignments`, in base-layer block order.
+///
+/// # Safety
+/// `plan` must be a live plan; `assignments` must be valid for `len` reads.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn om_plan_set_assignments(plan: *mut OmPlan, assignments: *const u32, len: usize) -> OmStatus {
+    status(|| {
+        let plan = unsafe { borrow_mut(plan, "plan") }?;
+        let expected = plan.map.base()?.len();
+        anyhow::ensure!(len == expected, "[capi] Expected {expected} assignments, got len {len}");
+        anyhow::ensure!(!assignments.is_null(), "[capi] assignments is NULL");
+        let assignments = unsafe { std::slice::from_raw_parts(assignments, len) };
+        plan.inner.set_assignment_bulk(assignments, false).map(|_| ())
+    })
+}
+
+/// Randomize the plan into contiguous districts.
+///
+/// # Safety
+/// `plan` must be a live plan.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn om_plan_randomize(plan: *mut OmPlan) -> OmStatus {
+    status(|| unsafe { borrow_mut(plan, "plan") }?.inner.randomize())
+}
+
+/// Equalize a weight series (e.g. `"T_20_CENS_Total"`) across districts to within `tolerance`.
+///
+/// # Safety
+/// `plan` must be a live plan; `series` must be a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn om_plan_equalize(plan: *mut OmPlan, series: *const c_char, tolerance: f64, max_iter: usize) -> OmStatus {
+    status(|| {
+        let plan = unsafe { borrow_mut(plan, "plan") }?;
+        plan.inner.equalize(unsafe { str_arg(series, "series") }?, tolerance, max_iter)
+    })
+}
+
+/// Score the plan on a metric given as JSON, e.g. `{"type": "population_deviation", "pop_series": "T_20_CENS_Total"}`.
+///
+/// # Safety
+/// `plan` must be a live plan; `metric_json` must be a NUL-terminated string; `out` must be valid for a write.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn om_plan_metric_score(plan: *const OmPlan, metric_json: *const c_char, out: *mut f64) -> OmStatus {
+    status(|| {
+        let plan = unsafe { borrow(plan, "plan") }?;
+        let metric: Metric = unsafe { json_arg(metric_json, "metric_json") }?;
+        unsafe { write_out(out, plan.inner.compute_metric_score(&metric), "out") }
+    })
+}
+
+/// Score the plan on a weighted objective given as JSON (`{"metrics": [...], "weights": [...]}`).
+///
+/// # Safety
+/// `plan` must be a live plan; `objective_json` must be a NUL-terminated string; `out` must be valid for a write.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn om_plan_objective_score(plan: *const OmPlan, objective_json: *const c_char, out: *mut f64) -> OmStatus {
+    status(|| {
+        let plan = unsafe { borrow(plan, "plan") }?;
+        let objective: Objective = unsafe { json_arg(objective_json, "objective_json") }?;
+        unsafe { write_out(out, plan.inner.compute_objective(&objective), "out") }
+    })
+}
+
+/// Take one ReCom step: merge a random pair of adjacent districts and split them again,
+/// balancing the halves by `pop_series`.
+/// The recombined districts are written to `out_a` and `out_b` when those are not NULL.
+///
+/// # Safety
+/// `plan` must be a live plan; `pop_series` must be a NUL-terminated string;
+/// `out_a` and `out_b` must each be NULL or valid for a write.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn om_plan_recom_step(plan: *mut OmPlan, pop_series: *const c_char, out_a: *mut u32, out_b: *mut u32) -> OmStatus {
+    status(|| {
+        let plan = unsafe { borrow_mut(plan, "plan") }?;
+        let (a, b) = plan.inner.recombine_random(unsafe { str_arg(pop_series, "pop_series") }?)?;
+        if !out_a.is_null() { unsafe { out_a.write(a) } }
+        if !out_b.is_null() { unsafe { out_b.write(b) } }
+        Ok(())
+    })
+}
+
+/// Anneal the plan with a config given as JSON (the `AnnealConfig` format used by `Plan.anneal`).
+///
+/// # Safety
+/// `plan` must be a live plan; `config_json` must be a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn om_plan_anneal(plan: *mut OmPlan, config_json: *const c_char) -> OmStatus {
+    status(|| {
+        let plan = unsafe { borrow_mut(plan, "plan") }?;
+        let config: AnnealConfig = unsafe { json_arg(config_json, "config_json") }?;
+        plan.inner.anneal_with_config(&config)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ptr;
+
+    use super::*;
+    use crate::{map::load_row, om_map_free};
+
+    #[test]
+    fn null_handles_are_errors() {
+        assert!(unsafe { om_plan_new(ptr::null(), 2) }.is_null());
+        assert_eq!(last_error_message(), "[capi] map is NULL");
+        assert!(unsafe { om_plan_clone(ptr::null()) }.is_null());
+        assert_eq!(last_error_message(), "[capi] plan is NULL");
+        assert_eq!(unsafe { om_plan_randomize(ptr::null_mut()) }, OmStatus::Error);
+        assert_eq!(last_error_message(), "[capi] plan is NULL");
+        assert_eq!(unsafe { om_plan_num_districts(ptr::null()) }, 0);
+        unsafe { om_plan_free(ptr::null_mut()) };
+    }
+
+    #[test]
+    fn bad_arguments_are_errors() {
+        let map = load_row(4);
+        let plan = unsafe { om_plan_new(map, 2) };
+        unsafe { om_map_free(map) };
+        assert_eq!(unsafe { om_plan_num_districts(plan) }, 2);
+
+        let mut assignments = [0u32; 4];
+        assert_eq!(unsafe { om_plan_get_assignments(plan, assignments.as_mut_ptr(), 3) }, OmStatus::Error);
+        assert_eq!(last_error_message(), "[capi] Expected 4 assignments, got len 3");
+        assert_eq!(unsafe { om_plan_get_assignments(plan, ptr::null_mut(), 4) }, OmStatus::Error);
+        assert_eq!(last_error_message(), "[capi] out is NULL");
+        assert_eq!(unsafe { om_plan_set_assignments(plan, ptr::null(), 4) }, OmStatus::Error);
+        assert_eq!(last_error_message(), "[capi] assignments is NULL");
+
+        let mut score = 0.0;
+        assert_eq!(unsafe { om_plan_metric_score(plan, c"{\"type\": \"nope\"}".as_ptr(), &mut score) }, OmStatus::Error);
+        assert!(last_error_message().starts_with("[capi] Invalid metric_json: "));
+        assert_eq!(unsafe { om_plan_equalize(plan, ptr::null(), 0.01, 10) }, OmStatus::Error);
+        assert_eq!(last_error_message(), "[capi] series is NULL");
+        assert_eq!(unsafe { om_plan_anneal(plan, c"{".as_ptr()) }, OmStatus::Error);
+        assert!(last_error_message().starts_with("[capi] Invalid config_json"));
+
+        assert_eq!(unsafe { om_plan_set_assignments(plan, [1, 1, 2, 2].as_ptr(), 4) }, OmStatus::Ok);
+        let copy = unsafe { om_plan_clone(plan) };
+        unsafe { om_plan_free(plan) };
+        assert_eq!(unsafe { om_plan_get_assignments(copy, assignments.as_mut_ptr(), 4) }, OmStatus::Ok);
+        assert_eq!(assignments, [1, 1, 2, 2]);
+        assert_eq!(unsafe { om_plan_metric_score(copy, c"{\"type\": \"population_deviation\", \"pop_series\": \"pop\"}".as_ptr(), &mut score) }, OmStatus::Ok);
+        unsafe { om_plan_free(copy) };
+    }
+}
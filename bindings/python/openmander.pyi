@@ -0,0 +1,636 @@
+# Type stubs for the openmander extension module. Keep in sync with bindings/python/src.
+from typing import TYPE_CHECKING, Literal, Optional, Sequence, TypedDict
+
+if TYPE_CHECKING:
+    import pandas
+
+Layer = Literal["state", "county", "tract", "group", "vtd", "block"]
+Projection = Literal["equirectangular", "mercator", "albers", "albers-usa"]
+PaletteName = Literal["okabe-ito", "set2", "dark2", "paired", "set3"]
+HullName = Literal["dissolve", "convex", "concave"]
+Precision = Literal["f64", "f32"]
+PackFormat = Literal["parquet", "json"]
+Aggregate = Literal["mean", "min"]
+
+class PlanMetadata(TypedDict):
+    name: Optional[str]
+    author: Optional[str]
+    description: Optional[str]
+    source: Optional[str]
+    parent: Optional[str]
+    created: Optional[int]
+    tool_version: Optional[str]
+
+class GeometryStats(TypedDict):
+    geo_id: str
+    idx: int
+    num_polygons: int
+    holes_per_polygon: list[int]
+    is_exterior: bool
+
+class MetricInfo(TypedDict):
+    name: str
+    params: list[str]
+    description: str
+
+class AlgorithmInfo(TypedDict):
+    name: str
+    kind: str
+    description: str
+
+class StateInfo(TypedDict):
+    code: str
+    fips: str
+    name: str
+    congress: int
+    sldu: Optional[int]
+    sldl: Optional[int]
+
+class ExportStamp(TypedDict):
+    plan_id: str
+    tool: str
+    sha256: str
+
+class MemoryFootprint(TypedDict):
+    geometry: int
+    tables: int
+    adjacency: int
+    indices: int
+    total: int
+
+class ColumnStats(TypedDict):
+    min: float
+    max: float
+    sum: float
+    q1: float
+    median: float
+    q3: float
+    histogram: list[int]
+
+class Map:
+    """A multi-layer geographic map loaded from a pack."""
+    def __init__(self, pack_dir: str, precision: Precision = "f64") -> None: ...
+    @classmethod
+    def from_pack(cls, pack_dir: str, format: Optional[PackFormat] = None, precision: Precision = "f64") -> Map: ...
+    def to_pack(self, pack_dir: str, format: Optional[PackFormat] = None) -> None: ...
+    def geometry_stats(self, layer: Optional[Layer] = None) -> list[GeometryStats]: ...
+    def clip_to(self, geo_ids: list[str], layer: Optional[Layer] = None) -> Map: ...
+    def clip_to_geojson(self, path: str) -> Map: ...
+    def crosswalk_vintage(self, crosswalk_path: str, target: Map, series: list[str]) -> Map: ...
+    def merge(self, other: Map) -> Map: ...
+    def attach_geometries(self, path_or_url: str) -> None: ...
+    def apply_boundary_changes(self, changes_path: str, layer: Layer) -> int: ...
+    def set_unit_column(self, layer: Layer, name: str, values: Sequence[float]) -> None: ...
+    def check_integrity(self, series: Optional[list[str]] = None) -> list[str]: ...
+    def memory_footprint(self, layer: Optional[Layer] = None) -> MemoryFootprint: ...
+    def column_stats(self) -> dict[str, dict[str, ColumnStats]]: ...
+    def to_svg(
+        self,
+        path: str,
+        layer: Optional[Layer] = None,
+        series: Optional[str] = None,
+        group_by: Optional[Layer] = None,
+        projection: Projection = "equirectangular",
+        annotate: bool = False,
+        title: Optional[str] = None,
+        description: Optional[str] = None,
+    ) -> None: ...
+    def to_shapefile(self, path: str, layer: Layer = "block", fields: Optional[dict[str, str]] = None) -> None: ...
+    def _repr_html_(self) -> str: ...
+
+class Metric:
+    """A single metric used in a multi-objective optimization."""
+    @staticmethod
+    def population_deviation(pop_series: str) -> Metric: ...
+    @staticmethod
+    def population_deviation_absolute(pop_series: str) -> Metric: ...
+    @staticmethod
+    def population_deviation_smooth(pop_series: str) -> Metric: ...
+    @staticmethod
+    def population_deviation_sharp(pop_series: str) -> Metric: ...
+    @staticmethod
+    def compactness_polsby_popper(aggregate: Aggregate = "mean") -> Metric: ...
+    @staticmethod
+    def compactness_schwartzberg(aggregate: Aggregate = "mean") -> Metric: ...
+    @staticmethod
+    def compactness_reock(aggregate: Aggregate = "mean") -> Metric: ...
+    @staticmethod
+    def compactness_convex_hull(aggregate: Aggregate = "mean") -> Metric: ...
+    @staticmethod
+    def competitiveness_binary(dem_series: str, rep_series: str, threshold: float) -> Metric: ...
+    @staticmethod
+    def competitiveness_quadratic(dem_series: str, rep_series: str, threshold: float) -> Metric: ...
+    @staticmethod
+    def competitiveness_gaussian(dem_series: str, rep_series: str, sigma: float) -> Metric: ...
+    @staticmethod
+    def proportionality(dem_series: str, rep_series: str) -> Metric: ...
+
+class Objective:
+    """A weighted or lexicographic combination of metrics."""
+    def __init__(
+        self,
+        metrics: list[Metric],
+        weights: Optional[list[float]] = None,
+        priorities: Optional[list[int]] = None,
+        resolution: float = 1e-3,
+    ) -> None: ...
+    @property
+    def num_metrics(self) -> int: ...
+    @property
+    def priorities(self) -> Optional[list[int]]: ...
+    @property
+    def weights(self) -> list[float]: ...
+    def set_weights(self, weights: list[float]) -> None: ...
+    def to_json(self) -> str: ...
+    @classmethod
+    def from_json(cls, json: str) -> Objective: ...
+
+class ObjectiveSpec:
+    """Structured form of an Objective, serializing to the same JSON/TOML."""
+    metrics: list[Metric]
+    weights: Optional[list[float]]
+    priorities: Optional[list[int]]
+    resolution: float
+    def __init__(
+        self,
+        metrics: list[Metric],
+        weights: Optional[list[float]] = None,
+        priorities: Optional[list[int]] = None,
+        resolution: float = 1e-3,
+    ) -> None: ...
+    def to_objective(self) -> Objective: ...
+    @classmethod
+    def from_objective(cls, objective: Objective) -> ObjectiveSpec: ...
+    def to_json(self) -> str: ...
+    @classmethod
+    def from_json(cls, json: str) -> ObjectiveSpec: ...
+    def to_toml(self) -> str: ...
+    @classmethod
+    def from_toml(cls, toml: str) -> ObjectiveSpec: ...
+
+class StoppingRules:
+    """Rules for ending a chain early. The time limit ends the run; the other rules end the current phase."""
+    no_improvement_iters: Optional[int]
+    target_score: Optional[float]
+    time_limit_secs: Optional[float]
+    plateau_metric: Optional[Metric]
+    plateau_window: int
+    plateau_tolerance: float
+    def __init__(
+        self,
+        no_improvement_iters: Optional[int] = None,
+        target_score: Optional[float] = None,
+        time_limit_secs: Optional[float] = None,
+        plateau_metric: Optional[Metric] = None,
+        plateau_window: int = 10000,
+        plateau_tolerance: float = 1e-6,
+    ) -> None: ...
+    def to_json(self) -> str: ...
+    @classmethod
+    def from_json(cls, json: str) -> StoppingRules: ...
+
+class Constraints:
+    """Hard constraints a chain never breaks; moves that break one are rejected before scoring."""
+    balance: list[tuple[str, Optional[float], Optional[float]]]
+    contiguous: bool
+    preserve: list[tuple[str, list[str]]]
+    def __init__(
+        self,
+        balance: Optional[list[tuple[str, Optional[float], Optional[float]]]] = None,
+        contiguous: bool = False,
+        preserve: Optional[list[tuple[str, list[str]]]] = None,
+    ) -> None: ...
+    def to_json(self) -> str: ...
+    @classmethod
+    def from_json(cls, json: str) -> Constraints: ...
+
+class ChainConfig:
+    """Structured form of a multi-phase annealing run, serializing to the same JSON/TOML as the Rust AnnealConfig."""
+    objectives: list[ObjectiveSpec]
+    max_iter: int
+    phase_start_probs: list[float]
+    phase_end_probs: list[Optional[float]]
+    phase_cooling_rates: list[float]
+    init_temp: float
+    early_stop_iters: int
+    temp_search_batch_size: int
+    batch_size: int
+    stop: StoppingRules
+    proposals: list[tuple[str, float]]
+    constraints: Constraints
+    profile: bool
+    def __init__(
+        self,
+        objectives: list[ObjectiveSpec],
+        max_iter: int,
+        phase_start_probs: list[float],
+        phase_end_probs: list[Optional[float]],
+        phase_cooling_rates: list[float],
+        init_temp: float = 1.0,
+        early_stop_iters: int = 100000,
+        temp_search_batch_size: int = 1000,
+        batch_size: int = 1000,
+        stop: Optional[StoppingRules] = None,
+        proposals: Optional[list[tuple[str, float]]] = None,
+        constraints: Optional[Constraints] = None,
+        profile: bool = False,
+    ) -> None: ...
+    def to_json(self) -> str: ...
+    @classmethod
+    def from_json(cls, json: str) -> ChainConfig: ...
+    def to_toml(self) -> str: ...
+    @classmethod
+    def from_toml(cls, toml: str) -> ChainConfig: ...
+    def save(self, path: str) -> None: ...
+    @classmethod
+    def load(cls, path: str) -> ChainConfig: ...
+
+class RunProfile(TypedDict):
+    steps: int
+    invalid: int
+    proposal_secs: float
+    contiguity_secs: float
+    metrics_secs: float
+    acceptance_secs: float
+    other_secs: float
+
+class PartisanTests(TypedDict):
+    mean_median: float
+    mean_median_ci: tuple[float, float]
+    mean_median_p_value: float
+    lopsided_wins: Optional[float]
+    lopsided_wins_p_value: Optional[float]
+
+class ElectionResults(TypedDict):
+    contest: str
+    dem_share: float
+    dem_seats: int
+    dem_votes: list[float]
+    rep_votes: list[float]
+    dem_shares: list[float]
+
+SuggestedMove = TypedDict("SuggestedMove", {
+    "block": str,
+    "from": int,
+    "to": int,
+    "score": float,
+    "improvement": float,
+})
+
+class Contribution(TypedDict):
+    label: str
+    districts: list[int]
+    value: float
+    contribution: float
+
+class ScoreExplanation(TypedDict):
+    score: float
+    contributions: list[Contribution]
+
+class BoxplotRank(TypedDict):
+    rank: int
+    min: float
+    q1: float
+    median: float
+    q3: float
+    max: float
+    enacted: Optional[float]
+
+class EnsembleBoxplot(TypedDict):
+    contest: str
+    num_plans: int
+    ranks: list[BoxplotRank]
+
+class StepRecord(TypedDict):
+    index: int
+    phase: int
+    accepted: bool
+    score: float
+    metrics: dict[str, float]
+
+class StepBuffer:
+    """In-memory ring buffer of the most recent chain steps."""
+    def __init__(self, capacity: int = 10000) -> None: ...
+    @property
+    def capacity(self) -> int: ...
+    def __len__(self) -> int: ...
+    def records(self) -> list[StepRecord]: ...
+    def clear(self) -> None: ...
+
+class Plan:
+    """A districting plan: an assignment of every block of a map to a district."""
+    def __init__(self, map: Map, num_districts: int) -> None: ...
+    @staticmethod
+    def from_assignment_column(map: Map, column: str) -> Plan: ...
+    def crosswalk_vintage(self, crosswalk_path: str, map: Map, pop_series: str) -> Plan: ...
+    def copy(self) -> Plan: ...
+    def __copy__(self) -> Plan: ...
+    def fork(self) -> Plan: ...
+
+    # Metadata, audit log, and history
+    def metadata(self) -> PlanMetadata: ...
+    def set_metadata(
+        self,
+        name: Optional[str] = None,
+        author: Optional[str] = None,
+        description: Optional[str] = None,
+        parent: Optional[str] = None,
+    ) -> None: ...
+    def enable_audit_log(self) -> None: ...
+    def disable_audit_log(self) -> None: ...
+    def audit_log_jsonl(self) -> Optional[str]: ...
+    def write_audit_log(self, path: str) -> None: ...
+    def enable_history(self, capacity: int = ...) -> None: ...
+    def disable_history(self) -> None: ...
+    def undo(self) -> bool: ...
+    def redo(self) -> bool: ...
+    def can_undo(self) -> bool: ...
+    def can_redo(self) -> bool: ...
+
+    # Assignments and scores
+    def num_districts(self) -> int: ...
+    def memory_footprint(self) -> int: ...
+    def assignments(self) -> dict[str, int]: ...
+    def set_assignments(self, assignments: dict[str, int]) -> None: ...
+    def set_assignment_bulk(self, assignments: Sequence[int], check_contiguity: bool = False) -> list[int]: ...
+    def series(self) -> list[str]: ...
+    def district_totals(self, series: str) -> list[float]: ...
+    def compute_metric(self, metric: Metric) -> list[float]: ...
+    def compute_metric_score(self, metric: Metric) -> float: ...
+    def best_single_moves(self, metric: Metric, k: int = 5) -> list[SuggestedMove]: ...
+    def explain_metric(self, metric: Metric) -> ScoreExplanation: ...
+    def explain_county_splits(self) -> ScoreExplanation: ...
+    def explain_efficiency_gap(self, contest: str) -> ScoreExplanation: ...
+    def compute_objective(self, objective: Objective) -> float: ...
+
+    # Algorithms
+    def randomize(self) -> None: ...
+    def equalize(self, series: str, tolerance: float, max_iter: int) -> None: ...
+    def rebalance(self, series: str, tolerance: float = 0.01) -> bool: ...
+    def refine_pair_exact(self, a: int, b: int, series: str, tolerance: float = 0.01) -> bool: ...
+    def anneal_balance(self, series: str, max_iter: int, initial_temp: float, final_temp: float, boundary_factor: float) -> None: ...
+    def anneal(
+        self,
+        objectives: list[Objective],
+        max_iter: int,
+        phase_start_probs: list[float],
+        phase_end_probs: list[Optional[float]],
+        phase_cooling_rates: list[float],
+        init_temp: float = 1.0,
+        early_stop_iters: int = 100000,
+        temp_search_batch_size: int = 1000,
+        batch_size: int = 1000,
+    ) -> None: ...
+    def anneal_with_config(
+        self,
+        config: ChainConfig,
+        steps: Optional[StepBuffer] = None,
+        log_path: Optional[str] = None,
+        every: int = 1,
+    ) -> None: ...
+    def constraint_violations(self, constraints: Constraints) -> list[str]: ...
+    def run_profile(self) -> Optional[RunProfile]: ...
+    def partisan_tests(
+        self,
+        dem_series: str,
+        rep_series: str,
+        samples: int = 1000,
+        seed: Optional[int] = None,
+    ) -> PartisanTests: ...
+    def contests(self) -> list[tuple[str, str, str]]: ...
+    def election_results(
+        self,
+        contest: str,
+        swing: float = 0.0,
+        kind: Literal["uniform", "proportional"] = "uniform",
+    ) -> ElectionResults: ...
+    def seats_under_swings(
+        self,
+        contest: str,
+        swings: list[float],
+        kind: Literal["uniform", "proportional"] = "uniform",
+    ) -> list[int]: ...
+    def anneal_by_region(
+        self,
+        regions: list[tuple[list[str], int, float]],
+        config: ChainConfig,
+        seam: Optional[ChainConfig] = None,
+    ) -> None: ...
+    def anneal_relaxed(
+        self,
+        objective: Objective,
+        pop_series: str,
+        max_deviation: float,
+        max_iter: int,
+        max_splits: Optional[int] = None,
+        initial_temp: float = 1.0,
+        final_temp: float = 1e-4,
+        initial_penalty: float = 1.0,
+        final_penalty: float = 1000.0,
+        max_total_deviation: Optional[float] = None,
+    ) -> bool: ...
+    def tabu_balance(self, series: str, max_iter: int, tabu_tenure: int, boundary_factor: float, candidates_per_iter: int) -> None: ...
+    def recombine(self, a: int, b: int, pop_series: str) -> None: ...
+    def recom(self, pop_series: str, tolerance: float = 0.02, steps: int = 1000, seed: Optional[int] = None) -> int: ...
+    def recom_ensemble(self, pop_series: str, steps: int, sample_every: int, tolerance: float = 0.02, seed: Optional[int] = None) -> list[Plan]: ...
+    def warm_starts(self, k: int, steps: int, pop_series: str, tolerance: float = 0.01, seed: Optional[int] = None) -> list[Plan]: ...
+
+    # Constraints
+    def anchor_unit(self, layer: Layer, geo_id: str, district: int) -> None: ...
+    def clear_anchors(self) -> None: ...
+    def anchors_satisfied(self) -> bool: ...
+    def at_large_seats(self) -> int: ...
+    def set_at_large_seats(self, seats: int) -> None: ...
+    def total_seats(self) -> int: ...
+    def set_at_large_area(self, layer: Layer, geo_ids: list[str]) -> None: ...
+    def clear_at_large_area(self) -> None: ...
+    def at_large_area(self) -> list[str]: ...
+    def set_whole_county_mode(self, pop_series: str) -> tuple[int, list[str]]: ...
+    def clear_whole_county_mode(self) -> None: ...
+    def county_clusters(self, pop_series: str, tolerance: float = 0.05, limit: int = 100) -> list[list[tuple[list[str], int, float]]]: ...
+    def set_county_clusters(self, clusters: list[tuple[list[str], int, float]]) -> None: ...
+    def clear_county_clusters(self) -> None: ...
+    def county_clusters_satisfied(self) -> bool: ...
+
+    # Input and output
+    def load_csv(self, path: str) -> None: ...
+    def to_csv(self, path: str) -> None: ...
+    def load_baf(self, path: str) -> None: ...
+    def to_baf(self, path: str, layer: Layer = "block", pop_series: Optional[str] = None) -> None: ...
+    def to_geojson(self, path: str, pop_series: str, metrics: Optional[dict[str, Metric]] = None) -> None: ...
+    def to_shapefile(self, path: str, fields: Optional[dict[str, str]] = None, metrics: Optional[dict[str, Metric]] = None) -> None: ...
+    def plan_id(self) -> str: ...
+    def set_stamp_exports(self, enabled: bool) -> None: ...
+    def verify_export(self, path: str) -> ExportStamp: ...
+    def district_profiles(self, pop_series: str, locale: Optional[str] = None) -> pandas.DataFrame: ...
+    def write_district_profiles(self, path: str, pop_series: str) -> None: ...
+    def district_report(self, pop_series: str, locale: str = "en-US") -> str: ...
+    def write_district_report(self, path: str, pop_series: str, locale: str = "en-US") -> None: ...
+    def to_json(self) -> str: ...
+    def load_json(self, json: str) -> None: ...
+    def to_compact_bytes(self) -> bytes: ...
+    def load_compact_bytes(self, data: bytes) -> None: ...
+
+    # Drawing and geometry
+    def to_svg(
+        self,
+        path: str,
+        color_partisan: bool = False,
+        palette: Optional[PaletteName] = None,
+        projection: Projection = "equirectangular",
+        annotate: bool = False,
+        metric_series: Optional[tuple[str, str, str]] = None,
+        symbols: Optional[dict[str, object]] = None,
+        locale: str = "en-US",
+        title: Optional[str] = None,
+        description: Optional[str] = None,
+        patterns: bool = False,
+    ) -> None: ...
+    def to_block_svg(
+        self,
+        path: str,
+        color_partisan: bool = False,
+        palette: Optional[PaletteName] = None,
+        projection: Projection = "equirectangular",
+        annotate: bool = False,
+        metric_series: Optional[tuple[str, str, str]] = None,
+        symbols: Optional[dict[str, object]] = None,
+        locale: str = "en-US",
+        title: Optional[str] = None,
+        description: Optional[str] = None,
+        patterns: bool = False,
+    ) -> None: ...
+    @staticmethod
+    def to_panels_svg(
+        path: str,
+        plans: Sequence[Plan],
+        titles: Optional[list[str]] = None,
+        columns: int = 2,
+        panel_width: float = 600.0,
+        insets: Optional[list[tuple[float, float, float, float]]] = None,
+        color_partisan: bool = False,
+        palette: Optional[PaletteName] = None,
+        projection: Projection = "equirectangular",
+    ) -> None: ...
+    def district_borders_geojson(self) -> str: ...
+    def to_border_svg(self, path: str, projection: Projection = "equirectangular", annotate: bool = False) -> None: ...
+    @staticmethod
+    def unit_flippability(plans: Sequence[Plan]) -> list[float]: ...
+    @staticmethod
+    def unit_stability(plans: Sequence[Plan]) -> pandas.DataFrame: ...
+    @staticmethod
+    def write_unit_stability(path: str, plans: Sequence[Plan]) -> None: ...
+    @staticmethod
+    def ensemble_boxplot(plans: Sequence[Plan], contest: str, enacted: Optional[Plan] = None) -> EnsembleBoxplot: ...
+    @staticmethod
+    def write_ensemble_boxplot(path: str, plans: Sequence[Plan], contest: str, enacted: Optional[Plan] = None) -> None: ...
+    @staticmethod
+    def district_count_sweep(
+        map: Map,
+        min_districts: int,
+        max_districts: int,
+        pop_series: str,
+        tolerance: float,
+        equalize_iter: int = 1000,
+        config: Optional[ChainConfig] = None,
+    ) -> list[Plan]: ...
+    @staticmethod
+    def compare_plans(plans: Sequence[Plan], pop_series: str) -> pandas.DataFrame: ...
+    def draw_wards(self, pop_series: str, max_total_deviation: float, max_iter: int = 100000, objective: Optional[Objective] = None) -> bool: ...
+    def export_wards(self, dir: str, pop_series: str, preset: Literal["clerk", "gis", "all"] = "all") -> list[str]: ...
+    @staticmethod
+    def to_flippability_svg(path: str, plans: Sequence[Plan], projection: Projection = "equirectangular", annotate: bool = False) -> None: ...
+    def district_colors(self, palette: PaletteName = "okabe-ito") -> list[str]: ...
+    def district_geometries_wkb(self, hull: HullName = "dissolve", concavity: Optional[float] = None) -> list[tuple[int, bytes]]: ...
+    def hull_compactness(self, hull: HullName = "convex", concavity: Optional[float] = None) -> list[float]: ...
+    def _repr_html_(self) -> str: ...
+
+class StoreOperation(TypedDict):
+    timestamp: Optional[int]
+    operation: str
+    details: str
+    plans_before: int
+    plans_after: int
+
+class WeightedDistribution(TypedDict):
+    mean: float
+    std_dev: float
+    quantiles: list[tuple[float, float]]
+    effective_sample_size: float
+    samples: list[tuple[float, float]]
+
+class PlanEnsemble:
+    """Sampled plans and their metric scores, for outlier analysis against a neutral ensemble."""
+    @staticmethod
+    def sample(
+        start: Plan,
+        pop_series: str,
+        metrics: dict[str, Metric],
+        chains: int = 1,
+        steps: int = 1000,
+        sample_every: int = 1,
+        tolerance: float = 0.02,
+        seed: Optional[int] = None,
+    ) -> PlanEnsemble: ...
+    @staticmethod
+    def from_plans(plans: Sequence[Plan], metrics: dict[str, Metric]) -> PlanEnsemble: ...
+    def __len__(self) -> int: ...
+    def plans(self) -> list[Plan]: ...
+    def metric_names(self) -> list[str]: ...
+    def scores(self, name: str) -> list[float]: ...
+    def percentile(self, name: str, q: float) -> float: ...
+    def percentile_rank(self, name: str, value: float) -> float: ...
+    def summary(self) -> dict[str, ColumnStats]: ...
+    def to_pandas(self) -> pandas.DataFrame: ...
+    def write(self, path: str) -> None: ...
+
+class PlanStore:
+    """A directory of named plans of one map, deduplicated by content hash."""
+    def __init__(self, path: str, map: Map) -> None: ...
+    def __len__(self) -> int: ...
+    def __contains__(self, name: str) -> bool: ...
+    def names(self) -> list[str]: ...
+    def put(self, name: str, plan: Plan, scores: Optional[dict[str, float]] = None) -> str: ...
+    def get(self, name: str) -> Plan: ...
+    def remove(self, name: str) -> bool: ...
+    def rename(self, old: str, new: str) -> None: ...
+    def hash(self, name: str) -> Optional[str]: ...
+    def scores(self, name: str) -> dict[str, float]: ...
+    def duplicates(self, name: str) -> list[str]: ...
+    def ranked_by(self, score: str) -> list[tuple[str, float]]: ...
+    def merge(self, other: PlanStore, prefix: str) -> int: ...
+    def thin(self, prefix: str, burn_in: int = 0, every: int = 1) -> list[str]: ...
+    def dedup(self) -> list[str]: ...
+    def provenance(self) -> list[StoreOperation]: ...
+    def tempered_weights(self, score: str, beta: float) -> dict[str, float]: ...
+    def weighted_distribution(self, metric: str, weights: dict[str, float]) -> WeightedDistribution: ...
+
+def build_pack(
+    state_code: str,
+    path: str = ".",
+    has_vtd: bool = True,
+    verbose: int = 0,
+    rename: Optional[dict[str, str]] = None,
+    keep: Optional[list[str]] = None,
+    drop_unmapped: bool = False,
+    hilbert_order: bool = False,
+    vintage: int = 2020,
+    layers: Optional[list[Layer]] = None,
+    columns: Optional[list[str]] = None,
+    elections: Optional[str] = None,
+    cache_dir: Optional[str] = None,
+    pin: Optional[str] = None,
+) -> str: ...
+def download_pack(state_code: str, path: str = ".", verbose: int = 0) -> str: ...
+def download_pack_without_geoms(state_code: str, path: str = ".", verbose: int = 0) -> str: ...
+def validate_pack(pack_path: str, verbose: int = 0) -> None: ...
+def update_pack(pack_path: str, changes_path: str, layer: Layer, format: PackFormat = "parquet") -> int: ...
+def read_pack_column_stats(pack_path: str) -> dict[str, dict[str, ColumnStats]]: ...
+def pack_manifest(pack_path: str, format: Literal["text", "json"] = "text") -> str: ...
+def cite_pack(pack_path: str) -> str: ...
+def diff_packs(old_path: str, new_path: str, patch_path: str) -> list[str]: ...
+def apply_pack_patch(pack_path: str, patch_path: str) -> int: ...
+def convert_pack(src: str, dst: str) -> int: ...
+def list_metrics() -> list[MetricInfo]: ...
+def list_algorithms() -> list[AlgorithmInfo]: ...
+def list_states() -> list[StateInfo]: ...
@@ -0,0 +1,402 @@
+#![allow(unsafe_op_in_unsafe_fn)]
+use std::path::Path;
+
+use pyo3::{pyclass, pymethods, Bound, PyResult};
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::types::PyType;
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{Metric, Objective};
+
+/// Structured form of an ``Objective``: metrics, optional weights (all 1.0 if None), and optional
+/// lexicographic ``priorities`` with their ``resolution`` (see ``Objective``).
+/// Serializes to the same JSON/TOML as the Rust ``Objective``.
+#[pyclass(get_all, set_all)]
+#[derive(Clone)]
+pub struct ObjectiveSpec {
+    pub metrics: Vec<Metric>,
+    pub weights: Option<Vec<f64>>,
+    pub priorities: Option<Vec<u32>>,
+    pub resolution: f64,
+}
+
+impl ObjectiveSpec {
+    fn to_core(&self) -> PyResult<openmander_core::Objective> {
+        if let Some(weights) = &self.weights && weights.len() != self.metrics.len() {
+            return Err(PyValueError::new_err(format!(
+                "[ObjectiveSpec] weights length ({}) must match metrics length ({})", weights.len(), self.metrics.len())))
+        }
+        let metrics = self.metrics.iter().map(|metric| metric.inner.clone()).collect();
+        let objective = openmander_core::Objective::new(metrics, self.weights.clone());
+        let Some(priorities) = &self.priorities else { return Ok(objective) };
+        let composition = openmander_core::Composition::Lexicographic { priorities: priorities.clone(), resolution: self.resolution };
+        composition.validate(self.metrics.len()).map_err(|e| PyValueError::new_err(format!("[ObjectiveSpec] {e}")))?;
+        Ok(objective.with_composition(composition))
+    }
+
+    fn from_core(objective: &openmander_core::Objective) -> Self {
+        let metrics = objective.metrics().iter().map(|metric| Metric { inner: metric.clone() }).collect();
+        let (priorities, resolution) = match objective.composition() {
+            openmander_core::Composition::Lexicographic { priorities, resolution } => (Some(priorities.clone()), *resolution),
+            openmander_core::Composition::Weighted => (None, openmander_core::DEFAULT_RESOLUTION),
+        };
+        Self { metrics, weights: Some(objective.weights().to_vec()), priorities, resolution }
+    }
+}
+
+#[pymethods]
+impl ObjectiveSpec {
+    #[new]
+    #[pyo3(signature = (metrics, weights=None, priorities=None, resolution=openmander_core::DEFAULT_RESOLUTION))]
+    pub fn new(metrics: Vec<Metric>, weights: Option<Vec<f64>>, priorities: Option<Vec<u32>>, resolution: f64) -> Self {
+        Self { metrics, weights, priorities, resolution }
+    }
+
+    /// Build the ``Objective`` described by this spec.
+    pub fn to_objective(&self) -> PyResult<Objective> {
+        Ok(Objective { inner: self.to_core()? })
+    }
+
+    /// Create a spec from an existing ``Objective``.
+    #[classmethod]
+    pub fn from_objective(_cls: &Bound<'_, PyType>, objective: &Objective) -> Self {
+        Self::from_core(&objective.inner)
+    }
+
+    pub fn to_json(&self) -> PyResult<String> { to_json(&self.to_core()?) }
+
+    #[classmethod]
+    pub fn from_json(_cls: &Bound<'_, PyType>, json: &str) -> PyResult<Self> {
+        Ok(Self::from_core(&from_json(json)?))
+    }
+
+    pub fn to_toml(&self) -> PyResult<String> { to_toml(&self.to_core()?) }
+
+    #[classmethod]
+    pub fn from_toml(_cls: &Bound<'_, PyType>, toml: &str) -> PyResult<Self> {
+        Ok(Self::from_core(&from_toml(toml)?))
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        matches!((self.to_json(), other.to_json()), (Ok(a), Ok(b)) if a == b)
+    }
+
+    fn __repr__(&self) -> String {
+        let metrics = self.metrics.iter().map(|metric| metric.inner.to_string()).collect::<Vec<_>>();
+        let weights = self.weights.as_ref().map_or("None".to_string(), |weights| format!("{weights:?}"));
+        match &self.priorities {
+            Some(priorities) => format!("ObjectiveSpec(metrics=[{}], weights={weights}, priorities={priorities:?}, resolution={:?})", metrics.join(", "), self.resolution),
+            None => format!("ObjectiveSpec(metrics=[{}], weights={weights})", metrics.join(", ")),
+        }
+    }
+}
+
+/// Rules for ending a chain early (see ``ChainConfig.stop``); every rule is off by default.
+///
+/// ``time_limit_secs`` ends the whole run. The other rules end the current phase: after
+/// ``no_improvement_iters`` steps without a new best score, once the best score reaches
+/// ``target_score``, or once ``plateau_metric`` stays within ``plateau_tolerance`` for
+/// ``plateau_window`` steps.
+#[pyclass(get_all, set_all)]
+#[derive(Clone)]
+pub struct StoppingRules {
+    pub no_improvement_iters: Option<usize>,
+    pub target_score: Option<f64>,
+    pub time_limit_secs: Option<f64>,
+    pub plateau_metric: Option<Metric>,
+    pub plateau_window: usize,
+    pub plateau_tolerance: f64,
+}
+
+impl Default for StoppingRules {
+    fn default() -> Self { Self::new(None, None, None, None, 10000, 1e-6) }
+}
+
+impl StoppingRules {
+    fn to_core(&self) -> openmander_core::StoppingRules {
+        openmander_core::StoppingRules {
+            no_improvement_iters: self.no_improvement_iters,
+            target_score: self.target_score,
+            time_limit_secs: self.time_limit_secs,
+            plateau: self.plateau_metric.as_ref().map(|metric| openmander_core::PlateauRule {
+                metric: metric.inner.clone(),
+                window: self.plateau_window,
+                tolerance: self.plateau_tolerance,
+            }),
+        }
+    }
+
+    fn from_core(rules: openmander_core::StoppingRules) -> Self {
+        Self {
+            no_improvement_iters: rules.no_improvement_iters,
+            target_score: rules.target_score,
+            time_limit_secs: rules.time_limit_secs,
+            plateau_metric: rules.plateau.as_ref().map(|rule| Metric { inner: rule.metric.clone() }),
+            plateau_window: rules.plateau.as_ref().map_or(10000, |rule| rule.window),
+            plateau_tolerance: rules.plateau.as_ref().map_or(1e-6, |rule| rule.tolerance),
+        }
+    }
+}
+
+#[pymethods]
+impl StoppingRules {
+    #[new]
+    #[pyo3(signature = (no_improvement_iters=None, target_score=None, time_limit_secs=None, plateau_metric=None, plateau_window=10000, plateau_tolerance=1e-6))]
+    pub fn new(
+        no_improvement_iters: Option<usize>,
+        target_score: Option<f64>,
+        time_limit_secs: Option<f64>,
+        plateau_metric: Option<Metric>,
+        plateau_window: usize,
+        plateau_tolerance: f64,
+    ) -> Self {
+        Self { no_improvement_iters, target_score, time_limit_secs, plateau_metric, plateau_window, plateau_tolerance }
+    }
+
+    pub fn to_json(&self) -> PyResult<String> { to_json(&self.to_core()) }
+
+    #[classmethod]
+    pub fn from_json(_cls: &Bound<'_, PyType>, json: &str) -> PyResult<Self> {
+        Ok(Self::from_core(from_json(json)?))
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        matches!((self.to_json(), other.to_json()), (Ok(a), Ok(b)) if a == b)
+    }
+
+    fn __repr__(&self) -> String {
+        let show = |value: Option<String>| value.unwrap_or_else(|| "None".to_string());
+        format!("StoppingRules(no_improvement_iters={}, target_score={}, time_limit_secs={}, plateau_metric={}, plateau_window={}, plateau_tolerance={:?})",
+            show(self.no_improvement_iters.map(|n| n.to_string())),
+            show(self.target_score.map(|score| format!("{score:?}"))),
+            show(self.time_limit_secs.map(|secs| format!("{secs:?}"))),
+            show(self.plateau_metric.as_ref().map(|metric| metric.inner.to_string())),
+            self.plateau_window, self.plateau_tolerance)
+    }
+}
+
+/// Hard constraints a chain never breaks (see ``ChainConfig.constraints``): ``balance`` bounds as
+/// ``(series, min, max)`` deviations from the ideal district total, ``contiguous`` districts, and
+/// units to ``preserve`` whole as ``(layer, geo_ids)``, e.g. ``("county", ["17031"])``. Moves that
+/// break one are rejected before they are scored.
+#[pyclass(get_all, set_all)]
+#[derive(Clone, Default)]
+pub struct Constraints {
+    pub balance: Vec<(String, Option<f64>, Option<f64>)>,
+    pub contiguous: bool,
+    pub preserve: Vec<(String, Vec<String>)>,
+}
+
+impl Constraints {
+    pub(crate) fn to_core(&self) -> openmander_core::Constraints {
+        openmander_core::Constraints {
+            balance: self.balance.iter()
+                .map(|(series, min, max)| openmander_core::BalanceBound { series: series.clone(), min: *min, max: *max })
+                .collect(),
+            contiguous: self.contiguous,
+            preserve: self.preserve.iter()
+                .map(|(layer, geo_ids)| openmander_core::PreservedUnits { layer: layer.clone(), geo_ids: geo_ids.clone() })
+                .collect(),
+        }
+    }
+
+    fn from_core(constraints: openmander_core::Constraints) -> Self {
+        Self {
+            balance: constraints.balance.into_iter().map(|bound| (bound.series, bound.min, bound.max)).collect(),
+            contiguous: constraints.contiguous,
+            preserve: constraints.preserve.into_iter().map(|units| (units.layer, units.geo_ids)).collect(),
+        }
+    }
+}
+
+#[pymethods]
+impl Constraints {
+    #[new]
+    #[pyo3(signature = (balance=None, contiguous=false, preserve=None))]
+    pub fn new(
+        balance: Option<Vec<(String, Option<f64>, Option<f64>)>>,
+        contiguous: bool,
+        preserve: Option<Vec<(String, Vec<String>)>>,
+    ) -> Self {
+        Self { balance: balance.unwrap_or_default(), contiguous, preserve: preserve.unwrap_or_default() }
+    }
+
+    pub fn to_json(&self) -> PyResult<String> { to_json(&self.to_core()) }
+
+    #[classmethod]
+    pub fn from_json(_cls: &Bound<'_, PyType>, json: &str) -> PyResult<Self> {
+        Ok(Self::from_core(from_json(json)?))
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        matches!((self.to_json(), other.to_json()), (Ok(a), Ok(b)) if a == b)
+    }
+
+    fn __repr__(&self) -> String {
+        let show = |value: &Option<f64>| value.map_or("None".to_string(), |value| format!("{value:?}"));
+        let balance = self.balance.iter()
+            .map(|(series, min, max)| format!("({series:?}, {}, {})", show(min), show(max)))
+            .collect::<Vec<_>>();
+        format!("Constraints(balance=[{}], contiguous={}, preserve={:?})",
+            balance.join(", "), if self.contiguous { "True" } else { "False" }, self.preserve)
+    }
+}
+
+/// Structured form of a multi-phase annealing run, one objective per phase (see ``Plan.anneal``
+/// for the meaning of each field). Serializes to the same JSON/TOML as the Rust ``AnnealConfig``,
+/// so a config can be shared between the server, batch runs, and Python.
+#[pyclass(get_all, set_all)]
+#[derive(Clone)]
+pub struct ChainConfig {
+    pub objectives: Vec<ObjectiveSpec>,
+    pub max_iter: usize,
+    pub phase_start_probs: Vec<f64>,
+    pub phase_end_probs: Vec<Option<f64>>,
+    pub phase_cooling_rates: Vec<f64>,
+    pub init_temp: f64,
+    pub early_stop_iters: usize,
+    pub temp_search_batch_size: usize,
+    pub batch_size: usize,
+    pub stop: StoppingRules,
+    pub proposals: Vec<(String, f64)>,
+    pub constraints: Constraints,
+    pub profile: bool,
+}
+
+impl ChainConfig {
+    pub(crate) fn to_core(&self) -> PyResult<openmander_core::AnnealConfig> {
+        let phases = self.objectives.len();
+        if [self.phase_start_probs.len(), self.phase_end_probs.len(), self.phase_cooling_rates.len()].iter().any(|&len| len != phases) {
+            return Err(PyValueError::new_err(format!("[ChainConfig] Every phase list must have one entry per objective ({phases})")))
+        }
+        Ok(openmander_core::AnnealConfig {
+            objectives: self.objectives.iter().map(ObjectiveSpec::to_core).collect::<PyResult<_>>()?,
+            max_iter: self.max_iter,
+            phase_start_probs: self.phase_start_probs.clone(),
+            phase_end_probs: self.phase_end_probs.clone(),
+            phase_cooling_rates: self.phase_cooling_rates.clone(),
+            init_temp: self.init_temp,
+            early_stop_iters: self.early_stop_iters,
+            temp_search_batch_size: self.temp_search_batch_size,
+            batch_size: self.batch_size,
+            stop: self.stop.to_core(),
+            proposals: self.proposals.iter().map(|(proposal, weight)| Ok(openmander_core::WeightedProposal {
+                proposal: proposal.parse::<openmander_core::Proposal>().map_err(|e| PyValueError::new_err(e.to_string()))?,
+                weight: *weight,
+            })).collect::<PyResult<_>>()?,
+            constraints: self.constraints.to_core(),
+            profile: self.profile,
+        })
+    }
+
+    fn from_core(config: openmander_core::AnnealConfig) -> Self {
+        Self {
+            objectives: config.objectives.iter().map(ObjectiveSpec::from_core).collect(),
+            max_iter: config.max_iter,
+            phase_start_probs: config.phase_start_probs,
+            phase_end_probs: config.phase_end_probs,
+            phase_cooling_rates: config.phase_cooling_rates,
+            init_temp: config.init_temp,
+            early_stop_iters: config.early_stop_iters,
+            temp_search_batch_size: config.temp_search_batch_size,
+            batch_size: config.batch_size,
+            stop: StoppingRules::from_core(config.stop),
+            proposals: config.proposals.iter().map(|weighted| (weighted.proposal.to_string(), weighted.weight)).collect(),
+            constraints: Constraints::from_core(config.constraints),
+            profile: config.profile,
+        }
+    }
+}
+
+#[pymethods]
+impl ChainConfig {
+    #[new]
+    #[pyo3(signature = (objectives, max_iter, phase_start_probs, phase_end_probs, phase_cooling_rates, init_temp=1.0, early_stop_iters=100000, temp_search_batch_size=1000, batch_size=1000, stop=None, proposals=None, constraints=None, profile=false))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        objectives: Vec<ObjectiveSpec>,
+        max_iter: usize,
+        phase_start_probs: Vec<f64>,
+        phase_end_probs: Vec<Option<f64>>,
+        phase_cooling_rates: Vec<f64>,
+        init_temp: f64,
+        early_stop_iters: usize,
+        temp_search_batch_size: usize,
+        batch_size: usize,
+        stop: Option<StoppingRules>,
+        proposals: Option<Vec<(String, f64)>>,
+        constraints: Option<Constraints>,
+        profile: bool,
+    ) -> Self {
+        let (stop, proposals, constraints) = (stop.unwrap_or_default(), proposals.unwrap_or_default(), constraints.unwrap_or_default());
+        Self { objectives, max_iter, phase_start_probs, phase_end_probs, phase_cooling_rates, init_temp, early_stop_iters, temp_search_batch_size, batch_size, stop, proposals, constraints, profile }
+    }
+
+    pub fn to_json(&self) -> PyResult<String> { to_json(&self.to_core()?) }
+
+    #[classmethod]
+    pub fn from_json(_cls: &Bound<'_, PyType>, json: &str) -> PyResult<Self> {
+        Ok(Self::from_core(from_json(json)?))
+    }
+
+    /// Serialize to TOML. TOML has no null, so phases without an end probability need JSON instead.
+    pub fn to_toml(&self) -> PyResult<String> {
+        if self.phase_end_probs.contains(&None) {
+            return Err(PyValueError::new_err("[ChainConfig] TOML can't represent a phase_end_probs entry of None; use JSON"))
+        }
+        to_toml(&self.to_core()?)
+    }
+
+    #[classmethod]
+    pub fn from_toml(_cls: &Bound<'_, PyType>, toml: &str) -> PyResult<Self> {
+        Ok(Self::from_core(from_toml(toml)?))
+    }
+
+    /// Write the config to a ``.toml`` or ``.json`` file (chosen by extension).
+    pub fn save(&self, path: &str) -> PyResult<()> {
+        let text = if is_toml(path) { self.to_toml()? } else { self.to_json()? };
+        std::fs::write(path, text).map_err(|e| PyIOError::new_err(format!("[ChainConfig] Failed to write {path}: {e}")))
+    }
+
+    /// Read a config from a ``.toml`` or ``.json`` file (chosen by extension).
+    #[classmethod]
+    pub fn load(cls: &Bound<'_, PyType>, path: &str) -> PyResult<Self> {
+        let text = std::fs::read_to_string(path).map_err(|e| PyIOError::new_err(format!("[ChainConfig] Failed to read {path}: {e}")))?;
+        if is_toml(path) { Self::from_toml(cls, &text) } else { Self::from_json(cls, &text) }
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        matches!((self.to_json(), other.to_json()), (Ok(a), Ok(b)) if a == b)
+    }
+
+    fn __repr__(&self) -> String {
+        let show = |value: &Option<f64>| value.map_or("None".to_string(), |value| format!("{value:?}"));
+        let end_probs = self.phase_end_probs.iter().map(show).collect::<Vec<_>>();
+        format!("ChainConfig(objectives=[{}], max_iter={}, phase_start_probs={:?}, phase_end_probs=[{}], phase_cooling_rates={:?}, \
+            init_temp={:?}, early_stop_iters={}, temp_search_batch_size={}, batch_size={}, stop={}, proposals={:?}, constraints={}, profile={})",
+            self.objectives.iter().map(ObjectiveSpec::__repr__).collect::<Vec<_>>().join(", "),
+            self.max_iter, self.phase_start_probs, end_probs.join(", "), self.phase_cooling_rates,
+            self.init_temp, self.early_stop_iters, self.temp_search_batch_size, self.batch_size, self.stop.__repr__(), self.proposals,
+            self.constraints.__repr__(), if self.profile { "True" } else { "False" })
+    }
+}
+
+fn is_toml(path: &str) -> bool {
+    Path::new(path).extension().is_some_and(|ext| ext.eq_ignore_ascii_case("toml"))
+}
+
+fn to_json(value: &impl Serialize) -> PyResult<String> {
+    serde_json::to_string_pretty(value).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+fn from_json<T: DeserializeOwned>(json: &str) -> PyResult<T> {
+    serde_json::from_str(json).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+fn to_toml(value: &impl Serialize) -> PyResult<String> {
+    toml::to_string(value).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+fn from_toml<T: DeserializeOwned>(toml: &str) -> PyResult<T> {
+    toml::from_str(toml).map_err(|e| PyValueError::new_err(e.to_string()))
+}
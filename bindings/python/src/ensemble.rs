@@ -0,0 +1,108 @@
+#![allow(unsafe_op_in_unsafe_fn)]
+use std::{collections::BTreeMap, path::PathBuf};
+
+use pyo3::{pyclass, pymethods, Bound, PyAny, PyRef, PyResult, Python};
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::types::{PyDict, PyDictMethods};
+
+use crate::{Metric, Plan, pack::column_stats_entry, plan::to_pandas};
+
+/// Named metrics, in name order.
+fn to_metrics(metrics: BTreeMap<String, Metric>) -> Vec<(String, openmander_core::Metric)> {
+    metrics.into_iter().map(|(name, metric)| (name, metric.inner)).collect()
+}
+
+/// A collection of sampled plans and each plan's metric scores, for comparing a plan against
+/// the distribution of plans a neutral process draws (outlier analysis).
+#[pyclass]
+pub struct PlanEnsemble {
+    inner: openmander_core::PlanEnsemble,
+}
+
+#[pymethods]
+impl PlanEnsemble {
+    /// Sample an ensemble from ``start`` with ``chains`` independent ReCom chains of ``steps``
+    /// steps each (see ``Plan.recom``), keeping a plan every ``sample_every`` steps, and score
+    /// each plan with ``metrics`` (by name). Chain ``i`` uses seed ``seed + i``.
+    #[staticmethod]
+    #[pyo3(signature = (start, pop_series, metrics, chains=1, steps=1000, sample_every=1, tolerance=0.02, seed=None))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn sample(
+        py: Python<'_>,
+        start: PyRef<'_, Plan>,
+        pop_series: &str,
+        metrics: BTreeMap<String, Metric>,
+        chains: usize,
+        steps: usize,
+        sample_every: usize,
+        tolerance: f64,
+        seed: Option<u64>,
+    ) -> PyResult<Self> {
+        let config = openmander_core::EnsembleConfig {
+            pop_series: pop_series.to_string(), tolerance, chains, steps, sample_every, seed,
+        };
+        let (start, metrics) = (start.inner(), to_metrics(metrics));
+        let inner = py.allow_threads(|| openmander_core::PlanEnsemble::sample(start, &config, &metrics))
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(Self { inner })
+    }
+
+    /// An ensemble of existing plans, scored with ``metrics`` (by name).
+    #[staticmethod]
+    pub fn from_plans(py: Python<'_>, plans: Vec<PyRef<'_, Plan>>, metrics: BTreeMap<String, Metric>) -> PyResult<Self> {
+        let plans = plans.iter().map(|plan| plan.inner().clone()).collect::<Vec<_>>();
+        let metrics = to_metrics(metrics);
+        let inner = py.allow_threads(|| openmander_core::PlanEnsemble::from_plans(plans, &metrics))
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(Self { inner })
+    }
+
+    pub fn __len__(&self) -> usize { self.inner.len() }
+
+    /// The sampled plans, in chain order.
+    pub fn plans(&self) -> PyResult<Vec<Plan>> {
+        let plans = self.inner.plans().map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(plans.into_iter().map(Plan::from_inner).collect())
+    }
+
+    /// Names of the metrics each plan is scored with.
+    pub fn metric_names(&self) -> Vec<String> { self.inner.metric_names().to_vec() }
+
+    /// Score of every plan on the metric ``name``, in plan order.
+    pub fn scores(&self, name: &str) -> PyResult<Vec<f64>> {
+        self.inner.scores(name).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// The ``q``-quantile (0 to 1) of the ensemble's scores on the metric ``name``.
+    pub fn percentile(&self, name: &str, q: f64) -> PyResult<f64> {
+        self.inner.percentile(name, q).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Share of the ensemble's plans scoring at most ``value`` on the metric ``name``, e.g. where
+    /// an enacted plan's score falls; values near 0 or 1 mark it as an outlier.
+    pub fn percentile_rank(&self, name: &str, value: f64) -> PyResult<f64> {
+        self.inner.percentile_rank(name, value).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Range, quartiles and histogram of the ensemble's scores on each metric, by name.
+    pub fn summary<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        for (name, stats) in self.inner.summary() {
+            dict.set_item(name, column_stats_entry(py, &stats)?)?;
+        }
+        Ok(dict)
+    }
+
+    /// One row per plan as a pandas ``DataFrame`` (requires pandas): the ``chain`` and ``step``
+    /// it was kept at, and its score on each metric.
+    pub fn to_pandas<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let df = self.inner.to_dataframe().map_err(|e| PyValueError::new_err(e.to_string()))?;
+        to_pandas(py, &df)
+    }
+
+    /// Write the per-plan scores (see ``to_pandas``) to a ``.csv`` or ``.parquet`` file.
+    pub fn write(&self, py: Python<'_>, path: &str) -> PyResult<()> {
+        py.allow_threads(|| self.inner.write(&PathBuf::from(path)))
+            .map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+}
@@ -1,27 +1,56 @@
+mod config;
+mod ensemble;
 mod map;
 mod metric;
+mod notebook;
 mod objective;
+mod observer;
 mod plan;
 mod pack;
+mod registry;
+mod store;
 
+pub use config::{ChainConfig, Constraints, ObjectiveSpec, StoppingRules};
+pub use ensemble::PlanEnsemble;
 pub use map::Map;
 pub use metric::Metric;
 pub use objective::Objective;
+pub use observer::StepBuffer;
 pub use plan::Plan;
 pub use pack::*;
+pub use registry::*;
+pub use store::PlanStore;
 
 use pyo3::{pymodule, Bound, PyResult, Python, types::PyModule};
 
 #[pymodule]
 fn openmander(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<ChainConfig>()?;
+    m.add_class::<Constraints>()?;
     m.add_class::<Map>()?;
     m.add_class::<Metric>()?;
     m.add_class::<Objective>()?;
+    m.add_class::<ObjectiveSpec>()?;
     m.add_class::<Plan>()?;
+    m.add_class::<PlanEnsemble>()?;
+    m.add_class::<PlanStore>()?;
+    m.add_class::<StepBuffer>()?;
+    m.add_class::<StoppingRules>()?;
 
     m.add_function(pyo3::wrap_pyfunction!(build_pack, m)?)?;
     m.add_function(pyo3::wrap_pyfunction!(download_pack, m)?)?;
+    m.add_function(pyo3::wrap_pyfunction!(download_pack_without_geoms, m)?)?;
     m.add_function(pyo3::wrap_pyfunction!(validate_pack, m)?)?;
+    m.add_function(pyo3::wrap_pyfunction!(update_pack, m)?)?;
+    m.add_function(pyo3::wrap_pyfunction!(read_pack_column_stats, m)?)?;
+    m.add_function(pyo3::wrap_pyfunction!(pack_manifest, m)?)?;
+    m.add_function(pyo3::wrap_pyfunction!(cite_pack, m)?)?;
+    m.add_function(pyo3::wrap_pyfunction!(diff_packs, m)?)?;
+    m.add_function(pyo3::wrap_pyfunction!(apply_pack_patch, m)?)?;
+    m.add_function(pyo3::wrap_pyfunction!(convert_pack, m)?)?;
+    m.add_function(pyo3::wrap_pyfunction!(list_metrics, m)?)?;
+    m.add_function(pyo3::wrap_pyfunction!(list_algorithms, m)?)?;
+    m.add_function(pyo3::wrap_pyfunction!(list_states, m)?)?;
 
     Ok(())
 }
@@ -2,7 +2,8 @@
 use std::sync::Arc;
 
 use pyo3::{pyclass, pymethods, Bound, PyResult, Python};
-use pyo3::exceptions::PyValueError;
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::types::{PyAnyMethods, PyDict, PyDictMethods};
 
 /// Python-facing Map wrapper.
 #[pyclass]
@@ -17,33 +18,39 @@ impl Map {
 #[pymethods]
 impl Map {
     #[new]
-    pub fn new(pack_dir: &str) -> PyResult<Self> {
-        let map = openmander_core::Map::read_from_pack(&std::path::PathBuf::from(pack_dir))
+    #[pyo3(signature = (pack_dir, precision="f64"))]
+    pub fn new(pack_dir: &str, precision: &str) -> PyResult<Self> {
+        let map = openmander_core::Map::read_from_pack_with_precision(&std::path::PathBuf::from(pack_dir), parse_precision(precision)?)
             .map_err(|e| PyValueError::new_err(e.to_string()))?;
         Ok(Self { inner: Arc::new(map) })
     }
 
-    /// Read a map from a pack directory, optionally specifying format.
+    /// Read a map from a pack, optionally specifying format.
     ///
     /// Parameters
     /// ----------
     /// pack_dir : str
-    ///     Path to the pack directory.
+    ///     Path to the pack directory, zip archive or ``.openmander`` bundle.
     /// format : str, optional
     ///     Pack format: "parquet" or "json". If None, auto-detects from files.
-    #[pyo3(signature = (pack_dir, format=None))]
+    /// precision : str, optional
+    ///     Geometry storage precision: "f64" (default) or "f32", which halves geometry
+    ///     memory. Metrics are computed in f64 either way.
+    #[pyo3(signature = (pack_dir, format=None, precision="f64"))]
     #[classmethod]
-    pub fn from_pack(_cls: &Bound<'_, pyo3::types::PyType>, pack_dir: &str, format: Option<&str>) -> PyResult<Self> {
+    pub fn from_pack(_cls: &Bound<'_, pyo3::types::PyType>, pack_dir: &str, format: Option<&str>, precision: &str) -> PyResult<Self> {
         use std::str::FromStr;
         let path = std::path::PathBuf::from(pack_dir);
+        let precision = parse_precision(precision)?;
         let map = if let Some(fmt_str) = format {
             let fmt = openmander_core::PackFormat::from_str(fmt_str)
                 .map_err(|e| PyValueError::new_err(format!("Invalid format: {}. Expected 'parquet' or 'json'", e)))?;
-            let src = openmander_core::DiskPack::new(&path);
-            openmander_core::Map::read_from_pack_source(&src, fmt)
+            let src = openmander_core::open_pack_store(&path)
+                .map_err(|e| PyValueError::new_err(e.to_string()))?;
+            openmander_core::Map::read_from_pack_source_with_precision(&*src, fmt, precision)
                 .map_err(|e| PyValueError::new_err(e.to_string()))?
         } else {
-            openmander_core::Map::read_from_pack(&path)
+            openmander_core::Map::read_from_pack_with_precision(&path, precision)
                 .map_err(|e| PyValueError::new_err(e.to_string()))?
         };
         Ok(Self { inner: Arc::new(map) })
@@ -116,6 +123,181 @@ impl Map {
         Ok(out)
     }
 
+    /// Extract the sub-map covering the given units of one layer.
+    ///
+    /// Partially covered units of higher layers are clipped to the selection,
+    /// and their data is recomputed from the selected blocks.
+    ///
+    /// Parameters
+    /// ----------
+    /// geo_ids : list[str]
+    ///     GEOIDs of the units to keep.
+    /// layer : str, default="county"
+    ///     One of: "state", "county", "tract", "group", "vtd", "block".
+    #[pyo3(signature = (geo_ids, layer=None))]
+    pub fn clip_to(&self, py: Python<'_>, geo_ids: Vec<String>, layer: Option<&str>) -> PyResult<Self> {
+        let layer_name = layer.unwrap_or("county");
+        let ty = openmander_core::GeoType::from_str(layer_name).ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "Unknown layer {:?}. Expected one of: state, county, tract, group, vtd, block",
+                layer_name
+            ))
+        })?;
+        let geo_ids = geo_ids.iter()
+            .map(|id| openmander_core::GeoId::new(ty, id))
+            .collect::<Vec<_>>();
+        let map = py.allow_threads(|| self.inner.clip_to(&geo_ids))
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(Self { inner: Arc::new(map) })
+    }
+
+    /// Extract the sub-map of blocks whose centroid lies inside a boundary (e.g. a city limit),
+    /// read from a GeoJSON file holding a Polygon, MultiPolygon, Feature or FeatureCollection.
+    ///
+    /// Parameters
+    /// ----------
+    /// path : str
+    ///     Path to the boundary GeoJSON file.
+    pub fn clip_to_geojson(&self, py: Python<'_>, path: &str) -> PyResult<Self> {
+        let map = py.allow_threads(|| self.inner.clip_to_geojson(std::path::Path::new(path)))
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(Self { inner: Arc::new(map) })
+    }
+
+    /// Translate data series onto ``target``, a map of a later (or earlier) Census vintage, using
+    /// an NHGIS block crosswalk CSV (e.g. ``nhgis_blk2010_blk2020_<state>.csv``). Returns a copy of
+    /// ``target`` with the series added, each target block receiving the weighted sum of the
+    /// source blocks overlapping it.
+    ///
+    /// Parameters
+    /// ----------
+    /// crosswalk_path : str
+    ///     Path to the NHGIS crosswalk CSV, from this map's vintage to the target's.
+    /// target : Map
+    ///     Map of the target vintage.
+    /// series : list[str]
+    ///     Names of the block-level series to translate.
+    pub fn crosswalk_vintage(&self, py: Python<'_>, crosswalk_path: &str, target: &Map, series: Vec<String>) -> PyResult<Self> {
+        let map = py.allow_threads(|| {
+            let crosswalk = openmander_core::VintageCrosswalk::read_nhgis_csv(std::path::Path::new(crosswalk_path))?;
+            self.inner.crosswalk_vintage(&crosswalk, &target.inner, &series.iter().map(String::as_str).collect::<Vec<_>>())
+        }).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(Self { inner: Arc::new(map) })
+    }
+
+    /// Merge with a map covering a disjoint neighboring area (e.g. another state).
+    ///
+    /// Adjacency across the shared boundary is recomputed from geometry. Only
+    /// layers and data columns present in both maps are kept.
+    ///
+    /// Parameters
+    /// ----------
+    /// other : Map
+    ///     The map to merge with.
+    pub fn merge(&self, py: Python<'_>, other: &Map) -> PyResult<Self> {
+        let map = py.allow_threads(|| self.inner.merge(&other.inner))
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(Self { inner: Arc::new(map) })
+    }
+
+    /// Attach unit geometry to a map loaded without it (e.g. from ``download_pack_without_geoms``).
+    ///
+    /// Layers that already carry geometry are left as they are.
+    ///
+    /// Parameters
+    /// ----------
+    /// path_or_url : str
+    ///     A pack directory for the same units, or the URL of a zipped pack.
+    pub fn attach_geometries(&mut self, py: Python<'_>, path_or_url: &str) -> PyResult<()> {
+        let map = Arc::make_mut(&mut self.inner);
+        py.allow_threads(|| map.attach_geometries(path_or_url))
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Apply boundary changes (e.g. annexations) to units of ``layer`` in place, read from a
+    /// GeoJSON FeatureCollection whose features carry each unit's ``geo_id`` (or ``GEOID``) and
+    /// its new boundary. Blocks inside a new boundary move into that unit, and only the units
+    /// gaining or losing blocks are recomputed. Returns the number of blocks that moved.
+    pub fn apply_boundary_changes(&mut self, py: Python<'_>, changes_path: &str, layer: &str) -> PyResult<usize> {
+        let ty = openmander_core::GeoType::from_str(layer).ok_or_else(|| {
+            PyValueError::new_err(format!("Unknown layer {layer:?}. Expected one of: state, county, tract, group, vtd, block"))
+        })?;
+        let map = Arc::make_mut(&mut self.inner);
+        py.allow_threads(|| {
+            let changes = openmander_core::BoundaryChange::read_geojson(std::path::Path::new(changes_path), ty)?;
+            map.apply_boundary_changes(&changes)
+        }).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Add a float column of per-unit values to ``layer`` (replacing any column of that name), e.g.
+    /// the ``entropy`` of ``Plan.unit_stability`` for mapping. The column is also usable as a series.
+    pub fn set_unit_column(&mut self, layer: &str, name: &str, values: Vec<f64>) -> PyResult<()> {
+        let ty = openmander_core::GeoType::from_str(layer).ok_or_else(|| {
+            PyValueError::new_err(format!("Unknown layer {layer:?}. Expected one of: state, county, tract, group, vtd, block"))
+        })?;
+        Arc::make_mut(&mut self.inner).set_unit_column(ty, name, values)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Check that every layer nests consistently inside its parent layers.
+    ///
+    /// Reports units with missing or unknown parents, units whose geometry lies
+    /// outside their parent, and parents whose series totals differ from the sum
+    /// over their children. Returns a list of human-readable issue descriptions.
+    ///
+    /// Parameters
+    /// ----------
+    /// series : list[str], optional
+    ///     Series whose totals must roll up exactly across layers.
+    #[pyo3(signature = (series=None))]
+    pub fn check_integrity(&self, py: Python<'_>, series: Option<Vec<String>>) -> Vec<String> {
+        let series = series.unwrap_or_default();
+        let names = series.iter().map(String::as_str).collect::<Vec<_>>();
+        py.allow_threads(|| self.inner.check_integrity(&names))
+            .iter().map(|issue| issue.to_string()).collect()
+    }
+
+    /// Estimated heap memory of the map, in bytes, by section.
+    ///
+    /// Returns a dict with keys "geometry", "tables", "adjacency", "indices", and "total",
+    /// for the whole map or for one layer. Geometry usually dominates, so maps loaded from
+    /// geometry-less packs take a fraction of the memory.
+    ///
+    /// Parameters
+    /// ----------
+    /// layer : str, optional
+    ///     One of: "state", "county", "tract", "group", "vtd", "block". Defaults to every layer.
+    #[pyo3(signature = (layer=None))]
+    pub fn memory_footprint<'py>(&self, py: Python<'py>, layer: Option<&str>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        use pyo3::types::{PyDict, PyDictMethods};
+
+        let footprint = match layer {
+            None => self.inner.memory_footprint(),
+            Some(name) => {
+                let ty = openmander_core::GeoType::from_str(name).ok_or_else(|| {
+                    PyValueError::new_err(format!("Unknown layer {name:?}. Expected one of: state, county, tract, group, vtd, block"))
+                })?;
+                self.inner.layer(ty)
+                    .ok_or_else(|| PyValueError::new_err(format!("Map has no {name} layer")))?
+                    .memory_footprint()
+            }
+        };
+        let dict = PyDict::new_bound(py);
+        dict.set_item("geometry", footprint.geometry)?;
+        dict.set_item("tables", footprint.tables)?;
+        dict.set_item("adjacency", footprint.adjacency)?;
+        dict.set_item("indices", footprint.indices)?;
+        dict.set_item("total", footprint.total())?;
+        Ok(dict)
+    }
+
+    /// Min, max, sum, quartiles, and a 10-bin histogram of each numeric column, by layer and
+    /// column. The same statistics are recorded in a pack's manifest when it is written (see
+    /// ``read_pack_column_stats``).
+    pub fn column_stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        crate::pack::column_stats_dict(py, &py.allow_threads(|| self.inner.column_stats()))
+    }
+
     /// Write an SVG for a given layer.
     ///
     /// Parameters
@@ -126,8 +308,18 @@ impl Map {
     ///     One of: "state", "county", "tract", "group", "vtd", "block".
     /// series : Optional[str]
     ///     Optional column name in the layer's dataframe to use for coloring.
-    #[pyo3(text_signature = "(self, path, layer='block', series=None)")]
-    pub fn to_svg(&self, path: &str, layer: Option<&str>, series: Option<&str>) -> PyResult<()> {
+    /// group_by : Optional[str]
+    ///     Optional larger layer (e.g. "county") to wrap shapes in one ``<g>`` per unit of.
+    /// projection : str, default="equirectangular"
+    ///     One of: "equirectangular", "mercator", "albers", "albers-usa" (with Alaska/Hawaii insets).
+    /// annotate : bool, default=False
+    ///     Draw a scale bar and north arrow below the map.
+    /// title, description : Optional[str]
+    ///     Document ``<title>`` and ``<desc>``, read by screen readers.
+    #[pyo3(signature = (path, layer=None, series=None, group_by=None, projection="equirectangular", annotate=false, title=None, description=None))]
+    #[pyo3(text_signature = "(self, path, layer='block', series=None, group_by=None, projection='equirectangular', annotate=False, title=None, description=None)")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn to_svg(&self, py: Python<'_>, path: &str, layer: Option<&str>, series: Option<&str>, group_by: Option<&str>, projection: &str, annotate: bool, title: Option<String>, description: Option<String>) -> PyResult<()> {
         // Determine which layer to use (default = "block")
         let layer = layer.unwrap_or("block");
         let ty = openmander_core::GeoType::from_str(layer).ok_or_else(|| {
@@ -137,9 +329,65 @@ impl Map {
             ))
         })?;
 
-        self.inner.as_ref().layer(ty)
-            .ok_or_else(|| PyValueError::new_err(format!("Layer {:?} is not present in this map/pack.", layer)))?
-            .to_svg(&std::path::PathBuf::from(path), series)
+        let group_by = group_by
+            .map(|name| openmander_core::GeoType::from_str(name)
+                .ok_or_else(|| PyValueError::new_err(format!("Unknown group_by layer {:?}", name))))
+            .transpose()?;
+        let annotations = openmander_core::Annotations { scale_bar: annotate, north_arrow: annotate, ..Default::default() };
+        let accessibility = openmander_core::Accessibility { title, description, patterns: false };
+        let options = openmander_core::SvgOptions { projection: parse_projection(projection)?, annotations, accessibility, ..Default::default() };
+
+        let layer = self.inner.as_ref().layer(ty)
+            .ok_or_else(|| PyValueError::new_err(format!("Layer {:?} is not present in this map/pack.", layer)))?;
+        py.allow_threads(|| layer.to_svg_with_options(&std::path::PathBuf::from(path), series, group_by, &options))
             .map_err(|e| PyValueError::new_err(e.to_string()))
     }
+
+    /// Write a layer as an ESRI shapefile (``.shp`` with ``.shx``, ``.dbf`` and ``.prj``), one
+    /// polygon per unit with its ``GEOID``. ``fields`` maps DBF field names (at most 10 characters)
+    /// to the data columns they hold, in order.
+    #[pyo3(signature = (path, layer="block", fields=None))]
+    pub fn to_shapefile(&self, py: Python<'_>, path: &str, layer: &str, fields: Option<&Bound<'_, PyDict>>) -> PyResult<()> {
+        let ty = openmander_core::GeoType::from_str(layer)
+            .ok_or_else(|| PyValueError::new_err(format!("Unknown layer {:?}", layer)))?;
+        let layer = self.inner.as_ref().layer(ty)
+            .ok_or_else(|| PyValueError::new_err(format!("Layer {:?} is not present in this map/pack.", layer)))?;
+        let schema = parse_shapefile_schema(fields)?;
+        py.allow_threads(|| layer.write_shapefile(&std::path::PathBuf::from(path), &schema))
+            .map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    /// HTML preview for Jupyter: the coarsest layer beside a table of unit counts per layer.
+    fn _repr_html_(&self, py: Python<'_>) -> String {
+        py.allow_threads(|| crate::notebook::map_html(&self.inner))
+    }
+
+    fn __repr__(&self) -> String {
+        let layers = self.inner.layers_iter()
+            .map(|layer| format!("{}={}", layer.ty().to_str(), layer.len()))
+            .collect::<Vec<_>>();
+        format!("<Map: {}>", layers.join(", "))
+    }
+}
+
+/// Parse a map projection name for SVG export.
+pub(crate) fn parse_projection(projection: &str) -> PyResult<openmander_core::MapProjection> {
+    openmander_core::MapProjection::from_name(projection).ok_or_else(|| PyValueError::new_err(format!(
+        "Unknown projection {projection:?}. Expected one of: equirectangular, mercator, albers, albers-usa"
+    )))
+}
+
+/// Parse a geometry precision name ("f64" or "f32").
+fn parse_precision(precision: &str) -> PyResult<openmander_core::GeometryPrecision> {
+    openmander_core::GeometryPrecision::from_name(precision)
+        .ok_or_else(|| PyValueError::new_err(format!("Unknown precision {precision:?}. Expected one of: f64, f32")))
+}
+
+/// Parse a ``{field name: column}`` dict into a shapefile attribute schema, keeping its order.
+pub(crate) fn parse_shapefile_schema(fields: Option<&Bound<'_, PyDict>>) -> PyResult<openmander_core::ShapefileSchema> {
+    let mut schema = openmander_core::ShapefileSchema::default();
+    for (name, column) in fields.into_iter().flat_map(|fields| fields.iter()) {
+        schema = schema.field(&name.extract::<String>()?, &column.extract::<String>()?);
+    }
+    Ok(schema)
 }
@@ -1,5 +1,6 @@
 #![allow(unsafe_op_in_unsafe_fn)]
-use pyo3::{pyclass, pymethods};
+use pyo3::{pyclass, pymethods, PyResult};
+use pyo3::exceptions::PyValueError;
 
 /// A single metric used in a multi-objective optimization.
 /// Examples: population equality, compactness, competitiveness, proportionality.
@@ -9,6 +10,17 @@ pub struct Metric {
     pub(crate) inner: openmander_core::Metric,
 }
 
+/// Apply an aggregation given by name ("mean" or "min") to a metric.
+fn with_aggregation(metric: openmander_core::Metric, aggregate: &str) -> PyResult<Metric> {
+    let aggregation = match aggregate {
+        "mean" => openmander_core::Aggregation::Mean,
+        "min" => openmander_core::Aggregation::Min,
+        _ => return Err(PyValueError::new_err(format!("Unknown aggregate '{aggregate}', expected 'mean' or 'min'"))),
+    };
+    let inner = metric.with_aggregation(aggregation).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok(Metric { inner })
+}
+
 #[pymethods]
 impl Metric {
     /// Population equality metric for a given weight series (e.g., total population).
@@ -40,18 +52,35 @@ impl Metric {
         Self { inner }
     }
 
-    /// Polsby–Popper compactness metric.
+    /// Polsby–Popper compactness metric. ``aggregate`` combines the district scores: ``"mean"``
+    /// or ``"min"`` (the least compact district).
     #[staticmethod]
-    pub fn compactness_polsby_popper() -> Self {
-        let inner = openmander_core::Metric::compactness_polsby_popper();
-        Self { inner }
+    #[pyo3(signature = (aggregate="mean"))]
+    pub fn compactness_polsby_popper(aggregate: &str) -> PyResult<Self> {
+        with_aggregation(openmander_core::Metric::compactness_polsby_popper(), aggregate)
     }
 
-    /// Schwartzberg compactness metric.
+    /// Schwartzberg compactness metric. ``aggregate`` is ``"mean"`` or ``"min"``.
     #[staticmethod]
-    pub fn compactness_schwartzberg() -> Self {
-        let inner = openmander_core::Metric::compactness_schwartzberg();
-        Self { inner }
+    #[pyo3(signature = (aggregate="mean"))]
+    pub fn compactness_schwartzberg(aggregate: &str) -> PyResult<Self> {
+        with_aggregation(openmander_core::Metric::compactness_schwartzberg(), aggregate)
+    }
+
+    /// Reock compactness metric: district area over the area of its smallest enclosing circle.
+    /// ``aggregate`` is ``"mean"`` or ``"min"``. Requires a map with geometry.
+    #[staticmethod]
+    #[pyo3(signature = (aggregate="mean"))]
+    pub fn compactness_reock(aggregate: &str) -> PyResult<Self> {
+        with_aggregation(openmander_core::Metric::compactness_reock(), aggregate)
+    }
+
+    /// Convex hull compactness metric: district area over the area of its convex hull.
+    /// ``aggregate`` is ``"mean"`` or ``"min"``. Requires a map with geometry.
+    #[staticmethod]
+    #[pyo3(signature = (aggregate="mean"))]
+    pub fn compactness_convex_hull(aggregate: &str) -> PyResult<Self> {
+        with_aggregation(openmander_core::Metric::compactness_convex_hull(), aggregate)
     }
 
     /// Competitiveness metric based on district-level vote shares (binary).
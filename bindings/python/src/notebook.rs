@@ -0,0 +1,100 @@
+//! HTML previews shown by Jupyter (`_repr_html_`): a small inline SVG next to a summary table.
+//! Rendering never fails; parts that can't be drawn (e.g. a map loaded without geometry) are left out.
+
+use std::fmt::Write;
+
+use openmander_core::{Metric, MetricSeries, Palette, SvgOptions};
+
+/// Width of the inline map, in pixels.
+const PREVIEW_WIDTH: f64 = 360.0;
+
+/// Largest layer drawn in a map preview; bigger layers would make the notebook sluggish.
+const MAX_PREVIEW_UNITS: usize = 5_000;
+
+const TABLE_STYLE: &str = "border-collapse:collapse;font-size:12px";
+const CELL_STYLE: &str = "padding:2px 8px;text-align:right";
+
+pub(crate) fn plan_html(plan: &openmander_core::Plan) -> String {
+    let palette = Palette::OkabeIto;
+    let options = SvgOptions { width: PREVIEW_WIDTH, margin: 4.0, ..Default::default() };
+    let svg = plan.to_svg_string_with_options(false, Some(palette), &options).ok();
+    let colors = plan.district_colors(palette);
+
+    let series = plan.series();
+    let MetricSeries { pop, dem, rep } = MetricSeries::default();
+    let population = series.contains(&pop).then(|| plan.all_part_totals(&pop).ok()).flatten();
+    let votes = (series.contains(&dem) && series.contains(&rep))
+        .then(|| Some((plan.district_totals(&dem).ok()?, plan.district_totals(&rep).ok()?)))
+        .flatten();
+    let polsby_popper = plan.compute_metric(&Metric::compactness_polsby_popper());
+
+    let mut caption = format!("{} districts", plan.num_districts());
+    if let Some(name) = &plan.metadata().name { caption = format!("{} ({caption})", escape(name)) }
+
+    let mut header = vec!["District"];
+    if population.is_some() { header.extend(["Population", "Deviation"]) }
+    if votes.is_some() { header.push("Dem share") }
+    header.push("Polsby–Popper");
+
+    let ideal = population.as_ref()
+        .map(|totals| totals[1..].iter().sum::<f64>() / plan.num_districts().max(1) as f64);
+    let mut rows = String::new();
+    for district in 1..=plan.num_districts() as usize {
+        let color = colors.get(district - 1).map_or("#ffffff", String::as_str);
+        let _ = write!(rows, "<tr><td style=\"{CELL_STYLE}\"><span style=\"display:inline-block;width:10px;height:10px;\
+            margin-right:6px;background:{color}\"></span>{district}</td>");
+        if let (Some(totals), Some(ideal)) = (&population, ideal) {
+            let deviation = if ideal > 0.0 { (totals[district] - ideal) / ideal * 100.0 } else { 0.0 };
+            let _ = write!(rows, "<td style=\"{CELL_STYLE}\">{:.0}</td><td style=\"{CELL_STYLE}\">{deviation:+.2}%</td>", totals[district]);
+        }
+        if let Some((dem, rep)) = &votes {
+            let total = dem[district - 1] + rep[district - 1];
+            let share = if total > 0.0 { format!("{:.1}%", dem[district - 1] / total * 100.0) } else { "–".to_string() };
+            let _ = write!(rows, "<td style=\"{CELL_STYLE}\">{share}</td>");
+        }
+        let _ = write!(rows, "<td style=\"{CELL_STYLE}\">{:.3}</td></tr>", polsby_popper.get(district - 1).copied().unwrap_or(f64::NAN));
+    }
+    if let Some(unassigned) = population.as_ref().map(|totals| totals[0]).filter(|&total| total > 0.0) {
+        let _ = write!(caption, ", {unassigned:.0} people unassigned");
+    }
+
+    layout(svg.as_deref(), &format!("Plan: {caption}"), &header, &rows)
+}
+
+pub(crate) fn map_html(map: &openmander_core::Map) -> String {
+    let mut rows = String::new();
+    for layer in map.layers_iter() {
+        let _ = write!(rows, "<tr><td style=\"{CELL_STYLE};text-align:left\">{}</td><td style=\"{CELL_STYLE}\">{}</td></tr>",
+            layer.ty().to_str(), layer.len());
+    }
+
+    // Draw the coarsest layer that shows more than one unit.
+    let options = SvgOptions { width: PREVIEW_WIDTH, margin: 4.0, ..Default::default() };
+    let svg = map.layers_iter()
+        .find(|layer| layer.len() > 1 && layer.has_geometry())
+        .filter(|layer| layer.len() <= MAX_PREVIEW_UNITS)
+        .and_then(|layer| layer.to_svg_string_with_options(None, None, &options).ok());
+
+    let caption = if map.has_geometry() { "Map" } else { "Map (no geometry)" };
+    layout(svg.as_deref(), caption, &["Layer", "Units"], &rows)
+}
+
+/// Lay out an optional SVG and a captioned table side by side.
+fn layout(svg: Option<&str>, caption: &str, header: &[&str], rows: &str) -> String {
+    let mut html = String::from("<div style=\"display:flex;gap:16px;align-items:flex-start\">");
+    if let Some(svg) = svg {
+        // Drop the XML declaration, which isn't valid inside HTML.
+        let svg = svg.find("<svg").map_or(svg, |start| &svg[start..]);
+        let _ = write!(html, "<div style=\"flex:none;width:{PREVIEW_WIDTH}px\">{svg}</div>");
+    }
+    let _ = write!(html, "<table style=\"{TABLE_STYLE}\"><caption style=\"text-align:left;font-weight:bold\">{caption}</caption><thead><tr>");
+    for name in header {
+        let _ = write!(html, "<th style=\"{CELL_STYLE}\">{name}</th>");
+    }
+    let _ = write!(html, "</tr></thead><tbody>{rows}</tbody></table></div>");
+    html
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
@@ -1,5 +1,6 @@
 #![allow(unsafe_op_in_unsafe_fn)]
-use pyo3::{pyclass, pymethods};
+use pyo3::{pyclass, pymethods, Bound, PyResult};
+use pyo3::exceptions::PyValueError;
 
 use crate::Metric;
 
@@ -21,6 +22,13 @@ impl Objective {
     /// weights : list[float] | None, default None
     ///     Optional list of weights; if None, the core may default to all 1.0
     ///     or a normalized scheme.
+    /// priorities : list[int] | None, default None
+    ///     Rank metrics lexicographically instead of averaging them: metrics sharing a priority
+    ///     are averaged by weight into a tier, and lower priorities come first, so a later tier
+    ///     only matters when the earlier ones tie.
+    /// resolution : float, default 1e-3
+    ///     For lexicographic objectives, how much each tier is worth relative to the one above;
+    ///     a gain of more than about this much in a tier outweighs any change below it.
     ///
     /// Examples
     /// --------
@@ -30,10 +38,24 @@ impl Objective {
     /// ...     weights=[0.7, 0.3],
     /// ... )
     #[new]
-    pub fn new(metrics: Vec<Metric>, weights: Option<Vec<f64>>) -> Self {
-        let inner_metrics = metrics.into_iter().map(|m| m.inner).collect();
-        let inner = openmander_core::Objective::new(inner_metrics, weights);
-        Self { inner }
+    #[pyo3(signature = (metrics, weights=None, priorities=None, resolution=openmander_core::DEFAULT_RESOLUTION))]
+    pub fn new(metrics: Vec<Metric>, weights: Option<Vec<f64>>, priorities: Option<Vec<u32>>, resolution: f64) -> PyResult<Self> {
+        let inner_metrics = metrics.into_iter().map(|m| m.inner).collect::<Vec<_>>();
+        let Some(priorities) = priorities else {
+            return Ok(Self { inner: openmander_core::Objective::new(inner_metrics, weights) })
+        };
+        let composition = openmander_core::Composition::Lexicographic { priorities, resolution };
+        composition.validate(inner_metrics.len()).map_err(|e| PyValueError::new_err(format!("[Objective] {e}")))?;
+        Ok(Self { inner: openmander_core::Objective::new(inner_metrics, weights).with_composition(composition) })
+    }
+
+    /// Priority of each metric if the objective is lexicographic, otherwise None.
+    #[getter]
+    pub fn priorities(&self) -> Option<Vec<u32>> {
+        match self.inner.composition() {
+            openmander_core::Composition::Lexicographic { priorities, .. } => Some(priorities.clone()),
+            openmander_core::Composition::Weighted => None,
+        }
     }
 
     /// Number of metric terms in this objective.
@@ -54,5 +76,17 @@ impl Objective {
         self.inner.set_weights(weights);
     }
 
+    /// Serialize this objective (metrics and weights) to a JSON string.
+    pub fn to_json(&self) -> PyResult<String> {
+        self.inner.to_json().map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Create an objective from a JSON string produced by ``to_json``.
+    #[classmethod]
+    pub fn from_json(_cls: &Bound<'_, pyo3::types::PyType>, json: &str) -> PyResult<Self> {
+        let inner = openmander_core::Objective::from_json(json).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(Self { inner })
+    }
+
     fn __repr__(&self) -> String { format!("{}", self.inner) }
 }
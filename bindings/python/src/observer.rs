@@ -0,0 +1,85 @@
+#![allow(unsafe_op_in_unsafe_fn)]
+use std::{fs::File, io::BufWriter, path::Path};
+
+use openmander_core::{CsvStepWriter, JsonlStepWriter, Step, StepObserver};
+use pyo3::{pyclass, pymethods, Bound, PyResult, Python};
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::types::{PyDict, PyDictMethods};
+
+/// In-memory ring buffer of the most recent chain steps, filled by ``Plan.anneal_with_config(steps=...)``.
+///
+/// Each record is a dict with keys ``index``, ``phase``, ``accepted``, ``score``, and ``metrics``
+/// (a dict of metric name to score after the step).
+#[pyclass]
+pub struct StepBuffer {
+    pub(crate) inner: openmander_core::StepBuffer,
+}
+
+#[pymethods]
+impl StepBuffer {
+    #[new]
+    #[pyo3(signature = (capacity=10000))]
+    pub fn new(capacity: usize) -> Self {
+        Self { inner: openmander_core::StepBuffer::new(capacity) }
+    }
+
+    #[getter]
+    pub fn capacity(&self) -> usize { self.inner.capacity() }
+
+    fn __len__(&self) -> usize { self.inner.len() }
+
+    /// Recorded steps, oldest first.
+    pub fn records<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        self.inner.records().map(|record| {
+            let metrics = PyDict::new_bound(py);
+            for (name, score) in &record.metrics { metrics.set_item(name, score)? }
+            let dict = PyDict::new_bound(py);
+            dict.set_item("index", record.index)?;
+            dict.set_item("phase", record.phase)?;
+            dict.set_item("accepted", record.accepted)?;
+            dict.set_item("score", record.score)?;
+            dict.set_item("metrics", metrics)?;
+            Ok(dict)
+        }).collect()
+    }
+
+    pub fn clear(&mut self) { self.inner.clear() }
+
+    fn __repr__(&self) -> String {
+        format!("StepBuffer(len={}, capacity={})", self.inner.len(), self.inner.capacity())
+    }
+}
+
+/// A step log file, CSV or JSON Lines by extension.
+pub(crate) enum StepLog {
+    Csv(CsvStepWriter<BufWriter<File>>),
+    Jsonl(JsonlStepWriter<BufWriter<File>>),
+}
+
+impl StepLog {
+    pub(crate) fn create(path: &str) -> PyResult<Self> {
+        let extension = Path::new(path).extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase);
+        let log = match extension.as_deref() {
+            Some("csv") => CsvStepWriter::create(Path::new(path)).map(Self::Csv),
+            Some("jsonl") => JsonlStepWriter::create(Path::new(path)).map(Self::Jsonl),
+            _ => return Err(PyValueError::new_err(format!("Step log {path:?} must end in .csv or .jsonl"))),
+        };
+        log.map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    pub(crate) fn finish(self) -> PyResult<()> {
+        match self {
+            Self::Csv(writer) => writer.finish().map(drop),
+            Self::Jsonl(writer) => writer.finish().map(drop),
+        }.map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+}
+
+impl StepObserver for StepLog {
+    fn observe(&mut self, step: &Step) {
+        match self {
+            Self::Csv(writer) => writer.observe(step),
+            Self::Jsonl(writer) => writer.observe(step),
+        }
+    }
+}
@@ -1,16 +1,87 @@
 #![allow(unsafe_op_in_unsafe_fn)]
-use std::path::PathBuf;
+use std::{collections::BTreeMap, path::PathBuf};
 
-use pyo3::{pyfunction, PyResult, Python};
-use pyo3::exceptions::PyRuntimeError;
+use openmander_core::{BlockCsvSource, ColumnStats, DataSource, DemographicSource, GeoType};
+use pyo3::{pyfunction, Bound, PyResult, Python};
+use pyo3::types::{PyDict, PyDictMethods};
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
 
+/// Download data files for a state and build a map pack in a new directory under ``path``.
+///
+/// ``rename`` maps source data columns to pack column names (e.g. ``{"P0010001": "total_pop"}``)
+/// and ``keep`` lists source columns to keep as-is. With ``drop_unmapped=True`` all other
+/// source data columns are left out of the pack; ``columns`` is shorthand for ``keep=columns,
+/// drop_unmapped=True``. With ``hilbert_order=True`` units are renumbered along a Hilbert curve
+/// for better cache locality in chains.
+///
+/// ``layers`` lists the layers to build (``state``, ``county``, and ``block`` are always built).
+/// ``elections`` is a block-level CSV (with a ``GEOID`` column) to use instead of the default
+/// election data. With ``cache_dir`` set, downloads are kept there and reused by later builds,
+/// so a warm cache can build packs offline. Only the 2020 ``vintage`` is available.
+///
+/// The manifest records the URL and SHA-256 hash of every downloaded file and the build options.
+/// ``pin`` is an earlier pack (its directory or ``manifest.json``) to reproduce: the build fails
+/// unless it uses the same options and byte-identical source files.
+/// Returns the pack directory.
 #[pyfunction]
-#[pyo3(text_signature = "(state_code, path='.', has_vtd=True, verbose=0)")]
-#[pyo3(signature = (state_code, path=".", has_vtd=true, verbose=0))]
-pub fn build_pack(py: Python<'_>, state_code: &str, path: &str, has_vtd: bool, verbose: u8) -> PyResult<String> {
+#[pyo3(text_signature = "(state_code, path='.', has_vtd=True, verbose=0, rename=None, keep=None, drop_unmapped=False, hilbert_order=False, \
+    vintage=2020, layers=None, columns=None, elections=None, cache_dir=None, pin=None)")]
+#[pyo3(signature = (state_code, path=".", has_vtd=true, verbose=0, rename=None, keep=None, drop_unmapped=false, hilbert_order=false,
+    vintage=2020, layers=None, columns=None, elections=None, cache_dir=None, pin=None))]
+#[allow(clippy::too_many_arguments)]
+pub fn build_pack(
+    py: Python<'_>,
+    state_code: &str,
+    path: &str,
+    has_vtd: bool,
+    verbose: u8,
+    rename: Option<BTreeMap<String, String>>,
+    keep: Option<Vec<String>>,
+    drop_unmapped: bool,
+    hilbert_order: bool,
+    vintage: u16,
+    layers: Option<Vec<String>>,
+    columns: Option<Vec<String>>,
+    elections: Option<&str>,
+    cache_dir: Option<&str>,
+    pin: Option<&str>,
+) -> PyResult<String> {
     let pathbuf = PathBuf::from(path);
-    let p = py.allow_threads(|| openmander_core::build_pack(state_code, &pathbuf, has_vtd, verbose))
-        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    let layers = match layers {
+        Some(names) => names.iter()
+            .map(|name| GeoType::from_str(name).ok_or_else(|| PyValueError::new_err(format!(
+                "Unknown layer {name:?}. Expected one of: state, county, tract, group, vtd, block"))))
+            .collect::<PyResult<_>>()?,
+        None => GeoType::ALL.to_vec(),
+    };
+    let pin = pin.map(|pin| openmander_core::BuildRecord::read_pin(&PathBuf::from(pin)))
+        .transpose()
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let mut keep = keep.unwrap_or_default();
+    if let Some(columns) = &columns { keep.extend(columns.iter().cloned()) }
+    let options = openmander_core::BuildOptions {
+        vintage,
+        has_vtd,
+        layers,
+        fields: openmander_core::FieldMapping {
+            rename: rename.unwrap_or_default(),
+            keep,
+            drop_unmapped: drop_unmapped || columns.is_some(),
+        },
+        hilbert_order,
+        cache_dir: cache_dir.map(PathBuf::from),
+        pin,
+        verbose,
+        ..Default::default()
+    };
+    let p = py.allow_threads(|| {
+        let sources: Vec<Box<dyn DataSource>> = match elections {
+            Some(elections) => vec![Box::new(DemographicSource), Box::new(BlockCsvSource::new("elections", elections))],
+            None => openmander_core::default_sources(),
+        };
+        let sources = sources.iter().map(|source| source.as_ref()).collect::<Vec<_>>();
+        openmander_core::build_pack_with_sources(state_code, &pathbuf, &sources, &options)
+    }).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
     Ok(p.to_string_lossy().into_owned())
 }
 
@@ -24,6 +95,16 @@ pub fn download_pack(py: Python<'_>, state_code: &str, path: &str, verbose: u8)
     Ok(p.to_string_lossy().into_owned())
 }
 
+#[pyfunction]
+#[pyo3(text_signature = "(state_code, path='.', verbose=0)")]
+#[pyo3(signature = (state_code, path=".", verbose=0))]
+pub fn download_pack_without_geoms(py: Python<'_>, state_code: &str, path: &str, verbose: u8) -> PyResult<String> {
+    let pathbuf = PathBuf::from(path);
+    let p = py.allow_threads(|| openmander_core::download_pack_without_geoms(state_code, &pathbuf, verbose))
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    Ok(p.to_string_lossy().into_owned())
+}
+
 #[pyfunction]
 #[pyo3(text_signature = "(pack_path, verbose=0)")]
 #[pyo3(signature = (pack_path, verbose=0))]
@@ -32,3 +113,114 @@ pub fn validate_pack(py: Python<'_>, pack_path: &str, verbose: u8) -> PyResult<(
     py.allow_threads(|| openmander_core::validate_pack(&pathbuf, verbose))
         .map_err(|e| PyRuntimeError::new_err(e.to_string()))
 }
+
+/// Apply boundary changes (e.g. annexations) to units of ``layer`` in the pack at ``pack_path``
+/// and write it back in ``format``, without rebuilding it. ``changes_path`` is a GeoJSON
+/// FeatureCollection of new boundaries (see ``Map.apply_boundary_changes``). Returns the number
+/// of blocks that moved.
+#[pyfunction]
+#[pyo3(text_signature = "(pack_path, changes_path, layer, format='parquet')")]
+#[pyo3(signature = (pack_path, changes_path, layer, format="parquet"))]
+pub fn update_pack(py: Python<'_>, pack_path: &str, changes_path: &str, layer: &str, format: &str) -> PyResult<usize> {
+    use std::str::FromStr;
+    let ty = GeoType::from_str(layer).ok_or_else(|| PyValueError::new_err(format!(
+        "Unknown layer {layer:?}. Expected one of: state, county, tract, group, vtd, block")))?;
+    let format = openmander_core::PackFormat::from_str(format)
+        .map_err(|e| PyValueError::new_err(format!("Invalid format: {e}. Expected 'parquet' or 'json'")))?;
+    py.allow_threads(|| {
+        let changes = openmander_core::BoundaryChange::read_geojson(&PathBuf::from(changes_path), ty)?;
+        openmander_core::Map::update_pack(&PathBuf::from(pack_path), &changes, format)
+    }).map_err(|e| PyRuntimeError::new_err(e.to_string()))
+}
+
+/// Pretty-print the manifest of the pack at ``pack_path``: its layers with their column schemas,
+/// the files and options it was built from, and its file hashes. With ``format="text"`` returns a
+/// human-readable summary; with ``format="json"`` returns the manifest as indented JSON.
+#[pyfunction]
+#[pyo3(text_signature = "(pack_path, format='text')")]
+#[pyo3(signature = (pack_path, format="text"))]
+pub fn pack_manifest(pack_path: &str, format: &str) -> PyResult<String> {
+    let manifest = openmander_core::Manifest::read(&PathBuf::from(pack_path))
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    match format {
+        "text" => Ok(manifest.to_string()),
+        "json" => manifest.to_json().map_err(|e| PyRuntimeError::new_err(e.to_string())),
+        _ => Err(PyValueError::new_err(format!("Unknown format {format:?}. Expected 'text' or 'json'"))),
+    }
+}
+
+/// Citations of the datasets the pack at ``pack_path`` was built from (Census, VEST, ...), with
+/// their licenses, as a numbered list to reproduce in publications.
+#[pyfunction]
+#[pyo3(text_signature = "(pack_path)")]
+pub fn cite_pack(pack_path: &str) -> PyResult<String> {
+    openmander_core::cite_pack(&PathBuf::from(pack_path))
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+}
+
+/// Read the column statistics recorded in the manifest of the pack at ``pack_path``, by layer
+/// and column, without reading its tables (see ``Map.column_stats``). Packs written before
+/// statistics were recorded return an empty dict.
+#[pyfunction]
+#[pyo3(text_signature = "(pack_path)")]
+pub fn read_pack_column_stats<'py>(py: Python<'py>, pack_path: &str) -> PyResult<Bound<'py, PyDict>> {
+    let stats = openmander_core::Map::read_pack_column_stats(&PathBuf::from(pack_path))
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    column_stats_dict(py, &stats)
+}
+
+/// Convert column statistics by layer and column into nested dicts.
+pub(crate) fn column_stats_dict<'py>(py: Python<'py>, stats: &BTreeMap<String, BTreeMap<String, ColumnStats>>) -> PyResult<Bound<'py, PyDict>> {
+    let layers = PyDict::new_bound(py);
+    for (layer, columns) in stats {
+        let dict = PyDict::new_bound(py);
+        for (name, column) in columns {
+            dict.set_item(name, column_stats_entry(py, column)?)?;
+        }
+        layers.set_item(layer, dict)?;
+    }
+    Ok(layers)
+}
+
+/// Convert the statistics of one column into a dict.
+pub(crate) fn column_stats_entry<'py>(py: Python<'py>, column: &ColumnStats) -> PyResult<Bound<'py, PyDict>> {
+    let entry = PyDict::new_bound(py);
+    entry.set_item("min", column.min)?;
+    entry.set_item("max", column.max)?;
+    entry.set_item("sum", column.sum)?;
+    entry.set_item("q1", column.q1)?;
+    entry.set_item("median", column.median)?;
+    entry.set_item("q3", column.q3)?;
+    entry.set_item("histogram", column.histogram.clone())?;
+    Ok(entry)
+}
+
+/// Write a patch file to ``patch_path`` holding the changes from the pack at ``old_path`` to the
+/// pack at ``new_path`` (e.g. new columns or updated election data), so the old pack can be
+/// updated without downloading unchanged files such as its geometry. Returns the files it carries.
+#[pyfunction]
+#[pyo3(text_signature = "(old_path, new_path, patch_path)")]
+pub fn diff_packs(py: Python<'_>, old_path: &str, new_path: &str, patch_path: &str) -> PyResult<Vec<String>> {
+    let patch = py.allow_threads(|| openmander_core::diff_packs(&PathBuf::from(old_path), &PathBuf::from(new_path), &PathBuf::from(patch_path)))
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    Ok(patch.changed_files().into_iter().map(str::to_string).collect())
+}
+
+/// Apply the patch file at ``patch_path`` (see ``diff_packs``) to the pack at ``pack_path``.
+/// Returns the number of files written or deleted, or 0 if the pack was already up to date.
+#[pyfunction]
+#[pyo3(text_signature = "(pack_path, patch_path)")]
+pub fn apply_pack_patch(py: Python<'_>, pack_path: &str, patch_path: &str) -> PyResult<usize> {
+    py.allow_threads(|| openmander_core::apply_pack_patch(&PathBuf::from(pack_path), &PathBuf::from(patch_path)))
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+}
+
+/// Copy the pack at ``src`` (a directory, zip archive or ``.openmander`` bundle) to a new pack at
+/// ``dst``, stored by its extension: ``.zip``, ``.openmander`` (one file, easy to share), or
+/// otherwise a directory. Returns the number of files copied.
+#[pyfunction]
+#[pyo3(text_signature = "(src, dst)")]
+pub fn convert_pack(py: Python<'_>, src: &str, dst: &str) -> PyResult<usize> {
+    py.allow_threads(|| openmander_core::convert_pack(&PathBuf::from(src), &PathBuf::from(dst)))
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+}
@@ -1,11 +1,22 @@
 #![allow(unsafe_op_in_unsafe_fn)]
-use std::{collections::HashMap, path::PathBuf};
+use std::{collections::{BTreeMap, HashMap}, path::PathBuf};
 
-use pyo3::{pyclass, pymethods, Bound, Py, PyResult, Python};
+use pyo3::{pyclass, pymethods, Bound, Py, PyAny, PyRef, PyResult, Python};
 use pyo3::exceptions::{PyIOError, PyRuntimeError, PyValueError};
 use pyo3::types::{PyAnyMethods, PyBytes, PyDict, PyDictMethods, PyList, PyListMethods};
 
-use crate::Map;
+use openmander_core::EveryNth;
+
+use crate::{Map, map::{parse_projection, parse_shapefile_schema}};
+
+/// County clusters as ``(counties, districts, total)`` tuples.
+type Clustering = Vec<(Vec<String>, u32, f64)>;
+
+fn to_clusters(clusters: Clustering) -> Vec<openmander_core::CountyCluster> {
+    clusters.into_iter()
+        .map(|(counties, districts, total)| openmander_core::CountyCluster { counties, districts, total })
+        .collect()
+}
 
 /// Python-facing Plan wrapper that holds a strong ref to the PyMap owner.
 /// This ensures the underlying Map outlives the Plan reference stored in `inner`.
@@ -14,6 +25,11 @@ pub struct Plan {
     inner: openmander_core::Plan,
 }
 
+impl Plan {
+    #[inline] pub(crate) fn inner(&self) -> &openmander_core::Plan { &self.inner }
+    #[inline] pub(crate) fn from_inner(inner: openmander_core::Plan) -> Self { Self { inner } }
+}
+
 #[pymethods]
 impl Plan {
     /// Construct a Plan from a Python Map.
@@ -25,11 +41,123 @@ impl Plan {
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))? })
     }
 
+    /// Translate the plan onto ``map``, a map of another Census vintage, using an NHGIS block
+    /// crosswalk CSV from this plan's vintage to it. Each target block joins the district holding
+    /// most of the ``pop_series`` population flowing into it; unreached blocks stay unassigned.
+    pub fn crosswalk_vintage(&self, py: Python<'_>, crosswalk_path: &str, map: Py<Map>, pop_series: &str) -> PyResult<Self> {
+        let arc = map.borrow(py).inner_arc();
+        let plan = py.allow_threads(|| {
+            let crosswalk = openmander_core::VintageCrosswalk::read_nhgis_csv(std::path::Path::new(crosswalk_path))?;
+            self.inner.crosswalk_vintage(&crosswalk, arc, pop_series)
+        }).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(Self { inner: plan })
+    }
+
+    /// Create a plan from a block-level column of district numbers stored in the map,
+    /// such as a historical enacted plan column (e.g. ``"cd114"``).
+    #[staticmethod]
+    pub fn from_assignment_column(py: Python<'_>, map: Py<Map>, column: &str) -> PyResult<Self> {
+        let arc = map.borrow(py).inner_arc();
+        Ok(Self { inner: openmander_core::Plan::from_assignment_column(arc, column)
+            .map_err(|e| PyValueError::new_err(e.to_string()))? })
+    }
+
+    /// Return an independent copy of this plan sharing the same map.
+    /// Only the assignment state is copied, so this is cheap even for large maps.
+    pub fn copy(&self) -> Self {
+        Self { inner: self.inner.clone() }
+    }
+
+    pub fn __copy__(&self) -> Self { self.copy() }
+
+    /// Return a copy of this plan whose metadata names this plan as its ``parent``
+    /// (its name, or else its source file). The author and description are kept.
+    pub fn fork(&self) -> Self {
+        Self { inner: self.inner.fork() }
+    }
+
+    /// Get the plan's metadata as a dict with keys ``name``, ``author``, ``description``,
+    /// ``source``, ``parent``, ``created`` (Unix seconds) and ``tool_version``.
+    pub fn metadata<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let metadata = self.inner.metadata();
+        let dict = PyDict::new_bound(py);
+        dict.set_item("name", &metadata.name)?;
+        dict.set_item("author", &metadata.author)?;
+        dict.set_item("description", &metadata.description)?;
+        dict.set_item("source", &metadata.source)?;
+        dict.set_item("parent", &metadata.parent)?;
+        dict.set_item("created", metadata.created)?;
+        dict.set_item("tool_version", &metadata.tool_version)?;
+        Ok(dict)
+    }
+
+    /// Update descriptive metadata fields. Fields left as None are unchanged.
+    #[pyo3(signature = (name=None, author=None, description=None, parent=None))]
+    pub fn set_metadata(&mut self, name: Option<String>, author: Option<String>, description: Option<String>, parent: Option<String>) {
+        let metadata = self.inner.metadata_mut();
+        if name.is_some() { metadata.name = name }
+        if author.is_some() { metadata.author = author }
+        if description.is_some() { metadata.description = description }
+        if parent.is_some() { metadata.parent = parent }
+    }
+
+    /// Start recording every assignment operation on this plan.
+    pub fn enable_audit_log(&mut self) { self.inner.enable_audit_log() }
+
+    /// Stop recording and discard the audit log.
+    pub fn disable_audit_log(&mut self) { self.inner.disable_audit_log() }
+
+    /// Get the audit log as JSON Lines (one operation per line), or None if not enabled.
+    pub fn audit_log_jsonl(&self) -> PyResult<Option<String>> {
+        self.inner.audit_log()
+            .map(|log| log.to_jsonl())
+            .transpose()
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+
+    /// Write the audit log to a JSON Lines file.
+    pub fn write_audit_log(&self, path: &str) -> PyResult<()> {
+        self.inner.write_audit_log(&PathBuf::from(path))
+            .map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    /// Start keeping an undo/redo history of edits.
+    ///
+    /// Parameters
+    /// ----------
+    /// capacity : int, optional
+    ///     Maximum number of block moves to remember; the oldest edits are forgotten first.
+    #[pyo3(signature = (capacity=openmander_core::DEFAULT_HISTORY_CAPACITY))]
+    pub fn enable_history(&mut self, capacity: usize) { self.inner.enable_history(capacity) }
+
+    /// Stop keeping history and discard it.
+    pub fn disable_history(&mut self) { self.inner.disable_history() }
+
+    /// Revert the most recent edit. Returns False if there was nothing to undo.
+    /// Only assignments are restored; anchors and whole-county mode are unchanged.
+    pub fn undo(&mut self) -> PyResult<bool> {
+        self.inner.undo().map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+
+    /// Reapply the most recently undone edit. Returns False if there was nothing to redo.
+    pub fn redo(&mut self) -> PyResult<bool> {
+        self.inner.redo().map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+
+    /// Check if there is an edit to undo.
+    pub fn can_undo(&self) -> bool { self.inner.can_undo() }
+
+    /// Check if there is an undone edit to redo.
+    pub fn can_redo(&self) -> bool { self.inner.can_redo() }
+
     /// Get the number of districts in this plan (excluding unassigned 0).
     pub fn num_districts(&self) -> PyResult<u32> {
         Ok(self.inner.num_districts())
     }
 
+    /// Estimated heap memory of the plan's own state, in bytes (the map is shared and not counted).
+    pub fn memory_footprint(&self) -> usize { self.inner.memory_footprint() }
+
     /// Get block assignments as a Python dict { "block_geoid": district:int }.
     /// Includes zeros for unassigned blocks.
     pub fn assignments<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
@@ -63,6 +191,16 @@ impl Plan {
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))
     }
 
+    /// Set every block's district from a list in base-layer order (0 for unassigned), checking the
+    /// length and district range, and with ``check_contiguity`` that every changed district stays
+    /// contiguous (the plan is left unchanged otherwise). Only changed blocks are moved.
+    /// Returns the districts whose blocks changed.
+    #[pyo3(signature = (assignments, check_contiguity=false))]
+    pub fn set_assignment_bulk(&mut self, py: Python<'_>, assignments: Vec<u32>, check_contiguity: bool) -> PyResult<Vec<u32>> {
+        py.allow_threads(|| self.inner.set_assignment_bulk(&assignments, check_contiguity))
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
     /// Get the list of weight series available in the map's node weights.
     pub fn series<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
         let mut series = self.inner.series().into_iter().collect::<Vec<_>>();
@@ -93,6 +231,51 @@ impl Plan {
         )
     }
 
+    /// The ``k`` single-block reassignments that most improve ``metric``, best first, as dicts with
+    /// ``block``, ``from``, ``to``, ``score`` (after the move) and ``improvement``. Only moves
+    /// that keep districts contiguous and improve the score are suggested; the plan is unchanged.
+    #[pyo3(signature = (metric, k=5))]
+    pub fn best_single_moves<'py>(&self, py: Python<'py>, metric: &crate::Metric, k: usize) -> PyResult<Bound<'py, PyList>> {
+        let moves = py.allow_threads(|| self.inner.best_single_moves(&metric.inner, k))
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let result = PyList::empty_bound(py);
+        for suggested in moves {
+            let dict = PyDict::new_bound(py);
+            dict.set_item("block", suggested.block)?;
+            dict.set_item("from", suggested.from)?;
+            dict.set_item("to", suggested.to)?;
+            dict.set_item("score", suggested.score)?;
+            dict.set_item("improvement", suggested.improvement)?;
+            result.append(dict)?;
+        }
+        Ok(result)
+    }
+
+    /// Break ``metric``'s score down by district, as a dict with ``score`` and ``contributions``:
+    /// dicts with ``label``, ``districts``, ``value`` (the district's value) and ``contribution``
+    /// (its part of the mean), worst district first.
+    pub fn explain_metric<'py>(&self, py: Python<'py>, metric: &crate::Metric) -> PyResult<Bound<'py, PyDict>> {
+        let explanation = py.allow_threads(|| self.inner.explain_metric(&metric.inner));
+        explanation_dict(py, explanation)
+    }
+
+    /// Break the number of county splits down by split county, most splits first, in the shape
+    /// of ``explain_metric`` (each ``label`` is a county GEOID).
+    pub fn explain_county_splits<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let explanation = self.inner.explain_county_splits()
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        explanation_dict(py, explanation)
+    }
+
+    /// Break the efficiency gap of ``contest`` (e.g. ``"20_PRES"``) down by district, largest
+    /// contribution first, in the shape of ``explain_metric``. Each ``value`` is the district's
+    /// Democratic share; positive contributions favor Democrats.
+    pub fn explain_efficiency_gap<'py>(&self, py: Python<'py>, contest: &str) -> PyResult<Bound<'py, PyDict>> {
+        let explanation = self.inner.explain_efficiency_gap(&openmander_core::Contest::named(contest))
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        explanation_dict(py, explanation)
+    }
+
     /// Compute objective value for the current partition.
     pub fn compute_objective<'py>(&self, py: Python<'py>, objective: &crate::Objective) -> PyResult<f64> {
         py.allow_threads(||
@@ -114,6 +297,48 @@ impl Plan {
         )
     }
 
+    /// Deterministically repair balance of a weight series (e.g. after manual edits), moving frontier
+    /// units from over- to under-populated districts. Returns True if all districts end within tolerance.
+    #[pyo3(signature = (series, tolerance=0.01))]
+    pub fn rebalance(&mut self, py: Python<'_>, series: &str, tolerance: f64) -> PyResult<bool> {
+        py.allow_threads(|| self.inner.rebalance(series, tolerance))
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+
+    /// Re-split two small adjacent districts exactly, along the shortest boundary keeping both within
+    /// tolerance of the ideal total. Returns False, leaving the plan unchanged, if no split is possible.
+    #[pyo3(signature = (a, b, series, tolerance=0.01))]
+    pub fn refine_pair_exact(&mut self, py: Python<'_>, a: u32, b: u32, series: &str, tolerance: f64) -> PyResult<bool> {
+        py.allow_threads(|| self.inner.refine_pair_exact(a, b, series, tolerance))
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+
+    /// Enumerate county clusterings: splits of the state into connected clusters of whole counties,
+    /// each fitting a whole number of districts within tolerance. Each clustering is a list of
+    /// ``(counties, districts, total)`` tuples; finer clusterings come first.
+    #[pyo3(signature = (pop_series, tolerance=0.05, limit=100))]
+    pub fn county_clusters(&self, py: Python<'_>, pop_series: &str, tolerance: f64, limit: usize) -> PyResult<Vec<Clustering>> {
+        let clusterings = py.allow_threads(|| self.inner.county_clusters(pop_series, tolerance, limit))
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Ok(clusterings.into_iter()
+            .map(|clusters| clusters.into_iter().map(|c| (c.counties, c.districts, c.total)).collect())
+            .collect())
+    }
+
+    /// Confine districts to a county clustering (one from ``county_clusters``) and redraw the plan
+    /// within it. Optimizers and samplers keep every block in a district of its cluster.
+    pub fn set_county_clusters(&mut self, py: Python<'_>, clusters: Clustering) -> PyResult<()> {
+        let clusters = to_clusters(clusters);
+        py.allow_threads(|| self.inner.set_county_clusters(&clusters))
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+
+    /// Remove the county clustering constraint.
+    pub fn clear_county_clusters(&mut self) { self.inner.clear_county_clusters() }
+
+    /// Check that every block is in a district of its county cluster.
+    pub fn county_clusters_satisfied(&self) -> bool { self.inner.county_clusters_satisfied() }
+
     pub fn anneal_balance<'py>(&mut self,
         py: Python<'py>,
         series: &str,
@@ -181,6 +406,177 @@ impl Plan {
         )
     }
 
+    /// Run simulated annealing as described by a ``ChainConfig`` (see ``anneal`` for the fields).
+    ///
+    /// Every ``every``-th step (with the score of each metric in the config) is recorded in
+    /// ``steps``, a ``StepBuffer``, and written to ``log_path``, a ``.csv`` or ``.jsonl`` file.
+    #[pyo3(signature = (config, steps=None, log_path=None, every=1))]
+    pub fn anneal_with_config(
+        &mut self,
+        py: Python<'_>,
+        config: &crate::ChainConfig,
+        steps: Option<&Bound<'_, crate::StepBuffer>>,
+        log_path: Option<&str>,
+        every: usize,
+    ) -> PyResult<()> {
+        let config = config.to_core()?;
+        if steps.is_none() && log_path.is_none() {
+            return py.allow_threads(|| self.inner.anneal_with_config(&config))
+                .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+        }
+
+        // Move the buffer out of Python while the chain runs without the GIL.
+        let buffer = steps.map(|steps| std::mem::take(&mut steps.borrow_mut().inner));
+        let log = log_path.map(crate::observer::StepLog::create).transpose()?;
+        let mut observer = EveryNth { inner: (buffer, log), n: every };
+        let result = py.allow_threads(|| self.inner.anneal_with_observer(&config, &mut observer));
+
+        let (buffer, log) = observer.inner;
+        if let (Some(steps), Some(buffer)) = (steps, buffer) { steps.borrow_mut().inner = buffer }
+        result.map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        log.map_or(Ok(()), crate::observer::StepLog::finish)
+    }
+
+    /// Describe each way the plan breaks ``constraints`` (empty if it satisfies them all).
+    pub fn constraint_violations(&self, constraints: &crate::Constraints) -> PyResult<Vec<String>> {
+        self.inner.constraint_violations(&constraints.to_core())
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Seconds spent in each stage of the last annealing run (proposal, contiguity, metrics,
+    /// acceptance, other), with step counts, if its ``ChainConfig`` set ``profile``.
+    pub fn run_profile<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDict>>> {
+        let Some(profile) = self.inner.run_profile() else { return Ok(None) };
+        let dict = PyDict::new_bound(py);
+        dict.set_item("steps", profile.steps)?;
+        dict.set_item("invalid", profile.invalid)?;
+        dict.set_item("proposal_secs", profile.proposal_secs)?;
+        dict.set_item("contiguity_secs", profile.contiguity_secs)?;
+        dict.set_item("metrics_secs", profile.metrics_secs)?;
+        dict.set_item("acceptance_secs", profile.acceptance_secs)?;
+        dict.set_item("other_secs", profile.other_secs)?;
+        Ok(Some(dict))
+    }
+
+    /// Mean-median and lopsided-wins tests of the ``dem_series`` and ``rep_series`` votes, with
+    /// p-values. The mean-median confidence interval and p-value come from ``samples`` bootstrap
+    /// replicates resampling each district's blocks; pass ``seed`` for reproducible results.
+    #[pyo3(signature = (dem_series, rep_series, samples=1000, seed=None))]
+    pub fn partisan_tests<'py>(&self, py: Python<'py>, dem_series: &str, rep_series: &str, samples: usize, seed: Option<u64>) -> PyResult<Bound<'py, PyDict>> {
+        let tests = py.allow_threads(|| self.inner.partisan_tests(dem_series, rep_series, samples, seed))
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let dict = PyDict::new_bound(py);
+        dict.set_item("mean_median", tests.mean_median)?;
+        dict.set_item("mean_median_ci", tests.mean_median_ci)?;
+        dict.set_item("mean_median_p_value", tests.mean_median_p_value)?;
+        dict.set_item("lopsided_wins", tests.lopsided_wins)?;
+        dict.set_item("lopsided_wins_p_value", tests.lopsided_wins_p_value)?;
+        Ok(dict)
+    }
+
+    /// Contests stored in the map as ``(name, dem_series, rep_series)``, e.g.
+    /// ``("20_PRES", "E_20_PRES_Dem", "E_20_PRES_Rep")``.
+    pub fn contests(&self) -> Vec<(String, String, String)> {
+        self.inner.contests().into_iter().map(|contest| (contest.name, contest.dem, contest.rep)).collect()
+    }
+
+    /// District-level results of the contest ``contest`` (e.g. ``"20_PRES"``) in a year that swings
+    /// by ``swing`` towards Democrats (e.g. 0.03 for D+3). ``kind`` is ``"uniform"`` (every block
+    /// moves by ``swing``) or ``"proportional"`` (blocks move in proportion to their Democratic share).
+    #[pyo3(signature = (contest, swing=0.0, kind="uniform"))]
+    pub fn election_results<'py>(&self, py: Python<'py>, contest: &str, swing: f64, kind: &str) -> PyResult<Bound<'py, PyDict>> {
+        let swing = parse_swing(swing, kind)?;
+        let results = self.inner.election_results(&openmander_core::Contest::named(contest), swing)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let dict = PyDict::new_bound(py);
+        dict.set_item("contest", results.contest.name)?;
+        dict.set_item("dem_share", results.dem_share)?;
+        dict.set_item("dem_seats", results.dem_seats)?;
+        dict.set_item("dem_votes", results.districts.iter().map(|result| result.dem).collect::<Vec<_>>())?;
+        dict.set_item("rep_votes", results.districts.iter().map(|result| result.rep).collect::<Vec<_>>())?;
+        dict.set_item("dem_shares", results.districts.iter().map(|result| result.dem_share()).collect::<Vec<_>>())?;
+        Ok(dict)
+    }
+
+    /// Democratic seats won in ``contest`` under each of ``swings`` of the given ``kind``, e.g. to
+    /// trace a seats-votes curve.
+    #[pyo3(signature = (contest, swings, kind="uniform"))]
+    pub fn seats_under_swings(&self, contest: &str, swings: Vec<f64>, kind: &str) -> PyResult<Vec<u32>> {
+        let swings = swings.into_iter().map(|swing| parse_swing(swing, kind)).collect::<PyResult<Vec<_>>>()?;
+        self.inner.seats_under_swings(&openmander_core::Contest::named(contest), &swings)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Anneal super-regions of whole counties (``(counties, districts, total)`` tuples, e.g. a metro
+    /// area and the rest of the state) independently and in parallel with ``config``, stitch them
+    /// together, then anneal the districts along region seams with ``seam``, if given.
+    #[pyo3(signature = (regions, config, seam=None))]
+    pub fn anneal_by_region(&mut self, py: Python<'_>, regions: Clustering, config: &crate::ChainConfig, seam: Option<&crate::ChainConfig>) -> PyResult<()> {
+        let regions = to_clusters(regions);
+        let (config, seam) = (config.to_core()?, seam.map(|seam| seam.to_core()).transpose()?);
+        py.allow_threads(|| self.inner.anneal_by_region(&regions, &config, seam.as_ref()))
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+
+    /// Run simulated annealing from a possibly infeasible plan, with hard constraints that start
+    /// relaxed and tighten to their targets over the run.
+    ///
+    /// Violations of the current bounds are penalized with a weight that grows geometrically
+    /// from ``initial_penalty`` to ``final_penalty`` ("big-M" annealing).
+    ///
+    /// Parameters
+    /// ----------
+    /// objective : Objective
+    ///     Objective to maximize.
+    /// pop_series : str
+    ///     Name of the node-weight series to balance (e.g., total population).
+    /// max_deviation : float
+    ///     Target maximum absolute deviation from the ideal district total (e.g., 0.01).
+    /// max_iter : int
+    ///     Number of annealing iterations.
+    /// max_splits : int, optional
+    ///     Target maximum number of county splits.
+    /// initial_temp, final_temp : float
+    ///     Geometric temperature schedule.
+    /// initial_penalty, final_penalty : float
+    ///     Geometric penalty weight schedule.
+    /// max_total_deviation : float, optional
+    ///     Target maximum total deviation (largest minus smallest district, e.g. 0.1 for ±5%).
+    ///
+    /// Returns
+    /// -------
+    /// bool
+    ///     True if a plan satisfying every target bound was found.
+    #[pyo3(signature = (objective, pop_series, max_deviation, max_iter, max_splits=None, initial_temp=1.0, final_temp=1e-4, initial_penalty=1.0, final_penalty=1000.0, max_total_deviation=None))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn anneal_relaxed<'py>(&mut self,
+        py: Python<'py>,
+        objective: &crate::Objective,
+        pop_series: &str,
+        max_deviation: f64,
+        max_iter: usize,
+        max_splits: Option<usize>,
+        initial_temp: f64,
+        final_temp: f64,
+        initial_penalty: f64,
+        final_penalty: f64,
+        max_total_deviation: Option<f64>,
+    ) -> PyResult<bool> {
+        let mut constraints = vec![
+            openmander_core::RelaxedConstraint::population_deviation(pop_series.to_string(), max_deviation),
+        ];
+        if let Some(max_splits) = max_splits {
+            constraints.push(openmander_core::RelaxedConstraint::county_splits(max_splits));
+        }
+        if let Some(max_total_deviation) = max_total_deviation {
+            constraints.push(openmander_core::RelaxedConstraint::total_deviation(pop_series.to_string(), max_total_deviation));
+        }
+        py.allow_threads(||
+            self.inner.anneal_relaxed(&objective.inner, &constraints, max_iter, initial_temp, final_temp, initial_penalty, final_penalty)
+                .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+        )
+    }
+
     /// Improve balance using a Tabu search heuristic.
     ///
     /// Parameters
@@ -209,39 +605,510 @@ impl Plan {
         })
     }
 
-    pub fn recombine<'py>(&mut self, py: Python<'py>, a: u32, b: u32) -> PyResult<()> {
+    /// Merge districts ``a`` and ``b`` and split them again, balancing the halves by ``pop_series``.
+    pub fn recombine<'py>(&mut self, py: Python<'py>, a: u32, b: u32, pop_series: &str) -> PyResult<()> {
         py.allow_threads(||
-            self.inner.recombine(a, b)
+            self.inner.recombine(a, b, pop_series)
                 .map_err(|e| PyRuntimeError::new_err(e.to_string()))
         )
     }
 
-    /// Load assignments from a CSV path (same validation as Rust `load_csv`)
+    /// Run a ReCom (recombination) chain for ``steps`` steps, as GerryChain does: each step merges
+    /// a random pair of adjacent districts and re-splits them along a random spanning tree, keeping
+    /// both halves' ``pop_series`` totals within ``tolerance`` of the ideal. Steps with no such split
+    /// leave the plan unchanged. The same ``seed`` gives the same chain.
+    /// Returns the number of steps that changed the plan.
+    #[pyo3(signature = (pop_series, tolerance=0.02, steps=1000, seed=None))]
+    pub fn recom(&mut self, py: Python<'_>, pop_series: &str, tolerance: f64, steps: usize, seed: Option<u64>) -> PyResult<usize> {
+        py.allow_threads(|| self.inner.recom(pop_series, tolerance, steps, seed))
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+
+    /// Sample an ensemble with a ReCom chain started from this plan (see ``recom``), keeping a copy
+    /// of the plan every ``sample_every`` steps. This plan is unchanged.
+    #[pyo3(signature = (pop_series, steps, sample_every, tolerance=0.02, seed=None))]
+    pub fn recom_ensemble(&self, py: Python<'_>, pop_series: &str, steps: usize, sample_every: usize, tolerance: f64, seed: Option<u64>) -> PyResult<Vec<Plan>> {
+        let samples = py.allow_threads(|| self.inner.recom_ensemble(pop_series, tolerance, steps, sample_every, seed))
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Ok(samples.into_iter().map(Plan::from_inner).collect())
+    }
+
+    /// Perturb this plan into `k` diverse starting plans, each after `steps` random ReCom steps
+    /// whose splits keep both districts' `pop_series` totals within `tolerance` of the ideal.
+    /// The same `seed` gives the same starts.
+    #[pyo3(signature = (k, steps, pop_series, tolerance=0.01, seed=None))]
+    pub fn warm_starts(&self, py: Python<'_>, k: usize, steps: usize, pop_series: &str, tolerance: f64, seed: Option<u64>) -> PyResult<Vec<Plan>> {
+        let starts = py.allow_threads(|| self.inner.warm_starts(k, steps, pop_series, tolerance, seed))
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Ok(starts.into_iter().map(Plan::from_inner).collect())
+    }
+
+    /// Require that a district always contains a geographic unit.
+    ///
+    /// The unit's blocks are assigned to the district immediately, and optimization
+    /// moves will never take them out of it.
+    ///
+    /// Parameters
+    /// ----------
+    /// layer : str
+    ///     One of: "state", "county", "tract", "group", "vtd", "block".
+    /// geo_id : str
+    ///     Identifier of the unit at that level.
+    /// district : int
+    ///     District that must contain the unit (1-indexed).
+    pub fn anchor_unit(&mut self, layer: &str, geo_id: &str, district: u32) -> PyResult<()> {
+        self.inner.anchor_unit(layer, geo_id, district)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Remove all district anchors.
+    pub fn clear_anchors(&mut self) { self.inner.clear_anchors() }
+
+    /// Check that every anchored unit is in its district.
+    pub fn anchors_satisfied(&self) -> bool { self.inner.anchors_satisfied() }
+
+    /// Get the number of seats elected at large, besides the districts.
+    pub fn at_large_seats(&self) -> u32 { self.inner.at_large_seats() }
+
+    /// Set the number of seats elected at large (e.g. 2 for a board of 5 districts and 2 at-large members).
+    pub fn set_at_large_seats(&mut self, seats: u32) { self.inner.set_at_large_seats(seats) }
+
+    /// Get the total number of seats: districts plus at-large seats.
+    pub fn total_seats(&self) -> u32 { self.inner.total_seats() }
+
+    /// Hold the blocks of the given units unassigned and whole as the at-large area of a hybrid
+    /// plan. Optimization never moves them, and district metrics only cover the districted rest.
+    ///
+    /// Parameters
+    /// ----------
+    /// layer : str
+    ///     One of: "state", "county", "tract", "group", "vtd", "block".
+    /// geo_ids : list[str]
+    ///     Identifiers of the units at that level.
+    pub fn set_at_large_area(&mut self, layer: &str, geo_ids: Vec<String>) -> PyResult<()> {
+        let geo_ids = geo_ids.iter().map(String::as_str).collect::<Vec<_>>();
+        self.inner.set_at_large_area(layer, &geo_ids)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Release the at-large area (its blocks stay unassigned until redrawn).
+    pub fn clear_at_large_area(&mut self) -> PyResult<()> {
+        self.inner.clear_at_large_area().map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+
+    /// Get the GEOIDs of the blocks in the at-large area.
+    pub fn at_large_area(&self) -> PyResult<Vec<String>> {
+        let blocks = self.inner.at_large_area().map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Ok(blocks.iter().map(|geo_id| geo_id.id().to_string()).collect())
+    }
+
+    /// Switch to whole-county districting.
+    ///
+    /// Each county moves between districts as a single unit, except counties whose
+    /// ``pop_series`` total exceeds one district's ideal total, which are subdivided
+    /// along VTD lines. Split counties are consolidated where that keeps districts contiguous
+    /// and respects anchors. Returns the number of atomic units and the GEOIDs of those left split.
+    pub fn set_whole_county_mode(&mut self, pop_series: &str) -> PyResult<(usize, Vec<String>)> {
+        self.inner.set_whole_county_mode(pop_series)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Leave whole-county mode, allowing individual blocks to move again.
+    pub fn clear_whole_county_mode(&mut self) { self.inner.clear_whole_county_mode() }
+
+    /// Load assignments from a CSV path (same validation as Rust `load_csv`).
+    /// Metadata is read from a ``.meta.json`` sidecar file next to the CSV, if present.
     pub fn load_csv(&mut self, path: &str) -> PyResult<()> {
         self.inner.read_from_csv(&PathBuf::from(path))
             .map_err(|e| PyIOError::new_err(e.to_string()))
     }
 
-    /// Save plan to CSV at the given path (non-zero assignments only)
+    /// Save plan to CSV at the given path (non-zero assignments only).
+    /// Metadata is written to a ``.meta.json`` sidecar file next to the CSV.
     pub fn to_csv(&self, path: &str) -> PyResult<()> {
         self.inner.write_to_csv(&PathBuf::from(path))
             .map_err(|e| PyIOError::new_err(e.to_string()))
     }
 
-    /// Save plan to SVG at the given path (shows district outlines and fills)
-    #[pyo3(signature = (path, color_partisan=false))]
-    pub fn to_svg(&self, path: &str, color_partisan: bool) -> PyResult<()> {
-        self.inner.to_svg(&PathBuf::from(path), color_partisan)
+    /// Load assignments from a block assignment file (``GEOID20,District``), as exchanged with
+    /// Dave's Redistricting, Districtr and state portals. Files assigning coarser units (e.g. VTDs
+    /// or tracts) assign every block in each unit.
+    pub fn load_baf(&mut self, path: &str) -> PyResult<()> {
+        self.inner.read_baf(&PathBuf::from(path))
+            .map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    /// Save the plan as a block assignment file of ``layer`` units. Units of a coarser layer than
+    /// blocks that are split between districts go to the district holding most of their
+    /// ``pop_series`` (or most of their blocks).
+    #[pyo3(signature = (path, layer="block", pop_series=None))]
+    pub fn to_baf(&self, path: &str, layer: &str, pop_series: Option<&str>) -> PyResult<()> {
+        self.inner.write_baf(&PathBuf::from(path), layer, pop_series)
+            .map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    /// Content ID of the plan's assignments (SHA-256 of its ``GEOID,district`` lines in GEOID order).
+    pub fn plan_id(&self) -> PyResult<String> {
+        self.inner.plan_id().map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+
+    /// Stamp every export written from now on (CSV, block assignment files, GeoJSON, SVG) with the
+    /// plan ID, library version and a hash of its contents, to detect exhibits edited after export.
+    pub fn set_stamp_exports(&mut self, enabled: bool) { self.inner.set_stamp_exports(enabled) }
+
+    /// Check that the stamped export at ``path`` is unmodified and was exported from this plan,
+    /// raising ``ValueError`` otherwise. Returns its stamp as a dict (``plan_id``, ``tool``, ``sha256``).
+    pub fn verify_export<'py>(&self, py: Python<'py>, path: &str) -> PyResult<Bound<'py, PyDict>> {
+        let stamp = self.inner.verify_export_file(&PathBuf::from(path))
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let dict = PyDict::new_bound(py);
+        dict.set_item("plan_id", stamp.plan_id)?;
+        dict.set_item("tool", stamp.tool)?;
+        dict.set_item("sha256", stamp.sha256)?;
+        Ok(dict)
+    }
+
+    /// Save the plan as a GeoJSON FeatureCollection with one feature per district, dissolved from
+    /// its blocks, e.g. to drop into a web map. Each feature has ``district``, ``population`` and
+    /// ``deviation`` (of ``pop_series``) properties, plus its score on each of ``metrics`` (by name).
+    #[pyo3(signature = (path, pop_series, metrics=None))]
+    pub fn to_geojson(&self, py: Python<'_>, path: &str, pop_series: &str, metrics: Option<BTreeMap<String, crate::Metric>>) -> PyResult<()> {
+        let metrics = metrics.unwrap_or_default().into_iter().map(|(name, metric)| (name, metric.inner)).collect::<Vec<_>>();
+        py.allow_threads(|| self.inner.write_geojson(&PathBuf::from(path), pop_series, &metrics))
+            .map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    /// Write the plan as an ESRI shapefile (``.shp`` with ``.shx``, ``.dbf`` and ``.prj``), one
+    /// dissolved polygon per district with its ``DISTRICT`` number. ``fields`` maps DBF field names
+    /// (at most 10 characters) to the district's score on one of ``metrics`` or its total of a series.
+    #[pyo3(signature = (path, fields=None, metrics=None))]
+    pub fn to_shapefile(&self, py: Python<'_>, path: &str, fields: Option<&Bound<'_, PyDict>>, metrics: Option<BTreeMap<String, crate::Metric>>) -> PyResult<()> {
+        let schema = parse_shapefile_schema(fields)?;
+        let metrics = metrics.unwrap_or_default().into_iter().map(|(name, metric)| (name, metric.inner)).collect::<Vec<_>>();
+        py.allow_threads(|| self.inner.write_shapefile(&PathBuf::from(path), &schema, &metrics))
+            .map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    /// Per-district profile as a pandas ``DataFrame`` (requires pandas): population and deviation of
+    /// ``pop_series``, shares of its sibling series (e.g. ``Black_share``), Democratic share of each
+    /// contest, compactness scores, and the counties each district touches. With a ``locale`` (e.g.
+    /// ``"es-US"``), columns are named by their translated labels.
+    #[pyo3(signature = (pop_series, locale=None))]
+    pub fn district_profiles<'py>(&self, py: Python<'py>, pop_series: &str, locale: Option<&str>) -> PyResult<Bound<'py, PyAny>> {
+        let df = match locale {
+            Some(locale) => self.inner.localized_district_profiles(pop_series, &parse_locale(locale)?),
+            None => self.inner.district_profiles(pop_series),
+        }.map_err(|e| PyValueError::new_err(e.to_string()))?;
+        to_pandas(py, &df)
+    }
+
+    /// Write the per-district profile of ``pop_series`` to a ``.csv`` or ``.parquet`` file.
+    pub fn write_district_profiles(&self, path: &str, pop_series: &str) -> PyResult<()> {
+        self.inner.write_district_profiles(&PathBuf::from(path), pop_series)
+            .map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    /// Markdown report of the districts of ``pop_series`` in ``locale``'s language and number format
+    /// (``"en-US"``, ``"es-US"``, ``"es-MX"`` or ``"es-ES"``): population balance, then a table of the
+    /// district profiles with translated column labels.
+    #[pyo3(signature = (pop_series, locale="en-US"))]
+    pub fn district_report(&self, pop_series: &str, locale: &str) -> PyResult<String> {
+        self.inner.district_report(pop_series, &parse_locale(locale)?)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Write ``district_report`` to a Markdown file.
+    #[pyo3(signature = (path, pop_series, locale="en-US"))]
+    pub fn write_district_report(&self, path: &str, pop_series: &str, locale: &str) -> PyResult<()> {
+        self.inner.write_district_report(&PathBuf::from(path), pop_series, &parse_locale(locale)?)
+            .map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    /// Serialize the plan (district count and non-zero assignments) to a JSON string.
+    pub fn to_json(&self) -> PyResult<String> {
+        self.inner.to_json()
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+
+    /// Load assignments from a JSON string produced by ``to_json``.
+    pub fn load_json(&mut self, json: &str) -> PyResult<()> {
+        self.inner.load_json(json)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Encode the plan's assignments as compact bytes (runs of districts along a Hilbert curve),
+    /// typically a few KB. Decode with ``load_compact_bytes`` on a plan of the same map.
+    pub fn to_compact_bytes<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        let bytes = self.inner.assignment_codec()
+            .and_then(|codec| self.inner.to_compact_bytes(&codec))
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Ok(PyBytes::new_bound(py, &bytes))
+    }
+
+    /// Load assignments from bytes produced by ``to_compact_bytes``.
+    pub fn load_compact_bytes(&mut self, data: &[u8]) -> PyResult<()> {
+        let codec = self.inner.assignment_codec()
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        self.inner.load_compact_bytes(&codec, data)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Save plan to SVG at the given path (shows district outlines and fills).
+    /// If `palette` is given ("okabe-ito", "set2", "dark2", "paired", "set3"), districts are filled
+    /// from it so that bordering districts never share a color, overriding `color_partisan`.
+    /// `projection` is one of "equirectangular", "mercator", "albers", "albers-usa".
+    /// If `annotate`, a legend, scale bar, north arrow, and metrics box (population deviation and
+    /// efficiency gap from `metric_series`, a ``(pop, dem, rep)`` tuple of series names) are drawn below the map.
+    /// `symbols` draws units over the districts, e.g. ``{"style": "dots", "series": ["P_20_BLACK", "P_20_HISP"],
+    /// "per_dot": 100, "layer": "block", "seed": 0}`` or ``{"style": "proportional", "series": "T_20_CENS_Total",
+    /// "max_radius": 12, "layer": "tract"}``. `locale` (e.g. ``"es-US"``) sets the language and
+    /// number format of the annotations and district labels.
+    /// For accessible publishing, `title` and `description` become the document's ``<title>`` and
+    /// ``<desc>``, and `patterns` hatches each district over its color so that bordering districts
+    /// can be told apart without color. Districts are always labelled for screen readers.
+    #[pyo3(signature = (path, color_partisan=false, palette=None, projection="equirectangular", annotate=false, metric_series=None, symbols=None, locale="en-US", title=None, description=None, patterns=false))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn to_svg(&self, path: &str, color_partisan: bool, palette: Option<&str>, projection: &str, annotate: bool, metric_series: Option<(String, String, String)>, symbols: Option<&Bound<'_, PyDict>>, locale: &str, title: Option<String>, description: Option<String>, patterns: bool) -> PyResult<()> {
+        let palette = palette.map(parse_palette).transpose()?;
+        let mut options = svg_options(projection, annotate, metric_series)?;
+        options.symbols = symbols.map(parse_symbols).transpose()?;
+        options.annotations.locale = parse_locale(locale)?;
+        options.accessibility = openmander_core::Accessibility { title, description, patterns };
+        self.inner.to_svg_with_options(&PathBuf::from(path), color_partisan, palette, &options)
+            .map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    /// Save every block to SVG at the given path, grouped into one ``<g>`` per district.
+    /// Streams to disk, so it works for full block-level maps. Other arguments work as in ``to_svg``.
+    #[pyo3(signature = (path, color_partisan=false, palette=None, projection="equirectangular", annotate=false, metric_series=None, symbols=None, locale="en-US", title=None, description=None, patterns=false))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn to_block_svg(&self, py: Python<'_>, path: &str, color_partisan: bool, palette: Option<&str>, projection: &str, annotate: bool, metric_series: Option<(String, String, String)>, symbols: Option<&Bound<'_, PyDict>>, locale: &str, title: Option<String>, description: Option<String>, patterns: bool) -> PyResult<()> {
+        let palette = palette.map(parse_palette).transpose()?;
+        let mut options = svg_options(projection, annotate, metric_series)?;
+        options.symbols = symbols.map(parse_symbols).transpose()?;
+        options.annotations.locale = parse_locale(locale)?;
+        options.accessibility = openmander_core::Accessibility { title, description, patterns };
+        py.allow_threads(|| self.inner.to_block_svg_with_options(&PathBuf::from(path), color_partisan, palette, &options))
             .map_err(|e| PyIOError::new_err(e.to_string()))
     }
 
+    /// Save several plans of the same map side by side to one SVG, one titled panel per plan.
+    ///
+    /// `plans` is a list of plans and `titles` an optional list of panel titles. `insets` is an
+    /// optional list of ``(min_lon, min_lat, max_lon, max_lat)`` areas (e.g. dense metros) drawn
+    /// zoomed in the corner of every panel. `palette`, `color_partisan`, and `projection` work as in ``to_svg``.
+    #[staticmethod]
+    #[pyo3(signature = (path, plans, titles=None, columns=2, panel_width=600.0, insets=None, color_partisan=false, palette=None, projection="equirectangular"))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn to_panels_svg(
+        py: Python<'_>,
+        path: &str,
+        plans: Vec<PyRef<'_, Plan>>,
+        titles: Option<Vec<String>>,
+        columns: usize,
+        panel_width: f64,
+        insets: Option<Vec<(f64, f64, f64, f64)>>,
+        color_partisan: bool,
+        palette: Option<&str>,
+        projection: &str,
+    ) -> PyResult<()> {
+        let titles = titles.unwrap_or_default();
+        if !titles.is_empty() && titles.len() != plans.len() {
+            return Err(PyValueError::new_err(format!("Expected {} titles, got {}", plans.len(), titles.len())));
+        }
+        let palette = palette.map(parse_palette).transpose()?;
+        let layout = openmander_core::PanelLayout {
+            columns,
+            panel: openmander_core::SvgOptions { projection: parse_projection(projection)?, width: panel_width, ..Default::default() },
+            insets: insets.unwrap_or_default().into_iter().map(|(min_lon, min_lat, max_lon, max_lat)| openmander_core::Inset {
+                width: panel_width / 3.0,
+                ..openmander_core::Inset::new(min_lon, min_lat, max_lon, max_lat)
+            }).collect(),
+            ..Default::default()
+        };
+        let panels = plans.iter().enumerate()
+            .map(|(i, plan)| (titles.get(i).map_or("", String::as_str), &plan.inner))
+            .collect::<Vec<_>>();
+        py.allow_threads(|| openmander_core::Plan::to_panels_svg(&PathBuf::from(path), &panels, color_partisan, palette, &layout))
+            .map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    /// Lines where districts meet, as a GeoJSON FeatureCollection string with one MultiLineString
+    /// feature per pair of bordering districts (``properties.districts = [a, b]``).
+    pub fn district_borders_geojson(&self, py: Python<'_>) -> PyResult<String> {
+        py.allow_threads(|| self.inner.district_borders_geojson())
+            .map(|geojson| geojson.to_string())
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+
+    /// Save only the lines where districts meet to SVG, for overlay on a basemap.
+    /// `projection` and `annotate` (scale bar and north arrow) work as in ``to_svg``.
+    #[pyo3(signature = (path, projection="equirectangular", annotate=false))]
+    pub fn to_border_svg(&self, py: Python<'_>, path: &str, projection: &str, annotate: bool) -> PyResult<()> {
+        let options = svg_options(projection, annotate, None)?;
+        py.allow_threads(|| self.inner.to_border_svg(&PathBuf::from(path), &options))
+            .map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    /// How often each block changes district across an ensemble of plans of the same map, as the
+    /// entropy of its assignments scaled to [0, 1]. District labels must be comparable across plans.
+    #[staticmethod]
+    pub fn unit_flippability(py: Python<'_>, plans: Vec<PyRef<'_, Plan>>) -> PyResult<Vec<f64>> {
+        let plans = plans.iter().map(|plan| &plan.inner).collect::<Vec<_>>();
+        py.allow_threads(|| openmander_core::Plan::unit_flippability(&plans))
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Per-block district stability across an ensemble of plans of the same map, as a pandas
+    /// ``DataFrame`` (requires pandas): ``geo_id``, ``entropy``, ``modal_district``, ``modal_share``,
+    /// ``unassigned``, and ``district_<d>``, the share of plans placing the block in district ``d``.
+    #[staticmethod]
+    pub fn unit_stability<'py>(py: Python<'py>, plans: Vec<PyRef<'_, Plan>>) -> PyResult<Bound<'py, PyAny>> {
+        let plans = plans.iter().map(|plan| &plan.inner).collect::<Vec<_>>();
+        let df = py.allow_threads(|| openmander_core::Plan::unit_stability(&plans))
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        to_pandas(py, &df)
+    }
+
+    /// Write ``unit_stability`` over `plans` to a ``.csv`` or ``.parquet`` file.
+    #[staticmethod]
+    pub fn write_unit_stability(py: Python<'_>, path: &str, plans: Vec<PyRef<'_, Plan>>) -> PyResult<()> {
+        let plans = plans.iter().map(|plan| &plan.inner).collect::<Vec<_>>();
+        py.allow_threads(|| openmander_core::Plan::write_unit_stability(&plans, &PathBuf::from(path)))
+            .map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    /// Sorted-district boxplot of ``contest`` (e.g. ``"20_PRES"``) across an ensemble, the classic
+    /// "ensemble vs. enacted" chart, as a dict with ``contest``, ``num_plans`` and ``ranks``: one dict
+    /// per rank (1 for each plan's least Democratic district) with the ``min``, ``q1``, ``median``,
+    /// ``q3`` and ``max`` Democratic share at that rank, and the ``enacted`` plan's (``None`` if not given).
+    #[staticmethod]
+    #[pyo3(signature = (plans, contest, enacted=None))]
+    pub fn ensemble_boxplot<'py>(py: Python<'py>, plans: Vec<PyRef<'_, Plan>>, contest: &str, enacted: Option<PyRef<'_, Plan>>) -> PyResult<Bound<'py, PyDict>> {
+        let boxplot = boxplot(py, &plans, contest, enacted.as_deref())?;
+        let ranks = PyList::empty_bound(py);
+        for rank in boxplot.ranks {
+            let dict = PyDict::new_bound(py);
+            dict.set_item("rank", rank.rank)?;
+            dict.set_item("min", rank.min)?;
+            dict.set_item("q1", rank.q1)?;
+            dict.set_item("median", rank.median)?;
+            dict.set_item("q3", rank.q3)?;
+            dict.set_item("max", rank.max)?;
+            dict.set_item("enacted", rank.enacted)?;
+            ranks.append(dict)?;
+        }
+        let dict = PyDict::new_bound(py);
+        dict.set_item("contest", boxplot.contest)?;
+        dict.set_item("num_plans", boxplot.num_plans)?;
+        dict.set_item("ranks", ranks)?;
+        Ok(dict)
+    }
+
+    /// Write ``ensemble_boxplot`` to a ``.json``, ``.csv`` or ``.parquet`` file.
+    #[staticmethod]
+    #[pyo3(signature = (path, plans, contest, enacted=None))]
+    pub fn write_ensemble_boxplot(py: Python<'_>, path: &str, plans: Vec<PyRef<'_, Plan>>, contest: &str, enacted: Option<PyRef<'_, Plan>>) -> PyResult<()> {
+        let boxplot = boxplot(py, &plans, contest, enacted.as_deref())?;
+        py.allow_threads(|| boxplot.write(&PathBuf::from(path)))
+            .map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    /// Draw one plan of ``map`` for each district count from ``min_districts`` to ``max_districts``
+    /// (inclusive), e.g. to compare council sizes: each is randomized, equalized to ``tolerance`` on
+    /// ``pop_series``, then annealed with ``config`` if given. Compare them with ``compare_plans``.
+    #[staticmethod]
+    #[pyo3(signature = (map, min_districts, max_districts, pop_series, tolerance, equalize_iter=1000, config=None))]
+    pub fn district_count_sweep(
+        py: Python<'_>,
+        map: Py<Map>,
+        min_districts: u32,
+        max_districts: u32,
+        pop_series: &str,
+        tolerance: f64,
+        equalize_iter: usize,
+        config: Option<&crate::ChainConfig>,
+    ) -> PyResult<Vec<Plan>> {
+        let map = map.borrow(py).inner_arc();
+        let config = openmander_core::SweepConfig {
+            pop_series: pop_series.to_string(),
+            tolerance,
+            equalize_iter,
+            anneal: config.map(|config| config.to_core()).transpose()?,
+        };
+        let plans = py.allow_threads(|| openmander_core::Plan::district_count_sweep(map, min_districts..=max_districts, &config))
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Ok(plans.into_iter().map(Plan::from_inner).collect())
+    }
+
+    /// Comparative table of `plans` of one map as a pandas ``DataFrame`` (requires pandas), one row
+    /// per plan: ``num_districts``, ``ideal_population``, ``max_deviation``, ``split_counties``,
+    /// compactness, and Democratic seats in each contest.
+    #[staticmethod]
+    pub fn compare_plans<'py>(py: Python<'py>, plans: Vec<PyRef<'_, Plan>>, pop_series: &str) -> PyResult<Bound<'py, PyAny>> {
+        let plans = plans.iter().map(|plan| &plan.inner).collect::<Vec<_>>();
+        let df = openmander_core::Plan::compare_plans(&plans, pop_series).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        to_pandas(py, &df)
+    }
+
+    /// Draw wards from a random plan: anneal until the total deviation of ``pop_series`` (largest
+    /// minus smallest ward, e.g. 0.1 for the usual ±5% standard) is within ``max_total_deviation``.
+    /// Optimizes ``objective`` if given, else compactness. Returns True if the wards satisfy the rule.
+    #[pyo3(signature = (pop_series, max_total_deviation, max_iter=100000, objective=None))]
+    pub fn draw_wards(&mut self, py: Python<'_>, pop_series: &str, max_total_deviation: f64, max_iter: usize, objective: Option<&crate::Objective>) -> PyResult<bool> {
+        let rules = openmander_core::WardRules {
+            max_iter,
+            objective: objective.map(|objective| objective.inner.clone()),
+            ..openmander_core::WardRules::new(pop_series, max_total_deviation)
+        };
+        py.allow_threads(|| self.inner.draw_wards(&rules))
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+
+    /// Write the ward files of `preset` into the directory `dir` and return their paths:
+    /// "clerk" (assignments.csv, wards.csv, wards.svg), "gis" (assignments.csv, wards.geojson)
+    /// or "all".
+    #[pyo3(signature = (dir, pop_series, preset="all"))]
+    pub fn export_wards(&self, py: Python<'_>, dir: &str, pop_series: &str, preset: &str) -> PyResult<Vec<String>> {
+        let preset = match preset {
+            "clerk" => openmander_core::ExportPreset::Clerk,
+            "gis" => openmander_core::ExportPreset::Gis,
+            "all" => openmander_core::ExportPreset::All,
+            other => return Err(PyValueError::new_err(format!("Unknown preset {other:?}. Expected one of: clerk, gis, all"))),
+        };
+        let written = py.allow_threads(|| self.inner.export_wards(&PathBuf::from(dir), pop_series, preset))
+            .map_err(|e| PyIOError::new_err(e.to_string()))?;
+        Ok(written.iter().map(|path| path.display().to_string()).collect())
+    }
+
+    /// Save a heat map of ``unit_flippability`` over `plans` to SVG, with the districts of the
+    /// first plan outlined. `projection` and `annotate` work as in ``to_svg``.
+    #[staticmethod]
+    #[pyo3(signature = (path, plans, projection="equirectangular", annotate=false))]
+    pub fn to_flippability_svg(py: Python<'_>, path: &str, plans: Vec<PyRef<'_, Plan>>, projection: &str, annotate: bool) -> PyResult<()> {
+        let options = svg_options(projection, annotate, None)?;
+        let plans = plans.iter().map(|plan| &plan.inner).collect::<Vec<_>>();
+        py.allow_threads(|| openmander_core::Plan::to_flippability_svg(&PathBuf::from(path), &plans, &options))
+            .map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    /// Hex color of each district (1..=num_districts) from `palette`, with bordering districts
+    /// given different colors. The same plan always gets the same colors.
+    #[pyo3(signature = (palette="okabe-ito"))]
+    pub fn district_colors(&self, palette: &str) -> PyResult<Vec<String>> {
+        Ok(self.inner.district_colors(parse_palette(palette)?))
+    }
+
     /// Get district geometries as WKB bytes.
     ///
     /// Returns a list of tuples: [(district_id, wkb_bytes), ...]
     /// Districts 1 through num_districts are included. District 0 (unassigned) is excluded.
-    pub fn district_geometries_wkb<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+    /// `hull` selects the outline: "dissolve" (exact, default), "convex", or "concave" (with `concavity`).
+    #[pyo3(signature = (hull="dissolve", concavity=None))]
+    pub fn district_geometries_wkb<'py>(&self, py: Python<'py>, hull: &str, concavity: Option<f64>) -> PyResult<Bound<'py, PyList>> {
+        let kind = parse_hull(hull, concavity)?;
         let geometries = py.allow_threads(|| {
-            self.inner.district_geometries_wkb()
+            self.inner.district_outlines_wkb(kind)
                 .map_err(|e| PyRuntimeError::new_err(e.to_string()))
         })?;
 
@@ -253,4 +1120,131 @@ impl Plan {
 
         Ok(result)
     }
+
+    /// Ratio of each district's area to the area of its hull (0 to 1), indexed by district (0 = unassigned).
+    /// `hull` is "convex" (default) or "concave" (with `concavity`).
+    #[pyo3(signature = (hull="convex", concavity=None))]
+    pub fn hull_compactness(&self, py: Python<'_>, hull: &str, concavity: Option<f64>) -> PyResult<Vec<f64>> {
+        let kind = parse_hull(hull, concavity)?;
+        py.allow_threads(|| self.inner.hull_compactness(kind))
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+
+    /// HTML preview for Jupyter: the districts beside a table of population deviation,
+    /// Democratic vote share, and Polsby–Popper compactness (columns whose series are missing are skipped).
+    fn _repr_html_(&self, py: Python<'_>) -> String {
+        py.allow_threads(|| crate::notebook::plan_html(&self.inner))
+    }
+
+    fn __repr__(&self) -> String {
+        match &self.inner.metadata().name {
+            Some(name) => format!("<Plan {name:?}: {} districts>", self.inner.num_districts()),
+            None => format!("<Plan: {} districts>", self.inner.num_districts()),
+        }
+    }
+}
+
+/// Parse a locale tag for reports and SVG annotations.
+fn parse_locale(tag: &str) -> PyResult<openmander_core::Locale> {
+    openmander_core::Locale::from_tag(tag)
+        .ok_or_else(|| PyValueError::new_err(format!("Unknown locale {tag:?}. Expected e.g. \"en-US\", \"es-US\" or \"es-ES\"")))
+}
+
+/// Parse a hull name ("dissolve", "convex", "concave") and optional concavity.
+/// SVG export options for a projection name, with every annotation drawn if `annotate`.
+fn svg_options(projection: &str, annotate: bool, metric_series: Option<(String, String, String)>) -> PyResult<openmander_core::SvgOptions> {
+    let mut annotations = if annotate { openmander_core::Annotations::all() } else { openmander_core::Annotations::default() };
+    if let (true, Some((pop, dem, rep))) = (annotate, metric_series) {
+        annotations.metrics = Some(openmander_core::MetricSeries { pop, dem, rep });
+    }
+    Ok(openmander_core::SvgOptions { projection: parse_projection(projection)?, annotations, ..Default::default() })
+}
+
+/// Parse a ``symbols`` dict (see ``Plan.to_svg``).
+fn parse_symbols(dict: &Bound<'_, PyDict>) -> PyResult<openmander_core::Symbols> {
+    let get = |key: &str| dict.get_item(key);
+    let layer = match get("layer")? {
+        Some(layer) => {
+            let name = layer.extract::<String>()?;
+            openmander_core::GeoType::from_str(&name).ok_or_else(|| PyValueError::new_err(format!("Unknown symbols layer {name:?}")))?
+        }
+        None => openmander_core::GeoType::Block,
+    };
+    let style = get("style")?.map(|style| style.extract::<String>()).transpose()?.unwrap_or_else(|| "dots".into());
+    let series = get("series")?.ok_or_else(|| PyValueError::new_err("symbols requires a \"series\" entry"))?;
+    let style = match style.as_str() {
+        "dots" | "dot-density" => openmander_core::SymbolStyle::DotDensity {
+            series: series.extract::<Vec<String>>()?,
+            per_dot: get("per_dot")?.map(|v| v.extract()).transpose()?.unwrap_or(100.0),
+            seed: get("seed")?.map(|v| v.extract()).transpose()?.unwrap_or(0),
+        },
+        "proportional" => openmander_core::SymbolStyle::Proportional {
+            series: series.extract::<String>()?,
+            max_radius: get("max_radius")?.map(|v| v.extract()).transpose()?.unwrap_or(12.0),
+        },
+        _ => return Err(PyValueError::new_err(format!("Unknown symbols style {style:?}. Expected one of: dots, proportional"))),
+    };
+    Ok(openmander_core::Symbols { layer, style })
+}
+
+/// Convert a table of u32, string and f64 columns to a pandas ``DataFrame``.
+pub(crate) fn to_pandas<'py>(py: Python<'py>, df: &polars::frame::DataFrame) -> PyResult<Bound<'py, PyAny>> {
+    let to_err = |e: polars::error::PolarsError| PyRuntimeError::new_err(e.to_string());
+    let columns = PyDict::new_bound(py);
+    for column in df.get_columns() {
+        let name = column.name().as_str();
+        match column.dtype() {
+            polars::datatypes::DataType::UInt32 => columns.set_item(name, column.u32().map_err(to_err)?.into_no_null_iter().collect::<Vec<_>>())?,
+            polars::datatypes::DataType::String => columns.set_item(name, column.str().map_err(to_err)?.into_no_null_iter().collect::<Vec<_>>())?,
+            _ => columns.set_item(name, column.f64().map_err(to_err)?.into_no_null_iter().collect::<Vec<_>>())?,
+        }
+    }
+    py.import_bound("pandas")?.call_method1("DataFrame", (columns,))
+}
+
+fn boxplot(py: Python<'_>, plans: &[PyRef<'_, Plan>], contest: &str, enacted: Option<&Plan>) -> PyResult<openmander_core::EnsembleBoxplot> {
+    let plans = plans.iter().map(|plan| &plan.inner).collect::<Vec<_>>();
+    let enacted = enacted.map(|plan| &plan.inner);
+    py.allow_threads(|| openmander_core::Plan::ensemble_boxplot(&plans, &openmander_core::Contest::named(contest), enacted))
+        .map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+fn explanation_dict<'py>(py: Python<'py>, explanation: openmander_core::ScoreExplanation) -> PyResult<Bound<'py, PyDict>> {
+    let contributions = PyList::empty_bound(py);
+    for contribution in explanation.contributions {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("label", contribution.label)?;
+        dict.set_item("districts", contribution.districts)?;
+        dict.set_item("value", contribution.value)?;
+        dict.set_item("contribution", contribution.contribution)?;
+        contributions.append(dict)?;
+    }
+    let dict = PyDict::new_bound(py);
+    dict.set_item("score", explanation.score)?;
+    dict.set_item("contributions", contributions)?;
+    Ok(dict)
+}
+
+fn parse_swing(swing: f64, kind: &str) -> PyResult<openmander_core::Swing> {
+    match kind {
+        "uniform" => Ok(openmander_core::Swing::Uniform(swing)),
+        "proportional" => Ok(openmander_core::Swing::Proportional(swing)),
+        _ => Err(PyValueError::new_err(format!("Unknown swing kind {kind:?}. Expected one of: uniform, proportional"))),
+    }
+}
+
+fn parse_palette(palette: &str) -> PyResult<openmander_core::Palette> {
+    openmander_core::Palette::from_name(palette).ok_or_else(|| PyValueError::new_err(format!(
+        "Unknown palette {palette:?}. Expected one of: okabe-ito, set2, dark2, paired, set3"
+    )))
+}
+
+fn parse_hull(hull: &str, concavity: Option<f64>) -> PyResult<openmander_core::HullKind> {
+    match openmander_core::HullKind::from_name(hull) {
+        Some(openmander_core::HullKind::Concave { .. }) => Ok(openmander_core::HullKind::Concave {
+            concavity: concavity.unwrap_or(openmander_core::HullKind::DEFAULT_CONCAVITY),
+        }),
+        Some(kind) => Ok(kind),
+        None => Err(PyValueError::new_err(format!("Unknown hull {hull:?}. Expected one of: dissolve, convex, concave"))),
+    }
 }
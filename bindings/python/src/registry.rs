@@ -0,0 +1,63 @@
+use pyo3::{pyfunction, Bound, PyResult, Python};
+use pyo3::types::{PyDict, PyDictMethods};
+
+/// List the metrics available to objectives.
+///
+/// Returns
+/// -------
+/// list[dict]
+///     One dict per metric with keys ``name``, ``params`` and ``description``.
+#[pyfunction]
+pub fn list_metrics<'py>(py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    openmander_core::METRICS.iter()
+        .map(|info| {
+            let dict = PyDict::new_bound(py);
+            dict.set_item("name", info.name)?;
+            dict.set_item("params", info.params.to_vec())?;
+            dict.set_item("description", info.description)?;
+            Ok(dict)
+        })
+        .collect()
+}
+
+/// List the optimization algorithms available on ``Plan``.
+///
+/// Returns
+/// -------
+/// list[dict]
+///     One dict per algorithm with keys ``name``, ``kind`` and ``description``.
+#[pyfunction]
+pub fn list_algorithms<'py>(py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    openmander_core::ALGORITHMS.iter()
+        .map(|info| {
+            let dict = PyDict::new_bound(py);
+            dict.set_item("name", info.name)?;
+            dict.set_item("kind", info.kind.as_str())?;
+            dict.set_item("description", info.description)?;
+            Ok(dict)
+        })
+        .collect()
+}
+
+/// List U.S. states and territories with their identifiers and 2020-cycle seat counts.
+///
+/// Returns
+/// -------
+/// list[dict]
+///     One dict per state with keys ``code``, ``fips``, ``name``, ``congress``,
+///     ``sldu`` and ``sldl`` (None where the chamber does not exist).
+#[pyfunction]
+pub fn list_states<'py>(py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    openmander_core::STATES.iter()
+        .map(|info| {
+            let dict = PyDict::new_bound(py);
+            dict.set_item("code", info.code)?;
+            dict.set_item("fips", info.fips)?;
+            dict.set_item("name", info.name)?;
+            dict.set_item("congress", info.congress)?;
+            dict.set_item("sldu", info.sldu)?;
+            dict.set_item("sldl", info.sldl)?;
+            Ok(dict)
+        })
+        .collect()
+}
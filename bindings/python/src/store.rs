@@ -0,0 +1,141 @@
+#![allow(unsafe_op_in_unsafe_fn)]
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+use pyo3::{pyclass, pymethods, Bound, Py, PyResult, Python};
+use pyo3::types::{PyDict, PyDictMethods};
+use pyo3::exceptions::{PyIOError, PyKeyError, PyValueError};
+
+use crate::{Map, Plan};
+
+/// A directory of named plans for one map, deduplicated by assignment.
+#[pyclass]
+pub struct PlanStore {
+    inner: openmander_core::PlanStore,
+    map: Arc<openmander_core::Map>,
+}
+
+#[pymethods]
+impl PlanStore {
+    /// Open (or create) the plan store at `path` for plans of `map`.
+    #[new]
+    pub fn new(py: Python<'_>, path: &str, map: Py<Map>) -> PyResult<Self> {
+        let map = map.borrow(py).inner_arc();
+        let inner = openmander_core::PlanStore::open(&PathBuf::from(path), &map)
+            .map_err(|e| PyIOError::new_err(e.to_string()))?;
+        Ok(Self { inner, map })
+    }
+
+    fn __len__(&self) -> usize { self.inner.len() }
+
+    fn __contains__(&self, name: &str) -> bool { self.inner.contains(name) }
+
+    /// Names of all stored plans, in sorted order.
+    pub fn names(&self) -> Vec<String> { self.inner.names().map(str::to_string).collect() }
+
+    /// Store `plan` under `name`, replacing any plan of that name, with an optional snapshot of
+    /// scores (e.g. ``{"polsby_popper": 0.31}``). Returns the assignment hash.
+    #[pyo3(signature = (name, plan, scores=None))]
+    pub fn put(&mut self, name: &str, plan: &Plan, scores: Option<HashMap<String, f64>>) -> PyResult<String> {
+        let scores = scores.unwrap_or_default();
+        let scores = scores.iter().map(|(key, &value)| (key.as_str(), value)).collect::<Vec<_>>();
+        self.inner.put(name, plan.inner(), &scores)
+            .map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    /// Load the named plan.
+    pub fn get(&self, name: &str) -> PyResult<Plan> {
+        if !self.inner.contains(name) { return Err(PyKeyError::new_err(name.to_string())) }
+        self.inner.get(name, self.map.clone())
+            .map(Plan::from_inner)
+            .map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    /// Remove the named plan. Returns False if there was no such plan.
+    pub fn remove(&mut self, name: &str) -> PyResult<bool> {
+        self.inner.remove(name).map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    /// Rename a stored plan.
+    pub fn rename(&mut self, old: &str, new: &str) -> PyResult<()> {
+        self.inner.rename(old, new).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Assignment hash of the named plan, or None.
+    pub fn hash(&self, name: &str) -> Option<String> {
+        self.inner.entry(name).map(|entry| entry.hash.clone())
+    }
+
+    /// Score snapshot stored with the named plan.
+    pub fn scores(&self, name: &str) -> PyResult<HashMap<String, f64>> {
+        let entry = self.inner.entry(name).ok_or_else(|| PyKeyError::new_err(name.to_string()))?;
+        Ok(entry.scores.iter().map(|(key, &value)| (key.clone(), value)).collect())
+    }
+
+    /// Names of all plans with the same assignments as the named plan (including itself).
+    pub fn duplicates(&self, name: &str) -> PyResult<Vec<String>> {
+        let entry = self.inner.entry(name).ok_or_else(|| PyKeyError::new_err(name.to_string()))?;
+        Ok(self.inner.names_with_hash(&entry.hash).into_iter().map(str::to_string).collect())
+    }
+
+    /// ``(name, value)`` for every plan with a snapshot of `score`, from lowest to highest.
+    pub fn ranked_by(&self, score: &str) -> Vec<(String, f64)> {
+        self.inner.ranked_by(score).into_iter().map(|(name, value)| (name.to_string(), value)).collect()
+    }
+
+    /// Copy every plan of ``other`` into this store as ``<prefix><name>``, e.g. to combine chains
+    /// run from several seeds. Returns the number of plans copied.
+    pub fn merge(&mut self, other: &PlanStore, prefix: &str) -> PyResult<usize> {
+        self.inner.merge(&other.inner, prefix).map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    /// Drop the first ``burn_in`` plans whose names start with ``prefix`` (in name order), then keep
+    /// every ``every``-th of the rest. Returns the names removed.
+    #[pyo3(signature = (prefix, burn_in=0, every=1))]
+    pub fn thin(&mut self, prefix: &str, burn_in: usize, every: usize) -> PyResult<Vec<String>> {
+        self.inner.thin(prefix, burn_in, every).map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    /// Remove plans with the same assignments as an earlier plan in name order. Returns the names removed.
+    pub fn dedup(&mut self) -> PyResult<Vec<String>> {
+        self.inner.dedup().map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    /// Merge, thin and dedup operations applied to the store, oldest first, as dicts with
+    /// ``timestamp``, ``operation``, ``details``, ``plans_before`` and ``plans_after``.
+    pub fn provenance<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        self.inner.provenance().iter().map(|op| {
+            let dict = PyDict::new_bound(py);
+            dict.set_item("timestamp", op.timestamp)?;
+            dict.set_item("operation", &op.operation)?;
+            dict.set_item("details", &op.details)?;
+            dict.set_item("plans_before", op.plans_before)?;
+            dict.set_item("plans_after", op.plans_after)?;
+            Ok(dict)
+        }).collect()
+    }
+
+    /// Importance weights tempering the stored ensemble by the score snapshot `score`: each plan's
+    /// weight is proportional to ``exp(beta * score)``. Plans without the score are left out.
+    pub fn tempered_weights(&self, score: &str, beta: f64) -> PyResult<HashMap<String, f64>> {
+        let weights = self.inner.tempered_weights(score, beta).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(weights.iter().map(|(name, weight)| (name.to_string(), weight)).collect())
+    }
+
+    /// Distribution of the score snapshot `metric` over the plans of `weights` (name -> weight,
+    /// e.g. from ``tempered_weights``): its ``mean``, ``std_dev``, ``quantiles`` at 5/25/50/75/95%,
+    /// ``effective_sample_size`` of the weights, and the sorted ``(value, weight)`` ``samples``.
+    pub fn weighted_distribution<'py>(&self, py: Python<'py>, metric: &str, weights: HashMap<String, f64>) -> PyResult<Bound<'py, PyDict>> {
+        let weights = openmander_core::ImportanceWeights::from_log_ratios(
+            weights.into_iter().map(|(name, weight)| (name, weight.ln())).collect()
+        ).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let dist = self.inner.weighted_distribution(metric, &weights).map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+        let dict = PyDict::new_bound(py);
+        dict.set_item("mean", dist.mean())?;
+        dict.set_item("std_dev", dist.std_dev())?;
+        dict.set_item("quantiles", [0.05, 0.25, 0.5, 0.75, 0.95].map(|q| (q, dist.quantile(q))).to_vec())?;
+        dict.set_item("effective_sample_size", weights.effective_sample_size())?;
+        dict.set_item("samples", dist.samples().to_vec())?;
+        Ok(dict)
+    }
+}
@@ -0,0 +1,17 @@
+use extendr_api::prelude::*;
+
+mod map;
+mod metric;
+mod plan;
+
+/// Convert a library error into an R error (raised with `stop()` by the wrappers).
+pub(crate) fn r_err(error: anyhow::Error) -> Error {
+    Error::Other(format!("{error:#}"))
+}
+
+extendr_module! {
+    mod openmanderr;
+    use map;
+    use metric;
+    use plan;
+}
@@ -0,0 +1,60 @@
+use std::{path::Path, sync::Arc};
+
+use extendr_api::prelude::*;
+
+use crate::r_err;
+
+/// A multi-layer geographic map loaded from a pack.
+/// @export
+#[extendr]
+pub struct Map {
+    pub(crate) inner: Arc<openmander_core::Map>,
+}
+
+#[extendr]
+impl Map {
+    /// Load a map from a pack directory (format auto-detected).
+    fn new(pack_dir: &str) -> Result<Self> {
+        let map = openmander_core::Map::read_from_pack(Path::new(pack_dir)).map_err(r_err)?;
+        Ok(Self { inner: Arc::new(map) })
+    }
+
+    /// Layers of the map as a data frame with columns `layer` and `units`.
+    fn layers(&self) -> Robj {
+        let (layers, units): (Vec<&str>, Vec<i32>) = self.inner.layers_iter()
+            .map(|layer| (layer.ty().to_str(), layer.len() as i32))
+            .unzip();
+        data_frame!(layer = layers, units = units)
+    }
+
+    /// Geo ids of the base-layer blocks, in the order used by plan assignments.
+    fn blocks(&self) -> Result<Vec<String>> {
+        let base = self.inner.base().map_err(r_err)?;
+        Ok(base.geo_ids().iter().map(|geo_id| geo_id.id().to_string()).collect())
+    }
+
+    /// Write a layer as an ESRI shapefile (.shp with .shx, .dbf and .prj), one polygon per unit.
+    /// `fields` is a named list mapping DBF field names to the data columns they hold.
+    fn to_shapefile(&self, path: &str, #[extendr(default = "\"block\"")] layer: &str, #[extendr(default = "NULL")] fields: Nullable<List>) -> Result<()> {
+        let ty = openmander_core::GeoType::from_str(layer).ok_or_else(|| Error::Other(format!("Unknown layer '{layer}'")))?;
+        let layer = self.inner.layer(ty).ok_or_else(|| Error::Other(format!("Layer '{layer}' is not present in this map")))?;
+        layer.write_shapefile(Path::new(path), &shapefile_schema(fields)?).map_err(r_err)
+    }
+}
+
+/// Shapefile attribute schema from a named list of DBF field names and the columns they hold.
+pub(crate) fn shapefile_schema(fields: Nullable<List>) -> Result<openmander_core::ShapefileSchema> {
+    let mut schema = openmander_core::ShapefileSchema::default();
+    if let Nullable::NotNull(fields) = fields {
+        for (name, column) in fields.iter() {
+            let column = column.as_str().ok_or_else(|| Error::Other(format!("Field '{name}' must name a column")))?;
+            schema = schema.field(name, column);
+        }
+    }
+    Ok(schema)
+}
+
+extendr_module! {
+    mod map;
+    impl Map;
+}
@@ -0,0 +1,93 @@
+use extendr_api::prelude::*;
+
+use crate::r_err;
+
+/// A single metric used to score plans.
+/// Examples: population equality, compactness, competitiveness, proportionality.
+/// @export
+#[extendr]
+#[derive(Clone)]
+pub struct Metric {
+    pub(crate) inner: openmander_core::Metric,
+}
+
+#[extendr]
+impl Metric {
+    /// Population equality metric for a given weight series (e.g., total population).
+    fn population_deviation(pop_series: &str) -> Self {
+        Self { inner: openmander_core::Metric::population_deviation(pop_series.to_string()) }
+    }
+
+    /// Population equality absolute metric for a given weight series.
+    fn population_deviation_absolute(pop_series: &str) -> Self {
+        Self { inner: openmander_core::Metric::population_deviation_absolute(pop_series.to_string()) }
+    }
+
+    /// Population equality smooth metric for a given weight series.
+    fn population_deviation_smooth(pop_series: &str) -> Self {
+        Self { inner: openmander_core::Metric::population_deviation_smooth(pop_series.to_string()) }
+    }
+
+    /// Population equality sharp (linear) metric for a given weight series.
+    fn population_deviation_sharp(pop_series: &str) -> Self {
+        Self { inner: openmander_core::Metric::population_deviation_sharp(pop_series.to_string()) }
+    }
+
+    /// Polsby–Popper compactness metric.
+    fn compactness_polsby_popper() -> Self {
+        Self { inner: openmander_core::Metric::compactness_polsby_popper() }
+    }
+
+    /// Schwartzberg compactness metric.
+    fn compactness_schwartzberg() -> Self {
+        Self { inner: openmander_core::Metric::compactness_schwartzberg() }
+    }
+
+    /// Reock compactness metric (area versus smallest enclosing circle).
+    fn compactness_reock() -> Self {
+        Self { inner: openmander_core::Metric::compactness_reock() }
+    }
+
+    /// Convex hull compactness metric (area versus convex hull).
+    fn compactness_convex_hull() -> Self {
+        Self { inner: openmander_core::Metric::compactness_convex_hull() }
+    }
+
+    /// Competitiveness metric based on district-level vote shares (binary).
+    fn competitiveness_binary(dem_series: &str, rep_series: &str, threshold: f64) -> Self {
+        Self { inner: openmander_core::Metric::competitiveness_binary(dem_series.to_string(), rep_series.to_string(), threshold) }
+    }
+
+    /// Competitiveness metric based on district-level vote shares (piecewise quadratic).
+    fn competitiveness_quadratic(dem_series: &str, rep_series: &str, threshold: f64) -> Self {
+        Self { inner: openmander_core::Metric::competitiveness_quadratic(dem_series.to_string(), rep_series.to_string(), threshold) }
+    }
+
+    /// Competitiveness metric based on district-level vote shares (Gaussian).
+    fn competitiveness_gaussian(dem_series: &str, rep_series: &str, sigma: f64) -> Self {
+        Self { inner: openmander_core::Metric::competitiveness_gaussian(dem_series.to_string(), rep_series.to_string(), sigma) }
+    }
+
+    /// Seats–votes proportionality / partisan fairness metric.
+    fn proportionality(dem_series: &str, rep_series: &str) -> Self {
+        Self { inner: openmander_core::Metric::proportionality(dem_series.to_string(), rep_series.to_string()) }
+    }
+
+    /// Parse a metric from its JSON form, e.g. `{"type": "compactness_polsby_popper"}`.
+    fn from_json(json: &str) -> Result<Self> {
+        let inner = serde_json::from_str(json).map_err(|e| r_err(e.into()))?;
+        Ok(Self { inner })
+    }
+
+    /// The metric's JSON form.
+    fn to_json(&self) -> Result<String> {
+        serde_json::to_string(&self.inner).map_err(|e| r_err(e.into()))
+    }
+
+    fn print(&self) { rprintln!("{}", self.inner) }
+}
+
+extendr_module! {
+    mod metric;
+    impl Metric;
+}
@@ -0,0 +1,206 @@
+use std::{collections::HashMap, path::Path};
+
+use extendr_api::prelude::*;
+
+use crate::{map::Map, metric::Metric, r_err};
+
+/// A districting plan over a map.
+/// @export
+#[extendr]
+pub struct Plan {
+    inner: openmander_core::Plan,
+}
+
+#[extendr]
+impl Plan {
+    /// Create a plan with `num_districts` districts and every block unassigned.
+    fn new(map: &Map, num_districts: i32) -> Result<Self> {
+        let num_districts = u32::try_from(num_districts)
+            .map_err(|_| Error::Other(format!("[Plan$new] Invalid number of districts {num_districts}")))?;
+        let inner = openmander_core::Plan::new(map.inner.clone(), num_districts).map_err(r_err)?;
+        Ok(Self { inner })
+    }
+
+    fn num_districts(&self) -> i32 { self.inner.num_districts() as i32 }
+
+    /// Weight series available for totals and metrics.
+    fn series(&self) -> Vec<String> {
+        let mut series: Vec<String> = self.inner.series().into_iter().collect();
+        series.sort();
+        series
+    }
+
+    /// Block assignments as a data frame with columns `geoid` and `district` (0 = unassigned).
+    fn assignments(&self) -> Result<Robj> {
+        let (geoids, districts): (Vec<String>, Vec<i32>) = self.inner.get_assignments().map_err(r_err)?.into_iter()
+            .map(|(geo_id, district)| (geo_id.id().to_string(), district as i32))
+            .unzip();
+        Ok(data_frame!(geoid = geoids, district = districts))
+    }
+
+    /// Set block assignments from a data frame with columns `geoid` and `district`.
+    /// Blocks missing from the data frame are left unassigned.
+    fn set_assignments(&mut self, assignments: Robj) -> Result<()> {
+        let geoids = assignments.dollar("geoid")?;
+        let geoids = geoids.as_str_vector()
+            .ok_or_else(|| Error::Other("[Plan$set_assignments] Column 'geoid' must be character (read CSVs with colClasses = \"character\")".to_string()))?;
+        let districts = assignments.dollar("district")?;
+        let districts = districts.as_integer_vector()
+            .or_else(|| districts.as_real_vector().map(|values| values.into_iter().map(|v| v as i32).collect()))
+            .ok_or_else(|| Error::Other("[Plan$set_assignments] Column 'district' must be numeric".to_string()))?;
+
+        let assignments = geoids.into_iter().zip(districts)
+            .map(|(geoid, district)| match u32::try_from(district) {
+                Ok(district) if district <= self.inner.num_districts() =>
+                    Ok((openmander_core::GeoId::new(openmander_core::GeoType::Block, geoid), district)),
+                _ => Err(Error::Other(format!("[Plan$set_assignments] Invalid district {district} for block {geoid}"))),
+            })
+            .collect::<Result<HashMap<_, _>>>()?;
+        self.inner.set_assignments(assignments).map_err(r_err)
+    }
+
+    /// Totals of each series by district, as a data frame with a `district` column and one column per series.
+    fn district_table(&self, series: Vec<String>) -> Result<Robj> {
+        let mut names = vec!["district".to_string()];
+        let mut columns = vec![(1..=self.inner.num_districts() as i32).collect::<Vec<_>>().into_robj()];
+        for name in series {
+            columns.push(self.inner.district_totals(&name).map_err(r_err)?.into_robj());
+            names.push(name);
+        }
+        call!("as.data.frame", List::from_names_and_values(names, columns)?)
+    }
+
+    /// Per-district values of a metric.
+    fn metric(&self, metric: &Metric) -> Vec<f64> { self.inner.compute_metric(&metric.inner) }
+
+    /// Aggregated score of a metric.
+    fn score(&self, metric: &Metric) -> f64 { self.inner.compute_metric_score(&metric.inner) }
+
+    /// Score of a weighted combination of metrics (equal weights when `weights` is NULL), or of a
+    /// lexicographic one if `priorities` gives each metric's priority (lower first).
+    fn objective(&self, metrics: List, #[extendr(default = "NULL")] weights: Nullable<Vec<f64>>, #[extendr(default = "NULL")] priorities: Nullable<Vec<i32>>) -> Result<f64> {
+        let metrics = metrics.values()
+            .map(|metric| <&Metric>::try_from(&metric).map(|metric| metric.inner.clone()))
+            .collect::<Result<Vec<_>>>()?;
+        let weights = match weights {
+            Nullable::NotNull(weights) => {
+                if weights.len() != metrics.len() {
+                    return Err(Error::Other(format!("[Plan$objective] Expected {} weights, got {}", metrics.len(), weights.len())))
+                }
+                Some(weights)
+            }
+            Nullable::Null => None,
+        };
+        let num_metrics = metrics.len();
+        let mut objective = openmander_core::Objective::new(metrics, weights);
+        if let Nullable::NotNull(priorities) = priorities {
+            let priorities = priorities.into_iter()
+                .map(|priority| u32::try_from(priority).map_err(|_| Error::Other(format!("[Plan$objective] Invalid priority {priority}"))))
+                .collect::<Result<Vec<_>>>()?;
+            let composition = openmander_core::Composition::lexicographic(priorities);
+            composition.validate(num_metrics).map_err(|e| Error::Other(format!("[Plan$objective] {e}")))?;
+            objective = objective.with_composition(composition);
+        }
+        Ok(self.inner.compute_objective(&objective))
+    }
+
+    /// Randomize the plan into contiguous districts.
+    fn randomize(&mut self) -> Result<()> { self.inner.randomize().map_err(r_err) }
+
+    /// Equalize a weight series across districts to within `tolerance`.
+    fn equalize(&mut self, series: &str, tolerance: f64, max_iter: i32) -> Result<()> {
+        self.inner.equalize(series, tolerance, max_iter.max(0) as usize).map_err(r_err)
+    }
+
+    /// Merge districts `a` and `b` and split them again along a random spanning tree,
+    /// balancing the halves by `pop_series`.
+    fn recombine(&mut self, a: i32, b: i32, pop_series: &str) -> Result<()> {
+        self.inner.recombine(a.max(0) as u32, b.max(0) as u32, pop_series).map_err(r_err)
+    }
+
+    /// Take one ReCom step on a random pair of adjacent districts, balanced by `pop_series`,
+    /// returning the pair.
+    fn recom_step(&mut self, pop_series: &str) -> Result<Vec<i32>> {
+        let (a, b) = self.inner.recombine_random(pop_series).map_err(r_err)?;
+        Ok(vec![a as i32, b as i32])
+    }
+
+    /// Anneal the plan with an `AnnealConfig` given as JSON (the format of the server's `POST /plans/{id}/runs`).
+    fn anneal(&mut self, config: &str) -> Result<()> {
+        let config: openmander_core::AnnealConfig = serde_json::from_str(config).map_err(|e| r_err(e.into()))?;
+        self.inner.anneal_with_config(&config).map_err(r_err)
+    }
+
+    /// Copy the plan, e.g. to keep the current state of a chain.
+    fn copy(&self) -> Self { Self { inner: self.inner.clone() } }
+
+    /// Load block assignments from a CSV file written by `to_csv` (or any `GEOID,district` file).
+    fn read_csv(&mut self, path: &str) -> Result<()> { self.inner.read_from_csv(Path::new(path)).map_err(r_err) }
+
+    fn to_csv(&self, path: &str) -> Result<()> { self.inner.write_to_csv(Path::new(path)).map_err(r_err) }
+
+    /// Load block assignments from a block assignment file (`GEOID20,District`), e.g. from Dave's
+    /// Redistricting. Files assigning coarser units (e.g. VTDs) assign every block in each unit.
+    fn read_baf(&mut self, path: &str) -> Result<()> { self.inner.read_baf(Path::new(path)).map_err(r_err) }
+
+    /// Write a block assignment file of `layer` units; split units go to the district holding most
+    /// of their `pop_series` (or most of their blocks, if NULL).
+    fn to_baf(&self, path: &str, #[extendr(default = "\"block\"")] layer: &str, #[extendr(default = "NULL")] pop_series: Nullable<String>) -> Result<()> {
+        let pop_series = match pop_series { Nullable::NotNull(series) => Some(series), Nullable::Null => None };
+        self.inner.write_baf(Path::new(path), layer, pop_series.as_deref()).map_err(r_err)
+    }
+
+    /// Content ID of the plan's assignments (SHA-256 of its `GEOID,district` lines in GEOID order).
+    fn plan_id(&self) -> Result<String> { self.inner.plan_id().map_err(r_err) }
+
+    /// Stamp every export written from now on with the plan ID, library version and a content hash.
+    fn set_stamp_exports(&mut self, enabled: bool) { self.inner.set_stamp_exports(enabled) }
+
+    /// Check that the stamped export at `path` is unmodified and was exported from this plan.
+    /// Returns the library version that wrote it.
+    fn verify_export(&self, path: &str) -> Result<String> {
+        self.inner.verify_export_file(Path::new(path)).map(|stamp| stamp.tool).map_err(r_err)
+    }
+
+    /// Write a GeoJSON file with one feature per district, dissolved from its blocks, holding its
+    /// `district`, `population` and `deviation` (of `pop_series`) and its score on each metric of
+    /// the named list `metrics`.
+    fn to_geojson(&self, path: &str, pop_series: &str, #[extendr(default = "NULL")] metrics: Nullable<List>) -> Result<()> {
+        let metrics = match metrics {
+            Nullable::NotNull(metrics) => metrics.iter()
+                .map(|(name, metric)| <&Metric>::try_from(&metric).map(|metric| (name.to_string(), metric.inner.clone())))
+                .collect::<Result<Vec<_>>>()?,
+            Nullable::Null => Vec::new(),
+        };
+        self.inner.write_geojson(Path::new(path), pop_series, &metrics).map_err(r_err)
+    }
+
+    /// Markdown report of the districts of `pop_series` in the language and number format of
+    /// `locale` ("en-US", "es-US", "es-MX" or "es-ES").
+    fn district_report(&self, pop_series: &str, #[extendr(default = "\"en-US\"")] locale: &str) -> Result<String> {
+        let locale = openmander_core::Locale::from_tag(locale).ok_or_else(|| Error::Other(format!("Unknown locale '{locale}'")))?;
+        self.inner.district_report(pop_series, &locale).map_err(r_err)
+    }
+
+    /// Write the plan as an ESRI shapefile (.shp with .shx, .dbf and .prj), one dissolved polygon per
+    /// district. `fields` is a named list mapping DBF field names to a metric name or series.
+    fn to_shapefile(&self, path: &str, #[extendr(default = "NULL")] fields: Nullable<List>, #[extendr(default = "NULL")] metrics: Nullable<List>) -> Result<()> {
+        let schema = crate::map::shapefile_schema(fields)?;
+        let metrics = match metrics {
+            Nullable::NotNull(metrics) => metrics.iter()
+                .map(|(name, metric)| <&Metric>::try_from(&metric).map(|metric| (name.to_string(), metric.inner.clone())))
+                .collect::<Result<Vec<_>>>()?,
+            Nullable::Null => Vec::new(),
+        };
+        self.inner.write_shapefile(Path::new(path), &schema, &metrics).map_err(r_err)
+    }
+
+    fn print(&self) {
+        rprintln!("<Plan: {} districts>", self.inner.num_districts())
+    }
+}
+
+extendr_module! {
+    mod plan;
+    impl Plan;
+}
@@ -40,3 +40,32 @@ pub(crate) fn parse_layer(layer: Option<String>) -> Result<openmander_core::GeoT
         )
     })
 }
+
+pub(crate) fn parse_hull(hull: Option<String>, concavity: Option<f64>) -> Result<openmander_core::HullKind> {
+    let hull = hull.as_deref().unwrap_or("dissolve");
+    match openmander_core::HullKind::from_name(hull) {
+        Some(openmander_core::HullKind::Concave { .. }) => Ok(openmander_core::HullKind::Concave {
+            concavity: concavity.unwrap_or(openmander_core::HullKind::DEFAULT_CONCAVITY),
+        }),
+        Some(kind) => Ok(kind),
+        None => Err(anyhow!("Unknown hull {:?}. Expected one of: dissolve, convex, concave", hull)),
+    }
+}
+
+pub(crate) fn parse_precision(precision: Option<String>) -> Result<openmander_core::GeometryPrecision> {
+    let precision = precision.as_deref().unwrap_or("f64");
+    openmander_core::GeometryPrecision::from_name(precision)
+        .ok_or_else(|| anyhow!("Unknown precision {:?}. Expected one of: f64, f32", precision))
+}
+
+pub(crate) fn parse_palette(palette: Option<String>) -> Result<openmander_core::Palette> {
+    let palette = palette.as_deref().unwrap_or("okabe-ito");
+    openmander_core::Palette::from_name(palette)
+        .ok_or_else(|| anyhow!("Unknown palette {:?}. Expected one of: okabe-ito, set2, dark2, paired, set3", palette))
+}
+
+pub(crate) fn parse_projection(projection: Option<String>) -> Result<openmander_core::MapProjection> {
+    let projection = projection.as_deref().unwrap_or("equirectangular");
+    openmander_core::MapProjection::from_name(projection)
+        .ok_or_else(|| anyhow!("Unknown projection {:?}. Expected one of: equirectangular, mercator, albers, albers-usa", projection))
+}
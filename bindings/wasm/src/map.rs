@@ -14,14 +14,27 @@ pub struct WasmMap {
 impl WasmMap {
     /// Construct a Map from an in-memory pack:
     /// files: { "data/block.parquet": Uint8Array, "adj/block.csr.bin": Uint8Array, ... }
+    /// precision: "f64" (default) or "f32" to halve geometry memory.
     #[wasm_bindgen(constructor)]
-    pub fn new(files: JsValue) -> Result<WasmMap, JsValue> {
+    pub fn new(files: JsValue, precision: Option<String>) -> Result<WasmMap, JsValue> {
+        let precision = parse_precision(precision).map_err(js_err)?;
         let mem = js_files_to_mempack(files).map_err(js_err)?;
         // Auto-detect format from available files
         let format = openmander_core::Map::detect_pack_format(&mem)
             .map_err(js_err)
             .unwrap_or_else(|_| openmander_core::PackFormat::Pmtiles); // Default to PMTiles for WASM
-        let map = openmander_core::Map::read_from_pack_source(&mem, format).map_err(js_err)?;
+        let map = openmander_core::Map::read_from_pack_source_with_precision(&mem, format, precision).map_err(js_err)?;
+        Ok(WasmMap { inner: Arc::new(map) })
+    }
+
+    /// Construct a Map from the bytes of a single-file `.openmander` pack bundle, e.g. a file
+    /// the user uploaded. precision: as for the constructor.
+    pub fn from_bundle(bytes: &[u8], precision: Option<String>) -> Result<WasmMap, JsValue> {
+        let precision = parse_precision(precision).map_err(js_err)?;
+        let mem = openmander_core::BundlePack::unpack(bytes).map_err(js_err)?;
+        let format = openmander_core::Map::detect_pack_format(&mem)
+            .unwrap_or(openmander_core::PackFormat::Pmtiles);
+        let map = openmander_core::Map::read_from_pack_source_with_precision(&mem, format, precision).map_err(js_err)?;
         Ok(WasmMap { inner: Arc::new(map) })
     }
 
@@ -36,15 +49,49 @@ impl WasmMap {
         serde_wasm_bindgen::to_value(&out).map_err(|e| e.into())
     }
 
-    /// Generate SVG text for a given layer, optionally colored by series.
+    /// Column statistics recorded in a pack's manifest, by layer and column, without loading the
+    /// pack: { block: { pop: { min, max, sum, q1, median, q3, histogram }, ... }, ... }.
+    /// files: the in-memory pack, as for the constructor (only "manifest.json" is read).
+    pub fn pack_column_stats(files: JsValue) -> Result<JsValue, JsValue> {
+        let mem = js_files_to_mempack(files).map_err(js_err)?;
+        let stats = openmander_core::Map::read_pack_source_column_stats(&mem).map_err(js_err)?;
+        serde_wasm_bindgen::to_value(&stats).map_err(|e| e.into())
+    }
+
+    /// The manifest of an in-memory pack, as a JavaScript object (see `manifest.json`), without
+    /// loading the pack. files: as for the constructor (only "manifest.json" is read).
+    pub fn pack_manifest(files: JsValue) -> Result<JsValue, JsValue> {
+        let mem = js_files_to_mempack(files).map_err(js_err)?;
+        let manifest = openmander_core::Manifest::from_pack_source(&mem).map_err(js_err)?;
+        serde_wasm_bindgen::to_value(&manifest).map_err(|e| e.into())
+    }
+
+    /// Licenses and citations of the datasets an in-memory pack was built from, as an array of
+    /// `{source, dataset, license, citation}` objects. files: as for `pack_manifest`.
+    pub fn pack_citations(files: JsValue) -> Result<JsValue, JsValue> {
+        let mem = js_files_to_mempack(files).map_err(js_err)?;
+        let manifest = openmander_core::Manifest::from_pack_source(&mem).map_err(js_err)?;
+        serde_wasm_bindgen::to_value(&manifest.citations()).map_err(|e| e.into())
+    }
+
+    /// Column statistics of the loaded map, by layer and column (see `pack_column_stats`).
+    pub fn column_stats(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(&self.inner.column_stats()).map_err(|e| e.into())
+    }
+
+    /// Generate SVG text for a given layer, optionally colored by series and
+    /// grouped into one <g> per unit of a larger layer (e.g. "county").
+    /// `projection` is one of "equirectangular" (default), "mercator", "albers", "albers-usa".
     /// Returns SVG XML string (UI can set innerHTML or create Blob).
     #[wasm_bindgen(js_name = "to_svg")]
-    pub fn to_svg(&self, layer: Option<String>, series: Option<String>) -> Result<String, JsValue> {
+    pub fn to_svg(&self, layer: Option<String>, series: Option<String>, group_by: Option<String>, projection: Option<String>) -> Result<String, JsValue> {
         let ty = parse_layer(layer).map_err(js_err)?;
         let lyr = self.inner.layer(ty)
             .ok_or_else(|| js_err(format!("Layer {:?} is not present in this map/pack.", ty.to_str())))?;
+        let group_by = group_by.map(|name| parse_layer(Some(name))).transpose().map_err(js_err)?;
+        let options = openmander_core::SvgOptions { projection: parse_projection(projection).map_err(js_err)?, ..Default::default() };
 
-        lyr.to_svg_string(series.as_deref()).map_err(js_err)
+        lyr.to_svg_string_with_options(series.as_deref(), group_by, &options).map_err(js_err)
     }
 
     /// Export layer geometries as GeoJSON FeatureCollection.
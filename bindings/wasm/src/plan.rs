@@ -57,6 +57,16 @@ impl WasmPlan {
         self.inner.equalize(&series, tolerance, max_iter).map_err(js_err)
     }
 
+    /// Deterministically repair balance after manual edits. Returns `true` if within tolerance.
+    pub fn rebalance(&mut self, series: String, tolerance: f64) -> Result<bool, JsValue> {
+        self.inner.rebalance(&series, tolerance).map_err(js_err)
+    }
+
+    /// Exactly re-split two small adjacent districts. Returns `false` if no balanced split exists.
+    pub fn refine_pair_exact(&mut self, a: u32, b: u32, series: String, tolerance: f64) -> Result<bool, JsValue> {
+        self.inner.refine_pair_exact(a, b, &series, tolerance).map_err(js_err)
+    }
+
     pub fn anneal_balance(
         &mut self,
         series: String,
@@ -83,8 +93,63 @@ impl WasmPlan {
             .map_err(js_err)
     }
 
-    pub fn recombine(&mut self, a: u32, b: u32) -> Result<(), JsValue> {
-        self.inner.recombine(a, b).map_err(js_err)
+    /// Run multi-phase simulated annealing from an `AnnealConfig` given as JSON *text*.
+    /// Returns the last `capacity` of every `every`-th step as a JS array of
+    /// `{ index, phase, accepted, score, metrics: [[name, score], ...] }`.
+    pub fn anneal_with_steps(&mut self, config_json: String, capacity: usize, every: Option<usize>) -> Result<JsValue, JsValue> {
+        let config: openmander_core::AnnealConfig = serde_json::from_str(&config_json).map_err(js_err)?;
+        let mut steps = openmander_core::EveryNth { inner: openmander_core::StepBuffer::new(capacity), n: every.unwrap_or(1) };
+        self.inner.anneal_with_observer(&config, &mut steps).map_err(js_err)?;
+        let records = steps.inner.records().collect::<Vec<_>>();
+        serde_wasm_bindgen::to_value(&records).map_err(|e| e.into())
+    }
+
+    /// The `k` single-block reassignments that most improve a `Metric` given as JSON *text*
+    /// (e.g. `{"type":"compactness_polsby_popper"}`), best first, as a JS array of
+    /// `{ block, from, to, score, improvement }`. The plan is not changed.
+    pub fn best_single_moves(&self, metric_json: String, k: usize) -> Result<JsValue, JsValue> {
+        let metric: openmander_core::Metric = serde_json::from_str(&metric_json).map_err(js_err)?;
+        let moves = self.inner.best_single_moves(&metric, k).map_err(js_err)?;
+        serde_wasm_bindgen::to_value(&moves).map_err(|e| e.into())
+    }
+
+    /// Describe each way the plan breaks `Constraints` given as JSON *text* (e.g.
+    /// `{"contiguous":true,"preserve":[{"layer":"county","geo_ids":["17031"]}]}`), as a JS array of strings.
+    pub fn constraint_violations(&self, constraints_json: String) -> Result<JsValue, JsValue> {
+        let constraints: openmander_core::Constraints = serde_json::from_str(&constraints_json).map_err(js_err)?;
+        let violations = self.inner.constraint_violations(&constraints).map_err(js_err)?;
+        serde_wasm_bindgen::to_value(&violations).map_err(|e| e.into())
+    }
+
+    /// Break the score of a `Metric` given as JSON *text* down by district, as a JS object
+    /// `{ score, contributions: [{ label, districts, value, contribution }] }`, worst district first.
+    pub fn explain_metric(&self, metric_json: String) -> Result<JsValue, JsValue> {
+        let metric: openmander_core::Metric = serde_json::from_str(&metric_json).map_err(js_err)?;
+        serde_wasm_bindgen::to_value(&self.inner.explain_metric(&metric)).map_err(|e| e.into())
+    }
+
+    /// Break the number of county splits down by split county (labelled by GEOID), most splits
+    /// first, in the shape of `explain_metric`.
+    pub fn explain_county_splits(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(&self.inner.explain_county_splits().map_err(js_err)?).map_err(|e| e.into())
+    }
+
+    /// Break the efficiency gap of a contest (e.g. `"20_PRES"`) down by district, largest
+    /// contribution first, in the shape of `explain_metric`.
+    pub fn explain_efficiency_gap(&self, contest: String) -> Result<JsValue, JsValue> {
+        let explanation = self.inner.explain_efficiency_gap(&openmander_core::Contest::named(&contest)).map_err(js_err)?;
+        serde_wasm_bindgen::to_value(&explanation).map_err(|e| e.into())
+    }
+
+    /// Merge districts `a` and `b` and split them again, balancing the halves by `pop_series`.
+    pub fn recombine(&mut self, a: u32, b: u32, pop_series: String) -> Result<(), JsValue> {
+        self.inner.recombine(a, b, &pop_series).map_err(js_err)
+    }
+
+    /// Run `steps` ReCom chain steps, keeping both halves of every split within `tolerance`
+    /// (e.g. 0.02) of the ideal `pop_series` total. Returns the number of steps that changed the plan.
+    pub fn recom(&mut self, pop_series: String, tolerance: f64, steps: usize, seed: Option<u64>) -> Result<usize, JsValue> {
+        self.inner.recom(&pop_series, tolerance, steps, seed).map_err(js_err)
     }
 
     /// Assign all blocks belonging to a geographic unit to a given district.
@@ -106,6 +171,29 @@ impl WasmPlan {
         self.inner.assign_units_batch(&layer, &ids_refs, district).map_err(js_err)
     }
 
+    /// Require that a district always contains a geographic unit.
+    /// Optimization moves will never take the unit's blocks out of `district` (1-indexed).
+    pub fn anchor_unit(&mut self, layer: String, geo_id: String, district: u32) -> Result<(), JsValue> {
+        self.inner.anchor_unit(&layer, &geo_id, district).map_err(js_err)
+    }
+
+    /// Remove all district anchors.
+    pub fn clear_anchors(&mut self) {
+        self.inner.clear_anchors()
+    }
+
+    /// Switch to whole-county districting (oversized counties are split along VTD lines).
+    /// Returns `[units, split]`: the number of atomic units and the GEOIDs of those left split.
+    pub fn set_whole_county_mode(&mut self, pop_series: String) -> Result<JsValue, JsValue> {
+        let result = self.inner.set_whole_county_mode(&pop_series).map_err(js_err)?;
+        serde_wasm_bindgen::to_value(&result).map_err(|e| e.into())
+    }
+
+    /// Leave whole-county mode, allowing individual blocks to move again.
+    pub fn clear_whole_county_mode(&mut self) {
+        self.inner.clear_whole_county_mode()
+    }
+
     /// FAST assignments export: return a Uint32Array of length = #units in active layer.
     pub fn assignments_u32(&self) -> Result<Uint32Array, JsValue> {
         let a: Vec<u32> = self.inner.get_assignments_vec().map_err(js_err)?;
@@ -130,6 +218,15 @@ impl WasmPlan {
         self.inner.set_assignments_vec(v).map_err(js_err)
     }
 
+    /// Set assignments from a Uint32Array (index-based), checking district range and, if asked,
+    /// contiguity of the changed districts. Returns the districts that changed.
+    pub fn set_assignment_bulk(&mut self, arr: Uint32Array, check_contiguity: bool) -> Result<Uint32Array, JsValue> {
+        let mut v = vec![0u32; arr.length() as usize];
+        arr.copy_to(&mut v[..]);
+        let dirty = self.inner.set_assignment_bulk(&v, check_contiguity).map_err(js_err)?;
+        Ok(Uint32Array::from(dirty.as_slice()))
+    }
+
     /// Load assignments from CSV *text* (browser has no file paths).
     pub fn load_csv_text(&mut self, csv: String) -> Result<(), JsValue> {
         self.inner.load_csv(&csv).map_err(js_err)
@@ -140,6 +237,141 @@ impl WasmPlan {
         self.inner.to_csv().map_err(js_err)
     }
 
+    /// Load assignments from the *text* of a block assignment file (`GEOID20,District`).
+    pub fn load_baf_text(&mut self, baf: String) -> Result<(), JsValue> {
+        self.inner.load_baf(&baf).map_err(js_err)
+    }
+
+    /// Export a block assignment file of `layer` units as *text*.
+    pub fn to_baf_text(&self, layer: String, pop_series: Option<String>) -> Result<String, JsValue> {
+        self.inner.to_baf(&layer, pop_series.as_deref()).map_err(js_err)
+    }
+
+    /// Markdown report of the districts of `pop_series` in the language and number format of
+    /// `locale` (e.g. "en-US", "es-US", "es-ES"; default "en-US").
+    #[wasm_bindgen(js_name = "district_report")]
+    pub fn district_report(&self, pop_series: String, locale: Option<String>) -> Result<String, JsValue> {
+        let tag = locale.as_deref().unwrap_or("en-US");
+        let locale = openmander_core::Locale::from_tag(tag).ok_or_else(|| js_err(format!("Unknown locale {tag:?}")))?;
+        self.inner.district_report(&pop_series, &locale).map_err(js_err)
+    }
+
+    /// Export the plan as a GeoJSON FeatureCollection (a JS object) with one feature per district,
+    /// dissolved from its blocks, with `district`, `population` and `deviation` (of `pop_series`)
+    /// properties. `metrics_json` is optional JSON *text* mapping property names to `Metric`s to score.
+    #[wasm_bindgen(js_name = "districts_geojson")]
+    pub fn districts_geojson(&self, pop_series: String, metrics_json: Option<String>) -> Result<JsValue, JsValue> {
+        let metrics: std::collections::BTreeMap<String, openmander_core::Metric> = match metrics_json {
+            Some(json) => serde_json::from_str(&json).map_err(js_err)?,
+            None => Default::default(),
+        };
+        let geojson = self.inner.to_geojson(&pop_series, &metrics.into_iter().collect::<Vec<_>>()).map_err(js_err)?;
+        let json_string = serde_json::to_string(&geojson)
+            .map_err(|e| js_err(format!("Failed to serialize GeoJSON to string: {}", e)))?;
+        js_sys::JSON::parse(&json_string)
+            .map_err(|e| js_err(format!("Failed to parse GeoJSON string: {:?}", e)))
+    }
+
+    /// Content ID of the plan's assignments (SHA-256 of its `GEOID,district` lines in GEOID order).
+    pub fn plan_id(&self) -> Result<String, JsValue> {
+        self.inner.plan_id().map_err(js_err)
+    }
+
+    /// Stamp every text export from now on with the plan ID, library version and a content hash.
+    pub fn set_stamp_exports(&mut self, enabled: bool) { self.inner.set_stamp_exports(enabled) }
+
+    /// Check that a stamped export (as *text*) is unmodified and was exported from this plan.
+    /// Returns its stamp as `{plan_id, tool, sha256}`.
+    pub fn verify_export_text(&self, text: String) -> Result<JsValue, JsValue> {
+        let stamp = self.inner.verify_export(&text).map_err(js_err)?;
+        serde_wasm_bindgen::to_value(&stamp).map_err(|e| e.into())
+    }
+
+    /// Load assignments from JSON *text* produced by `to_json_text`.
+    pub fn load_json_text(&mut self, json: String) -> Result<(), JsValue> {
+        self.inner.load_json(&json).map_err(js_err)
+    }
+
+    /// Export the plan (district count and assignments) as JSON *text*.
+    pub fn to_json_text(&self) -> Result<String, JsValue> {
+        self.inner.to_json().map_err(js_err)
+    }
+
+    /// Start recording every assignment operation on this plan.
+    pub fn enable_audit_log(&mut self) { self.inner.enable_audit_log() }
+
+    /// Export the audit log as JSON Lines *text* (empty if not enabled).
+    pub fn audit_log_jsonl(&self) -> Result<String, JsValue> {
+        self.inner.audit_log()
+            .map_or(Ok(String::new()), |log| log.to_jsonl())
+            .map_err(js_err)
+    }
+
+    /// Start keeping an undo/redo history, storing at most `capacity` block moves (default 1,000,000).
+    pub fn enable_history(&mut self, capacity: Option<usize>) {
+        self.inner.enable_history(capacity.unwrap_or(openmander_core::DEFAULT_HISTORY_CAPACITY))
+    }
+
+    /// Revert the most recent edit. Returns false if there was nothing to undo.
+    pub fn undo(&mut self) -> Result<bool, JsValue> { self.inner.undo().map_err(js_err) }
+
+    /// Reapply the most recently undone edit. Returns false if there was nothing to redo.
+    pub fn redo(&mut self) -> Result<bool, JsValue> { self.inner.redo().map_err(js_err) }
+
+    pub fn can_undo(&self) -> bool { self.inner.can_undo() }
+
+    pub fn can_redo(&self) -> bool { self.inner.can_redo() }
+
+    /// Get the plan's metadata as JSON *text*.
+    pub fn metadata_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(self.inner.metadata()).map_err(js_err)
+    }
+
+    /// Replace the plan's metadata from JSON *text*.
+    pub fn set_metadata_json(&mut self, json: String) -> Result<(), JsValue> {
+        let metadata = serde_json::from_str(&json).map_err(js_err)?;
+        self.inner.set_metadata(metadata);
+        Ok(())
+    }
+
+    /// Hex color of each district (1..=num_districts) from `palette` (default "okabe-ito"),
+    /// with bordering districts given different colors. The same plan always gets the same colors.
+    #[wasm_bindgen(js_name = "district_colors")]
+    pub fn district_colors(&self, palette: Option<String>) -> Result<Vec<String>, JsValue> {
+        let palette = parse_palette(palette).map_err(js_err)?;
+        Ok(self.inner.district_colors(palette))
+    }
+
+    /// Lines where districts meet, as a GeoJSON FeatureCollection of MultiLineStrings with one
+    /// feature per pair of bordering districts (`properties.districts = [a, b]`).
+    #[wasm_bindgen(js_name = "district_borders_geojson")]
+    pub fn district_borders_geojson(&self) -> Result<JsValue, JsValue> {
+        let geojson = self.inner.district_borders_geojson().map_err(js_err)?;
+        let json_string = serde_json::to_string(&geojson)
+            .map_err(|e| js_err(format!("Failed to serialize GeoJSON to string: {}", e)))?;
+        js_sys::JSON::parse(&json_string)
+            .map_err(|e| js_err(format!("Failed to parse GeoJSON string: {:?}", e)))
+    }
+
+    /// Render dissolved districts as an SVG string, filled from `palette` (e.g. "okabe-ito") if given.
+    /// `title` and `description` become the document's `<title>` and `<desc>`, and `patterns`
+    /// hatches each district over its color so that districts can be told apart without color.
+    #[wasm_bindgen(js_name = "to_svg")]
+    pub fn to_svg(&self, projection: Option<String>, palette: Option<String>, title: Option<String>, description: Option<String>, patterns: Option<bool>) -> Result<String, JsValue> {
+        let palette = palette.map(|name| openmander_core::Palette::from_name(&name).ok_or_else(|| js_err(format!("Unknown palette {name:?}")))).transpose()?;
+        let accessibility = openmander_core::Accessibility { title, description, patterns: patterns.unwrap_or(false) };
+        let options = openmander_core::SvgOptions { projection: parse_projection(projection).map_err(js_err)?, accessibility, ..Default::default() };
+        self.inner.to_svg_string_with_options(false, palette, &options).map_err(js_err)
+    }
+
+    /// Render only the lines where districts meet as an SVG string, for overlay on a basemap.
+    /// `projection` is one of "equirectangular" (default), "mercator", "albers", "albers-usa".
+    #[wasm_bindgen(js_name = "to_border_svg")]
+    pub fn to_border_svg(&self, projection: Option<String>) -> Result<String, JsValue> {
+        let options = openmander_core::SvgOptions { projection: parse_projection(projection).map_err(js_err)?, ..Default::default() };
+        self.inner.to_border_svg_string(&options).map_err(js_err)
+    }
+
     /// Export layer geometries as GeoJSON FeatureCollection with district assignments.
     /// Returns GeoJSON as a JavaScript object.
     /// Note: assignments are for the base layer (blocks), so this only works for the base layer.
@@ -186,7 +418,8 @@ impl WasmPlan {
                 }
             });
             
-            let geojson = lyr.to_geojson_with_districts_and_bounds(&assignments, bounds_opt).map_err(js_err)?;
+            let mut geojson = lyr.to_geojson_with_districts_and_bounds(&assignments, bounds_opt).map_err(js_err)?;
+            geojson["metadata"] = serde_json::to_value(self.inner.metadata()).map_err(js_err)?;
             
             // Serialize to JSON string first, then parse in JS to avoid large in-memory structures
             // This is more memory-efficient for large GeoJSON structures
@@ -212,9 +445,31 @@ impl WasmPlan {
     ///
     /// Returns a JavaScript array of objects: [{ district: number, wkb: Uint8Array }, ...]
     /// Districts 1 through num_districts are included. District 0 (unassigned) is excluded.
+    /// `hull` selects the outline: "dissolve" (exact, default), "convex", or "concave" (with `concavity`).
     #[wasm_bindgen(js_name = "district_geometries_wkb")]
-    pub fn district_geometries_wkb(&self) -> Result<Array, JsValue> {
-        let geometries = self.inner.district_geometries_wkb().map_err(js_err)?;
+    pub fn district_geometries_wkb(&self, hull: Option<String>, concavity: Option<f64>) -> Result<Array, JsValue> {
+        let kind = parse_hull(hull, concavity).map_err(js_err)?;
+        let geometries = self.inner.district_outlines_wkb(kind).map_err(js_err)?;
+
+        let arr = Array::new();
+        for (district, wkb) in &geometries {
+            let obj = Object::new();
+            Reflect::set(&obj, &JsValue::from_str("district"), &JsValue::from_f64(*district as f64))
+                .map_err(|_| js_err("failed to set district"))?;
+            Reflect::set(&obj, &JsValue::from_str("wkb"), &Uint8Array::from(wkb.as_slice()))
+                .map_err(|_| js_err("failed to set wkb"))?;
+            arr.push(&obj);
+        }
+
+        Ok(arr)
+    }
+
+    /// Get WKB outlines of only the districts that changed since the last call, for redrawing
+    /// after an edit. The first call returns every district. Same shape as `district_geometries_wkb`;
+    /// a district that became empty has an empty MultiPolygon.
+    #[wasm_bindgen(js_name = "changed_district_geometries_wkb")]
+    pub fn changed_district_geometries_wkb(&mut self) -> Result<Array, JsValue> {
+        let geometries = self.inner.changed_district_outlines_wkb().map_err(js_err)?;
 
         let arr = Array::new();
         for (district, wkb) in &geometries {
@@ -0,0 +1,15 @@
+fn main() {
+    // The gRPC worker service is generated from its proto definition, with the `protoc` named by
+    // PROTOC or else the prebuilt one from `protoc-bin-vendored`.
+    #[cfg(feature = "grpc")]
+    {
+        let mut config = tonic_build::Config::new();
+        if std::env::var_os("PROTOC").is_none() {
+            config.protoc_executable(protoc_bin_vendored::protoc_bin_path().expect("No prebuilt protoc for this platform; set PROTOC"));
+        }
+        println!("cargo:rerun-if-env-changed=PROTOC");
+        tonic_build::configure()
+            .compile_protos_with_config(config, &["proto/worker.proto"], &["proto"])
+            .expect("Failed to compile proto/worker.proto");
+    }
+}
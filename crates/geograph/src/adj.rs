@@ -217,6 +217,25 @@ impl AdjacencyMatrix {
         Self::from_directed_pairs_weighted(nu, triples)
     }
 
+    /// Return a new `AdjacencyMatrix` with units renumbered, where
+    /// `new_id[u]` is the new id of old unit `u`.  Weights move with their edges.
+    pub(crate) fn permuted(&self, new_id: &[UnitId]) -> Self {
+        let nu = self.num_units();
+        let mut triples: Vec<(UnitId, UnitId, f64)> = Vec::with_capacity(self.neighbors.len());
+        for u in 0..nu {
+            let start = self.offsets[u] as usize;
+            let end   = self.offsets[u + 1] as usize;
+            for i in start..end {
+                let w = self.weights.as_ref().map_or(0.0, |ws| ws[i]);
+                triples.push((new_id[u], new_id[self.neighbors[i].0 as usize], w));
+            }
+        }
+
+        let mut matrix = Self::from_directed_pairs_weighted(nu, triples);
+        if self.weights.is_none() { matrix.weights = None }
+        matrix
+    }
+
     /// Approximate heap bytes consumed by this matrix.
     pub(crate) fn heap_bytes(&self) -> usize {
         self.offsets.capacity()   * std::mem::size_of::<u32>()
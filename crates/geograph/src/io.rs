@@ -10,7 +10,8 @@
 //! [Header]
 //!   magic:           4 bytes ("OMRP")
 //!   version:         1 byte
-//!   reserved:        3 bytes
+//!   flags:           1 byte   (version 3 and later; bit 0 = graph-only)
+//!   reserved:        2 bytes
 //!   num_vertices:    u32
 //!   num_half_edges:  u32  (always even)
 //!   num_faces:       u32
@@ -30,22 +31,42 @@
 //! [RookAdj neighbors]     num_rook_edges      × u32                             4 B each
 //! [TouchingAdj offsets]   num_units + 1       × u32                             4 B each
 //! [TouchingAdj neighbors] num_touching_edges  × u32                             4 B each
+//! [SpatialIndex]          (version 2 and later)
+//!   num_nodes:            u32  (units plus internal nodes of the packed R-tree)
+//!   boxes:                num_nodes           × (f64 min_x, f64 min_y,
+//!                                                f64 max_x, f64 max_y)         32 B each
+//!   indices:              num_nodes           × u32                             4 B each
+//! [GraphOnly]             (graph-only files only)
+//!   rook weights:         num_rook_edges      × f64                             8 B each
+//!   unit metrics:         num_units           × (f64 exterior_boundary_m,
+//!                                                f64 lon, f64 lat,
+//!                                                f64 min_x, f64 min_y,
+//!                                                f64 max_x, f64 max_y,
+//!                                                u8 is_exterior)               57 B each
 //! ```
 //!
 //! Fields not stored (centroids, bounds, `is_exterior`, `exterior_boundary_length`)
-//! are recomputed from the DCEL on load.
+//! are recomputed from the DCEL on load.  The spatial index is read as stored;
+//! for version 1 files, which lack it, it is rebuilt from the unit bounds.
+//!
+//! A region without geometry (see [`crate::Region::drop_geometry`]) is written
+//! graph-only: the DCEL sections are empty, so the adjacency weights and the
+//! otherwise-derived unit metrics are stored in a trailing section instead.
 
 mod read;
 mod write;
 
-pub use read::read;
+pub use read::{read, read_with_precision};
 pub use write::write;
 
 /// Magic bytes at the start of every geograph binary file.
 pub const MAGIC: &[u8; 4] = b"OMRP";
 
 /// Current file format version.
-pub const VERSION: u8 = 1;
+pub const VERSION: u8 = 3;
+
+/// Header flag: the file has no DCEL and carries a [GraphOnly] section.
+pub(crate) const FLAG_GRAPH_ONLY: u8 = 1;
 
 /// Errors that can occur during serialisation or deserialisation.
 #[derive(Debug)]
@@ -191,6 +212,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn round_trip_preserves_spatial_index() {
+        let r  = make_two_unit_region();
+        let r2 = round_trip(&r);
+        assert_eq!(r.rtree.boxes, r2.rtree.boxes);
+        assert_eq!(r.rtree.indices, r2.rtree.indices);
+        assert_eq!(r2.unit_at(geo::Coord { x: 1.5, y: 0.5 }), Some(crate::UnitId(1)));
+    }
+
+    #[test]
+    fn version_1_file_rebuilds_spatial_index() {
+        let r = make_two_unit_region();
+        let mut buf = Vec::new();
+        write(&r, &mut buf).expect("write failed");
+        buf.truncate(buf.len() - 4 - r.rtree.boxes.len() * 36); // drop the spatial index section
+        buf[4] = 1;
+        let r2 = read(&mut buf.as_slice()).expect("read failed");
+        assert_eq!(r2.unit_at(geo::Coord { x: 0.5, y: 0.5 }), Some(crate::UnitId(0)));
+    }
+
+    #[test]
+    fn graph_only_round_trip_keeps_graph_and_metrics() {
+        let r = make_two_unit_region();
+        let mut graph = r.clone();
+        graph.drop_geometry();
+        let r2 = round_trip(&graph);
+        assert!(!r2.has_geometry());
+        assert_eq!(r2.num_units(), 2);
+        for uid in r.unit_ids() {
+            assert_eq!(r.neighbors(uid), r2.neighbors(uid));
+            assert_eq!(r.adjacency().weights_of(uid), r2.adjacency().weights_of(uid));
+            assert_eq!(r.area(uid), r2.area(uid));
+            assert_eq!(r.exterior_boundary_length(uid), r2.exterior_boundary_length(uid));
+            assert_eq!(r.centroid(uid), r2.centroid(uid));
+            assert_eq!(r.bounds(uid), r2.bounds(uid));
+            assert_eq!(r.is_exterior(uid), r2.is_exterior(uid));
+        }
+    }
+
     // -----------------------------------------------------------------------
     // Error cases
     // -----------------------------------------------------------------------
@@ -5,11 +5,11 @@ use geo::{Coord, Rect};
 use crate::adj::AdjacencyMatrix;
 use crate::dcel::{Dcel, Face, FaceId, HalfEdge, HalfEdgeId, Vertex, VertexId};
 use crate::region::cache::{compute_bounds, compute_centroids, compute_exterior_boundary_length, compute_is_exterior};
-use crate::region::Region;
+use crate::region::{GeometryPrecision, Region};
 use crate::rtree::SpatialIndex;
 use crate::unit::UnitId;
 
-use super::{IoError, MAGIC, VERSION};
+use super::{FLAG_GRAPH_ONLY, IoError, MAGIC, VERSION};
 
 const NONE_U32: u32 = 0xFFFF_FFFF;
 const COORD_SCALE: f64 = 1e7;
@@ -22,9 +22,11 @@ fn decode_coord(v: i32) -> f64 { v as f64 / COORD_SCALE }
 /// Deserialise a [`Region`] from `reader`.
 ///
 /// The counterpart to [`crate::io::write`]. Reads DCEL topology, adjacency
-/// CSRs, and pre-cached `area`/`perimeter`/edge-lengths from disk; recomputes
+/// CSRs, pre-cached `area`/`perimeter`/edge-lengths, and the spatial index
+/// (rebuilt for version 1 files) from disk; recomputes
 /// all other cached fields (centroids, bounds, `is_exterior`,
 /// `exterior_boundary_length`, rook adjacency weights) from the DCEL on load.
+/// Graph-only files carry those fields directly and load without geometry.
 ///
 /// See the [`crate::io`] module for the full file format.
 ///
@@ -32,14 +34,25 @@ fn decode_coord(v: i32) -> f64 { v as f64 / COORD_SCALE }
 ///
 /// Returns [`IoError`] if the magic bytes are wrong, the version is
 /// unsupported, or an I/O error occurs.
+#[inline]
 pub fn read(reader: &mut impl Read) -> Result<Region, IoError> {
+    read_with_precision(reader, GeometryPrecision::F64)
+}
+
+/// Deserialise a [`Region`] from `reader`, storing unit geometries at `precision`.
+///
+/// Same as [`read`], but with [`GeometryPrecision::F32`] the reconstructed
+/// geometries are narrowed as they are loaded, halving their memory.
+pub fn read_with_precision(reader: &mut impl Read, precision: GeometryPrecision) -> Result<Region, IoError> {
     // ---- Header ----
     let mut magic = [0u8; 4];
     reader.read_exact(&mut magic)?;
     if &magic != MAGIC { return Err(IoError::InvalidMagic) }
-    let mut vr = [0u8; 4]; // version + 3 reserved
+    let mut vr = [0u8; 4]; // version + flags + 2 reserved
     reader.read_exact(&mut vr)?;
-    if vr[0] != VERSION { return Err(IoError::UnsupportedVersion(vr[0])) }
+    let version = vr[0];
+    if !(1..=VERSION).contains(&version) { return Err(IoError::UnsupportedVersion(version)) }
+    let graph_only = version >= 3 && vr[1] & FLAG_GRAPH_ONLY != 0;
     let num_vertices   = read_u32(reader)? as usize;
     let num_half_edges = read_u32(reader)? as usize;
     let num_faces      = read_u32(reader)? as usize;
@@ -133,12 +146,21 @@ pub fn read(reader: &mut impl Read) -> Result<Region, IoError> {
     // ---- Adjacency CSR ----
     // Read the stored Rook CSR.  It may contain forced pairs (island bridges)
     // that are not present in the DCEL geometry; those must be preserved.
-    let adjacent_stored = read_csr(reader, num_units)?;
-    let touching = read_csr(reader, num_units)?;
+    let rook_pairs = read_csr_pairs(reader, num_units)?;
+    let touching = AdjacencyMatrix::from_directed_pairs(num_units, read_csr_pairs(reader, num_units)?);
+
+    // ---- Spatial index (absent before version 2) ----
+    let rtree = if version >= 2 { Some(read_spatial_index(reader, num_units)?) } else { None };
 
     // ---- Rebuild DCEL ----
     let dcel = Dcel { vertices, half_edges, faces };
 
+    if graph_only {
+        let rtree = rtree.ok_or_else(|| IoError::InvalidData("graph-only file without spatial index".into()))?;
+        return read_graph_only(reader, dcel, face_to_unit, area, perimeter, rook_pairs, touching, rtree);
+    }
+    let adjacent_stored = AdjacencyMatrix::from_directed_pairs(num_units, rook_pairs);
+
     // ---- Rebuild Rook adjacency: DCEL-derived weights + forced pairs from stored CSR ----
     // Build the natural adjacency from the DCEL (correct shared-boundary weights).
     let adjacent_natural = crate::region::adj::build_adjacent(&dcel, &face_to_unit, &edge_length, num_units);
@@ -172,8 +194,9 @@ pub fn read(reader: &mut impl Read) -> Result<Region, IoError> {
         rect
     };
     let is_exterior = compute_is_exterior(&dcel, &face_to_unit, num_units);
-    let geometries = crate::region::build::reconstruct_geometries(&dcel, &face_to_unit, num_units);
-    let rtree = SpatialIndex::new(&bounds);
+    let geometries = crate::region::geometries::Geometries::F64(crate::region::build::reconstruct_geometries(&dcel, &face_to_unit, num_units))
+        .with_precision(precision);
+    let rtree = rtree.unwrap_or_else(|| SpatialIndex::new(&bounds));
     let (unit_to_faces_offsets, unit_to_faces_data) = crate::region::build::compute_unit_to_faces(&face_to_unit, num_units);
     let face_inner_cycles = crate::region::build::compute_face_inner_cycles(&dcel);
 
@@ -212,11 +235,76 @@ pub fn read(reader: &mut impl Read) -> Result<Region, IoError> {
     Ok(region)
 }
 
+// ---------------------------------------------------------------------------
+// Graph-only helper
+// ---------------------------------------------------------------------------
+
+/// Finish reading a graph-only file: the rook weights and unit metrics that
+/// are normally derived from the DCEL are read from the trailing section.
+#[allow(clippy::too_many_arguments)]
+fn read_graph_only(
+    reader: &mut impl Read,
+    dcel: Dcel<Coord<f64>>,
+    face_to_unit: Vec<UnitId>,
+    area: Vec<f64>,
+    perimeter: Vec<f64>,
+    rook_pairs: Vec<(UnitId, UnitId)>,
+    touching: AdjacencyMatrix,
+    rtree: SpatialIndex,
+) -> Result<Region, IoError> {
+    let num_units = area.len();
+    let mut triples = Vec::with_capacity(rook_pairs.len());
+    for (u, v) in rook_pairs {
+        triples.push((u, v, read_f64(reader)?));
+    }
+    let adjacent = AdjacencyMatrix::from_directed_pairs_weighted(num_units, triples);
+
+    let mut exterior_boundary_length = Vec::with_capacity(num_units);
+    let mut centroid = Vec::with_capacity(num_units);
+    let mut bounds = Vec::with_capacity(num_units);
+    let mut is_exterior = Vec::with_capacity(num_units);
+    for _ in 0..num_units {
+        exterior_boundary_length.push(read_f64(reader)?);
+        centroid.push(Coord { x: read_f64(reader)?, y: read_f64(reader)? });
+        let min = Coord { x: read_f64(reader)?, y: read_f64(reader)? };
+        let max = Coord { x: read_f64(reader)?, y: read_f64(reader)? };
+        bounds.push(Rect::new(min, max));
+        let mut flag = [0u8; 1];
+        reader.read_exact(&mut flag)?;
+        is_exterior.push(flag[0] != 0);
+    }
+    let bounds_all = bounds.iter().copied().reduce(|a, b| Rect::new(
+        Coord { x: a.min().x.min(b.min().x), y: a.min().y.min(b.min().y) },
+        Coord { x: a.max().x.max(b.max().x), y: a.max().y.max(b.max().y) },
+    )).unwrap_or_else(|| Rect::new(Coord { x: 0.0, y: 0.0 }, Coord { x: 0.0, y: 0.0 }));
+
+    Ok(Region {
+        dcel,
+        face_to_unit,
+        geometries: crate::region::geometries::Geometries::F64(vec![geo::MultiPolygon(Vec::new()); num_units]),
+        area,
+        perimeter,
+        exterior_boundary_length,
+        centroid,
+        bounds,
+        bounds_all,
+        is_exterior,
+        edge_length: Vec::new(),
+        adjacent,
+        touching,
+        rtree,
+        unit_to_faces_offsets: vec![0; num_units + 1],
+        unit_to_faces_data: Vec::new(),
+        face_inner_cycles: Default::default(),
+    })
+}
+
 // ---------------------------------------------------------------------------
 // CSR helper
 // ---------------------------------------------------------------------------
 
-fn read_csr(reader: &mut impl Read, num_units: usize) -> Result<AdjacencyMatrix, IoError> {
+/// Read a stored CSR as directed `(unit, neighbor)` pairs in file order.
+fn read_csr_pairs(reader: &mut impl Read, num_units: usize) -> Result<Vec<(UnitId, UnitId)>, IoError> {
     let mut offsets = Vec::with_capacity(num_units + 1);
     for _ in 0..=num_units {
         offsets.push(read_u32(reader)?);
@@ -235,6 +323,26 @@ fn read_csr(reader: &mut impl Read, num_units: usize) -> Result<AdjacencyMatrix,
             pairs.push((UnitId(u as u32), UnitId(nb)));
         }
     }
-    Ok(AdjacencyMatrix::from_directed_pairs(num_units, pairs))
+    Ok(pairs)
 }
 
+
+// ---------------------------------------------------------------------------
+// Spatial index helper
+// ---------------------------------------------------------------------------
+
+fn read_spatial_index(reader: &mut impl Read, num_units: usize) -> Result<SpatialIndex, IoError> {
+    let num_nodes = read_u32(reader)? as usize;
+    let mut boxes = Vec::with_capacity(num_nodes);
+    for _ in 0..num_nodes {
+        let (min_x, min_y) = (read_f64(reader)?, read_f64(reader)?);
+        let (max_x, max_y) = (read_f64(reader)?, read_f64(reader)?);
+        boxes.push(Rect::new(Coord { x: min_x, y: min_y }, Coord { x: max_x, y: max_y }));
+    }
+    let mut indices = Vec::with_capacity(num_nodes);
+    for _ in 0..num_nodes {
+        indices.push(read_u32(reader)?);
+    }
+    SpatialIndex::from_parts(num_units, boxes, indices)
+        .ok_or_else(|| IoError::InvalidData("spatial index does not match unit count".into()))
+}
@@ -4,7 +4,7 @@ use crate::adj::AdjacencyMatrix;
 use crate::dcel::{FaceId, HalfEdgeId, VertexId};
 use crate::region::Region;
 
-use super::{IoError, MAGIC, VERSION};
+use super::{FLAG_GRAPH_ONLY, IoError, MAGIC, VERSION};
 
 const NONE_U32: u32 = 0xFFFF_FFFF;
 const COORD_SCALE: f64 = 1e7;
@@ -17,10 +17,11 @@ fn encode_coord(v: f64) -> i32 { (v * COORD_SCALE).round() as i32 }
 /// Serialise a [`Region`] to `writer` using the geograph binary format.
 ///
 /// The counterpart to [`crate::io::read`]. Writes DCEL topology, adjacency
-/// CSRs (neighbor pairs only, not weights), and pre-cached
-/// `area`/`perimeter`/edge-lengths; other cached fields (centroids, bounds,
+/// CSRs (neighbor pairs only, not weights), pre-cached
+/// `area`/`perimeter`/edge-lengths, and the packed spatial index; other cached fields (centroids, bounds,
 /// `is_exterior`, `exterior_boundary_length`, rook adjacency weights) are
-/// recomputed on load.
+/// recomputed on load. A region without geometry is written graph-only, with
+/// those fields stored instead.
 ///
 /// See the [`crate::io`] module for the full file format.
 ///
@@ -35,7 +36,8 @@ pub fn write(region: &Region, writer: &mut impl Write) -> Result<(), IoError> {
 
     // ---- Header ----
     writer.write_all(MAGIC)?;
-    writer.write_all(&[VERSION, 0, 0, 0])?; // version + 3 reserved bytes
+    let graph_only = !region.has_geometry();
+    writer.write_all(&[VERSION, if graph_only { FLAG_GRAPH_ONLY } else { 0 }, 0, 0])?; // version + flags + 2 reserved bytes
     write_u32(writer, num_vertices)?;
     write_u32(writer, num_half_edges)?;
     write_u32(writer, num_faces)?;
@@ -87,6 +89,33 @@ pub fn write(region: &Region, writer: &mut impl Write) -> Result<(), IoError> {
     // ---- Queen adjacency CSR ----
     write_csr(writer, region, region.touching())?;
 
+    // ---- Spatial index ----
+    write_u32(writer, region.rtree.boxes.len() as u32)?;
+    for rect in &region.rtree.boxes {
+        write_f64(writer, rect.min().x)?;
+        write_f64(writer, rect.min().y)?;
+        write_f64(writer, rect.max().x)?;
+        write_f64(writer, rect.max().y)?;
+    }
+    for &index in &region.rtree.indices {
+        write_u32(writer, index)?;
+    }
+
+    // ---- Graph-only: values otherwise derived from the DCEL ----
+    if graph_only {
+        let adjacency = region.adjacency();
+        for k in 0..adjacency.num_directed_edges() {
+            write_f64(writer, adjacency.weight_at(k))?;
+        }
+        for u in 0..num_units as usize {
+            let (centroid, bounds) = (region.centroid[u], region.bounds[u]);
+            for v in [region.exterior_boundary_length[u], centroid.x, centroid.y, bounds.min().x, bounds.min().y, bounds.max().x, bounds.max().y] {
+                write_f64(writer, v)?;
+            }
+            writer.write_all(&[region.is_exterior[u] as u8])?;
+        }
+    }
+
     Ok(())
 }
 
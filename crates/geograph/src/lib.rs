@@ -6,5 +6,5 @@ pub(crate) mod rtree;
 pub(crate) mod unit;
 
 pub use adj::AdjacencyMatrix;
-pub use region::{Region, RegionError};
+pub use region::{GeometryPrecision, LabelOutlines, Region, RegionError};
 pub use unit::UnitId;
@@ -6,12 +6,20 @@ mod boundary;
 pub(crate) mod cache;
 mod snap;
 mod geom;
+mod outlines;
+pub(crate) mod geometries;
+mod reorder;
 mod simplify;
 mod topo;
 mod validate;
 
+use std::borrow::Cow;
+
 use geo::{Coord, LineString, MultiPolygon, Rect};
 
+pub use geometries::GeometryPrecision;
+pub use outlines::LabelOutlines;
+
 /// Errors that can occur when constructing or validating a [`Region`].
 #[derive(Debug)]
 pub enum RegionError {
@@ -34,6 +42,8 @@ use crate::dcel::{Dcel, FaceId, HalfEdgeId};
 use crate::rtree::SpatialIndex;
 use crate::unit::UnitId;
 
+use geometries::Geometries;
+
 // ---------------------------------------------------------------------------
 // Region
 // ---------------------------------------------------------------------------
@@ -73,9 +83,9 @@ pub struct Region {
     /// `UnitId::EXTERIOR` for the unbounded face and any interior gaps.
     pub(crate) face_to_unit: Vec<UnitId>,
 
-    /// Original input geometries, indexed by `UnitId.0`.
-    /// `UnitId::EXTERIOR` has no entry here.
-    pub(crate) geometries: Vec<MultiPolygon<f64>>,
+    /// Original input geometries, indexed by `UnitId.0`, stored at `f64` or `f32`
+    /// (see [`GeometryPrecision`]).  `UnitId::EXTERIOR` has no entry here.
+    pub(crate) geometries: Geometries,
 
     /// Pre-cached area in m² (per-edge cos(φ_mid) weighted shoelace).
     pub(crate) area: Vec<f64>,
@@ -174,18 +184,8 @@ impl Region {
 
         let face_to_unit_bytes = self.face_to_unit.capacity() * size_of::<UnitId>();
 
-        // Count every coordinate stored across all MultiPolygon geometries.
-        let geom_coord_count: usize = self.geometries.iter()
-            .flat_map(|mp| mp.0.iter())
-            .map(|poly| {
-                poly.exterior().0.len()
-                + poly.interiors().iter().map(|r| r.0.len()).sum::<usize>()
-            })
-            .sum();
-        // 16 bytes per Coord<f64>; add Vec/LineString/Polygon/MultiPolygon header
-        // overhead as a rough 24-byte-per-entry estimate for the outer containers.
-        let geom_bytes = geom_coord_count * size_of::<geo::Coord<f64>>()
-            + self.geometries.capacity() * 24;
+        // 16 bytes per Coord<f64> (8 at f32 precision), plus container overhead.
+        let geom_bytes = self.geometries.heap_bytes();
 
         let scalars_bytes =
             (self.area.capacity()
@@ -198,9 +198,9 @@ impl Region {
 
         let adj_bytes = self.adjacent.heap_bytes() + self.touching.heap_bytes();
 
-        // R-tree: each leaf node holds a UnitBBox (UnitId=4 + Rect=32 = ~40 bytes)
-        // plus internal tree node overhead (~64 bytes/node, ~n/9 nodes for 9-leaf pages).
-        let rtree_approx = self.geometries.len() * 48;
+        // Packed R-tree: one box and index per node (leaves plus ~n/15 internal nodes).
+        let rtree_bytes = self.rtree.boxes.capacity() * size_of::<geo::Rect<f64>>()
+            + self.rtree.indices.capacity() * size_of::<u32>();
 
         let unit_to_faces_bytes =
             self.unit_to_faces_offsets.capacity() * size_of::<u32>()
@@ -216,7 +216,7 @@ impl Region {
             ("geometries",        geom_bytes),
             ("scalars",           scalars_bytes),
             ("adjacency",         adj_bytes),
-            ("rtree",             rtree_approx),
+            ("rtree",             rtree_bytes),
             ("unit_to_faces",     unit_to_faces_bytes),
             ("face_inner_cycles", fic_bytes),
         ]
@@ -227,11 +227,12 @@ impl Region {
     /// The geometry is reconstructed from the internal DCEL during construction, so
     /// hole rings for donut-shaped units (e.g. a block surrounding an enclave) are
     /// correctly included even if the original input polygon did not encode them.
+    /// Borrowed at `f64` precision; widened into an owned copy at `f32`.
     ///
     /// <div class="warning">Panics if <code>unit</code> is <a href="UnitId::EXTERIOR"><code>UnitId::EXTERIOR</code></a> or out of range.</div>
     #[inline]
-    pub fn geometry(&self, unit: UnitId) -> &MultiPolygon<f64> {
-        &self.geometries[unit.0 as usize]
+    pub fn geometry(&self, unit: UnitId) -> Cow<'_, MultiPolygon<f64>> {
+        self.geometries.get(unit.0 as usize)
     }
 
 }
@@ -333,10 +334,10 @@ pub(crate) mod test_helpers {
         Region {
             dcel,
             face_to_unit,
-            geometries: vec![
+            geometries: super::Geometries::F64(vec![
                 make_poly(&[(0.0,0.0),(1.0,0.0),(1.0,1.0),(0.0,1.0),(0.0,0.0)]),
                 make_poly(&[(1.0,0.0),(2.0,0.0),(2.0,1.0),(1.0,1.0),(1.0,0.0)]),
-            ],
+            ]),
             // All cached scalars set to known values for test assertions.
             area:                     vec![10.0, 20.0],
             perimeter:                vec![4.0,  4.0 ],
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use geo::{Coord, LineString, MultiLineString, MultiPolygon, Polygon};
 
@@ -121,6 +121,77 @@ impl Region {
         cycles_to_multipolygon(cycles)
     }
 
+    /// Lines where units with different labels meet, grouped by the label pair `(a, b)` with
+    /// `a < b` and sorted by pair. Edges on the region exterior are not included.
+    ///
+    /// Only the faces of `units` are walked, so it is enough to pass every unit that borders a
+    /// unit with a different label (e.g. the frontier of each district). Shared edges are chained
+    /// into maximal runs; a run that closes on itself has first == last.
+    pub fn label_boundaries(
+        &self,
+        units: impl IntoIterator<Item = UnitId>,
+        label: impl Fn(UnitId) -> u32,
+    ) -> Vec<((u32, u32), MultiLineString<f64>)> {
+        // Each shared edge is kept once, as the half-edge on the side with the smaller label.
+        let mut pair_of: HashMap<HalfEdgeId, (u32, u32)> = HashMap::new();
+        let mut pairs: BTreeMap<(u32, u32), Vec<HalfEdgeId>> = BTreeMap::new();
+        for unit in units {
+            for &face_id in self.unit_faces(unit) {
+                let Some(start) = self.dcel.face(face_id).half_edge else { continue };
+                let starts = std::iter::once(start).chain(self.face_inner_cycle_starts(face_id).iter().copied());
+                for he in starts.flat_map(|start| self.dcel.face_cycle(start)) {
+                    let twin_unit = self.face_to_unit[self.dcel.half_edge(he.twin()).face.0 as usize];
+                    if twin_unit == UnitId::EXTERIOR { continue; }
+                    let (a, b) = (label(unit), label(twin_unit));
+                    if a == b { continue; }
+                    let (he, pair) = if a < b { (he, (a, b)) } else { (he.twin(), (b, a)) };
+                    if pair_of.insert(he, pair).is_none() {
+                        pairs.entry(pair).or_default().push(he);
+                    }
+                }
+            }
+        }
+
+        pairs.into_iter().map(|(pair, mut edges)| {
+            edges.sort_unstable();
+            let next: HashMap<HalfEdgeId, HalfEdgeId> = edges.iter()
+                .filter_map(|&he| self.next_labelled(he, |h| pair_of.get(&h) == Some(&pair)).map(|next| (he, next)))
+                .collect();
+            let has_prev: HashSet<HalfEdgeId> = next.values().copied().collect();
+
+            // Open runs start at edges with no predecessor; whatever is left over is closed loops.
+            let mut visited: HashSet<HalfEdgeId> = HashSet::new();
+            let mut lines = Vec::new();
+            let starts = edges.iter().filter(|he| !has_prev.contains(he)).chain(edges.iter());
+            for &start in starts {
+                if visited.contains(&start) { continue; }
+                let mut coords = vec![self.dcel.vertex(self.dcel.half_edge(start).origin).coords];
+                let mut cur = start;
+                loop {
+                    visited.insert(cur);
+                    coords.push(self.dcel.vertex(self.dcel.dest(cur)).coords);
+                    match next.get(&cur) {
+                        Some(&he) if !visited.contains(&he) => cur = he,
+                        _ => break,
+                    }
+                }
+                lines.push(LineString(coords));
+            }
+            (pair, MultiLineString(lines))
+        }).collect()
+    }
+
+    /// The first half-edge leaving the destination of `he` (turning from `he`'s face) that
+    /// satisfies `keep`, or `None` if only the reverse of `he` remains.
+    fn next_labelled(&self, he: HalfEdgeId, keep: impl Fn(HalfEdgeId) -> bool) -> Option<HalfEdgeId> {
+        let mut next = self.dcel.half_edge(he).next;
+        while next != he.twin() {
+            if keep(next) { return Some(next); }
+            next = self.dcel.half_edge(next.twin()).next;
+        }
+        None
+    }
+
     // -----------------------------------------------------------------------
     // Private helpers
     // -----------------------------------------------------------------------
@@ -184,7 +255,7 @@ impl Region {
 
 /// Partition cycles into outer rings and holes, match holes to their enclosing
 /// outer ring, and assemble a `MultiPolygon`.
-fn cycles_to_multipolygon(cycles: Vec<(Ring, f64)>) -> MultiPolygon<f64> {
+pub(super) fn cycles_to_multipolygon(cycles: Vec<(Ring, f64)>) -> MultiPolygon<f64> {
     let mut outers: Vec<(Ring, Interiors)> = Vec::new();
     let mut holes: Vec<Ring> = Vec::new();
 
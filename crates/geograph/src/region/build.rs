@@ -10,6 +10,7 @@ use crate::unit::UnitId;
 use super::{Region, RegionError, Ring};
 use super::adj::{build_adjacent, build_touching};
 use super::cache::{CacheData, compute_caches};
+use super::geometries::Geometries;
 
 impl Region {
     /// Build a `Region` from a vector of `MultiPolygon` geometries (one per
@@ -64,7 +65,7 @@ impl Region {
 
         // Replace input geometries with DCEL-derived ones so that donut-shaped
         // units (blocks surrounding enclaves) have correct interior hole rings.
-        let geometries = reconstruct_geometries(&dcel, &face_to_unit, num_units);
+        let geometries = Geometries::F64(reconstruct_geometries(&dcel, &face_to_unit, num_units));
         eprintln!("[region::new] 5e. geometries reconstructed in {:.2?}", t0.elapsed());
         let face_inner_cycles = compute_face_inner_cycles(&dcel);
         eprintln!("[region::new] 5f. face_inner_cycles built in {:.2?}", t0.elapsed());
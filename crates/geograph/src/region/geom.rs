@@ -1,7 +1,6 @@
 use std::collections::HashSet;
 
 use geo::{ConvexHull, Coord, Contains, LineString, MultiLineString, MultiPolygon, Polygon, Rect};
-use rstar::AABB;
 
 use crate::dcel::FaceId;
 use crate::unit::UnitId;
@@ -84,7 +83,7 @@ impl Region {
     /// Convex hull of `unit` in lon/lat.
     #[inline]
     pub fn convex_hull(&self, unit: UnitId) -> Polygon<f64> {
-        self.geometries[unit.0 as usize].convex_hull()
+        self.geometry(unit).convex_hull()
     }
 
     // -----------------------------------------------------------------------
@@ -174,7 +173,7 @@ impl Region {
     pub fn convex_hull_of(&self, units: impl IntoIterator<Item = UnitId>) -> Polygon<f64> {
         let combined: MultiPolygon<f64> = MultiPolygon(
             units.into_iter()
-                .flat_map(|u| self.geometries[u.0 as usize].0.iter().cloned())
+                .flat_map(|u| self.geometry(u).into_owned().0)
                 .collect(),
         );
         combined.convex_hull()
@@ -255,8 +254,8 @@ impl Region {
     #[inline]
     pub fn unit_at(&self, point: Coord<f64>) -> Option<UnitId> {
         let geo_point = geo::Point::from(point);
-        self.rtree.query_point([point.x, point.y])
-            .find(|&uid| self.geometries[uid.0 as usize].contains(&geo_point))
+        self.rtree.query_point(point).into_iter()
+            .find(|&uid| self.geometry(uid).contains(&geo_point))
     }
 
     /// Return all `UnitId`s whose bounding box intersects `envelope`.
@@ -265,11 +264,7 @@ impl Region {
     /// the envelope geometrically.  Use for candidate generation.
     #[inline]
     pub fn units_in_envelope(&self, envelope: Rect<f64>) -> Vec<UnitId> {
-        let aabb = AABB::from_corners(
-            [envelope.min().x, envelope.min().y],
-            [envelope.max().x, envelope.max().y],
-        );
-        self.rtree.query(aabb).collect()
+        self.rtree.query(envelope)
     }
 
     // -----------------------------------------------------------------------
@@ -0,0 +1,200 @@
+use std::borrow::Cow;
+
+use geo::{Coord, CoordsIter, MapCoords, MultiPolygon};
+
+use crate::unit::UnitId;
+
+use super::Region;
+
+/// Floating-point precision used to store unit geometries.
+///
+/// `F32` halves the memory held by unit geometries, at the cost of ~1 m of
+/// coordinate precision.  The DCEL and all cached metrics (area, perimeter,
+/// edge lengths, centroids) stay `f64` either way, so scores are unaffected.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GeometryPrecision {
+    #[default]
+    F64,
+    F32,
+}
+
+impl GeometryPrecision {
+    /// Get the string representation of the precision.
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            GeometryPrecision::F64 => "f64",
+            GeometryPrecision::F32 => "f32",
+        }
+    }
+
+    /// Create a precision from its string representation.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "f64" | "double" => Some(GeometryPrecision::F64),
+            "f32" | "single" | "float" => Some(GeometryPrecision::F32),
+            _ => None,
+        }
+    }
+}
+
+/// Per-unit geometries, stored at either precision.
+#[derive(Clone)]
+pub(crate) enum Geometries {
+    F64(Vec<MultiPolygon<f64>>),
+    F32(Vec<MultiPolygon<f32>>),
+}
+
+impl Geometries {
+    /// Number of units.
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Geometries::F64(geoms) => geoms.len(),
+            Geometries::F32(geoms) => geoms.len(),
+        }
+    }
+
+    /// Precision the geometries are stored at.
+    #[inline]
+    pub(crate) fn precision(&self) -> GeometryPrecision {
+        match self {
+            Geometries::F64(_) => GeometryPrecision::F64,
+            Geometries::F32(_) => GeometryPrecision::F32,
+        }
+    }
+
+    /// Geometry of unit `i` in `f64`, borrowed if stored at that precision.
+    #[inline]
+    pub(crate) fn get(&self, i: usize) -> Cow<'_, MultiPolygon<f64>> {
+        match self {
+            Geometries::F64(geoms) => Cow::Borrowed(&geoms[i]),
+            Geometries::F32(geoms) => Cow::Owned(widen(&geoms[i])),
+        }
+    }
+
+    /// All geometries in `f64`.
+    pub(crate) fn to_vec(&self) -> Vec<MultiPolygon<f64>> {
+        match self {
+            Geometries::F64(geoms) => geoms.clone(),
+            Geometries::F32(geoms) => geoms.iter().map(widen).collect(),
+        }
+    }
+
+    /// Convert to `precision`, consuming the current storage.
+    pub(crate) fn with_precision(self, precision: GeometryPrecision) -> Self {
+        match (self, precision) {
+            (Geometries::F64(geoms), GeometryPrecision::F32) => Geometries::F32(geoms.iter().map(narrow).collect()),
+            (Geometries::F32(geoms), GeometryPrecision::F64) => Geometries::F64(geoms.iter().map(widen).collect()),
+            (geoms, _) => geoms,
+        }
+    }
+
+    /// Geometries reordered so that entry `i` is the old entry `order[i]`.
+    pub(crate) fn permuted(&self, order: &[UnitId]) -> Self {
+        match self {
+            Geometries::F64(geoms) => Geometries::F64(order.iter().map(|u| geoms[u.0 as usize].clone()).collect()),
+            Geometries::F32(geoms) => Geometries::F32(order.iter().map(|u| geoms[u.0 as usize].clone()).collect()),
+        }
+    }
+
+    /// Approximate heap bytes: coordinates plus a rough 24 bytes of container overhead per unit.
+    pub(crate) fn heap_bytes(&self) -> usize {
+        use std::mem::size_of;
+        match self {
+            Geometries::F64(geoms) =>
+                geoms.iter().map(|mp| mp.coords_count()).sum::<usize>() * size_of::<Coord<f64>>() + geoms.capacity() * 24,
+            Geometries::F32(geoms) =>
+                geoms.iter().map(|mp| mp.coords_count()).sum::<usize>() * size_of::<Coord<f32>>() + geoms.capacity() * 24,
+        }
+    }
+}
+
+#[inline]
+fn narrow(mp: &MultiPolygon<f64>) -> MultiPolygon<f32> {
+    mp.map_coords(|c| Coord { x: c.x as f32, y: c.y as f32 })
+}
+
+#[inline]
+fn widen(mp: &MultiPolygon<f32>) -> MultiPolygon<f64> {
+    mp.map_coords(|c| Coord { x: c.x as f64, y: c.y as f64 })
+}
+
+impl Region {
+    /// Returns the precision unit geometries are stored at.
+    #[inline]
+    pub fn geometry_precision(&self) -> GeometryPrecision { self.geometries.precision() }
+
+    /// Convert stored unit geometries to `precision`.
+    ///
+    /// Only [`Region::geometry`] and the queries built on it (point lookup,
+    /// convex hulls) see the change; topology and cached metrics stay `f64`.
+    pub fn set_geometry_precision(&mut self, precision: GeometryPrecision) {
+        let geometries = std::mem::replace(&mut self.geometries, Geometries::F64(Vec::new()));
+        self.geometries = geometries.with_precision(precision);
+    }
+
+    /// Returns `false` if unit geometries and the DCEL were dropped (see [`Region::drop_geometry`]).
+    #[inline]
+    pub fn has_geometry(&self) -> bool { self.num_units() == 0 || self.dcel.num_half_edges() > 0 }
+
+    /// Drop unit geometries and the DCEL, keeping adjacency (with shared-boundary
+    /// weights), the spatial index, and every cached per-unit metric.
+    ///
+    /// Afterwards [`Region::geometry`] returns empty shapes and boundary walks
+    /// (union, boundary, contiguity via shared edges) find nothing, so callers
+    /// should check [`Region::has_geometry`] before asking for shapes.
+    pub fn drop_geometry(&mut self) {
+        let num_units = self.num_units();
+        self.geometries = Geometries::F64(vec![MultiPolygon(Vec::new()); num_units]);
+        self.dcel = crate::dcel::Dcel::new();
+        self.face_to_unit = vec![UnitId::EXTERIOR];
+        self.edge_length = Vec::new();
+        self.unit_to_faces_offsets = vec![0; num_units + 1];
+        self.unit_to_faces_data = Vec::new();
+        self.face_inner_cycles = Default::default();
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::region::test_helpers::make_two_unit_region;
+    use crate::unit::UnitId;
+
+    use super::GeometryPrecision;
+
+    #[test]
+    fn f32_halves_geometry_bytes_and_keeps_metrics() {
+        let mut r = make_two_unit_region();
+        let bytes = r.geometries.heap_bytes();
+        let before = r.geometry(UnitId(1)).into_owned();
+
+        r.set_geometry_precision(GeometryPrecision::F32);
+        assert_eq!(r.geometry_precision(), GeometryPrecision::F32);
+        assert_eq!(r.geometries.heap_bytes() - 2 * 24, (bytes - 2 * 24) / 2);
+        assert_eq!(*r.geometry(UnitId(1)), before);
+        assert_eq!(r.area(UnitId(1)), 20.0);
+        assert_eq!(r.unit_at(geo::Coord { x: 1.5, y: 0.5 }), Some(UnitId(1)));
+    }
+
+    #[test]
+    fn drop_geometry_keeps_graph_and_metrics() {
+        let mut r = make_two_unit_region();
+        let before = r.clone();
+        assert!(r.has_geometry());
+
+        r.drop_geometry();
+        assert!(!r.has_geometry());
+        assert_eq!(r.num_units(), 2);
+        assert!(r.geometry(UnitId(0)).0.is_empty());
+        assert_eq!(r.neighbors(UnitId(0)), before.neighbors(UnitId(0)));
+        assert_eq!(r.adjacency().weights_of(UnitId(0)), before.adjacency().weights_of(UnitId(0)));
+        assert_eq!(r.area(UnitId(1)), before.area(UnitId(1)));
+        assert_eq!(r.exterior_boundary_length(UnitId(1)), before.exterior_boundary_length(UnitId(1)));
+        assert_eq!(r.is_exterior(UnitId(0)), before.is_exterior(UnitId(0)));
+        assert!(r.union_of(r.unit_ids()).0.is_empty());
+    }
+}
@@ -0,0 +1,188 @@
+use ahash::AHashSet;
+use geo::MultiPolygon;
+
+use crate::dcel::HalfEdgeId;
+use crate::unit::UnitId;
+
+use super::boundary::cycles_to_multipolygon;
+use super::{Region, Ring};
+
+/// Boundary half-edges of each label (e.g. district) in a labelling of a [`Region`]'s units,
+/// kept up to date one relabelled unit at a time.
+///
+/// Build with [`Region::label_outlines`], update with [`Region::relabel_unit`], and trace a
+/// label's dissolved outline with [`Region::label_outline`]. Tracing only walks the label's
+/// own boundary, so redrawing after an edit never re-dissolves the units inside it.
+#[derive(Clone, Debug)]
+pub struct LabelOutlines {
+    /// Half-edges of each label whose twin lies outside it (another label or the exterior).
+    edges: Vec<AHashSet<HalfEdgeId>>,
+}
+
+impl LabelOutlines {
+    /// Returns the number of labels tracked.
+    #[inline]
+    pub fn num_labels(&self) -> usize { self.edges.len() }
+
+    /// Returns the number of boundary half-edges of `label`.
+    #[inline]
+    pub fn boundary_len(&self, label: u32) -> usize { self.edges[label as usize].len() }
+
+    /// Approximate heap memory of the boundary sets, in bytes.
+    pub fn heap_bytes(&self) -> usize {
+        self.edges.capacity() * std::mem::size_of::<AHashSet<HalfEdgeId>>()
+            + self.edges.iter().map(|set| set.capacity() * (std::mem::size_of::<HalfEdgeId>() + 1)).sum::<usize>()
+    }
+}
+
+impl Region {
+    /// Collect the boundary half-edges of every label in `0..num_labels`.
+    ///
+    /// <div class="warning">Panics if <code>label</code> returns a value outside <code>0..num_labels</code>.</div>
+    pub fn label_outlines(&self, num_labels: usize, label: impl Fn(UnitId) -> u32) -> LabelOutlines {
+        let mut edges = vec![AHashSet::new(); num_labels];
+        for unit in self.unit_ids() {
+            let own = label(unit);
+            for he in self.unit_half_edges(unit) {
+                let twin_unit = self.twin_unit(he);
+                if twin_unit == UnitId::EXTERIOR || label(twin_unit) != own {
+                    edges[own as usize].insert(he);
+                }
+            }
+        }
+        LabelOutlines { edges }
+    }
+
+    /// Update `outlines` after `unit` moved from label `from` to its current label.
+    ///
+    /// Only the half-edges of `unit` and their twins are touched. `label` must already return
+    /// the new labels; when several units move at once, relabel each of them (in any order)
+    /// after all labels have been updated.
+    pub fn relabel_unit(&self, outlines: &mut LabelOutlines, unit: UnitId, from: u32, label: impl Fn(UnitId) -> u32) {
+        let to = label(unit);
+        for he in self.unit_half_edges(unit) {
+            outlines.edges[from as usize].remove(&he);
+            let twin_unit = self.twin_unit(he);
+            if twin_unit == UnitId::EXTERIOR {
+                outlines.edges[to as usize].insert(he);
+                continue;
+            }
+
+            let neighbor = label(twin_unit);
+            if neighbor == to {
+                outlines.edges[to as usize].remove(&he);
+                outlines.edges[to as usize].remove(&he.twin());
+            } else {
+                outlines.edges[to as usize].insert(he);
+                outlines.edges[neighbor as usize].insert(he.twin());
+            }
+        }
+    }
+
+    /// Dissolved outline of all units with `label`, traced from its boundary half-edges.
+    ///
+    /// Equivalent to [`Region::union_of`] over the label's units.
+    pub fn label_outline(&self, outlines: &LabelOutlines, label: u32) -> MultiPolygon<f64> {
+        let boundary = &outlines.edges[label as usize];
+        let mut starts = boundary.iter().copied().collect::<Vec<_>>();
+        starts.sort_unstable(); // deterministic ring order
+
+        let mut visited: AHashSet<HalfEdgeId> = AHashSet::with_capacity(boundary.len());
+        let mut cycles: Vec<(Ring, f64)> = Vec::new();
+        for start in starts {
+            if visited.contains(&start) { continue; }
+
+            let mut coords = Vec::new();
+            let mut signed_area = 0.0;
+            let mut cur = start;
+            loop {
+                visited.insert(cur);
+                let c0 = self.dcel.vertex(self.dcel.half_edge(cur).origin).coords;
+                coords.push(c0);
+                let c1 = self.dcel.vertex(self.dcel.dest(cur)).coords;
+                signed_area += c0.x * c1.y - c1.x * c0.y;
+
+                let mut next = self.dcel.half_edge(cur).next;
+                while !boundary.contains(&next) {
+                    next = self.dcel.half_edge(next.twin()).next;
+                }
+                cur = next;
+
+                if cur == start { break; }
+            }
+            signed_area /= 2.0;
+            if let Some(&first) = coords.first() { coords.push(first); }
+            cycles.push((coords, signed_area));
+        }
+
+        cycles_to_multipolygon(cycles)
+    }
+
+    // -----------------------------------------------------------------------
+    // Private helpers
+    // -----------------------------------------------------------------------
+
+    /// All half-edges bounding the faces of `unit`, including inner (hole) cycles.
+    fn unit_half_edges(&self, unit: UnitId) -> impl Iterator<Item = HalfEdgeId> + '_ {
+        self.unit_faces(unit).iter().flat_map(move |&face_id| {
+            let start = self.dcel.face(face_id).half_edge;
+            start.into_iter()
+                .chain(self.face_inner_cycle_starts(face_id).iter().copied())
+                .flat_map(move |start| self.dcel.face_cycle(start))
+        })
+    }
+
+    /// The unit on the other side of `he`.
+    #[inline]
+    fn twin_unit(&self, he: HalfEdgeId) -> UnitId {
+        self.face_to_unit[self.dcel.half_edge(he.twin()).face.0 as usize]
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::unit::UnitId;
+    use crate::region::test_helpers::make_two_unit_region;
+
+    #[test]
+    fn label_outlines_split_units_have_four_edges_each() {
+        let r = make_two_unit_region();
+        let outlines = r.label_outlines(3, |u| u.0 + 1);
+        assert_eq!(outlines.boundary_len(0), 0);
+        assert_eq!(outlines.boundary_len(1), 4);
+        assert_eq!(outlines.boundary_len(2), 4);
+    }
+
+    #[test]
+    fn relabel_unit_merges_outlines() {
+        let r = make_two_unit_region();
+        let mut outlines = r.label_outlines(3, |u| u.0 + 1);
+        r.relabel_unit(&mut outlines, UnitId(1), 2, |_| 1);
+        assert_eq!(outlines.boundary_len(1), 6);
+        assert_eq!(outlines.boundary_len(2), 0);
+        assert_eq!(r.label_outline(&outlines, 1), r.union_of(r.unit_ids()));
+    }
+
+    #[test]
+    fn relabel_unit_matches_fresh_outlines() {
+        let r = make_two_unit_region();
+        let mut outlines = r.label_outlines(3, |_| 1);
+        r.relabel_unit(&mut outlines, UnitId(0), 1, |u| 2 - u.0);
+        let fresh = r.label_outlines(3, |u| 2 - u.0);
+        for label in 0..3 {
+            assert_eq!(outlines.boundary_len(label), fresh.boundary_len(label));
+            assert_eq!(r.label_outline(&outlines, label), r.label_outline(&fresh, label));
+        }
+    }
+
+    #[test]
+    fn label_outline_of_empty_label_is_empty() {
+        let r = make_two_unit_region();
+        let outlines = r.label_outlines(3, |_| 1);
+        assert!(r.label_outline(&outlines, 2).0.is_empty());
+    }
+}
@@ -0,0 +1,93 @@
+use crate::rtree::{SpatialIndex, hilbert_order};
+use crate::unit::UnitId;
+
+use super::Region;
+use super::build::compute_unit_to_faces;
+
+impl Region {
+    /// Returns all units sorted along a Hilbert curve through their bounding-box centers.
+    ///
+    /// Passing the result to [`Region::permuted`] places units that are close on the
+    /// map close together in memory, which improves cache locality of neighbor scans.
+    pub fn hilbert_order(&self) -> Vec<UnitId> {
+        hilbert_order(&self.bounds.iter().map(|b| b.center()).collect::<Vec<_>>())
+            .into_iter()
+            .map(UnitId)
+            .collect()
+    }
+
+    /// Returns a copy of the region with units renumbered so that new unit `i` is
+    /// old unit `order[i]`.  The DCEL itself is unchanged; only unit ids move.
+    ///
+    /// <div class="warning">Panics if <code>order</code> is not a permutation of the unit ids.</div>
+    pub fn permuted(&self, order: &[UnitId]) -> Region {
+        let num_units = self.num_units();
+        assert_eq!(order.len(), num_units, "order must list every unit exactly once");
+
+        let mut new_id = vec![UnitId::EXTERIOR; num_units];
+        for (new, &old) in order.iter().enumerate() {
+            assert!(new_id[old.0 as usize] == UnitId::EXTERIOR, "unit {old} appears twice in order");
+            new_id[old.0 as usize] = UnitId(new as u32);
+        }
+
+        let take = |i: usize| order[i].0 as usize;
+        let face_to_unit = self.face_to_unit.iter()
+            .map(|&unit| if unit == UnitId::EXTERIOR { unit } else { new_id[unit.0 as usize] })
+            .collect::<Vec<_>>();
+        let bounds = (0..num_units).map(|i| self.bounds[take(i)]).collect::<Vec<_>>();
+        let (unit_to_faces_offsets, unit_to_faces_data) = compute_unit_to_faces(&face_to_unit, num_units);
+
+        Region {
+            dcel: self.dcel.clone(),
+            geometries: self.geometries.permuted(order),
+            area: (0..num_units).map(|i| self.area[take(i)]).collect(),
+            perimeter: (0..num_units).map(|i| self.perimeter[take(i)]).collect(),
+            exterior_boundary_length: (0..num_units).map(|i| self.exterior_boundary_length[take(i)]).collect(),
+            centroid: (0..num_units).map(|i| self.centroid[take(i)]).collect(),
+            bounds_all: self.bounds_all,
+            is_exterior: (0..num_units).map(|i| self.is_exterior[take(i)]).collect(),
+            edge_length: self.edge_length.clone(),
+            adjacent: self.adjacent.permuted(&new_id),
+            touching: self.touching.permuted(&new_id),
+            rtree: SpatialIndex::new(&bounds),
+            bounds,
+            face_to_unit,
+            unit_to_faces_offsets,
+            unit_to_faces_data,
+            face_inner_cycles: self.face_inner_cycles.clone(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use geo::Coord;
+
+    use crate::region::test_helpers::make_two_unit_region;
+    use crate::unit::UnitId;
+
+    #[test]
+    fn permuted_swaps_units_consistently() {
+        let r  = make_two_unit_region();
+        let r2 = r.permuted(&[UnitId(1), UnitId(0)]);
+
+        assert_eq!(r2.geometry(UnitId(0)), r.geometry(UnitId(1)));
+        assert_eq!(r2.neighbors(UnitId(0)), &[UnitId(1)]);
+        assert_eq!(r2.shared_boundary_length(UnitId(0), UnitId(1)), r.shared_boundary_length(UnitId(1), UnitId(0)));
+        assert_eq!(r2.unit_at(Coord { x: 1.5, y: 0.5 }), Some(UnitId(0)));
+        assert_eq!(r2.boundary_of([UnitId(1)]), r.boundary_of([UnitId(0)]));
+        assert!(r2.validate().is_ok());
+    }
+
+    #[test]
+    fn hilbert_order_is_a_permutation() {
+        let r = make_two_unit_region();
+        let mut order = r.hilbert_order();
+        order.sort();
+        assert_eq!(order, vec![UnitId(0), UnitId(1)]);
+    }
+}
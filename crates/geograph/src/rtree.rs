@@ -1,65 +1,198 @@
-use geo::Rect;
-use rstar::{RTree, RTreeObject, AABB};
+use geo::{Coord, Rect};
 
 use crate::unit::UnitId;
 
-// ---------------------------------------------------------------------------
-// BoundingBox (R-tree element)
-// ---------------------------------------------------------------------------
-
-#[derive(Debug, Clone)]
-struct UnitBBox {
-    unit: UnitId,
-    bbox: Rect<f64>,
-}
-
-impl RTreeObject for UnitBBox {
-    type Envelope = AABB<[f64; 2]>;
-
-    fn envelope(&self) -> Self::Envelope {
-        AABB::from_corners(self.bbox.min().into(), self.bbox.max().into())
-    }
-}
-
 // ---------------------------------------------------------------------------
 // SpatialIndex
 // ---------------------------------------------------------------------------
 
-/// R-tree spatial index over unit bounding boxes.
+/// Number of children per node of the packed tree.
+pub(crate) const NODE_SIZE: usize = 16;
+
+/// Packed Hilbert R-tree over unit bounding boxes.
 ///
-/// Supports fast point-location and envelope queries.  Built once during
-/// `Region::new()` / `io::read()` and never mutated.
+/// Units are sorted along a Hilbert curve through their bounding-box centers,
+/// then grouped `NODE_SIZE` at a time into parent nodes, level by level, up to
+/// a single root.  The whole tree is two flat arrays, so it can be written to
+/// and read from disk as-is (see [`crate::io`]) instead of being rebuilt on
+/// load.  Built once during `Region::new()` and never mutated.
 #[derive(Debug, Clone)]
 pub(crate) struct SpatialIndex {
-    tree: RTree<UnitBBox>,
+    /// Node boxes, leaves first (one per unit, in Hilbert order), root last.
+    pub(crate) boxes: Vec<Rect<f64>>,
+    /// For a leaf, its unit; for an internal node, the position of its first child in `boxes`.
+    pub(crate) indices: Vec<u32>,
+    /// End position in `boxes` of each level, leaves first.
+    level_bounds: Vec<usize>,
 }
 
 impl SpatialIndex {
     /// Build a spatial index from per-unit bounding boxes.
     pub(crate) fn new(bounds: &[Rect<f64>]) -> Self {
-        let entries: Vec<UnitBBox> = bounds
-            .iter()
-            .enumerate()
-            .map(|(i, &bbox)| UnitBBox {
-                unit: UnitId(i as u32),
-                bbox,
-            })
-            .collect();
-        Self { tree: RTree::bulk_load(entries) }
+        let order = hilbert_order(&bounds.iter().map(|b| b.center()).collect::<Vec<_>>());
+        let mut boxes = order.iter().map(|&i| bounds[i as usize]).collect::<Vec<_>>();
+        let mut indices = order;
+        let mut level_bounds = vec![boxes.len()];
+
+        // Group each level NODE_SIZE at a time until a single root remains.
+        let mut start = 0;
+        loop {
+            let end = boxes.len();
+            for first in (start..end).step_by(NODE_SIZE) {
+                let last = (first + NODE_SIZE).min(end);
+                let node = boxes[first..last].iter().copied().reduce(union).unwrap();
+                boxes.push(node);
+                indices.push(first as u32);
+            }
+            level_bounds.push(boxes.len());
+            if boxes.len() - end <= 1 { break }
+            start = end;
+        }
+
+        Self { boxes, indices, level_bounds }
+    }
+
+    /// Rebuild an index from its stored `boxes` and `indices`, for `num_units` leaves.
+    /// Returns `None` if the arrays do not describe a packed tree of that size.
+    pub(crate) fn from_parts(num_units: usize, boxes: Vec<Rect<f64>>, indices: Vec<u32>) -> Option<Self> {
+        let mut level_bounds = vec![num_units];
+        let mut n = num_units;
+        loop {
+            n = n.div_ceil(NODE_SIZE).max(1);
+            level_bounds.push(level_bounds.last().unwrap() + n);
+            if n == 1 { break }
+        }
+
+        let valid = boxes.len() == indices.len()
+            && boxes.len() == *level_bounds.last().unwrap()
+            && indices.iter().enumerate().all(|(pos, &i)| if pos < num_units {
+                (i as usize) < num_units
+            } else {
+                (i as usize) < pos
+            });
+        valid.then_some(Self { boxes, indices, level_bounds })
     }
 
     /// Return all `UnitId`s whose bounding box intersects `envelope`.
-    #[inline]
-    pub(crate) fn query(&self, envelope: AABB<[f64; 2]>) -> impl Iterator<Item = UnitId> + '_ {
-        self.tree
-            .locate_in_envelope_intersecting(&envelope)
-            .map(|e| e.unit)
+    pub(crate) fn query(&self, envelope: Rect<f64>) -> Vec<UnitId> {
+        let num_units = self.level_bounds[0];
+        let mut results = Vec::new();
+        if num_units == 0 { return results }
+
+        let mut stack = vec![self.boxes.len() - 1];
+        while let Some(node) = stack.pop() {
+            // Children of a node are contiguous, and never cross the end of their level.
+            let level_end = self.level_bounds.iter().copied().find(|&end| end > node).unwrap();
+            let end = (node + NODE_SIZE).min(level_end);
+            for pos in node..end {
+                if !intersects(&self.boxes[pos], &envelope) { continue }
+                let index = self.indices[pos];
+                if node < num_units { results.push(UnitId(index)) } else { stack.push(index as usize) }
+            }
+        }
+        results
     }
 
     /// Return all `UnitId`s whose bounding box contains `point`.
     #[inline]
-    pub(crate) fn query_point(&self, point: [f64; 2]) -> impl Iterator<Item = UnitId> + '_ {
-        let envelope = AABB::from_point(point);
-        self.query(envelope)
+    pub(crate) fn query_point(&self, point: Coord<f64>) -> Vec<UnitId> {
+        self.query(Rect::new(point, point))
+    }
+}
+
+/// Indices of `points` sorted along a Hilbert curve through their bounding box.
+pub(crate) fn hilbert_order(points: &[Coord<f64>]) -> Vec<u32> {
+    let (mut min, mut max) = (Coord { x: f64::INFINITY, y: f64::INFINITY }, Coord { x: f64::NEG_INFINITY, y: f64::NEG_INFINITY });
+    for p in points.iter().filter(|p| p.x.is_finite() && p.y.is_finite()) {
+        min = Coord { x: min.x.min(p.x), y: min.y.min(p.y) };
+        max = Coord { x: max.x.max(p.x), y: max.y.max(p.y) };
+    }
+    let scale = |v: f64, min: f64, max: f64| if max > min { ((v - min) / (max - min) * 65535.0) as u32 } else { 0 };
+
+    let keys = points.iter()
+        .map(|p| hilbert(scale(p.x, min.x, max.x), scale(p.y, min.y, max.y)))
+        .collect::<Vec<_>>();
+    let mut order = (0..points.len() as u32).collect::<Vec<_>>();
+    order.sort_by_key(|&i| keys[i as usize]);
+    order
+}
+
+/// Smallest rectangle containing both `a` and `b`.
+#[inline]
+fn union(a: Rect<f64>, b: Rect<f64>) -> Rect<f64> {
+    Rect::new(
+        Coord { x: a.min().x.min(b.min().x), y: a.min().y.min(b.min().y) },
+        Coord { x: a.max().x.max(b.max().x), y: a.max().y.max(b.max().y) },
+    )
+}
+
+/// Whether two rectangles overlap (touching edges count).
+#[inline]
+fn intersects(a: &Rect<f64>, b: &Rect<f64>) -> bool {
+    a.min().x <= b.max().x && b.min().x <= a.max().x && a.min().y <= b.max().y && b.min().y <= a.max().y
+}
+
+/// Position of `(x, y)` along a Hilbert curve filling a 2^16 × 2^16 grid.
+fn hilbert(mut x: u32, mut y: u32) -> u64 {
+    const N: u32 = 1 << 16;
+    let mut d = 0u64;
+    let mut s = N / 2;
+    while s > 0 {
+        let rx = (x & s > 0) as u32;
+        let ry = (y & s > 0) as u32;
+        d += (s as u64) * (s as u64) * ((3 * rx) ^ ry) as u64;
+        if ry == 0 {
+            if rx == 1 {
+                x = N - 1 - x;
+                y = N - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    d
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(n: usize) -> Vec<Rect<f64>> {
+        (0..n * n)
+            .map(|i| {
+                let (x, y) = ((i % n) as f64, (i / n) as f64);
+                Rect::new(Coord { x, y }, Coord { x: x + 1.0, y: y + 1.0 })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn query_matches_brute_force() {
+        let bounds = grid(20);
+        let index = SpatialIndex::new(&bounds);
+        let envelope = Rect::new(Coord { x: 3.5, y: 7.2 }, Coord { x: 9.1, y: 8.0 });
+
+        let mut found = index.query(envelope);
+        found.sort();
+        let expected = (0..bounds.len() as u32)
+            .filter(|&i| intersects(&bounds[i as usize], &envelope))
+            .map(UnitId)
+            .collect::<Vec<_>>();
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn from_parts_round_trips() {
+        for n in [1, 4, 17] {
+            let index = SpatialIndex::new(&grid(n));
+            let rebuilt = SpatialIndex::from_parts(n * n, index.boxes.clone(), index.indices.clone()).unwrap();
+            assert_eq!(rebuilt.level_bounds, index.level_bounds);
+            assert_eq!(rebuilt.query_point(Coord { x: 0.5, y: 0.5 }), vec![UnitId(0)]);
+        }
+        assert!(SpatialIndex::from_parts(3, vec![], vec![]).is_none());
     }
 }
@@ -228,6 +228,39 @@ fn compactness_of_circle_approximation_near_one() {
     );
 }
 
+#[test]
+fn label_boundaries_chains_shared_edges() {
+    let r = four_squares();
+    let labels = [1, 1, 2, 2];
+    let lines = r.label_boundaries(r.unit_ids(), |u| labels[u.0 as usize]);
+    assert_eq!(lines.len(), 1, "only districts 1 and 2 meet");
+    assert_eq!(lines[0].0, (1, 2));
+    assert_eq!(lines[0].1.0.len(), 1, "the shared edges form one run");
+    let xs: Vec<f64> = lines[0].1.0[0].0.iter().map(|c| c.x).collect();
+    assert_eq!(lines[0].1.0[0].0.iter().map(|c| c.y).collect::<Vec<_>>(), vec![1.0; 3]);
+    assert!(xs == [0.0, 1.0, 2.0] || xs == [2.0, 1.0, 0.0], "run should span the grid, got {xs:?}");
+}
+
+#[test]
+fn label_boundaries_from_one_side_matches_both_sides() {
+    let r = four_squares();
+    let labels = [1, 2, 2, 1];
+    let all = r.label_boundaries(r.unit_ids(), |u| labels[u.0 as usize]);
+    let one_side = r.label_boundaries([UnitId(0), UnitId(3)], |u| labels[u.0 as usize]);
+    let edges = |lines: &[((u32, u32), geo::MultiLineString<f64>)]| -> usize {
+        lines.iter().flat_map(|(_, mls)| &mls.0).map(|line| line.0.len() - 1).sum()
+    };
+    assert_eq!(all.len(), 1);
+    assert_eq!(edges(&all), 4, "checkerboard has four interior edges");
+    assert_eq!(edges(&one_side), 4);
+}
+
+#[test]
+fn label_boundaries_skips_exterior_and_equal_labels() {
+    let r = two_squares();
+    assert!(r.label_boundaries(r.unit_ids(), |_| 7).is_empty());
+}
+
 #[test]
 fn union_of_single_unit_has_one_polygon() {
     let r = two_squares();
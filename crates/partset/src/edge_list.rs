@@ -1,6 +1,17 @@
-use std::collections::HashSet;
+use alloc::{collections::BTreeSet, vec, vec::Vec};
 
-use crate::graph::UnitGraph;
+/// Adjacency of a planar graph, with each node's neighbors listed in counter-clockwise order.
+/// Directed edges are numbered `0..2 * num_edges`, as in [`FrontierEdgeList`].
+pub trait PlanarAdjacency {
+    /// Number of neighbors of `node`.
+    fn degree(&self, node: usize) -> usize;
+
+    /// The `i`-th neighbor of `node`, in CCW order.
+    fn neighbor(&self, node: usize, i: usize) -> Option<usize>;
+
+    /// The (source, target) pair of a directed edge.
+    fn edge_endpoints(&self, edge: usize) -> Option<(usize, usize)>;
+}
 
 /// A per-part bucketed set for directed half-edges, with O(1) insert/remove/find.
 ///
@@ -15,14 +26,14 @@ use crate::graph::UnitGraph;
 ///
 /// Removal is swap-remove, so per-part order is not preserved.
 #[derive(Debug, Clone)]
-pub(crate) struct FrontierEdgeList {
+pub struct FrontierEdgeList {
     parts: Vec<Vec<usize>>,
     loc: Vec<Option<(usize, usize)>>, // loc[he] = Some((part, pos))
 }
 
 impl FrontierEdgeList {
     /// Create an empty frontier list with `num_parts` parts and `num_edges` edges in the graph.
-    pub(crate) fn new(num_parts: usize, num_edges: usize) -> Self {
+    pub fn new(num_parts: usize, num_edges: usize) -> Self {
         // Heuristic pre-allocation; same idea as your MultiSet.
         let cap = (num_edges * 2 / num_parts.max(1)).isqrt().saturating_add(1);
         Self {
@@ -31,9 +42,16 @@ impl FrontierEdgeList {
         }
     }
 
-    #[inline] pub(crate) fn num_parts(&self) -> usize { self.parts.len() }
+    #[inline] pub fn num_parts(&self) -> usize { self.parts.len() }
+
+    /// Heap memory held by the per-part lists and the locations, in bytes.
+    pub fn heap_bytes(&self) -> usize {
+        self.parts.iter().map(|part| part.capacity() * size_of::<usize>()).sum::<usize>()
+            + self.parts.capacity() * size_of::<Vec<usize>>()
+            + self.loc.capacity() * size_of::<Option<(usize, usize)>>()
+    }
 
-    #[inline] pub(crate) fn num_directed_edges(&self) -> usize { self.loc.len() }
+    #[inline] pub fn num_directed_edges(&self) -> usize { self.loc.len() }
 
     #[inline] fn check_part(&self, part: usize) { debug_assert!(part < self.parts.len(), "part id out of range") }
 
@@ -41,41 +59,41 @@ impl FrontierEdgeList {
 
     /// Returns the part that `edge` is currently assigned to, or `None` if absent.
     #[inline]
-    pub(crate) fn find(&self, edge: usize) -> Option<usize> {
+    pub fn find(&self, edge: usize) -> Option<usize> {
         self.check_directed_edge(edge);
         self.loc[edge].map(|(part, _)| part)
     }
 
     /// Returns true if `edge` is in any part.
-    #[inline] pub(crate) fn contains(&self, edge: usize) -> bool { self.find(edge).is_some() }
+    #[inline] pub fn contains(&self, edge: usize) -> bool { self.find(edge).is_some() }
 
     /// Read-only view of half-edges in `part`.
     #[inline]
-    pub(crate) fn get(&self, part: usize) -> &[usize] {
+    pub fn get(&self, part: usize) -> &[usize] {
         self.check_part(part);
         &self.parts[part]
     }
 
     /// Returns true if `part` currently has no frontier half-edges.
     #[inline]
-    pub(crate) fn is_empty_part(&self, part: usize) -> bool {
+    pub fn is_empty_part(&self, part: usize) -> bool {
         self.get(part).is_empty()
     }
 
     /// Iterator over each part as a slice.
     #[inline]
-    pub(crate) fn iter(&self) -> impl Iterator<Item = &[usize]> + '_ {
+    pub fn iter(&self) -> impl Iterator<Item = &[usize]> + '_ {
         self.parts.iter().map(|v| v.as_slice())
     }
 
     /// Iterator over all half-edges present (across all parts).
     #[inline]
-    pub(crate) fn iter_all(&self) -> impl Iterator<Item = usize> + '_ {
+    pub fn iter_all(&self) -> impl Iterator<Item = usize> + '_ {
         self.parts.iter().flat_map(|v| v.iter().copied())
     }
 
     /// Remove all half-edges from all parts (O(total_size)).
-    pub(crate) fn clear(&mut self) {
+    pub fn clear(&mut self) {
         for part in &mut self.parts { part.clear() }
         self.loc.fill(None);
     }
@@ -84,7 +102,7 @@ impl FrontierEdgeList {
     /// Half-edges not mentioned end up absent.
     ///
     /// Panics in debug if ids are out of range or a half-edge appears more than once.
-    pub(crate) fn rebuild_from<I>(&mut self, iter: I)
+    pub fn rebuild_from<I>(&mut self, iter: I)
     where I: IntoIterator<Item = (usize, usize)>,
     {
         self.clear();
@@ -97,7 +115,7 @@ impl FrontierEdgeList {
     }
 
     /// Insert `edge` into `part`. If `edge` is already present in a different part, it is moved.
-    pub(crate) fn insert(&mut self, edge: usize, part: usize) {
+    pub fn insert(&mut self, edge: usize, part: usize) {
         self.check_directed_edge(edge);
         self.check_part(part);
 
@@ -112,7 +130,7 @@ impl FrontierEdgeList {
     }
 
     /// Remove `edge` from whichever part it is in (no-op if absent).
-    pub(crate) fn remove(&mut self, edge: usize) {
+    pub fn remove(&mut self, edge: usize) {
         self.check_directed_edge(edge);
         if let Some((part, pos)) = self.loc[edge] {
             let bucket = &mut self.parts[part];
@@ -145,12 +163,12 @@ impl FrontierEdgeList {
     /// to the next neighbor. If that neighbor is outside the part, it is the
     /// next frontier edge. If inside, transition to that neighbor and repeat.
     ///
-    /// Requires that the graph's adjacency lists were sorted in CCW angular
-    /// order during pack construction (see `sort_adjacencies_ccw`).
-    pub(crate) fn walk_boundary(
+    /// Requires that the graph's adjacency lists are sorted in CCW angular
+    /// order (see [`PlanarAdjacency`]).
+    pub fn walk_boundary(
         &self,
         part: usize,
-        graph: &UnitGraph,
+        graph: &impl PlanarAdjacency,
         assignments: &[usize],
     ) -> Vec<Vec<(usize, usize)>> {
         let edge_indices = self.get(part);
@@ -159,7 +177,7 @@ impl FrontierEdgeList {
         }
 
         // Collect all frontier edges as (source, target) for this part.
-        // Build a set for O(1) visited checks.
+        // Build a set for fast visited checks.
         let mut all_frontier: Vec<(usize, usize)> = Vec::with_capacity(edge_indices.len());
         for &edge_idx in edge_indices {
             if let Some(pair) = graph.edge_endpoints(edge_idx) {
@@ -167,8 +185,8 @@ impl FrontierEdgeList {
             }
         }
 
-        let frontier_set: HashSet<(usize, usize)> = all_frontier.iter().copied().collect();
-        let mut visited: HashSet<(usize, usize)> = HashSet::with_capacity(all_frontier.len());
+        let frontier_set: BTreeSet<(usize, usize)> = all_frontier.iter().copied().collect();
+        let mut visited: BTreeSet<(usize, usize)> = BTreeSet::new();
         let mut cycles: Vec<Vec<(usize, usize)>> = Vec::new();
 
         for &start in &all_frontier {
@@ -226,7 +244,7 @@ impl FrontierEdgeList {
         u: usize,
         v: usize,
         part: usize,
-        graph: &UnitGraph,
+        graph: &impl PlanarAdjacency,
         assignments: &[usize],
     ) -> (usize, usize) {
         let mut cur = u;
@@ -237,17 +255,11 @@ impl FrontierEdgeList {
             debug_assert!(deg > 0);
 
             // Find prev's position in cur's adjacency list.
-            let mut pos = 0;
-            for (i, nbr) in graph.edges(cur).enumerate() {
-                if nbr == prev {
-                    pos = i;
-                    break;
-                }
-            }
+            let pos = (0..deg).find(|&i| graph.neighbor(cur, i) == Some(prev)).unwrap_or(0);
 
             // Advance one step CCW (next position, wrapping).
             let next_pos = (pos + 1) % deg;
-            let w = graph.edge(cur, next_pos).unwrap();
+            let w = graph.neighbor(cur, next_pos).unwrap();
 
             if w == cur || assignments[w] != part {
                 // w is outside the part (or a self-edge exterior sentinel)
@@ -0,0 +1,11 @@
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+mod edge_list;
+mod multi_set;
+mod partition_set;
+
+pub use edge_list::{FrontierEdgeList, PlanarAdjacency};
+pub use multi_set::MultiSet;
+pub use partition_set::PartitionSet;
@@ -1,15 +1,17 @@
+use alloc::{vec, vec::Vec};
+
 /// PartitionSet maintains a total assignment of elements to sets, with O(1) move/contains
 #[derive(Debug, Clone)]
-pub(crate) struct PartitionSet {
+pub struct PartitionSet {
     sets: Vec<Vec<usize>>,  // sets[s] = elements currently in set s
     index: Vec<usize>,      // index[e] = s when e is in sets[s]
     position: Vec<usize>    // position[e] = i when sets[s][i] is e
 }
 
 impl PartitionSet {
-    /// Create a MultiSet with `num_sets` sets and `num_elems` elements,
+    /// Create a PartitionSet with `num_sets` sets and `num_elems` elements,
     /// initially assigning all elements to set 0.
-    pub(crate) fn new(num_sets: usize, num_elems: usize) -> Self {
+    pub fn new(num_sets: usize, num_elems: usize) -> Self {
         assert!(num_sets > 0, "must have at least one set");
         let capacity = (num_elems / num_sets.max(1)).isqrt().saturating_add(1);
         let mut sets = (0..num_sets)
@@ -29,6 +31,13 @@ impl PartitionSet {
     /// Universe size (number of elements addressable by index).
     #[inline] pub fn num_elems(&self) -> usize { self.index.len() }
 
+    /// Heap memory held by the sets, index, and positions, in bytes.
+    pub fn heap_bytes(&self) -> usize {
+        self.sets.iter().map(|set| set.capacity() * size_of::<usize>()).sum::<usize>()
+            + self.sets.capacity() * size_of::<Vec<usize>>()
+            + (self.index.capacity() + self.position.capacity()) * size_of::<usize>()
+    }
+
     /// Return the set that `elem` is currently in.
     #[inline]
     pub fn find(&self, elem: usize) -> usize {
@@ -59,7 +68,7 @@ impl PartitionSet {
     }
 
     /// Remove all elements from all sets, placing them in set 0.
-    pub(crate) fn clear(&mut self) {
+    pub fn clear(&mut self) {
         self.sets.iter_mut().for_each(|v| v.clear());
         self.sets[0] = (0..self.num_elems()).collect();
         self.index = vec![0; self.num_elems()];
@@ -67,7 +76,7 @@ impl PartitionSet {
     }
 
     /// Rebuild partition from a complete slice of assignments.
-    pub(crate) fn rebuild(&mut self, assignments: &[usize]) {
+    pub fn rebuild(&mut self, assignments: &[usize]) {
         assert!(assignments.len() == self.num_elems(), "assignments length mismatch");
 
         self.sets.iter_mut().for_each(|v| v.clear());
@@ -80,7 +89,7 @@ impl PartitionSet {
     }
 
     /// Move `elem` to `set`. Panics in debug if out of range.
-    pub(crate) fn move_to(&mut self, elem: usize, set: usize) {
+    pub fn move_to(&mut self, elem: usize, set: usize) {
         debug_assert!(elem < self.index.len(), "element out of range");
         debug_assert!(set < self.sets.len(), "set out of range");
 
@@ -103,7 +112,7 @@ impl PartitionSet {
 
 #[cfg(test)]
 mod tests {
-    use crate::partition::PartitionSet;
+    use super::*;
 
     #[test]
     fn new_fills_first_set() {
@@ -0,0 +1,124 @@
+//! Interactive prompt for quick analyses of a pack without writing Python: create or load a plan,
+//! assign units, run optimization steps, print metrics, and export.
+//!
+//! Run with `cargo run --release --example repl -- --pack <dir>`, then type `help`.
+
+use std::{io::{BufRead, Write}, path::{Path, PathBuf}, sync::Arc};
+
+use anyhow::{Context, Result, anyhow, bail};
+
+use openmander::{Map, Metric, Objective, Plan};
+
+const HELP: &str = "\
+Commands:
+  new <districts>                      Start an empty plan
+  load <plan.csv>                      Load block assignments into the plan
+  assign <layer> <geo_id> <district>   Assign a unit (e.g. a county or block) to a district
+  random                               Assign every block to a random contiguous district
+  equalize <series> [tolerance]        Balance a series across districts with greedy swaps
+  anneal <steps> <series>              Run annealing steps balancing <series> and compactness
+  recom <steps> <series> [tolerance]   Run ReCom steps keeping <series> within tolerance
+  metrics <series>                     Print district totals, deviation, and compactness
+  undo | redo                          Step back or forward through plan edits
+  save <plan.csv>                      Write the plan's block assignments
+  quit                                 Leave the prompt";
+
+/// Edits kept for `undo`.
+const HISTORY: usize = 100;
+
+struct Session {
+    map: Arc<Map>,
+    plan: Option<Plan>,
+}
+
+impl Session {
+    fn plan(&mut self) -> Result<&mut Plan> {
+        self.plan.as_mut().ok_or_else(|| anyhow!("No plan yet; start one with `new <districts>`"))
+    }
+
+    /// Run one command line. Returns false once the session should end.
+    fn run(&mut self, line: &str) -> Result<bool> {
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        let Some((&command, args)) = words.split_first() else { return Ok(true) };
+        match (command, args) {
+            ("help", _) => println!("{HELP}"),
+            ("quit" | "exit", _) => return Ok(false),
+            ("new", [districts]) => {
+                let mut plan = Plan::new(self.map.clone(), parse(districts, "district count")?)?;
+                plan.enable_history(HISTORY);
+                self.plan = Some(plan);
+            }
+            ("load", [path]) => self.plan()?.read_from_csv(Path::new(path))?,
+            ("assign", [layer, geo_id, district]) => {
+                self.plan()?.assign_unit(layer, geo_id, parse(district, "district")?)?
+            }
+            ("random", []) => self.plan()?.randomize()?,
+            ("equalize", [series, rest @ ..]) => {
+                let tolerance = rest.first().map_or(Ok(0.001), |t| parse(t, "tolerance"))?;
+                self.plan()?.equalize(series, tolerance, 20_000)?;
+            }
+            ("anneal", [steps, series]) => {
+                let objective = Objective::new(vec![
+                    Metric::population_deviation_smooth(series.to_string()),
+                    Metric::compactness_polsby_popper(),
+                ], Some(vec![0.8, 0.2]));
+                let steps = parse(steps, "step count")?;
+                self.plan()?.anneal(&[objective], steps, 1.0, &[0.5], &[Some(0.01)], &[0.001], steps, 1000, 1000)?;
+            }
+            ("recom", [steps, series, rest @ ..]) => {
+                let tolerance = rest.first().map_or(Ok(0.02), |t| parse(t, "tolerance"))?;
+                let changed = self.plan()?.recom(series, tolerance, parse(steps, "step count")?, None)?;
+                println!("{changed} steps changed the plan");
+            }
+            ("metrics", [series]) => self.print_metrics(series)?,
+            ("undo", []) => if !self.plan()?.undo()? { println!("Nothing to undo") },
+            ("redo", []) => if !self.plan()?.redo()? { println!("Nothing to redo") },
+            ("save", [path]) => self.plan()?.write_to_csv(Path::new(path))?,
+            _ => bail!("Unknown command or wrong arguments: {line:?} (type `help`)"),
+        }
+        Ok(true)
+    }
+
+    fn print_metrics(&mut self, series: &str) -> Result<()> {
+        let plan = self.plan()?;
+        let totals = plan.district_totals(series)?;
+        let deviation = Metric::population_deviation(series.to_string());
+        let compactness = Metric::compactness_polsby_popper();
+        let (deviations, scores) = (plan.compute_metric(&deviation), plan.compute_metric(&compactness));
+
+        println!("{:>8} {:>14} {:>10} {:>14}", "district", series, "deviation", "polsby_popper");
+        for (i, total) in totals.iter().enumerate() {
+            println!("{:>8} {:>14.0} {:>10.4} {:>14.4}", i + 1, total, deviations[i], scores[i]);
+        }
+        println!("score: deviation {:.4}, polsby_popper {:.4}", plan.compute_metric_score(&deviation), plan.compute_metric_score(&compactness));
+        Ok(())
+    }
+}
+
+fn parse<T: std::str::FromStr>(word: &str, what: &str) -> Result<T> {
+    word.parse().map_err(|_| anyhow!("Invalid {what}: {word:?}"))
+}
+
+fn main() -> Result<()> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let pack = match args.as_slice() {
+        [flag, pack] if flag == "--pack" => PathBuf::from(pack),
+        _ => bail!("Usage: repl --pack <dir>"),
+    };
+    let map = Map::read_from_pack(&pack).with_context(|| format!("Failed to load pack {}", pack.display()))?;
+    println!("Loaded {} (type `help` for commands)", pack.display());
+
+    let mut session = Session { map: Arc::new(map), plan: None };
+    let mut lines = std::io::stdin().lock().lines();
+    loop {
+        print!("> ");
+        std::io::stdout().flush()?;
+        let Some(line) = lines.next() else { break };
+        match session.run(&line?) {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(error) => eprintln!("error: {error:#}"),
+        }
+    }
+    Ok(())
+}
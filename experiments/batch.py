@@ -0,0 +1,190 @@
+#!/usr/bin/env python3
+"""
+Run a matrix of redistricting jobs described by a TOML file.
+
+Every combination of (state, chamber, algorithm, seed) in the jobs file is one job. The seed
+drives a ReCom perturbation of each balanced start, so replicates start from different plans.
+Jobs run in separate processes, at most `max_workers` at a time. Each job writes its
+plan and log to `<out>/<state>_<chamber>_<algorithm>_<seed>/`. Scores for all jobs
+are collected in `<out>/results.csv`.
+
+Usage:
+    python batch.py jobs.toml [--max-workers N] [--dry-run]
+"""
+import argparse
+import csv
+import itertools
+import json
+import math
+import os
+import time
+import tomllib
+import traceback
+from concurrent.futures import ProcessPoolExecutor, as_completed
+from pathlib import Path
+
+import openmander as om
+from run import redirect_fds_to_file
+
+ALGORITHMS = ("equalize", "anneal")
+
+# Seat-count key of `om.list_states()` for each chamber with a default seat count.
+CHAMBER_SEATS = {"congress": "congress", "state_senate": "sldu", "state_house": "sldl"}
+
+RESULT_FIELDS = [
+    "state", "chamber", "algorithm", "seed", "num_districts", "status",
+    "seconds", "population_deviation", "polsby_popper", "plan", "log", "error",
+]
+
+
+def load_jobs(path: Path) -> tuple[dict, list[dict]]:
+    """Parse a jobs file into its settings and the expanded list of jobs."""
+    with open(path, "rb") as f:
+        cfg = tomllib.load(f)
+
+    root = path.resolve().parent
+    cfg["packs"] = root / cfg.get("packs", "../packs")
+    cfg["out"] = root / cfg.get("out", "artifacts/batch")
+    cfg.setdefault("pop_series", "T_20_CENS_Total")
+    seats = cfg.get("seats", {})
+    apportioned = {info["code"]: info for info in om.list_states()}
+
+    jobs = []
+    for state, chamber, algorithm, seed in itertools.product(
+        cfg["states"], cfg.get("chambers", ["congress"]), cfg.get("algorithms", ["anneal"]), cfg.get("seeds", [1]),
+    ):
+        if algorithm not in ALGORITHMS:
+            raise ValueError(f"Unknown algorithm '{algorithm}' (expected one of {', '.join(ALGORITHMS)})")
+        num_districts = seats.get(chamber, {}).get(state)
+        if num_districts is None and chamber in CHAMBER_SEATS:
+            num_districts = apportioned.get(state, {}).get(CHAMBER_SEATS[chamber])
+        if num_districts is None:
+            raise ValueError(f"No seat count for {state} {chamber} (add it under [seats.{chamber}])")
+        jobs.append({
+            "state": state, "chamber": chamber, "algorithm": algorithm, "seed": seed,
+            "num_districts": num_districts, "name": f"{state}_{chamber}_{algorithm}_{seed}",
+        })
+
+    return cfg, jobs
+
+
+def run_job(job: dict, cfg: dict) -> dict:
+    """Run one job in a worker process, returning its row of the results table."""
+    out_dir = cfg["out"] / job["name"]
+    out_dir.mkdir(parents=True, exist_ok=True)
+    log_path = out_dir / f"{job['name']}.log"
+    plan_path = out_dir / f"{job['name']}.csv"
+    result = {key: job[key] for key in ("state", "chamber", "algorithm", "seed", "num_districts")}
+    result.update(log=str(log_path), status="failed")
+
+    start = time.monotonic()
+    # Redirect file descriptors so the log also captures progress printed by the Rust core.
+    with redirect_fds_to_file(log_path):
+        try:
+            plan = build_plan(job, cfg)
+            plan.to_csv(str(plan_path))
+            result.update(
+                status="ok",
+                plan=str(plan_path),
+                population_deviation=plan.compute_metric_score(om.Metric.population_deviation(cfg["pop_series"])),
+                polsby_popper=plan.compute_metric_score(om.Metric.compactness_polsby_popper()),
+            )
+        except Exception as e:
+            traceback.print_exc()
+            result["error"] = str(e)
+    result["seconds"] = round(time.monotonic() - start, 1)
+    return result
+
+
+def build_plan(job: dict, cfg: dict) -> om.Plan:
+    """
+    Generate a plan for one job. The random start and annealing are not seeded, but the job's seed
+    fixes the ReCom steps applied to the balanced start, so each seed explores a different region.
+    """
+    state, num_districts = job["state"], job["num_districts"]
+    pack_path = cfg["packs"] / f"{state}_2020_pack"
+    if not pack_path.exists():
+        pack_path = om.download_pack(state, str(cfg["packs"]), verbose=1)
+
+    plan = om.Plan(om.Map(str(pack_path)), num_districts)
+    plan.randomize()
+    if num_districts == 1:
+        return plan
+
+    equalize = cfg.get("equalize", {})
+    tolerance = equalize.get("tolerance", 0.001)
+    plan.equalize(cfg["pop_series"], tolerance=tolerance, max_iter=equalize.get("max_iter", 20_000))
+    plan.recom(cfg["pop_series"], tolerance=tolerance, steps=cfg.get("recom_steps", 100), seed=job["seed"])
+
+    if job["algorithm"] == "anneal":
+        anneal = cfg.get("anneal", {})
+        objective = om.Objective(metrics=[
+            om.Metric.population_deviation_smooth(cfg["pop_series"]),
+            om.Metric.compactness_polsby_popper(),
+        ], weights=anneal.get("weights", [0.8, 0.2]))
+        plan.anneal_with_config(om.ChainConfig(
+            objectives=[om.ObjectiveSpec.from_objective(objective)],
+            max_iter=anneal.get("max_iter_per_district", 10_000_000) * num_districts,
+            phase_start_probs=[anneal.get("start_prob", 0.9)],
+            phase_end_probs=[anneal.get("end_prob", 0.05)],
+            phase_cooling_rates=[anneal.get("cooling_rate", 0.0005) / math.sqrt(num_districts)],
+            early_stop_iters=anneal.get("early_stop_iters", 10_000),
+            stop=om.StoppingRules.from_json(json.dumps(anneal.get("stop", {}))),
+        ))
+
+    return plan
+
+
+def write_results(path: Path, results: list[dict]) -> None:
+    with open(path, "w", newline="") as f:
+        writer = csv.DictWriter(f, fieldnames=RESULT_FIELDS)
+        writer.writeheader()
+        writer.writerows(results)
+
+
+def main() -> None:
+    parser = argparse.ArgumentParser(description="Run a matrix of redistricting jobs from a TOML file.")
+    parser.add_argument("jobs", type=Path, help="Path to the jobs file")
+    parser.add_argument("--max-workers", type=int, default=None, help="Maximum parallel jobs (overrides the jobs file)")
+    parser.add_argument("--dry-run", action="store_true", help="List the jobs without running them")
+    args = parser.parse_args()
+
+    cfg, jobs = load_jobs(args.jobs)
+    max_workers = args.max_workers or cfg.get("max_workers") or max(1, (os.cpu_count() or 2) // 2)
+
+    if args.dry_run:
+        for job in jobs:
+            print(f"{job['name']} ({job['num_districts']} districts)")
+        print(f"{len(jobs)} jobs, {max_workers} workers")
+        return
+
+    cfg["out"].mkdir(parents=True, exist_ok=True)
+    print(f"Running {len(jobs)} jobs with {max_workers} workers (Ctrl-C to cancel)")
+
+    results = {}
+    with ProcessPoolExecutor(max_workers=max_workers) as executor:
+        futures = {executor.submit(run_job, job, cfg): job for job in jobs}
+        try:
+            for fut in as_completed(futures):
+                job = futures[fut]
+                try:
+                    result = fut.result()
+                except Exception as e:
+                    # The worker process itself died; the job's own errors are caught in run_job.
+                    result = {"state": job["state"], "chamber": job["chamber"], "algorithm": job["algorithm"],
+                              "seed": job["seed"], "num_districts": job["num_districts"], "status": "failed", "error": str(e)}
+                results[job["name"]] = result
+                print(f"[{result['status'].upper():6s}] {job['name']} ({len(results)}/{len(jobs)})")
+        except KeyboardInterrupt:
+            print("\n[CTRL-C] Cancelling remaining jobs…")
+            executor.shutdown(wait=False, cancel_futures=True)
+
+    # Keep the table in job order, whatever order the jobs finished in.
+    results_path = cfg["out"] / "results.csv"
+    write_results(results_path, [results[job["name"]] for job in jobs if job["name"] in results])
+    failed = sum(result["status"] != "ok" for result in results.values())
+    print(f"Wrote {results_path} ({len(results)} jobs, {failed} failed)")
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,53 @@
+#!/usr/bin/env python3
+"""
+Enumerate county clusterings for states with county-grouping rules (e.g. NC, VA).
+
+A clustering splits the state into connected groups of whole counties, each holding a
+whole number of districts within the population tolerance. Clusterings are printed
+finest first, and can be written to JSON for use with `Plan.set_county_clusters`.
+
+Usage:
+    python clusters.py --state NC --districts 50 [--tolerance 0.05] [--limit 10] [--out clusters.json]
+"""
+import argparse
+import json
+from pathlib import Path
+
+import openmander as om
+
+SCRIPT_DIR = Path(__file__).resolve().parent
+PACKS_DIR = SCRIPT_DIR.parent / "packs"
+
+
+def main():
+    parser = argparse.ArgumentParser(description="Enumerate county clusterings")
+    parser.add_argument("--state", required=True, help="Two-letter state code (e.g. NC)")
+    parser.add_argument("--districts", type=int, required=True, help="Number of districts")
+    parser.add_argument("--pop-series", default="T_20_CENS_Total", help="Population series")
+    parser.add_argument("--tolerance", type=float, default=0.05, help="Relative population tolerance")
+    parser.add_argument("--limit", type=int, default=10, help="Maximum number of clusterings")
+    parser.add_argument("--pack-dir", type=Path, default=PACKS_DIR, help="Directory for packs")
+    parser.add_argument("--out", type=Path, help="Write clusterings to this JSON file")
+    args = parser.parse_args()
+
+    pack_path = args.pack_dir / f"{args.state}_2020_pack"
+    if not pack_path.exists():
+        pack_path = Path(om.download_pack(args.state, str(args.pack_dir), verbose=1))
+
+    plan = om.Plan(om.Map(str(pack_path)), args.districts)
+    clusterings = plan.county_clusters(args.pop_series, args.tolerance, args.limit)
+
+    for i, clusters in enumerate(clusterings):
+        print(f"Clustering {i}: {len(clusters)} clusters")
+        for counties, districts, total in clusters:
+            print(f"  {districts:3d} districts  {total:12.0f}  {', '.join(counties)}")
+
+    if args.out:
+        with open(args.out, "w") as f:
+            json.dump([[{"counties": c, "districts": d, "total": t} for c, d, t in clusters]
+                       for clusters in clusterings], f, indent=2)
+        print(f"Wrote {len(clusterings)} clusterings to {args.out}")
+
+
+if __name__ == "__main__":
+    main()
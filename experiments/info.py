@@ -0,0 +1,50 @@
+#!/usr/bin/env python3
+"""
+Print a summary of a pack: layers loaded and estimated memory by section.
+
+Compare a full pack with its geometry-less variant to decide which one fits your hardware.
+
+Usage:
+    python info.py --state IL [--pack-dir ../packs]
+    python info.py --pack path/to/pack
+"""
+import argparse
+from pathlib import Path
+
+import openmander as om
+
+SCRIPT_DIR = Path(__file__).resolve().parent
+PACKS_DIR = SCRIPT_DIR.parent / "packs"
+LAYERS = ["state", "county", "tract", "group", "vtd", "block"]
+
+
+def mb(n):
+    return f"{n / (1024 * 1024):10.1f} MB"
+
+
+def main():
+    parser = argparse.ArgumentParser(description="Summarize a pack and its memory footprint")
+    parser.add_argument("--state", help="Two-letter state code (e.g. IL)")
+    parser.add_argument("--pack", type=Path, help="Path to a pack directory")
+    parser.add_argument("--pack-dir", type=Path, default=PACKS_DIR, help="Directory for packs")
+    args = parser.parse_args()
+    if args.pack is None and args.state is None:
+        parser.error("one of --state or --pack is required")
+
+    pack_path = args.pack or args.pack_dir / f"{args.state}_2020_pack"
+    pack_map = om.Map(str(pack_path))
+
+    sections = ["geometry", "tables", "adjacency", "indices", "total"]
+    print(f"{'layer':<8}" + "".join(f"{s:>14}" for s in sections))
+    for layer in LAYERS:
+        try:
+            footprint = pack_map.memory_footprint(layer)
+        except ValueError:
+            continue
+        print(f"{layer:<8}" + "".join(f"{mb(footprint[s]):>14}" for s in sections))
+    footprint = pack_map.memory_footprint()
+    print(f"{'all':<8}" + "".join(f"{mb(footprint[s]):>14}" for s in sections))
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,82 @@
+#!/usr/bin/env python3
+"""
+Test the job matrix and results table of batch.py without running any jobs.
+
+The openmander and fire modules are stubbed, so this runs without building the bindings.
+
+Usage:
+    python test_batch.py
+"""
+import csv
+import sys
+import tempfile
+import types
+import unittest
+from pathlib import Path
+
+for name in ("openmander", "fire"):
+    stub = types.ModuleType(name)
+    stub.__getattr__ = lambda attr: object  # Only referenced in annotations and job bodies
+    sys.modules.setdefault(name, stub)
+
+STATES = [
+    {"code": "NJ", "congress": 12, "sldu": 40, "sldl": 80},
+    {"code": "VA", "congress": 11, "sldu": 40, "sldl": 100},
+    {"code": "WI", "congress": 8, "sldu": 33, "sldl": 99},
+]
+sys.modules["openmander"].list_states = lambda: STATES
+
+import batch
+
+EXAMPLE = Path(__file__).resolve().parent / "jobs.toml"
+
+
+def write_jobs(dir: str, text: str) -> Path:
+    path = Path(dir) / "jobs.toml"
+    path.write_text(text)
+    return path
+
+
+class LoadJobsTest(unittest.TestCase):
+    def test_example_expands_every_combination(self):
+        cfg, jobs = batch.load_jobs(EXAMPLE)
+        self.assertEqual(len(jobs), 3 * 2 * 1 * 3)
+        self.assertEqual(cfg["packs"], EXAMPLE.parent / "../packs")
+        self.assertEqual(jobs[0]["name"], "NJ_congress_anneal_1")
+        seats = {(job["state"], job["chamber"]): job["num_districts"] for job in jobs}
+        self.assertEqual(seats[("NJ", "congress")], 12)
+        self.assertEqual(seats[("WI", "state_senate")], 33)
+
+    def test_seat_counts_can_be_overridden(self):
+        with tempfile.TemporaryDirectory() as dir:
+            _, jobs = batch.load_jobs(write_jobs(dir, 'states = ["WI"]\nchambers = ["state_house", "ward"]\n[seats.ward]\nWI = 5\n'))
+        self.assertEqual([job["num_districts"] for job in jobs], [99, 5])
+
+    def test_defaults(self):
+        with tempfile.TemporaryDirectory() as dir:
+            cfg, jobs = batch.load_jobs(write_jobs(dir, 'states = ["WI"]\n'))
+        self.assertEqual([job["name"] for job in jobs], ["WI_congress_anneal_1"])
+        self.assertEqual(cfg["pop_series"], "T_20_CENS_Total")
+        self.assertEqual(cfg["out"], Path(dir).resolve() / "artifacts/batch")
+
+    def test_bad_jobs_are_rejected(self):
+        with tempfile.TemporaryDirectory() as dir:
+            with self.assertRaisesRegex(ValueError, "Unknown algorithm 'recom'"):
+                batch.load_jobs(write_jobs(dir, 'states = ["WI"]\nalgorithms = ["recom"]\n'))
+            with self.assertRaisesRegex(ValueError, r"No seat count for WI house \(add it under \[seats.house\]\)"):
+                batch.load_jobs(write_jobs(dir, 'states = ["WI"]\nchambers = ["house"]\n'))
+
+
+class WriteResultsTest(unittest.TestCase):
+    def test_missing_fields_are_left_blank(self):
+        with tempfile.TemporaryDirectory() as dir:
+            path = Path(dir) / "results.csv"
+            batch.write_results(path, [{"state": "WI", "status": "failed", "error": "boom"}])
+            with open(path, newline="") as f:
+                rows = list(csv.DictReader(f))
+        self.assertEqual(list(rows[0]), batch.RESULT_FIELDS)
+        self.assertEqual((rows[0]["state"], rows[0]["status"], rows[0]["plan"]), ("WI", "failed", ""))
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -2,4 +2,4 @@ mod unit_graph;
 mod weights;
 
 pub(crate) use unit_graph::UnitGraph;
-pub(crate) use weights::WeightMatrix;
+pub(crate) use weights::{WeightMatrix, compensated_add};
@@ -79,6 +79,15 @@ impl UnitGraph {
     }
 }
 
+/// Unit adjacency lists are sorted CCW during pack construction, so boundary walks can use them.
+impl partset::PlanarAdjacency for UnitGraph {
+    #[inline] fn degree(&self, node: usize) -> usize { UnitGraph::degree(self, node) }
+
+    #[inline] fn neighbor(&self, node: usize, i: usize) -> Option<usize> { self.edge(node, i) }
+
+    #[inline] fn edge_endpoints(&self, edge: usize) -> Option<(usize, usize)> { UnitGraph::edge_endpoints(self, edge) }
+}
+
 impl From<Arc<Region>> for UnitGraph {
     fn from(r: Arc<Region>) -> Self { Self(r) }
 }
@@ -7,11 +7,24 @@ use polars::{frame::DataFrame, prelude::DataType};
 pub(crate) enum WeightType { I64, F64 }
 
 /// Node weights stored as type-separated matrices.
+///
+/// Row updates are order-independent: integer series (e.g. population counts) are summed exactly,
+/// and float series carry a Neumaier compensation term, so running totals stay within rounding
+/// of the exact sum however many moves produced them.
 #[derive(Clone, Debug, Default)]
 pub(crate) struct WeightMatrix {
     series: HashMap<String, (WeightType, usize)>, // len = k_i + k_f
     i64: Array2<i64>, // (n, k_i)
     f64: Array2<f64>, // (n, k_f)
+    f64_error: Array2<f64>, // (n, k_f) compensation for `f64`, allocated on first row update
+}
+
+/// Add `value` to a compensated sum (Neumaier's variant of Kahan summation).
+#[inline]
+pub(crate) fn compensated_add(sum: &mut f64, error: &mut f64, value: f64) {
+    let total = *sum + value;
+    *error += if sum.abs() >= value.abs() { (*sum - total) + value } else { (value - total) + *sum };
+    *sum = total;
 }
 
 impl WeightMatrix {
@@ -21,6 +34,7 @@ impl WeightMatrix {
             series: HashMap::new(),
             i64: Array2::<i64>::zeros((size, weights_i64.len())),
             f64: Array2::<f64>::zeros((size, weights_f64.len())),
+            f64_error: Array2::<f64>::zeros((0, 0)),
         };
 
         weights_i64.into_iter().enumerate().for_each(|(i, (name, values))| {
@@ -39,6 +53,8 @@ impl WeightMatrix {
     }
 
     /// Build a `WeightMatrix` from the numeric columns of a DataFrame, skipping `idx`.
+    /// The schema marks count columns: integer columns (e.g. population or votes) are summed
+    /// exactly as i64, and float columns with compensation, even if every value is whole.
     pub(crate) fn from_dataframe(df: &DataFrame) -> Self {
         let weights_i64 = df.get_columns().iter()
             .map(|c| (c.name().to_string(), c))
@@ -73,6 +89,7 @@ impl WeightMatrix {
             series: HashMap::new(),
             i64: Array2::<i64>::zeros((size, 0)),
             f64: Array2::<f64>::zeros((size, 0)),
+            f64_error: Array2::<f64>::zeros((0, 0)),
         }
     }
 
@@ -82,20 +99,49 @@ impl WeightMatrix {
     /// Check if a weight series exists.
     pub(crate) fn contains(&self, series: &str) -> bool { self.series.contains_key(series) }
 
+    /// Heap memory held by the weights and the series names, in bytes.
+    pub(crate) fn heap_bytes(&self) -> usize {
+        (self.i64.len() + self.f64.len() + self.f64_error.len()) * size_of::<f64>()
+            + self.series.capacity() * size_of::<(String, (WeightType, usize))>()
+            + self.series.keys().map(String::capacity).sum::<usize>()
+    }
+
     /// Get a weight value as f64, regardless of original type.
     pub(crate) fn get_as_f64(&self, series: &str, u: usize) -> Option<f64> {
         self.series.get(series).map(|(kind, c)| match kind {
             WeightType::I64 => self.i64[(u, *c)] as f64,
-            WeightType::F64 => self.f64[(u, *c)],
+            WeightType::F64 => self.f64_value(u, *c),
+        })
+    }
+
+    /// Get an integer series' weight exactly (None for float or missing series).
+    pub(crate) fn get_as_i64(&self, series: &str, u: usize) -> Option<i64> {
+        self.series.get(series).and_then(|(kind, c)| match kind {
+            WeightType::I64 => Some(self.i64[(u, *c)]),
+            WeightType::F64 => None,
         })
     }
 
+    /// Float weight at (row, column), including its compensation term.
+    #[inline]
+    fn f64_value(&self, row: usize, column: usize) -> f64 {
+        self.f64[(row, column)] + self.f64_error.get((row, column)).copied().unwrap_or(0.0)
+    }
+
+    /// Add `sign` times float row `from_row` of `other` (with its compensation) into row `to_row`.
+    fn add_f64_row(&mut self, to_row: usize, other: &Self, from_row: usize, sign: f64) {
+        let values = (0..other.f64.ncols())
+            .map(|c| (other.f64[(from_row, c)], other.f64_error.get((from_row, c)).copied().unwrap_or(0.0)));
+        add_compensated_row(&mut self.f64, &mut self.f64_error, to_row, values, sign);
+    }
+
     /// Create a new empty WeightMatrix with a given size, copying the existing series.
     pub(crate) fn copy_of_size(&self, size: usize) -> Self {
         Self {
             series: self.series.clone(),
             i64: Array2::<i64>::zeros((size, self.i64.ncols())),
             f64: Array2::<f64>::zeros((size, self.f64.ncols())),
+            f64_error: Array2::<f64>::zeros((size, self.f64.ncols())),
         }
     }
 
@@ -103,12 +149,14 @@ impl WeightMatrix {
     pub(crate) fn clear_all_rows(&mut self) {
         self.i64.fill(0);
         self.f64.fill(0.0);
+        self.f64_error.fill(0.0);
     }
 
     /// Clear a specific row to zero.
     pub(crate) fn clear_row(&mut self, row: usize) {
         self.i64.row_mut(row).fill(0);
         self.f64.row_mut(row).fill(0.0);
+        if row < self.f64_error.nrows() { self.f64_error.row_mut(row).fill(0.0) }
     }
 
     /// Add a row to another row in place.
@@ -116,52 +164,51 @@ impl WeightMatrix {
         let row_i = self.i64.row(from_row).to_owned();
         self.i64.row_mut(to_row).scaled_add(1, &row_i);
 
-        let row_f = self.f64.row(from_row).to_owned();
-        self.f64.row_mut(to_row).scaled_add(1.0, &row_f);
+        let values = (0..self.f64.ncols())
+            .map(|c| (self.f64[(from_row, c)], self.f64_error.get((from_row, c)).copied().unwrap_or(0.0)))
+            .collect::<Vec<_>>();
+        add_compensated_row(&mut self.f64, &mut self.f64_error, to_row, values, 1.0);
     }
 
     /// Set a row to be the sum of all weights from another WeightMatrix.
     pub(crate) fn set_row_to_sum_of(&mut self, to_row: usize, other: &Self) {
         self.i64.row_mut(to_row).assign(&other.i64.sum_axis(Axis(0)));
-        self.f64.row_mut(to_row).assign(&other.f64.sum_axis(Axis(0)));
+        self.f64.row_mut(to_row).fill(0.0);
+        if to_row < self.f64_error.nrows() { self.f64_error.row_mut(to_row).fill(0.0) }
+        for row in 0..other.f64.nrows() { self.add_f64_row(to_row, other, row, 1.0) }
     }
 
     /// Add a row of another WeightMatrix to a row in this one.
     pub(crate) fn add_row_from(&mut self, to_row: usize, other: &Self, from_row: usize) {
         self.i64.row_mut(to_row).scaled_add(1, &other.i64.row(from_row));
-        self.f64.row_mut(to_row).scaled_add(1.0, &other.f64.row(from_row));
+        self.add_f64_row(to_row, other, from_row, 1.0);
     }
 
     /// Subtract a row of another WeightMatrix from the a row in this one.
     pub(crate) fn subtract_row_from(&mut self, to_row: usize, other: &Self, from_row: usize) {
         self.i64.row_mut(to_row).scaled_add(-1, &other.i64.row(from_row));
-        self.f64.row_mut(to_row).scaled_add(-1.0, &other.f64.row(from_row));
+        self.add_f64_row(to_row, other, from_row, -1.0);
     }
 
     /// Add multiple rows of another WeightMatrix to a row in this one.
     pub(crate) fn add_rows_from(&mut self, to_row: usize, other: &Self, from_rows: &[usize]) {
-        let mut sum_i = Array1::<i64>::zeros(other.i64.ncols());
-        let mut sum_f = Array1::<f64>::zeros(other.f64.ncols());
-
-        for &row in from_rows {
-            sum_i += &other.i64.row(row);
-            sum_f += &other.f64.row(row);
-        }
-
-        self.i64.row_mut(to_row).scaled_add(1, &sum_i);
-        self.f64.row_mut(to_row).scaled_add(1.0, &sum_f);
+        for &row in from_rows { self.add_row_from(to_row, other, row) }
     }
 
     /// Subtract multiple rows of another WeightMatrix from a row in this one.
     pub(crate) fn subtract_rows_from(&mut self, to_row: usize, other: &Self, from_rows: &[usize]) {
-        let mut sum_i = Array1::<i64>::zeros(other.i64.ncols());
-        let mut sum_f = Array1::<f64>::zeros(other.f64.ncols());
-        for &row in from_rows {
-            sum_i += &other.i64.row(row);
-            sum_f += &other.f64.row(row);
-        }
-        self.i64.row_mut(to_row).scaled_add(-1, &sum_i);
-        self.f64.row_mut(to_row).scaled_add(-1.0, &sum_f);
+        for &row in from_rows { self.subtract_row_from(to_row, other, row) }
+    }
+}
+
+/// Add `sign` times each (value, compensation) pair to a row of compensated sums,
+/// allocating the compensation matrix if it is not yet in use.
+fn add_compensated_row(sums: &mut Array2<f64>, errors: &mut Array2<f64>, row: usize, values: impl IntoIterator<Item = (f64, f64)>, sign: f64) {
+    if errors.dim() != sums.dim() { *errors = Array2::zeros(sums.dim()) }
+    for (c, (value, error)) in values.into_iter().enumerate() {
+        let (sum, compensation) = (&mut sums[(row, c)], &mut errors[(row, c)]);
+        compensated_add(sum, compensation, sign * value);
+        if error != 0.0 { compensated_add(sum, compensation, sign * error) }
     }
 }
 
@@ -201,6 +248,20 @@ mod tests {
         assert_eq!(m.get_as_f64("missing", 0), None);
     }
 
+    #[test]
+    fn test_count_columns_follow_the_schema() {
+        let df = polars::df![
+            "idx" => [0i64, 1],
+            "pop" => [120i32, 7],
+            "share" => [1.0f64, 2.0],
+        ].unwrap();
+        let m = WeightMatrix::from_dataframe(&df);
+        assert_eq!(m.get_as_i64("pop", 0), Some(120));
+        assert_eq!(m.get_as_i64("share", 1), None);
+        assert_eq!(m.get_as_f64("share", 1), Some(2.0));
+        assert!(!m.contains("idx"));
+    }
+
     #[test]
     fn test_copy_of_size() {
         let m = make_matrix();
@@ -286,6 +347,24 @@ mod tests {
         assert_eq!(dst.get_as_f64("area", 2), Some(-0.5));  // 3.5-1.5-2.5
     }
 
+    #[test]
+    fn test_compensated_rows_do_not_drift() {
+        let src = WeightMatrix::new(3, HashMap::new(), HashMap::from([("area".into(), vec![1e16, 1.0, 0.1])]));
+        let mut dst = src.copy_of_size(2);
+        dst.add_rows_from(0, &src, &[0, 1]);
+        dst.subtract_row_from(0, &src, 0);
+        assert_eq!(dst.get_as_f64("area", 0), Some(1.0)); // plain summation loses the 1.0
+
+        // Moving a unit back and forth many times leaves the total exactly as it was.
+        dst.set_row_to_sum_of(1, &src);
+        let before = dst.get_as_f64("area", 1);
+        for _ in 0..1000 {
+            dst.subtract_row_from(1, &src, 2);
+            dst.add_row_from(1, &src, 2);
+        }
+        assert_eq!(dst.get_as_f64("area", 1), before);
+    }
+
     #[test]
     fn test_empty_weights() {
         let m = WeightMatrix::new(3, HashMap::new(), HashMap::new());
@@ -0,0 +1,133 @@
+use std::{collections::VecDeque, sync::{Arc, Mutex}};
+
+use anyhow::{Context, Result, anyhow, ensure};
+use tonic::transport::Channel;
+
+use crate::{objective::Metric, plan::Plan};
+
+use super::{
+    MAX_MESSAGE_SIZE,
+    proto::{LoadMapRequest, SegmentRequest, SegmentResponse, segment_request::Chain, worker_client::WorkerClient},
+};
+
+/// A plan sampled by a remote worker.
+pub struct RemoteSample {
+    pub segment: usize,   // Index of the segment that produced it
+    pub step: u64,        // Step of the segment it was taken after
+    pub plan: Plan,
+    pub scores: Vec<f64>, // One per requested metric
+}
+
+/// Dispatches chain segments to a pool of workers and gathers their samples.
+pub struct Coordinator {
+    workers: Vec<WorkerClient<Channel>>,
+}
+
+impl Coordinator {
+    /// Connect to workers at the given endpoints (e.g. `"http://10.0.0.2:50051"`).
+    pub async fn connect(endpoints: &[&str]) -> Result<Self> {
+        ensure!(!endpoints.is_empty(), "[Coordinator] No worker endpoints given");
+        let mut workers = Vec::with_capacity(endpoints.len());
+        for &endpoint in endpoints {
+            let client = WorkerClient::connect(endpoint.to_string()).await
+                .with_context(|| format!("[Coordinator] Failed to connect to worker at {endpoint}"))?;
+            workers.push(client
+                .max_decoding_message_size(MAX_MESSAGE_SIZE)
+                .max_encoding_message_size(MAX_MESSAGE_SIZE));
+        }
+        Ok(Self { workers })
+    }
+
+    /// Get the number of connected workers.
+    #[inline] pub fn num_workers(&self) -> usize { self.workers.len() }
+
+    /// Load the pack at `path` on every worker, returning its block count.
+    /// Fails if the workers do not all load the same number of blocks.
+    pub async fn load_map(&self, path: &str) -> Result<u64> {
+        let tasks = self.workers.iter().cloned().map(|mut worker| {
+            let request = LoadMapRequest { path: path.to_string() };
+            tokio::spawn(async move { worker.load_map(request).await })
+        }).collect::<Vec<_>>();
+
+        let mut counts = Vec::with_capacity(tasks.len());
+        for (index, task) in tasks.into_iter().enumerate() {
+            let response = task.await.context("[Coordinator] Worker task panicked")?
+                .map_err(|status| anyhow!("[Coordinator] Worker {index} failed to load {path}: {}", status.message()))?;
+            counts.push(response.into_inner().num_units);
+        }
+        ensure!(counts.iter().all(|&count| count == counts[0]),
+            "[Coordinator] Workers loaded different maps from {path} (block counts {counts:?})");
+        Ok(counts[0])
+    }
+
+    /// Run segments on the workers, each worker taking the next segment as soon as it is free.
+    /// Responses are returned in the order of `segments`.
+    pub async fn run_segments(&self, segments: Vec<SegmentRequest>) -> Result<Vec<SegmentResponse>> {
+        let num_segments = segments.len();
+        let queue = Arc::new(Mutex::new(segments.into_iter().enumerate().collect::<VecDeque<_>>()));
+
+        let tasks = self.workers.iter().cloned().enumerate().map(|(index, mut worker)| {
+            let queue = queue.clone();
+            tokio::spawn(async move {
+                let mut finished = Vec::new();
+                loop {
+                    let next = queue.lock().unwrap().pop_front();
+                    let Some((segment, request)) = next else { break };
+                    let response = worker.run_segment(request).await
+                        .map_err(|status| anyhow!("[Coordinator] Worker {index} failed on segment {segment}: {}", status.message()))?;
+                    finished.push((segment, response.into_inner()));
+                }
+                anyhow::Ok(finished)
+            })
+        }).collect::<Vec<_>>();
+
+        let mut responses = (0..num_segments).map(|_| None).collect::<Vec<_>>();
+        for task in tasks {
+            for (segment, response) in task.await.context("[Coordinator] Worker task panicked")?? {
+                responses[segment] = Some(response);
+            }
+        }
+        Ok(responses.into_iter().map(|response| response.expect("every segment is run by some worker")).collect())
+    }
+
+    /// Run `num_segments` independent copies of `chain` from `start`, and collect every sample
+    /// scored by `metrics`. `path` is the pack of `start`'s map, relative to the workers' pack root.
+    /// With a `seed`, segment `i` walks with seed `seed + i`, so the ensemble can be reproduced.
+    pub async fn ensemble(
+        &self,
+        path: &str,
+        start: &Plan,
+        chain: Chain,
+        num_segments: usize,
+        metrics: &[Metric],
+        seed: Option<u64>,
+    ) -> Result<Vec<RemoteSample>> {
+        let codec = start.assignment_codec()?;
+        let num_units = self.load_map(path).await?;
+        ensure!(num_units as usize == codec.len(),
+            "[Coordinator] Workers loaded {num_units} blocks from {path}, but the plan has {}", codec.len());
+
+        let request = SegmentRequest {
+            map: path.to_string(),
+            num_districts: start.num_districts(),
+            start: start.to_compact_bytes(&codec)?,
+            chain: Some(chain),
+            metrics: metrics.iter().map(serde_json::to_string).collect::<Result<_, _>>()?,
+            seed: None,
+        };
+        let segments = (0..num_segments as u64)
+            .map(|segment| SegmentRequest { seed: seed.map(|seed| seed.wrapping_add(segment)), ..request.clone() })
+            .collect();
+        let responses = self.run_segments(segments).await?;
+
+        let mut samples = Vec::new();
+        for (segment, response) in responses.into_iter().enumerate() {
+            for sample in response.samples {
+                let mut plan = start.clone();
+                plan.load_compact_bytes(&codec, &sample.assignments)?;
+                samples.push(RemoteSample { segment, step: sample.step, plan, scores: sample.scores });
+            }
+        }
+        Ok(samples)
+    }
+}
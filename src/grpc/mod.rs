@@ -0,0 +1,40 @@
+//! gRPC service for running chain segments on remote worker processes (feature `grpc`).
+//!
+//! Each worker runs [`serve_worker`], wrapping the existing algorithm code. A [`Coordinator`]
+//! connects to the workers, hands out segments (a starting plan plus an annealing run or a ReCom
+//! walk) as workers become free, and collects the sampled plans into one ensemble. The service is
+//! defined in `proto/worker.proto`; plans travel compact-encoded with [`crate::AssignmentCodec`].
+
+mod coordinator;
+mod worker;
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+/// Messages and client/server stubs generated from `proto/worker.proto`.
+pub mod proto {
+    tonic::include_proto!("openmander.worker.v1");
+}
+
+pub use coordinator::{Coordinator, RemoteSample};
+pub use proto::segment_request::Chain;
+pub use worker::WorkerService;
+
+/// Largest message accepted by workers and coordinators; segments may carry many samples.
+const MAX_MESSAGE_SIZE: usize = 256 * 1024 * 1024;
+
+/// Serve a worker on `addr` (e.g. `"0.0.0.0:50051"`), blocking until the server stops.
+/// Map paths sent by coordinators are resolved against `pack_root`; paths leading outside it are rejected.
+pub fn serve_worker(addr: &str, pack_root: impl Into<PathBuf>) -> Result<()> {
+    let service = WorkerService::new(pack_root);
+    let addr = addr.parse().with_context(|| format!("[grpc] Invalid address {addr:?}"))?;
+    let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build()
+        .context("[grpc] Failed to start async runtime")?;
+    runtime.block_on(async {
+        tonic::transport::Server::builder()
+            .add_service(service.into_server())
+            .serve(addr).await
+            .context("[grpc] Worker stopped with an error")
+    })
+}
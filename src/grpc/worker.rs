@@ -0,0 +1,144 @@
+use std::{collections::HashMap, path::PathBuf, sync::{Arc, RwLock}};
+
+use anyhow::{Context, Result, anyhow};
+use tonic::{Request, Response, Status};
+
+use crate::{map::{Map, resolve_pack_path}, objective::Metric, plan::{AnnealConfig, AssignmentCodec, Plan}};
+
+use super::{
+    MAX_MESSAGE_SIZE,
+    proto::{
+        LoadMapRequest, LoadMapResponse, Sample, SegmentRequest, SegmentResponse,
+        segment_request::Chain,
+        worker_server::{Worker, WorkerServer},
+    },
+};
+
+/// A map loaded by a worker, with the codec its plans are exchanged in.
+struct LoadedMap {
+    map: Arc<Map>,
+    codec: AssignmentCodec,
+}
+
+/// Worker side of the service: runs segments with the local algorithm code, caching maps by path.
+pub struct WorkerService {
+    pack_root: PathBuf, // Directory that map paths are resolved against
+    maps: RwLock<HashMap<PathBuf, Arc<LoadedMap>>>,
+}
+
+impl WorkerService {
+    /// A worker loading maps only from packs under `pack_root`.
+    pub fn new(pack_root: impl Into<PathBuf>) -> Self {
+        Self { pack_root: pack_root.into(), maps: RwLock::default() }
+    }
+
+    /// Wrap the service for a tonic server, e.g. to serve it alongside other services.
+    pub fn into_server(self) -> WorkerServer<Self> {
+        WorkerServer::new(self)
+            .max_decoding_message_size(MAX_MESSAGE_SIZE)
+            .max_encoding_message_size(MAX_MESSAGE_SIZE)
+    }
+
+    async fn load(&self, path: String) -> Result<Arc<LoadedMap>, Status> {
+        let path = resolve_pack_path(&self.pack_root, &path)
+            .map_err(|e| Status::invalid_argument(format!("{e:#}")))?;
+        let cached = self.maps.read().unwrap().get(&path).cloned();
+        if let Some(loaded) = cached { return Ok(loaded) }
+
+        let pack = path.clone();
+        let loaded = blocking(move || {
+            let map = Map::read_from_pack(&pack)?;
+            let codec = AssignmentCodec::new(&map)?;
+            Ok(Arc::new(LoadedMap { map: Arc::new(map), codec }))
+        }).await?;
+        Ok(self.maps.write().unwrap().entry(path).or_insert(loaded).clone())
+    }
+}
+
+#[tonic::async_trait]
+impl Worker for WorkerService {
+    async fn load_map(&self, request: Request<LoadMapRequest>) -> Result<Response<LoadMapResponse>, Status> {
+        let loaded = self.load(request.into_inner().path).await?;
+        Ok(Response::new(LoadMapResponse { num_units: loaded.codec.len() as u64 }))
+    }
+
+    async fn run_segment(&self, request: Request<SegmentRequest>) -> Result<Response<SegmentResponse>, Status> {
+        let request = request.into_inner();
+        let loaded = self.load(request.map.clone()).await?;
+        let samples = blocking(move || run_segment(&loaded, request)).await?;
+        Ok(Response::new(SegmentResponse { samples }))
+    }
+}
+
+/// Run CPU-heavy work off the async executor. Library errors are caused by the request.
+async fn blocking<T: Send + 'static>(work: impl FnOnce() -> Result<T> + Send + 'static) -> Result<T, Status> {
+    tokio::task::spawn_blocking(work).await
+        .map_err(|e| Status::internal(format!("Worker task failed: {e}")))?
+        .map_err(|e| Status::invalid_argument(format!("{e:#}")))
+}
+
+/// Run one segment from its starting plan, sampling plans as it goes.
+fn run_segment(loaded: &LoadedMap, request: SegmentRequest) -> Result<Vec<Sample>> {
+    let metrics = request.metrics.iter()
+        .map(|spec| serde_json::from_str::<Metric>(spec).with_context(|| format!("[grpc] Invalid metric {spec:?}")))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut plan = Plan::new(loaded.map.clone(), request.num_districts)?;
+    plan.load_compact_bytes(&loaded.codec, &request.start)?;
+    let sample = |plan: &Plan, step: u64| -> Result<Sample> {
+        Ok(Sample {
+            step,
+            assignments: plan.to_compact_bytes(&loaded.codec)?,
+            scores: metrics.iter().map(|metric| plan.compute_metric_score(metric)).collect(),
+        })
+    };
+
+    match request.chain.ok_or_else(|| anyhow!("[grpc] Segment has no chain"))? {
+        Chain::Anneal(anneal) => {
+            let config: AnnealConfig = serde_json::from_str(&anneal.config).context("[grpc] Invalid anneal config")?;
+            plan.anneal_with_config(&config)?;
+            Ok(vec![sample(&plan, config.max_iter as u64)?])
+        }
+        Chain::Recom(recom) => {
+            let every = recom.sample_every as usize;
+            plan.recom_ensemble(&recom.pop_series, recom.tolerance, recom.steps as usize, every, request.seed)?
+                .iter().enumerate()
+                .map(|(index, plan)| sample(plan, ((index + 1) * every) as u64))
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{grpc::proto::RecomSegment, test_maps};
+
+    #[test]
+    fn recom_segments_follow_their_seed() {
+        let map = Arc::new(test_maps::grid(4));
+        let loaded = LoadedMap { codec: AssignmentCodec::new(&map).unwrap(), map: map.clone() };
+        let mut start = Plan::new(map, 2).unwrap();
+        start.set_assignments_vec((0..16).map(|k| k / 8 + 1).collect()).unwrap();
+
+        let segment = |seed, tolerance| SegmentRequest {
+            map: String::new(),
+            num_districts: 2,
+            start: start.to_compact_bytes(&loaded.codec).unwrap(),
+            chain: Some(Chain::Recom(RecomSegment { steps: 20, sample_every: 5, pop_series: "pop".into(), tolerance })),
+            metrics: vec![serde_json::to_string(&Metric::population_deviation("pop".into())).unwrap()],
+            seed,
+        };
+        let samples = run_segment(&loaded, segment(Some(7), 0.0)).unwrap();
+        assert_eq!(samples.iter().map(|sample| sample.step).collect::<Vec<_>>(), [5, 10, 15, 20]);
+        assert_eq!(samples, run_segment(&loaded, segment(Some(7), 0.0)).unwrap());
+
+        // Every sample keeps the two districts at exactly eight people each.
+        let mut plan = start.clone();
+        for sample in &samples {
+            plan.load_compact_bytes(&loaded.codec, &sample.assignments).unwrap();
+            assert_eq!(plan.get_assignments_vec().unwrap().iter().filter(|&&district| district == 1).count(), 8);
+        }
+        assert!(run_segment(&loaded, segment(Some(7), -1.0)).is_err());
+    }
+}
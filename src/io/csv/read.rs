@@ -2,12 +2,13 @@
 
 use std::{fs::File, io::Cursor, path::Path, sync::Arc};
 
-use anyhow::{Context, Result, ensure};
+use anyhow::{Context, Result, anyhow, ensure};
 use polars::{frame::DataFrame, io::SerReader, prelude::{CsvReadOptions, CsvReader, DataType, Field, Schema, SchemaRef}};
 
 use crate::map::{GeoId, GeoType, MapLayer};
 
 /// Reads a CSV file from `path` into a Polars DataFrame.
+#[cfg(feature = "download")]
 pub(crate) fn read_csv(path: &Path) -> Result<DataFrame> {
     let file = File::open(path)
         .with_context(|| format!("[io::csv::read] Failed to open CSV file: {}", path.display()))?;
@@ -16,6 +17,18 @@ pub(crate) fn read_csv(path: &Path) -> Result<DataFrame> {
         .with_context(|| format!("[io::csv::read] Failed to read CSV from {:?}", path))
 }
 
+/// Reads a CSV file from `path` with every column as a string, e.g. to keep leading zeros of GEOIDs.
+pub(crate) fn read_csv_strings(path: &Path) -> Result<DataFrame> {
+    let file = File::open(path)
+        .with_context(|| format!("[io::csv::read] Failed to open CSV file: {}", path.display()))?;
+    CsvReadOptions::default()
+        .with_has_header(true)
+        .with_infer_schema_length(Some(0))
+        .into_reader_with_file_handle(file)
+        .finish()
+        .with_context(|| format!("[io::csv::read] Failed to read CSV from {:?}", path))
+}
+
 /// Reads a CSV from a string (for WASM/browser use).
 pub(crate) fn read_csv_string(csv: &str) -> Result<DataFrame> {
     CsvReader::new(Cursor::new(csv.as_bytes()))
@@ -69,9 +82,12 @@ pub(crate) fn read_plan_assignments(df: DataFrame, block_layer: &MapLayer) -> Re
     blocks.str()?.into_no_null_iter()
         .zip(districts.u32()?.into_no_null_iter())
         .map(|(block, district)| {
-            let geo_id = GeoId::new(GeoType::Block, block);
-            ensure!(block_layer.geo_ids().contains(&geo_id), "[io::csv::read] GeoId {} in CSV not found in map", geo_id.id());
-            Ok((geo_id, district))
+            // Reuse the map's GeoId, which shares the layer's string table.
+            let geo_id = (block.len() == GeoType::Block.id_len())
+                .then(|| block_layer.index().get_key_value(&GeoId::new(GeoType::Block, block)))
+                .flatten()
+                .ok_or_else(|| anyhow!("[io::csv::read] GeoId {block} in CSV not found in map"))?;
+            Ok((geo_id.0.clone(), district))
         })
         .collect()
 }
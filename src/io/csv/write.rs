@@ -35,25 +35,6 @@ pub(crate) fn write_csv_string(df: &mut DataFrame) -> Result<String> {
         .with_context(|| "[io::csv::write] CSV output is not valid UTF-8")
 }
 
-/// Write plan assignments to a CSV file.
-/// 
-/// The assignments are a vector of (GeoId, district) tuples.
-/// Only non-zero districts are written.
-pub(crate) fn write_plan_assignments(assignments: &[(GeoId, u32)], path: &Path) -> Result<()> {
-    let (geo_ids, districts) = assignments.iter()
-        .filter_map(|(geo_id, district)| {
-            (*district != 0).then_some((geo_id.id().to_string(), *district))
-        })
-        .unzip::<_, _, Vec<_>, Vec<_>>();
-
-    let mut df = DataFrame::new(vec![
-        Series::new("geo_id".into(), geo_ids).into(),
-        Series::new("district".into(), districts).into(),
-    ])?;
-
-    write_csv(&mut df, path)
-}
-
 /// Write plan assignments to a CSV string (for WASM/browser use).
 pub(crate) fn write_plan_assignments_string(assignments: &[(GeoId, u32)]) -> Result<String> {
     let (geo_ids, districts) = assignments.iter()
@@ -0,0 +1,144 @@
+//! Localized number formatting and labels for reports and SVG annotations.
+
+/// Language of report text and labels.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    English,
+    Spanish,
+}
+
+/// How generated reports and SVG labels format numbers, and which language their labels are in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Locale {
+    pub language: Language,
+    pub decimal: char,          // Decimal separator
+    pub grouping: Option<char>, // Separator between groups of thousands, if any
+}
+
+impl Default for Locale {
+    fn default() -> Self { Self::EN_US }
+}
+
+/// Spanish translations of the English labels used in reports and SVG annotations. `{}` stands
+/// for a name filled in by [`Locale::column_label`].
+const SPANISH: &[(&str, &str)] = &[
+    ("District report", "Informe de distritos"),
+    ("District", "Distrito"),
+    ("Districts", "Distritos"),
+    ("Population", "Población"),
+    ("Total population", "Población total"),
+    ("Ideal population", "Población ideal"),
+    ("Deviation", "Desviación"),
+    ("Max population deviation", "Desviación máxima de población"),
+    ("Efficiency gap", "Brecha de eficiencia"),
+    ("Counties", "Condados"),
+    ("Number of counties", "Número de condados"),
+    ("{} share", "Proporción {}"),
+    ("{} Democratic share", "Proporción demócrata {}"),
+];
+
+/// English labels of the fixed [`crate::Plan::district_profiles`] columns.
+const COLUMN_LABELS: &[(&str, &str)] = &[
+    ("district", "District"),
+    ("population", "Population"),
+    ("deviation", "Deviation"),
+    ("polsby_popper", "Polsby-Popper"),
+    ("schwartzberg", "Schwartzberg"),
+    ("num_counties", "Number of counties"),
+    ("counties", "Counties"),
+];
+
+impl Locale {
+    /// English, as used in the United States.
+    pub const EN_US: Self = Self { language: Language::English, decimal: '.', grouping: Some(',') };
+
+    /// Spanish as used in the United States and Mexico, which formats numbers as English does.
+    pub const ES_US: Self = Self { language: Language::Spanish, decimal: '.', grouping: Some(',') };
+
+    /// Spanish as used in Spain, with a decimal comma and thousands separated by points.
+    pub const ES_ES: Self = Self { language: Language::Spanish, decimal: ',', grouping: Some('.') };
+
+    /// Locale for a language tag such as "en", "en-US", "es", "es-MX" or "es-ES" (case-insensitive,
+    /// `_` also separates). Spanish of regions other than Spain formats numbers as in the US.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let tag = tag.to_ascii_lowercase().replace('_', "-");
+        let (language, region) = tag.split_once('-').unwrap_or((&tag, ""));
+        match (language, region) {
+            ("en", _) => Some(Self::EN_US),
+            ("es", "es") => Some(Self::ES_ES),
+            ("es", _) => Some(Self::ES_US),
+            _ => None,
+        }
+    }
+
+    /// Format `value` with `decimals` digits after the decimal separator, grouping thousands.
+    pub fn format_number(&self, value: f64, decimals: usize) -> String {
+        if !value.is_finite() { return value.to_string() }
+        let digits = format!("{:.*}", decimals, value.abs());
+        let (int, frac) = digits.split_once('.').map_or((digits.as_str(), None), |(int, frac)| (int, Some(frac)));
+
+        let mut text = String::with_capacity(digits.len() + int.len() / 3 + 1);
+        if value < 0.0 && digits.bytes().any(|b| b.is_ascii_digit() && b != b'0') { text.push('-') }
+        for (i, digit) in int.chars().enumerate() {
+            if let Some(separator) = self.grouping && i > 0 && (int.len() - i) % 3 == 0 { text.push(separator) }
+            text.push(digit);
+        }
+        if let Some(frac) = frac {
+            text.push(self.decimal);
+            text.push_str(frac);
+        }
+        text
+    }
+
+    /// Format a `fraction` (e.g. 0.015) as a percentage with `decimals` digits (e.g. "1.50%").
+    pub fn format_percent(&self, fraction: f64, decimals: usize) -> String {
+        format!("{}%", self.format_number(fraction * 100.0, decimals))
+    }
+
+    /// Translate a report or annotation label written in English. Labels without a translation
+    /// are returned unchanged.
+    pub fn text<'a>(&self, english: &'a str) -> &'a str {
+        match self.language {
+            Language::English => english,
+            Language::Spanish => SPANISH.iter().find(|(en, _)| *en == english).map_or(english, |(_, es)| es),
+        }
+    }
+
+    /// Human-readable label for a [`crate::Plan::district_profiles`] column, e.g. "Population" for
+    /// `population` or "Proporción BLACK" for `BLACK_share` in Spanish. Unknown columns keep their name.
+    pub fn column_label(&self, column: &str) -> String {
+        if let Some((_, label)) = COLUMN_LABELS.iter().find(|(name, _)| *name == column) {
+            return self.text(label).to_string()
+        }
+        if let Some(contest) = column.strip_suffix("_dem_share") {
+            return self.text("{} Democratic share").replacen("{}", contest, 1)
+        }
+        if let Some(group) = column.strip_suffix("_share") {
+            return self.text("{} share").replacen("{}", group, 1)
+        }
+        column.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_and_labels_follow_the_locale() {
+        assert_eq!(Locale::EN_US.format_number(1234567.891, 2), "1,234,567.89");
+        assert_eq!(Locale::ES_ES.format_number(-1234.5, 1), "-1.234,5");
+        assert_eq!(Locale::EN_US.format_number(-0.001, 2), "0.00");
+        assert_eq!(Locale::ES_ES.format_percent(0.0152, 2), "1,52%");
+
+        assert_eq!(Locale::from_tag("es_MX"), Some(Locale::ES_US));
+        assert_eq!(Locale::from_tag("ES-es"), Some(Locale::ES_ES));
+        assert_eq!(Locale::from_tag("fr"), None);
+
+        assert_eq!(Locale::ES_US.column_label("population"), "Población");
+        assert_eq!(Locale::ES_US.column_label("PRES20_dem_share"), "Proporción demócrata PRES20");
+        assert_eq!(Locale::EN_US.column_label("BLACK_share"), "BLACK share");
+        assert_eq!(Locale::ES_US.text("Polsby-Popper"), "Polsby-Popper");
+    }
+}
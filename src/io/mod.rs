@@ -3,9 +3,10 @@
 //! Each format module handles reading and writing for a specific file format:
 //!
 //! - `csv` - CSV format for tabular data
+//! - `locale` - Localized number formatting and labels for reports and SVG annotations
 //! - `parquet` - Parquet format for tabular data (requires `parquet` feature)
 //! - `pmtiles` - PMTiles format for tile-based geometry storage (requires `pmtiles` feature)
-//! - `shp` - Shapefile format for geographic data
+//! - `shp` - Shapefile format for geographic data (requires `download` feature)
 //! - `svg` - SVG format for visualization export
 //! - `wkb` - Well-Known Binary format for hull geometry
 
@@ -17,16 +18,30 @@ pub(crate) mod csv {
 }
 
 pub(crate) mod svg {
+    mod access;
+    mod annotate;
     mod color;
     mod geometry;
+    mod layout;
     mod proj;
+    mod symbols;
     mod writer;
+    pub(crate) use access::*;
+    pub use access::Accessibility;
+    pub(crate) use annotate::*;
+    pub use annotate::{Annotations, MetricSeries};
     pub(crate) use color::*;
+    pub use color::Palette;
     pub(crate) use geometry::*;
+    pub use layout::{Corner, Inset, PanelLayout};
     pub(crate) use proj::*;
+    pub use proj::{MapProjection, SvgOptions};
+    pub(crate) use symbols::*;
+    pub use symbols::{SymbolStyle, Symbols};
     pub(crate) use writer::*;
 }
 
+pub(crate) mod locale;
 pub(crate) mod wkb;
 
 #[cfg(feature = "download")]
@@ -37,3 +52,17 @@ pub(crate) mod parquet;
 
 #[cfg(feature = "pmtiles")]
 pub(crate) mod pmtiles;
+
+/// Write a table to a `.csv` or `.parquet` file, chosen by the file extension.
+pub(crate) fn write_table(df: &mut polars::frame::DataFrame, path: &std::path::Path) -> anyhow::Result<()> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("csv") => csv::write_csv(df, path),
+        #[cfg(feature = "parquet")]
+        Some("parquet") => {
+            use anyhow::Context;
+            std::fs::write(path, parquet::write_parquet_bytes(df)?)
+                .with_context(|| format!("[io::write_table] Failed to write {}", path.display()))
+        }
+        _ => anyhow::bail!("[io::write_table] Unsupported file extension: {} (expected .csv or .parquet)", path.display()),
+    }
+}
@@ -1,9 +1,111 @@
-//! Shapefile format reading operations.
+//! Shapefile format reading and writing operations.
 
-use std::path::Path;
+use std::{collections::HashSet, path::Path};
 
-use anyhow::{Context, Result, bail};
-use shapefile::{self as shp, dbase::Record, Reader, Shape};
+use anyhow::{Context, Result, anyhow, bail, ensure};
+use shapefile::{self as shp, Reader, Shape, Writer, dbase::{self, FieldValue, Record, TableWriterBuilder}};
+
+/// Longest DBF field name, a limit of the dBase format.
+const MAX_FIELD_NAME: usize = 10;
+
+/// Longest DBF text value; longer values are cut off.
+const MAX_TEXT_LEN: usize = 254;
+
+/// Coordinate system of pack geometries (EPSG:4269, NAD83), as written to `.prj` files.
+const NAD83_PRJ: &str = "GEOGCS[\"GCS_North_American_1983\",DATUM[\"D_North_American_1983\",\
+    SPHEROID[\"GRS_1980\",6378137.0,298.257222101]],PRIMEM[\"Greenwich\",0.0],UNIT[\"Degree\",0.0174532925199433]]";
+
+/// Attribute table of a shapefile export: which columns to write, and the DBF field each is
+/// written to. Field names are at most 10 characters (a limit of the format), so long column names
+/// need a shorter field name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShapefileSchema {
+    pub fields: Vec<(String, String)>, // (DBF field name, column it holds)
+}
+
+impl ShapefileSchema {
+    /// Write each of `columns` to a field of the same name.
+    pub fn from_columns(columns: &[&str]) -> Self {
+        Self { fields: columns.iter().map(|column| (column.to_string(), column.to_string())).collect() }
+    }
+
+    /// Add a field `name` holding `column`.
+    pub fn field(mut self, name: &str, column: &str) -> Self {
+        self.fields.push((name.into(), column.into()));
+        self
+    }
+}
+
+/// Values of one DBF field, one per shape.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ShapefileColumn {
+    Text(Vec<String>),
+    Number(Vec<f64>),
+}
+
+impl ShapefileColumn {
+    fn len(&self) -> usize {
+        match self {
+            Self::Text(values) => values.len(),
+            Self::Number(values) => values.len(),
+        }
+    }
+
+    fn value(&self, i: usize) -> FieldValue {
+        match self {
+            Self::Text(values) => FieldValue::Character(Some(values[i].clone())),
+            Self::Number(values) => FieldValue::Numeric(values[i].is_finite().then_some(values[i])),
+        }
+    }
+
+    /// Add a field for the column, sized to fit its values, to `builder`.
+    fn add_field(&self, builder: TableWriterBuilder, name: dbase::FieldName) -> TableWriterBuilder {
+        match self {
+            Self::Text(values) => {
+                let len = values.iter().map(String::len).max().unwrap_or(1).clamp(1, MAX_TEXT_LEN);
+                builder.add_character_field(name, len as u8)
+            }
+            Self::Number(values) if values.iter().all(|value| value.fract() == 0.0 || !value.is_finite()) => {
+                builder.add_numeric_field(name, 19, 0)
+            }
+            Self::Number(_) => builder.add_numeric_field(name, 19, 6),
+        }
+    }
+}
+
+/// Write `shapes` as a polygon shapefile at `path` (the `.shp`, with the `.shx`, `.dbf` and `.prj`
+/// written next to it), with attribute `fields` (DBF field name, one value per shape).
+pub(crate) fn write_shapefile(path: &Path, shapes: &[geo::MultiPolygon<f64>], fields: &[(String, ShapefileColumn)]) -> Result<()> {
+    let mut names = HashSet::new();
+    for (name, column) in fields {
+        ensure!(!name.is_empty() && name.len() <= MAX_FIELD_NAME && name.is_ascii(),
+            "[io::shp] Field name '{name}' must be 1 to {MAX_FIELD_NAME} ASCII characters");
+        ensure!(names.insert(name.to_ascii_uppercase()), "[io::shp] Duplicate field name '{name}'");
+        ensure!(column.len() == shapes.len(),
+            "[io::shp] Field '{name}' has {} values for {} shapes", column.len(), shapes.len());
+    }
+
+    let mut table = TableWriterBuilder::new();
+    for (name, column) in fields {
+        let field_name = name.as_str().try_into().map_err(|e| anyhow!("[io::shp] Invalid field name '{name}': {e}"))?;
+        table = column.add_field(table, field_name);
+    }
+
+    let mut writer = Writer::from_path(path, table)
+        .with_context(|| format!("[io::shp] Failed to create shapefile: {}", path.display()))?;
+    for (i, shape) in shapes.iter().enumerate() {
+        let mut record = Record::default();
+        for (name, column) in fields {
+            record.insert(name.clone(), column.value(i));
+        }
+        writer.write_shape_and_record(&geo_to_shp(shape), &record)
+            .with_context(|| format!("[io::shp] Failed to write shape {i} to {}", path.display()))?;
+    }
+    drop(writer);
+
+    let prj = path.with_extension("prj");
+    std::fs::write(&prj, NAD83_PRJ).with_context(|| format!("[io::shp] Failed to write {}", prj.display()))
+}
 
 /// Coerce a generic shape into an owned multipolygon, raising error if different shape
 pub(crate) fn shape_to_multipolygon(shape: Shape) -> Result<geo::MultiPolygon<f64>> {
@@ -80,14 +182,13 @@ fn shp_to_geo(p: &shp::Polygon) -> geo::MultiPolygon<f64> {
 }
 
 /// Convert geo::MultiPolygon<f64> to shapefile::Polygon
-#[allow(unused)]
 fn geo_to_shp(mp: &geo::MultiPolygon<f64>) -> shp::Polygon {
     /// Create a shapefile::Point
     #[inline] fn shp_point(x: f64, y: f64) -> shp::Point { shp::Point { x, y } }
 
     /// Close a ring of shapefile::Point
     fn ensure_closed(pts: &mut Vec<shp::Point>) {
-        if !pts.is_empty() && pts[0].x != pts[pts.len() - 1].x || pts[0].y != pts[pts.len() - 1].y {
+        if !pts.is_empty() && (pts[0].x != pts[pts.len() - 1].x || pts[0].y != pts[pts.len() - 1].y) {
             pts.push(pts[0]);
         }
     }
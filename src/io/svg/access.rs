@@ -0,0 +1,114 @@
+//! Accessibility features for SVG visualization: document title and description, and pattern
+//! fills that tell districts apart without relying on color.
+
+use std::io::Write;
+
+use anyhow::Result;
+
+use super::{escape_xml, greedy_coloring};
+
+/// Accessibility features of an SVG export, e.g. for maps published on government websites.
+/// District shapes are always labelled for screen readers ("District 3").
+#[derive(Clone, Debug, Default)]
+pub struct Accessibility {
+    /// Document `<title>`, announced by screen readers.
+    pub title: Option<String>,
+    /// Document `<desc>`, a longer text alternative to the map.
+    pub description: Option<String>,
+    /// Hatch each district over its color, with no two bordering districts sharing a hatch where
+    /// possible, so that districts can be told apart in grayscale or with color blindness.
+    pub patterns: bool,
+}
+
+impl Accessibility {
+    /// A titled document with pattern fills.
+    pub fn titled(title: &str) -> Self {
+        Self { title: Some(title.into()), description: None, patterns: true }
+    }
+}
+
+/// Pattern tile size, in pixels.
+const TILE: f64 = 8.0;
+
+/// Marks drawn over the fill color of each pattern, on an 8×8 tile. The first is a plain fill.
+const HATCHES: &[&str] = &[
+    "",
+    r#"<path d="M-2,2 L2,-2 M0,8 L8,0 M6,10 L10,6"/>"#,
+    r#"<path d="M-2,6 L2,10 M0,0 L8,8 M6,-2 L10,2"/>"#,
+    r#"<path d="M0,4 L8,4"/>"#,
+    r#"<path d="M4,0 L4,8"/>"#,
+    r#"<path d="M0,4 L8,4 M4,0 L4,8"/>"#,
+    r#"<path d="M-2,2 L2,-2 M0,8 L8,0 M6,10 L10,6 M-2,6 L2,10 M0,0 L8,8 M6,-2 L10,2"/>"#,
+    r#"<circle cx="4" cy="4" r="1.5" style="stroke:none"/>"#,
+];
+
+/// Write the document `<title>` and `<desc>` of `access`, if any. Must directly follow the
+/// opening `<svg>` tag, which references them by id (see [`aria_attributes`]).
+pub(crate) fn write_title<W: Write>(writer: &mut W, access: &Accessibility) -> Result<()> {
+    if let Some(title) = &access.title { writeln!(writer, r#"<title id="map-title">{}</title>"#, escape_xml(title))? }
+    if let Some(description) = &access.description { writeln!(writer, r#"<desc id="map-desc">{}</desc>"#, escape_xml(description))? }
+    Ok(())
+}
+
+/// ARIA attributes of the root `<svg>` element referencing the title and description of `access`.
+pub(crate) fn aria_attributes(access: &Accessibility) -> String {
+    let mut attributes = String::from(r#" role="group""#);
+    if access.title.is_some() { attributes.push_str(r#" aria-labelledby="map-title""#) }
+    if access.description.is_some() { attributes.push_str(r#" aria-describedby="map-desc""#) }
+    attributes
+}
+
+/// ARIA attributes labelling one shape or group, e.g. a district.
+pub(crate) fn aria_label(role: &str, label: &str) -> String {
+    format!(r#" role="{role}" aria-label="{}""#, escape_xml(label))
+}
+
+/// Fill of each part referencing its pattern written by [`write_fill_patterns`] (index 0,
+/// unassigned, keeps its fill).
+pub(crate) fn pattern_fills(fills: &[String]) -> Vec<String> {
+    fills.iter().enumerate()
+        .map(|(part, fill)| if part == 0 { fill.clone() } else { format!("url(#fill-{part})") })
+        .collect()
+}
+
+/// Write one `<pattern>` per part hatching its color in `fills` (index 0, unassigned, is skipped),
+/// choosing hatches so that parts bordering in `adjacency` differ where possible.
+pub(crate) fn write_fill_patterns<W: Write>(writer: &mut W, fills: &[String], adjacency: &[Vec<u32>]) -> Result<()> {
+    let hatches = greedy_coloring(adjacency, HATCHES.len());
+    writeln!(writer, "<defs>")?;
+    for (part, fill) in fills.iter().enumerate().skip(1) {
+        writeln!(writer, r#"<pattern id="fill-{part}" width="{TILE}" height="{TILE}" patternUnits="userSpaceOnUse" style="stroke:#111827;stroke-width:1.2;stroke-opacity:0.7;fill:#111827;fill-opacity:0.7"><rect width="{TILE}" height="{TILE}" style="fill:{fill};fill-opacity:1;stroke:none"/>{}</pattern>"#, HATCHES[hatches[part]])?;
+    }
+    writeln!(writer, "</defs>")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bordering_parts_get_different_patterns() {
+        // Unassigned part 0, then three districts in a row.
+        let adjacency = vec![vec![], vec![2], vec![1, 3], vec![2]];
+        let fills = ["", "#ff0000", "#00ff00", "#0000ff"].map(String::from);
+        let mut svg = Vec::new();
+        write_fill_patterns(&mut svg, &fills, &adjacency).unwrap();
+        assert_eq!(pattern_fills(&fills)[1..], ["url(#fill-1)", "url(#fill-2)", "url(#fill-3)"]);
+
+        let svg = String::from_utf8(svg).unwrap();
+        let hatch = |part: usize| {
+            let start = svg.find(&format!(r#"id="fill-{part}""#)).unwrap();
+            svg[start..].split_once("</pattern>").unwrap().0.split_once("/>").unwrap().1.to_string()
+        };
+        assert_ne!(hatch(1), hatch(2));
+        assert_ne!(hatch(2), hatch(3));
+        assert!(svg.contains("fill:#0000ff"));
+
+        let access = Accessibility { title: Some("Plan <A>".into()), description: None, patterns: false };
+        let mut header = Vec::new();
+        write_title(&mut header, &access).unwrap();
+        assert_eq!(String::from_utf8(header).unwrap(), "<title id=\"map-title\">Plan &lt;A></title>\n");
+        assert_eq!(aria_attributes(&access), r#" role="group" aria-labelledby="map-title""#);
+    }
+}
@@ -0,0 +1,149 @@
+//! Map annotations (legend, scale bar, north arrow, metrics box) for SVG visualization.
+
+use std::io::Write;
+
+use anyhow::Result;
+use geo::Coord;
+
+use crate::io::locale::Locale;
+
+use super::{Viewport, escape_xml};
+
+/// Series used to compute the metrics box of a plan export.
+#[derive(Clone, Debug)]
+pub struct MetricSeries {
+    pub pop: String,
+    pub dem: String,
+    pub rep: String,
+}
+
+impl Default for MetricSeries {
+    fn default() -> Self {
+        Self { pop: "T_20_CENS_Total".into(), dem: "E_20_PRES_Dem".into(), rep: "E_20_PRES_Rep".into() }
+    }
+}
+
+/// Map furniture drawn in a band below the map.
+/// The legend and metrics box only apply to plan exports.
+#[derive(Clone, Debug, Default)]
+pub struct Annotations {
+    /// Swatch of each district's fill color.
+    pub legend: bool,
+    pub scale_bar: bool,
+    pub north_arrow: bool,
+    /// Population deviation and efficiency gap computed from these series.
+    pub metrics: Option<MetricSeries>,
+    /// Language of the labels and format of the numbers drawn.
+    pub locale: Locale,
+}
+
+impl Annotations {
+    /// Every annotation, with the default metric series.
+    pub fn all() -> Self {
+        Self { legend: true, scale_bar: true, north_arrow: true, metrics: Some(MetricSeries::default()), locale: Locale::default() }
+    }
+
+    fn is_empty(&self) -> bool {
+        !self.legend && !self.scale_bar && !self.north_arrow && self.metrics.is_none()
+    }
+}
+
+const ROW_HEIGHT: f64 = 18.0;
+const SWATCH_WIDTH: f64 = 48.0;
+const TOOLS_WIDTH: f64 = 240.0;
+const METRICS_WIDTH: f64 = 240.0;
+
+/// Height of the band needed below a `width`-pixel map for `annotations` with the given number of
+/// legend entries and metric lines (0 if nothing is drawn).
+pub(crate) fn annotation_height(annotations: &Annotations, width: f64, legend_len: usize, metrics_len: usize) -> f64 {
+    if annotations.is_empty() { return 0.0 }
+    let per_row = ((width - TOOLS_WIDTH - METRICS_WIDTH) / SWATCH_WIDTH).floor().max(1.0) as usize;
+    let legend_rows = if annotations.legend { legend_len.div_ceil(per_row) } else { 0 };
+    let metrics_rows = if annotations.metrics.is_some() { metrics_len } else { 0 };
+    (legend_rows.max(metrics_rows) as f64 * ROW_HEIGHT + 16.0).max(64.0)
+}
+
+/// Draw `annotations` in the band below the map, which must have been reserved with
+/// [`Viewport::with_footer`]. `legend` is (label, fill) per entry; `metrics` is (name, value) per line.
+pub(crate) fn write_annotations<W: Write>(
+    writer: &mut W,
+    vp: &Viewport,
+    annotations: &Annotations,
+    legend: &[(String, String)],
+    metrics: &[(String, String)],
+) -> Result<()> {
+    if annotations.is_empty() { return Ok(()) }
+    let top = vp.height - vp.footer;
+    writeln!(writer, r#"<g class="annotations" transform="translate({:.3},{top:.3})" style="font:12px sans-serif;fill:#111827">"#, vp.margin)?;
+
+    let center = vp.bounds.center();
+    if annotations.north_arrow {
+        // Point toward the projected direction of north at the map center.
+        let (x0, y0) = vp.project(&center);
+        let (x1, y1) = vp.project(&Coord { x: center.x, y: center.y + 0.01 });
+        let angle = (x1 - x0).atan2(y0 - y1).to_degrees();
+        writeln!(writer, r#"<g class="north-arrow" transform="translate(16,32) rotate({angle:.2})"><path d="M0,-18 L7,8 L0,3 L-7,8 Z"/><text x="0" y="-22" text-anchor="middle">N</text></g>"#)?;
+    }
+
+    if annotations.scale_bar {
+        // Pixels per meter along the parallel through the map center.
+        let (x0, _) = vp.project(&center);
+        let (x1, _) = vp.project(&Coord { x: center.x + 0.01, y: center.y });
+        let meters = 0.01 * 111_320.0 * center.y.to_radians().cos();
+        let px_per_m = (x1 - x0).abs() / meters;
+        for (row, (unit, unit_m)) in [("km", 1000.0), ("mi", 1609.344)].into_iter().enumerate() {
+            let length = nice_length(160.0 / px_per_m / unit_m);
+            let px = length * unit_m * px_per_m;
+            let label = annotations.locale.format_number(length, (-length.log10()).ceil().max(0.0) as usize);
+            let y = 24.0 + row as f64 * 22.0;
+            writeln!(writer, r#"<g class="scale-bar" transform="translate(44,{y})"><path d="M0,-4 L0,0 L{px:.3},0 L{px:.3},-4" style="fill:none;stroke:#111827;stroke-width:1.5"/><text x="{:.3}" y="-6">{label} {unit}</text></g>"#, px + 6.0)?;
+        }
+    }
+
+    if annotations.legend {
+        let left = TOOLS_WIDTH;
+        let per_row = ((vp.width - TOOLS_WIDTH - METRICS_WIDTH) / SWATCH_WIDTH).floor().max(1.0) as usize;
+        writeln!(writer, r#"<g class="legend">"#)?;
+        for (i, (label, fill)) in legend.iter().enumerate() {
+            let x = left + (i % per_row) as f64 * SWATCH_WIDTH;
+            let y = 8.0 + (i / per_row) as f64 * ROW_HEIGHT;
+            writeln!(writer, r#"<rect x="{x:.3}" y="{y:.3}" width="14" height="14" style="fill:{fill};stroke:#111827;stroke-width:0.5"/><text x="{:.3}" y="{:.3}">{}</text>"#, x + 18.0, y + 11.0, escape_xml(label))?;
+        }
+        writeln!(writer, "</g>")?;
+    }
+
+    if annotations.metrics.is_some() {
+        let left = vp.width - 2.0 * vp.margin - METRICS_WIDTH;
+        let height = metrics.len() as f64 * ROW_HEIGHT + 8.0;
+        writeln!(writer, r#"<g class="metrics"><rect x="{left:.3}" y="4" width="{METRICS_WIDTH}" height="{height}" style="fill:#f9fafb;stroke:#111827;stroke-width:0.5"/>"#)?;
+        for (i, (name, value)) in metrics.iter().enumerate() {
+            let y = 4.0 + (i + 1) as f64 * ROW_HEIGHT;
+            writeln!(writer, r#"<text x="{:.3}" y="{y:.3}">{}</text><text x="{:.3}" y="{y:.3}" text-anchor="end" style="font-weight:600">{}</text>"#,
+                left + 8.0, escape_xml(name), left + METRICS_WIDTH - 8.0, escape_xml(value))?;
+        }
+        writeln!(writer, "</g>")?;
+    }
+
+    writeln!(writer, "</g>")?;
+    Ok(())
+}
+
+/// Largest 1, 2, or 5 × 10^k not exceeding `max`.
+fn nice_length(max: f64) -> f64 {
+    if !max.is_finite() || max <= 0.0 { return 1.0 }
+    let base = 10f64.powf(max.log10().floor());
+    [5.0, 2.0, 1.0].into_iter().map(|m| m * base).find(|&v| v <= max).unwrap_or(base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nice_lengths_round_down() {
+        assert_eq!(nice_length(7.3), 5.0);
+        assert_eq!(nice_length(0.31), 0.2);
+        assert_eq!(nice_length(100.0), 100.0);
+        assert_eq!(nice_length(f64::NAN), 1.0);
+    }
+}
@@ -3,7 +3,7 @@
 use std::fmt;
 
 /// Simple RGB color.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) struct Rgb {
     pub(crate) r: u8,
     pub(crate) g: u8,
@@ -84,3 +84,182 @@ const GOLDEN_ANGLE: f64 = 137.50776405;
 pub(crate) fn golden_angle_color(index: usize) -> Hsl {
     Hsl { h: ((index as f64) * GOLDEN_ANGLE) % 360.0, s: 0.70, l: 0.55 }
 }
+
+/// Sequential yellow-orange-red ramp (ColorBrewer YlOrRd) for t in [0.0, 1.0].
+pub(crate) fn heat_color(t: f64) -> Rgb {
+    const STOPS: [Rgb; 5] = [hex(0xffffb2), hex(0xfecc5c), hex(0xfd8d3c), hex(0xf03b20), hex(0xbd0026)];
+    let t = if t.is_finite() { t.clamp(0.0, 1.0) } else { 0.0 } * (STOPS.len() - 1) as f64;
+    let (i, f) = ((t.floor() as usize).min(STOPS.len() - 2), t - t.floor().min((STOPS.len() - 2) as f64));
+    let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * f).round() as u8;
+    let (a, b) = (STOPS[i], STOPS[i + 1]);
+    Rgb { r: lerp(a.r, b.r), g: lerp(a.g, b.g), b: lerp(a.b, b.b) }
+}
+
+/// Shorthand for an `Rgb` from a 0xRRGGBB literal.
+const fn hex(rgb: u32) -> Rgb {
+    Rgb { r: (rgb >> 16) as u8, g: (rgb >> 8) as u8, b: rgb as u8 }
+}
+
+impl Rgb {
+    /// Format as a CSS hex color: #rrggbb
+    pub(crate) fn to_hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+/// Categorical palette for district fills.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Palette {
+    /// Okabe-Ito, safe for all common forms of color blindness (black swapped for gray).
+    #[default]
+    OkabeIto,
+    /// ColorBrewer Set2 (colorblind-safe, 8 colors).
+    Set2,
+    /// ColorBrewer Dark2 (colorblind-safe, 8 colors).
+    Dark2,
+    /// ColorBrewer Paired (12 colors).
+    Paired,
+    /// ColorBrewer Set3 (12 colors).
+    Set3,
+}
+
+impl Palette {
+    /// Get the string representation of the Palette.
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Palette::OkabeIto => "okabe-ito",
+            Palette::Set2 => "set2",
+            Palette::Dark2 => "dark2",
+            Palette::Paired => "paired",
+            Palette::Set3 => "set3",
+        }
+    }
+
+    /// Create a Palette from a string representation.
+    pub fn from_name(string: &str) -> Option<Palette> {
+        match string.to_lowercase().replace('_', "-").as_str() {
+            "okabe-ito" | "okabeito" => Some(Palette::OkabeIto),
+            "set2" => Some(Palette::Set2),
+            "dark2" => Some(Palette::Dark2),
+            "paired" => Some(Palette::Paired),
+            "set3" => Some(Palette::Set3),
+            _ => None,
+        }
+    }
+
+    /// Colors of the palette, in order.
+    pub(crate) fn colors(&self) -> &'static [Rgb] {
+        const OKABE_ITO: &[Rgb] = &[
+            hex(0xe69f00), hex(0x56b4e9), hex(0x009e73), hex(0xf0e442),
+            hex(0x0072b2), hex(0xd55e00), hex(0xcc79a7), hex(0x999999),
+        ];
+        const SET2: &[Rgb] = &[
+            hex(0x66c2a5), hex(0xfc8d62), hex(0x8da0cb), hex(0xe78ac3),
+            hex(0xa6d854), hex(0xffd92f), hex(0xe5c494), hex(0xb3b3b3),
+        ];
+        const DARK2: &[Rgb] = &[
+            hex(0x1b9e77), hex(0xd95f02), hex(0x7570b3), hex(0xe7298a),
+            hex(0x66a61e), hex(0xe6ab02), hex(0xa6761d), hex(0x666666),
+        ];
+        const PAIRED: &[Rgb] = &[
+            hex(0xa6cee3), hex(0x1f78b4), hex(0xb2df8a), hex(0x33a02c), hex(0xfb9a99), hex(0xe31a1c),
+            hex(0xfdbf6f), hex(0xff7f00), hex(0xcab2d6), hex(0x6a3d9a), hex(0xffff99), hex(0xb15928),
+        ];
+        const SET3: &[Rgb] = &[
+            hex(0x8dd3c7), hex(0xffffb3), hex(0xbebada), hex(0xfb8072), hex(0x80b1d3), hex(0xfdb462),
+            hex(0xb3de69), hex(0xfccde5), hex(0xd9d9d9), hex(0xbc80bd), hex(0xccebc5), hex(0xffed6f),
+        ];
+        match self {
+            Palette::OkabeIto => OKABE_ITO,
+            Palette::Set2 => SET2,
+            Palette::Dark2 => DARK2,
+            Palette::Paired => PAIRED,
+            Palette::Set3 => SET3,
+        }
+    }
+}
+
+/// Color each node of a graph from `palette` so that no two neighbors share a color where avoidable
+/// (see [`greedy_coloring`]).
+pub(crate) fn color_graph(adjacency: &[Vec<u32>], palette: Palette) -> Vec<Rgb> {
+    let colors = palette.colors();
+    greedy_coloring(adjacency, colors.len()).into_iter().map(|c| colors[c]).collect()
+}
+
+/// Assign each node of a graph one of `k` colors (indices) so that no two neighbors share a color
+/// where avoidable.
+///
+/// Nodes are colored greedily in smallest-last (degeneracy) order, which needs at most 6 colors on a
+/// planar graph; each node takes the least-used color free among its neighbors, so the result
+/// depends only on the adjacency and is the same on every call. If every color is taken by a
+/// neighbor, the one shared with the fewest neighbors is reused.
+pub(crate) fn greedy_coloring(adjacency: &[Vec<u32>], k: usize) -> Vec<usize> {
+    let n = adjacency.len();
+
+    // Smallest-last order: repeatedly remove a node of minimum remaining degree (lowest id on ties).
+    let mut degree = adjacency.iter().map(|adj| adj.len()).collect::<Vec<_>>();
+    let mut removed = vec![false; n];
+    let mut order = Vec::with_capacity(n);
+    for _ in 0..n {
+        let node = (0..n).filter(|&v| !removed[v]).min_by_key(|&v| degree[v]).unwrap();
+        removed[node] = true;
+        order.push(node);
+        for &v in &adjacency[node] { degree[v as usize] = degree[v as usize].saturating_sub(1) }
+    }
+
+    let mut assigned: Vec<Option<usize>> = vec![None; n];
+    let mut uses = vec![0usize; k];
+    for &node in order.iter().rev() {
+        let mut conflicts = vec![0usize; k];
+        for &v in &adjacency[node] {
+            if let Some(c) = assigned[v as usize] { conflicts[c] += 1 }
+        }
+        let color = (0..k).min_by_key(|&c| (conflicts[c], uses[c])).unwrap();
+        assigned[node] = Some(color);
+        uses[color] += 1;
+    }
+
+    assigned.into_iter().map(|c| c.unwrap()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A wheel: hub 0 surrounded by a cycle of `n` rim nodes.
+    fn wheel(n: u32) -> Vec<Vec<u32>> {
+        let mut adjacency = vec![(1..=n).collect::<Vec<_>>()];
+        for i in 1..=n {
+            adjacency.push(vec![0, (i + n - 2) % n + 1, i % n + 1]);
+        }
+        adjacency
+    }
+
+    #[test]
+    fn neighbors_never_share_a_color() {
+        let adjacency = wheel(7);
+        let colors = color_graph(&adjacency, Palette::OkabeIto);
+        for (u, adj) in adjacency.iter().enumerate() {
+            for &v in adj {
+                assert_ne!(colors[u], colors[v as usize], "{u} and {v} share a color");
+            }
+        }
+        assert_eq!(colors, color_graph(&adjacency, Palette::OkabeIto));
+    }
+
+    #[test]
+    fn parses_palette_names() {
+        assert_eq!(Palette::from_name("Okabe_Ito"), Some(Palette::OkabeIto));
+        assert_eq!(Palette::from_name(Palette::Dark2.to_str()), Some(Palette::Dark2));
+        assert_eq!(Palette::from_name("viridis"), None);
+        assert_eq!(hex(0x0072b2).to_hex(), "#0072b2");
+    }
+
+    #[test]
+    fn heat_ramp_hits_end_stops() {
+        assert_eq!(heat_color(0.0), hex(0xffffb2));
+        assert_eq!(heat_color(1.0), hex(0xbd0026));
+        assert_eq!(heat_color(0.5), hex(0xfd8d3c));
+        assert_eq!(heat_color(f64::NAN), heat_color(0.0));
+    }
+}
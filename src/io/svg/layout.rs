@@ -0,0 +1,137 @@
+//! Multi-panel figure layouts for SVG visualization.
+
+use std::io::Write;
+
+use anyhow::Result;
+use geo::{Coord, Rect};
+
+use super::{SvgOptions, Viewport, escape_xml};
+
+/// Corner of a panel where a zoom inset is placed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+/// A zoomed-in view of a small area (e.g. a dense metro), drawn in a corner of each panel.
+#[derive(Clone, Debug)]
+pub struct Inset {
+    /// Area to zoom into, in lon/lat.
+    pub bounds: Rect<f64>,
+    /// Width of the inset in pixels.
+    pub width: f64,
+    pub corner: Corner,
+    /// Optional caption drawn inside the inset.
+    pub label: Option<String>,
+}
+
+impl Inset {
+    /// A 200px inset in the bottom-right corner over the given lon/lat box.
+    pub fn new(min_lon: f64, min_lat: f64, max_lon: f64, max_lat: f64) -> Self {
+        let bounds = Rect::new(Coord { x: min_lon, y: min_lat }, Coord { x: max_lon, y: max_lat });
+        Self { bounds, width: 200.0, corner: Corner::default(), label: None }
+    }
+}
+
+/// Grid of small-multiple panels sharing one projection and scale, e.g. one panel per plan.
+#[derive(Clone, Debug)]
+pub struct PanelLayout {
+    /// Panels per row.
+    pub columns: usize,
+    /// Projection and size of each panel.
+    pub panel: SvgOptions,
+    /// Space between and around panels, in pixels.
+    pub gap: f64,
+    /// Zoom insets drawn in every panel.
+    pub insets: Vec<Inset>,
+}
+
+impl Default for PanelLayout {
+    fn default() -> Self {
+        Self { columns: 2, panel: SvgOptions { width: 600.0, ..Default::default() }, gap: 16.0, insets: vec![] }
+    }
+}
+
+/// Height reserved above each panel's map for its title.
+const TITLE_HEIGHT: f64 = 28.0;
+
+/// Margin between an inset and the edge of its panel.
+const INSET_PADDING: f64 = 8.0;
+
+impl PanelLayout {
+    /// Write a figure with one panel per title, all drawn with the viewport `vp`.
+    /// `draw(writer, index, vp)` draws the contents of panel `index` (main map or inset) in `vp`.
+    pub(crate) fn write<W: Write>(
+        &self,
+        writer: &mut W,
+        vp: &Viewport,
+        titles: &[&str],
+        mut draw: impl FnMut(&mut W, usize, &Viewport) -> Result<()>,
+    ) -> Result<()> {
+        let columns = self.columns.clamp(1, titles.len().max(1));
+        let rows = titles.len().div_ceil(columns);
+        let title_height = if titles.iter().any(|title| !title.is_empty()) { TITLE_HEIGHT } else { 0.0 };
+        let (panel_width, panel_height) = (vp.width, vp.height + title_height);
+        let width = columns as f64 * (panel_width + self.gap) + self.gap;
+        let height = rows as f64 * (panel_height + self.gap) + self.gap;
+
+        writeln!(writer, r##"<?xml version="1.0" encoding="UTF-8" standalone="no"?>"##)?;
+        writeln!(writer, r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" data-projection="{}"{}>"##,
+            vp.projection.to_str(), super::aria_attributes(&self.panel.accessibility))?;
+        super::write_title(writer, &self.panel.accessibility)?;
+        writeln!(writer, r##"<rect width="100%" height="100%" fill="#ffffff"/>"##)?;
+        super::write_svg_styles(writer)?;
+
+        for (i, title) in titles.iter().enumerate() {
+            let x = self.gap + (i % columns) as f64 * (panel_width + self.gap);
+            let y = self.gap + (i / columns) as f64 * (panel_height + self.gap);
+            let label = if title.is_empty() { String::new() } else { super::aria_label("group", title) };
+            writeln!(writer, r#"<svg class="panel" id="panel-{i}" x="{x:.3}" y="{y:.3}" width="{panel_width:.3}" height="{panel_height:.3}"{label}>"#)?;
+            if !title.is_empty() {
+                writeln!(writer, r#"<text class="title" x="{:.3}" y="20" text-anchor="middle">{}</text>"#, panel_width / 2.0, escape_xml(title))?;
+            }
+            writeln!(writer, r#"<g transform="translate(0,{title_height})">"#)?;
+            draw(writer, i, vp)?;
+            for inset in &self.insets {
+                write_inset_area(writer, vp, inset.bounds)?;
+            }
+            for inset in &self.insets {
+                let inset_vp = vp.zoomed(inset.bounds, inset.width, 4.0);
+                let (ix, iy) = match inset.corner {
+                    Corner::TopLeft => (INSET_PADDING, INSET_PADDING),
+                    Corner::TopRight => (vp.width - inset_vp.width - INSET_PADDING, INSET_PADDING),
+                    Corner::BottomLeft => (INSET_PADDING, vp.height - inset_vp.height - INSET_PADDING),
+                    Corner::BottomRight => (vp.width - inset_vp.width - INSET_PADDING, vp.height - inset_vp.height - INSET_PADDING),
+                };
+                // A nested <svg> clips everything outside the zoomed area.
+                writeln!(writer, r#"<svg class="inset" x="{ix:.3}" y="{iy:.3}" width="{:.3}" height="{:.3}">"#, inset_vp.width, inset_vp.height)?;
+                writeln!(writer, r##"<rect width="100%" height="100%" fill="#ffffff"/>"##)?;
+                draw(writer, i, &inset_vp)?;
+                writeln!(writer, r#"<rect class="inset-frame" width="100%" height="100%"/>"#)?;
+                if let Some(label) = &inset.label {
+                    writeln!(writer, r#"<text class="title" x="6" y="18" style="font-size:12px">{}</text>"#, escape_xml(label))?;
+                }
+                writeln!(writer, "</svg>")?;
+            }
+            writeln!(writer, "</g>")?;
+            writeln!(writer, "</svg>")?;
+        }
+
+        super::write_svg_footer(writer)
+    }
+}
+
+/// Outline the area covered by a zoom inset on the main map.
+fn write_inset_area<W: Write>(writer: &mut W, vp: &Viewport, bounds: Rect<f64>) -> Result<()> {
+    let vp = *vp;
+    let (lo, hi) = (bounds.min(), bounds.max());
+    let corners = [lo, Coord { x: hi.x, y: lo.y }, hi, Coord { x: lo.x, y: hi.y }, lo];
+    write!(writer, r#"<path class="inset-area" d=""#)?;
+    super::write_ring_data(writer, &corners, &move |coord: &Coord<f64>| vp.project(coord))?;
+    writeln!(writer, r#""/>"#)?;
+    Ok(())
+}
@@ -1,9 +1,167 @@
 //! Projection utilities for SVG visualization.
 
-use std::io::Write;
+use std::{borrow::Borrow, fmt::Display, io::Write};
 
 use anyhow::{Ok, Result};
-use geo::{Coord, CoordsIter, LineString, MultiPolygon, Point, Rect};
+use geo::{Coord, MultiPolygon, Point, Rect};
+
+use super::{Accessibility, Annotations, Symbols, write_path_data};
+
+/// Map projection used when drawing lon/lat geometry to SVG.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MapProjection {
+    /// Plate carrée: lon/lat used directly as x/y.
+    #[default]
+    Equirectangular,
+    /// Spherical Mercator (conformal).
+    Mercator,
+    /// Albers equal-area conic, with standard parallels at 1/6 and 5/6 of the map's latitude range
+    /// and centered on the map, so each state gets its own low-distortion parallels.
+    Albers,
+    /// National Albers (parallels 29.5°N and 45.5°N) with Alaska and Hawaii moved into insets
+    /// below the lower 48.
+    AlbersUsa,
+}
+
+impl MapProjection {
+    /// Get the string representation of the MapProjection.
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            MapProjection::Equirectangular => "equirectangular",
+            MapProjection::Mercator => "mercator",
+            MapProjection::Albers => "albers",
+            MapProjection::AlbersUsa => "albers-usa",
+        }
+    }
+
+    /// Create a MapProjection from a string representation.
+    pub fn from_name(string: &str) -> Option<MapProjection> {
+        match string.to_lowercase().replace('_', "-").as_str() {
+            "equirectangular" | "plate-carree" | "lonlat" => Some(MapProjection::Equirectangular),
+            "mercator" => Some(MapProjection::Mercator),
+            "albers" => Some(MapProjection::Albers),
+            "albers-usa" | "albersusa" => Some(MapProjection::AlbersUsa),
+            _ => None,
+        }
+    }
+}
+
+/// Rendering options shared by the SVG exports.
+#[derive(Clone, Debug)]
+pub struct SvgOptions {
+    /// Projection from lon/lat to the canvas.
+    pub projection: MapProjection,
+    /// Canvas width in pixels; the height follows from the projected aspect ratio.
+    pub width: f64,
+    /// Blank margin around the drawing, in pixels.
+    pub margin: f64,
+    /// Legend, scale bar, north arrow, and metrics box drawn below the map.
+    pub annotations: Annotations,
+    /// Dot-density or proportional symbols drawn over the map.
+    pub symbols: Option<Symbols>,
+    /// Document title and description, and pattern fills for plan exports.
+    pub accessibility: Accessibility,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self { projection: MapProjection::Equirectangular, width: 1200.0, margin: 10.0, annotations: Annotations::default(), symbols: None, accessibility: Accessibility::default() }
+    }
+}
+
+/// Spherical Albers equal-area conic projection, in radians on the unit sphere (y up).
+#[derive(Clone, Copy, Debug)]
+struct Albers { lon0: f64, n: f64, c: f64, rho0: f64 }
+
+impl Albers {
+    /// Albers projection centered on `(lon0, lat0)` with standard parallels `lat1`, `lat2` (degrees).
+    fn new(lon0: f64, lat0: f64, lat1: f64, lat2: f64) -> Self {
+        let (phi0, phi1, phi2) = (lat0.to_radians(), lat1.to_radians(), lat2.to_radians());
+        let n = (phi1.sin() + phi2.sin()) / 2.0;
+        let c = phi1.cos().powi(2) + 2.0 * n * phi1.sin();
+        let rho0 = if n.abs() < 1e-9 { 0.0 } else { (c - 2.0 * n * phi0.sin()).max(0.0).sqrt() / n };
+        Self { lon0, n, c, rho0 }
+    }
+
+    fn forward(&self, lon: f64, lat: f64) -> (f64, f64) {
+        let lambda = (lon - self.lon0).to_radians();
+        let phi = lat.to_radians();
+        // Parallels symmetric about the equator degenerate to the cylindrical equal-area projection.
+        if self.n.abs() < 1e-9 { return (lambda, phi.sin()) }
+        let rho = (self.c - 2.0 * self.n * phi.sin()).max(0.0).sqrt() / self.n;
+        let theta = self.n * lambda;
+        (rho * theta.sin(), self.rho0 - rho * theta.cos())
+    }
+}
+
+/// A `MapProjection` with its parameters resolved for a particular map.
+#[derive(Clone, Copy, Debug)]
+enum Planar {
+    Identity,
+    Mercator,
+    Albers(Albers),
+    AlbersUsa { lower48: Albers, alaska: Albers, hawaii: Albers },
+}
+
+impl Planar {
+    fn new(projection: MapProjection, bounds: Rect<f64>) -> Self {
+        match projection {
+            MapProjection::Equirectangular => Planar::Identity,
+            MapProjection::Mercator => Planar::Mercator,
+            MapProjection::Albers => {
+                let (south, north) = (bounds.min().y, bounds.max().y);
+                let span = north - south;
+                let center = bounds.center();
+                Planar::Albers(Albers::new(center.x, center.y, south + span / 6.0, north - span / 6.0))
+            }
+            MapProjection::AlbersUsa => Planar::AlbersUsa {
+                lower48: Albers::new(-96.6, 38.7, 29.5, 45.5),
+                alaska: Albers::new(-156.0, 58.5, 55.0, 65.0),
+                hawaii: Albers::new(-160.0, 19.9, 8.0, 18.0),
+            },
+        }
+    }
+
+    /// Project lon/lat (degrees) to planar coordinates (y up).
+    fn forward(&self, coord: &Coord<f64>) -> (f64, f64) {
+        let (lon, lat) = (coord.x, coord.y);
+        match self {
+            Planar::Identity => (lon, lat),
+            Planar::Mercator => {
+                let phi = lat.clamp(-85.0, 85.0).to_radians();
+                (lon.to_radians(), (std::f64::consts::FRAC_PI_4 + phi / 2.0).tan().ln())
+            }
+            Planar::Albers(albers) => albers.forward(lon, lat),
+            Planar::AlbersUsa { lower48, alaska, hawaii } => {
+                // Insets sit below the lower 48 (offsets from the US-Albers layout used by d3 and Census maps).
+                if lat > 50.0 && !(-129.0..=170.0).contains(&lon) {
+                    let (x, y) = alaska.forward(if lon > 0.0 { lon - 360.0 } else { lon }, lat);
+                    (0.35 * x - 0.307, 0.35 * y - 0.201)
+                } else if lat < 30.0 && lon < -150.0 {
+                    let (x, y) = hawaii.forward(lon, lat);
+                    (x - 0.205, y - 0.212)
+                } else {
+                    lower48.forward(lon, lat)
+                }
+            }
+        }
+    }
+
+    /// Bounds of the projected rectangles, or `None` if there are none. Each rectangle's image is
+    /// bounded by its projected edges, which are sampled at the corners and midpoints.
+    fn extent(&self, rects: impl IntoIterator<Item = Rect<f64>>) -> Option<Rect<f64>> {
+        let (mut min, mut max) = ((f64::INFINITY, f64::INFINITY), (f64::NEG_INFINITY, f64::NEG_INFINITY));
+        for rect in rects {
+            let (lo, hi, mid) = (rect.min(), rect.max(), rect.center());
+            for (x, y) in [(lo.x, lo.y), (mid.x, lo.y), (hi.x, lo.y), (hi.x, mid.y), (hi.x, hi.y), (mid.x, hi.y), (lo.x, hi.y), (lo.x, mid.y)] {
+                let (px, py) = self.forward(&Coord { x, y });
+                min = (min.0.min(px), min.1.min(py));
+                max = (max.0.max(px), max.1.max(py));
+            }
+        }
+        (min.0 <= max.0).then(|| Rect::new(Coord { x: min.0, y: min.1 }, Coord { x: max.0, y: max.1 }))
+    }
+}
 
 /// Precomputed mapping from geographic bounds to SVG canvas coordinates.
 #[derive(Clone, Copy)]
@@ -12,22 +170,59 @@ pub(crate) struct Viewport {
     pub height: f64,
     pub scale: f64,
     pub margin: f64,
+    /// Geographic (lon/lat) bounds of the map.
     pub bounds: Rect<f64>,
+    pub projection: MapProjection,
+    /// Height of the band below the map reserved for annotations (included in `height`).
+    pub footer: f64,
+    planar: Planar,
+    /// Bounds of the map after projection.
+    extent: Rect<f64>,
 }
 
 impl Viewport {
     /// Compute a viewport from geographic bounds, a canvas width, and a margin (all in pixels).
     pub(crate) fn new(bounds: Rect<f64>, width: f64, margin: f64) -> Self {
-        let scale = (width - 2.0 * margin) / bounds.width();
-        let height = bounds.height() * scale + 2.0 * margin;
-        Self { width, height, scale, margin, bounds }
+        Self::fit(bounds, bounds, MapProjection::Equirectangular, Planar::Identity, width, margin)
+    }
+
+    /// Compute a projected viewport fitting every unit of `region`.
+    pub(crate) fn for_region(region: &geograph::Region, options: &SvgOptions) -> Self {
+        let bounds = region.bounds_all();
+        if options.projection == MapProjection::Equirectangular {
+            return Self::new(bounds, options.width, options.margin);
+        }
+        let planar = Planar::new(options.projection, bounds);
+        let extent = planar.extent(region.unit_ids().map(|u| region.bounds(u))).unwrap_or(bounds);
+        Self::fit(bounds, extent, options.projection, planar, options.width, options.margin)
+    }
+
+    /// A viewport over the geographic box `bounds`, drawn with the same projection as `self`
+    /// (e.g. a zoom inset of a dense metro).
+    pub(crate) fn zoomed(&self, bounds: Rect<f64>, width: f64, margin: f64) -> Self {
+        let extent = self.planar.extent([bounds]).unwrap();
+        Self::fit(bounds, extent, self.projection, self.planar, width, margin)
+    }
+
+    fn fit(bounds: Rect<f64>, extent: Rect<f64>, projection: MapProjection, planar: Planar, width: f64, margin: f64) -> Self {
+        let scale = (width - 2.0 * margin) / extent.width();
+        let height = extent.height() * scale + 2.0 * margin;
+        Self { width, height, scale, margin, bounds, projection, footer: 0.0, planar, extent }
+    }
+
+    /// Extend the canvas by `height` pixels below the map, for annotations.
+    pub(crate) fn with_footer(mut self, height: f64) -> Self {
+        self.height += height - self.footer;
+        self.footer = height;
+        self
     }
 
     /// Project a geographic coordinate to SVG canvas coordinates (x right, y down).
     #[inline]
     pub(crate) fn project(&self, coord: &Coord<f64>) -> (f64, f64) {
-        let x = self.margin + (coord.x - self.bounds.min().x) * self.scale;
-        let y = self.margin + (self.bounds.max().y - coord.y) * self.scale;
+        let (px, py) = self.planar.forward(coord);
+        let x = self.margin + (px - self.extent.min().x) * self.scale;
+        let y = self.margin + (self.extent.max().y - py) * self.scale;
         (x, y)
     }
 }
@@ -35,20 +230,9 @@ impl Viewport {
 /// Projection function: lon/lat -> SVG coords (x,y)
 pub(crate) type Projection = dyn Fn(&Coord<f64>) -> (f64, f64);
 
-/// Append a ring as an SVG subpath: "M x,y L x,y ... Z"
-pub(crate) fn ring_to_path(ring: &[Coord<f64>], project: &Projection, out: &mut String) {
-    if ring.is_empty() { return }
-    let coords = ring.coords_iter().map(|coord| project(&coord)).collect::<Vec<_>>();
-    out.push_str(&format!(" M{:.3},{:.3}", coords[0].0, coords[0].1));
-    for &(x, y) in &coords[1..] {
-        out.push_str(&format!(" L{x:.3},{y:.3}"));
-    }
-    out.push('Z');
-}
-
-pub(crate) fn draw_edges(
+pub(crate) fn draw_edges<'a>(
     writer: &mut impl Write,
-    edges: &[(&Point<f64>, &Point<f64>)],
+    edges: impl IntoIterator<Item = (&'a Point<f64>, &'a Point<f64>)>,
     project: &impl Fn(&Coord<f64>) -> (f64, f64),
 ) -> Result<()> {
     for edge in edges {
@@ -76,78 +260,64 @@ pub(crate) fn _draw_nodes(
     Ok(())
 }
 
-pub(crate) fn draw_polygons(writer: &mut impl Write, polygons: &[MultiPolygon<f64>], project: &Projection) -> Result<()> {
+/// Draw each MultiPolygon as a `<path>` with the default block style.
+pub(crate) fn draw_polygons<W: Write>(
+    writer: &mut W,
+    polygons: impl IntoIterator<Item = impl Borrow<MultiPolygon<f64>>>,
+    project: &Projection,
+) -> Result<()> {
     for polygon in polygons {
-        writeln!(writer, r#"<path class="blk" d="{}"/>"#, multipolygon_to_path(polygon, project))?;
+        write!(writer, r#"<path class="blk" d=""#)?;
+        write_path_data(writer, polygon.borrow(), project)?;
+        writeln!(writer, r#""/>"#)?;
     }
     Ok(())
 }
 
-/// Draw polygons with specified fill colors.
-pub(crate) fn draw_polygons_with_fill(writer: &mut impl Write, polygons: &[MultiPolygon<f64>], colors: &[String], project: &Projection) -> Result<()> {
-    assert_eq!(colors.len(), polygons.len(),
-        "[io::svg] length mismatch: {} colors for {} geometries",
-        colors.len(),
-        polygons.len(),
-    );
-
-    for (polygon, color) in polygons.iter().zip(colors.iter()) {
-        for points in multipolygon_to_points(polygon, project) {
-            writeln!(writer, r#"<polygon class="blk" points="{}" style="fill:{}"/>"#, points, color)?;
-        }
+/// Draw each MultiPolygon as a `<path>` with its own fill color (holes cut out via even-odd fill).
+pub(crate) fn draw_polygons_with_fill<W: Write>(
+    writer: &mut W,
+    polygons: impl IntoIterator<Item = (impl Borrow<MultiPolygon<f64>>, impl Display)>,
+    project: &Projection,
+) -> Result<()> {
+    for (polygon, color) in polygons {
+        write!(writer, r#"<path class="blk" fill-rule="evenodd" style="fill:{color}" d=""#)?;
+        write_path_data(writer, polygon.borrow(), project)?;
+        writeln!(writer, r#""/>"#)?;
     }
-
     Ok(())
 }
 
-/// Convert a MultiPolygon into a list of SVG `points` strings for each exterior ring.
-/// (Holes are ignored for polygon output.)
-fn multipolygon_to_points(shape: &MultiPolygon<f64>, project: &Projection) -> Vec<String> {
-    shape.0.iter()
-        .map(|polygon| ring_to_points(polygon.exterior(), project))
-        .collect()
-}
-
-/// Build an SVG points string for a LineString (ring).
-fn ring_to_points(ring: &LineString<f64>, project: &Projection) -> String {
-    let mut out = String::new();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    for (i, coord) in ring.coords_iter().enumerate() {
-        let (x, y) = project(&coord);
-        if i > 0 { out.push(' ') }
-        out.push_str(&format!("{x:.3},{y:.3}"));
-    }
+    #[test]
+    fn albers_usa_moves_insets_below_lower_48() {
+        let planar = Planar::new(MapProjection::AlbersUsa, Rect::new(Coord { x: -180.0, y: 18.0 }, Coord { x: -66.0, y: 72.0 }));
+        let project = |x: f64, y: f64| planar.forward(&Coord { x, y });
+        let (seattle, miami) = (project(-122.3, 47.6), project(-80.2, 25.8));
+        let (anchorage, honolulu) = (project(-149.9, 61.2), project(-157.9, 21.3));
 
-    out
-}
-
-/// Build a compact SVG path string for a MultiPolygon (exteriors + holes).
-fn multipolygon_to_path(shape: &MultiPolygon<f64>, project: &Projection) -> String {
-    let mut out = String::new();
-
-    for polygon in &shape.0 {
-        out.push_str(&linestring_to_path(polygon.exterior(), project));
-        for interior in polygon.interiors() {
-            out.push_str(&linestring_to_path(interior, project));
+        for inset in [anchorage, honolulu] {
+            assert!(inset.0 > seattle.0 - 0.1 && inset.0 < miami.0, "inset {inset:?} not under the lower 48");
+            assert!(inset.1 < seattle.1, "inset {inset:?} not south of Seattle");
         }
+        assert_eq!(project(175.0, 52.0).0.signum(), project(-175.0, 52.0).0.signum(), "Aleutians split across the antimeridian");
     }
 
-    out
-}
-
-/// Build a compact SVG path string for a LineString (ring).
-fn linestring_to_path(ring: &LineString<f64>, project: &Projection) -> String {
-    let mut out = String::new();
-
-    let mut coords = ring.coords_iter()
-        .map(|coord| project(&coord));
-    if let Some((x, y)) = coords.next() {
-        out.push_str(&format!(" M{x:.3},{y:.3}"));
-        for (x, y) in coords {
-            out.push_str(&format!(" L{x:.3},{y:.3}"));
+    #[test]
+    fn albers_is_equal_area() {
+        let albers = Albers::new(-89.0, 40.0, 38.0, 42.0);
+        let area = |lon: f64, lat: f64| {
+            let (a, b, c) = (albers.forward(lon, lat), albers.forward(lon + 0.1, lat), albers.forward(lon, lat + 0.1));
+            ((b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)).abs()
+        };
+        // A 0.1° cell's area on the unit sphere is proportional to the change in sin(lat).
+        for (lon, lat) in [(-91.0, 37.0), (-87.5, 42.4)] {
+            let sphere = 0.1f64.to_radians() * ((lat + 0.1f64).to_radians().sin() - lat.to_radians().sin());
+            assert!((area(lon, lat) / sphere - 1.0).abs() < 1e-3);
         }
-        out.push('Z');
+        assert_eq!(MapProjection::from_name("Albers_USA"), Some(MapProjection::AlbersUsa));
     }
-
-    out
 }
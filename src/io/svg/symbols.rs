@@ -0,0 +1,98 @@
+//! Dot-density and proportional symbol overlays for SVG visualization.
+
+use std::io::Write;
+
+use anyhow::Result;
+use geo::{Contains, Coord, InteriorPoint, Point};
+use geograph::{Region, UnitId};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::map::GeoType;
+
+use super::{Palette, Viewport, escape_xml};
+
+/// How unit values are drawn over the map.
+#[derive(Clone, Debug)]
+pub enum SymbolStyle {
+    /// One dot per `per_dot` people of each series (e.g. race/ethnicity counts), scattered at
+    /// random inside each unit and colored by series. The same `seed` gives the same dots.
+    DotDensity { series: Vec<String>, per_dot: f64, seed: u64 },
+    /// A circle at each unit with area proportional to `series`; the largest has `max_radius` pixels.
+    Proportional { series: String, max_radius: f64 },
+}
+
+/// Symbols drawn over the map for the units of one layer.
+#[derive(Clone, Debug)]
+pub struct Symbols {
+    pub layer: GeoType,
+    pub style: SymbolStyle,
+}
+
+/// Draw one `<g class="dots">` per series, filled with the series' Okabe-Ito color, holding a dot
+/// per `per_dot` of its value in each unit of `region`. Fractional dots are rounded at random so
+/// the expected count is exact.
+pub(crate) fn write_dot_density<W: Write>(
+    writer: &mut W,
+    region: &Region,
+    series: &[(&str, Vec<f64>)],
+    per_dot: f64,
+    seed: u64,
+    vp: &Viewport,
+) -> Result<()> {
+    let colors = Palette::OkabeIto.colors();
+    let mut rng = StdRng::seed_from_u64(seed);
+    for (i, (name, values)) in series.iter().enumerate() {
+        writeln!(writer, r#"<g class="dots" id="dots-{}" style="fill:{}">"#, escape_xml(name), colors[i % colors.len()])?;
+        for (u, &value) in values.iter().enumerate() {
+            let expected = value / per_dot;
+            if !expected.is_finite() || expected <= 0.0 { continue }
+            let count = expected.floor() as usize + rng.random_bool(expected.fract()) as usize;
+            for point in random_points(region, UnitId(u as u32), count, &mut rng) {
+                let (x, y) = vp.project(&point);
+                writeln!(writer, r#"<circle cx="{x:.2}" cy="{y:.2}" r="0.8"/>"#)?;
+            }
+        }
+        writeln!(writer, "</g>")?;
+    }
+    Ok(())
+}
+
+/// Draw a circle at an interior point of each unit of `region`, with area proportional to its value.
+/// Larger circles are drawn first so that smaller ones stay visible.
+pub(crate) fn write_proportional<W: Write>(writer: &mut W, region: &Region, name: &str, values: &[f64], max_radius: f64, vp: &Viewport) -> Result<()> {
+    let max = values.iter().copied().filter(|v| v.is_finite()).fold(0.0, f64::max);
+    let mut order = (0..values.len()).filter(|&u| values[u] > 0.0 && values[u].is_finite()).collect::<Vec<_>>();
+    order.sort_by(|&a, &b| values[b].total_cmp(&values[a]));
+
+    writeln!(writer, r#"<g class="symbols" id="symbols-{}" style="fill:#6b7280;fill-opacity:0.5;stroke:#111827;stroke-width:0.5">"#, escape_xml(name))?;
+    for u in order {
+        let Some(center) = region.geometry(UnitId(u as u32)).interior_point() else { continue };
+        let (x, y) = vp.project(&center.0);
+        let r = max_radius * (values[u] / max).sqrt();
+        writeln!(writer, r#"<circle cx="{x:.2}" cy="{y:.2}" r="{r:.2}"/>"#)?;
+    }
+    writeln!(writer, "</g>")?;
+    Ok(())
+}
+
+/// `count` points drawn uniformly from inside a unit, by rejection sampling its bounding box.
+/// Falls back to an interior point for slivers that reject too many samples.
+fn random_points(region: &Region, unit: UnitId, count: usize, rng: &mut impl Rng) -> Vec<Coord<f64>> {
+    if count == 0 { return vec![] }
+    let shape = region.geometry(unit);
+    let bounds = region.bounds(unit);
+    let mut points = Vec::with_capacity(count);
+    for _ in 0..count * 100 {
+        if points.len() == count { break }
+        let coord = Coord {
+            x: bounds.min().x + rng.random::<f64>() * bounds.width(),
+            y: bounds.min().y + rng.random::<f64>() * bounds.height(),
+        };
+        if shape.contains(&Point(coord)) { points.push(coord) }
+    }
+    if points.len() < count {
+        let fallback = shape.interior_point().map_or(bounds.center(), |point| point.0);
+        points.resize(count, fallback);
+    }
+    points
+}
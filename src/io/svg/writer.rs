@@ -1,10 +1,14 @@
 //! SVG writing operations.
+//!
+//! Path data is written straight to the output as it is generated, so documents with
+//! hundreds of thousands of shapes never need to be held in memory as strings.
 
 use std::{fs::File, io::{BufWriter, Write}, path::Path};
 
 use anyhow::{Context, Result};
+use geo::{Coord, MultiPolygon};
 
-use super::Viewport;
+use super::{Accessibility, Projection, Viewport, aria_attributes, write_title};
 
 pub(crate) struct SvgWriter {
     writer: BufWriter<File>
@@ -61,8 +65,8 @@ impl SvgWriter {
     }
 
     /// Write the SVG header, including the XML declaration and opening <svg> tag.
-    pub(crate) fn write_header(&mut self, vp: &Viewport) -> Result<()> {
-        write_svg_header(self, vp)
+    pub(crate) fn write_header(&mut self, vp: &Viewport, access: &Accessibility) -> Result<()> {
+        write_svg_header(self, vp, access)
     }
     
     /// Write SVG styles for map features.
@@ -77,21 +81,24 @@ impl SvgWriter {
 }
 
 
-/// Write SVG header to any writer (standalone function).
-pub(crate) fn write_svg_header<W: Write>(writer: &mut W, vp: &Viewport) -> Result<()> {
-    let Viewport { width, height, margin, scale, bounds } = vp;
+/// Write SVG header to any writer (standalone function), with the title and description of `access`.
+pub(crate) fn write_svg_header<W: Write>(writer: &mut W, vp: &Viewport, access: &Accessibility) -> Result<()> {
+    let Viewport { width, height, margin, scale, bounds, .. } = vp;
     writeln!(writer, r##"<?xml version="1.0" encoding="UTF-8" standalone="no"?>"##)?;
     writeln!(writer, r##"<svg xmlns="http://www.w3.org/2000/svg"
         width="{width}" height="{height}"
         viewBox="0 0 {width} {height}"
         data-lon-min="{lon_min}" data-lon-max="{lon_max}"
         data-lat-min="{lat_min}" data-lat-max="{lat_max}"
-        data-margin="{margin}" data-scale="{scale}">"##,
+        data-margin="{margin}" data-scale="{scale}" data-projection="{projection}"{aria}>"##,
         lon_min = bounds.min().x,
         lon_max = bounds.max().x,
         lat_min = bounds.min().y,
         lat_max = bounds.max().y,
+        projection = vp.projection.to_str(),
+        aria = aria_attributes(access),
     )?;
+    write_title(writer, access)?;
     writeln!(writer, r##"<rect width="100%" height="100%" fill="#ffffff"/>"##)?;
     Ok(())
 }
@@ -103,6 +110,9 @@ pub(crate) fn write_svg_styles<W: Write>(writer: &mut W) -> Result<()> {
     .blk {{ fill: #e5e7eb; stroke: #111827; stroke-width: 0.5; fill-opacity: 0.85; }}
     .edge {{ stroke: #2563eb; stroke-opacity: 0.35; stroke-width: 0.6; }}
     .dist {{ vector-effect: non-scaling-stroke; }}
+    .title {{ font: 600 16px sans-serif; fill: #111827; }}
+    .inset-frame {{ fill: none; stroke: #111827; stroke-width: 1; }}
+    .inset-area {{ fill: none; stroke: #111827; stroke-width: 1; stroke-dasharray: 4 2; }}
 </style>
 </defs>"##)?;
     Ok(())
@@ -113,3 +123,53 @@ pub(crate) fn write_svg_footer<W: Write>(writer: &mut W) -> Result<()> {
     writeln!(writer, "</svg>")?;
     Ok(())
 }
+
+/// Write the opening `<g>` tag of a group of shapes, e.g. all blocks of one county or district,
+/// labelled for screen readers if `label` is given.
+pub(crate) fn write_group_start<W: Write>(writer: &mut W, class: &str, id: &str, style: Option<&str>, label: Option<&str>) -> Result<()> {
+    write!(writer, r#"<g class="{class}" id="{}""#, escape_xml(id))?;
+    if let Some(style) = style { write!(writer, r#" style="{style}""#)? }
+    if let Some(label) = label { write!(writer, "{}", super::aria_label("group", label))? }
+    writeln!(writer, ">")?;
+    Ok(())
+}
+
+/// Write the closing `</g>` tag of a group.
+pub(crate) fn write_group_end<W: Write>(writer: &mut W) -> Result<()> {
+    writeln!(writer, "</g>")?;
+    Ok(())
+}
+
+/// Write a MultiPolygon as SVG path data, one " M x,y L x,y ... Z" subpath per ring (holes included).
+pub(crate) fn write_path_data<W: Write>(writer: &mut W, shape: &MultiPolygon<f64>, project: &Projection) -> std::io::Result<()> {
+    for polygon in &shape.0 {
+        write_ring_data(writer, &polygon.exterior().0, project)?;
+        for interior in polygon.interiors() {
+            write_ring_data(writer, &interior.0, project)?;
+        }
+    }
+    Ok(())
+}
+
+/// Write a ring as an SVG subpath: " M x,y L x,y ... Z".
+pub(crate) fn write_ring_data<W: Write>(writer: &mut W, ring: &[Coord<f64>], project: &Projection) -> std::io::Result<()> {
+    if ring.is_empty() { return std::io::Result::Ok(()) }
+    write_line_data(writer, ring, project)?;
+    write!(writer, "Z")
+}
+
+/// Write an open line as an SVG subpath: " M x,y L x,y ...".
+pub(crate) fn write_line_data<W: Write>(writer: &mut W, line: &[Coord<f64>], project: &Projection) -> std::io::Result<()> {
+    let mut coords = line.iter().map(project);
+    let Some((x, y)) = coords.next() else { return std::io::Result::Ok(()) };
+    write!(writer, " M{x:.3},{y:.3}")?;
+    for (x, y) in coords {
+        write!(writer, " L{x:.3},{y:.3}")?;
+    }
+    std::io::Result::Ok(())
+}
+
+/// Escape a value for use as XML text or inside a double-quoted XML attribute.
+pub(crate) fn escape_xml(value: &str) -> String {
+    value.replace('&', "&amp;").replace('"', "&quot;").replace('<', "&lt;")
+}
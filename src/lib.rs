@@ -22,28 +22,95 @@ mod map;
 mod objective;
 mod partition;
 mod plan;
+mod plans_store;
+
+#[cfg(test)]
+pub(crate) mod test_maps;
+
+#[cfg(feature = "grpc")]
+pub mod grpc;
+
+#[cfg(feature = "server")]
+pub mod server;
 
 #[doc(inline)]
 pub use map::{
+    BoundaryChange,
+    BuildRecord,
+    Citation,
+    ColumnSchema,
+    ColumnStats,
+    CrosswalkEntry,
     GeoId,
     GeoType,
+    IntegrityIssue,
+    LayerManifest,
+    Manifest,
     Map,
     MapLayer,
+    MemoryFootprint,
+    SharedBuffers,
     ParentRefs,
+    StateInfo,
+    STATES,
+    SourceRecord,
+    VintageCrosswalk,
     PackSource,
     PackSink,
+    PackStore,
     DiskPack,
     MemPack,
+    ZipPack,
+    BundlePack,
+    VerifiedPack,
+    CorruptSection,
     PackFormat,
+    PackPatch,
+    apply_pack_patch,
+    convert_pack,
+    create_pack_store,
+    diff_packs,
+    open_pack_store,
+    cite_pack,
     validate_pack,
 };
 
 #[doc(inline)]
 #[cfg(feature = "download")]
-pub use map::{build_pack, download_pack};
+pub use map::{
+    Artifact, BlockAssignSource, BlockCsvSource, BuildOptions, DataSource, DemographicSource, ElectionSource,
+    FieldMapping, HistoricalPlanSource, PrecinctShapefile, QaIssue, QaReason, QaReport, TigerSource,
+    build_pack, build_pack_with_sources, default_sources, download_pack, download_pack_without_geoms,
+    read_block_csv,
+};
+
+#[doc(inline)]
+#[cfg(feature = "download")]
+pub use io::shp::ShapefileSchema;
+
+#[doc(inline)]
+pub use geograph::GeometryPrecision;
 
 #[doc(inline)]
-pub use plan::{Plan};
+pub use io::locale::{Language, Locale};
 
 #[doc(inline)]
-pub use objective::{Metric, Objective};
+pub use io::svg::{Accessibility, Annotations, Corner, Inset, MapProjection, MetricSeries, Palette, PanelLayout, SvgOptions, SymbolStyle, Symbols};
+
+#[doc(inline)]
+pub use plan::{
+    AnnealConfig, AssignmentCodec, AuditEntry, AuditLog, BoxplotRank, Contest, Contribution, CountyCluster, DEFAULT_HISTORY_CAPACITY, DistrictResult, EditSource,
+    ElectionResults, EnsembleBoxplot, EnsembleConfig, ExportPreset, ExportStamp, PartisanTests, Plan, PlanData, PlanEnsemble, PlanMetadata, RelaxedAnnealConfig, ScoreExplanation, SuggestedMove, SweepConfig, Swing, WardRules,
+};
+
+#[doc(inline)]
+pub use plans_store::{ImportanceWeights, PlanStore, StoreOperation, StoredPlan, WeightedDistribution};
+
+#[doc(inline)]
+pub use objective::{Aggregation, Composition, Constraints, DEFAULT_RESOLUTION, METRICS, Metric, MetricInfo, Objective, PreservedUnits, RelaxedConstraint, metric_info};
+
+#[doc(inline)]
+pub use partition::{
+    ALGORITHMS, AlgorithmInfo, AlgorithmKind, AnnealProgress, BalanceBound, CsvStepWriter, EveryNth, HullKind, JsonlStepWriter, PlateauRule,
+    Proposal, RunProfile, Step, StepBuffer, StepObserver, StepRecord, StopReason, StoppingRules, WeightedProposal, algorithm_info,
+};
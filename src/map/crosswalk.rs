@@ -0,0 +1,140 @@
+use std::{collections::HashMap, path::Path};
+
+use anyhow::{Context, Result, anyhow, bail, ensure};
+use polars::frame::DataFrame;
+use serde::{Deserialize, Serialize};
+
+use crate::map::{GeoType, Map, MapLayer};
+
+/// One row of a [`VintageCrosswalk`]: a `weight` share of the `source` block (its share of the
+/// source block's population) lies in the `target` block.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CrosswalkEntry {
+    pub source: String, // Block GEOID in the source vintage
+    pub target: String, // Block GEOID in the target vintage
+    pub weight: f64,
+}
+
+/// A population-weighted block crosswalk from one Census vintage to another, e.g. the NHGIS
+/// 2010 → 2020 block crosswalk. Crosswalks are directional: translating 2020 data back to 2010
+/// blocks takes the 2020 → 2010 file.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VintageCrosswalk {
+    pub from: u16,
+    pub to: u16,
+    pub entries: Vec<CrosswalkEntry>,
+}
+
+impl VintageCrosswalk {
+    /// Build a crosswalk, checking that every GEOID is a 15-digit block and every weight lies in [0, 1].
+    pub fn new(from: u16, to: u16, entries: Vec<CrosswalkEntry>) -> Result<Self> {
+        ensure!(from != to, "[VintageCrosswalk] Source and target vintages must differ");
+        let block_len = GeoType::Block.id_len();
+        if let Some(entry) = entries.iter().find(|entry| entry.source.len() != block_len || entry.target.len() != block_len) {
+            bail!("[VintageCrosswalk] Invalid block GEOIDs {:?} -> {:?}: expected {} characters", entry.source, entry.target, block_len);
+        }
+        if let Some(entry) = entries.iter().find(|entry| !(0.0..=1.0).contains(&entry.weight)) {
+            bail!("[VintageCrosswalk] Weight {} of {} -> {} is outside [0, 1]", entry.weight, entry.source, entry.target);
+        }
+        Ok(Self { from, to, entries })
+    }
+
+    /// Read an NHGIS block crosswalk CSV, e.g. `nhgis_blk2010_blk2020_<state>.csv`. The vintages
+    /// come from its `blk<year>ge` GEOID columns (source first) and the weights from `weight`.
+    /// GEOIDs that lost their leading zeros (e.g. after a round trip through a spreadsheet) are padded.
+    pub fn read_nhgis_csv(path: &Path) -> Result<Self> {
+        let df = crate::io::csv::read_csv_strings(path)?;
+        Self::from_nhgis_dataframe(&df)
+            .with_context(|| format!("[VintageCrosswalk] Failed to read NHGIS crosswalk {}", path.display()))
+    }
+
+    fn from_nhgis_dataframe(df: &DataFrame) -> Result<Self> {
+        let columns = df.get_column_names().into_iter()
+            .filter_map(|name| Some((name.strip_prefix("blk")?.strip_suffix("ge")?.parse::<u16>().ok()?, name.as_str())))
+            .collect::<Vec<_>>();
+        let [(from, source), (to, target)] = columns[..] else {
+            bail!("[VintageCrosswalk] Expected two blk<year>ge columns, found {}", columns.len());
+        };
+
+        let pad = |id: &str| format!("{id:0>width$}", width = GeoType::Block.id_len());
+        let weights = df.column("weight")?.str()?.into_iter()
+            .map(|weight| weight.unwrap_or("0").parse::<f64>().map_err(|e| anyhow!("[VintageCrosswalk] Invalid weight {weight:?}: {e}")))
+            .collect::<Result<Vec<_>>>()?;
+        let entries = df.column(source)?.str()?.into_iter()
+            .zip(df.column(target)?.str()?)
+            .zip(weights)
+            .filter_map(|((source, target), weight)| Some(CrosswalkEntry { source: pad(source?), target: pad(target?), weight }))
+            .collect();
+
+        Self::new(from, to, entries)
+    }
+
+    /// Resolve the entries to `(source index, target index, weight)` in the base layers of the
+    /// source and target maps, dropping blocks missing from either. Fails if nothing is left.
+    pub(crate) fn resolve(&self, source: &MapLayer, target: &MapLayer) -> Result<Vec<(usize, usize, f64)>> {
+        let (sources, targets) = (block_indices(source), block_indices(target));
+        let resolved = self.entries.iter()
+            .filter_map(|entry| Some((*sources.get(entry.source.as_str())?, *targets.get(entry.target.as_str())?, entry.weight)))
+            .collect::<Vec<_>>();
+        ensure!(!resolved.is_empty(), "[VintageCrosswalk] No crosswalk entries match the blocks of both maps");
+        Ok(resolved)
+    }
+}
+
+/// Index of each block GEOID in a layer.
+fn block_indices(layer: &MapLayer) -> HashMap<&str, usize> {
+    layer.geo_ids().iter().enumerate().map(|(i, geo_id)| (geo_id.id(), i)).collect()
+}
+
+impl Map {
+    /// Translate data series from this map's blocks to the blocks of `target`, a map of the
+    /// crosswalk's target vintage, returning a copy of `target` with the series added (replacing
+    /// any of the same name). Each target block receives the weighted sum of the source blocks
+    /// that overlap it, so population-weighted counts keep their totals. Blocks missing from
+    /// either map are skipped.
+    pub fn crosswalk_vintage(&self, crosswalk: &VintageCrosswalk, target: &Map, series: &[&str]) -> Result<Map> {
+        let (source_base, target_base) = (self.base()?, target.base()?);
+        let weights = source_base.get_unit_weights();
+        if let Some(name) = series.iter().find(|name| !weights.contains(name)) {
+            bail!("[Map.crosswalk_vintage] Unknown series '{name}'");
+        }
+
+        let entries = crosswalk.resolve(source_base, target_base)?;
+        let mut translated = target.clone();
+        for &name in series {
+            let mut values = vec![0.0; target_base.len()];
+            for &(source, target, weight) in &entries {
+                values[target] += weight * weights.get_as_f64(name, source).unwrap();
+            }
+            translated.set_unit_column(GeoType::BOTTOM, name, values)?;
+        }
+        Ok(translated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nhgis_columns_give_vintages_and_padded_ids() {
+        let df = polars::df![
+            "blk2010gj" => ["G01000100201001000"],
+            "blk2010ge" => ["10010201001000"],
+            "blk2020gj" => ["G01000100201001000"],
+            "blk2020ge" => ["010010201001000"],
+            "weight" => ["0.25"],
+            "parea" => ["0.3"],
+        ].unwrap();
+        let crosswalk = VintageCrosswalk::from_nhgis_dataframe(&df).unwrap();
+        assert_eq!((crosswalk.from, crosswalk.to), (2010, 2020));
+        assert_eq!(crosswalk.entries, vec![CrosswalkEntry {
+            source: "010010201001000".into(),
+            target: "010010201001000".into(),
+            weight: 0.25,
+        }]);
+
+        let df = polars::df!["blk2010ge" => ["010010201001000"], "weight" => ["1"]].unwrap();
+        assert!(VintageCrosswalk::from_nhgis_dataframe(&df).is_err());
+    }
+}
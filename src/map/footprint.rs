@@ -0,0 +1,150 @@
+use std::{collections::HashSet, fmt, ops::Add, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::map::{GeoId, GeoType, Map, MapLayer, ParentRefs};
+
+/// Estimated heap memory of a map or layer, in bytes, by section. Estimates count the contents of
+/// each container, so they slightly undercount allocator overhead.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryFootprint {
+    pub geometry: usize,    // Unit polygons, the planar subdivision, and per-unit areas, centroids, and bounds
+    pub tables: usize,      // Unit data tables and the weight matrices extracted from them
+    pub adjacency: usize,   // Unit adjacency and shared-boundary lengths
+    pub indices: usize,     // GEOIDs, GEOID lookups, parent references, and the spatial index
+}
+
+impl MemoryFootprint {
+    /// Total bytes over all sections.
+    pub fn total(&self) -> usize { self.geometry + self.tables + self.adjacency + self.indices }
+}
+
+impl Add for MemoryFootprint {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            geometry: self.geometry + other.geometry,
+            tables: self.tables + other.tables,
+            adjacency: self.adjacency + other.adjacency,
+            indices: self.indices + other.indices,
+        }
+    }
+}
+
+impl fmt::Display for MemoryFootprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mb = |bytes: usize| bytes as f64 / (1024.0 * 1024.0);
+        write!(f, "geometry {:.1} MB, tables {:.1} MB, adjacency {:.1} MB, indices {:.1} MB (total {:.1} MB)",
+            mb(self.geometry), mb(self.tables), mb(self.adjacency), mb(self.indices), mb(self.total()))
+    }
+}
+
+/// Buffers behind `Arc`s that have already been counted, so that data shared between layers,
+/// maps, and plans (regions, weight matrices, GEOID tables) is counted once. Pass the same set
+/// to each `memory_footprint_shared` call to total a map and the plans built on it.
+#[derive(Clone, Debug, Default)]
+pub struct SharedBuffers(HashSet<usize>);
+
+impl SharedBuffers {
+    /// Mark the buffer at `ptr` as counted, returning `true` if it was not already.
+    pub(crate) fn first<T: ?Sized>(&mut self, ptr: *const T) -> bool {
+        self.0.insert(ptr.cast::<()>() as usize)
+    }
+
+    /// Get `bytes()` the first time the buffer at `ptr` is counted, and 0 after that.
+    pub(crate) fn once<T: ?Sized>(&mut self, ptr: *const T, bytes: impl FnOnce() -> usize) -> usize {
+        if self.first(ptr) { bytes() } else { 0 }
+    }
+}
+
+impl MapLayer {
+    /// Estimated heap memory of this layer, by section.
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        self.memory_footprint_shared(&mut SharedBuffers::default())
+    }
+
+    /// Estimated heap memory of this layer, by section, leaving out buffers already in `shared`.
+    pub fn memory_footprint_shared(&self, shared: &mut SharedBuffers) -> MemoryFootprint {
+        let (mut geometry, mut adjacency, mut indices) = (0, 0, 0);
+        if shared.first(Arc::as_ptr(&self.region)) {
+            for (label, bytes) in self.region.heap_bytes_breakdown() {
+                match label {
+                    "adjacency" => adjacency += bytes,
+                    "rtree" => indices += bytes,
+                    _ => geometry += bytes,
+                }
+            }
+        }
+
+        // GEOID strings live in string tables shared by many GeoIds, often across layers.
+        indices += self.geo_ids.capacity() * size_of::<GeoId>()
+            + self.index.capacity() * (size_of::<(GeoId, u32)>() + 1)
+            + self.parents.capacity() * size_of::<ParentRefs>()
+            + self.geo_ids.iter()
+                .chain(self.parents.iter().flat_map(|refs| GeoType::ALL.iter().filter_map(|&ty| refs.get(ty))))
+                .map(|geo_id| { let (table, bytes) = geo_id.table(); shared.once(table, || bytes) })
+                .sum::<usize>();
+
+        MemoryFootprint {
+            geometry,
+            tables: self.unit_data.estimated_size() + shared.once(Arc::as_ptr(&self.unit_weights), || self.unit_weights.heap_bytes()),
+            adjacency,
+            indices,
+        }
+    }
+}
+
+impl Map {
+    /// Estimated heap memory of the map, by section, summed over its layers. Useful for choosing
+    /// between full and geometry-less packs: geometry usually dominates.
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        self.memory_footprint_shared(&mut SharedBuffers::default())
+    }
+
+    /// Estimated heap memory of the map, by section, leaving out buffers already in `shared`.
+    pub fn memory_footprint_shared(&self, shared: &mut SharedBuffers) -> MemoryFootprint {
+        self.layers_iter().map(|layer| layer.memory_footprint_shared(shared)).fold(MemoryFootprint::default(), Add::add)
+    }
+
+    /// Estimated heap memory of each layer, by section, from the top layer down. Tables shared
+    /// between layers are counted in the first layer holding them, so the footprints add up to
+    /// [`Map::memory_footprint`].
+    pub fn layer_footprints(&self) -> Vec<(GeoType, MemoryFootprint)> {
+        let mut shared = SharedBuffers::default();
+        self.layers_iter().map(|layer| (layer.ty(), layer.memory_footprint_shared(&mut shared))).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_maps;
+
+    #[test]
+    fn footprints_grow_with_data_and_add_up() {
+        let mut map = test_maps::grid(3);
+        let before = map.memory_footprint();
+        assert!(before.geometry > 0 && before.tables > 0 && before.adjacency > 0 && before.indices > 0);
+
+        map.set_unit_column(GeoType::BOTTOM, "votes", vec![1.0; 9]).unwrap();
+        let after = map.memory_footprint();
+        assert!(after.tables > before.tables);
+        assert_eq!((after.geometry, after.adjacency), (before.geometry, before.adjacency));
+
+        let layers = map.layer_footprints().into_iter().map(|(_, footprint)| footprint).fold(MemoryFootprint::default(), Add::add);
+        assert_eq!(layers, after);
+    }
+
+    #[test]
+    fn shared_buffers_are_counted_once() {
+        let map = test_maps::grid(3);
+        let mut shared = SharedBuffers::default();
+        let first = map.memory_footprint_shared(&mut shared);
+        assert_eq!(first, map.memory_footprint());
+
+        let again = map.memory_footprint_shared(&mut shared);
+        assert_eq!((again.geometry, again.adjacency), (0, 0));
+        assert!(again.total() < first.total());
+    }
+}
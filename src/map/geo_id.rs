@@ -1,33 +1,112 @@
+use std::{collections::HashMap, fmt, hash::{Hash, Hasher}, sync::Arc};
+
 use crate::map::GeoType;
 
 /// Stable key for any entity across levels.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+///
+/// GEOID strings are stored in tables shared by the entities of a layer (see [`GeoId::new_all`]),
+/// so a `GeoId` is a small handle: cloning never allocates, and ids from one table compare
+/// without touching the string.
+///
+/// This is not a global interner: [`GeoId::new`] allocates a table of its own, so the same GEOID
+/// may live in several tables. Ids from different tables compare by string, and every id hashes
+/// its string (at most 15 bytes) so that equal ids hash alike whatever table they come from.
+#[derive(Clone)]
 pub struct GeoId {
-    ty: GeoType,  // ex: county, block
-    id: Box<str>, // ex: "17019" for county, "170190111002007" for block
+    ty: GeoType,       // ex: county, block
+    handle: u32,       // Index of the GEOID string in the table
+    table: Arc<String>, // Concatenated GEOIDs of `ty`, ex: "17019" for county, "170190111002007" for block
 }
 
 impl GeoId {
+    /// Create a `GeoId` with a table of its own, e.g. to look up a unit by GEOID. Use
+    /// [`GeoId::new_all`] for the ids of a layer.
     pub fn new(ty: GeoType, id: &str) -> Self {
         assert_eq!(id.len(), ty.id_len(), "GEOID length does not match GeoType");
-        GeoId { ty, id: id.into() }
+        GeoId { ty, handle: 0, table: Arc::new(id.into()) }
+    }
+
+    /// Create `GeoId`s for many GEOIDs of one type, sharing one string table. Repeated GEOIDs
+    /// (e.g. the county of every block) share one entry.
+    pub(crate) fn new_all<'a>(ty: GeoType, ids: impl IntoIterator<Item = &'a str>) -> Vec<Self> {
+        let mut text = String::new();
+        let mut handles = HashMap::<&str, u32>::new();
+        let handles = ids.into_iter()
+            .map(|id| {
+                assert_eq!(id.len(), ty.id_len(), "GEOID length does not match GeoType");
+                let next = handles.len() as u32;
+                *handles.entry(id).or_insert_with(|| { text.push_str(id); next })
+            })
+            .collect::<Vec<_>>();
+        text.shrink_to_fit();
+        let table = Arc::new(text);
+        handles.into_iter().map(|handle| GeoId { ty, handle, table: table.clone() }).collect()
     }
 
     /// Get the geographic type of this GeoId.
     #[inline] pub fn ty(&self) -> GeoType { self.ty }
 
     /// Get the string identifier of this GeoId.
-    #[inline] pub fn id(&self) -> &str { &self.id }
+    #[inline]
+    pub fn id(&self) -> &str {
+        let start = self.handle as usize * self.ty.id_len();
+        &self.table[start..start + self.ty.id_len()]
+    }
+
+    /// Address of the string table this GeoId points into, and the bytes the table holds.
+    pub(crate) fn table(&self) -> (*const String, usize) {
+        (Arc::as_ptr(&self.table), self.table.len())
+    }
 
     /// Syntactic sugar for creating a new GeoId of type Block.
     #[cfg(feature = "download")]
     #[inline] pub(super) fn new_block(id: &str) -> Self { Self::new(GeoType::Block, id) }
+}
 
-    /// Returns a new `GeoId` corresponding to the higher-level `GeoType`
-    /// by truncating this GeoId's string to the correct prefix length.
-    #[cfg(feature = "download")]
-    #[inline] pub(super) fn to_parent(&self, parent_ty: GeoType) -> GeoId {
-        let n = self.id.len().min(parent_ty.id_len());
-        GeoId { ty: parent_ty, id: self.id[..n].into() }
+impl PartialEq for GeoId {
+    fn eq(&self, other: &Self) -> bool {
+        self.ty == other.ty && if Arc::ptr_eq(&self.table, &other.table) {
+            self.handle == other.handle
+        } else {
+            self.id() == other.id()
+        }
+    }
+}
+
+impl Eq for GeoId {}
+
+impl Hash for GeoId {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.ty.hash(state);
+        self.id().hash(state);
+    }
+}
+
+impl fmt::Debug for GeoId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GeoId").field("ty", &self.ty).field("id", &self.id()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_tables_compare_by_id() {
+        let counties = GeoId::new_all(GeoType::County, ["99001", "99002", "99001"]);
+        assert_eq!(counties[0], counties[2]);
+        assert_eq!(counties[0].table(), counties[1].table());
+        assert_eq!(counties[1].id(), "99002");
+        assert_eq!(counties[0].table().1, 10);
+
+        // Ids from different tables are equal, and hash alike, when their strings are.
+        let single = GeoId::new(GeoType::County, "99001");
+        assert_eq!(single, counties[0]);
+        assert_ne!(single, counties[1]);
+        let index = counties.iter().cloned().zip(0..).collect::<HashMap<_, _>>();
+        assert_eq!(index.get(&single), Some(&2));
+        assert_ne!(GeoId::new(GeoType::State, "99"), GeoId::new(GeoType::State, "98"));
+        assert_eq!(format!("{single:?}"), r#"GeoId { ty: County, id: "99001" }"#);
     }
 }
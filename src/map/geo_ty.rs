@@ -39,6 +39,18 @@ impl GeoType {
         }
     }
 
+    /// Get a human-readable description of the units of this GeoType.
+    pub fn description(&self) -> &'static str {
+        match self {
+            GeoType::State  => "State or territory",
+            GeoType::County => "Counties and county equivalents",
+            GeoType::Tract  => "Census tracts",
+            GeoType::Group  => "Census block groups",
+            GeoType::VTD    => "Voting districts (precincts)",
+            GeoType::Block  => "Census blocks",
+        }
+    }
+
     /// Create a GeoType from a string representation.
     #[inline]
     pub fn from_str(string: &str) -> Option<GeoType> {
@@ -55,7 +67,7 @@ impl GeoType {
 
     /// Get the expected length of the GEOID string for this GeoType.
     #[inline]
-    pub(super) fn id_len(&self) -> usize {
+    pub(crate) fn id_len(&self) -> usize {
         match self {
             GeoType::State  => 2,
             GeoType::County => 5,
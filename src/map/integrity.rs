@@ -0,0 +1,299 @@
+use std::{fmt, sync::Arc};
+
+use anyhow::{anyhow, ensure, Result};
+use geo::{Coord, InteriorPoint};
+use polars::prelude::{NamedFrom, Series};
+
+use geograph::UnitId;
+
+use crate::{graph::WeightMatrix, map::{GeoId, GeoType, Map, MapLayer}};
+
+/// A single inconsistency between a layer and one of its parent layers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IntegrityIssue {
+    /// The unit has no reference to a parent type that the rest of its layer uses.
+    MissingParent { child: GeoId, parent_ty: GeoType },
+    /// The unit references a parent that does not exist in the parent layer.
+    UnknownParent { child: GeoId, parent: GeoId },
+    /// An interior point of the unit does not fall inside its referenced parent.
+    NotContained { child: GeoId, parent: GeoId, located: Option<GeoId> },
+    /// The sum of a series over a parent's children does not equal the parent's own value.
+    RollupMismatch { parent: GeoId, child_ty: GeoType, series: String, expected: f64, actual: f64 },
+}
+
+impl fmt::Display for IntegrityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityIssue::MissingParent { child, parent_ty } =>
+                write!(f, "{:?} {} has no {} parent", child.ty(), child.id(), parent_ty.to_str()),
+            IntegrityIssue::UnknownParent { child, parent } =>
+                write!(f, "{:?} {} references unknown {} {}", child.ty(), child.id(), parent.ty().to_str(), parent.id()),
+            IntegrityIssue::NotContained { child, parent, located: Some(located) } =>
+                write!(f, "{:?} {} lies in {} {}, not its parent {}", child.ty(), child.id(), located.ty().to_str(), located.id(), parent.id()),
+            IntegrityIssue::NotContained { child, parent, located: None } =>
+                write!(f, "{:?} {} lies outside its parent {} {}", child.ty(), child.id(), parent.ty().to_str(), parent.id()),
+            IntegrityIssue::RollupMismatch { parent, child_ty, series, expected, actual } =>
+                write!(f, "{:?} {} has {series} = {expected}, but its {} children sum to {actual}", parent.ty(), parent.id(), child_ty.to_str()),
+        }
+    }
+}
+
+/// Whether two series totals agree (exact for integer counts, up to rounding for floats).
+#[inline]
+fn totals_match(expected: f64, actual: f64) -> bool {
+    (expected - actual).abs() <= 1e-9 * expected.abs().max(1.0)
+}
+
+impl MapLayer {
+    /// Index of the referenced `parent_ty` parent of each unit (None if missing or unknown).
+    pub(super) fn parent_indices(&self, parent: &MapLayer) -> Vec<Option<usize>> {
+        self.parents.iter()
+            .map(|refs| refs.get(parent.ty())
+                .and_then(|geo_id| parent.index.get(geo_id))
+                .map(|&i| i as usize))
+            .collect()
+    }
+
+    /// Index of the unit in `parent` that contains an interior point of each unit's geometry.
+    fn locate_in(&self, parent: &MapLayer) -> Vec<Option<usize>> {
+        self.region.unit_ids()
+            .map(|uid| self.region.geometry(uid).interior_point()
+                .and_then(|point| parent.region.unit_at(Coord { x: point.x(), y: point.y() }))
+                .map(|UnitId(i)| i as usize))
+            .collect()
+    }
+
+    /// Sum a weight series over the units of this layer, grouped by parent index.
+    fn rollup(&self, series: &str, parent_indices: &[Option<usize>], num_parents: usize) -> Vec<f64> {
+        let mut totals = vec![0.0; num_parents];
+        for (u, &parent) in parent_indices.iter().enumerate() {
+            if let (Some(p), Some(value)) = (parent, self.unit_weights.get_as_f64(series, u)) {
+                totals[p] += value;
+            }
+        }
+        totals
+    }
+}
+
+impl Map {
+    /// Check that every layer nests consistently inside each of its parent layers.
+    ///
+    /// For each pair of child and parent layers, checks that every child references an existing
+    /// parent, that an interior point of each child lies inside its parent's geometry, and that
+    /// each of the given `series` sums exactly over a parent's children to the parent's own value.
+    /// Series missing from either layer of a pair are skipped, as is the containment check for
+    /// layers loaded without geometry.
+    pub fn check_integrity(&self, series: &[&str]) -> Vec<IntegrityIssue> {
+        let mut issues = Vec::new();
+
+        for child in self.layers_iter() {
+            for parent in self.layers_iter().filter(|parent| (parent.ty() as usize) < (child.ty() as usize)) {
+                let parent_ty = parent.ty();
+
+                // Skip parent types that this layer does not reference at all (e.g. tract -> vtd).
+                if child.parents.iter().all(|refs| refs.get(parent_ty).is_none()) { continue }
+
+                let indices = child.parent_indices(parent);
+                let located = match child.has_geometry() && parent.has_geometry() {
+                    true => child.locate_in(parent),
+                    false => indices.clone(),
+                };
+
+                for (u, refs) in child.parents.iter().enumerate() {
+                    let child_id = &child.geo_ids[u];
+                    match (refs.get(parent_ty), indices[u]) {
+                        (None, _) => issues.push(IntegrityIssue::MissingParent { child: child_id.clone(), parent_ty }),
+                        (Some(geo_id), None) => issues.push(IntegrityIssue::UnknownParent { child: child_id.clone(), parent: geo_id.clone() }),
+                        (Some(geo_id), Some(p)) if located[u] != Some(p) => issues.push(IntegrityIssue::NotContained {
+                            child: child_id.clone(),
+                            parent: geo_id.clone(),
+                            located: located[u].map(|l| parent.geo_ids[l].clone()),
+                        }),
+                        _ => (),
+                    }
+                }
+
+                for &name in series {
+                    if !child.unit_weights.contains(name) || !parent.unit_weights.contains(name) { continue }
+                    let totals = child.rollup(name, &indices, parent.len());
+                    for (p, &actual) in totals.iter().enumerate() {
+                        let expected = parent.unit_weights.get_as_f64(name, p).unwrap_or(0.0);
+                        if !totals_match(expected, actual) {
+                            issues.push(IntegrityIssue::RollupMismatch {
+                                parent: parent.geo_ids[p].clone(),
+                                child_ty: child.ty(),
+                                series: name.to_string(),
+                                expected,
+                                actual,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        issues
+    }
+
+    /// Reassign `parent_ty` references of `ty` units to the parent that actually contains them,
+    /// then recompute the given `series` on the parent layer as sums over its children.
+    ///
+    /// A reassigned unit also takes its new parent's references to every layer above it (e.g. a
+    /// block moved to a VTD in another county moves to that county), and the given `series` are
+    /// recomputed on those ancestor layers that already have them.
+    ///
+    /// This repairs crosswalk errors such as Census blocks assigned to the wrong VTD. Units whose
+    /// interior point falls outside every parent keep their existing reference. Returns the number
+    /// of units that were reassigned.
+    pub fn repair_parents(&mut self, ty: GeoType, parent_ty: GeoType, series: &[&str]) -> Result<usize> {
+        ensure!((parent_ty as usize) < (ty as usize), "[Map] {:?} is not a parent type of {:?}", parent_ty, ty);
+
+        let child = self.layer(ty).ok_or_else(|| anyhow!("[Map] Missing layer {:?}", ty))?;
+        let parent = self.layer(parent_ty).ok_or_else(|| anyhow!("[Map] Missing layer {:?}", parent_ty))?;
+        child.require_geometry("[Map.repair_parents]")?;
+        parent.require_geometry("[Map.repair_parents]")?;
+        for &name in series {
+            ensure!(child.unit_weights.contains(name), "[Map] Series '{}' not found in layer {:?}", name, ty);
+        }
+
+        let indices = child.parent_indices(parent);
+        let located = child.locate_in(parent);
+        let ancestors = GeoType::ALL[..parent_ty as usize].iter().copied()
+            .filter(|&ancestor| self.layer(ancestor).is_some())
+            .collect::<Vec<_>>();
+        let reassigned = (0..child.len())
+            .filter_map(|u| located[u].filter(|&l| indices[u] != Some(l)).map(|l| (u, parent.geo_ids[l].clone(), parent.parents[l].clone())))
+            .collect::<Vec<_>>();
+
+        let child = self.layer_mut(ty).expect("layer checked above");
+        for (u, geo_id, refs) in &reassigned {
+            child.parents[*u].set(parent_ty, Some(geo_id.clone()));
+            for &ancestor in &ancestors {
+                if let Some(geo_id) = refs.get(ancestor) { child.parents[*u].set(ancestor, Some(geo_id.clone())) }
+            }
+        }
+
+        self.write_rollups(ty, parent_ty, series, true)?;
+        if !reassigned.is_empty() {
+            for ancestor in ancestors {
+                let existing = series.iter().copied()
+                    .filter(|&name| self.layer(ancestor).is_some_and(|layer| layer.unit_weights.contains(name)))
+                    .collect::<Vec<_>>();
+                self.write_rollups(ty, ancestor, &existing, false)?;
+            }
+        }
+
+        Ok(reassigned.len())
+    }
+
+    /// Recompute `series` on the `parent_ty` layer as sums over the `ty` units referencing each
+    /// parent, keeping each column's type. Missing columns are added only if `add_missing`.
+    fn write_rollups(&mut self, ty: GeoType, parent_ty: GeoType, series: &[&str], add_missing: bool) -> Result<()> {
+        let child = self.layer(ty).expect("layer checked by caller");
+        let parent = self.layer(parent_ty).expect("layer checked by caller");
+        if child.parents.iter().all(|refs| refs.get(parent_ty).is_none()) { return Ok(()) }
+
+        let indices = child.parent_indices(parent);
+        let totals = series.iter()
+            .map(|&name| (name, child.rollup(name, &indices, parent.len())))
+            .collect::<Vec<_>>();
+
+        let parent = self.layer_mut(parent_ty).expect("layer checked by caller");
+        for (name, values) in totals {
+            let dtype = parent.unit_data.column(name).ok().map(|column| column.dtype().clone());
+            let column = match &dtype {
+                Some(dtype) if dtype.is_float() => Series::new(name.into(), values),
+                _ => Series::new(name.into(), values.into_iter().map(|v| v.round() as i64).collect::<Vec<_>>()),
+            };
+            match dtype {
+                Some(dtype) => { parent.unit_data.replace(name, column.cast(&dtype)?)?; }
+                None if add_missing => { parent.unit_data.with_column(column)?; }
+                None => (),
+            }
+        }
+        parent.unit_weights = Arc::new(WeightMatrix::from_dataframe(&parent.unit_data));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use geograph::Region;
+    use polars::prelude::IntoColumn;
+
+    use super::*;
+    use crate::{map::ParentRefs, test_maps};
+
+    /// A layer of `ty` with the given units, parents and integer `pop` column.
+    fn layer(ty: GeoType, units: &[(&str, f64, f64, &ParentRefs, i64)]) -> MapLayer {
+        let geo_ids = units.iter().map(|&(id, ..)| GeoId::new(ty, id)).collect::<Vec<_>>();
+        let index = geo_ids.iter().enumerate().map(|(i, geo_id)| (geo_id.clone(), i as u32)).collect::<HashMap<_, _>>();
+        let parents = units.iter().map(|&(_, _, _, refs, _)| refs.clone()).collect();
+        let unit_data = polars::frame::DataFrame::new(vec![
+            Series::new("geo_id".into(), units.iter().map(|&(id, ..)| id).collect::<Vec<_>>()).into_column(),
+            Series::new("pop".into(), units.iter().map(|&(.., pop)| pop).collect::<Vec<_>>()).into_column(),
+        ]).unwrap().with_row_index("idx".into(), None).unwrap();
+        let unit_weights = Arc::new(WeightMatrix::from_dataframe(&unit_data));
+        let region = Region::new(units.iter().map(|&(_, x0, x1, ..)| test_maps::rect(x0, x1, 0.0, 1.0)).collect(), None).unwrap();
+        MapLayer::new(ty, geo_ids, index, parents, unit_data, unit_weights, Arc::new(region))
+    }
+
+    /// References to state "00", `county` and optionally `vtd`.
+    fn refs(county: &str, vtd: Option<&str>) -> ParentRefs {
+        let mut refs = ParentRefs::default();
+        refs.set(GeoType::State, Some(GeoId::new(GeoType::State, "00")));
+        refs.set(GeoType::County, Some(GeoId::new(GeoType::County, county)));
+        refs.set(GeoType::VTD, vtd.map(|vtd| GeoId::new(GeoType::VTD, vtd)));
+        refs
+    }
+
+    /// A row of four blocks in two counties of one VTD each, covering blocks 0-1 and 2-3, except
+    /// that block 2 is assigned to the first VTD and county (whose populations count it).
+    fn misassigned() -> Map {
+        let mut map = test_maps::row(4, &["010010001001000", "010010001001001", "010020001001000", "010020001001001"]);
+        map.insert(layer(GeoType::County, &[("01001", 0.0, 2.0, &refs("01001", None), 3), ("01002", 2.0, 4.0, &refs("01002", None), 1)]));
+        map.insert(layer(GeoType::VTD, &[("01001000001", 0.0, 2.0, &refs("01001", None), 3), ("01002000001", 2.0, 4.0, &refs("01002", None), 1)]));
+        for (u, parents) in map.layer_mut(GeoType::Block).unwrap().parents.iter_mut().enumerate() {
+            *parents = if u <= 2 { refs("01001", Some("01001000001")) } else { refs("01002", Some("01002000001")) };
+        }
+        map
+    }
+
+    fn pops(map: &Map, ty: GeoType) -> Vec<i64> {
+        map.layer(ty).unwrap().data().column("pop").unwrap().i64().unwrap().into_no_null_iter().collect()
+    }
+
+    #[test]
+    fn integrity_issues_are_reported() {
+        let mut map = misassigned();
+        let issues = map.check_integrity(&["pop"]);
+        assert_eq!(issues.len(), 2);
+        assert!(issues.iter().all(|issue| matches!(issue, IntegrityIssue::NotContained { child, located: Some(_), .. } if child.id() == "010020001001000")));
+
+        let blocks = map.layer_mut(GeoType::Block).unwrap();
+        blocks.parents[0].set(GeoType::VTD, Some(GeoId::new(GeoType::VTD, "01001000009")));
+        blocks.parents[1].set(GeoType::VTD, None);
+        let issues = map.check_integrity(&["pop"]);
+        assert!(issues.iter().any(|issue| matches!(issue, IntegrityIssue::UnknownParent { parent, .. } if parent.id() == "01001000009")));
+        assert!(issues.iter().any(|issue| matches!(issue, IntegrityIssue::MissingParent { parent_ty: GeoType::VTD, .. })));
+        // The first VTD now sums to 1 over the children that still reference it, not 3.
+        assert!(issues.iter().any(|issue| matches!(issue, IntegrityIssue::RollupMismatch { expected, actual, .. } if *expected == 3.0 && *actual == 1.0)));
+    }
+
+    #[test]
+    fn repair_moves_units_and_their_ancestors() {
+        let mut map = misassigned();
+        assert_eq!(map.repair_parents(GeoType::Block, GeoType::VTD, &["pop"]).unwrap(), 1);
+
+        let refs = &map.layer(GeoType::Block).unwrap().parents()[2];
+        assert_eq!(refs.get(GeoType::VTD).unwrap().id(), "01002000001");
+        assert_eq!(refs.get(GeoType::County).unwrap().id(), "01002");
+        assert_eq!((pops(&map, GeoType::VTD), pops(&map, GeoType::County)), (vec![2, 2], vec![2, 2]));
+        assert!(map.check_integrity(&["pop"]).is_empty());
+
+        // Repairs need a parent type above the child type.
+        assert!(map.repair_parents(GeoType::County, GeoType::VTD, &["pop"]).is_err());
+    }
+}
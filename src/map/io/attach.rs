@@ -0,0 +1,142 @@
+use std::{path::Path, sync::Arc};
+
+use anyhow::{Result, bail, ensure};
+use geograph::{GeometryPrecision, Region};
+
+use crate::map::{Map, MapLayer, pack::{PackSource, VerifiedPack, open_pack_store, surface_corruption}};
+#[cfg(feature = "download")]
+use crate::map::{pack::DiskPack, util};
+
+use super::read::read_region_from_pack_source;
+
+impl Map {
+    /// Attach unit geometry to a map loaded without it (e.g. from [`crate::download_pack_without_geoms`]),
+    /// reading the geometry sections of the pack at `path_or_url`: a local pack directory, zip archive
+    /// or `.openmander` bundle, or the URL of a zipped pack. Layers that already carry geometry are left as they are.
+    ///
+    /// The pack must describe the same units in the same order. PMTiles packs work since they also
+    /// carry geometry sections, but a bare `.pmtiles` file does not: its clipped, simplified tiles
+    /// cannot rebuild the unit topology.
+    pub fn attach_geometries(&mut self, path_or_url: &str) -> Result<()> {
+        if path_or_url.ends_with(".pmtiles") {
+            bail!("[Map.attach_geometries] Cannot attach geometry from a bare PMTiles file; use the pack directory instead")
+        }
+        if path_or_url.starts_with("http://") || path_or_url.starts_with("https://") {
+            return self.attach_geometries_from_url(path_or_url)
+        }
+        let store = open_pack_store(Path::new(path_or_url))?;
+        self.attach_geometries_from_source(&VerifiedPack::from_manifest(&*store, path_or_url)?)
+    }
+
+    /// Attach unit geometry from the geometry sections of any [`PackSource`] (see [`Map::attach_geometries`]).
+    /// Either every missing layer is attached, or the map is left unchanged.
+    pub fn attach_geometries_from_source(&mut self, src: &dyn PackSource) -> Result<()> {
+        let regions = self.layers_iter()
+            .filter(|layer| !layer.has_geometry())
+            .map(|layer| Ok((layer.ty(), read_matching_region(layer, src)?)))
+            .collect::<Result<Vec<_>>>()
+            .map_err(surface_corruption)?;
+
+        for (ty, region) in regions {
+            let layer = self.layer_mut(ty).expect("layer checked above");
+            layer.region = Arc::new(region);
+        }
+        Ok(())
+    }
+
+    /// Download a zipped pack to a scratch directory and attach its geometry.
+    #[cfg(feature = "download")]
+    fn attach_geometries_from_url(&mut self, url: &str) -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("openmander-attach-{}", std::process::id()));
+        util::ensure_dir_exists(&dir)?;
+        let result = self.attach_geometries_from_zip(url, &dir);
+        std::fs::remove_dir_all(&dir).ok();
+        result
+    }
+
+    #[cfg(not(feature = "download"))]
+    fn attach_geometries_from_url(&mut self, _url: &str) -> Result<()> {
+        bail!("[Map.attach_geometries] Attaching geometry from a URL requires the 'download' feature")
+    }
+
+    #[cfg(feature = "download")]
+    fn attach_geometries_from_zip(&mut self, url: &str, dir: &Path) -> Result<()> {
+        let zip_path = dir.join("pack.zip");
+        crate::map::pack::download_big_file(url.to_string(), &zip_path, true)?;
+        util::extract_zip(&zip_path, dir, true)?;
+        let pack = DiskPack::new(find_pack_root(dir)?);
+        self.attach_geometries_from_source(&VerifiedPack::from_manifest(&pack, url)?)
+    }
+}
+
+/// Read the geometry section for `layer` from `src`, checking that it describes the same units.
+fn read_matching_region(layer: &MapLayer, src: &dyn PackSource) -> Result<Region> {
+    let name = layer.ty().to_str();
+    let region = read_region_from_pack_source(layer.ty(), src, GeometryPrecision::F64)?;
+    ensure!(region.has_geometry(), "[Map.attach_geometries] Pack has no geometry for the {name} layer");
+    ensure!(region.num_units() == layer.len(),
+        "[Map.attach_geometries] Pack has {} {name} units, but the map has {}", region.num_units(), layer.len());
+    ensure!(layer.region.unit_ids().all(|uid| region.neighbors(uid) == layer.region.neighbors(uid)),
+        "[Map.attach_geometries] Pack adjacency for the {name} layer differs from the map; is it the same pack?");
+    Ok(region)
+}
+
+/// Find the pack directory inside an extracted archive: `dir` itself, or a subdirectory holding one.
+#[cfg(feature = "download")]
+fn find_pack_root(dir: &Path) -> Result<std::path::PathBuf> {
+    if dir.join("geom").is_dir() { return Ok(dir.to_path_buf()) }
+    std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .find(|path| path.join("geom").is_dir())
+        .ok_or_else(|| anyhow::anyhow!("[Map.attach_geometries] Archive does not contain a pack"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_maps;
+
+    /// Write `map` to a pack in `dir` and return its path as a string.
+    fn pack(map: &Map, dir: &Path, name: &str) -> String {
+        let path = dir.join(name);
+        map.write_to_pack(&path).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn geometry_is_attached_from_a_matching_pack() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = pack(&test_maps::row(3, &[]), dir.path(), "full");
+
+        let mut map = test_maps::row(3, &[]);
+        map.drop_geometry();
+        assert!(!map.has_geometry());
+        map.attach_geometries(&path).unwrap();
+        assert!(map.has_geometry());
+
+        // Layers that already carry geometry are left alone.
+        map.attach_geometries(&path).unwrap();
+        assert!(map.has_geometry());
+    }
+
+    #[test]
+    fn mismatched_packs_leave_the_map_unchanged() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut bare = test_maps::row(4, &[]);
+        bare.drop_geometry();
+
+        let mut map = bare.clone();
+        let err = map.attach_geometries(&pack(&bare, dir.path(), "bare")).unwrap_err();
+        assert!(err.to_string().contains("has no geometry"), "{err:#}");
+
+        let err = map.attach_geometries(&pack(&test_maps::row(3, &[]), dir.path(), "short")).unwrap_err();
+        assert!(err.to_string().contains("Pack has 3 block units, but the map has 4"), "{err:#}");
+
+        let err = map.attach_geometries(&pack(&test_maps::grid(2), dir.path(), "grid")).unwrap_err();
+        assert!(err.to_string().contains("adjacency"), "{err:#}");
+        assert!(!map.has_geometry());
+
+        let err = map.attach_geometries("tiles/block.pmtiles").unwrap_err();
+        assert!(err.to_string().contains("bare PMTiles file"), "{err:#}");
+    }
+}
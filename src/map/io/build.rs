@@ -1,4 +1,4 @@
-use std::{collections::HashMap, path::Path, sync::Arc};
+use std::{collections::{HashMap, HashSet}, path::Path, sync::Arc};
 
 use anyhow::{Context, Ok, Result, anyhow, bail, ensure};
 use polars::{frame::DataFrame, prelude::*, series::Series};
@@ -6,12 +6,13 @@ use shapefile::dbase::{FieldValue, Record};
 
 use crate::{
     ParentRefs,
-    map::{GeoId, GeoType, Map, MapLayer, util},
+    map::{GeoId, GeoType, Map, MapLayer, StateInfo, util, pack::{BlockAssignSource, BuildOptions, DataSource, QaReason, QaReport, TigerSource}},
 };
 
 impl MapLayer {
     /// Loads layer geometries and data from a given .shp file path.
-    fn from_tiger_shapefile(ty: GeoType, path: &Path) -> Result<Self> {
+    /// Geometries that cannot be used as-is are cleaned where possible and recorded in `qa`.
+    fn from_tiger_shapefile(ty: GeoType, path: &Path, qa: &mut QaReport) -> Result<Self> {
         let (shapes, records) = crate::io::shp::read_shapefile(path)?;
 
         /// Convert a vector of records to a DataFrame (using TIGER/PL census format)
@@ -93,18 +94,16 @@ impl MapLayer {
             .with_row_index("idx".into(), None)?;
 
         // Convert shapes from shapefile::Polygon to geo::MultiPolygon<f64> and build Region.
-        let multipolygons: Vec<geo::MultiPolygon<f64>> = shapes.into_iter()
-            .map(crate::io::shp::shape_to_multipolygon)
-            .collect::<Result<Vec<_>>>()
-            .with_context(|| format!("Error converting shapes to multipolygons in shapefile: {}", path.display()))?;
+        let ids = df.column("geo_id")?.str()?.into_no_null_iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>();
+        let multipolygons = qa.clean_shapes(ty, &ids, shapes);
 
         let region = geograph::Region::new(multipolygons, None)
             .map_err(|e| anyhow!("Region construction failed for {:?}: {}: {:?}", ty, path.display(), e))?;
 
         let n = df.height();
-        let geo_ids: Vec<GeoId> = df.column("geo_id")?.str()?.into_no_null_iter()
-            .map(|val| GeoId::new(ty, val))
-            .collect();
+        let geo_ids = GeoId::new_all(ty, df.column("geo_id")?.str()?.into_no_null_iter());
         let index = geo_ids.iter().enumerate()
             .map(|(i, geo_id)| (geo_id.clone(), i as u32))
             .collect();
@@ -143,9 +142,10 @@ impl MapLayer {
 
     /// Assign parent references for each entity in the layer, based on their truncated geo_id.
     fn assign_parents(&mut self, parent_ty: GeoType) {
-        self.geo_ids.iter().enumerate()
-            .map(|(i, geo_id)| self.parents[i].set(parent_ty,Some(geo_id.to_parent(parent_ty))))
-            .collect()
+        let prefixes = self.geo_ids.iter().map(|geo_id| &geo_id.id()[..geo_id.id().len().min(parent_ty.id_len())]);
+        for (refs, parent) in self.parents.iter_mut().zip(GeoId::new_all(parent_ty, prefixes)) {
+            refs.set(parent_ty, Some(parent));
+        }
     }
 
     /// Assign parent references for each entity in the layer, based on a provided map of geo_id to parent geo_id.
@@ -225,6 +225,17 @@ impl MapLayer {
         Ok(())
     }
 
+    /// Record units with no neighbors in the layer's Region (after patching) in `qa`.
+    fn report_isolated(&self, qa: &mut QaReport) {
+        for (i, geo_id) in self.geo_ids.iter().enumerate() {
+            let unit = geograph::UnitId(i as u32);
+            if self.region.neighbors(unit).is_empty() {
+                qa.push(self.ty(), geo_id.id(), QaReason::Isolated, "no shared boundary with any other unit",
+                    self.region.geometry(unit).into_owned());
+            }
+        }
+    }
+
     /// Compute outer perimeters from the layer's Region (Block layer only),
     /// returning a DataFrame suitable for `merge_block_data`.
     ///
@@ -305,41 +316,21 @@ impl Map {
         Ok(())
     }
 
-    /// Build a map pack from the download files in `input_dir`
+    /// Build a map pack from the download files in `input_dir`, recording unusable geometries in `qa`.
     #[cfg(feature = "download")]
-    pub(crate) fn build_pack(input_dir: &Path, state_code: &str, fips: &str, has_vtd: bool, verbose: u8) -> Result<Self> {
+    pub(crate) fn build_pack(input_dir: &Path, state: &StateInfo, sources: &[&dyn DataSource], options: &BuildOptions, qa: &mut QaReport) -> Result<Self> {
         util::require_dir_exists(input_dir)?;
+        let verbose = options.verbose;
 
         let mut map = Self::default();
 
-        // Load all layers from TIGER Census shapefiles.
-        if verbose > 0 { eprintln!("[build_pack] loading state shapes"); }
-        map.insert(MapLayer::from_tiger_shapefile(GeoType::State,
-            &input_dir.join(format!("tl_2020_{fips}_state20/tl_2020_{fips}_state20.shp")))?);
-
-        if verbose > 0 { eprintln!("[build_pack] loading county shapes"); }
-        map.insert(MapLayer::from_tiger_shapefile(GeoType::County,
-            &input_dir.join(format!("tl_2020_{fips}_county20/tl_2020_{fips}_county20.shp")))?);
-
-        if verbose > 0 { eprintln!("[build_pack] loading tract shapes"); }
-        map.insert(MapLayer::from_tiger_shapefile(GeoType::Tract,
-            &input_dir.join(format!("tl_2020_{fips}_tract20/tl_2020_{fips}_tract20.shp")))?);
-
-        if verbose > 0 { eprintln!("[build_pack] loading group shapes"); }
-        map.insert(MapLayer::from_tiger_shapefile(GeoType::Group,
-            &input_dir.join(format!("tl_2020_{fips}_bg20/tl_2020_{fips}_bg20.shp")))?);
-
-        // If the vtd data isn't available (CA, ME, OR, WY), skip this layer.
-        if verbose > 0 { eprintln!("[build_pack] loading vtd shapes"); }
-        if has_vtd {
-            map.insert(MapLayer::from_tiger_shapefile(GeoType::VTD,
-                &input_dir.join(format!("tl_2020_{fips}_vtd20/tl_2020_{fips}_vtd20.shp")))?);
+        // Load the requested layers from TIGER Census shapefiles. The vtd layer is skipped if
+        // TIGER has no VTDs for the state (CA, ME, OR, WY) or precincts replace it.
+        for ty in options.tiger_layers() {
+            if verbose > 0 { eprintln!("[build_pack] loading {} shapes", ty.to_str()); }
+            map.insert(MapLayer::from_tiger_shapefile(ty, &TigerSource::shapefile_path(input_dir, state, ty), qa)?);
         }
 
-        if verbose > 0 { eprintln!("[build_pack] loading block shapes"); }
-        map.insert(MapLayer::from_tiger_shapefile(GeoType::Block,
-            &input_dir.join(format!("tl_2020_{fips}_tabblock20/tl_2020_{fips}_tabblock20.shp")))?);
-
         // Compute parent references for all layers based on truncated geo_id.
         if verbose > 0 { eprintln!("[build_pack] computing crosswalks"); }
         if let Some(layer) = map.layer_mut(GeoType::County) {
@@ -380,13 +371,13 @@ impl Map {
             )
         }
 
-        if has_vtd {
+        if options.uses_tiger_vtd() {
             if verbose > 0 { eprintln!("[build_pack] loading block -> vtd crosswalks"); }
             if let Some(layer) = map.layer_mut(GeoType::Block) {
                 layer.assign_parents_from_map(
                     GeoType::VTD,
                     map_from_crosswalk_df(
-                        &crate::io::csv::read_pipe_delimited_txt(&input_dir.join(format!("BlockAssign_ST{fips}_{state_code}/BlockAssign_ST{fips}_{state_code}_VTD.txt")))?, 
+                        &crate::io::csv::read_pipe_delimited_txt(&BlockAssignSource::vtd_path(input_dir, state))?,
                         (GeoType::Block, GeoType::VTD), 
                         ("BLOCKID", "DISTRICT")
                     )?
@@ -394,32 +385,39 @@ impl Map {
             }
         }
 
-        /// Convert GEOID column from i64 to String type
-        #[inline]
-        fn ensure_geoid_is_str(mut df: DataFrame) -> Result<DataFrame> {
-            if *df.column("GEOID")?.dtype() != DataType::String {
-                let geoid_str = df.column("GEOID")?.i64()?.into_iter()
-                    .map(|opt| opt.map(|v| format!("{:015}", v)))
-                    .collect::<StringChunked>();
-                df.replace("GEOID", geoid_str)?;
+        if let Some(precincts) = &options.precincts {
+            if verbose > 0 { eprintln!("[build_pack] loading precinct shapes from {}", precincts.path.display()); }
+            let blocks = map.layer(GeoType::Block)
+                .ok_or_else(|| anyhow!("[build_pack] Missing layer {:?}", GeoType::Block))?;
+            let (mut layer, crosswalk) = MapLayer::vtds_from_precincts(blocks, precincts, qa)?;
+            layer.assign_parents(GeoType::State);
+            layer.assign_parents(GeoType::County);
+            map.insert(layer);
+            if let Some(layer) = map.layer_mut(GeoType::Block) {
+                layer.assign_parents_from_map(GeoType::VTD, crosswalk)?;
             }
-            Ok(df)
+            map.set_provenance(GeoType::VTD, precincts.provenance());
         }
 
-        if verbose > 0 { eprintln!("[build_pack] loading demographic data"); }
-        map.merge_block_data(ensure_geoid_is_str(crate::io::csv::read_csv(
-            &input_dir.join(format!("Demographic_Data_Block_{state_code}/demographic_data_block_{state_code}.v06.csv"))
-        )?)?, "GEOID")?;
-
-        if verbose > 0 { eprintln!("[build_pack] loading election data"); }
-        map.merge_block_data(ensure_geoid_is_str(crate::io::csv::read_csv(
-            &input_dir.join(format!("Election_Data_Block_{state_code}/election_data_block_{state_code}.v06.csv"))
-        )?)?, "GEOID")?;
+        let mut used = HashSet::new();
+        for source in sources {
+            if verbose > 0 { eprintln!("[build_pack] loading {} data", source.name()); }
+            let blocks = map.layer(GeoType::Block)
+                .ok_or_else(|| anyhow!("[build_pack] Missing layer {:?}", GeoType::Block))?;
+            if let Some(df) = source.block_columns(state, input_dir, blocks)
+                .with_context(|| format!("Failed to parse data source '{}'", source.name()))? {
+                let (df, source_used) = options.fields.apply(df, "GEOID")?;
+                used.extend(source_used);
+                map.merge_block_data(df, "GEOID")?;
+            }
+        }
+        options.fields.check_used(&used)?;
 
         // Bake island-bridge patches into the block Region so they survive serialisation.
         if verbose > 0 { eprintln!("[build_pack] patching island bridges"); }
         if let Some(block_layer) = map.layer_mut(GeoType::Block) {
             block_layer.patch_region()?;
+            block_layer.report_isolated(qa);
         }
 
         // Compute outer perimeters at the block level and aggregate to higher layers.
@@ -433,6 +431,11 @@ impl Map {
             layer.finalize_weights();
         }
 
+        if options.hilbert_order {
+            if verbose > 0 { eprintln!("[build_pack] reordering units along a Hilbert curve"); }
+            map.reorder_hilbert()?;
+        }
+
         Ok(map)
     }
 }
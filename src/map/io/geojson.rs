@@ -1,5 +1,5 @@
 use anyhow::{anyhow, Result};
-use geo::{MultiPolygon, Rect};
+use geo::{MultiLineString, MultiPolygon, Rect};
 use serde_json::{json, Map, Value};
 
 use crate::map::MapLayer;
@@ -16,6 +16,7 @@ impl MapLayer {
     /// bounds: Optional bounding box [min_lon, min_lat, max_lon, max_lat] to filter features.
     /// Only features that intersect the bounds will be included. If None, all features are included.
     pub fn to_geojson_with_bounds(&self, bounds: Option<[f64; 4]>) -> Result<Value> {
+        self.require_geometry("[MapLayer.to_geojson]")?;
         let region = &*self.region;
 
         // Determine which indices to include based on bounds
@@ -97,7 +98,7 @@ impl MapLayer {
             features.push(json!({
                 "type": "Feature",
                 "id": geo_id_str, // Feature ID for MapLibre efficient updates
-                "geometry": multipolygon_to_geojson(mp)?,
+                "geometry": multipolygon_to_geojson(&mp)?,
                 "properties": properties,
             }));
         }
@@ -118,6 +119,7 @@ impl MapLayer {
     /// assignments: index -> district_id mapping
     /// bounds: Optional bounding box [min_lon, min_lat, max_lon, max_lat] to filter features.
     pub fn to_geojson_with_districts_and_bounds(&self, assignments: &[u32], bounds: Option<[f64; 4]>) -> Result<Value> {
+        self.require_geometry("[MapLayer.to_geojson_with_districts]")?;
         let region = &*self.region;
 
         let num_entities = self.geo_ids.len();
@@ -213,7 +215,7 @@ impl MapLayer {
             }
 
             // Convert multipolygon to GeoJSON geometry - handle errors gracefully
-            let geometry = match multipolygon_to_geojson(mp) {
+            let geometry = match multipolygon_to_geojson(&mp) {
                 Ok(geom) => geom,
                 Err(e) => {
                     // If geometry conversion fails, skip this feature
@@ -249,19 +251,69 @@ impl MapLayer {
 }
 
 /// Helper to convert a MultiPolygon to a serde_json::Value representing GeoJSON Geometry.
-fn multipolygon_to_geojson(mp: &MultiPolygon<f64>) -> Result<Value> {
+pub(crate) fn multipolygon_to_geojson(mp: &MultiPolygon<f64>) -> Result<Value> {
     let mut polygons_json = Vec::new();
     for polygon in mp.0.iter() {
-        let exterior: Vec<Vec<f64>> = polygon.exterior().coords()
-            .map(|c| vec![c.x, c.y])
-            .collect();
-        let interiors: Vec<Vec<Vec<f64>>> = polygon.interiors().iter()
+        // Each polygon is a list of rings: the exterior first, then any holes.
+        let rings: Vec<Vec<Vec<f64>>> = std::iter::once(polygon.exterior()).chain(polygon.interiors())
             .map(|ls| ls.coords().map(|c| vec![c.x, c.y]).collect())
             .collect();
-        polygons_json.push(json!([exterior, interiors]));
+        polygons_json.push(json!(rings));
     }
     Ok(json!({
         "type": "MultiPolygon",
         "coordinates": polygons_json
     }))
 }
+
+/// Helper to convert a MultiLineString to a serde_json::Value representing GeoJSON Geometry.
+pub(crate) fn multilinestring_to_geojson(mls: &MultiLineString<f64>) -> Value {
+    let lines: Vec<Vec<Vec<f64>>> = mls.0.iter()
+        .map(|ls| ls.coords().map(|c| vec![c.x, c.y]).collect())
+        .collect();
+    json!({
+        "type": "MultiLineString",
+        "coordinates": lines
+    })
+}
+
+/// Helper to read a GeoJSON Polygon, MultiPolygon, Feature or FeatureCollection as one MultiPolygon
+/// (the union of every polygon it contains), e.g. a city boundary.
+pub(crate) fn geojson_to_multipolygon(value: &Value) -> Result<MultiPolygon<f64>> {
+    let ring = |ring: &Value| -> Result<geo::LineString<f64>> {
+        ring.as_array().ok_or_else(|| anyhow!("[geojson] Ring must be an array of positions"))?.iter()
+            .map(|position| match position.as_array().map(Vec::as_slice) {
+                Some([x, y, ..]) => Ok(geo::Coord {
+                    x: x.as_f64().ok_or_else(|| anyhow!("[geojson] Invalid coordinate"))?,
+                    y: y.as_f64().ok_or_else(|| anyhow!("[geojson] Invalid coordinate"))?,
+                }),
+                _ => Err(anyhow!("[geojson] Position must have two coordinates")),
+            })
+            .collect()
+    };
+    let polygon = |rings: &Value| -> Result<geo::Polygon<f64>> {
+        let mut rings = rings.as_array().ok_or_else(|| anyhow!("[geojson] Polygon must be an array of rings"))?
+            .iter().map(ring).collect::<Result<Vec<_>>>()?;
+        if rings.is_empty() { return Err(anyhow!("[geojson] Polygon has no exterior ring")) }
+        let exterior = rings.remove(0);
+        Ok(geo::Polygon::new(exterior, rings))
+    };
+    let coordinates = || value.get("coordinates").ok_or_else(|| anyhow!("[geojson] Geometry has no coordinates"));
+
+    match value.get("type").and_then(Value::as_str) {
+        Some("Polygon") => Ok(MultiPolygon(vec![polygon(coordinates()?)?])),
+        Some("MultiPolygon") => Ok(MultiPolygon(coordinates()?.as_array()
+            .ok_or_else(|| anyhow!("[geojson] MultiPolygon must be an array of polygons"))?
+            .iter().map(polygon).collect::<Result<_>>()?)),
+        Some("Feature") => geojson_to_multipolygon(value.get("geometry").ok_or_else(|| anyhow!("[geojson] Feature has no geometry"))?),
+        Some("FeatureCollection") => {
+            let features = value.get("features").and_then(Value::as_array)
+                .ok_or_else(|| anyhow!("[geojson] FeatureCollection has no features"))?;
+            let polygons = features.iter()
+                .map(|feature| Ok(geojson_to_multipolygon(feature)?.0))
+                .collect::<Result<Vec<_>>>()?;
+            Ok(MultiPolygon(polygons.into_iter().flatten().collect()))
+        }
+        other => Err(anyhow!("[geojson] Expected polygon geometry, found {:?}", other)),
+    }
+}
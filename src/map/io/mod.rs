@@ -1,3 +1,4 @@
+mod attach;
 mod geojson;
 mod read;
 mod svg;
@@ -5,3 +6,9 @@ mod write;
 
 #[cfg(feature = "download")]
 mod build;
+#[cfg(feature = "download")]
+mod precincts;
+#[cfg(feature = "download")]
+mod shp;
+
+pub(crate) use geojson::{geojson_to_multipolygon, multilinestring_to_geojson, multipolygon_to_geojson};
@@ -0,0 +1,114 @@
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::{Result, anyhow, bail, ensure};
+use geo::Coord;
+use geograph::{Region, UnitId};
+use polars::prelude::*;
+use shapefile::dbase::FieldValue;
+
+use crate::map::{GeoId, GeoType, MapLayer, ParentRefs, pack::{PrecinctShapefile, QaReport}};
+
+impl MapLayer {
+    /// Build a VTD layer from state-provided precincts by dissolving the blocks whose internal
+    /// point falls in each precinct. Returns the layer and the block -> VTD crosswalk.
+    ///
+    /// Blocks outside every precinct (gaps, slivers) join a neighboring block's precinct, and each
+    /// precinct is placed in the county holding most of its blocks. VTD ids are generated per
+    /// county; the precinct's own identifier is kept in the `name` column. Unusable precinct
+    /// geometries are recorded in `qa`.
+    pub(crate) fn vtds_from_precincts(blocks: &MapLayer, source: &PrecinctShapefile, qa: &mut QaReport) -> Result<(Self, HashMap<GeoId, GeoId>)> {
+        let (shapes, records) = crate::io::shp::read_shapefile(&source.path)?;
+        let precinct_names = records.iter()
+            .map(|record| match record.get(&source.id_field) {
+                Some(FieldValue::Character(Some(s))) => Ok(s.trim().to_string()),
+                Some(FieldValue::Numeric(Some(n))) => Ok(n.to_string()),
+                _ => bail!("[build_pack] Missing or invalid precinct field {} in {}", source.id_field, source.path.display()),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let polygons = qa.clean_shapes(GeoType::VTD, &precinct_names, shapes);
+        let precincts = Region::new(polygons, None)
+            .map_err(|e| anyhow!("Region construction failed for precincts {}: {:?}", source.path.display(), e))?;
+
+        // Locate each block by its TIGER internal point, which always lies inside the block.
+        let lon = blocks.unit_data.column("centroid_lon")?.f64()?;
+        let lat = blocks.unit_data.column("centroid_lat")?.f64()?;
+        let mut precinct_of = lon.into_iter().zip(lat)
+            .map(|(x, y)| precincts.unit_at(Coord { x: x?, y: y? }).map(|UnitId(p)| p as usize))
+            .collect::<Vec<_>>();
+
+        loop {
+            let mut changed = false;
+            for u in 0..precinct_of.len() {
+                if precinct_of[u].is_some() { continue }
+                precinct_of[u] = blocks.region.neighbors(UnitId(u as u32)).iter()
+                    .find_map(|&UnitId(v)| precinct_of[v as usize]);
+                changed |= precinct_of[u].is_some();
+            }
+            if !changed { break }
+        }
+        let unplaced = precinct_of.iter().filter(|p| p.is_none()).count();
+        ensure!(unplaced == 0, "[build_pack] {} blocks are not covered by or adjacent to any precinct", unplaced);
+
+        let mut members = vec![Vec::new(); precinct_names.len()];
+        for (u, p) in precinct_of.into_iter().enumerate() { members[p.unwrap()].push(u) }
+
+        let mut geo_ids = Vec::new();
+        let mut names = Vec::new();
+        let mut geometries = Vec::new();
+        let mut crosswalk = HashMap::with_capacity(blocks.len());
+        let mut next_in_county: HashMap<String, u32> = HashMap::new();
+        let (mut area, mut land, mut water) = (Vec::new(), Vec::new(), Vec::new());
+        let block_column = |name: &str| blocks.unit_data.column(name).ok().and_then(|c| c.f64().ok().cloned());
+        let (block_area, block_land, block_water) = (block_column("area_m2"), block_column("land_m2"), block_column("water_m2"));
+
+        for (p, units) in members.into_iter().enumerate().filter(|(_, units)| !units.is_empty()) {
+            let mut counts: HashMap<&str, usize> = HashMap::new();
+            for &u in &units {
+                let county = blocks.parents[u].get(GeoType::County)
+                    .map_or(&blocks.geo_ids[u].id()[..GeoType::County.id_len()], |county| county.id());
+                *counts.entry(county).or_default() += 1;
+            }
+            let county = counts.into_iter()
+                .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+                .map(|(county, _)| county.to_string())
+                .unwrap();
+
+            let seq = next_in_county.entry(county.clone()).or_insert(0);
+            *seq += 1;
+            ensure!(*seq < 1_000_000, "[build_pack] Too many precincts in county {}", county);
+            let geo_id = GeoId::new(GeoType::VTD, &format!("{county}{seq:06}"));
+
+            for &u in &units { crosswalk.insert(blocks.geo_ids[u].clone(), geo_id.clone()); }
+            let sum = |column: &Option<Float64Chunked>| column.as_ref()
+                .map_or(0.0, |column| units.iter().filter_map(|&u| column.get(u)).sum());
+            area.push(sum(&block_area));
+            land.push(sum(&block_land));
+            water.push(sum(&block_water));
+            geometries.push(blocks.region.union_of(units.iter().map(|&u| UnitId(u as u32))));
+            names.push(precinct_names[p].clone());
+            geo_ids.push(geo_id);
+        }
+
+        let region = Region::new(geometries, None)
+            .map_err(|e| anyhow!("Region construction failed for VTDs from {}: {:?}", source.path.display(), e))?;
+        let centroids = region.unit_ids().map(|uid| region.centroid(uid)).collect::<Vec<_>>();
+
+        let df = DataFrame::new(vec![
+            Column::new("geo_id".into(), geo_ids.iter().map(|geo_id| geo_id.id().to_string()).collect::<Vec<_>>()),
+            Column::new("name".into(), names),
+            Column::new("centroid_lon".into(), centroids.iter().map(|c| c.x).collect::<Vec<_>>()),
+            Column::new("centroid_lat".into(), centroids.iter().map(|c| c.y).collect::<Vec<_>>()),
+            Column::new("area_m2".into(), area),
+            Column::new("land_m2".into(), land),
+            Column::new("water_m2".into(), water),
+        ])?.with_row_index("idx".into(), None)?;
+
+        let index = geo_ids.iter().enumerate()
+            .map(|(i, geo_id)| (geo_id.clone(), i as u32))
+            .collect();
+        let parents = vec![ParentRefs::default(); geo_ids.len()];
+        let unit_weights = Arc::new(crate::graph::WeightMatrix::from_dataframe(&df));
+
+        Ok((Self::new(GeoType::VTD, geo_ids, index, parents, df, unit_weights, Arc::new(region)), crosswalk))
+    }
+}
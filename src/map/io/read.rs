@@ -1,12 +1,13 @@
 use std::{path::Path, sync::Arc};
 
 use anyhow::{Context, Result};
+use geograph::{GeometryPrecision, Region};
 use polars::frame::DataFrame;
 
 use crate::{
     graph::WeightMatrix,
-    map::{GeoId, GeoType, Map, MapLayer, ParentRefs, util},
-    map::pack::{DiskPack, PackSource, PackFormat, PackFormats, Manifest},
+    map::{GeoId, GeoType, Map, MapLayer, ParentRefs},
+    map::pack::{PackSource, PackFormat, PackFormats, Manifest, VerifiedPack, open_pack_store, surface_corruption},
 };
 
 /// Extract parent refs from the data DataFrame, returning (data, parents).
@@ -15,25 +16,28 @@ fn unpack_layer_data(data: DataFrame, _ty: GeoType) -> Result<(DataFrame, Vec<Pa
     let data_only = data.select_by_range(0..data.width() - 5)
         .with_context(|| format!("Expected at least 6 columns in data, got {}", data.width()))?;
 
-    let parents = (0..data_only.height()).map(|i| {
-        Ok(ParentRefs::new([
-            data.column("parent_state").ok()
-                .map(|c| c.str()).transpose()?
-                .and_then(|c| c.get(i).map(|s| GeoId::new(GeoType::State, s))),
-            data.column("parent_county").ok()
-                .map(|c| c.str()).transpose()?
-                .and_then(|c| c.get(i).map(|s| GeoId::new(GeoType::County, s))),
-            data.column("parent_tract").ok()
-                .map(|c| c.str()).transpose()?
-                .and_then(|c| c.get(i).map(|s| GeoId::new(GeoType::Tract, s))),
-            data.column("parent_group").ok()
-                .map(|c| c.str()).transpose()?
-                .and_then(|c| c.get(i).map(|s| GeoId::new(GeoType::Group, s))),
-            data.column("parent_vtd").ok()
-                .map(|c| c.str()).transpose()?
-                .and_then(|c| c.get(i).map(|s| GeoId::new(GeoType::VTD, s))),
-        ]))
-    }).collect::<Result<_>>()?;
+    // Parents of each type share one string table, so repeated parents (e.g. the county of every
+    // block) are stored once.
+    let parent_column = |name: &str, ty: GeoType| -> Result<Vec<Option<GeoId>>> {
+        let Ok(column) = data.column(name) else { return Ok(vec![None; data_only.height()]) };
+        let column = column.str()?;
+        let mut geo_ids = GeoId::new_all(ty, column.iter().flatten()).into_iter();
+        Ok(column.iter().map(|id| id.and_then(|_| geo_ids.next())).collect())
+    };
+    let mut columns = Vec::new();
+    for (name, ty) in [
+        ("parent_state", GeoType::State),
+        ("parent_county", GeoType::County),
+        ("parent_tract", GeoType::Tract),
+        ("parent_group", GeoType::Group),
+        ("parent_vtd", GeoType::VTD),
+    ] {
+        columns.push(parent_column(name, ty)?.into_iter());
+    }
+
+    let parents = (0..data_only.height())
+        .map(|_| ParentRefs::new(std::array::from_fn(|i| columns[i].next().flatten())))
+        .collect();
 
     Ok((data_only, parents))
 }
@@ -42,7 +46,8 @@ fn unpack_layer_data(data: DataFrame, _ty: GeoType) -> Result<(DataFrame, Vec<Pa
 fn read_layer_from_pack_source_with_formats(
     ty: GeoType,
     src: &dyn PackSource,
-    formats: &PackFormats
+    formats: &PackFormats,
+    precision: GeometryPrecision,
 ) -> Result<MapLayer> {
     let layer_name = ty.to_str();
 
@@ -77,15 +82,21 @@ fn read_layer_from_pack_source_with_formats(
         .with_context(|| format!("Failed to unpack data for layer: {}", layer_name))?;
 
     // geo ids / index
-    let geo_ids: Vec<GeoId> = unit_data.column("geo_id")?.str()?.into_no_null_iter()
-        .map(|val| GeoId::new(ty, val))
-        .collect();
+    let geo_ids = GeoId::new_all(ty, unit_data.column("geo_id")?.str()?.into_no_null_iter());
 
     let index = geo_ids.iter().enumerate()
         .map(|(i, geo_id)| (geo_id.clone(), i as u32))
         .collect();
 
-    // region — required (geom/{layer_name}.region.gz or legacy .region)
+    let region = read_region_from_pack_source(ty, src, precision)?;
+
+    let unit_weights = Arc::new(WeightMatrix::from_dataframe(&unit_data));
+    Ok(MapLayer::new(ty, geo_ids, index, parents, unit_data, unit_weights, Arc::new(region)))
+}
+
+/// Read the region section of a layer (geom/{layer_name}.region.gz or legacy .region).
+pub(super) fn read_region_from_pack_source(ty: GeoType, src: &dyn PackSource, precision: GeometryPrecision) -> Result<Region> {
+    let layer_name = ty.to_str();
     let region_file = if src.has(&format!("geom/{layer_name}.region.gz")) {
         format!("geom/{layer_name}.region.gz")
     } else {
@@ -94,18 +105,16 @@ fn read_layer_from_pack_source_with_formats(
     let region_bytes = src.get(&region_file)
         .with_context(|| format!("Pack missing required region file: {}", region_file))?;
     // Auto-detect: gzip magic = [1f 8b], raw geograph magic = b"OMRP"
-    let region = if region_bytes.starts_with(&[0x1f, 0x8b]) {
+    if region_bytes.starts_with(&[0x1f, 0x8b]) {
         let mut gz = flate2::read::GzDecoder::new(region_bytes.as_ref());
-        geograph::io::read(&mut gz)
+        geograph::io::read_with_precision(&mut gz, precision)
     } else {
-        geograph::io::read(&mut region_bytes.as_ref())
-    }.map_err(|e| anyhow::anyhow!("Failed to deserialize region for {layer_name}: {e:?}"))?;
-
-    let unit_weights = Arc::new(WeightMatrix::from_dataframe(&unit_data));
-    Ok(MapLayer::new(ty, geo_ids, index, parents, unit_data, unit_weights, Arc::new(region)))
+        geograph::io::read_with_precision(&mut region_bytes.as_ref(), precision)
+    }.map_err(|e| anyhow::anyhow!("Failed to deserialize region for {layer_name}: {e:?}"))
 }
 
 /// Detect the data format from file extensions in the pack.
+#[cfg_attr(not(feature = "parquet"), allow(unused_variables))]
 fn detect_formats_from_files(src: &dyn PackSource) -> PackFormats {
     #[cfg(feature = "parquet")]
     for ty in GeoType::ALL {
@@ -117,7 +126,7 @@ fn detect_formats_from_files(src: &dyn PackSource) -> PackFormats {
 }
 
 /// Read map from any PackSource using format information from manifest.
-fn read_map_from_pack_source_with_formats(src: &dyn PackSource, formats: &PackFormats) -> Result<Map> {
+fn read_map_from_pack_source_with_formats(src: &dyn PackSource, formats: &PackFormats, precision: GeometryPrecision) -> Result<Map> {
     let mut map = Map::default();
 
     // Determine data file extension from format
@@ -135,7 +144,7 @@ fn read_map_from_pack_source_with_formats(src: &dyn PackSource, formats: &PackFo
         }
 
         // Load the layer - if it fails, return the error (don't silently skip)
-        let layer = read_layer_from_pack_source_with_formats(ty, src, formats)
+        let layer = read_layer_from_pack_source_with_formats(ty, src, formats, precision)
             .with_context(|| format!("Failed to load layer {}", ty.to_str()))?;
         map.insert(layer);
     }
@@ -186,24 +195,37 @@ impl Map {
         ))
     }
 
-    /// Read a map from a pack directory at `path`.
+    /// Read a map from the pack at `path`: a directory, zip archive or `.openmander` bundle.
+    #[inline]
     pub fn read_from_pack(path: &Path) -> Result<Self> {
-        util::require_dir_exists(path)?;
-        let src = DiskPack::new(path);
+        Self::read_from_pack_with_precision(path, GeometryPrecision::F64)
+    }
+
+    /// Read a map from the pack at `path` (a directory, zip archive or `.openmander` bundle),
+    /// storing unit geometries at `precision`. Each section is checked against the hash in the
+    /// pack's manifest as it is read, so a damaged pack fails with a [`crate::CorruptSection`].
+    pub fn read_from_pack_with_precision(path: &Path, precision: GeometryPrecision) -> Result<Self> {
+        let store = open_pack_store(path)?;
+        let src: &dyn PackSource = &*store;
         
         // Try to read format from manifest first
         if src.has("manifest.json") {
-            match Manifest::from_pack_source(&src) {
+            match Manifest::from_pack_source(src) {
                 Ok(manifest) => {
-                    let manifest_formats = manifest.formats();
+                    let manifest_formats = &manifest.formats;
                     // If the manifest reports the default data format, detect from file extensions
                     // instead in case this is an old manifest without an explicit formats field.
                     let formats = if manifest_formats.data == PackFormats::default().data {
-                        detect_formats_from_files(&src)
+                        detect_formats_from_files(src)
                     } else {
                         manifest_formats.clone()
                     };
-                    return read_map_from_pack_source_with_formats(&src, &formats);
+                    let src = VerifiedPack::new(src, path.display().to_string(), manifest.files);
+                    let mut map = read_map_from_pack_source_with_formats(&src, &formats, precision)
+                        .map_err(surface_corruption)?;
+                    map.provenance = manifest.provenance;
+                    map.build = manifest.build;
+                    return Ok(map);
                 }
                 Err(_) => {
                     // If manifest parsing fails, fall back to detection
@@ -212,13 +234,21 @@ impl Map {
         }
         
         // Fall back to format detection for backward compatibility (no manifest or manifest parse failed)
-        let formats = detect_formats_from_files(&src);
-        read_map_from_pack_source_with_formats(&src, &formats)
+        let formats = detect_formats_from_files(src);
+        read_map_from_pack_source_with_formats(src, &formats, precision)
     }
 
     /// Read a map from any [`PackSource`] with the specified format.
+    #[inline]
     pub fn read_from_pack_source(src: &dyn PackSource, format: PackFormat) -> Result<Self> {
+        Self::read_from_pack_source_with_precision(src, format, GeometryPrecision::F64)
+    }
+
+    /// Read a map from any [`PackSource`] with the specified format, storing unit geometries at `precision`.
+    /// Sections are checked against the hashes in the pack's manifest, if it has one.
+    pub fn read_from_pack_source_with_precision(src: &dyn PackSource, format: PackFormat, precision: GeometryPrecision) -> Result<Self> {
         let formats = PackFormats::from_pack_format(format);
-        read_map_from_pack_source_with_formats(src, &formats)
+        read_map_from_pack_source_with_formats(&VerifiedPack::from_manifest(src, "pack")?, &formats, precision)
+            .map_err(surface_corruption)
     }
 }
@@ -0,0 +1,35 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use polars::prelude::DataType;
+
+use crate::{io::shp::{ShapefileColumn, ShapefileSchema, write_shapefile}, map::MapLayer};
+
+impl MapLayer {
+    /// Export the layer as an ESRI shapefile at `path` (`.shp`, with `.shx`, `.dbf` and `.prj`
+    /// files next to it), with one polygon per unit. Each record has the unit's `GEOID`, followed
+    /// by the fields of `schema` holding the named data columns.
+    pub fn write_shapefile(&self, path: &Path, schema: &ShapefileSchema) -> Result<()> {
+        self.require_geometry("[MapLayer.write_shapefile]")?;
+
+        let geo_ids = self.geo_ids.iter().map(|geo_id| geo_id.id().to_string()).collect();
+        let mut fields = vec![("GEOID".to_string(), ShapefileColumn::Text(geo_ids))];
+        for (name, column) in &schema.fields {
+            let values = self.unit_data.column(column)
+                .with_context(|| format!("[MapLayer.write_shapefile] Unknown column '{column}'"))?;
+            let values = if values.dtype().is_primitive_numeric() {
+                let values = values.cast(&DataType::Float64)?;
+                ShapefileColumn::Number(values.f64()?.iter().map(|value| value.unwrap_or(f64::NAN)).collect())
+            } else {
+                let values = values.cast(&DataType::String)?;
+                ShapefileColumn::Text(values.str()?.iter().map(|value| value.unwrap_or_default().to_string()).collect())
+            };
+            fields.push((name.clone(), values));
+        }
+
+        let shapes = (0..self.len())
+            .map(|i| self.region.geometry(geograph::UnitId(i as u32)).into_owned())
+            .collect::<Vec<_>>();
+        write_shapefile(path, &shapes, &fields)
+    }
+}
@@ -1,53 +1,88 @@
-use std::{io::Write, path::Path};
+use std::{collections::BTreeMap, io::Write, path::Path};
 
-use anyhow::{Context, Result, anyhow};
+use anyhow::{Context, Result, anyhow, ensure};
 use geo::Coord;
+use geograph::UnitId;
 use polars::prelude::{ChunkAgg, DataType};
 
-use crate::{io::svg::Viewport, map::MapLayer};
+use crate::{io::svg::{Annotations, SvgOptions, SymbolStyle, Viewport}, map::{GeoType, MapLayer}};
 
 impl MapLayer {
     /// Write the layer as an SVG file.
     /// If `series` is `Some(col)`, polygons are colored by that numeric column.
+    #[inline]
     pub fn to_svg(&self, path: &Path, series: Option<&str>) -> Result<()> {
+        self.to_svg_grouped(path, series, None)
+    }
+
+    /// Write the layer as an SVG file, streaming each shape to disk as it is drawn.
+    /// If `group_by` is `Some(ty)`, shapes are wrapped in one `<g id="{ty}-{geoid}">` per
+    /// parent unit of that (larger) type, e.g. one group per county.
+    #[inline]
+    pub fn to_svg_grouped(&self, path: &Path, series: Option<&str>, group_by: Option<GeoType>) -> Result<()> {
+        self.to_svg_with_options(path, series, group_by, &SvgOptions::default())
+    }
+
+    /// Write the layer as an SVG file (see [`MapLayer::to_svg_grouped`]) with the given projection and size.
+    pub fn to_svg_with_options(&self, path: &Path, series: Option<&str>, group_by: Option<GeoType>, options: &SvgOptions) -> Result<()> {
         let mut writer = crate::io::svg::SvgWriter::new(path)?;
-        self.render_svg(&mut writer, 1200, 10, series)?;
+        self.render_svg(&mut writer, options, series, group_by)?;
         writer.flush()?;
         Ok(())
     }
 
     /// Return the layer as an SVG string (for browser/WASM use).
     /// If `series` is `Some(col)`, polygons are colored by that numeric column.
+    #[inline]
     pub fn to_svg_string(&self, series: Option<&str>) -> Result<String> {
+        self.to_svg_string_grouped(series, None)
+    }
+
+    /// Return the layer as an SVG string, optionally grouped by parent unit (see [`MapLayer::to_svg_grouped`]).
+    #[inline]
+    pub fn to_svg_string_grouped(&self, series: Option<&str>, group_by: Option<GeoType>) -> Result<String> {
+        self.to_svg_string_with_options(series, group_by, &SvgOptions::default())
+    }
+
+    /// Return the layer as an SVG string with the given projection and size.
+    pub fn to_svg_string_with_options(&self, series: Option<&str>, group_by: Option<GeoType>, options: &SvgOptions) -> Result<String> {
         let mut writer = crate::io::svg::SvgStringWriter::new();
-        self.render_svg(&mut writer, 1200, 10, series)?;
+        self.render_svg(&mut writer, options, series, group_by)?;
         writer.into_string()
     }
 
-    fn render_svg(&self, writer: &mut impl Write, width: i32, margin: i32, series: Option<&str>) -> Result<()> {
+    fn render_svg(&self, writer: &mut impl Write, options: &SvgOptions, series: Option<&str>, group_by: Option<GeoType>) -> Result<()> {
+        self.require_geometry("[MapLayer.to_svg]")?;
         let region = &*self.region;
-        let bounds = region.bounds_all();
 
-        let shapes: Vec<geo::MultiPolygon<f64>> = region.unit_ids()
-            .map(|u| region.geometry(u).clone())
-            .collect();
+        let colors = series.map(|series| self.compute_fill_colors(series)).transpose()?;
+        let groups = group_by.map(|ty| self.svg_groups(ty)).transpose()?;
 
         let centroids = self.centroids();
-        let vp = Viewport::new(bounds, width as f64, margin as f64);
+        // The legend and metrics box describe a plan, so only the scale bar and north arrow apply here.
+        let annotations = Annotations { legend: false, metrics: None, ..options.annotations.clone() };
+        let footer = crate::io::svg::annotation_height(&annotations, options.width, 0, 0);
+        let vp = Viewport::for_region(region, options).with_footer(footer);
         let project = move |coord: &Coord<f64>| vp.project(coord);
 
-        crate::io::svg::write_svg_header(writer, &vp)?;
+        crate::io::svg::write_svg_header(writer, &vp, &options.accessibility)?;
         crate::io::svg::write_svg_styles(writer)?;
 
-        if let Some(series) = series {
-            crate::io::svg::draw_polygons_with_fill(
-                writer,
-                &shapes,
-                &self.compute_fill_colors(series)?,
-                &project,
-            )?;
-        } else {
-            crate::io::svg::draw_polygons(writer, &shapes, &project)?;
+        let draw_units = |writer: &mut _, units: &[usize]| -> Result<()> {
+            let shapes = units.iter().map(|&u| region.geometry(UnitId(u as u32)));
+            match &colors {
+                Some(colors) => crate::io::svg::draw_polygons_with_fill(writer, shapes.zip(units.iter().map(|&u| &colors[u])), &project),
+                None => crate::io::svg::draw_polygons(writer, shapes, &project),
+            }
+        };
+
+        match groups {
+            Some(groups) => for (id, units) in groups {
+                crate::io::svg::write_group_start(writer, "grp", &id, None, None)?;
+                draw_units(writer, &units)?;
+                crate::io::svg::write_group_end(writer)?;
+            },
+            None => draw_units(writer, &(0..self.len()).collect::<Vec<_>>())?,
         }
 
         let edges = region.unit_ids()
@@ -56,14 +91,47 @@ impl MapLayer {
                 region.adjacency().neighbors(u).iter()
                     .filter_map(move |&v| (v.0 as usize > i).then_some((i, v.0 as usize)))
             })
-            .map(|(i, j)| (&centroids[i], &centroids[j]))
-            .collect::<Vec<_>>();
-        crate::io::svg::draw_edges(writer, &edges, &project)?;
+            .map(|(i, j)| (&centroids[i], &centroids[j]));
+        crate::io::svg::draw_edges(writer, edges, &project)?;
+        if let Some(symbols) = &options.symbols {
+            ensure!(symbols.layer == self.ty(), "[to_svg] symbols for the {} layer cannot be drawn on the {} layer", symbols.layer.to_str(), self.ty().to_str());
+            self.write_symbols(writer, &symbols.style, &vp)?;
+        }
+        crate::io::svg::write_annotations(writer, &vp, &annotations, &[], &[])?;
 
         crate::io::svg::write_svg_footer(writer)?;
         Ok(())
     }
 
+    /// Draw `symbols` for the units of this layer in `vp`.
+    pub(crate) fn write_symbols(&self, writer: &mut impl Write, symbols: &SymbolStyle, vp: &Viewport) -> Result<()> {
+        let values = |series: &str| -> Result<Vec<f64>> {
+            ensure!(self.unit_weights.contains(series), "[to_svg] missing series {:?} in {} layer", series, self.ty().to_str());
+            Ok((0..self.len()).map(|u| self.unit_weights.get_as_f64(series, u).unwrap_or(0.0)).collect())
+        };
+        match symbols {
+            SymbolStyle::DotDensity { series, per_dot, seed } => {
+                ensure!(*per_dot > 0.0, "[to_svg] people per dot must be positive, got {per_dot}");
+                let series = series.iter().map(|name| Ok((name.as_str(), values(name)?))).collect::<Result<Vec<_>>>()?;
+                crate::io::svg::write_dot_density(writer, &self.region, &series, *per_dot, *seed, vp)
+            }
+            SymbolStyle::Proportional { series, max_radius } => {
+                crate::io::svg::write_proportional(writer, &self.region, series, &values(series)?, *max_radius, vp)
+            }
+        }
+    }
+
+    /// Units of this layer grouped by their parent of type `ty`, keyed by SVG group id (e.g. `county-17019`).
+    fn svg_groups(&self, ty: GeoType) -> Result<BTreeMap<String, Vec<usize>>> {
+        ensure!((ty as usize) < (self.ty() as usize), "[to_svg] cannot group {} units by {}", self.ty().to_str(), ty.to_str());
+        let mut groups = BTreeMap::<String, Vec<usize>>::new();
+        for (u, parents) in self.parents.iter().enumerate() {
+            let id = parents.get(ty).map_or("none", |geo_id| geo_id.id());
+            groups.entry(format!("{}-{}", ty.to_str(), id)).or_default().push(u);
+        }
+        Ok(groups)
+    }
+
     /// Compute a choropleth color for each row based on a numeric column.
     /// Returns one hex color string per geometry.
     fn compute_fill_colors(&self, series: &str) -> Result<Vec<String>> {
@@ -2,21 +2,14 @@ use std::{collections::BTreeMap, path::Path};
 
 use anyhow::{Context, Result};
 use polars::{df, frame::DataFrame, prelude::DataFrameJoinOps};
-use sha2::{Digest, Sha256};
 
 use crate::{
     map::{GeoType, Map, MapLayer, ParentRefs, util},
-    map::pack::{DiskPack, FileHash, Manifest, PackSink, PackFormat, PackFormats},
+    map::pack::{FileHash, LayerManifest, Manifest, PackSink, PackFormat, PackFormats, PathLock, create_pack_store, is_container_path, sha256_hex},
 };
 
-/// Computes the SHA-256 hash of the given bytes and returns it as a hex string.
-fn sha256_bytes(bytes: &[u8]) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(bytes);
-    hex::encode(hasher.finalize())
-}
-
 /// Get the recommended PMTiles zoom range for a given layer type.
+#[cfg(feature = "pmtiles")]
 fn pmtiles_zoom_range_for_layer(ty: GeoType) -> (u8, u8) {
     match ty {
         GeoType::State  =>  (4, 14),
@@ -56,7 +49,7 @@ impl MapLayer {
         &self,
         sink: &mut dyn PackSink,
         formats: &PackFormats,
-        counts: &mut BTreeMap<&'static str, usize>,
+        layers: &mut BTreeMap<String, LayerManifest>,
         hashes: &mut BTreeMap<String, FileHash>,
     ) -> Result<()> {
         let layer_name = self.ty().to_str();
@@ -68,19 +61,19 @@ impl MapLayer {
         };
         let data_file = format!("data/{layer_name}.{data_ext}");
 
-        counts.insert(layer_name, self.geo_ids.len());
-
         // data (parquet or csv)
+        let data = self.pack_data()?;
+        layers.insert(layer_name.into(), LayerManifest::new(self.ty(), data_file.clone(), &data));
         let data_bytes = match formats.data.as_str() {
             #[cfg(feature = "parquet")]
-            "parquet" => crate::io::parquet::write_parquet_bytes(&self.pack_data()?)?,
-            "csv" => crate::io::csv::write_csv_bytes(&self.pack_data()?)?,
+            "parquet" => crate::io::parquet::write_parquet_bytes(&data)?,
+            "csv" => crate::io::csv::write_csv_bytes(&data)?,
             #[cfg(not(feature = "parquet"))]
             "parquet" => return Err(anyhow::anyhow!("Parquet format requires 'parquet' feature to be enabled")),
             _ => return Err(anyhow::anyhow!("Unsupported data format: {}. Use 'parquet' or 'csv'.", formats.data)),
         };
         sink.put(&data_file, &data_bytes)?;
-        hashes.insert(data_file, FileHash { sha256: sha256_bytes(&data_bytes) });
+        hashes.insert(data_file, FileHash { sha256: sha256_hex(&data_bytes) });
 
         // region — geom/{layer_name}.region.gz
         let region_file = format!("geom/{layer_name}.region.gz");
@@ -92,28 +85,38 @@ impl MapLayer {
             gz.finish().context("Failed to finish gzip encoding for region")?;
         }
         sink.put(&region_file, &region_bytes)?;
-        hashes.insert(region_file, FileHash { sha256: sha256_bytes(&region_bytes) });
+        hashes.insert(region_file, FileHash { sha256: sha256_hex(&region_bytes) });
 
         Ok(())
     }
 }
 
 impl Map {
-    /// Write pack to disk directory using the default format.
+    /// Write pack to disk using the default format (see [`Map::write_to_pack_with_format`]).
     pub fn write_to_pack(&self, path: &Path) -> Result<()> {
         self.write_to_pack_with_format(path, PackFormat::default())
     }
 
-    /// Write pack to disk directory with the specified format.
+    /// Write pack to disk with the specified format: to a directory, or to a single file if `path`
+    /// ends in `.zip` or `.openmander` (see [`create_pack_store`]).
+    /// Other processes writing, building or downloading the same pack wait for this write to finish.
     pub fn write_to_pack_with_format(&self, path: &Path, format: PackFormat) -> Result<()> {
-        for dir in ["data", "geom"] {
-            util::ensure_dir_exists(&path.join(dir))?;
-        }
+        let _lock = PathLock::acquire(path, 0)?;
+        self.write_to_pack_unlocked(path, format)
+    }
 
-        let mut sink = DiskPack::new(path);
-        self.write_to_pack_sink_with_format(&mut sink, path, format)?;
+    /// Write pack to disk (see [`Map::write_to_pack_with_format`]), for callers already holding
+    /// the pack's lock.
+    pub(crate) fn write_to_pack_unlocked(&self, path: &Path, format: PackFormat) -> Result<()> {
+        if !is_container_path(path) {
+            for dir in ["data", "geom"] {
+                util::ensure_dir_exists(&path.join(dir))?;
+            }
+        }
 
-        Ok(())
+        let mut store = create_pack_store(path)?;
+        self.write_to_pack_sink_with_format(&mut *store, path, format)?;
+        store.flush()
     }
 
     /// Write pack into any [`PackSink`] using the default format.
@@ -124,22 +127,22 @@ impl Map {
     /// Write pack into any [`PackSink`] with the specified format.
     pub fn write_to_pack_sink_with_format(&self, sink: &mut dyn PackSink, pack_root_for_manifest: &Path, format: PackFormat) -> Result<()> {
         let mut file_hashes: BTreeMap<String, FileHash> = BTreeMap::new();
-        let mut counts: BTreeMap<&'static str, usize> = BTreeMap::new();
+        let mut layers: BTreeMap<String, LayerManifest> = BTreeMap::new();
 
         let formats = PackFormats::from_pack_format(format);
         
         // Special handling for PMTiles: write all layers to a single file
         #[cfg(feature = "pmtiles")]
         if format == PackFormat::Pmtiles {
-            return self.write_to_pack_sink_with_multilayer_pmtiles(sink, pack_root_for_manifest, &formats, &mut counts, &mut file_hashes);
+            return self.write_to_pack_sink_with_multilayer_pmtiles(sink, pack_root_for_manifest, &formats, &mut layers, &mut file_hashes);
         }
         
         for layer in self.layers_iter() {
-            layer.write_to_pack_sink_with_formats(sink, &formats, &mut counts, &mut file_hashes)?;
+            layer.write_to_pack_sink_with_formats(sink, &formats, &mut layers, &mut file_hashes)?;
         }
 
         // Create manifest with format information
-        let manifest = Manifest::new(pack_root_for_manifest, counts, file_hashes, formats);
+        let manifest = Manifest::new(pack_root_for_manifest, self, layers, file_hashes, formats);
         let manifest_bytes = serde_json::to_vec_pretty(&manifest)?;
         sink.put("manifest.json", &manifest_bytes)?;
 
@@ -153,20 +156,21 @@ impl Map {
         sink: &mut dyn PackSink,
         pack_root_for_manifest: &Path,
         formats: &PackFormats,
-        counts: &mut BTreeMap<&'static str, usize>,
+        layers: &mut BTreeMap<String, LayerManifest>,
         file_hashes: &mut BTreeMap<String, FileHash>,
     ) -> Result<()> {
+        self.require_geometry("[Map.write_to_pack] PMTiles format")?;
         // Write data and region files for each layer
         for layer in self.layers_iter() {
             let layer_name = layer.ty().to_str();
             let data_file = format!("data/{layer_name}.csv");
 
-            counts.insert(layer_name, layer.geo_ids.len());
-
             // Write data file
-            let data_bytes = crate::io::csv::write_csv_bytes(&layer.pack_data()?)?;
+            let data = layer.pack_data()?;
+            layers.insert(layer_name.into(), LayerManifest::new(layer.ty(), data_file.clone(), &data));
+            let data_bytes = crate::io::csv::write_csv_bytes(&data)?;
             sink.put(&data_file, &data_bytes)?;
-            file_hashes.insert(data_file.clone(), FileHash { sha256: sha256_bytes(&data_bytes) });
+            file_hashes.insert(data_file.clone(), FileHash { sha256: sha256_hex(&data_bytes) });
 
             // Write region file
             let region_file = format!("geom/{layer_name}.region.gz");
@@ -178,7 +182,7 @@ impl Map {
                 gz.finish().context("Failed to finish gzip encoding for region")?;
             }
             sink.put(&region_file, &region_bytes)?;
-            file_hashes.insert(region_file, FileHash { sha256: sha256_bytes(&region_bytes) });
+            file_hashes.insert(region_file, FileHash { sha256: sha256_hex(&region_bytes) });
         }
 
         // Collect all layers for the combined multi-layer PMTiles file.
@@ -213,11 +217,11 @@ impl Map {
             let geom_file = "geom/geometries.pmtiles";
             let geom_bytes = crate::io::pmtiles::write_to_pmtiles_bytes(pmtiles_layers)?;
             sink.put(geom_file, &geom_bytes)?;
-            file_hashes.insert(geom_file.to_string(), FileHash { sha256: sha256_bytes(&geom_bytes) });
+            file_hashes.insert(geom_file.to_string(), FileHash { sha256: sha256_hex(&geom_bytes) });
         }
         
         // Create manifest
-        let manifest = Manifest::new(pack_root_for_manifest, (*counts).clone(), (*file_hashes).clone(), (*formats).clone());
+        let manifest = Manifest::new(pack_root_for_manifest, self, (*layers).clone(), (*file_hashes).clone(), (*formats).clone());
         let manifest_bytes = serde_json::to_vec_pretty(&manifest)?;
         sink.put("manifest.json", &manifest_bytes)?;
         
@@ -1,9 +1,10 @@
 use std::{collections::HashMap, fmt, sync::Arc};
 
+use anyhow::{Result, ensure};
 use geo::{MultiPolygon, Point};
 use polars::frame::DataFrame;
 
-use geograph::Region;
+use geograph::{Region, UnitId};
 
 use crate::{graph::{UnitGraph, WeightMatrix}, map::{GeoId, GeoType, ParentRefs}};
 
@@ -54,8 +55,17 @@ impl MapLayer {
     /// Get a reference to the DataFrame containing entity data for this layer.
     #[inline] pub fn data(&self) -> &DataFrame { &self.unit_data }
 
+    /// Check whether this layer carries unit geometry (layers read from a graph-only pack do not).
+    #[inline] pub fn has_geometry(&self) -> bool { self.region.has_geometry() }
+
+    /// Fail with a clear error if this layer was loaded without geometry; `context` names the caller.
+    pub(crate) fn require_geometry(&self, context: &str) -> Result<()> {
+        ensure!(self.has_geometry(), "{context} requires geometry, but the {} layer was loaded without it", self.ty.to_str());
+        Ok(())
+    }
+
     /// Get the union of all MultiPolygons in this layer into a single MultiPolygon.
-    /// Note that this can be computationally expensive for large layers.
+    /// Note that this can be computationally expensive for large layers. Empty without geometry.
     #[inline]
     pub fn union(&self) -> MultiPolygon<f64> {
         self.region.union_of(self.region.unit_ids())
@@ -89,6 +99,20 @@ impl MapLayer {
 
     /// Get a reference to the Region for this layer.
     #[inline] pub(crate) fn region(&self) -> &Region { &self.region }
+
+    /// Get an Arc clone of the Region for this layer.
+    #[inline] pub(crate) fn shared_region(&self) -> Arc<Region> { self.region.clone() }
+
+    /// Adjacencies with no shared boundary (e.g. patched island bridges), as pairs `(a, b)` with `a < b`.
+    pub(super) fn forced_adjacencies(&self) -> Vec<(UnitId, UnitId)> {
+        let adjacency = self.region.adjacency();
+        if !adjacency.has_weights() { return Vec::new() }
+        (0..adjacency.num_directed_edges())
+            .filter(|&k| adjacency.weight_at(k) == 0.0)
+            .filter_map(|k| adjacency.edge_at(k))
+            .filter(|(a, b)| a < b)
+            .collect()
+    }
 }
 
 impl fmt::Debug for MapLayer {
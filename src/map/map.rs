@@ -1,18 +1,27 @@
-use crate::map::{GeoType, MapLayer};
+use std::{collections::BTreeMap, sync::Arc};
 
-use anyhow::{anyhow, Result};
+use crate::{graph::WeightMatrix, map::{GeoType, MapLayer, pack::BuildRecord}};
+
+use anyhow::{anyhow, ensure, Result};
+use polars::prelude::{NamedFrom, Series};
+use geograph::GeometryPrecision;
 
 /// Map struct that contains geographic data and geometries for redistricting.
-#[derive(Debug, Default)]
+///
+/// Layers are reference-counted, so cloning a map is cheap and a loaded map can be
+/// shared across threads; mutating a shared layer copies it first.
+#[derive(Debug, Default, Clone)]
 pub struct Map {
-    layers: [Option<MapLayer>; GeoType::COUNT],
+    layers: [Option<Arc<MapLayer>>; GeoType::COUNT],
+    pub(super) provenance: BTreeMap<String, String>, // Layer name -> note on where its data came from
+    pub(super) build: Option<BuildRecord>,           // How the map's pack was built, if it was
 }
 
 impl Map {
     /// Get a reference to a specific map layer by geographic type.
     #[inline]
     pub fn layer(&self, ty: GeoType) -> Option<&MapLayer> {
-        self.layers[ty as usize].as_ref()
+        self.layers[ty as usize].as_deref()
     }
 
     /// Get a reference to the lowest-level (basic unit) layer in the map.
@@ -29,29 +38,88 @@ impl Map {
             .ok_or_else(|| anyhow!("[Map] Missing region layer {:?}", GeoType::TOP))
     }
 
+    /// Get notes on where each layer's data came from, keyed by layer name (stored in the pack manifest).
+    #[inline]
+    pub fn provenance(&self) -> &BTreeMap<String, String> { &self.provenance }
+
+    /// Record where a layer's data came from.
+    pub fn set_provenance(&mut self, ty: GeoType, note: impl Into<String>) {
+        self.provenance.insert(ty.to_str().to_string(), note.into());
+    }
+
+    /// Get the files and options the map's pack was built from (stored in the pack manifest).
+    #[inline]
+    pub fn build_record(&self) -> Option<&BuildRecord> { self.build.as_ref() }
+
+    /// Record the files and options the map was built from.
+    pub fn set_build_record(&mut self, build: BuildRecord) {
+        self.build = Some(build);
+    }
+
     /// Get a mutable reference to a specific map layer by geographic type.
-    #[cfg(feature = "download")]
     #[inline]
     pub(super) fn layer_mut(&mut self, ty: GeoType) -> Option<&mut MapLayer> {
-        self.layers[ty as usize].as_mut()
+        self.layers[ty as usize].as_mut().map(Arc::make_mut)
     }
 
     /// Get all non-null map layers as an iterator.
     #[inline]
     pub fn layers_iter(&self) -> impl Iterator<Item = &MapLayer> {
-        self.layers.iter().filter_map(|layer| layer.as_ref())
+        self.layers.iter().filter_map(|layer| layer.as_deref())
     }
 
     /// Get all non-null map layers as an iterator.
     #[inline]
     pub fn layers_iter_mut(&mut self) -> impl Iterator<Item = &mut MapLayer> {
-        self.layers.iter_mut().filter_map(|layer| layer.as_mut())
+        self.layers.iter_mut().filter_map(|layer| layer.as_mut().map(Arc::make_mut))
+    }
+
+    /// Store the unit geometries of every layer at `precision`. Metrics are unaffected;
+    /// see [`GeometryPrecision`] for the trade-off.
+    pub fn set_geometry_precision(&mut self, precision: GeometryPrecision) {
+        for layer in self.layers_iter_mut() {
+            Arc::make_mut(&mut layer.region).set_geometry_precision(precision);
+        }
+    }
+
+    /// Check whether every layer carries unit geometry.
+    pub fn has_geometry(&self) -> bool {
+        self.layers_iter().all(MapLayer::has_geometry)
+    }
+
+    /// Fail with a clear error if any layer was loaded without geometry; `context` names the caller.
+    pub(crate) fn require_geometry(&self, context: &str) -> Result<()> {
+        self.layers_iter().try_for_each(|layer| layer.require_geometry(context))
+    }
+
+    /// Discard the unit geometries of every layer, keeping adjacency, unit metrics, and data.
+    /// Scoring and optimization still work; drawing and geometry exports return an error.
+    pub fn drop_geometry(&mut self) {
+        for layer in self.layers_iter_mut() {
+            Arc::make_mut(&mut layer.region).drop_geometry();
+        }
+    }
+
+    /// Add a float column of per-unit values to a layer (replacing any column of that name), e.g. to
+    /// map an ensemble statistic such as [`crate::Plan::unit_stability`]. The column is also
+    /// available as a weight series.
+    pub fn set_unit_column(&mut self, ty: GeoType, name: &str, values: Vec<f64>) -> Result<()> {
+        let layer = self.layer_mut(ty).ok_or_else(|| anyhow!("[Map.set_unit_column] Missing layer {:?}", ty))?;
+        ensure!(values.len() == layer.len(),
+            "[Map.set_unit_column] Expected {} values for layer {:?}, got {}", layer.len(), ty, values.len());
+        let column = Series::new(name.into(), values);
+        match layer.unit_data.column(name) {
+            Ok(_) => { layer.unit_data.replace(name, column)?; }
+            Err(_) => { layer.unit_data.with_column(column)?; }
+        }
+        layer.unit_weights = Arc::new(WeightMatrix::from_dataframe(&layer.unit_data));
+        Ok(())
     }
 
     /// Set a specific map layer, replacing any existing data for that geographic type.
     pub(crate) fn insert(&mut self, layer: MapLayer) {
         let ty = layer.ty();
-        self.layers[ty as usize] = Some(layer);
+        self.layers[ty as usize] = Some(Arc::new(layer));
     }
 
     /// Return per-unit geometry statistics for a given layer.
@@ -60,6 +128,7 @@ impl Map {
     pub fn geometry_stats(&self, ty: GeoType) -> Result<Vec<(String, usize, Vec<usize>, bool)>> {
         let map_layer = self.layer(ty)
             .ok_or_else(|| anyhow!("Layer {:?} not present in this map.", ty))?;
+        map_layer.require_geometry("[Map.geometry_stats]")?;
         let region = map_layer.region();
         let geo_ids = map_layer.geo_ids();
 
@@ -0,0 +1,124 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, ensure, Result};
+use polars::frame::DataFrame;
+
+use geograph::{Region, UnitId};
+
+use crate::{graph::WeightMatrix, map::{GeoType, Map, MapLayer}};
+
+impl MapLayer {
+    /// Concatenate two layers of the same type, recomputing adjacency from the combined geometry.
+    fn concat(&self, other: &MapLayer) -> Result<MapLayer> {
+        ensure!(self.ty() == other.ty(), "[MapLayer] Cannot merge {:?} layer with {:?} layer", self.ty(), other.ty());
+        if let Some(duplicate) = other.geo_ids.iter().find(|geo_id| self.index.contains_key(geo_id)) {
+            return Err(anyhow!("[MapLayer] {:?} {} appears in both layers", self.ty(), duplicate.id()));
+        }
+
+        let offset = self.len() as u32;
+        let geo_ids = self.geo_ids.iter().chain(&other.geo_ids).cloned().collect::<Vec<_>>();
+        let index = geo_ids.iter().enumerate()
+            .map(|(i, geo_id)| (geo_id.clone(), i as u32))
+            .collect();
+        let parents = self.parents.iter().chain(&other.parents).cloned().collect();
+
+        // Keep the columns common to both layers, in this layer's order and with this layer's types.
+        let columns = self.unit_data.get_columns().iter()
+            .filter(|column| column.name() != "idx")
+            .filter_map(|column| other.unit_data.column(column.name()).ok()
+                .map(|other_column| Ok((column.clone(), other_column.cast(column.dtype())?))))
+            .collect::<Result<Vec<_>>>()?;
+        let (ours, theirs): (Vec<_>, Vec<_>) = columns.into_iter().unzip();
+        let unit_data = DataFrame::new(ours)?
+            .vstack(&DataFrame::new(theirs)?)?
+            .with_row_index("idx".into(), None)?;
+        let unit_weights = Arc::new(WeightMatrix::from_dataframe(&unit_data));
+
+        let geometries = self.region.unit_ids().map(|uid| self.region.geometry(uid).into_owned())
+            .chain(other.region.unit_ids().map(|uid| other.region.geometry(uid).into_owned()))
+            .collect();
+        let forced = self.forced_adjacencies().into_iter()
+            .chain(other.forced_adjacencies().into_iter()
+                .map(|(a, b)| (UnitId(a.0 + offset), UnitId(b.0 + offset))))
+            .collect::<Vec<_>>();
+        let region = Region::new(geometries, None)
+            .map_err(|e| anyhow!("[MapLayer] Region construction failed for merged {:?} layer: {:?}", self.ty(), e))?
+            .with_forced_adjacencies(&forced);
+
+        Ok(MapLayer::new(self.ty(), geo_ids, index, parents, unit_data, unit_weights, Arc::new(region)))
+    }
+}
+
+impl Map {
+    /// Merge two maps covering disjoint areas (e.g. two neighboring states) into a single map.
+    ///
+    /// Layers present in both maps are concatenated, with adjacency across the shared boundary
+    /// recomputed from geometry. Only data columns present in both maps are kept. Layers present
+    /// in just one map are dropped, since they would not cover the merged area, along with the
+    /// parent references into them.
+    pub fn merge(&self, other: &Map) -> Result<Map> {
+        self.require_geometry("[Map.merge]")?;
+        other.require_geometry("[Map.merge]")?;
+        let mut map = Map::default();
+        for layer in self.layers_iter() {
+            if let Some(other_layer) = other.layer(layer.ty()) {
+                map.insert(layer.concat(other_layer)?);
+            }
+        }
+
+        ensure!(map.layer(GeoType::TOP).is_some() && map.layer(GeoType::BOTTOM).is_some(),
+            "[Map] Cannot merge maps without common {:?} and {:?} layers", GeoType::TOP, GeoType::BOTTOM);
+
+        let dropped = GeoType::ALL.into_iter().filter(|&ty| map.layer(ty).is_none()).collect::<Vec<_>>();
+        for layer in map.layers_iter_mut() {
+            for refs in &mut layer.parents {
+                dropped.iter().for_each(|&ty| refs.set(ty, None));
+            }
+        }
+
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{map::GeoId, test_maps};
+
+    /// Relabel the region of a synthetic map as state `id`, and point its blocks at `county`.
+    fn relabel(mut map: Map, id: &str, county: &str) -> Map {
+        let state = GeoId::new(GeoType::State, id);
+        let top = map.layer_mut(GeoType::TOP).unwrap();
+        top.geo_ids = vec![state.clone()];
+        top.index = [(state.clone(), 0)].into();
+        for refs in &mut map.layer_mut(GeoType::BOTTOM).unwrap().parents {
+            refs.set(GeoType::State, Some(state.clone()));
+            refs.set(GeoType::County, Some(GeoId::new(GeoType::County, county)));
+        }
+        map
+    }
+
+    #[test]
+    fn merged_maps_drop_refs_into_missing_layers() {
+        let a = relabel(test_maps::row(2, &["010010001001000", "010010001001001"]), "01", "01001");
+        // The second row sits to the right of the first.
+        let geoms = (2..4).map(|k| test_maps::rect(k as f64, k as f64 + 1.0, 0.0, 1.0)).collect();
+        let ids = vec!["020010001001000".to_string(), "020010001001001".to_string()];
+        let b = Map::from_parts(geoms, Some(ids), vec![("pop".into(), vec![1.0; 2])], &[]).unwrap();
+        let b = relabel(b, "02", "02001");
+        let merged = a.merge(&b).unwrap();
+
+        let blocks = merged.layer(GeoType::BOTTOM).unwrap();
+        assert_eq!(blocks.len(), 4);
+        assert!(blocks.parents().iter().all(|refs| refs.get(GeoType::County).is_none()));
+        let states = blocks.parents().iter().map(|refs| refs.get(GeoType::State).unwrap().id()).collect::<Vec<_>>();
+        assert_eq!(states, ["01", "01", "02", "02"]);
+
+        // The seam between the two rows becomes an edge, in the block and state graphs alike.
+        let (region, [a1, b0]) = (blocks.region(), [UnitId(1), UnitId(2)]);
+        assert!(region.are_adjacent(a1, b0));
+        assert_eq!(region.neighbors(a1), [UnitId(0), b0]);
+        assert!(!region.are_adjacent(UnitId(0), b0));
+        assert!(merged.layer(GeoType::TOP).unwrap().region().are_adjacent(UnitId(0), UnitId(1)));
+    }
+}
@@ -1,19 +1,49 @@
+mod crosswalk;
+mod footprint;
 mod geo_id;
 mod geo_ty;
+mod integrity;
 mod io;
 mod layer;
 mod map;
+mod merge;
 mod parent;
+mod parts;
+mod reorder;
+mod states;
+mod stats;
+mod subset;
+mod update;
 mod util;
 pub mod pack;
 
+pub use crosswalk::{CrosswalkEntry, VintageCrosswalk};
+pub use footprint::{MemoryFootprint, SharedBuffers};
 pub use geo_id::GeoId;
 pub use geo_ty::GeoType;
 pub use map::Map;
 pub use layer::MapLayer;
+pub use integrity::IntegrityIssue;
 pub use parent::ParentRefs;
+pub use states::{STATES, StateInfo};
+pub use stats::ColumnStats;
+pub use update::BoundaryChange;
 
-pub use pack::{PackFormat, PackSink, PackSource, DiskPack, MemPack, validate_pack};
+pub(crate) use io::{multilinestring_to_geojson, multipolygon_to_geojson};
+pub(crate) use stats::quantile;
+#[cfg(any(feature = "server", feature = "grpc"))]
+pub(crate) use pack::resolve_pack_path;
+
+pub use pack::{
+    BuildRecord, BundlePack, Citation, ColumnSchema, CorruptSection, LayerManifest, Manifest, PackFormat, PackPatch, PackSink, PackSource, PackStore,
+    DiskPack, MemPack, SourceRecord, ZipPack, apply_pack_patch, convert_pack, create_pack_store, diff_packs, open_pack_store,
+    VerifiedPack, cite_pack, validate_pack,
+};
 
 #[cfg(feature = "download")]
-pub use pack::{build_pack, download_pack};
+pub use pack::{
+    Artifact, BlockAssignSource, BlockCsvSource, BuildOptions, DataSource, DemographicSource, ElectionSource,
+    FieldMapping, HistoricalPlanSource, PrecinctShapefile, QaIssue, QaReason, QaReport, TigerSource,
+    build_pack, build_pack_with_sources, default_sources, download_pack, download_pack_without_geoms,
+    read_block_csv,
+};
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+/// A dataset a pack's data comes from, with the license it is published under and the citation
+/// its publisher asks for in publications using it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Citation {
+    pub source: String,   // Data source that used the dataset, e.g. "tiger"
+    pub dataset: String,  // Name of the dataset, e.g. "2020 TIGER/Line Shapefiles"
+    pub license: String,  // License or terms of use
+    pub citation: String, // Text to cite in publications
+}
+
+/// Public domain notice for Census Bureau products.
+const CENSUS_LICENSE: &str = "Public domain (U.S. Government work)";
+
+/// Terms for Dave's Redistricting block data.
+const DRA_LICENSE: &str = "See the terms of use at https://davesredistricting.org";
+
+impl Citation {
+    pub fn new(source: &str, dataset: &str, license: &str, citation: &str) -> Self {
+        Self { source: source.into(), dataset: dataset.into(), license: license.into(), citation: citation.into() }
+    }
+
+    /// Citations for the datasets behind a built-in data source, by source name (e.g. "tiger",
+    /// "elections"). Unknown sources have none.
+    pub fn for_source(source: &str) -> Vec<Self> {
+        let cite = |dataset, license, citation| Self::new(source, dataset, license, citation);
+        match source {
+            "tiger" => vec![cite("2020 TIGER/Line Shapefiles", CENSUS_LICENSE,
+                "U.S. Census Bureau. 2020 TIGER/Line Shapefiles. Washington, DC: U.S. Department of Commerce, 2020. \
+                https://www.census.gov/geographies/mapping-files/time-series/geo/tiger-line-file.html")],
+            "baf" => vec![cite("2020 Census Block Assignment Files", CENSUS_LICENSE,
+                "U.S. Census Bureau. 2020 Census Block Assignment Files. Washington, DC: U.S. Department of Commerce, 2021. \
+                https://www.census.gov/geographies/reference-files/time-series/geo/block-assignment-files.html")],
+            "demographics" => vec![
+                cite("2020 Census Redistricting Data (P.L. 94-171)", CENSUS_LICENSE,
+                    "U.S. Census Bureau. 2020 Census Redistricting Data (Public Law 94-171) Summary File. \
+                    Washington, DC: U.S. Department of Commerce, 2021."),
+                cite("American Community Survey 5-Year Estimates", CENSUS_LICENSE,
+                    "U.S. Census Bureau. American Community Survey 5-Year Estimates. Washington, DC: U.S. Department of Commerce."),
+                cite("DRA block-level demographic data", DRA_LICENSE,
+                    "Dave's Redistricting. Block-level demographic data, version 06. https://davesredistricting.org"),
+            ],
+            "elections" => vec![
+                cite("VEST 2020 Precinct-Level Election Results", "See the dataset's terms at https://doi.org/10.7910/DVN/K7760H",
+                    "Voting and Election Science Team. 2020 Precinct-Level Election Results. Harvard Dataverse. \
+                    https://doi.org/10.7910/DVN/K7760H"),
+                cite("DRA block-level election data", DRA_LICENSE,
+                    "Dave's Redistricting. Block-level election data (disaggregated precinct results), version 06. \
+                    https://davesredistricting.org"),
+            ],
+            "historical_districts" => vec![cite("Digital Boundary Definitions of United States Congressional Districts",
+                "See https://cdmaps.polisci.ucla.edu",
+                "Jeffrey B. Lewis, Brandon DeVine, Lincoln Pitcher, and Kenneth C. Martis. (2013) Digital Boundary Definitions \
+                of United States Congressional Districts, 1789-2012. Retrieved from https://cdmaps.polisci.ucla.edu")],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Format `citations` as a numbered list with their licenses, for the pack `pack_id`.
+pub(crate) fn format_citations(pack_id: &str, citations: &[Citation]) -> String {
+    let mut text = format!("Data in {pack_id} comes from the following datasets. Please cite them in publications using it:\n");
+    for (i, citation) in citations.iter().enumerate() {
+        text.push_str(&format!("\n[{}] {}\n    Dataset: {} (via {})\n    License: {}\n",
+            i + 1, citation.citation, citation.dataset, citation.source, citation.license));
+    }
+    text
+}
@@ -1,9 +1,12 @@
 use std::{fs::File, io::{Seek, Write}, path::{Path, PathBuf}};
 
 use anyhow::{Context, Result, ensure};
+use sha2::{Digest, Sha256};
 use tempfile::NamedTempFile;
 
-use crate::map::util;
+use crate::map::StateInfo;
+
+use super::{DataSource, SourceRecord};
 
 /// Write-then-rename wrapper for atomic big-file outputs
 struct PendingWrite {
@@ -70,116 +73,86 @@ pub(crate) fn download_big_file(file_url: String, out_path: &Path, force: bool)
     Ok(())
 }
 
-/// Delete the `download/` directory (and all its contents) under `pack_dir`.
-pub(crate) fn cleanup_download_dir(pack_dir: &Path, verbose: u8) -> Result<()> {
-    let download_dir = pack_dir.join("download");
-
-    if !download_dir.exists() {
-        if verbose > 0 { eprintln!("[cleanup] nothing to remove at {}", download_dir.display()) }
-        return Ok(());
-    }
-
-    if verbose > 0 { eprintln!("[cleanup] removing {}", download_dir.display()) }
-    std::fs::remove_dir_all(&download_dir)
-        .with_context(|| format!("failed to remove {}", download_dir.display()))
-}
-
-/// Download demographic data from Dave's redistricting
-fn download_daves_demographics(out_dir: &Path, state: &str, verbose: u8) -> Result<()> {
-    let file_url = format!("https://data.dra2020.net/file/dra-block-data/Demographic_Data_Block_{state}.v06.zip");
-    let zip_path = out_dir.join(format!("Demographic_Data_Block_{state}.v06.zip"));
-    let out_path = out_dir.join(format!("Demographic_Data_Block_{state}"));
+/// Download a source file from `url` to `out_path`, recording its hash and ETag in a
+/// `<file>.source.json` file next to it (see [`read_snapshot`]), so builds can pin it.
+pub(crate) fn download_snapshot(source: &str, url: &str, out_path: &Path) -> Result<SourceRecord> {
+    let mut sink = PendingWrite::open(out_path, true)?;
 
-    if verbose > 0 { eprintln!("[download] downloading {file_url}"); }
-    download_big_file(file_url, &zip_path, true)?;
+    let mut resp = reqwest::blocking::get(url)
+        .with_context(|| format!("GET {url}"))?
+        .error_for_status()
+        .with_context(|| format!("GET {url} returned error status"))?;
+    let etag = resp.headers().get(reqwest::header::ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
 
-    if verbose > 0 { eprintln!("[download] extracting {}", zip_path.display()); }
-    util::extract_zip(&zip_path, &out_path, true)?;
+    std::io::copy(&mut resp, &mut sink).with_context(|| format!("write {}", out_path.display()))?;
+    sink.finalize()?;
 
-    Ok(())
+    let record = SourceRecord { source: source.into(), url: url.into(), sha256: Some(sha256_file(out_path)?), etag };
+    write_snapshot(out_path, &record)?;
+    Ok(record)
 }
 
-/// Download election data from Dave's redistricting
-fn download_daves_elections(out_dir: &Path, state: &str, verbose: u8) -> Result<()> {
-    let file_url = format!("https://data.dra2020.net/file/dra-block-data/Election_Data_Block_{state}.v06.zip");
-    let zip_path = out_dir.join(format!("Election_Data_Block_{state}.v06.zip"));
-    let out_path = out_dir.join(format!("Election_Data_Block_{state}"));
-
-    if verbose > 0 { eprintln!("[download] downloading {file_url}"); }
-    download_big_file(file_url, &zip_path, true)?;
-
-    if verbose > 0 { eprintln!("[download] extracting {}", zip_path.display()); }
-    util::extract_zip(&zip_path, &out_path, true)?;
-
-    Ok(())
+/// Record the hash of a source file downloaded by an earlier build, if it has no record yet.
+pub(crate) fn ensure_snapshot(source: &str, url: &str, path: &Path) -> Result<()> {
+    if read_snapshot(path).is_some() { return Ok(()) }
+    write_snapshot(path, &SourceRecord { source: source.into(), url: url.into(), sha256: Some(sha256_file(path)?), etag: None })
 }
 
-/// Download geometry data from US Census TIGER 2020 PL directory
-/// Example URL: "NE" -> "https://www2.census.gov/geo/tiger/TIGER2020PL/STATE/31_NEBRASKA/31/"
-fn download_tiger_geometries(out_dir: &Path, state: &str, has_vtd: bool, verbose: u8) -> Result<()> {
-    let fips = util::state_abbr_to_fips(state)
-        .with_context(|| format!("Unknown state/territory postal code: {state}"))?;
-    let name = util::state_abbr_to_name(state)
-        .with_context(|| format!("Unknown state/territory postal code: {state}"))?
-        .to_ascii_uppercase().replace(' ', "_");
-
-    let base = format!("https://www2.census.gov/geo/tiger/TIGER2020PL/STATE/{fips}_{name}/{fips}/");
+/// Read the record written next to a downloaded source file, if any. The record outlives the
+/// file itself when the file is an archive deleted after extraction.
+pub(crate) fn read_snapshot(path: &Path) -> Option<SourceRecord> {
+    let bytes = std::fs::read(snapshot_path(path)).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
 
-    // Filenames we need for TIGER 2020 (state/county/tract/bg/vtd/block)
-    let files = ["state20", "county20", "tract20", "bg20", "vtd20", "tabblock20"];
+fn write_snapshot(path: &Path, record: &SourceRecord) -> Result<()> {
+    let snapshot = snapshot_path(path);
+    let mut sink = PendingWrite::open(&snapshot, true)?;
+    sink.write_all(&serde_json::to_vec_pretty(record)?)
+        .with_context(|| format!("write {}", snapshot.display()))?;
+    sink.finalize()
+}
 
-    for name in files {
-        // Skip if the vtd data isn't available (CA, ME, OR, WY)
-        if !has_vtd && name == "vtd20" { continue }
+fn snapshot_path(path: &Path) -> PathBuf {
+    let name = path.file_name().map_or_else(Default::default, |name| name.to_string_lossy().into_owned());
+    path.with_file_name(format!("{name}.source.json"))
+}
 
-        let file_url = format!("{base}tl_2020_{fips}_{name}.zip");
-        let zip_path = out_dir.join(format!("tl_2020_{fips}_{name}.zip"));
-        let out_path = out_dir.join(format!("tl_2020_{fips}_{name}"));
+/// SHA-256 hash of a file, as lowercase hex.
+fn sha256_file(path: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    let mut file = File::open(path).with_context(|| format!("open {}", path.display()))?;
+    std::io::copy(&mut file, &mut hasher).with_context(|| format!("read {}", path.display()))?;
+    Ok(hex::encode(hasher.finalize()))
+}
 
-        if verbose > 0 { eprintln!("[download] downloading {file_url}"); }
-        download_big_file(file_url, &zip_path, true)?;
+/// Delete the `download/` directory (and all its contents) under `pack_dir`.
+pub(crate) fn cleanup_download_dir(pack_dir: &Path, verbose: u8) -> Result<()> {
+    let download_dir = pack_dir.join("download");
 
-        if verbose > 0 { eprintln!("[download] extracting {}", zip_path.display()); }
-        util::extract_zip(&zip_path, &out_path, true)?;
+    if !download_dir.exists() {
+        if verbose > 0 { eprintln!("[cleanup] nothing to remove at {}", download_dir.display()) }
+        return Ok(());
     }
 
-    Ok(())
+    if verbose > 0 { eprintln!("[cleanup] removing {}", download_dir.display()) }
+    std::fs::remove_dir_all(&download_dir)
+        .with_context(|| format!("failed to remove {}", download_dir.display()))
 }
 
-/// Download block-level crosswalks from the US Census website
-/// Example URL: "NE" -> "https://www2.census.gov/geo/docs/maps-data/data/baf2020/BlockAssign_ST31_NE.zip"
-fn download_census_crosswalks(out_dir: &Path, state: &str, verbose: u8) -> Result<()> {
-    let fips = util::state_abbr_to_fips(state)
-        .with_context(|| format!("Unknown state/territory postal code: {state}"))?;
-
-    let file_url = format!("https://www2.census.gov/geo/docs/maps-data/data/baf2020/BlockAssign_ST{fips}_{state}.zip");
+/// Download all map files for the given state into `download_dir`, creating it if needed.
+pub(crate) fn download_data(state: &StateInfo, download_dir: &Path, sources: &[&dyn DataSource], verbose: u8) -> Result<()> {
+    std::fs::create_dir_all(download_dir)
+        .with_context(|| format!("create dir {}", download_dir.display()))?;
 
-    let zip_path = out_dir.join(format!("BlockAssign_ST{fips}_{state}.zip"));
-    let out_path = out_dir.join(format!("BlockAssign_ST{fips}_{state}"));
+    if verbose > 0 { eprintln!("[download] state={} -> dir {}", state.code, download_dir.display()); }
 
-    if verbose > 0 { eprintln!("[download] downloading {file_url}"); }
-    download_big_file(file_url, &zip_path, true)?;
-
-    if verbose > 0 { eprintln!("[download] extracting {}", zip_path.display()); }
-    util::extract_zip(&zip_path, &out_path, true)?;
+    for source in sources {
+        source.fetch(state, download_dir, verbose)
+            .with_context(|| format!("Failed to fetch data source '{}'", source.name()))?;
+    }
 
     Ok(())
 }
-
-/// Download all map files for the given state into the `download/` directory under `pack_dir`.
-/// Returns the path to the `download/` directory.
-pub(crate) fn download_data(state: &str, pack_dir: &Path, has_vtd: bool, verbose: u8) -> Result<PathBuf> {
-    util::require_dir_exists(pack_dir)?;
-
-    let download_dir = pack_dir.join("download");
-    util::ensure_dir_exists(&download_dir)?;
-
-    if verbose > 0 { eprintln!("[download] state={state} -> dir {}", download_dir.display()); }
-
-    download_tiger_geometries(&download_dir, state, has_vtd, verbose)?;
-    download_daves_demographics(&download_dir, state, verbose)?;
-    download_daves_elections(&download_dir, state, verbose)?;
-    download_census_crosswalks(&download_dir, state, verbose)?;
-
-    Ok(download_dir)
-}
@@ -0,0 +1,89 @@
+use std::collections::{BTreeMap, HashSet};
+
+use anyhow::{Context, Result, ensure};
+use polars::frame::DataFrame;
+use serde::{Deserialize, Serialize};
+
+/// Which source columns to keep in a pack and what to call them, e.g. `P0010001 -> total_pop`.
+///
+/// Applies to the block-level columns produced by data sources; geometry-derived columns
+/// (names, centroids, areas, perimeters) and the `GEOID` key are never affected.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FieldMapping {
+    pub rename: BTreeMap<String, String>, // Source column -> pack column
+    pub keep: Vec<String>,                // Source columns to keep under their own names
+    pub drop_unmapped: bool,              // Drop source columns not listed in `rename` or `keep`
+}
+
+impl FieldMapping {
+    /// Keep every column under its original name.
+    #[inline] pub fn is_identity(&self) -> bool { self.rename.is_empty() && !self.drop_unmapped }
+
+    /// Parse a mapping from JSON, e.g. `{"rename": {"P0010001": "total_pop"}, "drop_unmapped": true}`.
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).context("[FieldMapping] Failed to parse field mapping JSON")
+    }
+
+    /// Source columns named in this mapping.
+    fn mapped(&self) -> impl Iterator<Item = &String> { self.rename.keys().chain(&self.keep) }
+
+    /// Select and rename the columns of one source's block data. Returns the source columns it used.
+    pub(crate) fn apply(&self, df: DataFrame, id_col: &str) -> Result<(DataFrame, HashSet<String>)> {
+        let present = df.get_column_names().into_iter()
+            .map(|name| name.to_string())
+            .collect::<HashSet<_>>();
+        let used = self.mapped().filter(|&name| present.contains(name)).cloned().collect::<HashSet<_>>();
+        if self.is_identity() { return Ok((df, used)) }
+
+        let mut df = if self.drop_unmapped {
+            df.select(present.iter().filter(|&name| name == id_col || used.contains(name)))?
+        } else { df };
+
+        for (from, to) in self.rename.iter().filter(|&(from, _)| present.contains(from)) {
+            ensure!(!df.get_column_names().iter().any(|name| name.as_str() == to),
+                "[FieldMapping] Cannot rename {} to {}: column already exists", from, to);
+            df.rename(from, to.as_str().into())?;
+        }
+        Ok((df, used))
+    }
+
+    /// Error if any column named in this mapping was not produced by a data source.
+    pub(crate) fn check_used(&self, used: &HashSet<String>) -> Result<()> {
+        let missing = self.mapped().filter(|&name| !used.contains(name)).cloned().collect::<Vec<_>>();
+        ensure!(missing.is_empty(), "[FieldMapping] Unknown source columns: {}", missing.join(", "));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::prelude::*;
+
+    fn frame() -> DataFrame {
+        df!("GEOID" => ["a", "b"], "P0010001" => [1.0, 2.0], "P0010002" => [3.0, 4.0]).unwrap()
+    }
+
+    #[test]
+    fn renames_and_drops() {
+        let mapping = FieldMapping {
+            rename: [("P0010001".to_string(), "total_pop".to_string())].into(),
+            drop_unmapped: true,
+            ..Default::default()
+        };
+        let (df, used) = mapping.apply(frame(), "GEOID").unwrap();
+        let mut names = df.get_column_names().iter().map(|name| name.to_string()).collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, ["GEOID", "total_pop"]);
+        assert!(mapping.check_used(&used).is_ok());
+    }
+
+    #[test]
+    fn reports_unknown_columns() {
+        let mapping = FieldMapping { keep: vec!["X".to_string()], ..Default::default() };
+        let (df, used) = mapping.apply(frame(), "GEOID").unwrap();
+        assert_eq!(df.width(), 3);
+        assert!(mapping.check_used(&used).is_err());
+    }
+}
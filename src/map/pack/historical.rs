@@ -0,0 +1,85 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow, ensure};
+use geo::Coord;
+use geograph::{Region, UnitId};
+use polars::prelude::*;
+use shapefile::dbase::FieldValue;
+
+use crate::map::{MapLayer, StateInfo};
+
+use super::{Artifact, DataSource};
+
+/// Enacted congressional districts for one Congress from the UCLA/Lewis historical districts
+/// dataset (1st through 114th Congress), stored as a block-level assignment column `cd{congress}`.
+///
+/// Each block is assigned to the district containing its internal point (0 if none); at-large
+/// seats are numbered 1. Load a stored plan with [`Plan::from_assignment_column`](crate::Plan::from_assignment_column).
+#[derive(Clone, Copy, Debug)]
+pub struct HistoricalPlanSource {
+    pub congress: u32,
+}
+
+impl HistoricalPlanSource {
+    /// Last Congress covered by the dataset.
+    pub const LAST_CONGRESS: u32 = 114;
+
+    pub fn new(congress: u32) -> Result<Self> {
+        ensure!((1..=Self::LAST_CONGRESS).contains(&congress),
+            "[HistoricalPlanSource] Congress {} not in dataset (1-{})", congress, Self::LAST_CONGRESS);
+        Ok(Self { congress })
+    }
+
+    /// Name of the assignment column this source produces, e.g. "cd114".
+    pub fn column(&self) -> String { format!("cd{}", self.congress) }
+
+    fn stem(&self) -> String { format!("districts{:03}", self.congress) }
+}
+
+impl DataSource for HistoricalPlanSource {
+    fn name(&self) -> &str { "historical_districts" }
+
+    /// Example: 114 -> "https://cdmaps.polisci.ucla.edu/shp/districts114.zip"
+    fn artifacts(&self, _: &StateInfo) -> Vec<Artifact> {
+        vec![Artifact::zip(format!("https://cdmaps.polisci.ucla.edu/shp/{}.zip", self.stem()))]
+    }
+
+    fn block_columns(&self, state: &StateInfo, dir: &Path, blocks: &MapLayer) -> Result<Option<DataFrame>> {
+        let stem = self.stem();
+        let path = dir.join(&stem).join("districtShapes").join(format!("{stem}.shp"));
+        let (shapes, records) = crate::io::shp::read_shapefile(&path)?;
+
+        let text = |value: Option<&FieldValue>| match value {
+            Some(FieldValue::Character(Some(s))) => Some(s.trim().to_string()),
+            Some(FieldValue::Numeric(Some(n))) => Some(n.to_string()),
+            _ => None,
+        };
+
+        let mut districts = Vec::new();
+        let mut polygons = Vec::new();
+        for (shape, record) in shapes.into_iter().zip(&records) {
+            if !text(record.get("STATENAME")).is_some_and(|name| name.eq_ignore_ascii_case(state.name)) { continue }
+            let district = text(record.get("DISTRICT"))
+                .and_then(|d| d.parse::<f64>().ok())
+                .with_context(|| format!("[HistoricalPlanSource] Invalid DISTRICT field in {}", path.display()))?;
+            districts.push((district as u32).max(1)); // At-large seats are coded 0
+            polygons.push(crate::io::shp::shape_to_multipolygon(shape)?);
+        }
+        ensure!(!districts.is_empty(), "[HistoricalPlanSource] No districts for {} in Congress {}", state.name, self.congress);
+
+        let region = Region::new(polygons, None)
+            .map_err(|e| anyhow!("Region construction failed for {}: {:?}", path.display(), e))?;
+
+        let lon = blocks.data().column("centroid_lon")?.f64()?;
+        let lat = blocks.data().column("centroid_lat")?.f64()?;
+        let assignments = lon.into_iter().zip(lat)
+            .map(|(x, y)| region.unit_at(Coord { x: x?, y: y? }).map(|UnitId(i)| districts[i as usize]))
+            .map(|district| district.unwrap_or(0))
+            .collect::<Vec<_>>();
+
+        Ok(Some(DataFrame::new(vec![
+            Column::new("GEOID".into(), blocks.geo_ids().iter().map(|geo_id| geo_id.id().to_string()).collect::<Vec<_>>()),
+            Column::new(self.column().into(), assignments),
+        ])?))
+    }
+}
@@ -0,0 +1,60 @@
+use std::{fs::{File, OpenOptions, TryLockError}, path::{Path, PathBuf}};
+
+use anyhow::{Context, Result};
+
+/// An advisory lock on a pack or download directory, held until dropped, so that processes
+/// building, downloading or writing the same pack take turns. The lock is taken on a `<dir>.lock`
+/// file next to the directory, which is left in place.
+#[derive(Debug)]
+pub(crate) struct PathLock {
+    _file: File,
+}
+
+impl PathLock {
+    /// Lock `path`, waiting for any other process holding it.
+    pub(crate) fn acquire(path: &Path, verbose: u8) -> Result<Self> {
+        let lock_path = Self::lock_path(path);
+        if let Some(parent) = lock_path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("[PathLock] Failed to create dir {}", parent.display()))?;
+        }
+        let file = OpenOptions::new().create(true).truncate(false).write(true).open(&lock_path)
+            .with_context(|| format!("[PathLock] Failed to open {}", lock_path.display()))?;
+
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                if verbose > 0 { eprintln!("[lock] waiting for another process using {}", path.display()) }
+                file.lock().with_context(|| format!("[PathLock] Failed to lock {}", lock_path.display()))?;
+            }
+            Err(TryLockError::Error(e)) => {
+                return Err(e).with_context(|| format!("[PathLock] Failed to lock {}", lock_path.display()))
+            }
+        }
+        Ok(Self { _file: file })
+    }
+
+    /// Lock file guarding `path`: `<path>.lock` next to it.
+    fn lock_path(path: &Path) -> PathBuf {
+        let name = path.file_name().map_or_else(Default::default, |name| name.to_string_lossy().into_owned());
+        path.with_file_name(format!("{name}.lock"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lock_excludes_other_holders_until_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let pack = dir.path().join("IL_2020_pack");
+
+        let held = PathLock::acquire(&pack, 0).unwrap();
+        let other = File::options().write(true).open(dir.path().join("IL_2020_pack.lock")).unwrap();
+        assert!(matches!(other.try_lock(), Err(TryLockError::WouldBlock)));
+
+        drop(held);
+        assert!(other.try_lock().is_ok());
+    }
+}
@@ -1,20 +1,22 @@
-use std::{collections::BTreeMap, path::Path};
+use std::{collections::{BTreeMap, BTreeSet}, fmt, path::Path};
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail, ensure};
+use polars::frame::DataFrame;
 use serde::{Deserialize, Serialize};
 
-use crate::map::GeoType;
-use super::{PackFormat, PackSource};
+use crate::map::{ColumnStats, GeoType, Map};
+use super::{Citation, PackFormat, PackSource, citation::format_citations, is_container_path, open_pack_store};
 
-#[derive(Serialize, Deserialize, Clone)]
-pub(crate) struct FileHash {
+/// SHA-256 hash of a pack file, as lowercase hex.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileHash {
     pub sha256: String,
 }
 
 /// Format specification for pack data files.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
-pub(crate) struct PackFormats {
+pub struct PackFormats {
     /// Format for data files (e.g., "csv", "parquet")
     pub data: String,
 }
@@ -36,49 +38,295 @@ impl PackFormats {
     }
 }
 
-#[derive(Serialize, Deserialize)]
-pub(crate) struct Manifest {
-    pack_id: String,
-    version: String,
-    crs: String,
+/// Name and type of one column of a layer's data file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnSchema {
+    pub name: String,
+    pub dtype: String, // Polars data type, e.g. "i64", "f64", "str"
+}
+
+/// Description of one layer of a pack.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayerManifest {
+    pub description: String,
+    pub count: usize,               // Number of units
+    pub data_file: String,          // Path of the layer's data table within the pack
+    pub columns: Vec<ColumnSchema>, // Columns of the data table, in file order
+}
+
+impl LayerManifest {
+    pub(crate) fn new(ty: GeoType, data_file: String, data: &DataFrame) -> Self {
+        Self {
+            description: ty.description().into(),
+            count: data.height(),
+            data_file,
+            columns: data.schema().iter()
+                .map(|(name, dtype)| ColumnSchema { name: name.to_string(), dtype: dtype.to_string() })
+                .collect(),
+        }
+    }
+}
+
+/// A remote file a pack was built from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceRecord {
+    pub source: String, // Name of the data source, e.g. "tiger"
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>, // Hash of the downloaded file, as lowercase hex
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>, // ETag the server sent with the file, if any
+}
+
+/// How a pack was built: the files it was built from and the build options.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BuildRecord {
+    pub sources: Vec<SourceRecord>,
+    pub parameters: BTreeMap<String, serde_json::Value>, // Build option name -> value
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub citations: Vec<Citation>, // Licenses and citations of the datasets the sources used
+}
+
+impl BuildRecord {
+    /// Read the build record of the pack at `path` (a pack directory or its `manifest.json`),
+    /// e.g. to pin a rebuild to it. Fails if the pack does not record how it was built.
+    pub fn read_pin(path: &Path) -> Result<Self> {
+        let pack_dir = if path.is_file() { path.parent().unwrap_or(Path::new(".")) } else { path };
+        Manifest::read(pack_dir)?.build
+            .with_context(|| format!("[BuildRecord] Pack {} does not record how it was built", pack_dir.display()))
+    }
+
+    /// Check that a build with `parameters` from `sources` reproduces this pinned build: the same
+    /// build options, and byte-identical source files (by SHA-256) from the same URLs.
+    pub fn verify_pin(&self, sources: &[SourceRecord], parameters: &BTreeMap<String, serde_json::Value>) -> Result<()> {
+        let differing = self.parameters.keys().chain(parameters.keys())
+            .filter(|&name| self.parameters.get(name) != parameters.get(name))
+            .collect::<BTreeSet<_>>();
+        ensure!(differing.is_empty(), "[BuildRecord] Build options differ from the pinned build: {}",
+            differing.into_iter().map(String::as_str).collect::<Vec<_>>().join(", "));
+
+        for source in sources {
+            let pinned = self.sources.iter().find(|pinned| pinned.url == source.url)
+                .with_context(|| format!("[BuildRecord] Source {} is not in the pinned build", source.url))?;
+            let pinned_hash = pinned.sha256.as_deref()
+                .with_context(|| format!("[BuildRecord] The pinned build records no hash for {}", source.url))?;
+            ensure!(source.sha256.as_deref() == Some(pinned_hash),
+                "[BuildRecord] {} changed since the pinned build (sha256 {}, pinned {pinned_hash})",
+                source.url, source.sha256.as_deref().unwrap_or("unknown"));
+        }
+        if let Some(unused) = self.sources.iter().find(|pinned| sources.iter().all(|source| source.url != pinned.url)) {
+            bail!("[BuildRecord] Pinned source {} is not used by this build", unused.url);
+        }
+        Ok(())
+    }
+}
+
+/// Contents of a pack's `manifest.json`.
+///
+/// The manifest is versioned by `version`. Fields added after version 2 default to empty when
+/// reading older manifests, so every version can still be read:
+/// - 2: `formats` and `provenance`, then `stats`.
+/// - 3: `generator`, `layers` (descriptions and column schemas), and `build`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub pack_id: String, // Name of the pack directory
+    pub version: String, // Manifest format version (see `Manifest::VERSION`)
     #[serde(default)]
-    formats: PackFormats,
-    levels: Vec<String>,
-    counts: BTreeMap<String, usize>,
-    files: BTreeMap<String, FileHash>,
+    pub generator: String, // Library version that wrote the pack, e.g. "openmander 0.1.0"
+    pub crs: String, // Coordinate reference system of the geometries
+    #[serde(default)]
+    pub formats: PackFormats,
+    pub levels: Vec<String>, // Layer names, from the top layer down
+    pub counts: BTreeMap<String, usize>, // Layer name -> number of units
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub layers: BTreeMap<String, LayerManifest>, // Layer name -> description and column schema
+    pub files: BTreeMap<String, FileHash>, // Path within the pack -> hash
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub provenance: BTreeMap<String, String>, // Layer name -> note on where its data came from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build: Option<BuildRecord>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub stats: BTreeMap<String, BTreeMap<String, ColumnStats>>, // Layer name -> column name -> stats
 }
 
 impl Manifest {
+    /// Manifest format version written by this library.
+    pub const VERSION: &'static str = "3";
+
     pub(crate) fn new(
         path: &Path,
-        counts: BTreeMap<&'static str, usize>,
+        map: &Map,
+        layers: BTreeMap<String, LayerManifest>,
         files: BTreeMap<String, FileHash>,
         formats: PackFormats,
     ) -> Self {
         Self {
-            pack_id: path.file_name()
+            pack_id: if is_container_path(path) { path.file_stem() } else { path.file_name() }
                 .and_then(|s| s.to_str())
                 .unwrap_or("unknown-pack")
                 .to_string(),
-            version: "2".into(),
+            version: Self::VERSION.into(),
+            generator: format!("openmander {}", env!("CARGO_PKG_VERSION")),
             crs: "EPSG:4269".into(),
             levels: GeoType::ALL.iter().map(|ty| ty.to_str().into()).collect(),
-            counts: counts.into_iter().map(|(k, v)| (k.into(), v)).collect(),
+            counts: layers.iter().map(|(name, layer)| (name.clone(), layer.count)).collect(),
+            layers,
             files,
             formats,
+            provenance: map.provenance().clone(),
+            build: map.build_record().cloned(),
+            stats: map.column_stats(),
         }
     }
 
-    pub(crate) fn formats(&self) -> &PackFormats {
-        &self.formats
+    /// Read the manifest of the pack at `path`.
+    pub fn read(path: &Path) -> Result<Self> {
+        Self::from_pack_source(&*open_pack_store(path)?)
+            .with_context(|| format!("[Manifest] Failed to read manifest of pack {}", path.display()))
     }
 
     /// Read manifest from a PackSource
-    pub(crate) fn from_pack_source(src: &dyn PackSource) -> Result<Self> {
+    pub fn from_pack_source(src: &dyn PackSource) -> Result<Self> {
         let manifest_bytes = src.get("manifest.json")
             .context("Failed to read manifest.json")?;
         let manifest: Manifest = serde_json::from_slice(&manifest_bytes)
             .context("Failed to parse manifest.json")?;
         Ok(manifest)
     }
+
+    /// Serialize the manifest as pretty-printed JSON, as stored in `manifest.json`.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Licenses and citations of the datasets the pack was built from. Packs built before these
+    /// were recorded fall back to the citations of the built-in sources they name.
+    pub fn citations(&self) -> Vec<Citation> {
+        let Some(build) = &self.build else { return Vec::new() };
+        if !build.citations.is_empty() { return build.citations.clone() }
+        let mut citations = Vec::<Citation>::new();
+        for source in build.sources.iter().map(|source| source.source.as_str()).collect::<BTreeSet<_>>() {
+            citations.extend(Citation::for_source(source));
+        }
+        citations
+    }
+
+    /// The citations of the pack's datasets (see [`Manifest::citations`]) as a numbered list
+    /// with their licenses, to reproduce in publications.
+    pub fn cite(&self) -> Result<String> {
+        let citations = self.citations();
+        ensure!(!citations.is_empty(), "[Manifest.cite] Pack {} does not record the datasets it was built from", self.pack_id);
+        Ok(format_citations(&self.pack_id, &citations))
+    }
+}
+
+impl fmt::Display for Manifest {
+    /// Human-readable summary of the manifest: layers with their columns, sources, and build options.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Pack {} (manifest version {})", self.pack_id, self.version)?;
+        if !self.generator.is_empty() { writeln!(f, "Written by {}", self.generator)? }
+        writeln!(f, "CRS {}, {} data files", self.crs, self.formats.data)?;
+
+        writeln!(f, "Layers:")?;
+        for name in self.levels.iter().filter(|name| self.counts.contains_key(*name)) {
+            let description = self.layers.get(name).map_or("", |layer| layer.description.as_str());
+            writeln!(f, "  {name:<7} {:>9} units  {description}", self.counts[name])?;
+            if let Some(note) = self.provenance.get(name) { writeln!(f, "          source: {note}")? }
+            if let Some(layer) = self.layers.get(name) {
+                let columns = layer.columns.iter().map(|column| format!("{} ({})", column.name, column.dtype)).collect::<Vec<_>>();
+                writeln!(f, "          columns: {}", columns.join(", "))?;
+            }
+        }
+
+        if let Some(build) = &self.build {
+            writeln!(f, "Sources:")?;
+            for source in &build.sources {
+                let hash = source.sha256.as_deref().map_or(String::new(), |hash| format!(" (sha256 {hash})"));
+                writeln!(f, "  {}: {}{hash}", source.source, source.url)?;
+            }
+            writeln!(f, "Build parameters:")?;
+            for (name, value) in &build.parameters { writeln!(f, "  {name}: {value}")? }
+            if !build.citations.is_empty() {
+                writeln!(f, "Licenses:")?;
+                for citation in &build.citations { writeln!(f, "  {}: {}", citation.dataset, citation.license)? }
+            }
+        }
+
+        write!(f, "Files: {} (SHA-256 hashes recorded)", self.files.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_maps;
+    use crate::map::MemPack;
+
+    #[test]
+    fn written_manifest_documents_layers() {
+        let mut map = test_maps::row_with(2, &[], vec![("pop".into(), vec![3.0, 4.0])]);
+        map.set_build_record(BuildRecord {
+            sources: vec![SourceRecord { source: "tiger".into(), url: "https://example.com/blocks.zip".into(), sha256: None, etag: None }],
+            parameters: BTreeMap::from([("vintage".into(), serde_json::json!(2020))]),
+            citations: Citation::for_source("tiger"),
+        });
+
+        let mut pack = MemPack::new(Default::default());
+        map.write_to_pack_sink(&mut pack, Path::new("XX_2020_pack")).unwrap();
+        let manifest = Manifest::from_pack_source(&pack).unwrap();
+        assert_eq!(manifest.version, Manifest::VERSION);
+        assert_eq!(manifest.counts["block"], 2);
+        let block = &manifest.layers["block"];
+        assert_eq!(block.description, "Census blocks");
+        assert!(block.columns.iter().any(|column| column.name == "pop"));
+        assert_eq!(manifest.build.as_ref(), map.build_record());
+        assert_eq!(serde_json::from_str::<Manifest>(&manifest.to_json().unwrap()).unwrap(), manifest);
+
+        let summary = manifest.to_string();
+        assert!(summary.starts_with("Pack XX_2020_pack (manifest version 3)"));
+        assert!(summary.contains("tiger: https://example.com/blocks.zip"));
+        assert!(summary.contains("2020 TIGER/Line Shapefiles: Public domain"));
+        assert!(manifest.cite().unwrap().contains("[1] U.S. Census Bureau. 2020 TIGER/Line Shapefiles."));
+    }
+
+    #[test]
+    fn pins_require_identical_sources_and_options() {
+        let source = |url: &str, sha256: &str| SourceRecord { source: "tiger".into(), url: url.into(), sha256: Some(sha256.into()), etag: None };
+        let parameters = BTreeMap::from([("vintage".to_string(), serde_json::json!(2020))]);
+        let pin = BuildRecord { sources: vec![source("https://example.com/a.zip", "aa")], parameters: parameters.clone(), citations: Vec::new() };
+
+        assert!(pin.verify_pin(&[source("https://example.com/a.zip", "aa")], &parameters).is_ok());
+        assert!(pin.verify_pin(&[source("https://example.com/a.zip", "ab")], &parameters).is_err());
+        assert!(pin.verify_pin(&[source("https://example.com/b.zip", "aa")], &parameters).is_err());
+        assert!(pin.verify_pin(&[], &parameters).is_err());
+
+        let other = BTreeMap::from([("vintage".to_string(), serde_json::json!(2010))]);
+        let error = pin.verify_pin(&[source("https://example.com/a.zip", "aa")], &other).unwrap_err();
+        assert!(error.to_string().ends_with("vintage"));
+    }
+
+    #[test]
+    fn version_2_manifests_still_parse() {
+        let manifest = serde_json::from_str::<Manifest>(r#"{
+            "pack_id": "XX_2020_pack", "version": "2", "crs": "EPSG:4269", "levels": ["state", "block"],
+            "counts": {"block": 2, "state": 1}, "files": {"data/block.csv": {"sha256": "00"}}
+        }"#).unwrap();
+        assert!(manifest.layers.is_empty() && manifest.build.is_none());
+        assert!(manifest.to_string().contains("block"));
+        assert!(manifest.cite().is_err());
+    }
+
+    #[test]
+    fn citations_fall_back_to_source_names() {
+        let source = |name: &str| SourceRecord { source: name.into(), url: format!("https://example.com/{name}.zip"), sha256: None, etag: None };
+        let build = BuildRecord { sources: vec![source("tiger"), source("elections"), source("tiger"), source("local")], ..Default::default() };
+        let manifest = serde_json::from_value::<Manifest>(serde_json::json!({
+            "pack_id": "XX_2020_pack", "version": "3", "crs": "EPSG:4269", "levels": ["state", "block"],
+            "counts": {"block": 2, "state": 1}, "files": {}, "build": build,
+        })).unwrap();
+
+        let datasets = manifest.citations().into_iter().map(|citation| citation.dataset).collect::<Vec<_>>();
+        assert_eq!(datasets, ["VEST 2020 Precinct-Level Election Results", "DRA block-level election data", "2020 TIGER/Line Shapefiles"]);
+        assert!(manifest.cite().unwrap().contains("[3] U.S. Census Bureau. 2020 TIGER/Line Shapefiles."));
+    }
 }
@@ -1,14 +1,53 @@
+mod citation;
 #[cfg(feature = "download")]
 mod download;
+#[cfg(feature = "download")]
+mod fields;
 mod format;
+mod lock;
+#[cfg(feature = "download")]
+mod historical;
 mod manifest;
+#[cfg(feature = "download")]
+mod options;
 mod pack;
+mod patch;
 mod source;
+mod store;
+mod verify;
+#[cfg(feature = "download")]
+mod qa;
+#[cfg(feature = "download")]
+mod sources;
 
+pub use citation::Citation;
 pub use format::PackFormat;
-pub(crate) use manifest::{FileHash, Manifest, PackFormats};
-pub use pack::validate_pack;
+pub use manifest::{BuildRecord, ColumnSchema, FileHash, LayerManifest, Manifest, PackFormats, SourceRecord};
+pub use pack::{cite_pack, validate_pack};
+pub use patch::{PackPatch, apply_pack_patch, diff_packs};
+#[cfg(feature = "download")]
+pub(crate) use download::download_big_file;
 pub use source::{PackSource, PackSink, DiskPack, MemPack};
+pub use store::{BundlePack, PackStore, ZipPack, convert_pack, create_pack_store, open_pack_store};
+pub(crate) use lock::PathLock;
+pub(crate) use store::is_container_path;
+#[cfg(any(feature = "server", feature = "grpc"))]
+pub(crate) use store::resolve_pack_path;
+pub use verify::{CorruptSection, VerifiedPack};
+pub(crate) use verify::{sha256_hex, surface_corruption};
 
 #[cfg(feature = "download")]
-pub use pack::{build_pack, download_pack};
+pub use fields::FieldMapping;
+#[cfg(feature = "download")]
+pub use historical::HistoricalPlanSource;
+#[cfg(feature = "download")]
+pub use options::{BuildOptions, PrecinctShapefile};
+#[cfg(feature = "download")]
+pub use pack::{build_pack, build_pack_with_sources, download_pack, download_pack_without_geoms};
+#[cfg(feature = "download")]
+pub use qa::{QaIssue, QaReason, QaReport};
+#[cfg(feature = "download")]
+pub use sources::{
+    Artifact, BlockAssignSource, BlockCsvSource, DataSource, DemographicSource, ElectionSource, TigerSource,
+    default_sources, read_block_csv,
+};
@@ -0,0 +1,117 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use serde_json::json;
+
+use crate::map::GeoType;
+
+use super::{BuildRecord, FieldMapping};
+
+/// A state-provided precinct shapefile to use as the `vtd` layer in place of TIGER VTDs.
+#[derive(Clone, Debug)]
+pub struct PrecinctShapefile {
+    pub path: PathBuf,
+    pub id_field: String,     // Attribute holding each precinct's identifier
+    pub note: Option<String>, // Provenance note for the manifest (defaults to the file name)
+}
+
+impl PrecinctShapefile {
+    pub fn new(path: impl Into<PathBuf>, id_field: impl Into<String>) -> Self {
+        Self { path: path.into(), id_field: id_field.into(), note: None }
+    }
+
+    /// Provenance note recorded for the `vtd` layer.
+    pub(crate) fn provenance(&self) -> String {
+        self.note.clone().unwrap_or_else(|| format!("State-provided precincts from {}",
+            self.path.file_name().map_or_else(|| self.path.display().to_string(), |name| name.to_string_lossy().into_owned())))
+    }
+}
+
+/// Options for building a pack (see [`build_pack_with_sources`](super::build_pack_with_sources)).
+#[derive(Clone, Debug)]
+pub struct BuildOptions {
+    pub vintage: u16,                         // Census vintage of the geometries and counts (only 2020 is available)
+    pub has_vtd: bool,                        // Whether TIGER has VTDs for the state (not CA, ME, OR, WY)
+    pub layers: Vec<GeoType>,                 // Layers to build; state, county, and block are always built
+    pub precincts: Option<PrecinctShapefile>, // Precincts to use as the `vtd` layer instead of TIGER VTDs
+    pub fields: FieldMapping,
+    pub hilbert_order: bool,                  // Renumber units along a Hilbert curve for cache locality
+    pub cache_dir: Option<PathBuf>,           // Keep downloads here and reuse them, instead of a temporary directory
+    pub pin: Option<BuildRecord>,             // Fail unless sources and options match this earlier build (see `BuildRecord::read_pin`)
+    pub verbose: u8,
+}
+
+impl Default for BuildOptions {
+    fn default() -> Self {
+        Self {
+            vintage: Self::VINTAGE,
+            has_vtd: true,
+            layers: GeoType::ALL.to_vec(),
+            precincts: None,
+            fields: FieldMapping::default(),
+            hilbert_order: false,
+            cache_dir: None,
+            pin: None,
+            verbose: 0,
+        }
+    }
+}
+
+impl BuildOptions {
+    /// The only Census vintage the default sources provide.
+    pub const VINTAGE: u16 = 2020;
+
+    /// Layers built whatever `layers` says: the map needs its top, its counties, and its blocks.
+    pub const REQUIRED_LAYERS: [GeoType; 3] = [GeoType::State, GeoType::County, GeoType::Block];
+
+    /// Whether the pack will have a layer of type `ty`.
+    #[inline] pub fn includes(&self, ty: GeoType) -> bool {
+        Self::REQUIRED_LAYERS.contains(&ty) || self.layers.contains(&ty)
+    }
+
+    /// Whether TIGER VTDs and the block -> VTD assignment file are needed.
+    #[inline] pub(crate) fn uses_tiger_vtd(&self) -> bool {
+        self.has_vtd && self.precincts.is_none() && self.includes(GeoType::VTD)
+    }
+
+    /// TIGER layers to download and load.
+    pub(crate) fn tiger_layers(&self) -> Vec<GeoType> {
+        GeoType::ALL.into_iter()
+            .filter(|&ty| self.includes(ty) && (ty != GeoType::VTD || self.uses_tiger_vtd()))
+            .collect()
+    }
+
+    /// Options that shape the pack of state `state_code`, as recorded in its manifest. The cache
+    /// directory, pin, and verbosity are left out.
+    pub(crate) fn parameters_for(&self, state_code: &str) -> BTreeMap<String, serde_json::Value> {
+        let layers = GeoType::ALL.into_iter().filter(|&ty| self.includes(ty)).map(|ty| ty.to_str()).collect::<Vec<_>>();
+        let mut parameters = BTreeMap::from([
+            ("state".to_string(), json!(state_code)),
+            ("vintage".to_string(), json!(self.vintage)),
+            ("has_vtd".to_string(), json!(self.has_vtd)),
+            ("layers".to_string(), json!(layers)),
+            ("hilbert_order".to_string(), json!(self.hilbert_order)),
+        ]);
+        if !self.fields.is_identity() {
+            parameters.insert("fields".into(), serde_json::to_value(&self.fields).unwrap_or_default());
+        }
+        if let Some(precincts) = &self.precincts {
+            parameters.insert("precincts".into(), json!({ "path": precincts.path.display().to_string(), "id_field": precincts.id_field }));
+        }
+        parameters
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiger_layers_keep_required_layers() {
+        let options = BuildOptions { layers: vec![GeoType::Tract], ..Default::default() };
+        assert_eq!(options.tiger_layers(), [GeoType::State, GeoType::County, GeoType::Tract, GeoType::Block]);
+        assert!(!options.uses_tiger_vtd());
+
+        let options = BuildOptions { has_vtd: false, ..Default::default() };
+        assert!(!options.tiger_layers().contains(&GeoType::VTD));
+    }
+}
@@ -1,16 +1,23 @@
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
-use anyhow::Result;
+use anyhow::{Context, Result, ensure};
+
+use super::{Manifest, VerifiedPack, open_pack_store};
+
+#[cfg(feature = "download")]
+use super::{PackFormat, PathLock};
 
 #[cfg(feature = "download")]
-use std::time::Duration;
+use std::{path::PathBuf, time::Duration};
 #[cfg(feature = "download")]
-use anyhow::{Context, anyhow};
+use anyhow::anyhow;
 #[cfg(feature = "download")]
-use crate::map::{Map, util};
+use crate::map::{GeoType, Map, StateInfo, util};
 
 #[cfg(feature = "download")]
-use super::download::{cleanup_download_dir, download_data, download_big_file};
+use super::download::{cleanup_download_dir, download_data, download_big_file, read_snapshot};
+#[cfg(feature = "download")]
+use super::{BuildOptions, BuildRecord, QaReport, SourceRecord, sources::{BlockAssignSource, DataSource, TigerSource, default_sources}};
 
 /// Lightweight existence check for a remote file.
 /// Returns Ok(true) if it exists, Ok(false) if it's 404/410, Err(_) otherwise.
@@ -51,31 +58,93 @@ pub(crate) fn remote_file_exists(url: &str) -> Result<bool> {
 /// Returns the path to the new pack directory.
 #[cfg(feature = "download")]
 pub fn build_pack(state_code: &str, path: &Path, has_vtd: bool, verbose: u8) -> Result<PathBuf> {
+    let sources = default_sources();
+    let sources = sources.iter().map(|source| source.as_ref()).collect::<Vec<_>>();
+    build_pack_with_sources(state_code, path, &sources, &BuildOptions { has_vtd, verbose, ..BuildOptions::default() })
+}
+
+/// Like [`build_pack`], but with block-level columns taken from `sources` instead of the defaults,
+/// and with further control through `options` (layers, column mapping, replacement precincts, download cache).
+/// TIGER geometries and Census block assignments are always downloaded, unless already in the cache.
+#[cfg(feature = "download")]
+pub fn build_pack_with_sources(state_code: &str, path: &Path, sources: &[&dyn DataSource], options: &BuildOptions) -> Result<PathBuf> {
+    let verbose = options.verbose;
     let state_code = state_code.to_ascii_uppercase();
+    let state = StateInfo::from_code(&state_code)
+        .with_context(|| format!("Unknown state/territory postal code: {state_code}"))?;
+    anyhow::ensure!(options.vintage == BuildOptions::VINTAGE,
+        "Census vintage {} is not available (only {})", options.vintage, BuildOptions::VINTAGE);
+    if let Some(precincts) = &options.precincts {
+        anyhow::ensure!(options.includes(GeoType::VTD), "Precincts replace the vtd layer, which is not in the requested layers");
+        anyhow::ensure!(precincts.path.is_file(), "Precinct shapefile not found: {}", precincts.path.display());
+    }
     util::require_dir_exists(path)?;
 
+    // Hold the pack for the whole build, so concurrent builds of the same state take turns.
     let pack_dir = path.join(format!("{state_code}_2020_pack"));
+    let _pack_lock = PathLock::acquire(&pack_dir, verbose)?;
     util::ensure_dir_exists(&pack_dir)?;
 
-    let download_dir = download_data(&state_code, &pack_dir, has_vtd, verbose)?;
-    if verbose > 0 { eprintln!("Downloaded files for {} into {}", state_code, pack_dir.display()); }
+    let tiger = TigerSource { layers: options.tiger_layers() };
+    let mut all_sources: Vec<&dyn DataSource> = vec![&tiger];
+    if options.uses_tiger_vtd() { all_sources.push(&BlockAssignSource) }
+    all_sources.extend_from_slice(sources);
+
+    // Downloads go to a scratch directory inside the pack, or to the cache directory if one is given.
+    let download_dir = match &options.cache_dir {
+        Some(cache_dir) => cache_dir.join(&state_code),
+        None => pack_dir.join("download"),
+    };
+    let _cache_lock = options.cache_dir.as_ref().map(|_| PathLock::acquire(&download_dir, verbose)).transpose()?;
+    download_data(state, &download_dir, &all_sources, verbose)?;
+    if verbose > 0 { eprintln!("Downloaded files for {} into {}", state_code, download_dir.display()); }
+
+    // Record exactly which files the pack is built from, and check them against the pin.
+    let record = BuildRecord {
+        sources: all_sources.iter()
+            .flat_map(|source| source.artifacts(state).into_iter().map(|artifact| {
+                read_snapshot(&download_dir.join(&artifact.file_name)).unwrap_or(SourceRecord {
+                    source: source.name().into(), url: artifact.url, sha256: None, etag: None,
+                })
+            }))
+            .collect(),
+        parameters: options.parameters_for(&state_code),
+        citations: all_sources.iter()
+            .flat_map(|source| source.citations())
+            .fold(Vec::new(), |mut citations, citation| {
+                if !citations.contains(&citation) { citations.push(citation) }
+                citations
+            }),
+    };
+    if let Some(pin) = &options.pin {
+        pin.verify_pin(&record.sources, &record.parameters)?;
+        if verbose > 0 { eprintln!("Sources match the pinned build"); }
+    }
 
-    let fips = util::state_abbr_to_fips(&state_code)
-        .with_context(|| format!("Unknown state/territory postal code: {state_code}"))?;
+    // Write the QA report next to the pack even if the build fails, so offending geometries can be inspected.
+    let mut qa = QaReport::default();
+    let built = Map::build_pack(&download_dir, state, sources, options, &mut qa);
+    let qa_path = path.join(format!("{state_code}_2020_qa.geojson"));
+    if !qa.is_empty() {
+        qa.write(&qa_path)?;
+        if verbose > 0 { eprintln!("Flagged {} geometries, see {}", qa.len(), qa_path.display()); }
+    } else if qa_path.exists() {
+        std::fs::remove_file(&qa_path)?;
+    }
 
-    let map = Map::build_pack(&download_dir, &state_code, fips, has_vtd, verbose)?;
+    let mut map = built?;
+    map.set_build_record(record);
     if verbose > 0 { eprintln!("Built pack for {state_code}"); }
-    map.write_to_pack( &pack_dir)?;
+    map.write_to_pack_unlocked(&pack_dir, PackFormat::default())?;
     if verbose > 0 { eprintln!("Wrote pack to {}", pack_dir.display()); }
 
-    cleanup_download_dir(&pack_dir, verbose)?;
+    if options.cache_dir.is_none() { cleanup_download_dir(&pack_dir, verbose)? }
 
     Ok(pack_dir)
 }
 
 /// Download the full map pack for a given state into `path`.
 /// Falls back to building the pack locally if no prebuilt pack is available.
-/// `include_geoms` controls whether geometries are included in the download.
 /// Returns the path to the downloaded pack directory.
 #[cfg(feature = "download")]
 pub fn download_pack(state_code: &str, path: &Path, verbose: u8) -> Result<PathBuf> {
@@ -90,19 +159,66 @@ pub fn download_pack(state_code: &str, path: &Path, verbose: u8) -> Result<PathB
     }
 
     let zip_path = path.join(format!("{pack_name}.zip"));
-    let pack_dir = path.join(pack_name);
+    let pack_dir = path.join(&pack_name);
+    let _lock = PathLock::acquire(&pack_dir, verbose)?;
 
     if verbose > 0 { eprintln!("[download] downloading {pack_url}"); }
     download_big_file(pack_url, &zip_path, true)?;
 
+    // Extract next to the pack, then swap it into place, so readers never see a partial pack.
     if verbose > 0 { eprintln!("[download] extracting {}", zip_path.display()); }
-    util::extract_zip(&zip_path, path, true)?;
+    let staging = tempfile::TempDir::new_in(path)
+        .with_context(|| format!("[download_pack] Failed to create a staging dir in {}", path.display()))?;
+    util::extract_zip(&zip_path, staging.path(), true)?;
+    let extracted = staging.path().join(&pack_name);
+    ensure!(extracted.is_dir(), "[download_pack] The downloaded archive does not contain {pack_name}/");
+    if pack_dir.exists() {
+        std::fs::rename(&pack_dir, staging.path().join("previous"))
+            .with_context(|| format!("[download_pack] Failed to replace {}", pack_dir.display()))?;
+    }
+    std::fs::rename(&extracted, &pack_dir)
+        .with_context(|| format!("[download_pack] Failed to move the pack into {}", pack_dir.display()))?;
 
     if verbose > 0 { eprintln!("Downloaded pack to {}", pack_dir.display()); }
 
     Ok(pack_dir)
 }
 
-/// Validate the contents of a map pack at `pack_path`.
-#[allow(dead_code, unused_variables)]
-pub fn validate_pack(pack_path: &Path, verbose: u8) -> Result<()> { todo!()}
+/// Download the map pack for a given state into `path` (see [`download_pack`]), then rewrite it
+/// without unit geometry. The graph-only pack is much smaller and still supports loading, scoring,
+/// optimizing, and exporting assignments; drawing and geometry exports return an error.
+/// Returns the path to the pack directory.
+#[cfg(feature = "download")]
+pub fn download_pack_without_geoms(state_code: &str, path: &Path, verbose: u8) -> Result<PathBuf> {
+    let pack_dir = download_pack(state_code, path, verbose)?;
+
+    let mut map = Map::read_from_pack(&pack_dir)?;
+    map.drop_geometry();
+    map.write_to_pack(&pack_dir)?;
+    if verbose > 0 { eprintln!("Removed geometry from pack at {}", pack_dir.display()); }
+
+    Ok(pack_dir)
+}
+
+/// Validate the contents of a map pack at `pack_path`: check every section against the hash
+/// recorded in its manifest, then load the map to check that each section parses. Reading a pack
+/// already checks the sections it reads; this also covers sections a read would skip.
+pub fn validate_pack(pack_path: &Path, verbose: u8) -> Result<()> {
+    let store = open_pack_store(pack_path)?;
+    ensure!(store.has("manifest.json"), "[validate_pack] {} has no manifest.json to validate against", pack_path.display());
+    let manifest = Manifest::from_pack_source(&*store)
+        .with_context(|| format!("[validate_pack] Failed to read manifest of {}", pack_path.display()))?;
+
+    let checked = VerifiedPack::new(&*store, pack_path.display().to_string(), manifest.files).verify_all()?;
+    if verbose > 0 { eprintln!("[validate_pack] {checked} sections match their recorded hashes"); }
+
+    let map = crate::Map::read_from_pack(pack_path)?;
+    if verbose > 0 { eprintln!("[validate_pack] {} loaded with {} layers", pack_path.display(), map.layers_iter().count()); }
+    Ok(())
+}
+
+/// Print-ready citations of the datasets the pack at `pack_path` was built from, with their
+/// licenses (see [`Manifest::cite`]), for correct attribution in publications.
+pub fn cite_pack(pack_path: &Path) -> Result<String> {
+    Manifest::read(pack_path)?.cite()
+}
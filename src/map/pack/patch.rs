@@ -0,0 +1,169 @@
+use std::{collections::BTreeMap, io::{Cursor, Read, Write}, path::Path, sync::Arc};
+
+use anyhow::{Context, Result, bail, ensure};
+use serde::{Deserialize, Serialize};
+
+use super::{DiskPack, FileHash, Manifest, PackSink, PackSource};
+
+/// Header of a patch file, stored as `patch.json` in the archive.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct PatchHeader {
+    version: u32,
+    base: BTreeMap<String, FileHash>,   // Files of the pack the patch applies to, from its manifest
+    target: BTreeMap<String, FileHash>, // Files of the pack after applying it
+    removed: Vec<String>,               // Files to delete
+}
+
+/// The changes between two versions of a pack, e.g. new columns or updated election data, so a
+/// pack can be updated without downloading its unchanged files (usually the geometry) again.
+///
+/// Files are compared by the SHA-256 hashes in the packs' manifests. A patch holds every file
+/// that was added or changed, plus the new `manifest.json`, and is written as a zip archive.
+#[derive(Debug, Clone)]
+pub struct PackPatch {
+    header: PatchHeader,
+    files: BTreeMap<String, Arc<[u8]>>, // Pack-relative path -> new contents
+}
+
+impl PackPatch {
+    /// Patch file format version written by this library.
+    pub const VERSION: u32 = 1;
+
+    /// Compute the patch turning pack `old` into pack `new`.
+    pub fn diff(old: &dyn PackSource, new: &dyn PackSource) -> Result<Self> {
+        let (base, target) = (Manifest::from_pack_source(old)?.files, Manifest::from_pack_source(new)?.files);
+
+        let mut files = BTreeMap::new();
+        for (path, hash) in &target {
+            if base.get(path) != Some(hash) {
+                files.insert(path.clone(), new.get(path).with_context(|| format!("[PackPatch] Failed to read {path}"))?);
+            }
+        }
+        files.insert("manifest.json".into(), new.get("manifest.json")?);
+        let removed = base.keys().filter(|path| !target.contains_key(*path)).cloned().collect();
+
+        Ok(Self { header: PatchHeader { version: Self::VERSION, base, target, removed }, files })
+    }
+
+    /// Pack-relative paths of the files the patch adds or replaces, including `manifest.json`.
+    pub fn changed_files(&self) -> Vec<&str> { self.files.keys().map(String::as_str).collect() }
+
+    /// Pack-relative paths of the files the patch deletes.
+    pub fn removed_files(&self) -> &[String] { &self.header.removed }
+
+    /// Total size of the files the patch carries, in bytes.
+    pub fn size(&self) -> usize { self.files.values().map(|bytes| bytes.len()).sum() }
+
+    /// Apply the patch to the pack directory at `path`. Returns the number of files written or
+    /// deleted, or 0 if the pack is already up to date.
+    ///
+    /// Fails, leaving the pack untouched, unless the pack's manifest matches the pack the patch
+    /// was computed from. The new manifest is written last, so an interrupted update leaves the
+    /// old manifest in place and the patch can simply be applied again.
+    pub fn apply(&self, path: &Path) -> Result<usize> {
+        let current = Manifest::read(path)?.files;
+        if current == self.header.target { return Ok(0) }
+        ensure!(current == self.header.base, "[PackPatch] Pack {} is not the version this patch applies to", path.display());
+
+        let mut pack = DiskPack::new(path);
+        for (rel, bytes) in self.files.iter().filter(|(rel, _)| rel.as_str() != "manifest.json") {
+            pack.put(rel, bytes)?;
+        }
+        for rel in &self.header.removed {
+            let file = path.join(rel);
+            if file.exists() {
+                std::fs::remove_file(&file).with_context(|| format!("[PackPatch] Failed to remove {}", file.display()))?;
+            }
+        }
+        pack.put("manifest.json", &self.files["manifest.json"])?;
+        Ok(self.files.len() + self.header.removed.len())
+    }
+
+    /// Write the patch to a zip archive at `path`.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        zip.start_file("patch.json", options)?;
+        zip.write_all(&serde_json::to_vec_pretty(&self.header)?)?;
+        for (rel, bytes) in &self.files {
+            zip.start_file(format!("files/{rel}"), options)?;
+            zip.write_all(bytes)?;
+        }
+        let bytes = zip.finish()?.into_inner();
+        std::fs::write(path, bytes).with_context(|| format!("[PackPatch] Failed to write {}", path.display()))
+    }
+
+    /// Read a patch written by [`PackPatch::write`].
+    pub fn read(path: &Path) -> Result<Self> {
+        let file = std::fs::File::open(path).with_context(|| format!("[PackPatch] Failed to open {}", path.display()))?;
+        let mut zip = zip::ZipArchive::new(file).with_context(|| format!("[PackPatch] {} is not a patch archive", path.display()))?;
+
+        let mut read_entry = |name: &str| -> Result<Vec<u8>> {
+            let mut bytes = Vec::new();
+            zip.by_name(name)?.read_to_end(&mut bytes)?;
+            Ok(bytes)
+        };
+        let header = serde_json::from_slice::<PatchHeader>(&read_entry("patch.json")?)
+            .with_context(|| format!("[PackPatch] Invalid patch header in {}", path.display()))?;
+        if header.version > Self::VERSION {
+            bail!("[PackPatch] Patch version {} is newer than this library supports ({})", header.version, Self::VERSION);
+        }
+        if let Some(rel) = header.target.keys().chain(&header.removed).find(|rel| !is_pack_relative(rel)) {
+            bail!("[PackPatch] Invalid file path {rel:?} in {}", path.display());
+        }
+
+        let changed = header.target.iter()
+            .filter(|(rel, hash)| header.base.get(*rel) != Some(*hash))
+            .map(|(rel, _)| rel.as_str())
+            .chain(["manifest.json"])
+            .collect::<Vec<_>>();
+        let files = changed.into_iter()
+            .map(|rel| Ok((rel.to_string(), Arc::from(read_entry(&format!("files/{rel}"))?))))
+            .collect::<Result<_>>()?;
+        Ok(Self { header, files })
+    }
+}
+
+/// Whether `rel` is a path inside a pack, so a patch cannot write or delete files outside it.
+fn is_pack_relative(rel: &str) -> bool {
+    Path::new(rel).components().all(|component| matches!(component, std::path::Component::Normal(_)))
+}
+
+/// Compute the patch from the pack directory `old` to the pack directory `new` and write it to
+/// `patch_path`. Returns the patch.
+pub fn diff_packs(old: &Path, new: &Path, patch_path: &Path) -> Result<PackPatch> {
+    let patch = PackPatch::diff(&DiskPack::new(old), &DiskPack::new(new))?;
+    patch.write(patch_path)?;
+    Ok(patch)
+}
+
+/// Apply the patch file at `patch_path` to the pack directory at `pack_path` (see [`PackPatch::apply`]).
+pub fn apply_pack_patch(pack_path: &Path, patch_path: &Path) -> Result<usize> {
+    PackPatch::read(patch_path)?.apply(pack_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{map::{GeoType, Map}, test_maps};
+
+    #[test]
+    fn patch_carries_only_changed_files() {
+        let mut map = test_maps::row_with(2, &[], vec![("pop".into(), vec![3.0, 4.0])]);
+
+        let dir = tempfile::tempdir().unwrap();
+        let (old, new, patch_path) = (dir.path().join("old"), dir.path().join("new"), dir.path().join("update.ompatch"));
+        map.write_to_pack(&old).unwrap();
+        map.set_unit_column(GeoType::BOTTOM, "E_20_PRES_Dem", vec![1.0, 2.0]).unwrap();
+        map.write_to_pack(&new).unwrap();
+
+        let patch = diff_packs(&old, &new, &patch_path).unwrap();
+        assert!(patch.changed_files().iter().all(|rel| !rel.starts_with("geom/")));
+        assert!(patch.changed_files().contains(&"data/block.parquet") || patch.changed_files().contains(&"data/block.csv"));
+
+        assert!(apply_pack_patch(&old, &patch_path).unwrap() > 0);
+        assert_eq!(Manifest::read(&old).unwrap().files, Manifest::read(&new).unwrap().files);
+        assert!(Map::read_from_pack(&old).unwrap().base().unwrap().get_unit_weights().contains("E_20_PRES_Dem"));
+        assert_eq!(apply_pack_patch(&old, &patch_path).unwrap(), 0);
+    }
+}
@@ -0,0 +1,208 @@
+use std::{fmt, path::Path};
+
+use anyhow::{Context, Result};
+use geo::{Area, BooleanOps, MultiPolygon, Polygon, Validation};
+use serde_json::{Value, json};
+use shapefile::Shape;
+
+use crate::map::{GeoType, io::multipolygon_to_geojson};
+
+/// Why a geometry was flagged while building a pack.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QaReason {
+    /// The shape is not a polygon and could not be parsed; the unit was kept with no geometry.
+    NotPolygon,
+    /// A ring has too few points or no area; the ring was dropped.
+    DegenerateRing,
+    /// A polygon is invalid (e.g. a self-intersecting ring); it was repaired with [`make_valid`].
+    InvalidPolygon,
+    /// The unit shares no boundary with any other unit, so it has no neighbors in the graph.
+    Isolated,
+}
+
+impl QaReason {
+    /// Get the string representation of the reason, as written to the report.
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            QaReason::NotPolygon     => "not_polygon",
+            QaReason::DegenerateRing => "degenerate_ring",
+            QaReason::InvalidPolygon => "invalid_polygon",
+            QaReason::Isolated       => "isolated",
+        }
+    }
+}
+
+/// A geometry flagged during a pack build.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QaIssue {
+    pub layer: GeoType,
+    pub id: String,                  // GEOID of the unit (or precinct identifier)
+    pub reason: QaReason,
+    pub detail: String,
+    pub geometry: MultiPolygon<f64>, // Offending geometry as read from the source
+}
+
+impl fmt::Display for QaIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}: {} ({})", self.layer.to_str(), self.id, self.reason.to_str(), self.detail)
+    }
+}
+
+/// Geometries that could not be used as-is while building a pack, with the reason for each.
+#[derive(Clone, Debug, Default)]
+pub struct QaReport {
+    issues: Vec<QaIssue>,
+}
+
+impl QaReport {
+    /// Get the recorded issues, in the order they were found.
+    #[inline] pub fn issues(&self) -> &[QaIssue] { &self.issues }
+
+    /// Number of recorded issues.
+    #[inline] pub fn len(&self) -> usize { self.issues.len() }
+
+    /// Check if no geometry was flagged.
+    #[inline] pub fn is_empty(&self) -> bool { self.issues.is_empty() }
+
+    /// Record a flagged geometry.
+    pub(crate) fn push(&mut self, layer: GeoType, id: &str, reason: QaReason, detail: impl Into<String>, geometry: MultiPolygon<f64>) {
+        self.issues.push(QaIssue { layer, id: id.to_string(), reason, detail: detail.into(), geometry });
+    }
+
+    /// Convert shapes to multipolygons, dropping degenerate rings and recording every problem.
+    /// Unparseable shapes become empty multipolygons so that each unit keeps its row.
+    pub(crate) fn clean_shapes(&mut self, layer: GeoType, ids: &[String], shapes: Vec<Shape>) -> Vec<MultiPolygon<f64>> {
+        shapes.into_iter().zip(ids)
+            .map(|(shape, id)| match crate::io::shp::shape_to_multipolygon(shape) {
+                Ok(mp) => self.clean_multipolygon(layer, id, mp),
+                Err(e) => {
+                    self.push(layer, id, QaReason::NotPolygon, e.to_string(), MultiPolygon(vec![]));
+                    MultiPolygon(vec![])
+                }
+            })
+            .collect()
+    }
+
+    /// Drop degenerate rings from a multipolygon and repair invalid polygons, recording both.
+    fn clean_multipolygon(&mut self, layer: GeoType, id: &str, mp: MultiPolygon<f64>) -> MultiPolygon<f64> {
+        /// A ring is degenerate if it has fewer than 3 distinct points or encloses no area.
+        fn is_degenerate(ring: &geo::LineString<f64>) -> bool {
+            ring.0.len() < 4 || Polygon::new(ring.clone(), vec![]).unsigned_area() == 0.0
+        }
+
+        let degenerate = mp.0.iter()
+            .map(|poly| std::iter::once(poly.exterior()).chain(poly.interiors()).filter(|&ring| is_degenerate(ring)).count())
+            .sum::<usize>();
+        let cleaned = if degenerate == 0 { mp } else {
+            self.push(layer, id, QaReason::DegenerateRing, format!("dropped {degenerate} degenerate ring(s)"), mp.clone());
+            MultiPolygon(mp.0.into_iter()
+                .filter(|poly| !is_degenerate(poly.exterior()))
+                .map(|poly| {
+                    let (exterior, interiors) = poly.into_inner();
+                    Polygon::new(exterior, interiors.into_iter().filter(|ring| !is_degenerate(ring)).collect())
+                })
+                .collect())
+        };
+
+        let Some(e) = cleaned.0.iter().find_map(|poly| poly.check_validation().err()) else { return cleaned };
+        let repaired = MultiPolygon(cleaned.0.iter()
+            .flat_map(|poly| if poly.is_valid() { vec![poly.clone()] } else { make_valid(poly).0 })
+            .collect());
+        self.push(layer, id, QaReason::InvalidPolygon, format!("{e}; repaired into {} polygon(s)", repaired.0.len()), cleaned);
+        repaired
+    }
+
+    /// Export the report as a GeoJSON FeatureCollection, one feature per issue.
+    pub fn to_geojson(&self) -> Result<Value> {
+        let features = self.issues.iter()
+            .map(|issue| Ok(json!({
+                "type": "Feature",
+                "geometry": multipolygon_to_geojson(&issue.geometry)?,
+                "properties": {
+                    "layer": issue.layer.to_str(),
+                    "id": issue.id,
+                    "reason": issue.reason.to_str(),
+                    "detail": issue.detail,
+                },
+            })))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(json!({ "type": "FeatureCollection", "features": features }))
+    }
+
+    /// Write the report to a GeoJSON file.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string(&self.to_geojson()?)?)
+            .with_context(|| format!("[QaReport] Failed to write {}", path.display()))
+    }
+}
+
+/// Repair an invalid polygon, in the spirit of `buffer(0)`: its rings are re-traced with an
+/// even-odd fill rule, so a bowtie becomes two triangles and crossing or overlapping rings are resolved.
+pub(crate) fn make_valid(poly: &Polygon<f64>) -> MultiPolygon<f64> {
+    poly.union(&MultiPolygon::<f64>::new(vec![]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::polygon;
+
+    #[test]
+    fn drops_degenerate_rings() {
+        let square = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.), (x: 0., y: 0.)];
+        let sliver = polygon![(x: 2., y: 0.), (x: 3., y: 0.), (x: 4., y: 0.), (x: 2., y: 0.)];
+        let mut report = QaReport::default();
+
+        let cleaned = report.clean_multipolygon(GeoType::Block, "a", MultiPolygon(vec![square.clone(), sliver]));
+        assert_eq!(cleaned, MultiPolygon(vec![square.clone()]));
+        assert_eq!(report.issues()[0].reason, QaReason::DegenerateRing);
+
+        report.clean_multipolygon(GeoType::Block, "b", MultiPolygon(vec![square]));
+        assert_eq!(report.len(), 1);
+    }
+
+    #[test]
+    fn repairs_self_intersections() {
+        let bowtie = polygon![(x: 0., y: 0.), (x: 2., y: 2.), (x: 2., y: 0.), (x: 0., y: 1.), (x: 0., y: 0.)];
+        let mut report = QaReport::default();
+        let repaired = report.clean_multipolygon(GeoType::Block, "a", MultiPolygon(vec![bowtie]));
+        assert_eq!(report.issues()[0].reason, QaReason::InvalidPolygon);
+        assert_eq!(repaired.0.len(), 2);
+        assert!(repaired.is_valid());
+
+        let geojson = report.to_geojson().unwrap();
+        assert_eq!(geojson["features"][0]["properties"]["reason"], "invalid_polygon");
+    }
+
+    #[test]
+    fn repairs_keep_the_traced_area() {
+        // A square whose ring doubles back over its own right half: even-odd leaves the left half.
+        let folded = polygon![(x: 0., y: 0.), (x: 2., y: 0.), (x: 2., y: 2.), (x: 0., y: 2.), (x: 0., y: 0.),
+                              (x: 1., y: 0.), (x: 1., y: 2.), (x: 2., y: 2.), (x: 2., y: 0.), (x: 0., y: 0.)];
+        assert!(!folded.is_valid());
+        let repaired = make_valid(&folded);
+        assert!(repaired.is_valid());
+        assert!((repaired.unsigned_area() - 2.).abs() < 1e-9);
+
+        let bowtie = polygon![(x: 0., y: 0.), (x: 2., y: 2.), (x: 2., y: 0.), (x: 0., y: 2.), (x: 0., y: 0.)];
+        assert!((make_valid(&bowtie).unsigned_area() - 2.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn reports_the_original_geometry() {
+        let square = polygon![(x: 3., y: 0.), (x: 4., y: 0.), (x: 4., y: 1.), (x: 3., y: 1.), (x: 3., y: 0.)];
+        let bowtie = polygon![(x: 0., y: 0.), (x: 2., y: 2.), (x: 2., y: 0.), (x: 0., y: 1.), (x: 0., y: 0.)];
+        let original = MultiPolygon(vec![square.clone(), bowtie]);
+        let mut report = QaReport::default();
+
+        let repaired = report.clean_multipolygon(GeoType::Block, "a", original.clone());
+        assert_eq!(repaired.0.len(), 3);
+        assert_eq!(repaired.0[0], square);
+        assert_eq!(report.issues()[0].geometry, original);
+        assert!(report.issues()[0].detail.ends_with("repaired into 3 polygon(s)"));
+
+        assert_eq!(report.clean_multipolygon(GeoType::Block, "b", MultiPolygon(vec![square.clone()])), MultiPolygon(vec![square]));
+        assert_eq!(report.len(), 1);
+    }
+}
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, path::PathBuf, sync::Arc};
+use std::{collections::HashMap, io::Write, path::{Path, PathBuf}, sync::Arc};
 
 use anyhow::{anyhow, Result};
 
@@ -23,6 +23,9 @@ pub struct DiskPack {
 impl DiskPack {
     pub fn new(root: impl Into<PathBuf>) -> Self { Self { root: root.into() } }
 
+    /// Directory holding the pack.
+    pub fn root(&self) -> &Path { &self.root }
+
     fn full(&self, rel: &str) -> PathBuf { self.root.join(rel) }
 }
 
@@ -40,7 +43,10 @@ impl PackSink for DiskPack {
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
-        std::fs::write(path, bytes)?;
+        // Write to a temporary file and rename it into place, so readers never see a partial section.
+        let mut tmp = tempfile::NamedTempFile::new_in(path.parent().unwrap_or(Path::new(".")))?;
+        tmp.write_all(bytes)?;
+        tmp.persist(&path)?;
         Ok(())
     }
 }
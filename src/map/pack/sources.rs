@@ -0,0 +1,270 @@
+//! Data sources for building packs.
+//!
+//! Built in are Census TIGER/Line shapefiles (geometry), Census block assignment files (the
+//! block -> VTD crosswalk), and block-level columns from Dave's Redistricting. Census P.L. 94-171
+//! counts and ACS estimates arrive through DRA's demographic files, and VEST precinct returns
+//! through its election files, already disaggregated to blocks; there are no separate PL, ACS or
+//! VEST sources, since ACS is not published by block and VEST precincts would need disaggregating.
+//! Other datasets plug in by implementing [`DataSource`], or through [`BlockCsvSource`].
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use polars::{frame::DataFrame, prelude::*};
+
+use crate::map::{GeoType, MapLayer, StateInfo, util};
+
+use super::{Citation, download::{download_snapshot, ensure_snapshot}};
+
+/// A remote file a data source needs for one state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Artifact {
+    pub url: String,
+    pub file_name: String,       // Name of the downloaded file within the download directory
+    pub extract: Option<String>, // Directory to extract the file into, if it is a zip archive
+}
+
+impl Artifact {
+    /// A zip archive at `url`, extracted into a directory named after the archive.
+    pub fn zip(url: String) -> Self {
+        let file_name = url.rsplit('/').next().unwrap_or(&url).to_string();
+        let stem = file_name.trim_end_matches(".zip").to_string();
+        Self { extract: Some(stem), file_name, url }
+    }
+
+    /// A zip archive at `url`, extracted into the directory `extract`.
+    pub fn zip_into(url: String, extract: impl Into<String>) -> Self {
+        Self { extract: Some(extract.into()), ..Self::zip(url) }
+    }
+}
+
+/// An external dataset used when building a pack.
+///
+/// Sources list the files they need for a state, download them into the build's download
+/// directory, and parse them into block-level columns keyed by a `GEOID` column.
+/// Implement this trait to add a new source (e.g. a state open-data portal) to `build_pack_with_sources`.
+pub trait DataSource: Send + Sync {
+    /// Short name of the source, for logging.
+    fn name(&self) -> &str;
+
+    /// Remote files this source needs for a state.
+    fn artifacts(&self, state: &StateInfo) -> Vec<Artifact>;
+
+    /// Licenses and citations of the datasets this source uses, recorded in the pack's manifest
+    /// (see [`Manifest::cite`](super::Manifest::cite)). Defaults to those of the built-in source
+    /// of the same name.
+    fn citations(&self) -> Vec<Citation> { Citation::for_source(self.name()) }
+
+    /// Download this source's artifacts into `dir`, extracting archives.
+    /// Files already in `dir` (e.g. a cache directory from an earlier build) are not downloaded again.
+    fn fetch(&self, state: &StateInfo, dir: &Path, verbose: u8) -> Result<()> {
+        for artifact in self.artifacts(state) {
+            let path = dir.join(&artifact.file_name);
+            if path.is_file() {
+                if verbose > 0 { eprintln!("[download] {}: reusing {}", self.name(), path.display()); }
+                ensure_snapshot(self.name(), &artifact.url, &path)?;
+            } else {
+                if verbose > 0 { eprintln!("[download] {}: downloading {}", self.name(), artifact.url); }
+                download_snapshot(self.name(), &artifact.url, &path)?;
+            }
+
+            if let Some(extract) = &artifact.extract {
+                if verbose > 0 { eprintln!("[download] {}: extracting {}", self.name(), path.display()); }
+                util::extract_zip(&path, &dir.join(extract), true)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse the downloaded files in `dir` into block-level columns, with block ids in a `GEOID` column.
+    /// `blocks` is the block layer loaded so far, for sources that need to locate blocks spatially.
+    /// Returns `None` for sources that contribute geometry or crosswalks rather than columns.
+    fn block_columns(&self, state: &StateInfo, dir: &Path, blocks: &MapLayer) -> Result<Option<DataFrame>>;
+}
+
+/// Census TIGER/Line 2020 PL shapefiles for the given layers.
+#[derive(Clone, Debug)]
+pub struct TigerSource {
+    pub layers: Vec<GeoType>, // TIGER has no VTDs for CA, ME, OR, WY
+}
+
+impl TigerSource {
+    /// TIGER file name for a layer, e.g. "tabblock20" for blocks.
+    fn layer_name(ty: GeoType) -> &'static str {
+        match ty {
+            GeoType::State => "state20",
+            GeoType::County => "county20",
+            GeoType::Tract => "tract20",
+            GeoType::Group => "bg20",
+            GeoType::VTD => "vtd20",
+            GeoType::Block => "tabblock20",
+        }
+    }
+
+    /// Path to the extracted shapefile for a layer within the download directory.
+    pub(crate) fn shapefile_path(dir: &Path, state: &StateInfo, ty: GeoType) -> PathBuf {
+        let stem = format!("tl_2020_{}_{}", state.fips, Self::layer_name(ty));
+        dir.join(&stem).join(format!("{stem}.shp"))
+    }
+}
+
+impl DataSource for TigerSource {
+    fn name(&self) -> &str { "tiger" }
+
+    /// Example: "NE" -> "https://www2.census.gov/geo/tiger/TIGER2020PL/STATE/31_NEBRASKA/31/tl_2020_31_state20.zip"
+    fn artifacts(&self, state: &StateInfo) -> Vec<Artifact> {
+        let name = state.name.to_ascii_uppercase().replace(' ', "_");
+        let base = format!("https://www2.census.gov/geo/tiger/TIGER2020PL/STATE/{}_{name}/{}/", state.fips, state.fips);
+        self.layers.iter()
+            .map(|&ty| Artifact::zip(format!("{base}tl_2020_{}_{}.zip", state.fips, Self::layer_name(ty))))
+            .collect()
+    }
+
+    fn block_columns(&self, _: &StateInfo, _: &Path, _: &MapLayer) -> Result<Option<DataFrame>> { Ok(None) }
+}
+
+/// Census 2020 block assignment files (block -> VTD crosswalk).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BlockAssignSource;
+
+impl BlockAssignSource {
+    /// Path to the extracted block -> VTD assignment file within the download directory.
+    pub(crate) fn vtd_path(dir: &Path, state: &StateInfo) -> PathBuf {
+        let stem = format!("BlockAssign_ST{}_{}", state.fips, state.code);
+        dir.join(&stem).join(format!("{stem}_VTD.txt"))
+    }
+}
+
+impl DataSource for BlockAssignSource {
+    fn name(&self) -> &str { "baf" }
+
+    /// Example: "NE" -> "https://www2.census.gov/geo/docs/maps-data/data/baf2020/BlockAssign_ST31_NE.zip"
+    fn artifacts(&self, state: &StateInfo) -> Vec<Artifact> {
+        vec![Artifact::zip(format!(
+            "https://www2.census.gov/geo/docs/maps-data/data/baf2020/BlockAssign_ST{}_{}.zip", state.fips, state.code,
+        ))]
+    }
+
+    fn block_columns(&self, _: &StateInfo, _: &Path, _: &MapLayer) -> Result<Option<DataFrame>> { Ok(None) }
+}
+
+/// Block-level demographic data (PL 94-171 counts and ACS estimates) from Dave's Redistricting.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DemographicSource;
+
+/// A version 06 DRA block data archive, e.g. "Demographic_Data_Block" for "NE" ->
+/// "https://data.dra2020.net/file/dra-block-data/Demographic_Data_Block_NE.v06.zip",
+/// extracted into "Demographic_Data_Block_NE".
+fn dra_artifact(dataset: &str, state: &StateInfo) -> Artifact {
+    let stem = format!("{dataset}_{}", state.code);
+    Artifact::zip_into(format!("https://data.dra2020.net/file/dra-block-data/{stem}.v06.zip"), stem)
+}
+
+impl DataSource for DemographicSource {
+    fn name(&self) -> &str { "demographics" }
+
+    fn artifacts(&self, state: &StateInfo) -> Vec<Artifact> {
+        vec![dra_artifact("Demographic_Data_Block", state)]
+    }
+
+    fn block_columns(&self, state: &StateInfo, dir: &Path, _: &MapLayer) -> Result<Option<DataFrame>> {
+        let code = state.code;
+        read_block_csv(&dir.join(format!("Demographic_Data_Block_{code}/demographic_data_block_{code}.v06.csv"))).map(Some)
+    }
+}
+
+/// Block-level election results (disaggregated VEST precinct returns) from Dave's Redistricting.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ElectionSource;
+
+impl DataSource for ElectionSource {
+    fn name(&self) -> &str { "elections" }
+
+    fn artifacts(&self, state: &StateInfo) -> Vec<Artifact> {
+        vec![dra_artifact("Election_Data_Block", state)]
+    }
+
+    fn block_columns(&self, state: &StateInfo, dir: &Path, _: &MapLayer) -> Result<Option<DataFrame>> {
+        let code = state.code;
+        read_block_csv(&dir.join(format!("Election_Data_Block_{code}/election_data_block_{code}.v06.csv"))).map(Some)
+    }
+}
+
+/// Block-level columns from a local CSV file with a `GEOID` column, e.g. election results
+/// prepared outside the pack builder. Nothing is downloaded.
+#[derive(Clone, Debug)]
+pub struct BlockCsvSource {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+impl BlockCsvSource {
+    pub fn new(name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        Self { name: name.into(), path: path.into() }
+    }
+}
+
+impl DataSource for BlockCsvSource {
+    fn name(&self) -> &str { &self.name }
+
+    fn artifacts(&self, _: &StateInfo) -> Vec<Artifact> { Vec::new() }
+
+    /// A local file is not a known dataset, whatever its name.
+    fn citations(&self) -> Vec<Citation> { Vec::new() }
+
+    fn block_columns(&self, _: &StateInfo, _: &Path, _: &MapLayer) -> Result<Option<DataFrame>> {
+        read_block_csv(&self.path).map(Some)
+    }
+}
+
+/// Column sources used by `build_pack`: demographics and elections.
+pub fn default_sources() -> Vec<Box<dyn DataSource>> {
+    vec![Box::new(DemographicSource), Box::new(ElectionSource)]
+}
+
+/// Read a block-level CSV, converting a numeric `GEOID` column to zero-padded strings.
+pub fn read_block_csv(path: &Path) -> Result<DataFrame> {
+    let mut df = crate::io::csv::read_csv(path)
+        .with_context(|| format!("Failed to read block data {}", path.display()))?;
+    if *df.column("GEOID")?.dtype() != DataType::String {
+        let geoid_str = df.column("GEOID")?.i64()?.into_iter()
+            .map(|opt| opt.map(|v| format!("{:015}", v)))
+            .collect::<StringChunked>();
+        df.replace("GEOID", geoid_str)?;
+    }
+    Ok(df)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn artifacts_extract_where_sources_read() {
+        let state = StateInfo::from_code("NE").unwrap();
+        let tiger = TigerSource { layers: vec![GeoType::County] }.artifacts(state);
+        assert_eq!(tiger[0].url, "https://www2.census.gov/geo/tiger/TIGER2020PL/STATE/31_NEBRASKA/31/tl_2020_31_county20.zip");
+        assert_eq!(tiger[0].extract.as_deref(), Some("tl_2020_31_county20"));
+        assert!(TigerSource::shapefile_path(Path::new("d"), state, GeoType::County).starts_with("d/tl_2020_31_county20"));
+
+        // Generic archives keep their full stem; DRA archives drop the version suffix.
+        assert_eq!(Artifact::zip("https://example.org/data.v2.zip".into()).extract.as_deref(), Some("data.v2"));
+        let demographics = DemographicSource.artifacts(state);
+        assert_eq!(demographics[0].file_name, "Demographic_Data_Block_NE.v06.zip");
+        assert_eq!(demographics[0].extract.as_deref(), Some("Demographic_Data_Block_NE"));
+        assert_eq!(ElectionSource.artifacts(state)[0].extract.as_deref(), Some("Election_Data_Block_NE"));
+    }
+
+    #[test]
+    fn block_csvs_pad_numeric_geoids() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("votes.csv");
+        std::fs::write(&path, "GEOID,dem,rep\n10010001001000,3,4\n").unwrap();
+
+        let source = BlockCsvSource::new("votes", &path);
+        assert!(source.artifacts(StateInfo::from_code("DE").unwrap()).is_empty() && source.citations().is_empty());
+        let df = read_block_csv(&path).unwrap();
+        assert_eq!(df.column("GEOID").unwrap().str().unwrap().get(0), Some("010010001001000"));
+        assert_eq!(df.column("dem").unwrap().i64().unwrap().get(0), Some(3));
+    }
+}
@@ -0,0 +1,382 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufWriter, Cursor, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{Context, Result, anyhow, bail, ensure};
+
+use super::{DiskPack, MemPack, PackSink, PackSource};
+
+/// Storage holding a whole pack: read and write access to its files plus a listing of them, so
+/// packs can be copied between storage kinds (see [`convert_pack`]). Writes may be buffered
+/// until [`PackStore::flush`].
+pub trait PackStore: PackSource + PackSink {
+    /// Pack-relative paths of every file in the store, sorted.
+    fn files(&self) -> Result<Vec<String>>;
+
+    /// Persist buffered writes.
+    fn flush(&mut self) -> Result<()> { Ok(()) }
+}
+
+impl PackStore for DiskPack {
+    fn files(&self) -> Result<Vec<String>> {
+        fn walk(dir: &Path, prefix: &str, files: &mut Vec<String>) -> Result<()> {
+            for entry in std::fs::read_dir(dir).with_context(|| format!("Failed to list {}", dir.display()))? {
+                let entry = entry?;
+                let name = format!("{prefix}{}", entry.file_name().to_string_lossy());
+                if entry.file_type()?.is_dir() {
+                    walk(&entry.path(), &format!("{name}/"), files)?;
+                } else {
+                    files.push(name);
+                }
+            }
+            Ok(())
+        }
+
+        let mut files = Vec::new();
+        walk(self.root(), "", &mut files)?;
+        files.sort();
+        Ok(files)
+    }
+}
+
+impl PackStore for MemPack {
+    fn files(&self) -> Result<Vec<String>> {
+        let mut files = self.files.keys().cloned().collect::<Vec<_>>();
+        files.sort();
+        Ok(files)
+    }
+}
+
+/// A pack stored as a zip archive, as packs are distributed for download. Files are read from
+/// the archive on demand; writes are buffered and the archive is rewritten on flush.
+pub struct ZipPack {
+    path: PathBuf,
+    archive: Option<Mutex<zip::ZipArchive<File>>>, // None until the archive is first written
+    prefix: String,                                 // Directory in the archive holding the pack, if any
+    pending: BTreeMap<String, Arc<[u8]>>,           // Writes not yet flushed
+}
+
+impl ZipPack {
+    /// Open the zip archive at `path`. Archives holding the pack in a single top-level directory,
+    /// as downloaded packs do, are read from that directory.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let file = File::open(&path).with_context(|| format!("[ZipPack] Failed to open {}", path.display()))?;
+        let archive = zip::ZipArchive::new(file).with_context(|| format!("[ZipPack] {} is not a zip archive", path.display()))?;
+        let prefix = archive.file_names()
+            .filter_map(|name| name.strip_suffix("manifest.json"))
+            .filter(|prefix| prefix.is_empty() || prefix.ends_with('/'))
+            .min_by_key(|prefix| prefix.len())
+            .unwrap_or_default()
+            .to_string();
+        Ok(Self { path, archive: Some(Mutex::new(archive)), prefix, pending: BTreeMap::new() })
+    }
+
+    /// Start a new, empty zip archive at `path`, replacing any existing file when flushed.
+    pub fn create(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), archive: None, prefix: String::new(), pending: BTreeMap::new() }
+    }
+}
+
+impl PackSource for ZipPack {
+    fn get(&self, rel: &str) -> Result<Arc<[u8]>> {
+        if let Some(bytes) = self.pending.get(rel) { return Ok(bytes.clone()) }
+        let archive = self.archive.as_ref().ok_or_else(|| anyhow!("missing pack file: {rel}"))?;
+        let mut archive = archive.lock().expect("zip archive lock poisoned");
+        let mut entry = archive.by_name(&format!("{}{rel}", self.prefix))
+            .map_err(|_| anyhow!("missing pack file: {rel}"))?;
+        let mut bytes = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut bytes)?;
+        Ok(Arc::from(bytes))
+    }
+
+    fn has(&self, rel: &str) -> bool {
+        self.pending.contains_key(rel) || self.archive.as_ref().is_some_and(|archive| {
+            archive.lock().expect("zip archive lock poisoned").index_for_name(&format!("{}{rel}", self.prefix)).is_some()
+        })
+    }
+}
+
+impl PackSink for ZipPack {
+    fn put(&mut self, rel: &str, bytes: &[u8]) -> Result<()> {
+        self.pending.insert(rel.to_string(), Arc::from(bytes));
+        Ok(())
+    }
+}
+
+impl PackStore for ZipPack {
+    fn files(&self) -> Result<Vec<String>> {
+        let mut files = self.pending.keys().cloned().collect::<Vec<_>>();
+        if let Some(archive) = &self.archive {
+            let archive = archive.lock().expect("zip archive lock poisoned");
+            files.extend(archive.file_names()
+                .filter(|name| !name.ends_with('/'))
+                .filter_map(|name| name.strip_prefix(self.prefix.as_str()))
+                .map(str::to_string));
+        }
+        files.sort();
+        files.dedup();
+        Ok(files)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if self.pending.is_empty() && self.archive.is_some() { return Ok(()) }
+
+        let mut tmp = tempfile::NamedTempFile::new_in(parent_dir(&self.path))?;
+        {
+            let mut zip = zip::ZipWriter::new(BufWriter::new(tmp.as_file_mut()));
+            for rel in self.files()? {
+                let method = if is_compressed(&rel) { zip::CompressionMethod::Stored } else { zip::CompressionMethod::Deflated };
+                zip.start_file(rel.as_str(), zip::write::SimpleFileOptions::default().compression_method(method))?;
+                zip.write_all(&self.get(&rel)?)?;
+            }
+            zip.finish()?.flush()?;
+        }
+        tmp.persist(&self.path).with_context(|| format!("[ZipPack] Failed to write {}", self.path.display()))?;
+
+        *self = Self::open(&self.path)?;
+        Ok(())
+    }
+}
+
+/// A whole pack in a single `.openmander` file, e.g. to email a state's pack or attach it to a
+/// court filing. Files are stored uncompressed (pack files are compressed already) behind a JSON
+/// index, so each is read with one seek. Writes are buffered and the file is rewritten on flush.
+///
+/// Layout: the magic bytes `OMPACK\0\0`, the format version (u32, little-endian), the index length
+/// (u64, little-endian), the index (JSON object of `path: [offset, length]`, offsets relative to
+/// the end of the index), then the file contents.
+pub struct BundlePack {
+    path: PathBuf,
+    file: Option<Mutex<File>>,             // None until the bundle is first written
+    index: BTreeMap<String, (u64, u64)>,   // Pack-relative path -> (absolute offset, length)
+    pending: BTreeMap<String, Arc<[u8]>>,  // Writes not yet flushed
+}
+
+impl BundlePack {
+    /// Bundle format version written by this library.
+    pub const VERSION: u32 = 1;
+
+    /// File extension of bundles.
+    pub const EXTENSION: &'static str = "openmander";
+
+    const MAGIC: &'static [u8; 8] = b"OMPACK\0\0";
+
+    /// Open the bundle at `path`.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let mut file = File::open(&path).with_context(|| format!("[BundlePack] Failed to open {}", path.display()))?;
+        let index = read_bundle_index(&mut file).with_context(|| format!("[BundlePack] Invalid bundle {}", path.display()))?;
+        Ok(Self { path, file: Some(Mutex::new(file)), index, pending: BTreeMap::new() })
+    }
+
+    /// Start a new, empty bundle at `path`, replacing any existing file when flushed.
+    pub fn create(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), file: None, index: BTreeMap::new(), pending: BTreeMap::new() }
+    }
+
+    /// Read a whole bundle held in memory, e.g. a file uploaded to a browser.
+    pub fn unpack(bytes: &[u8]) -> Result<MemPack> {
+        let index = read_bundle_index(&mut Cursor::new(bytes)).context("[BundlePack.unpack] Invalid bundle")?;
+        let files = index.into_iter().map(|(rel, (offset, len))| {
+            let range = usize::try_from(offset)?..usize::try_from(offset + len)?;
+            let bytes = bytes.get(range).ok_or_else(|| anyhow!("[BundlePack.unpack] Bundle is truncated at {rel}"))?;
+            Ok((rel, Arc::from(bytes)))
+        }).collect::<Result<_>>()?;
+        Ok(MemPack::new(files))
+    }
+
+    /// Write `files` as a bundle.
+    fn write_bundle(writer: &mut impl Write, files: &BTreeMap<String, Arc<[u8]>>) -> Result<()> {
+        let mut offset = 0;
+        let index = files.iter().map(|(rel, bytes)| {
+            let entry = (rel.as_str(), [offset, bytes.len() as u64]);
+            offset += bytes.len() as u64;
+            entry
+        }).collect::<BTreeMap<_, _>>();
+        let index = serde_json::to_vec(&index)?;
+
+        writer.write_all(Self::MAGIC)?;
+        writer.write_all(&Self::VERSION.to_le_bytes())?;
+        writer.write_all(&(index.len() as u64).to_le_bytes())?;
+        writer.write_all(&index)?;
+        for bytes in files.values() {
+            writer.write_all(bytes)?;
+        }
+        Ok(())
+    }
+}
+
+/// Read the index of a bundle, with absolute offsets.
+fn read_bundle_index(reader: &mut impl Read) -> Result<BTreeMap<String, (u64, u64)>> {
+    let mut header = [0u8; 20];
+    reader.read_exact(&mut header).context("File is too short")?;
+    ensure!(&header[..8] == BundlePack::MAGIC, "Not an openmander bundle");
+    let version = u32::from_le_bytes(header[8..12].try_into()?);
+    if version > BundlePack::VERSION {
+        bail!("Bundle version {version} is newer than this library supports ({})", BundlePack::VERSION);
+    }
+
+    let len = u64::from_le_bytes(header[12..20].try_into()?);
+    let mut index = vec![0u8; usize::try_from(len)?];
+    reader.read_exact(&mut index).context("Index is truncated")?;
+    let index = serde_json::from_slice::<BTreeMap<String, [u64; 2]>>(&index).context("Index is not valid JSON")?;
+
+    let data_start = header.len() as u64 + len;
+    Ok(index.into_iter().map(|(rel, [offset, len])| (rel, (data_start + offset, len))).collect())
+}
+
+impl PackSource for BundlePack {
+    fn get(&self, rel: &str) -> Result<Arc<[u8]>> {
+        if let Some(bytes) = self.pending.get(rel) { return Ok(bytes.clone()) }
+        let &(offset, len) = self.index.get(rel).ok_or_else(|| anyhow!("missing pack file: {rel}"))?;
+        let file = self.file.as_ref().ok_or_else(|| anyhow!("missing pack file: {rel}"))?;
+        let mut file = file.lock().expect("bundle file lock poisoned");
+        let mut bytes = vec![0u8; usize::try_from(len)?];
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut bytes).with_context(|| format!("[BundlePack] Bundle {} is truncated at {rel}", self.path.display()))?;
+        Ok(Arc::from(bytes))
+    }
+
+    fn has(&self, rel: &str) -> bool { self.pending.contains_key(rel) || self.index.contains_key(rel) }
+}
+
+impl PackSink for BundlePack {
+    fn put(&mut self, rel: &str, bytes: &[u8]) -> Result<()> {
+        self.pending.insert(rel.to_string(), Arc::from(bytes));
+        Ok(())
+    }
+}
+
+impl PackStore for BundlePack {
+    fn files(&self) -> Result<Vec<String>> {
+        let mut files = self.index.keys().chain(self.pending.keys()).cloned().collect::<Vec<_>>();
+        files.sort();
+        files.dedup();
+        Ok(files)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if self.pending.is_empty() && self.file.is_some() { return Ok(()) }
+
+        let files = self.files()?.into_iter()
+            .map(|rel| Ok((rel.clone(), self.get(&rel)?)))
+            .collect::<Result<BTreeMap<_, _>>>()?;
+        let mut tmp = tempfile::NamedTempFile::new_in(parent_dir(&self.path))?;
+        {
+            let mut writer = BufWriter::new(tmp.as_file_mut());
+            Self::write_bundle(&mut writer, &files)?;
+            writer.flush()?;
+        }
+        tmp.persist(&self.path).with_context(|| format!("[BundlePack] Failed to write {}", self.path.display()))?;
+
+        *self = Self::open(&self.path)?;
+        Ok(())
+    }
+}
+
+/// Directory holding `path`, for temporary files renamed over it.
+fn parent_dir(path: &Path) -> &Path {
+    path.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."))
+}
+
+/// Whether a pack file is compressed already, so zipping it again gains nothing.
+fn is_compressed(rel: &str) -> bool {
+    [".parquet", ".pmtiles", ".gz"].iter().any(|ext| rel.ends_with(ext))
+}
+
+/// Whether `path` names a single-file pack (a zip archive or bundle) rather than a directory.
+pub(crate) fn is_container_path(path: &Path) -> bool {
+    matches!(path.extension().and_then(|ext| ext.to_str()), Some("zip") | Some(BundlePack::EXTENSION))
+}
+
+/// Resolve a client-supplied pack `path` against the `root` directory packs are served from,
+/// rejecting absolute paths, `..` components, and symlinks that lead outside of `root`.
+#[cfg(any(feature = "server", feature = "grpc"))]
+pub(crate) fn resolve_pack_path(root: &Path, path: &str) -> Result<PathBuf> {
+    use std::path::Component;
+
+    let relative = Path::new(path);
+    ensure!(relative.components().all(|part| matches!(part, Component::Normal(_) | Component::CurDir)),
+        "Pack path must be relative to the pack root and stay inside it: {path:?}");
+    let root = root.canonicalize().with_context(|| format!("Pack root does not exist: {}", root.display()))?;
+    let resolved = root.join(relative).canonicalize().with_context(|| format!("Pack does not exist: {path:?}"))?;
+    ensure!(resolved.starts_with(&root), "Pack path must stay inside the pack root: {path:?}");
+    Ok(resolved)
+}
+
+/// Open the existing pack at `path`: a directory, a zip archive, or a `.openmander` bundle,
+/// detected from the file's contents.
+pub fn open_pack_store(path: &Path) -> Result<Box<dyn PackStore>> {
+    if path.is_dir() { return Ok(Box::new(DiskPack::new(path))) }
+    ensure!(path.exists(), "Pack does not exist: {}", path.display());
+
+    let mut magic = [0u8; 8];
+    let read = File::open(path)?.read(&mut magic)?;
+    match &magic[..read] {
+        magic if magic == BundlePack::MAGIC => Ok(Box::new(BundlePack::open(path)?)),
+        [b'P', b'K', 3, 4, ..] => Ok(Box::new(ZipPack::open(path)?)),
+        _ => bail!("Not a pack directory, zip archive or bundle: {}", path.display()),
+    }
+}
+
+/// Start a new pack at `path`, by its extension: a `.zip` archive, a `.openmander` bundle, or
+/// otherwise a directory. Files already at `path` are replaced on flush.
+pub fn create_pack_store(path: &Path) -> Result<Box<dyn PackStore>> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("zip") => Ok(Box::new(ZipPack::create(path))),
+        Some(BundlePack::EXTENSION) => Ok(Box::new(BundlePack::create(path))),
+        _ => {
+            std::fs::create_dir_all(path).with_context(|| format!("Failed to create directory {}", path.display()))?;
+            Ok(Box::new(DiskPack::new(path)))
+        }
+    }
+}
+
+/// Copy the pack at `src` into a new pack at `dst` (see [`open_pack_store`] and
+/// [`create_pack_store`]), e.g. to bundle a pack directory into one `.openmander` file.
+/// Returns the number of files copied.
+pub fn convert_pack(src: &Path, dst: &Path) -> Result<usize> {
+    ensure!(src != dst, "[convert_pack] Source and destination must differ");
+    let source = open_pack_store(src)?;
+    ensure!(source.has("manifest.json"), "[convert_pack] {} has no manifest.json", src.display());
+
+    let mut target = create_pack_store(dst)?;
+    let files = source.files()?;
+    for rel in &files {
+        target.put(rel, &source.get(rel)?)?;
+    }
+    target.flush()?;
+    Ok(files.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{map::Map, test_maps};
+
+    #[test]
+    fn packs_round_trip_through_every_store() {
+        let map = test_maps::row_with(3, &[], vec![("pop".into(), vec![3.0, 4.0, 5.0])]);
+
+        let dir = tempfile::tempdir().unwrap();
+        let (pack, zip, bundle) = (dir.path().join("XX_pack"), dir.path().join("XX_pack.zip"), dir.path().join("XX_pack.openmander"));
+        map.write_to_pack(&pack).unwrap();
+        assert!(convert_pack(&pack, &zip).unwrap() > 0);
+        convert_pack(&zip, &bundle).unwrap();
+
+        let files = open_pack_store(&pack).unwrap().files().unwrap();
+        assert_eq!(open_pack_store(&zip).unwrap().files().unwrap(), files);
+        assert_eq!(open_pack_store(&bundle).unwrap().files().unwrap(), files);
+
+        let read = Map::read_from_pack(&bundle).unwrap();
+        assert_eq!(read.base().unwrap().get_unit_weights().get_as_f64("pop", 2).unwrap(), 5.0);
+        let unpacked = BundlePack::unpack(&std::fs::read(&bundle).unwrap()).unwrap();
+        assert_eq!(unpacked.get("manifest.json").unwrap(), open_pack_store(&pack).unwrap().get("manifest.json").unwrap());
+        assert!(open_pack_store(&dir.path().join("missing")).is_err());
+    }
+}
@@ -0,0 +1,150 @@
+use std::{collections::{BTreeMap, HashSet}, fmt, sync::{Arc, Mutex}};
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+
+use super::{FileHash, Manifest, PackSource};
+
+/// SHA-256 hash of `bytes`, as lowercase hex.
+pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// A pack section whose contents do not match the hash recorded in the pack's manifest, usually
+/// because the pack was truncated or damaged in transfer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorruptSection {
+    pub pack: String,     // Pack the section belongs to, e.g. its path
+    pub section: String,  // Path within the pack, e.g. "data/block.parquet"
+    pub expected: String, // SHA-256 recorded in the manifest
+    pub actual: String,   // SHA-256 of the bytes read
+    pub len: usize,       // Number of bytes read
+}
+
+impl fmt::Display for CorruptSection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[Pack] Section '{}' of {} is corrupted: read {} bytes with sha256 {}, but the manifest records {}. \
+            The pack was likely truncated or damaged in transfer; delete it and download it again.",
+            self.section, self.pack, self.len, self.actual, self.expected)
+    }
+}
+
+impl std::error::Error for CorruptSection {}
+
+/// If `error` was caused by a corrupted section, report the corruption itself rather than the
+/// parse step it broke.
+pub(crate) fn surface_corruption(error: anyhow::Error) -> anyhow::Error {
+    match error.downcast_ref::<CorruptSection>() {
+        Some(corrupt) => corrupt.clone().into(),
+        None => error,
+    }
+}
+
+/// A [`PackSource`] that checks each section against the SHA-256 hash recorded in the pack's
+/// manifest the first time it is read, failing with [`CorruptSection`] on a mismatch. Sections
+/// without a recorded hash (including `manifest.json`) are read unchecked.
+pub struct VerifiedPack<'a> {
+    src: &'a dyn PackSource,
+    pack: String,                       // Name of the pack, for error messages
+    hashes: BTreeMap<String, FileHash>, // Section -> expected hash
+    verified: Mutex<HashSet<String>>,   // Sections already checked
+}
+
+impl<'a> VerifiedPack<'a> {
+    /// Verify the sections of `src` against `hashes`. `pack` names the pack in error messages.
+    pub fn new(src: &'a dyn PackSource, pack: impl Into<String>, hashes: BTreeMap<String, FileHash>) -> Self {
+        Self { src, pack: pack.into(), hashes, verified: Mutex::new(HashSet::new()) }
+    }
+
+    /// Verify the sections of `src` against the hashes in its manifest. Packs without a manifest
+    /// have nothing to check against and are read unchecked; a manifest that can't be read is
+    /// reported as corruption.
+    pub fn from_manifest(src: &'a dyn PackSource, pack: impl Into<String>) -> Result<Self> {
+        let pack = pack.into();
+        if !src.has("manifest.json") { return Ok(Self::new(src, pack, BTreeMap::new())) }
+        let manifest = Manifest::from_pack_source(src).with_context(|| format!(
+            "[Pack] Section 'manifest.json' of {pack} is corrupted and could not be parsed. \
+            The pack was likely truncated or damaged in transfer; delete it and download it again."))?;
+        Ok(Self::new(src, pack, manifest.files))
+    }
+
+    /// Check every section with a recorded hash, not only those read so far. Returns the number
+    /// of sections checked.
+    pub fn verify_all(&self) -> Result<usize> {
+        for section in self.hashes.keys() {
+            self.get(section)?;
+        }
+        Ok(self.hashes.len())
+    }
+
+    /// Check `bytes` read from `section` against its recorded hash.
+    fn check(&self, section: &str, bytes: &[u8]) -> Result<()> {
+        let Some(expected) = self.hashes.get(section) else { return Ok(()) };
+        let actual = sha256_hex(bytes);
+        if actual != expected.sha256 {
+            return Err(CorruptSection {
+                pack: self.pack.clone(),
+                section: section.into(),
+                expected: expected.sha256.clone(),
+                actual,
+                len: bytes.len(),
+            }.into())
+        }
+        Ok(())
+    }
+}
+
+impl PackSource for VerifiedPack<'_> {
+    fn get(&self, rel: &str) -> Result<Arc<[u8]>> {
+        let listed = self.hashes.contains_key(rel);
+        let bytes = self.src.get(rel).with_context(|| match listed {
+            true => format!("[Pack] Section '{rel}' of {} is listed in its manifest but missing; \
+                the pack may be incomplete, so download it again", self.pack),
+            false => format!("[Pack] Failed to read section '{rel}' of {}", self.pack),
+        })?;
+        if listed && !self.verified.lock().expect("verified set lock poisoned").contains(rel) {
+            self.check(rel, &bytes)?;
+            self.verified.lock().expect("verified set lock poisoned").insert(rel.to_string());
+        }
+        Ok(bytes)
+    }
+
+    fn has(&self, rel: &str) -> bool { self.src.has(rel) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::pack::MemPack;
+
+    #[test]
+    fn corrupted_sections_fail_on_first_read() {
+        let section = b"geo_id,pop\n01,5\n".as_slice();
+        let hashes = BTreeMap::from([("data/block.csv".to_string(), FileHash { sha256: sha256_hex(section) })]);
+
+        let intact = MemPack::new([("data/block.csv".to_string(), Arc::from(section))].into());
+        let pack = VerifiedPack::new(&intact, "intact", hashes.clone());
+        assert_eq!(pack.get("data/block.csv").unwrap().as_ref(), section);
+        assert_eq!(pack.verify_all().unwrap(), 1);
+
+        let truncated = MemPack::new([("data/block.csv".to_string(), Arc::from(&section[..8]))].into());
+        let pack = VerifiedPack::new(&truncated, "truncated", hashes.clone());
+        let error = pack.get("data/block.csv").unwrap_err().context("Failed to load layer block");
+        let corrupt = surface_corruption(error).downcast::<CorruptSection>().unwrap();
+        assert_eq!((corrupt.section.as_str(), corrupt.len), ("data/block.csv", 8));
+
+        let empty = MemPack::default();
+        let missing = VerifiedPack::new(&empty, "missing", hashes);
+        assert!(missing.get("data/block.csv").unwrap_err().to_string().contains("listed in its manifest but missing"));
+    }
+
+    #[test]
+    fn unreadable_manifests_are_corruption() {
+        let unlisted = MemPack::default();
+        assert_eq!(VerifiedPack::from_manifest(&unlisted, "bare").unwrap().verify_all().unwrap(), 0);
+
+        let truncated = MemPack::new([("manifest.json".to_string(), Arc::from(b"{\"files\": {".as_slice()))].into());
+        let error = VerifiedPack::from_manifest(&truncated, "truncated").err().unwrap().to_string();
+        assert!(error.contains("'manifest.json' of truncated is corrupted") && error.contains("download it again"));
+    }
+}
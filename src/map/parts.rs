@@ -0,0 +1,93 @@
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::{anyhow, ensure, Result};
+use geo::MultiPolygon;
+use polars::{frame::DataFrame, prelude::Column};
+
+use geograph::{Region, UnitId};
+
+use crate::{graph::WeightMatrix, map::{GeoId, GeoType, Map, MapLayer, ParentRefs}};
+
+/// GEOID of the synthetic region unit covering a map built from parts.
+const REGION_ID: &str = "00";
+
+impl Map {
+    /// Build a map directly from per-unit geometries and data, without a pack.
+    ///
+    /// Each geometry becomes a base unit. `ids` gives each unit's 15-character GEOID; if omitted,
+    /// units are numbered `000000000000000`, `000000000000001`, and so on. Each entry of `columns`
+    /// is a named data series with one value per unit. Adjacency is computed from the geometry,
+    /// and each pair in `adjacency` adds an extra edge between two unit indices (e.g. to connect
+    /// islands). A single region unit covering the whole map is created above the base layer.
+    pub fn from_parts(
+        geoms: Vec<MultiPolygon<f64>>,
+        ids: Option<Vec<String>>,
+        columns: Vec<(String, Vec<f64>)>,
+        adjacency: &[(usize, usize)],
+    ) -> Result<Self> {
+        let n = geoms.len();
+        ensure!(n > 0, "[Map] Cannot build a map with no units");
+
+        let ids = ids.unwrap_or_else(|| (0..n).map(|i| format!("{i:0width$}", width = GeoType::BOTTOM.id_len())).collect());
+        ensure!(ids.len() == n, "[Map] Expected {} ids, got {}", n, ids.len());
+        if let Some(id) = ids.iter().find(|id| id.len() != GeoType::BOTTOM.id_len()) {
+            return Err(anyhow!("[Map] Invalid id {:?}: expected {} characters", id, GeoType::BOTTOM.id_len()));
+        }
+        for (name, values) in &columns {
+            ensure!(values.len() == n, "[Map] Column '{}' has {} values, expected {}", name, values.len(), n);
+            ensure!(!matches!(name.as_str(), "idx" | "geo_id"), "[Map] Column name '{}' is reserved", name);
+        }
+        if let Some(&(a, b)) = adjacency.iter().find(|&&(a, b)| a >= n || b >= n) {
+            return Err(anyhow!("[Map] Adjacency ({}, {}) out of range for {} units", a, b, n));
+        }
+
+        let geo_ids = GeoId::new_all(GeoType::BOTTOM, ids.iter().map(String::as_str));
+        let index = geo_ids.iter().enumerate()
+            .map(|(i, geo_id)| (geo_id.clone(), i as u32))
+            .collect::<HashMap<_, _>>();
+        ensure!(index.len() == n, "[Map] Unit ids must be unique");
+
+        let region_id = GeoId::new(GeoType::TOP, REGION_ID);
+        let mut refs = ParentRefs::default();
+        refs.set(GeoType::TOP, Some(region_id.clone()));
+        let parents = vec![refs; n];
+
+        let forced = adjacency.iter()
+            .filter(|&&(a, b)| a != b)
+            .map(|&(a, b)| (UnitId(a as u32), UnitId(b as u32)))
+            .collect::<Vec<_>>();
+        let region = Region::new(geoms, None)
+            .map_err(|e| anyhow!("[Map] Region construction failed: {:?}", e))?
+            .with_forced_adjacencies(&forced);
+
+        let unit_data = DataFrame::new(
+            std::iter::once(Column::new("geo_id".into(), ids))
+                .chain(columns.iter().map(|(name, values)| Column::new(name.into(), values)))
+                .collect()
+        )?.with_row_index("idx".into(), None)?;
+        let unit_weights = Arc::new(WeightMatrix::from_dataframe(&unit_data));
+
+        let region_data = DataFrame::new(
+            std::iter::once(Column::new("geo_id".into(), [REGION_ID]))
+                .chain(columns.iter().map(|(name, values)| Column::new(name.into(), [values.iter().sum::<f64>()])))
+                .collect()
+        )?.with_row_index("idx".into(), None)?;
+        let region_weights = Arc::new(WeightMatrix::from_dataframe(&region_data));
+        let outline = Region::new(vec![region.union_of(region.unit_ids())], None)
+            .map_err(|e| anyhow!("[Map] Region construction failed for outline: {:?}", e))?;
+
+        let mut map = Map::default();
+        map.insert(MapLayer::new(
+            GeoType::TOP,
+            vec![region_id.clone()],
+            HashMap::from([(region_id, 0)]),
+            vec![ParentRefs::default()],
+            region_data,
+            region_weights,
+            Arc::new(outline),
+        ));
+        map.insert(MapLayer::new(GeoType::BOTTOM, geo_ids, index, parents, unit_data, unit_weights, Arc::new(region)));
+
+        Ok(map)
+    }
+}
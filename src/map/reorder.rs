@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use polars::prelude::{IdxCa, IdxSize};
+
+use crate::{graph::WeightMatrix, map::{Map, MapLayer}};
+
+impl MapLayer {
+    /// Renumber the units of this layer along a Hilbert curve through their bounding boxes,
+    /// so that units close on the map are close in memory.
+    fn reorder_hilbert(&mut self) -> Result<()> {
+        let order = self.region.hilbert_order();
+        let region = self.region.permuted(&order);
+        let units = order.iter().map(|unit| unit.0 as usize).collect::<Vec<_>>();
+
+        self.geo_ids = units.iter().map(|&u| self.geo_ids[u].clone()).collect();
+        self.index = self.geo_ids.iter().enumerate()
+            .map(|(i, geo_id)| (geo_id.clone(), i as u32))
+            .collect();
+        self.parents = units.iter().map(|&u| self.parents[u].clone()).collect();
+
+        let rows = IdxCa::from_vec("idx".into(), units.iter().map(|&u| u as IdxSize).collect());
+        self.unit_data = self.unit_data.take(&rows)?
+            .drop("idx")?
+            .with_row_index("idx".into(), None)?;
+        self.unit_weights = Arc::new(WeightMatrix::from_dataframe(&self.unit_data));
+        self.region = Arc::new(region);
+        Ok(())
+    }
+}
+
+impl Map {
+    /// Renumber the units of every layer along a Hilbert curve, improving the cache locality
+    /// of neighbor and frontier scans during chains. Parent references are by GeoId, so they
+    /// are unaffected; plans built against the previous unit order are no longer valid.
+    pub fn reorder_hilbert(&mut self) -> Result<()> {
+        for layer in self.layers_iter_mut() {
+            layer.reorder_hilbert()?;
+        }
+        Ok(())
+    }
+}
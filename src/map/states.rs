@@ -0,0 +1,120 @@
+/// Identifiers and 2020-cycle seat counts for a state or territory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StateInfo {
+    pub code: &'static str,  // Two-letter postal code, e.g. "NE"
+    pub fips: &'static str,  // Two-digit FIPS code, e.g. "31"
+    pub name: &'static str,  // Full name, e.g. "Nebraska"
+    pub congress: u32,       // Voting U.S. House seats (2020 apportionment)
+    pub sldu: Option<u32>,   // State senate seats, if any
+    pub sldl: Option<u32>,   // State house seats (None for unicameral legislatures)
+}
+
+/// Every state, DC, and Puerto Rico, ordered by FIPS code.
+/// Legislative counts are seats, which exceed district counts in states with multi-member districts.
+pub const STATES: &[StateInfo] = &[
+    StateInfo { code: "AL", fips: "01", name: "Alabama", congress: 7, sldu: Some(35), sldl: Some(105) },
+    StateInfo { code: "AK", fips: "02", name: "Alaska", congress: 1, sldu: Some(20), sldl: Some(40) },
+    StateInfo { code: "AZ", fips: "04", name: "Arizona", congress: 9, sldu: Some(30), sldl: Some(60) },
+    StateInfo { code: "AR", fips: "05", name: "Arkansas", congress: 4, sldu: Some(35), sldl: Some(100) },
+    StateInfo { code: "CA", fips: "06", name: "California", congress: 52, sldu: Some(40), sldl: Some(80) },
+    StateInfo { code: "CO", fips: "08", name: "Colorado", congress: 8, sldu: Some(35), sldl: Some(65) },
+    StateInfo { code: "CT", fips: "09", name: "Connecticut", congress: 5, sldu: Some(36), sldl: Some(151) },
+    StateInfo { code: "DE", fips: "10", name: "Delaware", congress: 1, sldu: Some(21), sldl: Some(41) },
+    StateInfo { code: "DC", fips: "11", name: "District of Columbia", congress: 0, sldu: None, sldl: None },
+    StateInfo { code: "FL", fips: "12", name: "Florida", congress: 28, sldu: Some(40), sldl: Some(120) },
+    StateInfo { code: "GA", fips: "13", name: "Georgia", congress: 14, sldu: Some(56), sldl: Some(180) },
+    StateInfo { code: "HI", fips: "15", name: "Hawaii", congress: 2, sldu: Some(25), sldl: Some(51) },
+    StateInfo { code: "ID", fips: "16", name: "Idaho", congress: 2, sldu: Some(35), sldl: Some(70) },
+    StateInfo { code: "IL", fips: "17", name: "Illinois", congress: 17, sldu: Some(59), sldl: Some(118) },
+    StateInfo { code: "IN", fips: "18", name: "Indiana", congress: 9, sldu: Some(50), sldl: Some(100) },
+    StateInfo { code: "IA", fips: "19", name: "Iowa", congress: 4, sldu: Some(50), sldl: Some(100) },
+    StateInfo { code: "KS", fips: "20", name: "Kansas", congress: 4, sldu: Some(40), sldl: Some(125) },
+    StateInfo { code: "KY", fips: "21", name: "Kentucky", congress: 6, sldu: Some(38), sldl: Some(100) },
+    StateInfo { code: "LA", fips: "22", name: "Louisiana", congress: 6, sldu: Some(39), sldl: Some(105) },
+    StateInfo { code: "ME", fips: "23", name: "Maine", congress: 2, sldu: Some(35), sldl: Some(151) },
+    StateInfo { code: "MD", fips: "24", name: "Maryland", congress: 8, sldu: Some(47), sldl: Some(141) },
+    StateInfo { code: "MA", fips: "25", name: "Massachusetts", congress: 9, sldu: Some(40), sldl: Some(160) },
+    StateInfo { code: "MI", fips: "26", name: "Michigan", congress: 13, sldu: Some(38), sldl: Some(110) },
+    StateInfo { code: "MN", fips: "27", name: "Minnesota", congress: 8, sldu: Some(67), sldl: Some(134) },
+    StateInfo { code: "MS", fips: "28", name: "Mississippi", congress: 4, sldu: Some(52), sldl: Some(122) },
+    StateInfo { code: "MO", fips: "29", name: "Missouri", congress: 8, sldu: Some(34), sldl: Some(163) },
+    StateInfo { code: "MT", fips: "30", name: "Montana", congress: 2, sldu: Some(50), sldl: Some(100) },
+    StateInfo { code: "NE", fips: "31", name: "Nebraska", congress: 3, sldu: Some(49), sldl: None },
+    StateInfo { code: "NV", fips: "32", name: "Nevada", congress: 4, sldu: Some(21), sldl: Some(42) },
+    StateInfo { code: "NH", fips: "33", name: "New Hampshire", congress: 2, sldu: Some(24), sldl: Some(400) },
+    StateInfo { code: "NJ", fips: "34", name: "New Jersey", congress: 12, sldu: Some(40), sldl: Some(80) },
+    StateInfo { code: "NM", fips: "35", name: "New Mexico", congress: 3, sldu: Some(42), sldl: Some(70) },
+    StateInfo { code: "NY", fips: "36", name: "New York", congress: 26, sldu: Some(63), sldl: Some(150) },
+    StateInfo { code: "NC", fips: "37", name: "North Carolina", congress: 14, sldu: Some(50), sldl: Some(120) },
+    StateInfo { code: "ND", fips: "38", name: "North Dakota", congress: 1, sldu: Some(47), sldl: Some(94) },
+    StateInfo { code: "OH", fips: "39", name: "Ohio", congress: 15, sldu: Some(33), sldl: Some(99) },
+    StateInfo { code: "OK", fips: "40", name: "Oklahoma", congress: 5, sldu: Some(48), sldl: Some(101) },
+    StateInfo { code: "OR", fips: "41", name: "Oregon", congress: 6, sldu: Some(30), sldl: Some(60) },
+    StateInfo { code: "PA", fips: "42", name: "Pennsylvania", congress: 17, sldu: Some(50), sldl: Some(203) },
+    StateInfo { code: "RI", fips: "44", name: "Rhode Island", congress: 2, sldu: Some(38), sldl: Some(75) },
+    StateInfo { code: "SC", fips: "45", name: "South Carolina", congress: 7, sldu: Some(46), sldl: Some(124) },
+    StateInfo { code: "SD", fips: "46", name: "South Dakota", congress: 1, sldu: Some(35), sldl: Some(70) },
+    StateInfo { code: "TN", fips: "47", name: "Tennessee", congress: 9, sldu: Some(33), sldl: Some(99) },
+    StateInfo { code: "TX", fips: "48", name: "Texas", congress: 38, sldu: Some(31), sldl: Some(150) },
+    StateInfo { code: "UT", fips: "49", name: "Utah", congress: 4, sldu: Some(29), sldl: Some(75) },
+    StateInfo { code: "VT", fips: "50", name: "Vermont", congress: 1, sldu: Some(30), sldl: Some(150) },
+    StateInfo { code: "VA", fips: "51", name: "Virginia", congress: 11, sldu: Some(40), sldl: Some(100) },
+    StateInfo { code: "WA", fips: "53", name: "Washington", congress: 10, sldu: Some(49), sldl: Some(98) },
+    StateInfo { code: "WV", fips: "54", name: "West Virginia", congress: 2, sldu: Some(34), sldl: Some(100) },
+    StateInfo { code: "WI", fips: "55", name: "Wisconsin", congress: 8, sldu: Some(33), sldl: Some(99) },
+    StateInfo { code: "WY", fips: "56", name: "Wyoming", congress: 1, sldu: Some(31), sldl: Some(62) },
+    StateInfo { code: "PR", fips: "72", name: "Puerto Rico", congress: 0, sldu: Some(27), sldl: Some(51) },
+];
+
+impl StateInfo {
+    /// Look up a state by postal code (case-insensitive).
+    pub fn from_code(code: &str) -> Option<&'static Self> {
+        STATES.iter().find(|state| state.code.eq_ignore_ascii_case(code))
+    }
+
+    /// Look up a state by FIPS code.
+    pub fn from_fips(fips: &str) -> Option<&'static Self> {
+        STATES.iter().find(|state| state.fips == fips)
+    }
+
+    /// Look up a state by full name (case-insensitive).
+    pub fn from_name(name: &str) -> Option<&'static Self> {
+        STATES.iter().find(|state| state.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Look up a state by postal code, FIPS code, or full name.
+    pub fn lookup(key: &str) -> Option<&'static Self> {
+        let key = key.trim();
+        Self::from_code(key).or_else(|| Self::from_fips(key)).or_else(|| Self::from_name(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apportionment_totals_435() {
+        assert_eq!(STATES.iter().map(|state| state.congress).sum::<u32>(), 435);
+    }
+
+    #[test]
+    fn codes_and_fips_are_unique() {
+        for (i, a) in STATES.iter().enumerate() {
+            for b in &STATES[i + 1..] {
+                assert_ne!(a.code, b.code);
+                assert_ne!(a.fips, b.fips);
+            }
+        }
+    }
+
+    #[test]
+    fn lookup_by_any_key() {
+        let nebraska = StateInfo::from_code("NE").unwrap();
+        assert_eq!(StateInfo::lookup("ne"), Some(nebraska));
+        assert_eq!(StateInfo::lookup("31"), Some(nebraska));
+        assert_eq!(StateInfo::lookup("nebraska"), Some(nebraska));
+        assert_eq!(nebraska.sldl, None);
+        assert_eq!(StateInfo::lookup("ZZ"), None);
+    }
+}
@@ -0,0 +1,117 @@
+use std::{collections::BTreeMap, path::Path};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::map::{Map, MapLayer, pack::{Manifest, PackSource, open_pack_store}};
+
+/// Number of equal-width bins in [`ColumnStats::histogram`].
+pub const HISTOGRAM_BINS: usize = 10;
+
+/// Summary of one numeric column of a layer, precomputed when a pack is written so readers can
+/// show ranges without scanning its tables.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ColumnStats {
+    pub min: f64,
+    pub max: f64,
+    pub sum: f64,
+    pub q1: f64,
+    pub median: f64,
+    pub q3: f64,
+    pub histogram: Vec<u64>, // Unit counts in HISTOGRAM_BINS equal-width bins from min to max
+}
+
+impl ColumnStats {
+    /// Summarize a column, or `None` if it has no values.
+    pub fn from_values(values: &[f64]) -> Option<Self> {
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let (&min, &max) = (sorted.first()?, sorted.last()?);
+
+        let mut histogram = vec![0; HISTOGRAM_BINS];
+        let width = (max - min) / HISTOGRAM_BINS as f64;
+        for &value in &sorted {
+            let bin = if width > 0.0 { ((value - min) / width) as usize } else { 0 };
+            histogram[bin.min(HISTOGRAM_BINS - 1)] += 1;
+        }
+
+        Some(Self {
+            min,
+            max,
+            sum: sorted.iter().sum(),
+            q1: quantile(&sorted, 0.25),
+            median: quantile(&sorted, 0.5),
+            q3: quantile(&sorted, 0.75),
+            histogram,
+        })
+    }
+}
+
+/// Linearly interpolated `q`-quantile of sorted, non-empty values.
+pub(crate) fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let position = q * (sorted.len() - 1) as f64;
+    let (lower, upper) = (position.floor() as usize, position.ceil() as usize);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f64)
+}
+
+impl MapLayer {
+    /// Statistics of each numeric column of this layer, by column name.
+    pub fn column_stats(&self) -> BTreeMap<String, ColumnStats> {
+        let weights = &self.unit_weights;
+        weights.series().into_iter()
+            .filter_map(|name| {
+                let values = (0..self.len()).map(|u| weights.get_as_f64(&name, u).unwrap()).collect::<Vec<_>>();
+                Some((name, ColumnStats::from_values(&values)?))
+            })
+            .collect()
+    }
+}
+
+impl Map {
+    /// Statistics of each numeric column of each layer, by layer name and column name.
+    pub fn column_stats(&self) -> BTreeMap<String, BTreeMap<String, ColumnStats>> {
+        self.layers_iter()
+            .map(|layer| (layer.ty().to_str().to_string(), layer.column_stats()))
+            .filter(|(_, stats)| !stats.is_empty())
+            .collect()
+    }
+
+    /// Read the column statistics stored in the manifest of the pack at `path`, without reading
+    /// its tables. Packs written before statistics were recorded have none.
+    pub fn read_pack_column_stats(path: &Path) -> Result<BTreeMap<String, BTreeMap<String, ColumnStats>>> {
+        Self::read_pack_source_column_stats(&*open_pack_store(path)?)
+    }
+
+    /// Read the column statistics stored in the manifest of any [`PackSource`].
+    pub fn read_pack_source_column_stats(src: &dyn PackSource) -> Result<BTreeMap<String, BTreeMap<String, ColumnStats>>> {
+        Ok(Manifest::from_pack_source(src)?.stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{map::MemPack, test_maps};
+
+    #[test]
+    fn stats_bin_values_between_min_and_max() {
+        let stats = ColumnStats::from_values(&[4.0, 0.0, 10.0, 5.0, 1.0]).unwrap();
+        assert_eq!((stats.min, stats.max, stats.sum), (0.0, 10.0, 20.0));
+        assert_eq!((stats.q1, stats.median, stats.q3), (1.0, 4.0, 5.0));
+        assert_eq!(stats.histogram, vec![1, 1, 0, 0, 1, 1, 0, 0, 0, 1]);
+
+        assert_eq!(ColumnStats::from_values(&[3.0; 4]).unwrap().histogram[0], 4);
+        assert!(ColumnStats::from_values(&[]).is_none());
+    }
+
+    #[test]
+    fn written_manifest_records_stats() {
+        let map = test_maps::row_with(4, &[], vec![("pop".into(), vec![1.0, 2.0, 3.0, 4.0])]);
+
+        let mut pack = MemPack::new(Default::default());
+        map.write_to_pack_sink(&mut pack, Path::new("test-pack")).unwrap();
+        let stats = Map::read_pack_source_column_stats(&pack).unwrap();
+        assert_eq!(stats, map.column_stats());
+        assert_eq!(stats["block"]["pop"].sum, 10.0);
+    }
+}
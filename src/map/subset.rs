@@ -0,0 +1,213 @@
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use anyhow::{anyhow, ensure, Result};
+use geo::{Contains, MultiPolygon};
+use polars::prelude::{DataType, IdxCa, IdxSize, NamedFrom, Series};
+
+use geograph::{Region, UnitId};
+
+use crate::{graph::WeightMatrix, map::{GeoId, GeoType, Map, MapLayer, ParentRefs}};
+
+impl MapLayer {
+    /// Build a layer containing only `units` (in order), with the given geometry for each.
+    /// Adjacency is recomputed from geometry; forced adjacencies between kept units are preserved.
+    pub(super) fn select(&self, units: &[usize], geometries: Vec<MultiPolygon<f64>>) -> Result<MapLayer> {
+        let new_index = units.iter().enumerate()
+            .map(|(i, &u)| (u, i as u32))
+            .collect::<HashMap<_, _>>();
+
+        let forced = self.forced_adjacencies().into_iter()
+            .filter_map(|(a, b)| Some((UnitId(*new_index.get(&(a.0 as usize))?), UnitId(*new_index.get(&(b.0 as usize))?))))
+            .collect::<Vec<_>>();
+
+        let region = Region::new(geometries, None)
+            .map_err(|e| anyhow!("[MapLayer] Region construction failed for {:?} subset: {:?}", self.ty(), e))?
+            .with_forced_adjacencies(&forced);
+
+        let geo_ids = units.iter().map(|&u| self.geo_ids[u].clone()).collect::<Vec<_>>();
+        let index = geo_ids.iter().enumerate()
+            .map(|(i, geo_id)| (geo_id.clone(), i as u32))
+            .collect();
+        let parents = units.iter().map(|&u| self.parents[u].clone()).collect();
+
+        let rows = IdxCa::from_vec("idx".into(), units.iter().map(|&u| u as IdxSize).collect());
+        let unit_data = self.unit_data.take(&rows)?
+            .drop("idx")?
+            .with_row_index("idx".into(), None)?;
+        let unit_weights = Arc::new(WeightMatrix::from_dataframe(&unit_data));
+
+        Ok(MapLayer::new(self.ty(), geo_ids, index, parents, unit_data, unit_weights, Arc::new(region)))
+    }
+
+    /// Replace the numeric data of selected rows with sums over base units, and their centroids with
+    /// the centroid of their (clipped) geometry. `rows` maps each row to the base units it now covers.
+    pub(super) fn overwrite_rows(&mut self, rows: &[(usize, Vec<usize>)], base: &MapLayer) -> Result<()> {
+        let columns = self.unit_data.get_columns().iter()
+            .filter(|column| column.dtype().is_primitive_numeric())
+            .map(|column| column.name().to_string())
+            .filter(|name| !matches!(name.as_str(), "idx" | "centroid_lon" | "centroid_lat"))
+            .filter(|name| base.unit_data.column(name).is_ok_and(|c| c.dtype().is_primitive_numeric()))
+            .collect::<Vec<_>>();
+
+        for name in columns {
+            let column = self.unit_data.column(&name)?;
+            let dtype = column.dtype().clone();
+            let mut values = column.cast(&DataType::Float64)?.f64()?.into_no_null_iter().collect::<Vec<_>>();
+            let base_values = base.unit_data.column(&name)?.cast(&DataType::Float64)?;
+            let base_values = base_values.f64()?;
+            for (row, units) in rows {
+                values[*row] = units.iter().filter_map(|&u| base_values.get(u)).sum();
+            }
+            self.unit_data.replace(&name, Series::new(name.as_str().into(), values).cast(&dtype)?)?;
+        }
+
+        for (name, coord) in [("centroid_lon", 0), ("centroid_lat", 1)] {
+            let Ok(column) = self.unit_data.column(name) else { continue };
+            let mut values = column.f64()?.into_iter().collect::<Vec<_>>();
+            for (row, _) in rows {
+                let centroid = self.region.centroid(UnitId(*row as u32));
+                values[*row] = Some(if coord == 0 { centroid.x } else { centroid.y });
+            }
+            self.unit_data.replace(name, Series::new(name.into(), values))?;
+        }
+
+        self.unit_weights = Arc::new(WeightMatrix::from_dataframe(&self.unit_data));
+        Ok(())
+    }
+}
+
+impl Map {
+    /// Extract a self-consistent sub-map containing the base units for which `predicate` holds.
+    ///
+    /// Higher layers keep every unit with at least one selected base unit. Units that are only
+    /// partially covered are clipped: their geometry becomes the union of their selected base
+    /// units, and their numeric data is recomputed as sums over those units. Adjacency is rebuilt
+    /// from the filtered geometry and parent references are preserved.
+    pub fn subset(&self, predicate: impl Fn(&GeoId, &ParentRefs) -> bool) -> Result<Map> {
+        self.require_geometry("[Map.subset]")?;
+        let base = self.base()?;
+        let keep = base.geo_ids.iter().zip(&base.parents)
+            .map(|(geo_id, parents)| predicate(geo_id, parents))
+            .collect::<Vec<_>>();
+        let base_units = (0..base.len()).filter(|&u| keep[u]).collect::<Vec<_>>();
+        ensure!(!base_units.is_empty(), "[Map] Subset contains no {:?} units", GeoType::BOTTOM);
+
+        let mut map = Map::default();
+        map.provenance = self.provenance.clone();
+        map.build = self.build.clone();
+        for layer in self.layers_iter() {
+            if layer.ty() == GeoType::BOTTOM {
+                let geometries = base_units.iter().map(|&u| base.region.geometry(UnitId(u as u32)).into_owned()).collect();
+                map.insert(layer.select(&base_units, geometries)?);
+                continue
+            }
+
+            // Group base units by their parent in this layer.
+            let mut kept = vec![Vec::new(); layer.len()];
+            let mut total = vec![0; layer.len()];
+            for (u, parent) in base.parent_indices(layer).into_iter().enumerate() {
+                let Some(p) = parent else { continue };
+                total[p] += 1;
+                if keep[u] { kept[p].push(u) }
+            }
+
+            let units = (0..layer.len()).filter(|&p| !kept[p].is_empty()).collect::<Vec<_>>();
+            if units.is_empty() { continue }
+            let geometries = units.iter()
+                .map(|&p| match kept[p].len() == total[p] {
+                    true => layer.region.geometry(UnitId(p as u32)).into_owned(),
+                    false => base.region.union_of(kept[p].iter().map(|&u| UnitId(u as u32))),
+                })
+                .collect();
+
+            let mut selected = layer.select(&units, geometries)?;
+            let clipped = units.iter().enumerate()
+                .filter(|&(_, &p)| kept[p].len() < total[p])
+                .map(|(row, &p)| (row, kept[p].clone()))
+                .collect::<Vec<_>>();
+            if !clipped.is_empty() { selected.overwrite_rows(&clipped, base)? }
+            map.insert(selected);
+        }
+
+        Ok(map)
+    }
+
+    /// Extract the sub-map covering the given units, which may come from any layer
+    /// (e.g. a list of counties, or the VTDs making up a metro region).
+    pub fn clip_to(&self, geo_ids: &[GeoId]) -> Result<Map> {
+        let mut targets = [const { Vec::new() }; GeoType::COUNT];
+        for geo_id in geo_ids {
+            let layer = self.layer(geo_id.ty())
+                .ok_or_else(|| anyhow!("[Map] Missing layer {:?}", geo_id.ty()))?;
+            ensure!(layer.index.contains_key(geo_id), "[Map] Unknown {:?} {}", geo_id.ty(), geo_id.id());
+            targets[geo_id.ty() as usize].push(geo_id);
+        }
+
+        self.subset(|geo_id, parents| GeoType::ALL.iter().any(|&ty| {
+            let candidate = match ty { GeoType::BOTTOM => Some(geo_id), ty => parents.get(ty) };
+            candidate.is_some_and(|candidate| targets[ty as usize].contains(&candidate))
+        }))
+    }
+
+    /// Extract the sub-map of base units whose centroid lies inside `boundary` (e.g. a city limit),
+    /// so wards can be drawn from a statewide pack. Units straddling the boundary are kept or
+    /// dropped whole, by their centroid.
+    pub fn clip_to_boundary(&self, boundary: &MultiPolygon<f64>) -> Result<Map> {
+        self.require_geometry("[Map.clip_to_boundary]")?;
+        let base = self.base()?;
+        let inside = (0..base.len())
+            .map(|u| boundary.contains(&base.region.centroid(UnitId(u as u32))))
+            .collect::<Vec<_>>();
+        self.subset(|geo_id, _| base.index.get(geo_id).is_some_and(|&u| inside[u as usize]))
+    }
+
+    /// [`Map::clip_to_boundary`] with the boundary read from a GeoJSON file holding a Polygon,
+    /// MultiPolygon, Feature or FeatureCollection.
+    pub fn clip_to_geojson(&self, path: &Path) -> Result<Map> {
+        let file = std::fs::File::open(path)
+            .map_err(|e| anyhow!("[Map.clip_to_geojson] Failed to open {}: {e}", path.display()))?;
+        let value = serde_json::from_reader(std::io::BufReader::new(file))?;
+        self.clip_to_boundary(&super::io::geojson_to_multipolygon(&value)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_maps;
+
+    fn pops(map: &Map, ty: GeoType) -> Vec<f64> {
+        map.layer(ty).unwrap().data().column("pop").unwrap().f64().unwrap().into_no_null_iter().collect()
+    }
+
+    #[test]
+    fn subsets_clip_parents_to_kept_units() {
+        // Keep the first two columns of a 4x4 grid.
+        let map = test_maps::grid(4);
+        let base = map.base().unwrap();
+        let kept = base.geo_ids()[..8].to_vec();
+        let subset = map.subset(|geo_id, _| kept.contains(geo_id)).unwrap();
+
+        let blocks = subset.base().unwrap();
+        assert_eq!(blocks.geo_ids(), &kept);
+        assert!(blocks.parents().iter().all(|refs| refs.get(GeoType::TOP).is_some()));
+        assert_eq!(blocks.get_unit_graph().edges(0).count(), 2);
+        assert_eq!(pops(&subset, GeoType::TOP), vec![8.0]);
+
+        assert!(map.subset(|_, _| false).is_err());
+    }
+
+    #[test]
+    fn clips_select_units_by_id_or_boundary() {
+        let map = test_maps::grid(4);
+        let base = map.base().unwrap();
+        let clipped = map.clip_to(&base.geo_ids()[..3]).unwrap();
+        assert_eq!(clipped.base().unwrap().len(), 3);
+        assert_eq!(pops(&clipped, GeoType::TOP), vec![3.0]);
+
+        // Whole-map clips by the region keep everything; boundaries keep units by their centroid.
+        assert_eq!(map.clip_to(&map.layer(GeoType::TOP).unwrap().geo_ids().clone()).unwrap().base().unwrap().len(), 16);
+        assert_eq!(map.clip_to_boundary(&test_maps::rect(0.0, 1.6, 0.0, 4.0)).unwrap().base().unwrap().len(), 8);
+        assert!(map.clip_to(&[GeoId::new(GeoType::County, "99999")]).is_err());
+    }
+}
@@ -0,0 +1,196 @@
+use std::{collections::{BTreeSet, HashMap}, path::Path};
+
+use anyhow::{Context, Result, anyhow, bail, ensure};
+use geo::{BoundingRect, Contains, InteriorPoint, MultiPolygon};
+use geograph::UnitId;
+
+use crate::map::{GeoId, GeoType, Map, PackFormat};
+
+/// A new boundary for one unit of a layer above the base, e.g. a county or VTD whose limits moved
+/// after an annexation reported to the Census Boundary and Annexation Survey (BAS).
+#[derive(Clone, Debug)]
+pub struct BoundaryChange {
+    pub geo_id: GeoId,
+    pub geometry: MultiPolygon<f64>,
+}
+
+impl BoundaryChange {
+    /// Read boundary changes for units of layer `ty` from a GeoJSON FeatureCollection whose
+    /// features carry the unit's GEOID in a `geo_id` (or `GEOID`) property.
+    pub fn read_geojson(path: &Path, ty: GeoType) -> Result<Vec<Self>> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("[BoundaryChange] Failed to read {}", path.display()))?;
+        let value = serde_json::from_str::<serde_json::Value>(&text)?;
+        let features = value.get("features").and_then(|features| features.as_array())
+            .ok_or_else(|| anyhow!("[BoundaryChange] Expected a GeoJSON FeatureCollection in {}", path.display()))?;
+
+        features.iter().map(|feature| {
+            let properties = feature.get("properties");
+            let id = ["geo_id", "GEOID"].iter()
+                .find_map(|key| properties?.get(key)?.as_str())
+                .ok_or_else(|| anyhow!("[BoundaryChange] Feature without a geo_id property in {}", path.display()))?;
+            ensure!(id.len() == ty.id_len(), "[BoundaryChange] Invalid {:?} GEOID {id:?}", ty);
+            Ok(Self { geo_id: GeoId::new(ty, id), geometry: super::io::geojson_to_multipolygon(feature)? })
+        }).collect()
+    }
+}
+
+impl Map {
+    /// Apply boundary changes to units above the base layer, updating the map in place instead of
+    /// rebuilding its pack. Returns the number of base units that changed parent.
+    ///
+    /// Base units whose interior point lies inside a changed unit's new boundary move into it.
+    /// Only the changed units, and the units they take base units from, are recomputed: their
+    /// geometry becomes the union of their base units and their numeric data the sum over them.
+    /// Every other unit keeps its data, and other layers are untouched. The changed layer's
+    /// adjacency is rebuilt from the updated geometry, keeping forced adjacencies.
+    ///
+    /// Fails if a base unit of a changed unit falls outside its new boundary and inside no other
+    /// changed unit (boundaries must be given for both sides of a transfer between them), or if a
+    /// unit would be left empty. Parent references in intermediate layers are not reassigned.
+    pub fn apply_boundary_changes(&mut self, changes: &[BoundaryChange]) -> Result<usize> {
+        self.require_geometry("[Map.apply_boundary_changes]")?;
+        for change in changes {
+            let ty = change.geo_id.ty();
+            ensure!(ty != GeoType::BOTTOM, "[Map.apply_boundary_changes] Base units cannot change boundary; change their parents instead");
+            let layer = self.layer(ty).ok_or_else(|| anyhow!("[Map.apply_boundary_changes] Missing layer {:?}", ty))?;
+            ensure!(layer.index.contains_key(&change.geo_id), "[Map.apply_boundary_changes] Unknown {:?} {}", ty, change.geo_id.id());
+            ensure!(changes.iter().filter(|other| other.geo_id == change.geo_id).count() == 1,
+                "[Map.apply_boundary_changes] Duplicate change for {:?} {}", ty, change.geo_id.id());
+        }
+
+        let mut moved = 0;
+        for ty in GeoType::ALL {
+            let changes = changes.iter().filter(|change| change.geo_id.ty() == ty).collect::<Vec<_>>();
+            if !changes.is_empty() { moved += self.apply_layer_changes(ty, &changes)? }
+        }
+        Ok(moved)
+    }
+
+    /// Apply the boundary changes of a single layer (see [`Map::apply_boundary_changes`]).
+    fn apply_layer_changes(&mut self, ty: GeoType, changes: &[&BoundaryChange]) -> Result<usize> {
+        let base = self.base()?;
+        let layer = self.layer(ty).expect("layer checked by caller");
+        let parents = base.parent_indices(layer);
+        let changed = changes.iter().map(|change| layer.index[&change.geo_id] as usize).collect::<Vec<_>>();
+
+        // Candidates: base units of changed units, and base units near their new boundaries.
+        let mut candidates = (0..base.len()).filter(|&u| parents[u].is_some_and(|p| changed.contains(&p))).collect::<BTreeSet<_>>();
+        for change in changes {
+            let Some(bounds) = change.geometry.bounding_rect() else { continue };
+            candidates.extend(base.region.units_in_envelope(bounds).into_iter().map(|UnitId(u)| u as usize));
+        }
+
+        let mut transfers = Vec::new();
+        for u in candidates {
+            let point = base.region.geometry(UnitId(u as u32)).interior_point();
+            let located = changes.iter().zip(&changed)
+                .find(|(change, _)| point.is_some_and(|point| change.geometry.contains(&point)))
+                .map(|(_, &p)| p);
+            match (located, parents[u]) {
+                (Some(p), current) if current != Some(p) => transfers.push((u, current, p)),
+                (None, Some(current)) if changed.contains(&current) => bail!(
+                    "[Map.apply_boundary_changes] {:?} {} falls outside the new boundary of {:?} {} and inside no other changed unit",
+                    GeoType::BOTTOM, base.geo_ids[u].id(), ty, layer.geo_ids[current].id()),
+                _ => (),
+            }
+        }
+        if transfers.is_empty() { return Ok(0) }
+
+        let mut members = HashMap::<usize, Vec<usize>>::new();
+        let affected = transfers.iter().flat_map(|&(_, from, to)| from.into_iter().chain([to])).collect::<BTreeSet<_>>();
+        let mut parents = parents;
+        for &(u, _, to) in &transfers { parents[u] = Some(to) }
+        for (u, parent) in parents.iter().enumerate() {
+            if let Some(p) = parent.filter(|p| affected.contains(p)) { members.entry(p).or_default().push(u) }
+        }
+        if let Some(&empty) = affected.iter().find(|p| !members.contains_key(p)) {
+            bail!("[Map.apply_boundary_changes] {:?} {} would be left with no {:?} units", ty, layer.geo_ids[empty].id(), GeoType::BOTTOM);
+        }
+
+        let geometries = (0..layer.len())
+            .map(|p| match members.get(&p) {
+                Some(units) => base.region.union_of(units.iter().map(|&u| UnitId(u as u32))),
+                None => layer.region.geometry(UnitId(p as u32)).into_owned(),
+            })
+            .collect();
+        let mut updated = layer.select(&(0..layer.len()).collect::<Vec<_>>(), geometries)?;
+        updated.overwrite_rows(&members.into_iter().collect::<Vec<_>>(), base)?;
+
+        let parent_ids = transfers.iter().map(|&(u, _, to)| (u, layer.geo_ids[to].clone())).collect::<Vec<_>>();
+        self.insert(updated);
+        let base = self.layer_mut(GeoType::BOTTOM).expect("base layer checked above");
+        for (u, geo_id) in parent_ids {
+            base.parents[u].set(ty, Some(geo_id));
+        }
+        Ok(transfers.len())
+    }
+
+    /// Apply boundary changes to the pack at `path` and write it back in `format`, without
+    /// rebuilding it from source files. Returns the number of base units that changed parent.
+    pub fn update_pack(path: &Path, changes: &[BoundaryChange], format: PackFormat) -> Result<usize> {
+        let mut map = Map::read_from_pack(path)?;
+        let moved = map.apply_boundary_changes(changes)?;
+        map.write_to_pack_with_format(path, format)?;
+        Ok(moved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use geograph::Region;
+    use polars::prelude::{Column, DataFrame};
+
+    use super::*;
+    use crate::test_maps;
+    use crate::{graph::WeightMatrix, map::{MapLayer, ParentRefs}};
+
+    /// A 4x4 grid of blocks (columns of four) split into two counties of two columns each.
+    fn two_county_map() -> Map {
+        let mut map = Map::from_parts(test_maps::grid_geoms(4, 1.0), None, vec![("pop".into(), (0..16).map(f64::from).collect())], &[]).unwrap();
+
+        let ids = ["00001", "00002"];
+        let geo_ids = ids.iter().map(|id| GeoId::new(GeoType::County, id)).collect::<Vec<_>>();
+        let pops = [(0..8).sum::<i32>() as f64, (8..16).sum::<i32>() as f64];
+        let data = DataFrame::new(vec![Column::new("geo_id".into(), ids), Column::new("pop".into(), pops)]).unwrap()
+            .with_row_index("idx".into(), None).unwrap();
+        let region = Region::new(vec![test_maps::rect(0.0, 2.0, 0.0, 4.0), test_maps::rect(2.0, 4.0, 0.0, 4.0)], None).unwrap();
+        map.insert(MapLayer::new(
+            GeoType::County,
+            geo_ids.clone(),
+            geo_ids.iter().enumerate().map(|(i, geo_id)| (geo_id.clone(), i as u32)).collect(),
+            vec![ParentRefs::default(); 2],
+            data.clone(),
+            Arc::new(WeightMatrix::from_dataframe(&data)),
+            Arc::new(region),
+        ));
+        let base = map.layer_mut(GeoType::BOTTOM).unwrap();
+        for (u, refs) in base.parents.iter_mut().enumerate() {
+            refs.set(GeoType::County, Some(geo_ids[u / 8].clone()));
+        }
+        map
+    }
+
+    #[test]
+    fn annexation_moves_blocks_and_updates_totals() {
+        let mut map = two_county_map();
+        // County 1 annexes the third column (blocks 8-11) from county 2.
+        let annexed = [
+            BoundaryChange { geo_id: GeoId::new(GeoType::County, "00001"), geometry: test_maps::rect(0.0, 3.0, 0.0, 4.0) },
+            BoundaryChange { geo_id: GeoId::new(GeoType::County, "00002"), geometry: test_maps::rect(3.0, 4.0, 0.0, 4.0) },
+        ];
+        assert_eq!(map.apply_boundary_changes(&annexed).unwrap(), 4);
+
+        let county = map.layer(GeoType::County).unwrap();
+        assert_eq!(county.unit_weights.get_as_f64("pop", 0), Some((0..12).sum::<i32>() as f64));
+        assert_eq!(county.unit_weights.get_as_f64("pop", 1), Some((12..16).sum::<i32>() as f64));
+        assert!((county.region.area(UnitId(0)) / county.region.area(UnitId(1)) - 3.0).abs() < 1e-6);
+        assert!(map.check_integrity(&["pop"]).is_empty());
+
+        // Shrinking county 2 alone would strand its remaining blocks.
+        let stranded = [BoundaryChange { geo_id: GeoId::new(GeoType::County, "00002"), geometry: test_maps::rect(3.8, 4.0, 0.0, 4.0) }];
+        assert!(map.apply_boundary_changes(&stranded).is_err());
+    }
+}
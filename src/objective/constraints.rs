@@ -0,0 +1,90 @@
+//! Hard constraints on plans, as opposed to the soft goals of an `Objective`.
+//!
+//! An objective only makes bad plans score worse; a constraint rules them out.
+//! Optimization runs check every proposed move against their constraints
+//! right after it is proposed, and reject moves that break one without
+//! scoring them, which is far cheaper than recomputing the objective.
+
+use serde::{Deserialize, Serialize};
+
+use crate::partition::BalanceBound;
+
+/// Units of one layer (e.g. counties or municipalities) that must not be split between districts.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PreservedUnits {
+    pub layer: String,        // Layer of the units, e.g. "county"
+    pub geo_ids: Vec<String>, // GEOIDs of the units to keep whole
+}
+
+/// Hard constraints every plan visited by an optimization run must satisfy:
+///
+/// - `balance`: bounds on district totals, e.g. the maximum population deviation. As for any
+///   [`BalanceBound`], a district already outside one may still move towards it.
+/// - `contiguous`: every district is contiguous. Moves never break contiguity, so this only
+///   requires the starting plan to be contiguous.
+/// - `preserve`: units kept whole. A move must carry a preserved unit whole or leave it alone, so
+///   preserved units only change districts through chunk proposals at their layer.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Constraints {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub balance: Vec<BalanceBound>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub contiguous: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub preserve: Vec<PreservedUnits>,
+}
+
+impl Constraints {
+    /// No constraints.
+    pub fn new() -> Self { Self::default() }
+
+    /// Every district within `max_deviation` (e.g. 0.01 = ±1%) of the ideal total of `pop_series`.
+    pub fn max_population_deviation(mut self, pop_series: &str, max_deviation: f64) -> Self {
+        self.balance.push(BalanceBound::within(pop_series.into(), max_deviation));
+        self
+    }
+
+    /// Add a bound on the district totals of a series.
+    pub fn balance(mut self, bound: BalanceBound) -> Self {
+        self.balance.push(bound);
+        self
+    }
+
+    /// Require every district to be contiguous.
+    pub fn contiguous(mut self) -> Self {
+        self.contiguous = true;
+        self
+    }
+
+    /// Keep the units of `layer` with the given GEOIDs whole.
+    pub fn preserve(mut self, layer: &str, geo_ids: &[&str]) -> Self {
+        self.preserve.push(PreservedUnits { layer: layer.into(), geo_ids: geo_ids.iter().map(|id| id.to_string()).collect() });
+        self
+    }
+
+    /// Whether no constraint is set.
+    pub fn is_empty(&self) -> bool {
+        self.balance.is_empty() && !self.contiguous && self.preserve.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constraints_round_trip_through_json() {
+        let constraints = Constraints::new()
+            .max_population_deviation("T_20_CENS_Total", 0.005)
+            .contiguous()
+            .preserve("county", &["17031", "17043"]);
+        let json = serde_json::to_string(&constraints).unwrap();
+        let parsed: Constraints = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.balance[0].max, Some(0.005));
+        assert!(parsed.contiguous);
+        assert_eq!(parsed.preserve, constraints.preserve);
+
+        let empty: Constraints = serde_json::from_str("{}").unwrap();
+        assert!(empty.is_empty());
+    }
+}
@@ -1,6 +1,10 @@
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
 use crate::partition::Partition;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub(crate) enum MetricKind {
     // Demographic metrics:
     PopulationDeviation { pop_series: String },
@@ -9,8 +13,22 @@ pub(crate) enum MetricKind {
     PopulationDeviationSharp { pop_series: String },
 
     // Geometric metrics:
-    CompactnessPolsbyPopper,
-    CompactnessSchwartzberg,
+    CompactnessPolsbyPopper {
+        #[serde(default, skip_serializing_if = "Aggregation::is_mean")]
+        aggregate: Aggregation,
+    },
+    CompactnessSchwartzberg {
+        #[serde(default, skip_serializing_if = "Aggregation::is_mean")]
+        aggregate: Aggregation,
+    },
+    CompactnessReock {
+        #[serde(default, skip_serializing_if = "Aggregation::is_mean")]
+        aggregate: Aggregation,
+    },
+    CompactnessConvexHull {
+        #[serde(default, skip_serializing_if = "Aggregation::is_mean")]
+        aggregate: Aggregation,
+    },
 
     // Electoral metrics:
     CompetitivenessBinary { dem_series: String, rep_series: String, threshold: f64 },
@@ -19,9 +37,32 @@ pub(crate) enum MetricKind {
     Proportionality { dem_series: String, rep_series: String },
 }
 
+/// How a metric's per-district scores are combined into one score.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Aggregation {
+    #[default]
+    Mean,
+    Min, // The worst district, e.g. to avoid a single sprawling district
+}
+
+impl Aggregation {
+    fn is_mean(&self) -> bool { *self == Aggregation::Mean }
+
+    /// Combine per-district scores (0 if there are none).
+    fn apply(&self, values: &[f64]) -> f64 {
+        if values.is_empty() { return 0.0 }
+        match self {
+            Aggregation::Mean => values.iter().sum::<f64>() / values.len() as f64,
+            Aggregation::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+        }
+    }
+}
+
 /// A single metric specification used in a multi-objective optimization.
 /// This does *not* carry a weight; weights live in `Objective`.
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct Metric {
     kind: MetricKind,
 }
@@ -51,12 +92,49 @@ impl Metric {
 
     /// Polsby–Popper compactness metric.
     pub fn compactness_polsby_popper() -> Self {
-        Self { kind: MetricKind::CompactnessPolsbyPopper }
+        Self { kind: MetricKind::CompactnessPolsbyPopper { aggregate: Aggregation::Mean } }
     }
 
     /// Schwartzberg compactness metric.
     pub fn compactness_schwartzberg() -> Self {
-        Self { kind: MetricKind::CompactnessSchwartzberg }
+        Self { kind: MetricKind::CompactnessSchwartzberg { aggregate: Aggregation::Mean } }
+    }
+
+    /// Reock compactness metric: district area over the area of its smallest enclosing circle.
+    /// Requires unit geometry.
+    pub fn compactness_reock() -> Self {
+        Self { kind: MetricKind::CompactnessReock { aggregate: Aggregation::Mean } }
+    }
+
+    /// Convex hull compactness metric: district area over the area of its convex hull.
+    /// Requires unit geometry.
+    pub fn compactness_convex_hull() -> Self {
+        Self { kind: MetricKind::CompactnessConvexHull { aggregate: Aggregation::Mean } }
+    }
+
+    /// Combine per-district scores with `aggregation` instead of the mean. Only compactness
+    /// metrics support other aggregations.
+    pub fn with_aggregation(mut self, aggregation: Aggregation) -> Result<Self> {
+        match &mut self.kind {
+            MetricKind::CompactnessPolsbyPopper { aggregate }
+            | MetricKind::CompactnessSchwartzberg { aggregate }
+            | MetricKind::CompactnessReock { aggregate }
+            | MetricKind::CompactnessConvexHull { aggregate } => *aggregate = aggregation,
+            _ if aggregation == Aggregation::Mean => {}
+            _ => bail!("[Metric.with_aggregation] {} only supports the mean", self.short_name()),
+        }
+        Ok(self)
+    }
+
+    /// How per-district scores are combined into one score.
+    pub fn aggregation(&self) -> Aggregation {
+        match &self.kind {
+            MetricKind::CompactnessPolsbyPopper { aggregate }
+            | MetricKind::CompactnessSchwartzberg { aggregate }
+            | MetricKind::CompactnessReock { aggregate }
+            | MetricKind::CompactnessConvexHull { aggregate } => *aggregate,
+            _ => Aggregation::Mean,
+        }
     }
 
     /// Competitiveness metric based on district-level vote shares (binary).
@@ -86,8 +164,10 @@ impl Metric {
             MetricKind::PopulationDeviationAbsolute { .. } => "PopulationDeviationAbsolute",
             MetricKind::PopulationDeviationSmooth { .. } => "PopulationDeviationSmooth",
             MetricKind::PopulationDeviationSharp { .. } => "PopulationDeviationSharp",
-            MetricKind::CompactnessPolsbyPopper => "CompactnessPolsbyPopper",
-            MetricKind::CompactnessSchwartzberg => "CompactnessSchwartzberg",
+            MetricKind::CompactnessPolsbyPopper { .. } => "CompactnessPolsbyPopper",
+            MetricKind::CompactnessSchwartzberg { .. } => "CompactnessSchwartzberg",
+            MetricKind::CompactnessReock { .. } => "CompactnessReock",
+            MetricKind::CompactnessConvexHull { .. } => "CompactnessConvexHull",
             MetricKind::CompetitivenessBinary { .. } => "CompetitivenessBinary",
             MetricKind::CompetitivenessQuadratic { .. } => "CompetitivenessQuadratic",
             MetricKind::CompetitivenessGaussian { .. } => "CompetitivenessGaussian",
@@ -111,12 +191,18 @@ impl Metric {
             MetricKind::PopulationDeviationSharp { pop_series } => {
                 districts.map(|part| partition.sharp_population_deviation(part, pop_series)).collect()
             }
-            MetricKind::CompactnessPolsbyPopper => {
+            MetricKind::CompactnessPolsbyPopper { .. } => {
                 districts.map(|part| partition.polsby_pobber(part)).collect()
             }
-            MetricKind::CompactnessSchwartzberg => {
+            MetricKind::CompactnessSchwartzberg { .. } => {
                 districts.map(|part| partition.schwartzberg(part)).collect()
             }
+            MetricKind::CompactnessReock { .. } => {
+                districts.map(|part| partition.reock(part)).collect()
+            }
+            MetricKind::CompactnessConvexHull { .. } => {
+                districts.map(|part| partition.convex_hull(part)).collect()
+            }
             MetricKind::CompetitivenessBinary { dem_series, rep_series, threshold } => {
                 districts.map(|part| partition.binary_competitiveness(part, dem_series, rep_series, *threshold)).collect()
             }
@@ -131,11 +217,10 @@ impl Metric {
         }
     }
 
-    /// Compute the overall score for this metric by aggregating per-district scores.
-    /// Currently uses average for all metrics, but can be customized per metric type in the future.
+    /// Compute the overall score for this metric by aggregating per-district scores
+    /// (see [`Metric::aggregation`]).
     pub(crate) fn compute_score(&self, partition: &Partition) -> f64 {
-        let values = self.compute(partition);
-        if values.is_empty() { 0.0 } else { values.iter().sum::<f64>() / values.len() as f64 }
+        self.aggregation().apply(&self.compute(partition))
     }
 }
 
@@ -152,10 +237,22 @@ impl fmt::Display for MetricKind {
                 write!(f, "PopulationDeviationSmooth(series='{}')", pop_series),
             MetricKind::PopulationDeviationSharp { pop_series } =>
                 write!(f, "PopulationDeviationSharp(series='{}')", pop_series),
-            MetricKind::CompactnessPolsbyPopper =>
+            MetricKind::CompactnessPolsbyPopper { aggregate: Aggregation::Mean } =>
                 write!(f, "CompactnessPolsbyPopper"),
-            MetricKind::CompactnessSchwartzberg =>
+            MetricKind::CompactnessPolsbyPopper { aggregate } =>
+                write!(f, "CompactnessPolsbyPopper(aggregate={:?})", aggregate),
+            MetricKind::CompactnessSchwartzberg { aggregate: Aggregation::Mean } =>
                 write!(f, "CompactnessSchwartzberg"),
+            MetricKind::CompactnessSchwartzberg { aggregate } =>
+                write!(f, "CompactnessSchwartzberg(aggregate={:?})", aggregate),
+            MetricKind::CompactnessReock { aggregate: Aggregation::Mean } =>
+                write!(f, "CompactnessReock"),
+            MetricKind::CompactnessReock { aggregate } =>
+                write!(f, "CompactnessReock(aggregate={:?})", aggregate),
+            MetricKind::CompactnessConvexHull { aggregate: Aggregation::Mean } =>
+                write!(f, "CompactnessConvexHull"),
+            MetricKind::CompactnessConvexHull { aggregate } =>
+                write!(f, "CompactnessConvexHull(aggregate={:?})", aggregate),
             MetricKind::CompetitivenessBinary { dem_series, rep_series, threshold } =>
                 write!(f, "CompetitivenessBinary(dem_series='{}', rep_series='{}', threshold={})",
                     dem_series, rep_series, threshold),
@@ -184,3 +281,36 @@ impl fmt::Debug for Metric {
         fmt::Display::fmt(self, f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metrics_round_trip_through_json() {
+        let metrics = [
+            Metric::population_deviation("pop".into()),
+            Metric::population_deviation_absolute("pop".into()),
+            Metric::population_deviation_smooth("pop".into()),
+            Metric::population_deviation_sharp("pop".into()),
+            Metric::compactness_polsby_popper(),
+            Metric::compactness_schwartzberg().with_aggregation(Aggregation::Min).unwrap(),
+            Metric::compactness_reock(),
+            Metric::compactness_convex_hull(),
+            Metric::competitiveness_binary("dem".into(), "rep".into(), 0.05),
+            Metric::competitiveness_quadratic("dem".into(), "rep".into(), 0.1),
+            Metric::competitiveness_gaussian("dem".into(), "rep".into(), 0.02),
+            Metric::proportionality("dem".into(), "rep".into()),
+        ];
+        for metric in &metrics {
+            let json = serde_json::to_string(metric).unwrap();
+            let parsed: Metric = serde_json::from_str(&json).unwrap();
+            assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+            assert_eq!(parsed.aggregation(), metric.aggregation());
+        }
+
+        // Mean aggregation is the default and is left out of the JSON.
+        assert_eq!(serde_json::to_string(&metrics[4]).unwrap(), r#"{"type":"compactness_polsby_popper"}"#);
+        assert!(serde_json::from_str::<Metric>(r#"{"type":"population_deviation"}"#).is_err());
+    }
+}
@@ -1,5 +1,12 @@
+mod constraints;
 mod metric;
 mod objective;
+mod registry;
+mod relaxation;
 
-pub use metric::Metric;
-pub use objective::Objective;
+pub use constraints::{Constraints, PreservedUnits};
+pub use metric::{Aggregation, Metric};
+pub use objective::{Composition, DEFAULT_RESOLUTION, Objective};
+pub use registry::{METRICS, MetricInfo, metric_info};
+pub use relaxation::RelaxedConstraint;
+pub(crate) use relaxation::ConstraintKind;
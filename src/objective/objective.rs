@@ -1,13 +1,59 @@
 //! Multi-objective scalarization: a set of metrics plus a set of weights.
 //!
 //! This is the “objective” as seen by search algorithms such as Tabu
-//! search or simulated annealing. It is deliberately simple: a weighted
-//! sum of metric values. More advanced schemes (lexicographic ordering,
-//! epsilon-constraints, etc.) can be layered on top later.
+//! search or simulated annealing. Metric values are combined either as a
+//! weighted mean or lexicographically, by priority; either way the result
+//! is a single score, so every search algorithm handles both.
+
+use serde::{Deserialize, Serialize};
 
 use crate::objective::Metric;
 use crate::partition::Partition;
 
+/// Smallest gain in a higher-priority tier that outweighs any change in the tiers below it, by default.
+pub const DEFAULT_RESOLUTION: f64 = 1e-3;
+
+/// How an objective combines the scores of its metrics.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Composition {
+    /// Weighted mean of the metric scores.
+    #[default]
+    Weighted,
+    /// Metrics grouped into tiers by priority (lower first), each scored by the weighted mean of its
+    /// metrics; a plan is better if it scores higher in the first tier where two plans differ. The
+    /// tiers are folded into one score, each worth `resolution` of the one above, so for metrics
+    /// scored in [0, 1] a gain of more than about `resolution` in a tier outweighs any change below it.
+    Lexicographic {
+        priorities: Vec<u32>,
+        #[serde(default = "default_resolution")]
+        resolution: f64,
+    },
+}
+
+fn default_resolution() -> f64 { DEFAULT_RESOLUTION }
+
+impl Composition {
+    /// Lexicographic order of metrics by the given priorities, with the default resolution.
+    pub fn lexicographic(priorities: Vec<u32>) -> Self {
+        Composition::Lexicographic { priorities, resolution: DEFAULT_RESOLUTION }
+    }
+
+    fn is_weighted(&self) -> bool { *self == Composition::Weighted }
+
+    /// Check that this composition fits an objective of `num_metrics` metrics.
+    pub fn validate(&self, num_metrics: usize) -> Result<(), String> {
+        let Composition::Lexicographic { priorities, resolution } = self else { return Ok(()) };
+        if priorities.len() != num_metrics {
+            return Err(format!("priorities length ({}) must match metrics length ({num_metrics})", priorities.len()))
+        }
+        if !(*resolution > 0.0 && *resolution < 1.0) {
+            return Err(format!("resolution ({resolution}) must be between 0 and 1"))
+        }
+        Ok(())
+    }
+}
+
 /// A multi-objective scalarization: metrics + corresponding weights.
 ///
 /// In the simplest form, this represents:
@@ -16,12 +62,39 @@ use crate::partition::Partition;
 /// total_cost = sum_i weights[i] * metric_i(plan)
 /// ```
 ///
+/// or, with a lexicographic [`Composition`], an ordering of metrics by priority.
+///
 /// The actual metric implementations are handled elsewhere; this type
 /// just stores configuration and provides evaluation entry-points.
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(try_from = "ObjectiveSpec")]
 pub struct Objective {
     metrics: Vec<Metric>,
     weights: Vec<f64>,
+    #[serde(skip_serializing_if = "Composition::is_weighted")]
+    composition: Composition,
+}
+
+/// Serialized form of an `Objective`, where weights and composition may be omitted.
+#[derive(Deserialize)]
+struct ObjectiveSpec {
+    metrics: Vec<Metric>,
+    #[serde(default)]
+    weights: Option<Vec<f64>>,
+    #[serde(default)]
+    composition: Composition,
+}
+
+impl TryFrom<ObjectiveSpec> for Objective {
+    type Error = String;
+
+    fn try_from(spec: ObjectiveSpec) -> Result<Self, Self::Error> {
+        if let Some(weights) = &spec.weights && weights.len() != spec.metrics.len() {
+            return Err(format!("weights length ({}) must match metrics length ({})", weights.len(), spec.metrics.len()));
+        }
+        spec.composition.validate(spec.metrics.len())?;
+        Ok(Self::new(spec.metrics, spec.weights).with_composition(spec.composition))
+    }
 }
 
 impl Objective {
@@ -42,9 +115,27 @@ impl Objective {
             metrics.len(),
         );
 
-        Self { metrics, weights }
+        Self { metrics, weights, composition: Composition::Weighted }
+    }
+
+    /// Rank `metrics` lexicographically in the given order: each metric matters only when every
+    /// metric before it ties (see [`Composition::Lexicographic`]).
+    pub fn lexicographic(metrics: Vec<Metric>) -> Self {
+        let priorities = (0..metrics.len() as u32).collect();
+        Self::new(metrics, None).with_composition(Composition::lexicographic(priorities))
+    }
+
+    /// Combine the metric scores as `composition` describes. Lexicographic priorities must have
+    /// one entry per metric, and the resolution must lie strictly between 0 and 1.
+    pub fn with_composition(mut self, composition: Composition) -> Self {
+        if let Err(e) = composition.validate(self.metrics.len()) { panic!("Objective::with_composition: {e}") }
+        self.composition = composition;
+        self
     }
 
+    /// How the metric scores are combined.
+    #[inline] pub fn composition(&self) -> &Composition { &self.composition }
+
     /// Number of metric terms in this objective.
     #[inline] pub fn num_metrics(&self) -> usize { self.metrics.len() }
 
@@ -62,35 +153,66 @@ impl Objective {
         self.weights = weights;
     }
 
-    /// Internal accessor for metrics.
-    pub(crate) fn metrics(&self) -> &[Metric] { &self.metrics }
+    /// Serialize this objective to a JSON string.
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Parse an objective from a JSON string produced by [`Objective::to_json`].
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Accessor for the metric terms.
+    #[inline] pub fn metrics(&self) -> &[Metric] { &self.metrics }
 
-    /// Evaluate this objective for a given partition.
-    /// Returns the weighted average of metric scores.
+    /// Evaluate this objective for a given partition: the weighted average of metric scores, or
+    /// for a lexicographic composition the tiers' weighted averages folded by priority.
     pub(crate) fn compute(&self, partition: &Partition) -> f64 {
-        let mut weighted_sum = 0.0;
-        let total_weight: f64 = self.weights.iter().sum();
+        let scores = self.metrics.iter().map(|metric| metric.compute_score(partition)).collect::<Vec<_>>();
+        self.combine(&scores)
+    }
 
-        for (metric, &weight) in self.metrics.iter().zip(&self.weights) {
-            let score = metric.compute_score(partition);
-            weighted_sum += weight * score;
-        }
+    /// Combine one score per metric as described by the composition.
+    fn combine(&self, scores: &[f64]) -> f64 {
+        let Composition::Lexicographic { priorities, resolution } = &self.composition else {
+            return weighted_mean(scores.iter().zip(&self.weights));
+        };
 
-        if total_weight > 0.0 {
-            weighted_sum / total_weight
-        } else {
-            0.0
+        let mut tiers = priorities.clone();
+        tiers.sort_unstable();
+        tiers.dedup();
+        let (mut total, mut scale, mut norm) = (0.0, 1.0, 0.0);
+        for tier in tiers {
+            let members = scores.iter().zip(&self.weights).zip(priorities).filter(|&(_, &p)| p == tier).map(|(term, _)| term);
+            total += scale * weighted_mean(members);
+            norm += scale;
+            scale *= resolution;
         }
+        if norm > 0.0 { total / norm } else { 0.0 }
     }
 }
 
+/// Weighted mean of (score, weight) pairs, or 0 if the weights sum to 0.
+fn weighted_mean<'a>(terms: impl Iterator<Item = (&'a f64, &'a f64)>) -> f64 {
+    let (sum, total_weight) = terms.fold((0.0, 0.0), |(sum, total), (score, weight)| (sum + weight * score, total + weight));
+    if total_weight > 0.0 { sum / total_weight } else { 0.0 }
+}
+
 use std::fmt;
 
 impl fmt::Display for Objective {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "Objective {{")?;
         for (i, (metric, weight)) in self.metrics.iter().zip(&self.weights).enumerate() {
-            writeln!(f, "  {:2}: {} * weight={}", i, metric, weight)?;
+            match &self.composition {
+                Composition::Weighted => writeln!(f, "  {:2}: {} * weight={}", i, metric, weight)?,
+                Composition::Lexicographic { priorities, .. } =>
+                    writeln!(f, "  {:2}: {} * weight={} priority={}", i, metric, weight, priorities[i])?,
+            }
+        }
+        if let Composition::Lexicographic { resolution, .. } = &self.composition {
+            writeln!(f, "  lexicographic, resolution={resolution}")?;
         }
         write!(f, "}}")
     }
@@ -101,3 +223,39 @@ impl fmt::Debug for Objective {
         fmt::Display::fmt(self, f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lexicographic_tiers_dominate_lower_tiers() {
+        let metrics = vec![Metric::population_deviation("pop".into()), Metric::compactness_polsby_popper(), Metric::compactness_reock()];
+        let objective = Objective::new(metrics.clone(), Some(vec![1.0, 3.0, 1.0]))
+            .with_composition(Composition::Lexicographic { priorities: vec![0, 1, 1], resolution: 0.01 });
+
+        // A small gain in the first tier beats the largest possible gain in the second.
+        assert!(objective.combine(&[0.52, 0.0, 0.0]) > objective.combine(&[0.5, 1.0, 1.0]));
+        // Within a tier, metrics are weighted.
+        assert!(objective.combine(&[0.5, 0.6, 0.2]) > objective.combine(&[0.5, 0.2, 0.6]));
+        assert!((Objective::new(metrics.clone(), Some(vec![3.0, 1.0, 0.0])).combine(&[1.0, 0.2, 0.7]) - 0.8).abs() < 1e-12);
+
+        let json = objective.to_json().unwrap();
+        assert!(json.contains(r#""composition":{"type":"lexicographic","priorities":[0,1,1],"resolution":0.01}"#));
+        assert_eq!(*Objective::from_json(&json).unwrap().composition(), *objective.composition());
+        assert!(!Objective::new(metrics.clone(), None).to_json().unwrap().contains("composition"));
+
+        let spec = r#"{"metrics":[{"type":"compactness_polsby_popper"}],"composition":{"type":"lexicographic","priorities":[0,1]}}"#;
+        assert!(Objective::from_json(spec).unwrap_err().to_string().contains("priorities length"));
+        assert_eq!(Objective::lexicographic(metrics).composition(), &Composition::lexicographic(vec![0, 1, 2]));
+    }
+
+    #[test]
+    fn objectives_round_trip_through_json() {
+        let objective = Objective::new(vec![Metric::population_deviation("pop".into()), Metric::compactness_reock()], Some(vec![2.0, 0.5]));
+        let json = objective.to_json().unwrap();
+        let parsed = Objective::from_json(&json).unwrap();
+        assert_eq!(parsed.to_json().unwrap(), json);
+        assert_eq!(parsed.combine(&[0.5, 0.2]), objective.combine(&[0.5, 0.2]));
+    }
+}
@@ -0,0 +1,107 @@
+/// Description of a metric that can be used in an objective.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MetricInfo {
+    pub name: &'static str,              // Type tag used in serialized objectives
+    pub params: &'static [&'static str], // Required parameters, in constructor order
+    pub description: &'static str,
+}
+
+/// Every metric available to objectives, in display order.
+pub const METRICS: &[MetricInfo] = &[
+    MetricInfo {
+        name: "population_deviation",
+        params: &["pop_series"],
+        description: "Population equality relative to the ideal district total",
+    },
+    MetricInfo {
+        name: "population_deviation_absolute",
+        params: &["pop_series"],
+        description: "Absolute population equality relative to the ideal district total",
+    },
+    MetricInfo {
+        name: "population_deviation_smooth",
+        params: &["pop_series"],
+        description: "Smooth population equality relative to the ideal district total",
+    },
+    MetricInfo {
+        name: "population_deviation_sharp",
+        params: &["pop_series"],
+        description: "Linear population equality: 1 at the ideal total, 0 when empty or at double",
+    },
+    MetricInfo {
+        name: "compactness_polsby_popper",
+        params: &[],
+        description: "Polsby-Popper compactness (area versus perimeter)",
+    },
+    MetricInfo {
+        name: "compactness_schwartzberg",
+        params: &[],
+        description: "Schwartzberg compactness (perimeter versus circle of equal area)",
+    },
+    MetricInfo {
+        name: "compactness_reock",
+        params: &[],
+        description: "Reock compactness (area versus smallest enclosing circle)",
+    },
+    MetricInfo {
+        name: "compactness_convex_hull",
+        params: &[],
+        description: "Convex hull compactness (area versus convex hull)",
+    },
+    MetricInfo {
+        name: "competitiveness_binary",
+        params: &["dem_series", "rep_series", "threshold"],
+        description: "Share of districts whose margin is within a threshold",
+    },
+    MetricInfo {
+        name: "competitiveness_quadratic",
+        params: &["dem_series", "rep_series", "threshold"],
+        description: "Piecewise quadratic score of each district's margin",
+    },
+    MetricInfo {
+        name: "competitiveness_gaussian",
+        params: &["dem_series", "rep_series", "sigma"],
+        description: "Gaussian score of each district's margin",
+    },
+    MetricInfo {
+        name: "proportionality",
+        params: &["dem_series", "rep_series"],
+        description: "Seats-votes proportionality (partisan fairness)",
+    },
+];
+
+/// Look up a metric by its type tag.
+pub fn metric_info(name: &str) -> Option<&'static MetricInfo> {
+    METRICS.iter().find(|info| info.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Metric;
+
+    #[test]
+    fn names_match_serialized_tags() {
+        let metrics = [
+            Metric::population_deviation("p".into()),
+            Metric::population_deviation_absolute("p".into()),
+            Metric::population_deviation_smooth("p".into()),
+            Metric::population_deviation_sharp("p".into()),
+            Metric::compactness_polsby_popper(),
+            Metric::compactness_schwartzberg(),
+            Metric::compactness_reock(),
+            Metric::compactness_convex_hull(),
+            Metric::competitiveness_binary("d".into(), "r".into(), 0.1),
+            Metric::competitiveness_quadratic("d".into(), "r".into(), 0.1),
+            Metric::competitiveness_gaussian("d".into(), "r".into(), 0.1),
+            Metric::proportionality("d".into(), "r".into()),
+        ];
+        assert_eq!(metrics.len(), METRICS.len());
+        for (metric, info) in metrics.iter().zip(METRICS) {
+            let value = serde_json::to_value(metric).unwrap();
+            assert_eq!(value["type"], info.name);
+            assert_eq!(value.as_object().unwrap().len(), info.params.len() + 1);
+            for param in info.params { assert!(value.get(param).is_some(), "{} missing {}", info.name, param) }
+        }
+    }
+}
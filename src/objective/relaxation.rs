@@ -0,0 +1,121 @@
+//! Hard constraints with a relaxation schedule ("big-M" annealing).
+//!
+//! A relaxed constraint starts with a loose bound (by default, whatever the
+//! seed plan currently achieves) and tightens linearly towards its target
+//! bound over the course of a run. Violations of the current bound are
+//! charged against the objective with a penalty weight that grows over the
+//! run, so a grossly infeasible seed plan is gradually pulled into the
+//! feasible region instead of being rejected outright.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum ConstraintKind {
+    /// Maximum absolute relative deviation from the ideal district total of a series.
+    PopulationDeviation { pop_series: String },
+    /// Maximum total deviation: the largest minus the smallest relative deviation from the ideal
+    /// district total of a series (the usual standard for local redistricting, e.g. 10%).
+    TotalDeviation { pop_series: String },
+    /// Maximum number of county splits (sum over counties of parts touched minus one).
+    CountySplits,
+}
+
+/// A hard constraint whose bound is relaxed at the start of a run and tightened over time.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RelaxedConstraint {
+    #[serde(flatten)]
+    pub(crate) kind: ConstraintKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) initial: Option<f64>,
+    pub(crate) target: f64,
+}
+
+impl RelaxedConstraint {
+    /// Every district must end within `max_deviation` (e.g. 0.01 = ±1%) of the ideal total of `pop_series`.
+    pub fn population_deviation(pop_series: String, max_deviation: f64) -> Self {
+        Self { kind: ConstraintKind::PopulationDeviation { pop_series }, initial: None, target: max_deviation }
+    }
+
+    /// The largest and smallest districts must end within `max_total_deviation` (e.g. 0.1 = 10%) of
+    /// each other, relative to the ideal total of `pop_series`.
+    pub fn total_deviation(pop_series: String, max_total_deviation: f64) -> Self {
+        Self { kind: ConstraintKind::TotalDeviation { pop_series }, initial: None, target: max_total_deviation }
+    }
+
+    /// The plan must end with at most `max_splits` county splits.
+    pub fn county_splits(max_splits: usize) -> Self {
+        Self { kind: ConstraintKind::CountySplits, initial: None, target: max_splits as f64 }
+    }
+
+    /// Start the schedule from an explicit bound instead of the seed plan's current value.
+    pub fn with_initial(mut self, initial: f64) -> Self {
+        self.initial = Some(initial);
+        self
+    }
+
+    /// Target bound reached at the end of the tightening schedule.
+    #[inline] pub fn target(&self) -> f64 { self.target }
+
+    /// Series whose district totals this constraint bounds, if any.
+    pub(crate) fn pop_series(&self) -> Option<&str> {
+        match &self.kind {
+            ConstraintKind::PopulationDeviation { pop_series } | ConstraintKind::TotalDeviation { pop_series } => Some(pop_series),
+            ConstraintKind::CountySplits => None,
+        }
+    }
+
+    /// Whether this constraint needs county labels for each unit.
+    #[inline] pub(crate) fn needs_counties(&self) -> bool { matches!(self.kind, ConstraintKind::CountySplits) }
+
+    /// Bound in effect at a given fraction `t` in [0, 1] of the tightening schedule.
+    pub(crate) fn bound_at(&self, initial: f64, t: f64) -> f64 {
+        let initial = initial.max(self.target);
+        initial + (self.target - initial) * t.clamp(0.0, 1.0)
+    }
+}
+
+impl fmt::Display for ConstraintKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self {
+            ConstraintKind::PopulationDeviation { pop_series } =>
+                write!(f, "PopulationDeviation(series='{}')", pop_series),
+            ConstraintKind::TotalDeviation { pop_series } =>
+                write!(f, "TotalDeviation(series='{}')", pop_series),
+            ConstraintKind::CountySplits =>
+                write!(f, "CountySplits"),
+        }
+    }
+}
+
+impl fmt::Display for RelaxedConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.initial {
+            Some(initial) => write!(f, "RelaxedConstraint({} <= {} -> {})", self.kind, initial, self.target),
+            None => write!(f, "RelaxedConstraint({} <= {})", self.kind, self.target),
+        }
+    }
+}
+
+impl fmt::Debug for RelaxedConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds_tighten_linearly_to_the_target() {
+        let constraint = RelaxedConstraint::population_deviation("pop".into(), 0.1);
+        let bounds = [0.0, 0.5, 1.0, 2.0].map(|t| constraint.bound_at(0.5, t));
+        assert!(bounds.iter().zip([0.5, 0.3, 0.1, 0.1]).all(|(bound, expected)| (bound - expected).abs() < 1e-12));
+
+        // A seed plan already within the target never loosens the bound.
+        assert_eq!(constraint.bound_at(0.05, 0.0), 0.1);
+    }
+}
@@ -2,6 +2,14 @@ use rand::Rng;
 
 use crate::{Objective, partition::Partition};
 
+use super::{
+    bounds::MoveConstraints,
+    observer::{StepObserver, StepReporter},
+    profile::{Profiler, RunProfile, Stage},
+    proposal::KernelMix,
+    stopping::{StopReason, Stopper, StoppingRules},
+};
+
 struct OptimizationParams {
     pub max_iter: usize,
     pub init_temp: f64,
@@ -9,6 +17,8 @@ struct OptimizationParams {
     pub early_stop_iters: usize,
     pub temp_search_batch_size: usize,
     pub batch_size: usize,
+    pub proposals: KernelMix,
+    pub constraints: MoveConstraints,
 }
 
 struct OptimizationState<Rng: rand::Rng> {
@@ -19,6 +29,27 @@ struct OptimizationState<Rng: rand::Rng> {
     pub best_assignments: Vec<u32>,
     pub best_iter: usize,
     pub temperature: f64,
+    pub phase: usize,
+    pub stopper: Stopper,
+    pub stopped: Option<StopReason>,   // Stopping rule that ended the current phase, if any
+    pub profiler: Profiler,
+}
+
+/// Progress of an annealing run, reported after each batch of moves.
+pub struct AnnealProgress<'a> {
+    pub phase: &'a str,                // "Temp Search" or "Phase N"
+    pub iter: usize,
+    pub score: f64,                    // Current objective value
+    pub best_score: f64,
+    pub temperature: f64,
+    pub accept_prob: f64,              // Average acceptance probability over the batch
+    pub metrics: Vec<(&'a str, f64)>,  // Score of each metric in the phase objective
+    partition: &'a Partition,
+}
+
+impl AnnealProgress<'_> {
+    /// Current district of each block (0 for unassigned). Copies every assignment, so call sparingly.
+    pub fn assignments(&self) -> Vec<u32> { self.partition.assignments() }
 }
 
 /// Epsilon threshold for treating small deltas as improvements (handles floating point precision).
@@ -35,7 +66,7 @@ const EPSILON: f64 = 1e-10;
 /// Schedule
 ///     T_k = T₀ * α^k,  where  α = (T_f / T₀)^(1/N)
 /// so that T_0 = T₀ and T_N = T_f exactly.
-fn temp_geometric(initial_temp: f64, final_temp: f64, max_iter: usize, iter: usize) -> f64 {
+pub(super) fn temp_geometric(initial_temp: f64, final_temp: f64, max_iter: usize, iter: usize) -> f64 {
     debug_assert!(initial_temp > 0.0 && final_temp > 0.0, "temperatures must be > 0");
 
     if iter > max_iter { return final_temp }
@@ -54,7 +85,7 @@ fn acceptance_probability(delta: f64, temp: f64) -> f64 {
 
 /// Metropolis acceptance criterion for simulated annealing in temperature space.
 /// Accept if `delta <= 0` or with probability `exp(-delta / T)`.
-fn accept_metropolis<R: Rng + ?Sized>(delta: f64, temp: f64, rng: &mut R) -> bool {
+pub(super) fn accept_metropolis<R: Rng + ?Sized>(delta: f64, temp: f64, rng: &mut R) -> bool {
     delta > EPSILON || rng.random::<f64>() < acceptance_probability(delta, temp)
 }
 
@@ -72,7 +103,7 @@ impl Partition {
         final_temp: f64,
         boundary_factor: f64,
     ) {
-        assert!(self.parts.get(0).is_empty(), "part 0 (unassigned) must be empty");
+        assert!(self.only_at_large_unassigned(), "part 0 (unassigned) must hold only the at-large area");
         assert!(self.num_parts() > 2, "need at least two parts for anneal_balance");
         assert!(self.part_weights().contains(series), "part_weights must contain series '{series}'");
 
@@ -95,10 +126,9 @@ impl Partition {
             // Pick random destination part (that neighbors node)
             let dest = self.random_neighboring_part(node, &mut rng).unwrap();
 
-            // Collect articulation bundle (if necessary)
-            let bundle =
-                if self.check_node_contiguity(node, dest) { vec![] }
-                else { self.cut_subgraph_within_part(node) };
+            // Collect articulation bundle (if necessary), skipping moves that violate constraints
+            let Some(subgraph) = self.move_proposal(node, dest) else { continue };
+            let bundle = &subgraph[..subgraph.len() - 1];
 
             // Compute cost of move, randomly accept based on metropolis filter
             let node_weight = self.unit_weights().get_as_f64(series, node).unwrap()
@@ -137,14 +167,7 @@ impl Partition {
                 );
             }
 
-            if accept {
-                if bundle.is_empty() {
-                    self.move_node(node, dest, false)
-                } else {
-                    let subgraph = bundle.iter().chain(std::iter::once(&node)).copied().collect::<Vec<_>>();
-                    self.move_subgraph(&subgraph, dest, false);
-                }
-            }
+            if accept { self.apply_move(&subgraph, dest) }
         }
     }
 
@@ -168,6 +191,13 @@ impl Partition {
     /// - `early_stop_iters`: Stop phase after this many iterations without improvement (when end_prob is None)
     /// - `temp_search_batch_size`: Batch size for temperature tuning steps
     /// - `batch_size`: Batch size for cooling phases
+    /// - `observer`: Called with the run's progress after each batch
+    /// - `steps`: Called after each step the observer wants, with snapshots of every metric of every phase
+    /// - `stop`: Rules for ending a phase, or the whole run, before it converges by the schedule
+    /// - `proposals`: Mix of move kinds proposed at each step
+    /// - `constraints`: Hard constraints (balance bounds, preserved units) that every accepted move must respect
+    /// - `profile`: Time each stage of the steps, returning the profile (also printed at the end)
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn anneal(&mut self,
         objectives: &[Objective],
         max_iter: usize,
@@ -178,8 +208,14 @@ impl Partition {
         early_stop_iters: usize,
         temp_search_batch_size: usize,
         batch_size: usize,
-    ) {
-        assert!(self.parts.get(0).is_empty(), "part 0 (unassigned) must be empty");
+        observer: &mut dyn FnMut(&AnnealProgress),
+        steps: &mut dyn StepObserver,
+        stop: &StoppingRules,
+        proposals: &KernelMix,
+        constraints: &MoveConstraints,
+        profile: bool,
+    ) -> Option<RunProfile> {
+        assert!(self.only_at_large_unassigned(), "part 0 (unassigned) must hold only the at-large area");
         assert!(self.num_parts() > 2, "need at least two parts for annealing");
         assert!(!objectives.is_empty(), "must provide at least one objective");
         assert!(phase_start_probs.len() == objectives.len(), "must provide start_prob for each phase");
@@ -190,6 +226,9 @@ impl Partition {
         }
         assert!(batch_size > 0, "batch_size must be > 0");
         assert!(temp_search_batch_size > 0, "temp_search_batch_size must be > 0");
+        for bound in &constraints.balance {
+            assert!(self.part_weights().contains(&bound.series), "part_weights must contain series '{}'", bound.series);
+        }
 
         let mut params = OptimizationParams {
             max_iter,
@@ -198,6 +237,8 @@ impl Partition {
             early_stop_iters,
             temp_search_batch_size,
             batch_size,
+            proposals: proposals.clone(),
+            constraints: constraints.clone(),
         };
 
         let first_objective = &objectives[0];
@@ -210,12 +251,18 @@ impl Partition {
             best_assignments: self.assignments(),
             best_iter: 0,
             temperature: params.init_temp,
+            phase: 1,
+            stopper: Stopper::new(stop),
+            stopped: None,
+            profiler: Profiler::new(profile),
         };
+        let mut steps = StepReporter::new(steps, objectives);
 
         // Run each phase
         for phase_idx in 0..objectives.len() {
             let objective = &objectives[phase_idx];
             let phase_num = phase_idx + 1;  // Display as 1-indexed
+            state.phase = phase_num;
             
             // Set cooling rate for this phase
             params.cooling_rate = phase_cooling_rates[phase_idx];
@@ -228,28 +275,35 @@ impl Partition {
                 state.best_assignments = self.assignments();
                 state.best_iter = state.current_iter;
             }
+            state.stopper.start_phase(state.current_iter);
+            state.stopped = None;
             
             // Step 1: Tune temperature to reach start_prob
             let start_prob = phase_start_probs[phase_idx];
-            self.tune_initial_temperature(objective, &params, &mut state, start_prob);
+            self.tune_initial_temperature(objective, &params, &mut state, start_prob, observer, &mut steps);
             
             // Step 2: Cool to end_prob (or use early stopping)
             match phase_end_probs[phase_idx] {
+                _ if state.stopped.is_some() => {}
                 Some(end_prob) => {
                     // Cool until probability threshold
-                    self.cool_to_probability_threshold(objective, &params, &mut state, phase_num, end_prob);
+                    self.cool_to_probability_threshold(objective, &params, &mut state, phase_num, end_prob, observer, &mut steps);
                 }
                 None => {
                     // Use early stopping
-                    self.cool_with_early_stopping(objective, &params, &mut state, phase_num);
+                    self.cool_with_early_stopping(objective, &params, &mut state, phase_num, observer, &mut steps);
                 }
             }
+
+            if state.stopped.is_some_and(|reason| reason.ends_run()) { break }
         }
 
         // Restore the best solution found
         if state.current_score < state.best_score {
             self.set_assignments(state.best_assignments);
         }
+
+        state.profiler.finish()
     }
 
     /// Find initial temperature where average acceptance probability reaches target.
@@ -261,6 +315,8 @@ impl Partition {
         params: &OptimizationParams,
         state: &mut OptimizationState<impl Rng>,
         target_prob: f64,
+        observer: &mut dyn FnMut(&AnnealProgress),
+        steps: &mut StepReporter,
     ) {
         let mut min_temp = state.temperature * 1e-10;  // Lower bound for binary search (very low)
         let mut max_temp = state.temperature * 1e10; // Upper bound for binary search
@@ -270,10 +326,11 @@ impl Partition {
             if state.current_iter >= params.max_iter { break }
             
             // Run a batch to measure average acceptance probability at current temperature
-            let (_, avg_prob, final_prob) = self.anneal_batch(objective, state, params.temp_search_batch_size);
+            let (_, avg_prob, final_prob) = self.anneal_batch(objective, params, state, params.temp_search_batch_size, steps);
             
             // Print progress during temp search
-            self.print_progress_with_avg_prob_and_curr(objective, avg_prob, final_prob, state, "Temp Search");
+            self.report_progress(objective, avg_prob, final_prob, state, "Temp Search", observer);
+            if self.should_stop(state, steps) { return }
 
             // Check if we're close enough to target (within 5%)
             if (avg_prob - target_prob).abs() < 0.05 { break }
@@ -288,6 +345,7 @@ impl Partition {
     }
 
     /// Intermediate cooling phase: Cool until average acceptance probability drops below threshold
+    #[allow(clippy::too_many_arguments)]
     fn cool_to_probability_threshold(
         &mut self,
         objective: &Objective,
@@ -295,19 +353,22 @@ impl Partition {
         state: &mut OptimizationState<impl Rng>,
         phase_num: usize,
         target_prob: f64,
+        observer: &mut dyn FnMut(&AnnealProgress),
+        steps: &mut StepReporter,
     ) {
         while state.current_iter < params.max_iter {
             let prev_best = state.best_score;
 
             // Perform batch of iterations
-            let (_, avg_prob, final_prob) = self.anneal_batch(objective, state, params.batch_size);
+            let (_, avg_prob, final_prob) = self.anneal_batch(objective, params, state, params.batch_size, steps);
             
             // Check if we improved the best objective
             if state.best_score > prev_best { state.best_iter = state.current_iter; }
 
             // Print progress after each batch
             let phase_label = format!("Phase {}", phase_num);
-            self.print_progress_with_avg_prob_and_curr(objective, avg_prob, final_prob, state, &phase_label);
+            self.report_progress(objective, avg_prob, final_prob, state, &phase_label, observer);
+            if self.should_stop(state, steps) { return }
 
             // Check if average probability has dropped below threshold
             if avg_prob < target_prob { return }
@@ -328,6 +389,8 @@ impl Partition {
         params: &OptimizationParams,
         state: &mut OptimizationState<impl Rng>,
         phase_num: usize,
+        observer: &mut dyn FnMut(&AnnealProgress),
+        steps: &mut StepReporter,
     ) {
         let mut iters_since_change = 0;
         
@@ -335,7 +398,7 @@ impl Partition {
             let prev_best = state.best_score;
 
             // Perform batch of iterations
-            let (any_accepted, avg_prob, final_prob) = self.anneal_batch(objective, state, params.batch_size);
+            let (any_accepted, avg_prob, final_prob) = self.anneal_batch(objective, params, state, params.batch_size, steps);
 
             if any_accepted { iters_since_change = 0; } else { iters_since_change += params.batch_size; }
             
@@ -344,7 +407,8 @@ impl Partition {
 
             // Print progress after each batch
             let phase_label = format!("Phase {}", phase_num);
-            self.print_progress_with_avg_prob_and_curr(objective, avg_prob, final_prob, state, &phase_label);
+            self.report_progress(objective, avg_prob, final_prob, state, &phase_label, observer);
+            if self.should_stop(state, steps) { return }
 
             // Early stopping check
             if iters_since_change >= params.early_stop_iters { return }
@@ -354,7 +418,13 @@ impl Partition {
         }
     }
 
-
+    /// Check the stopping rules after a batch, recording the rule that fired and reporting it to `steps`.
+    fn should_stop(&self, state: &mut OptimizationState<impl Rng>, steps: &mut StepReporter) -> bool {
+        let Some(reason) = state.stopper.check(self, state.current_iter, state.best_score) else { return false };
+        steps.stopped(state.current_iter, state.phase, reason);
+        state.stopped = Some(reason);
+        true
+    }
 
     /// Perform a batch of annealing iterations.
     /// Simply calls anneal_iteration n times without modifying temperature or handling stopping logic.
@@ -365,15 +435,18 @@ impl Partition {
     fn anneal_batch(
         &mut self,
         objective: &Objective,
+        params: &OptimizationParams,
         state: &mut OptimizationState<impl Rng>,
         n: usize,
+        steps: &mut StepReporter,
     ) -> (bool, f64, f64) {
         let mut any_accepted = false;
         let mut prob_sum = 0.0;
         let mut final_prob = 0.0;
         
         for _ in 0..n {
-            let (accepted, delta) = self.anneal_iteration(objective, state);
+            let (accepted, delta) = self.anneal_iteration(objective, params, state);
+            steps.report(self, state.current_iter - 1, state.phase, accepted, state.current_score);
             if accepted {
                 any_accepted = true;
             }
@@ -391,34 +464,31 @@ impl Partition {
     fn anneal_iteration(
         &mut self,
         objective: &Objective,
+        params: &OptimizationParams,
         state: &mut OptimizationState<impl Rng>,
     ) -> (bool, f64) {
-        // Pick random source part, weighted by frontier size
-        let src = self.random_part_weighted_by_frontier(&mut state.rng).unwrap();
-
-        // Pick random node on part boundary
-        let candidates = self.frontiers.get(src as usize);
-        let node = candidates[state.rng.random_range(0..candidates.len())];
-
-        // Pick random destination part (that neighbors node)
-        let dest = self.random_neighboring_part(node, &mut state.rng).unwrap();
-
-        // Collect articulation bundle (if necessary to maintain contiguity)
-        let bundle = if !self.check_node_contiguity(node, dest) { 
-            self.cut_subgraph_within_part(node)
-        } else { vec![] };
+        state.profiler.lap(Stage::Other);
+
+        // Apply a proposed move temporarily to compute new objective, rejecting moves that violate constraints
+        let Some(applied) = self.apply_proposal(&params.proposals, &mut state.rng, &mut state.profiler) else {
+            state.profiler.lap(Stage::Proposal);
+            state.profiler.count_step(false);
+            state.current_iter += 1;
+            return (false, f64::NEG_INFINITY);
+        };
 
-        // Apply the move temporarily to compute new objective
-        let prev_src = src;
-        if bundle.is_empty() {
-            self.move_node(node, dest, false);
-        } else {
-            let subgraph = bundle.iter().chain(std::iter::once(&node)).copied().collect::<Vec<_>>();
-            self.move_subgraph(&subgraph, dest, false);
+        // Reject moves that break a hard constraint before scoring them
+        if !self.keeps_constraints(&params.constraints, &applied) {
+            self.revert_proposal(&applied);
+            state.profiler.lap(Stage::Acceptance);
+            state.profiler.count_step(false);
+            state.current_iter += 1;
+            return (false, f64::NEG_INFINITY);
         }
 
         // Compute new objective value
         let new_score = objective.compute(self);
+        state.profiler.lap(Stage::Metrics);
         
         // Delta: negative of improvement (for minimization in Metropolis criterion)
         let delta = new_score - state.current_score;
@@ -436,13 +506,10 @@ impl Partition {
             }
         } else {
             // Revert the move
-            if bundle.is_empty() {
-                self.move_node(node, prev_src, false);
-            } else {
-                let subgraph = bundle.iter().chain(std::iter::once(&node)).copied().collect::<Vec<_>>();
-                self.move_subgraph(&subgraph, prev_src, false);
-            }
+            self.revert_proposal(&applied);
         }
+        state.profiler.lap(Stage::Acceptance);
+        state.profiler.count_step(true);
 
         state.current_iter += 1;
         (accept, delta)
@@ -495,17 +562,22 @@ impl Partition {
         );
     }
 
-    /// Print progress information with both average probability and current move probability
-    fn print_progress_with_avg_prob_and_curr(
+    /// Print progress information with both average probability and current move probability,
+    /// and pass it to `observer`.
+    fn report_progress(
         &self,
         objective: &Objective,
         avg_prob: f64,
         curr_prob: f64,
         state: &OptimizationState<impl Rng>,
         phase: &str,
+        observer: &mut dyn FnMut(&AnnealProgress),
     ) {
-        let comp_str = objective.metrics().iter()
-            .map(|metric| { format!("{}={:.4}", metric.short_name(), metric.compute_score(self)) })
+        let metrics = objective.metrics().iter()
+            .map(|metric| (metric.short_name(), metric.compute_score(self)))
+            .collect::<Vec<_>>();
+        let comp_str = metrics.iter()
+            .map(|(name, score)| format!("{}={:.4}", name, score))
             .collect::<Vec<_>>()
             .join(" ");
         
@@ -520,5 +592,16 @@ impl Partition {
             avg_prob,
             curr_prob,
         );
+
+        observer(&AnnealProgress {
+            phase,
+            iter: state.current_iter,
+            score: state.current_score,
+            best_score: state.best_score,
+            temperature: state.temperature,
+            accept_prob: avg_prob,
+            metrics,
+            partition: self,
+        });
     }
 }
\ No newline at end of file
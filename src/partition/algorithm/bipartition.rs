@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+
+use crate::partition::Partition;
+
+/// Largest subproblem the exact solver accepts (node sets are `u64` bitmasks).
+pub(crate) const MAX_EXACT_NODES: usize = 64;
+
+/// A small weighted graph for exact solving, with nodes numbered `0..n`.
+#[derive(Clone, Debug)]
+pub(crate) struct SmallGraph {
+    weights: Vec<f64>,              // Node weight (e.g. population)
+    edges: Vec<Vec<(usize, f64)>>,  // Neighbors of each node, with edge weights (e.g. shared perimeter)
+    adjacency: Vec<u64>,            // Neighbor bitmask of each node
+}
+
+impl SmallGraph {
+    pub(crate) fn new(weights: Vec<f64>, edges: Vec<Vec<(usize, f64)>>) -> Self {
+        assert!(weights.len() <= MAX_EXACT_NODES, "exact solver supports at most {MAX_EXACT_NODES} nodes");
+        assert_eq!(weights.len(), edges.len(), "weights and edges must have one entry per node");
+        let adjacency = edges.iter()
+            .map(|neighbors| mask(neighbors.iter().map(|&(v, _)| v)))
+            .collect();
+        Self { weights, edges, adjacency }
+    }
+
+    #[inline] pub(crate) fn len(&self) -> usize { self.weights.len() }
+
+    #[inline] fn all(&self) -> u64 { if self.len() == 64 { u64::MAX } else { (1 << self.len()) - 1 } }
+
+    fn weight(&self, mask: u64) -> f64 { bits(mask).map(|u| self.weights[u]).sum() }
+
+    fn is_connected(&self, mask: u64) -> bool {
+        mask != 0 && self.component(mask, mask.trailing_zeros() as usize) == mask
+    }
+
+    /// Nodes of `mask` reachable from `start` within `mask`.
+    fn component(&self, mask: u64, start: usize) -> u64 {
+        let mut seen = 1 << start;
+        let mut frontier = seen;
+        while frontier != 0 {
+            let next = bits(frontier).fold(0, |next, u| next | self.adjacency[u]) & mask & !seen;
+            seen |= next;
+            frontier = next;
+        }
+        seen
+    }
+
+    fn cut_weight(&self, mask: u64) -> f64 { self.edge_weight(mask, !mask) }
+
+    /// Total weight of edges from `from` to `to`.
+    fn edge_weight(&self, from: u64, to: u64) -> f64 {
+        bits(from).flat_map(|u| self.edges[u].iter())
+            .filter(|&&(v, _)| to & 1 << v != 0)
+            .map(|&(_, weight)| weight)
+            .sum()
+    }
+}
+
+/// Mask with the given bits set.
+fn mask(bits: impl IntoIterator<Item = usize>) -> u64 {
+    bits.into_iter().fold(0, |mask, bit| mask | 1 << bit)
+}
+
+/// Iterate over the set bits of a mask.
+fn bits(mut mask: u64) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        if mask == 0 { return None }
+        let bit = mask.trailing_zeros() as usize;
+        mask &= mask - 1;
+        Some(bit)
+    })
+}
+
+/// Split a small graph into two connected sides, each weighing within `bounds`, with the least total
+/// weight on edges between them. `accept` can reject a side (as a mask of its nodes) for other reasons.
+///
+/// Exact: connected sets holding node 0 are enumerated once each, pruned by weight, by the cut already
+/// fixed, and by the other side's connectivity, but the cost still grows exponentially with size. Returns the mask of the side holding node 0 and the cut weight, or `None`
+/// if no split is feasible.
+pub(crate) fn optimal_bipartition(graph: &SmallGraph, bounds: (f64, f64), accept: impl Fn(u64) -> bool) -> Option<(u64, f64)> {
+    if graph.len() < 2 { return None }
+    let mut search = Search { graph, bounds, total: graph.weight(graph.all()), accept, best: None };
+    search.extend(1, graph.weights[0], graph.adjacency[0], 0);
+    search.best
+}
+
+struct Search<'a, F> {
+    graph: &'a SmallGraph,
+    bounds: (f64, f64),
+    total: f64,
+    accept: F,
+    best: Option<(u64, f64)>,
+}
+
+impl<F: Fn(u64) -> bool> Search<'_, F> {
+    /// Visit connected set `side` (weighing `weight`), then every connected superset adding nodes
+    /// from `candidates` but none from `forbidden`.
+    fn extend(&mut self, side: u64, weight: f64, mut candidates: u64, mut forbidden: u64) {
+        // Forbidden nodes stay on the other side, so edges to them are cut in every superset,
+        // and they must all be connected without passing through this side.
+        let rest = self.graph.all() & !side;
+        if self.best.is_some_and(|(_, best)| self.graph.edge_weight(side, forbidden) >= best) { return }
+        if forbidden != 0 && forbidden & !self.graph.component(rest, forbidden.trailing_zeros() as usize) != 0 { return }
+
+        self.visit(side, weight);
+
+        // Prune if even every reachable node could not bring the side up to the lower bound.
+        if weight + self.graph.weight(rest & !forbidden) < self.bounds.0 { return }
+
+        while candidates != 0 {
+            let node = candidates.trailing_zeros() as usize;
+            let bit = 1 << node;
+            candidates &= !bit;
+            if weight + self.graph.weights[node] <= self.bounds.1 {
+                let next = candidates | (self.graph.adjacency[node] & !side & !forbidden & !bit);
+                self.extend(side | bit, weight + self.graph.weights[node], next, forbidden);
+            }
+            forbidden |= bit;
+        }
+    }
+
+    fn visit(&mut self, side: u64, weight: f64) {
+        let rest = self.graph.all() & !side;
+        let within = |weight: f64| weight >= self.bounds.0 && weight <= self.bounds.1;
+        if rest == 0 || !within(weight) || !within(self.total - weight) { return }
+
+        let cut = self.graph.cut_weight(side);
+        if self.best.is_some_and(|(_, best)| cut >= best) { return }
+        if self.graph.is_connected(rest) && (self.accept)(side) { self.best = Some((side, cut)) }
+    }
+}
+
+impl Partition {
+    /// Re-split adjacent parts `a` and `b` optimally: of all splits of their nodes into two contiguous
+    /// parts with `series` totals within `bounds`, apply one with the shortest shared boundary,
+    /// respecting groups and anchors and moving as few nodes as possible.
+    /// Returns `false`, leaving both parts unchanged, if the parts are not adjacent, hold more than
+    /// [`MAX_EXACT_NODES`] nodes together, or have no feasible split.
+    pub(crate) fn refine_pair_exact(&mut self, a: u32, b: u32, series: &str, bounds: (f64, f64)) -> bool {
+        assert!(self.unit_weights().contains(series), "series '{}' not found in node weights", series);
+        if !self.part_borders_part(a, b) { return false }
+
+        let nodes = [self.part_nodes(a), self.part_nodes(b)].concat();
+        if nodes.len() > MAX_EXACT_NODES { return false }
+        let local = nodes.iter().enumerate().map(|(i, &u)| (u, i)).collect::<HashMap<_, _>>();
+
+        let graph = SmallGraph::new(
+            nodes.iter().map(|&u| self.unit_weights().get_as_f64(series, u).unwrap()).collect(),
+            nodes.iter().map(|&u| self.graph().edges_with_weights(u)
+                .filter_map(|(v, weight)| local.get(&v).map(|&j| (j, weight)))
+                .collect()).collect(),
+        );
+
+        // Groups must stay whole; anchored nodes must end up in their part.
+        let group_masks = nodes.iter()
+            .filter_map(|&u| self.group_members(u))
+            .map(|members| mask(members.iter().map(|v| local[v])))
+            .collect::<Vec<_>>();
+        let only_a = mask((0..nodes.len()).filter(|&i| !self.can_move_node(nodes[i], b)));
+        let only_b = mask((0..nodes.len()).filter(|&i| !self.can_move_node(nodes[i], a)));
+        let orientation_ok = |to_a: u64| to_a & only_a == only_a && to_a & only_b == 0;
+        let accept = |side: u64| {
+            group_masks.iter().all(|&group| side & group == 0 || side & group == group)
+                && (orientation_ok(side) || orientation_ok(graph.all() & !side))
+        };
+
+        let Some((side, _)) = optimal_bipartition(&graph, bounds, accept) else { return false };
+
+        // Give `a` whichever side keeps more nodes in place (and respects anchors).
+        let in_a = mask((0..nodes.len()).filter(|&i| self.assignment(nodes[i]) == a));
+        let other = graph.all() & !side;
+        let keep = |to_a: u64| (to_a & in_a).count_ones() + (graph.all() & !to_a & !in_a).count_ones();
+        let to_a = match (orientation_ok(side), orientation_ok(other)) {
+            (true, true) => if keep(side) >= keep(other) { side } else { other },
+            (true, false) => side,
+            _ => other,
+        };
+
+        let moving_to_a = bits(to_a & !in_a).map(|i| nodes[i]).collect::<Vec<_>>();
+        let moving_to_b = bits(in_a & !to_a).map(|i| nodes[i]).collect::<Vec<_>>();
+        self.move_subgraph(&moving_to_a, a, false);
+        self.move_subgraph(&moving_to_b, b, false);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A `width` x `height` grid graph with unit edge weights.
+    fn grid(width: usize, height: usize, weights: Vec<f64>) -> SmallGraph {
+        let edges = (0..width * height).map(|u| {
+            let (x, y) = (u % width, u / width);
+            let mut neighbors = vec![];
+            if x > 0 { neighbors.push((u - 1, 1.0)) }
+            if x + 1 < width { neighbors.push((u + 1, 1.0)) }
+            if y > 0 { neighbors.push((u - width, 1.0)) }
+            if y + 1 < height { neighbors.push((u + width, 1.0)) }
+            neighbors
+        }).collect();
+        SmallGraph::new(weights, edges)
+    }
+
+    #[test]
+    fn splits_grid_along_shortest_balanced_cut() {
+        // A 4x2 grid splits into two 2x2 squares, cutting two edges.
+        let graph = grid(4, 2, vec![1.0; 8]);
+        let (side, cut) = optimal_bipartition(&graph, (4.0, 4.0), |_| true).unwrap();
+        assert_eq!(cut, 2.0);
+        assert_eq!(side, 0b0011_0011);
+
+        // Heavy corner nodes force a different shape.
+        let graph = grid(3, 3, vec![4.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0]);
+        let (side, _) = optimal_bipartition(&graph, (6.0, 6.0), |_| true).unwrap();
+        assert_eq!(graph.weight(side), 6.0);
+        assert!(graph.is_connected(side) && graph.is_connected(graph.all() & !side));
+
+        // No split meets impossible bounds, or a rejecting filter.
+        assert!(optimal_bipartition(&graph, (100.0, 200.0), |_| true).is_none());
+        assert!(optimal_bipartition(&graph, (0.0, 12.0), |_| false).is_none());
+    }
+}
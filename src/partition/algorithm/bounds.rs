@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+
+use crate::partition::{Partition, algorithm::proposal::AppliedMove};
+
+/// Hard bounds on the district totals of one weight series, as relative deviations from the
+/// ideal total (the region total over the number of districts).
+///
+/// Several bounds are enforced together, so e.g. total population within ±1% and a BVAP floor
+/// are `[{"series": "T_20_CENS_Total", "min": -0.01, "max": 0.01}, {"series": "T_20_CENS_Black_VAP", "min": -0.25}]`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BalanceBound {
+    pub series: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>, // Lowest allowed deviation (no floor if None)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>, // Highest allowed deviation (no ceiling if None)
+}
+
+impl BalanceBound {
+    /// Every district within `tolerance` (e.g. 0.01 = ±1%) of the ideal total of `series`.
+    pub fn within(series: String, tolerance: f64) -> Self {
+        assert!(tolerance >= 0.0, "tolerance must be non-negative");
+        Self { series, min: Some(-tolerance), max: Some(tolerance) }
+    }
+
+    /// Every district at least `min` (e.g. -0.2 = 80% of the ideal total) of the ideal total of `series`.
+    pub fn at_least(series: String, min: f64) -> Self {
+        Self { series, min: Some(min), max: None }
+    }
+
+    /// Amount by which a deviation falls outside the bound (0 if within).
+    pub(crate) fn excess(&self, deviation: f64) -> f64 {
+        let below = self.min.map_or(0.0, |min| min - deviation);
+        let above = self.max.map_or(0.0, |max| deviation - max);
+        below.max(above).max(0.0)
+    }
+}
+
+/// Unit of a node outside every preserved unit.
+const NOT_PRESERVED: u32 = u32::MAX;
+
+/// Hard constraints checked on every move of an optimization run before it is scored, resolved
+/// against the partition's nodes (see [`crate::Constraints`]).
+#[derive(Clone, Debug, Default)]
+pub(crate) struct MoveConstraints {
+    pub(crate) balance: Vec<BalanceBound>, // Bounds on district totals
+    preserved: Vec<u32>,                   // Preserved unit of each node (`NOT_PRESERVED` if none), empty if none
+    unit_sizes: Vec<usize>,                // Number of nodes in each preserved unit
+}
+
+impl MoveConstraints {
+    /// Constraints with the given balance bounds, keeping each set of nodes in `units` whole.
+    /// Overlapping units are merged, since keeping both whole keeps their union in one part.
+    pub(crate) fn new(balance: Vec<BalanceBound>, units: &[Vec<usize>], num_nodes: usize) -> Self {
+        if units.is_empty() { return Self { balance, ..Default::default() } }
+
+        let mut preserved = vec![NOT_PRESERVED; num_nodes];
+        for (unit, nodes) in units.iter().enumerate() {
+            let merged = nodes.iter().map(|&u| preserved[u]).filter(|&other| other != NOT_PRESERVED).collect::<Vec<_>>();
+            for label in preserved.iter_mut().filter(|label| merged.contains(label)) { *label = unit as u32 }
+            for &u in nodes { preserved[u] = unit as u32 }
+        }
+        let mut unit_sizes = vec![0; units.len()];
+        for &unit in preserved.iter().filter(|&&unit| unit != NOT_PRESERVED) { unit_sizes[unit as usize] += 1 }
+        Self { balance, preserved, unit_sizes }
+    }
+
+    /// Check that each step of a move carries every preserved unit it touches whole.
+    fn keeps_units(&self, applied: &AppliedMove) -> bool {
+        if self.preserved.is_empty() { return true }
+        applied.iter().all(|(nodes, _, _)| {
+            let mut counts: Vec<(u32, usize)> = Vec::new();
+            for unit in nodes.iter().map(|&u| self.preserved[u]).filter(|&unit| unit != NOT_PRESERVED) {
+                match counts.iter_mut().find(|(other, _)| *other == unit) {
+                    Some((_, count)) => *count += 1,
+                    None => counts.push((unit, 1)),
+                }
+            }
+            counts.iter().all(|&(unit, count)| count == self.unit_sizes[unit as usize])
+        })
+    }
+}
+
+impl Partition {
+    /// Check a move just applied against `constraints`: it must keep preserved units whole and
+    /// respect the balance bounds (see `keeps_balance`). Preserved units are checked first, as
+    /// that only looks at the moved nodes.
+    pub(super) fn keeps_constraints(&self, constraints: &MoveConstraints, applied: &AppliedMove) -> bool {
+        constraints.keeps_units(applied) && self.keeps_balance(&constraints.balance, applied)
+    }
+
+    /// Check that a move just applied left no district further outside any of `bounds` than it was
+    /// before. Districts already out of bounds (e.g. in an unbalanced seed plan) may still move
+    /// towards them, but no bound may be traded against another.
+    fn keeps_balance(&self, bounds: &[BalanceBound], applied: &AppliedMove) -> bool {
+        bounds.iter().all(|bound| {
+            // Change in the series total of each part touched by the move.
+            let mut changes: Vec<(u32, f64)> = Vec::new();
+            for (nodes, from, to) in applied {
+                let weight = nodes.iter()
+                    .map(|&u| self.unit_weights().get_as_f64(&bound.series, u).unwrap())
+                    .sum::<f64>();
+                for (part, change) in [(*from, -weight), (*to, weight)] {
+                    match changes.iter_mut().find(|(p, _)| *p == part) {
+                        Some((_, total)) => *total += change,
+                        None => changes.push((part, change)),
+                    }
+                }
+            }
+
+            let ideal = self.region_total(&bound.series) / (self.num_parts() - 1) as f64;
+            changes.iter().filter(|&&(part, _)| part != 0).all(|&(part, change)| {
+                let after = self.part_total(&bound.series, part);
+                bound.excess(after / ideal - 1.0) <= bound.excess((after - change) / ideal - 1.0) + 1e-12
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn excess_is_zero_within_bounds() {
+        let bound = BalanceBound::within("pop".into(), 0.01);
+        assert_eq!(bound.excess(0.005), 0.0);
+        assert_eq!(bound.excess(-0.01), 0.0);
+    }
+
+    #[test]
+    fn excess_measures_distance_outside_bounds() {
+        let bound = BalanceBound::within("pop".into(), 0.01);
+        assert!((bound.excess(0.03) - 0.02).abs() < 1e-12);
+        assert!((bound.excess(-0.02) - 0.01).abs() < 1e-12);
+    }
+
+    #[test]
+    fn floor_has_no_ceiling() {
+        let bound = BalanceBound::at_least("bvap".into(), -0.2);
+        assert_eq!(bound.excess(5.0), 0.0);
+        assert!((bound.excess(-0.5) - 0.3).abs() < 1e-12);
+    }
+
+    #[test]
+    fn moves_must_carry_preserved_units_whole() {
+        // Units {0, 1} and {1, 2} overlap, so they merge into {0, 1, 2}.
+        let constraints = MoveConstraints::new(vec![], &[vec![0, 1], vec![1, 2], vec![4, 5]], 6);
+        assert!(constraints.keeps_units(&vec![(vec![3], 1, 2)]));
+        assert!(constraints.keeps_units(&vec![(vec![2, 1, 0, 3], 1, 2), (vec![4, 5], 2, 1)]));
+        assert!(!constraints.keeps_units(&vec![(vec![0, 1], 1, 2)]));
+        assert!(!constraints.keeps_units(&vec![(vec![3], 1, 2), (vec![5], 2, 1)]));
+    }
+
+    #[test]
+    fn deserializes_one_sided_bound() {
+        let bound: BalanceBound = serde_json::from_str(r#"{"series": "bvap", "min": -0.2}"#).unwrap();
+        assert_eq!(bound.min, Some(-0.2));
+        assert_eq!(bound.max, None);
+    }
+}
@@ -56,6 +56,7 @@ impl Partition {
 
         let delta = src_total - dest_total;
         let mut remaining = delta / 2.0;
+        let mut blocked = 0;
 
         while remaining > 0.0 {
             // Pick a random candidate on the boundary of src.
@@ -65,21 +66,19 @@ impl Partition {
             // Skip if not adjacent.
             if !(self.part_is_empty(dest) || self.node_borders_part(node, dest)) { continue }
 
-            if self.check_node_contiguity(node, dest) {
-                let delta = self.unit_weights().get_as_f64(series, node).unwrap();
-                self.move_node(node, dest, false);
-                remaining -= delta;
-            } else {
-                // Compute articulation bundle and move node with it (if necessary).
-                let mut subgraph = self.cut_subgraph_within_part(node);
-                subgraph.push(node);
-
-                let delta = subgraph.iter()
-                    .map(|&u| self.unit_weights().get_as_f64(series, u).unwrap())
-                    .sum::<f64>();
-                self.move_subgraph(&subgraph, dest, false);
-                remaining -= delta;
-            }
+            // Compute articulation bundle or group (if necessary), skipping moves that violate
+            // constraints and giving up if the frontier appears to be pinned in place.
+            let Some(subgraph) = self.move_proposal(node, dest) else {
+                blocked += 1;
+                if blocked > 4 * self.frontiers.get(src as usize).len() { return }
+                continue
+            };
+
+            let delta = subgraph.iter()
+                .map(|&u| self.unit_weights().get_as_f64(series, u).unwrap())
+                .sum::<f64>();
+            self.apply_move(&subgraph, dest);
+            remaining -= delta;
         }
 
         // If we overshot, recursively equalize in the other direction with higher tolerance.
@@ -119,7 +118,7 @@ impl Partition {
         let distribution = WeightedIndex::new(&deviations).unwrap();
         let part = distribution.sample(&mut rng) as u32 + 1;
 
-        if totals[part as usize - 1] > target * 2.0 {
+        if totals[part as usize - 1] > target * 2.0 && !self.has_anchors() && !self.has_clusters() && self.num_groups().is_none() {
             let (smallest, _) = self.part_with_min_weight(series);
             let neighbors = self.sample_neighboring_parts(smallest, 8, &mut rng);
             if let Some((neighbor, _)) = neighbors.iter()
@@ -189,7 +188,7 @@ impl Partition {
             let part = distribution.sample(&mut rng) as u32 + 1;
 
             // If the part total is more than twice the target, split into two districts while the smallest.
-            if totals[part as usize - 1] > target * 2.0 {
+            if totals[part as usize - 1] > target * 2.0 && !self.has_anchors() && !self.has_clusters() && self.num_groups().is_none() {
                 let (smallest, _) = self.part_with_min_weight(series);
                 let (neighbor, _) = self.sample_neighboring_parts(smallest, 8, &mut rng).iter()
                     .map(|&p| (p, self.part_weights().get_as_f64(series, p as usize).unwrap()))
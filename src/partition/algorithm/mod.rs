@@ -1,4 +1,119 @@
 mod anneal;
+mod bipartition;
+mod bounds;
 mod equalize;
+mod observer;
+mod profile;
+mod proposal;
 mod randomize;
+mod rebalance;
+mod recom;
+mod relax;
+mod stopping;
 mod tabu;
+
+pub use anneal::AnnealProgress;
+pub use bounds::BalanceBound;
+pub(crate) use bounds::MoveConstraints;
+pub use observer::{CsvStepWriter, EveryNth, JsonlStepWriter, Step, StepBuffer, StepObserver, StepRecord};
+pub use profile::RunProfile;
+pub use proposal::{Proposal, WeightedProposal};
+pub(crate) use proposal::{Kernel, KernelMix};
+pub use stopping::{PlateauRule, StopReason, StoppingRules};
+
+/// Broad purpose of a partitioning algorithm.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlgorithmKind {
+    /// Produces a plan from scratch.
+    Initialize,
+    /// Improves balance of a single weight series.
+    Balance,
+    /// Optimizes a general objective.
+    Optimize,
+    /// Proposes moves for sampling.
+    Sample,
+}
+
+impl AlgorithmKind {
+    /// Lowercase name of this kind, for listings.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Initialize => "initialize",
+            Self::Balance => "balance",
+            Self::Optimize => "optimize",
+            Self::Sample => "sample",
+        }
+    }
+}
+
+/// Description of a partitioning algorithm exposed on `Plan`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AlgorithmInfo {
+    pub name: &'static str, // Name of the `Plan` method running the algorithm
+    pub kind: AlgorithmKind,
+    pub description: &'static str,
+}
+
+/// Every algorithm available on `Plan`, in display order.
+pub const ALGORITHMS: &[AlgorithmInfo] = &[
+    AlgorithmInfo {
+        name: "randomize",
+        kind: AlgorithmKind::Initialize,
+        description: "Grow contiguous districts from random seeds",
+    },
+    AlgorithmInfo {
+        name: "equalize",
+        kind: AlgorithmKind::Balance,
+        description: "Greedy boundary swaps until district totals are within tolerance",
+    },
+    AlgorithmInfo {
+        name: "rebalance",
+        kind: AlgorithmKind::Balance,
+        description: "Deterministic repair routing frontier units from over- to under-populated districts",
+    },
+    AlgorithmInfo {
+        name: "refine_pair_exact",
+        kind: AlgorithmKind::Balance,
+        description: "Exact re-split of two small adjacent districts along the shortest balanced boundary",
+    },
+    AlgorithmInfo {
+        name: "anneal_balance",
+        kind: AlgorithmKind::Balance,
+        description: "Simulated annealing on population balance and boundary length",
+    },
+    AlgorithmInfo {
+        name: "tabu_balance",
+        kind: AlgorithmKind::Balance,
+        description: "Tabu search on population balance and boundary length",
+    },
+    AlgorithmInfo {
+        name: "anneal",
+        kind: AlgorithmKind::Optimize,
+        description: "Multi-phase adaptive simulated annealing on weighted objectives",
+    },
+    AlgorithmInfo {
+        name: "anneal_relaxed",
+        kind: AlgorithmKind::Optimize,
+        description: "Annealing from an infeasible plan with tightening constraint bounds",
+    },
+    AlgorithmInfo {
+        name: "anneal_by_region",
+        kind: AlgorithmKind::Optimize,
+        description: "Annealing of county super-regions in parallel, stitched and refined at the seams",
+    },
+    AlgorithmInfo {
+        name: "recombine",
+        kind: AlgorithmKind::Sample,
+        description: "Merge two adjacent districts and split them along a random spanning tree",
+    },
+    AlgorithmInfo {
+        name: "recom",
+        kind: AlgorithmKind::Sample,
+        description: "ReCom chain: repeated recombination steps whose splits keep districts within a population tolerance",
+    },
+];
+
+/// Look up an algorithm by name.
+pub fn algorithm_info(name: &str) -> Option<&'static AlgorithmInfo> {
+    ALGORITHMS.iter().find(|info| info.name == name)
+}
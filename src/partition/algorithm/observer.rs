@@ -0,0 +1,321 @@
+use std::{collections::VecDeque, fs::File, io::{BufWriter, Write}, path::Path};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{Metric, Objective, partition::Partition};
+
+use super::StopReason;
+
+/// One step of a chain: a proposed move, and whether it was kept.
+#[derive(Clone, Copy, Debug)]
+pub struct Step<'a> {
+    pub index: usize,                  // Iteration number, counted across phases
+    pub phase: usize,                  // Phase of a multi-phase run (1-based; always 1 otherwise)
+    pub accepted: bool,
+    pub score: f64,                    // Objective value after the step
+    pub metrics: &'a [(&'a str, f64)], // Score of each metric of the run after the step
+}
+
+/// Receives the steps of a chain as it runs (see [`Plan::anneal_with_observer`](crate::Plan::anneal_with_observer)).
+///
+/// Metric snapshots are only computed for steps the observer [`wants`](StepObserver::wants),
+/// so sampling every n-th step (with [`EveryNth`]) keeps long chains fast.
+pub trait StepObserver {
+    /// Record one step.
+    fn observe(&mut self, step: &Step);
+
+    /// Whether step `index` should be recorded.
+    fn wants(&self, _index: usize) -> bool { true }
+
+    /// A stopping rule ended phase `phase` (or the whole run, see [`StopReason::ends_run`]) after step `index`.
+    fn stopped(&mut self, _index: usize, _phase: usize, _reason: StopReason) {}
+}
+
+/// Observes nothing.
+impl StepObserver for () {
+    fn observe(&mut self, _: &Step) {}
+    fn wants(&self, _: usize) -> bool { false }
+}
+
+impl<O: StepObserver> StepObserver for Option<O> {
+    fn observe(&mut self, step: &Step) {
+        if let Some(observer) = self { observer.observe(step) }
+    }
+
+    fn wants(&self, index: usize) -> bool { self.as_ref().is_some_and(|observer| observer.wants(index)) }
+
+    fn stopped(&mut self, index: usize, phase: usize, reason: StopReason) {
+        if let Some(observer) = self { observer.stopped(index, phase, reason) }
+    }
+}
+
+impl<A: StepObserver, B: StepObserver> StepObserver for (A, B) {
+    fn observe(&mut self, step: &Step) {
+        if self.0.wants(step.index) { self.0.observe(step) }
+        if self.1.wants(step.index) { self.1.observe(step) }
+    }
+
+    fn wants(&self, index: usize) -> bool { self.0.wants(index) || self.1.wants(index) }
+
+    fn stopped(&mut self, index: usize, phase: usize, reason: StopReason) {
+        self.0.stopped(index, phase, reason);
+        self.1.stopped(index, phase, reason);
+    }
+}
+
+/// Passes every `n`-th step (0, n, 2n, ...) on to `inner`.
+#[derive(Clone, Debug)]
+pub struct EveryNth<O> {
+    pub inner: O,
+    pub n: usize,
+}
+
+impl<O: StepObserver> StepObserver for EveryNth<O> {
+    fn observe(&mut self, step: &Step) { self.inner.observe(step) }
+
+    fn wants(&self, index: usize) -> bool { index.is_multiple_of(self.n.max(1)) && self.inner.wants(index) }
+
+    fn stopped(&mut self, index: usize, phase: usize, reason: StopReason) { self.inner.stopped(index, phase, reason) }
+}
+
+/// An owned copy of a [`Step`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StepRecord {
+    pub index: usize,
+    pub phase: usize,
+    pub accepted: bool,
+    pub score: f64,
+    pub metrics: Vec<(String, f64)>,
+}
+
+impl From<&Step<'_>> for StepRecord {
+    fn from(step: &Step) -> Self {
+        Self {
+            index: step.index,
+            phase: step.phase,
+            accepted: step.accepted,
+            score: step.score,
+            metrics: step.metrics.iter().map(|&(name, score)| (name.to_string(), score)).collect(),
+        }
+    }
+}
+
+/// Keeps the most recent `capacity` steps in memory.
+#[derive(Clone, Debug, Default)]
+pub struct StepBuffer {
+    capacity: usize,
+    records: VecDeque<StepRecord>,
+}
+
+impl StepBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, records: VecDeque::with_capacity(capacity.min(1 << 16)) }
+    }
+
+    #[inline] pub fn capacity(&self) -> usize { self.capacity }
+    #[inline] pub fn len(&self) -> usize { self.records.len() }
+    #[inline] pub fn is_empty(&self) -> bool { self.records.is_empty() }
+
+    /// Recorded steps, oldest first.
+    #[inline] pub fn records(&self) -> impl Iterator<Item = &StepRecord> { self.records.iter() }
+
+    #[inline] pub fn clear(&mut self) { self.records.clear() }
+}
+
+impl StepObserver for StepBuffer {
+    fn observe(&mut self, step: &Step) {
+        if self.capacity == 0 { return }
+        if self.records.len() == self.capacity { self.records.pop_front(); }
+        self.records.push_back(step.into());
+    }
+}
+
+/// Writes steps as CSV rows: `index,phase,accepted,score`, then one column per metric.
+///
+/// Write errors don't interrupt the chain; the first one is returned by [`finish`](Self::finish).
+pub struct CsvStepWriter<W: Write> {
+    writer: W,
+    header: bool,
+    error: Option<std::io::Error>,
+}
+
+impl CsvStepWriter<BufWriter<File>> {
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = File::create(path).with_context(|| format!("[CsvStepWriter] Failed to create {}", path.display()))?;
+        Ok(Self::new(BufWriter::new(file)))
+    }
+}
+
+impl<W: Write> CsvStepWriter<W> {
+    pub fn new(writer: W) -> Self { Self { writer, header: false, error: None } }
+
+    /// Flush the output, returning the writer or the first write error.
+    pub fn finish(mut self) -> Result<W> {
+        if let Some(error) = self.error.take() { return Err(error).context("[CsvStepWriter] Failed to write step") }
+        self.writer.flush().context("[CsvStepWriter] Failed to flush steps")?;
+        Ok(self.writer)
+    }
+
+    fn write(&mut self, step: &Step) -> std::io::Result<()> {
+        if !self.header {
+            write!(self.writer, "index,phase,accepted,score")?;
+            for (name, _) in step.metrics { write!(self.writer, ",{name}")? }
+            writeln!(self.writer)?;
+            self.header = true;
+        }
+        write!(self.writer, "{},{},{},{}", step.index, step.phase, step.accepted, step.score)?;
+        for (_, score) in step.metrics { write!(self.writer, ",{score}")? }
+        writeln!(self.writer)
+    }
+}
+
+impl<W: Write> StepObserver for CsvStepWriter<W> {
+    fn observe(&mut self, step: &Step) {
+        if self.error.is_none() && let Err(error) = self.write(step) { self.error = Some(error) }
+    }
+}
+
+/// Writes steps as JSON Lines, e.g. `{"index":0,"phase":1,"accepted":true,"score":0.5,"metrics":{...}}`.
+///
+/// Write errors don't interrupt the chain; the first one is returned by [`finish`](Self::finish).
+pub struct JsonlStepWriter<W: Write> {
+    writer: W,
+    error: Option<std::io::Error>,
+}
+
+impl JsonlStepWriter<BufWriter<File>> {
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = File::create(path).with_context(|| format!("[JsonlStepWriter] Failed to create {}", path.display()))?;
+        Ok(Self::new(BufWriter::new(file)))
+    }
+}
+
+impl<W: Write> JsonlStepWriter<W> {
+    pub fn new(writer: W) -> Self { Self { writer, error: None } }
+
+    /// Flush the output, returning the writer or the first write error.
+    pub fn finish(mut self) -> Result<W> {
+        if let Some(error) = self.error.take() { return Err(error).context("[JsonlStepWriter] Failed to write step") }
+        self.writer.flush().context("[JsonlStepWriter] Failed to flush steps")?;
+        Ok(self.writer)
+    }
+
+    fn write(&mut self, step: &Step) -> std::io::Result<()> {
+        let metrics = step.metrics.iter()
+            .map(|&(name, score)| (name.to_string(), serde_json::json!(score)))
+            .collect::<serde_json::Map<_, _>>();
+        let line = serde_json::json!({
+            "index": step.index,
+            "phase": step.phase,
+            "accepted": step.accepted,
+            "score": step.score,
+            "metrics": metrics,
+        });
+        writeln!(self.writer, "{line}")
+    }
+}
+
+impl<W: Write> StepObserver for JsonlStepWriter<W> {
+    fn observe(&mut self, step: &Step) {
+        if self.error.is_none() && let Err(error) = self.write(step) { self.error = Some(error) }
+    }
+}
+
+/// Builds steps for an observer, with snapshots of every metric used by any of a run's objectives.
+pub(super) struct StepReporter<'a> {
+    observer: &'a mut dyn StepObserver,
+    metrics: Vec<&'a Metric>,
+}
+
+impl<'a> StepReporter<'a> {
+    pub(super) fn new(observer: &'a mut dyn StepObserver, objectives: impl IntoIterator<Item = &'a Objective>) -> Self {
+        let mut metrics: Vec<&Metric> = Vec::new();
+        for metric in objectives.into_iter().flat_map(Objective::metrics) {
+            let name = metric.to_string();
+            if !metrics.iter().any(|seen| seen.to_string() == name) { metrics.push(metric) }
+        }
+        Self { observer, metrics }
+    }
+
+    pub(super) fn report(&mut self, partition: &Partition, index: usize, phase: usize, accepted: bool, score: f64) {
+        if !self.observer.wants(index) { return }
+        let metrics = self.metrics.iter()
+            .map(|metric| (metric.short_name(), metric.compute_score(partition)))
+            .collect::<Vec<_>>();
+        self.observer.observe(&Step { index, phase, accepted, score, metrics: &metrics });
+    }
+
+    pub(super) fn stopped(&mut self, index: usize, phase: usize, reason: StopReason) {
+        self.observer.stopped(index, phase, reason)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AnnealConfig, Plan, StoppingRules, test_maps};
+
+    fn step(index: usize, metrics: &[(&str, f64)]) -> StepRecord {
+        StepRecord::from(&Step { index, phase: 1, accepted: index.is_multiple_of(2), score: index as f64, metrics })
+    }
+
+    #[test]
+    fn buffer_keeps_most_recent_steps() {
+        let mut buffer = EveryNth { inner: StepBuffer::new(2), n: 2 };
+        for index in 0..7 {
+            if buffer.wants(index) { buffer.observe(&Step { index, phase: 1, accepted: true, score: 0.0, metrics: &[] }) }
+        }
+        assert_eq!(buffer.inner.records().map(|record| record.index).collect::<Vec<_>>(), [4, 6]);
+    }
+
+    #[test]
+    fn csv_and_jsonl_rows() {
+        let metrics = [("PolsbyPopper", 0.25)];
+        let record = step(2, &metrics);
+        let step = Step { index: 2, phase: 1, accepted: true, score: 2.0, metrics: &metrics };
+
+        let mut csv = CsvStepWriter::new(Vec::new());
+        csv.observe(&step);
+        assert_eq!(String::from_utf8(csv.finish().unwrap()).unwrap(), "index,phase,accepted,score,PolsbyPopper\n2,1,true,2,0.25\n");
+
+        let mut jsonl = JsonlStepWriter::new(Vec::new());
+        jsonl.observe(&step);
+        let line: serde_json::Value = serde_json::from_slice(&jsonl.finish().unwrap()).unwrap();
+        assert_eq!(line["metrics"]["PolsbyPopper"], record.metrics[0].1);
+        assert_eq!(line["accepted"], true);
+    }
+
+    #[test]
+    fn stops_are_reported_to_observers() {
+        struct Stops(Vec<(usize, StopReason)>);
+        impl StepObserver for Stops {
+            fn observe(&mut self, _: &Step) {}
+            fn stopped(&mut self, _index: usize, phase: usize, reason: StopReason) { self.0.push((phase, reason)) }
+        }
+
+        let mut plan = Plan::new(test_maps::grid(4), 2).unwrap();
+        plan.set_assignments_vec((0..16).map(|k| k / 8 + 1).collect()).unwrap();
+        let objective = Objective::new(vec![Metric::population_deviation("pop".into())], None);
+        let config = AnnealConfig {
+            objectives: vec![objective.clone(), objective],
+            max_iter: 1000,
+            phase_start_probs: vec![0.5; 2],
+            phase_end_probs: vec![Some(0.01); 2],
+            phase_cooling_rates: vec![0.1; 2],
+            init_temp: 1.0,
+            early_stop_iters: 100,
+            temp_search_batch_size: 10,
+            batch_size: 10,
+            stop: StoppingRules { time_limit_secs: Some(0.0), ..Default::default() },
+            proposals: vec![],
+            constraints: Default::default(),
+            profile: false,
+        };
+
+        // The time limit ends the whole run during the first phase.
+        let mut stops = (EveryNth { inner: Stops(Vec::new()), n: 10 }, ());
+        plan.anneal_with_observer(&config, &mut stops).unwrap();
+        assert_eq!(stops.0.inner.0, [(1, StopReason::TimeLimit)]);
+    }
+}
@@ -0,0 +1,122 @@
+use std::{fmt, time::Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// Wall-clock time an annealing run spent in each stage of its steps, for diagnosing slow
+/// configurations. Collected when `AnnealConfig::profile` is set (ignored in WASM, which has no clock).
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunProfile {
+    pub steps: usize,            // Moves proposed
+    pub invalid: usize,          // Proposals dropped by contiguity, group, or constraint checks
+    pub proposal_secs: f64,      // Picking units and districts, and gathering chunks
+    pub contiguity_secs: f64,    // Contiguity, group, anchor, and cluster checks
+    pub metrics_secs: f64,       // Updating part totals for moves and evaluating the objective
+    pub acceptance_secs: f64,    // Metropolis test, best-plan snapshots, and reverting rejected moves
+    pub other_secs: f64,         // Progress reports, step observers, and stopping rules
+}
+
+/// Stage of an annealing step that time is charged to.
+#[derive(Clone, Copy, Debug)]
+pub(super) enum Stage { Proposal, Contiguity, Metrics, Acceptance, Other }
+
+impl RunProfile {
+    /// Total time of the run.
+    pub fn total_secs(&self) -> f64 {
+        self.proposal_secs + self.contiguity_secs + self.metrics_secs + self.acceptance_secs + self.other_secs
+    }
+
+    /// Serialize the profile to JSON.
+    pub fn to_json(&self) -> String { serde_json::to_string_pretty(self).unwrap() }
+
+    fn stage_mut(&mut self, stage: Stage) -> &mut f64 {
+        match stage {
+            Stage::Proposal => &mut self.proposal_secs,
+            Stage::Contiguity => &mut self.contiguity_secs,
+            Stage::Metrics => &mut self.metrics_secs,
+            Stage::Acceptance => &mut self.acceptance_secs,
+            Stage::Other => &mut self.other_secs,
+        }
+    }
+}
+
+impl fmt::Display for RunProfile {
+    /// Table of seconds, share of the run, and microseconds per step for each stage.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (total, steps) = (self.total_secs(), self.steps.max(1) as f64);
+        writeln!(f, "Run profile: {} steps ({} invalid) in {:.3}s", self.steps, self.invalid, total)?;
+        for (name, secs) in [
+            ("proposal", self.proposal_secs),
+            ("contiguity", self.contiguity_secs),
+            ("metrics", self.metrics_secs),
+            ("acceptance", self.acceptance_secs),
+            ("other", self.other_secs),
+        ] {
+            let share = if total > 0.0 { 100.0 * secs / total } else { 0.0 };
+            writeln!(f, "  {name:<12} {secs:>10.3}s {share:>6.1}% {:>10.2}us/step", 1e6 * secs / steps)?;
+        }
+        Ok(())
+    }
+}
+
+/// Lap timer charging the time since the previous lap to a stage. Does nothing when disabled,
+/// so the clock is never read in unprofiled runs.
+#[derive(Debug, Default)]
+pub(super) struct Profiler(Option<(RunProfile, Instant)>);
+
+impl Profiler {
+    pub(super) fn new(enabled: bool) -> Self {
+        let enabled = enabled && cfg!(not(target_arch = "wasm32"));
+        Self(enabled.then(|| (RunProfile::default(), Instant::now())))
+    }
+
+    /// Charge the time since the last lap to `stage`.
+    #[inline]
+    pub(super) fn lap(&mut self, stage: Stage) {
+        if let Some((profile, last)) = &mut self.0 {
+            let now = Instant::now();
+            *profile.stage_mut(stage) += (now - *last).as_secs_f64();
+            *last = now;
+        }
+    }
+
+    /// Count a proposed move, and whether it was dropped as invalid.
+    #[inline]
+    pub(super) fn count_step(&mut self, valid: bool) {
+        if let Some((profile, _)) = &mut self.0 {
+            profile.steps += 1;
+            profile.invalid += usize::from(!valid);
+        }
+    }
+
+    /// The collected profile, if enabled.
+    pub(super) fn finish(mut self) -> Option<RunProfile> {
+        self.lap(Stage::Other);
+        self.0.map(|(profile, _)| profile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_profiler_collects_nothing() {
+        let mut profiler = Profiler::new(false);
+        profiler.lap(Stage::Metrics);
+        profiler.count_step(true);
+        assert_eq!(profiler.finish(), None);
+    }
+
+    #[test]
+    fn laps_are_charged_to_stages() {
+        let mut profiler = Profiler::new(true);
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        profiler.lap(Stage::Metrics);
+        profiler.count_step(false);
+        let profile = profiler.finish().unwrap();
+        assert!(profile.metrics_secs >= 0.002);
+        assert_eq!(profile.proposal_secs, 0.0);
+        assert_eq!((profile.steps, profile.invalid), (1, 1));
+        assert!(profile.to_string().contains("metrics"));
+    }
+}
@@ -0,0 +1,212 @@
+use std::{fmt, str::FromStr, sync::Arc};
+
+use anyhow::{Result, bail};
+use rand::{Rng, seq::IndexedRandom};
+use serde::{Deserialize, Serialize};
+
+use crate::partition::Partition;
+
+use super::profile::{Profiler, Stage};
+
+/// Kind of move proposed at each step of an annealing run.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Proposal {
+    /// Move one boundary unit (with its group or articulation bundle) to a neighboring district.
+    Flip,
+    /// Move a boundary unit to a neighboring district, and a boundary unit of that district back.
+    Swap,
+    /// Move the connected piece of a coarser unit (e.g. `"county"` or `"group"`) lying in the
+    /// source district to a neighboring district, all at once.
+    Chunk { layer: String },
+}
+
+impl fmt::Display for Proposal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Flip => f.write_str("flip"),
+            Self::Swap => f.write_str("swap"),
+            Self::Chunk { layer } => write!(f, "chunk:{layer}"),
+        }
+    }
+}
+
+impl FromStr for Proposal {
+    type Err = anyhow::Error;
+
+    /// Parse `"flip"`, `"swap"`, or `"chunk:<layer>"`.
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "flip" => Self::Flip,
+            "swap" => Self::Swap,
+            _ => match s.strip_prefix("chunk:") {
+                Some(layer) if !layer.is_empty() => Self::Chunk { layer: layer.to_string() },
+                _ => bail!("[Proposal] Unknown proposal '{s}' (expected flip, swap, or chunk:<layer>)"),
+            },
+        })
+    }
+}
+
+/// A proposal kind and its relative frequency in a run.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WeightedProposal {
+    #[serde(flatten)]
+    pub proposal: Proposal,
+    #[serde(default = "WeightedProposal::default_weight")]
+    pub weight: f64,
+}
+
+impl WeightedProposal {
+    fn default_weight() -> f64 { 1.0 }
+}
+
+/// A proposal resolved against a partition.
+#[derive(Clone, Debug)]
+pub(crate) enum Kernel {
+    Flip,
+    Swap,
+    Chunk(Arc<[u32]>), // Coarser unit index of each node
+}
+
+/// Weighted mix of kernels, sampled once per step.
+#[derive(Clone, Debug)]
+pub(crate) struct KernelMix {
+    kernels: Vec<(Kernel, f64)>,
+}
+
+impl KernelMix {
+    /// Build a mix from kernels and their weights. An empty mix only flips.
+    pub(crate) fn new(kernels: Vec<(Kernel, f64)>) -> Result<Self> {
+        if kernels.iter().any(|&(_, weight)| !weight.is_finite() || weight < 0.0) {
+            bail!("[KernelMix] Proposal weights must be finite and non-negative")
+        }
+        if !kernels.is_empty() && kernels.iter().all(|&(_, weight)| weight == 0.0) {
+            bail!("[KernelMix] At least one proposal weight must be positive")
+        }
+        Ok(Self { kernels })
+    }
+
+    /// Single-unit flips only.
+    pub(crate) fn flip() -> Self { Self { kernels: vec![] } }
+
+    fn sample(&self, rng: &mut impl Rng) -> &Kernel {
+        match self.kernels.as_slice() {
+            [] => &Kernel::Flip,
+            [(kernel, _)] => kernel,
+            kernels => &kernels.choose_weighted(rng, |&(_, weight)| weight).unwrap().0,
+        }
+    }
+}
+
+/// Nodes moved by an applied proposal: each piece moves from its first part to its second, in order.
+pub(super) type AppliedMove = Vec<(Vec<usize>, u32, u32)>;
+
+impl Partition {
+    /// Draw a kernel from `mix` and apply the move it proposes from a random boundary unit.
+    /// Returns `None`, leaving the partition unchanged, if the move would violate contiguity,
+    /// a group, or an anchor. Time spent is charged to `profiler`'s stages.
+    pub(super) fn apply_proposal(&mut self, mix: &KernelMix, rng: &mut impl Rng, profiler: &mut Profiler) -> Option<AppliedMove> {
+        // Pick random source part (weighted by frontier size), boundary node, and destination part.
+        let src = self.random_part_weighted_by_frontier(rng)?;
+        let candidates = self.frontiers.get(src as usize);
+        let node = candidates[rng.random_range(0..candidates.len())];
+        let dest = self.random_neighboring_part(node, rng)?;
+        let kernel = mix.sample(rng);
+        profiler.lap(Stage::Proposal);
+
+        match kernel {
+            Kernel::Flip => {
+                let subgraph = self.move_proposal(node, dest);
+                profiler.lap(Stage::Contiguity);
+                let subgraph = subgraph?;
+                self.apply_move(&subgraph, dest);
+                profiler.lap(Stage::Metrics);
+                Some(vec![(subgraph, src, dest)])
+            }
+            Kernel::Swap => {
+                let forward = self.move_proposal(node, dest);
+                profiler.lap(Stage::Contiguity);
+                let forward = forward?;
+                self.apply_move(&forward, dest);
+                profiler.lap(Stage::Metrics);
+
+                // Pick a unit of `dest` bordering `src`, other than the ones just moved.
+                let back = self.with_scratch(|this, scratch| {
+                    scratch.boundary.clear();
+                    scratch.boundary.extend(this.frontiers.get(dest as usize).iter().copied()
+                        .filter(|&v| !forward.contains(&v) && this.node_borders_part(v, src)));
+                    scratch.boundary.choose(rng).copied()
+                });
+                profiler.lap(Stage::Proposal);
+                let backward = back.and_then(|v| self.move_proposal(v, src));
+                profiler.lap(Stage::Contiguity);
+                let applied = match backward {
+                    Some(backward) => {
+                        self.apply_move(&backward, src);
+                        Some(vec![(forward, src, dest), (backward, dest, src)])
+                    }
+                    None => { self.apply_move(&forward, src); None }
+                };
+                profiler.lap(Stage::Metrics);
+                applied
+            }
+            Kernel::Chunk(labels) => {
+                let chunk = self.chunk_within_part(node, labels);
+                profiler.lap(Stage::Proposal);
+                if chunk.len() == self.parts.get(src as usize).len() { return None } // would empty the district
+                let valid = self.covers_groups(&chunk) && self.can_move_nodes(&chunk, dest)
+                    && self.check_subgraph_contiguity(&chunk, dest);
+                profiler.lap(Stage::Contiguity);
+                if !valid { return None }
+                self.apply_move(&chunk, dest);
+                profiler.lap(Stage::Metrics);
+                Some(vec![(chunk, src, dest)])
+            }
+        }
+    }
+
+    /// Undo a move returned by `apply_proposal`.
+    pub(super) fn revert_proposal(&mut self, applied: &AppliedMove) {
+        for (nodes, from, _) in applied.iter().rev() { self.apply_move(nodes, *from) }
+    }
+
+    /// Nodes connected to `node` within its part that share its label.
+    fn chunk_within_part(&mut self, node: usize, labels: &[u32]) -> Vec<usize> {
+        self.with_scratch(|this, scratch| {
+            let (part, label) = (this.assignment(node), labels[node]);
+            let mut chunk = vec![node];
+            let (seen, queue) = (&mut scratch.visited, &mut scratch.queue);
+            seen.clear();
+            seen.insert(node);
+            queue.clear();
+            queue.push_back(node);
+            while let Some(u) = queue.pop_front() {
+                for v in this.graph().edges(u) {
+                    if labels[v] == label && this.assignment(v) == part && seen.insert(v) {
+                        chunk.push(v);
+                        queue.push_back(v);
+                    }
+                }
+            }
+            chunk
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proposals_parse_and_serialize() {
+        for text in ["flip", "swap", "chunk:county"] {
+            assert_eq!(text.parse::<Proposal>().unwrap().to_string(), text);
+        }
+        assert!("chunk:".parse::<Proposal>().is_err());
+
+        let weighted: Vec<WeightedProposal> = serde_json::from_str(
+            r#"[{"type": "flip", "weight": 0.8}, {"type": "chunk", "layer": "group"}]"#).unwrap();
+        assert_eq!(weighted[1], WeightedProposal { proposal: Proposal::Chunk { layer: "group".into() }, weight: 1.0 });
+        assert!(KernelMix::new(vec![(Kernel::Flip, 0.0)]).is_err());
+    }
+}
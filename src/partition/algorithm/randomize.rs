@@ -54,28 +54,56 @@ impl Partition {
             .choose(rng)
     }
 
+    /// Select a random part neighboring an unassigned node that the node may join.
+    fn random_joinable_part<R: Rng + ?Sized>(&self, node: usize, rng: &mut R) -> Option<u32> {
+        self.graph().edges(node)
+            .map(|v| self.assignment(v))
+            .filter(|&p| p != 0 && self.can_move_node(node, p))
+            .choose(rng)
+    }
+
     /// Select a random part, weighted by frontier size.
     pub(crate) fn random_part_weighted_by_frontier<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<u32> {
-        let weights = self.frontiers.iter()
-            .map(|set| set.len().saturating_sub(1))
+        // Part 0 only holds the at-large area (if anything), which never moves.
+        let weights = self.frontiers.iter().enumerate()
+            .map(|(part, set)| if part == 0 { 0 } else { set.len().saturating_sub(1) })
             .collect::<Vec<_>>();
         let dist = WeightedIndex::new(&weights).ok()?; // None if all weights are zero
         Some(dist.sample(rng) as u32)
     }
 
+    /// Move a node to a part, together with the rest of its group (if groups are set).
+    fn move_group(&mut self, node: usize, part: u32) {
+        match self.group_members(node).map(|group| group.to_vec()) {
+            Some(group) => self.move_subgraph(&group, part, false),
+            None => self.move_node(node, part, false),
+        }
+    }
+
     /// Randomly assign all nodes to contiguous parts.
     pub(crate) fn randomize(&mut self) {
         let mut rng = rand::rng();
         self.clear_assignments();
 
-        // Seed parts with random starting nodes.
+        // Seed anchored parts with their anchors, and the remaining parts with random starting nodes
+        // (from their own cluster, if clusters are set, and outside the at-large area).
+        self.restore_anchors();
         for part in 1..self.num_parts() {
-            self.move_node(self.random_unassigned_node(&mut rng).unwrap(), part, false);
+            if self.part_is_empty(part) {
+                let node = match self.has_clusters() || self.has_at_large() {
+                    true => self.parts.get(0).iter().copied().filter(|&u| self.can_move_node(u, part)).choose(&mut rng),
+                    false => self.random_unassigned_node(&mut rng),
+                };
+                self.move_group(node.expect("every part needs an unassigned node to start from"), part);
+            }
         }
 
-        // Expand parts until all nodes are assigned.
+        // Expand parts until all nodes are assigned, or no unassigned node borders a part it may join.
         while let Some(u) = self.random_unassigned_boundary_node(&mut rng) {
-            self.move_node(u, self.random_neighboring_part(u, &mut rng).unwrap(), false);
+            match self.random_joinable_part(u, &mut rng) {
+                Some(part) => self.move_group(u, part),
+                None => if self.frontiers.get(0).iter().all(|&v| self.random_joinable_part(v, &mut rng).is_none()) { break },
+            }
         }
     }
 }
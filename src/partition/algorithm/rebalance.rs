@@ -0,0 +1,129 @@
+use std::collections::VecDeque;
+
+use crate::partition::Partition;
+
+impl Partition {
+    /// Deterministically repair balance of a weight series, e.g. after manual edits or layer translation.
+    ///
+    /// Each round takes the part deviating most from the ideal total and routes weight between it
+    /// and the nearest part deviating the other way, along a shortest path of adjacent parts:
+    /// every part on the path passes frontier units to the next, so only the two ends change total.
+    /// Returns `true` once every part is within `tolerance` (a fraction of the ideal total), or
+    /// `false` if no route can make progress or `max_rounds` is reached.
+    pub(crate) fn rebalance(&mut self, series: &str, tolerance: f64, max_rounds: usize) -> bool {
+        assert!(self.num_parts() > 2, "need at least two parts to rebalance");
+        assert!(self.unit_weights().contains(series), "series '{}' not found in node weights", series);
+
+        let totals = self.part_totals(series);
+        let target = totals[1..].iter().sum::<f64>() / (self.num_parts() - 1) as f64;
+        let allowed = target * tolerance;
+
+        for _ in 0..max_rounds {
+            let deviations = self.part_totals(series).iter().map(|&total| total - target).collect::<Vec<_>>();
+            let (worst, &deviation) = deviations.iter().enumerate().skip(1)
+                .max_by(|(_, a), (_, b)| a.abs().total_cmp(&b.abs()))
+                .unwrap();
+            if deviation.abs() <= allowed { return true }
+
+            // Try counterparts deviating the other way, nearest first, until one route moves weight.
+            let adjacency = self.part_adjacency();
+            let paths = shortest_paths(&adjacency, worst as u32);
+            let mut counterparts = (1..self.num_parts() as usize)
+                .filter(|&part| deviations[part] * deviation < 0.0 && paths[part].is_some())
+                .collect::<Vec<_>>();
+            counterparts.sort_by_key(|&part| (paths[part].as_ref().map_or(usize::MAX, Vec::len), part));
+
+            let progressed = counterparts.into_iter().any(|part| {
+                let mut path = paths[part].clone().unwrap();
+                if deviation < 0.0 { path.reverse() } // route from surplus to deficit
+                let amount = deviation.abs().min(deviations[part].abs());
+                self.shift_along_path(series, &path, amount, allowed / 2.0) > 0.0
+            });
+            if !progressed { return false }
+        }
+
+        false
+    }
+
+    /// Pass about `amount` of `series` along a path of adjacent parts, each part handing the next
+    /// as much as it received. Returns the amount that reached the last part.
+    fn shift_along_path(&mut self, series: &str, path: &[u32], amount: f64, slack: f64) -> f64 {
+        let mut amount = amount;
+        for pair in path.windows(2) {
+            amount = self.shift_weight(series, pair[0], pair[1], amount, slack);
+            if amount <= 0.0 { return 0.0 }
+        }
+        amount
+    }
+
+    /// Move frontier units of `src` bordering `dest` into `dest`, heaviest first, until about
+    /// `amount` (within `slack`) has moved. Returns the amount moved.
+    fn shift_weight(&mut self, series: &str, src: u32, dest: u32, amount: f64, slack: f64) -> f64 {
+        let mut moved = 0.0;
+        loop {
+            let mut candidates = self.frontiers.get(src as usize).iter().copied()
+                .filter(|&u| self.node_borders_part(u, dest))
+                .map(|u| (u, self.unit_weights().get_as_f64(series, u).unwrap()))
+                .filter(|&(_, weight)| weight > 0.0)
+                .collect::<Vec<_>>();
+            candidates.sort_by(|(u, a), (v, b)| b.total_cmp(a).then(u.cmp(v)));
+
+            let mut progressed = false;
+            for (node, weight) in candidates {
+                let remaining = amount - moved;
+                if remaining <= slack { return moved }
+                if weight > remaining + slack { continue }
+
+                // Earlier moves in this pass may have taken the node or cut it off from `dest`.
+                if self.assignment(node) != src || !self.node_borders_part(node, dest) { continue }
+                let Some(subgraph) = self.move_proposal(node, dest) else { continue };
+                if subgraph.len() >= self.parts.get(src as usize).len() { continue } // would empty the part
+
+                let weight = subgraph.iter().map(|&u| self.unit_weights().get_as_f64(series, u).unwrap()).sum::<f64>();
+                if weight > remaining + slack { continue }
+                self.apply_move(&subgraph, dest);
+                moved += weight;
+                progressed = true;
+            }
+            if !progressed { return moved }
+        }
+    }
+}
+
+/// Shortest path (by number of hops) from `start` to every part, over a part adjacency list.
+fn shortest_paths(adjacency: &[Vec<u32>], start: u32) -> Vec<Option<Vec<u32>>> {
+    let mut previous = vec![None; adjacency.len()];
+    previous[start as usize] = Some(start);
+    let mut queue = VecDeque::from([start]);
+    while let Some(part) = queue.pop_front() {
+        for &next in &adjacency[part as usize] {
+            if previous[next as usize].is_none() {
+                previous[next as usize] = Some(part);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    (0..adjacency.len() as u32).map(|end| {
+        previous[end as usize]?;
+        let mut path = vec![end];
+        while *path.last().unwrap() != start { path.push(previous[*path.last().unwrap() as usize].unwrap()) }
+        path.reverse();
+        Some(path)
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shortest_paths_follow_adjacency() {
+        // 1 - 2 - 3, and 4 unreachable.
+        let adjacency = vec![vec![], vec![2], vec![1, 3], vec![2], vec![]];
+        let paths = shortest_paths(&adjacency, 1);
+        assert_eq!(paths[3], Some(vec![1, 2, 3]));
+        assert_eq!(paths[1], Some(vec![1]));
+        assert_eq!(paths[4], None);
+    }
+}
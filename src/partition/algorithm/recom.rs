@@ -0,0 +1,34 @@
+use rand::{Rng, seq::IndexedRandom};
+
+use crate::partition::Partition;
+
+/// Spanning trees drawn for a district pair before a ReCom step gives up and leaves the plan unchanged.
+const TREE_ATTEMPTS: usize = 8;
+
+impl Partition {
+    /// Run `steps` steps of a ReCom (recombination) chain, as in GerryChain: each step picks a
+    /// pair of adjacent parts uniformly at random, merges them, draws a random spanning tree of the
+    /// union, and cuts it at an edge chosen uniformly among those leaving both halves' `series`
+    /// totals within `bounds`. If none of a few trees has such an edge, the step leaves the
+    /// partition unchanged (a self-loop of the chain). Returns the number of steps that changed
+    /// the partition.
+    pub(crate) fn recom_chain(&mut self, series: &str, bounds: (f64, f64), steps: usize, rng: &mut impl Rng) -> usize {
+        let mut pairs = self.adjacent_part_pairs();
+        let mut accepted = 0;
+        for _ in 0..steps {
+            if let Some(&(a, b)) = pairs.choose(rng)
+                && (0..TREE_ATTEMPTS).any(|_| self.recombine_parts_bounded(a, b, series, bounds, rng)) {
+                accepted += 1;
+                pairs = self.adjacent_part_pairs();
+            }
+        }
+        accepted
+    }
+
+    /// Every pair `(a, b)` of adjacent assigned parts, with `a < b`.
+    pub(crate) fn adjacent_part_pairs(&self) -> Vec<(u32, u32)> {
+        self.part_adjacency().into_iter().enumerate()
+            .flat_map(|(a, neighbors)| neighbors.into_iter().filter(move |&b| b > a as u32).map(move |b| (a as u32, b)))
+            .collect()
+    }
+}
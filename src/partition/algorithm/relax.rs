@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+
+use rand::Rng;
+
+use crate::{
+    Objective, RelaxedConstraint,
+    objective::ConstraintKind,
+    partition::{Partition, algorithm::{anneal::{accept_metropolis, temp_geometric}, observer::{StepObserver, StepReporter}, stopping::{Stopper, StoppingRules}}},
+};
+
+/// Fraction of the run spent tightening bounds; the remainder runs at the target bounds.
+const TIGHTEN_FRACTION: f64 = 0.8;
+
+/// Incrementally maintained count of county splits.
+struct SplitCounter {
+    counties: Vec<u32>,                 // County index for each node
+    counts: HashMap<(u32, u32), u32>,   // Number of nodes of (county, part)
+    parts_per_county: Vec<u32>,         // Number of distinct parts touching each county
+    splits: usize,                      // Sum over counties of (parts touched - 1)
+}
+
+impl SplitCounter {
+    /// Build a counter from the county index of each node and the current assignments.
+    fn new(counties: &[u32], assignments: &[u32]) -> Self {
+        let num_counties = counties.iter().max().map_or(0, |&c| c as usize + 1);
+        let mut counter = Self {
+            counties: counties.to_vec(),
+            counts: HashMap::new(),
+            parts_per_county: vec![0; num_counties],
+            splits: 0,
+        };
+        for (node, &part) in assignments.iter().enumerate() { counter.add(node, part) }
+        counter
+    }
+
+    /// Record a node being added to a part.
+    fn add(&mut self, node: usize, part: u32) {
+        let county = self.counties[node];
+        let count = self.counts.entry((county, part)).or_insert(0);
+        *count += 1;
+        if *count == 1 {
+            self.parts_per_county[county as usize] += 1;
+            if self.parts_per_county[county as usize] > 1 { self.splits += 1 }
+        }
+    }
+
+    /// Record a node being removed from a part.
+    fn remove(&mut self, node: usize, part: u32) {
+        let county = self.counties[node];
+        let count = self.counts.get_mut(&(county, part)).expect("node must be counted in its part");
+        *count -= 1;
+        if *count == 0 {
+            self.counts.remove(&(county, part));
+            if self.parts_per_county[county as usize] > 1 { self.splits -= 1 }
+            self.parts_per_county[county as usize] -= 1;
+        }
+    }
+
+    /// Record a set of nodes moving between parts.
+    fn apply_move(&mut self, nodes: &[usize], src: u32, dest: u32) {
+        for &node in nodes {
+            self.remove(node, src);
+            self.add(node, dest);
+        }
+    }
+}
+
+impl Partition {
+    /// Current value of a constrained quantity (max absolute deviation, total deviation, or number of splits).
+    fn constraint_value(&self, constraint: &RelaxedConstraint, splits: Option<&SplitCounter>) -> f64 {
+        match &constraint.kind {
+            ConstraintKind::PopulationDeviation { pop_series } => (1..self.num_parts())
+                .map(|part| self.absolute_population_deviation(part, pop_series))
+                .fold(0.0, f64::max),
+            ConstraintKind::TotalDeviation { pop_series } => {
+                if let Some(totals) = (1..self.num_parts()).map(|part| self.exact_totals(pop_series, part)).collect::<Option<Vec<_>>>() {
+                    let region = totals[0].1;
+                    let (min, max) = totals.iter().fold((i64::MAX, i64::MIN), |(min, max), &(total, _)| (min.min(total), max.max(total)));
+                    return ((max - min) as i128 * (self.num_parts() - 1) as i128) as f64 / region as f64
+                }
+                let (min, max) = (1..self.num_parts())
+                    .map(|part| self.population_deviation(part, pop_series))
+                    .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), dev| (min.min(dev), max.max(dev)));
+                max - min
+            }
+            ConstraintKind::CountySplits =>
+                splits.expect("county splits constraint requires county labels").splits as f64,
+        }
+    }
+
+    /// Total amount by which the current plan exceeds the given bounds.
+    fn constraint_violation(&self, constraints: &[RelaxedConstraint], bounds: &[f64], splits: Option<&SplitCounter>) -> f64 {
+        constraints.iter().zip(bounds).map(|(constraint, &bound)| match &constraint.kind {
+            ConstraintKind::PopulationDeviation { pop_series } => (1..self.num_parts())
+                .map(|part| (self.absolute_population_deviation(part, pop_series) - bound).max(0.0))
+                .sum::<f64>(),
+            ConstraintKind::TotalDeviation { .. } | ConstraintKind::CountySplits =>
+                (self.constraint_value(constraint, splits) - bound).max(0.0),
+        }).sum()
+    }
+
+    /// Run simulated annealing with hard constraints that start relaxed and tighten over the run.
+    ///
+    /// Each constraint's bound moves linearly from its initial value (by default the seed plan's
+    /// current value) to its target over the first 80% of the run. The score being maximized is
+    /// `objective - penalty * violation`, where the penalty weight grows geometrically from
+    /// `initial_penalty` to `final_penalty` (the "big-M" term).
+    ///
+    /// `counties` gives the county index of each node, and is required for county split constraints.
+    /// Returns `true` if a plan satisfying all target bounds was found, in which case the partition
+    /// is restored to the best such plan; otherwise the partition is left at its final state.
+    /// `steps` is called after each proposed move it wants, with the penalized score.
+    /// `stop` is checked every 1000 moves, against the best feasible score found so far.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn anneal_relaxed(&mut self,
+        objective: &Objective,
+        constraints: &[RelaxedConstraint],
+        counties: Option<&[u32]>,
+        max_iter: usize,
+        initial_temp: f64,
+        final_temp: f64,
+        initial_penalty: f64,
+        final_penalty: f64,
+        steps: &mut dyn StepObserver,
+        stop: &StoppingRules,
+    ) -> bool {
+        debug_assert!(self.only_at_large_unassigned(), "part 0 (unassigned) must hold only the at-large area");
+        debug_assert!(self.num_parts() > 2, "need at least two parts for annealing");
+        debug_assert!(max_iter > 0, "max_iter must be > 0");
+        debug_assert!(initial_penalty > 0.0 && final_penalty > 0.0, "penalties must be > 0");
+        debug_assert!(constraints.iter().filter_map(RelaxedConstraint::pop_series).all(|series| self.part_weights().contains(series)),
+            "part_weights must contain every constrained series");
+
+        let mut rng = rand::rng();
+        let mut splits = counties.map(|counties| {
+            debug_assert_eq!(counties.len(), self.num_nodes(), "counties length must match number of nodes");
+            SplitCounter::new(counties, &self.assignments())
+        });
+        debug_assert!(splits.is_some() || !constraints.iter().any(|c| c.needs_counties()),
+            "county splits constraint requires county labels");
+
+        let initial_bounds = constraints.iter()
+            .map(|c| c.initial.unwrap_or_else(|| self.constraint_value(c, splits.as_ref())))
+            .collect::<Vec<_>>();
+        let target_bounds = constraints.iter().map(|c| c.target).collect::<Vec<_>>();
+        let tighten_iters = (max_iter as f64 * TIGHTEN_FRACTION).max(1.0);
+
+        let mut best_feasible: Option<(f64, Vec<u32>)> = None;
+        let mut steps = StepReporter::new(steps, [objective]);
+        let mut stopper = Stopper::new(stop);
+
+        for i in 0..max_iter {
+            if i > 0 && i.is_multiple_of(1000) {
+                let best_score = best_feasible.as_ref().map_or(f64::NEG_INFINITY, |(score, _)| *score);
+                if let Some(reason) = stopper.check(self, i, best_score) {
+                    steps.stopped(i, 1, reason);
+                    break
+                }
+            }
+
+            let t = i as f64 / tighten_iters;
+            let bounds = constraints.iter().zip(&initial_bounds)
+                .map(|(c, &initial)| c.bound_at(initial, t))
+                .collect::<Vec<_>>();
+            let penalty = temp_geometric(initial_penalty, final_penalty, max_iter, i);
+            let temp = temp_geometric(initial_temp, final_temp, max_iter, i);
+
+            let current_score = objective.compute(self)
+                - penalty * self.constraint_violation(constraints, &bounds, splits.as_ref());
+
+            // Pick random source part (weighted by frontier size), boundary node, and destination part.
+            let Some(src) = self.random_part_weighted_by_frontier(&mut rng) else { break };
+            let candidates = self.frontiers.get(src as usize);
+            let node = candidates[rng.random_range(0..candidates.len())];
+            let Some(dest) = self.random_neighboring_part(node, &mut rng) else { continue };
+
+            // Collect articulation bundle (if necessary to maintain contiguity)
+            let Some(subgraph) = self.move_proposal(node, dest) else { continue };
+
+            self.apply_move(&subgraph, dest);
+            if let Some(splits) = splits.as_mut() { splits.apply_move(&subgraph, src, dest) }
+
+            let new_score = objective.compute(self)
+                - penalty * self.constraint_violation(constraints, &bounds, splits.as_ref());
+
+            let accepted = accept_metropolis(new_score - current_score, temp, &mut rng);
+            if accepted {
+                if self.constraint_violation(constraints, &target_bounds, splits.as_ref()) == 0.0 {
+                    let score = objective.compute(self);
+                    if best_feasible.as_ref().is_none_or(|(best, _)| score > *best) {
+                        best_feasible = Some((score, self.assignments()));
+                    }
+                }
+            } else {
+                self.apply_move(&subgraph, src);
+                if let Some(splits) = splits.as_mut() { splits.apply_move(&subgraph, dest, src) }
+            }
+            steps.report(self, i, 1, accepted, if accepted { new_score } else { current_score });
+        }
+
+        match best_feasible {
+            Some((_, assignments)) => { self.set_assignments(assignments); true }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Metric, Objective, Plan, RelaxedConstraint, test_maps};
+
+    fn objective() -> Objective {
+        Objective::new(vec![Metric::population_deviation_smooth("pop".into())], None)
+    }
+
+    #[test]
+    fn feasible_plans_are_kept() {
+        // Start with a 4/12 split of the 16 blocks, far outside the 5% target.
+        let mut plan = Plan::new(test_maps::grid(4), 2).unwrap();
+        plan.set_assignments_vec((0..16).map(|k| if k < 4 { 1 } else { 2 }).collect()).unwrap();
+        let constraints = [RelaxedConstraint::population_deviation("pop".into(), 0.05)];
+        assert!(plan.anneal_relaxed(&objective(), &constraints, 5000, 1.0, 1e-4, 1.0, 1000.0).unwrap());
+
+        let assignments = plan.get_assignments_vec().unwrap();
+        assert_eq!(assignments.iter().filter(|&&part| part == 1).count(), 8);
+    }
+
+    #[test]
+    fn infeasible_targets_are_reported() {
+        // Three districts can't split 16 equal blocks evenly.
+        let mut plan = Plan::new(test_maps::grid(4), 3).unwrap();
+        plan.randomize().unwrap();
+        let constraints = [RelaxedConstraint::population_deviation("pop".into(), 0.0)];
+        assert!(!plan.anneal_relaxed(&objective(), &constraints, 2000, 1.0, 1e-4, 1.0, 1000.0).unwrap());
+        assert!(plan.get_assignments_vec().unwrap().iter().all(|&part| part != 0));
+    }
+}
@@ -0,0 +1,151 @@
+use std::{fmt, time::{Duration, Instant}};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Metric, partition::Partition};
+
+/// Rules for ending an optimization run early, checked after each batch of moves.
+///
+/// The time limit ends the whole run. The other rules judge convergence of the current
+/// objective, so in a multi-phase run they end the current phase and move on to the next.
+/// The plan is then restored to the best state found, as at the end of a full run.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StoppingRules {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_improvement_iters: Option<usize>, // Stop after this many steps without a new best score
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_score: Option<f64>,           // Stop once the best score reaches this value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_limit_secs: Option<f64>,        // Wall-clock budget for the run (not available in WASM)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plateau: Option<PlateauRule>,
+}
+
+/// Stop when a metric's score stays within `tolerance` of one value for `window` steps.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PlateauRule {
+    pub metric: Metric,
+    pub window: usize,
+    #[serde(default = "PlateauRule::default_tolerance")]
+    pub tolerance: f64,
+}
+
+impl PlateauRule {
+    fn default_tolerance() -> f64 { 1e-6 }
+}
+
+impl StoppingRules {
+    /// Whether no rule is set.
+    pub fn is_empty(&self) -> bool {
+        self.no_improvement_iters.is_none() && self.target_score.is_none()
+            && self.time_limit_secs.is_none() && self.plateau.is_none()
+    }
+}
+
+/// Which stopping rule fired.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StopReason {
+    NoImprovement,
+    TargetScore,
+    TimeLimit,
+    Plateau,
+}
+
+impl StopReason {
+    /// Whether the rule ends the whole run, rather than the current phase.
+    #[inline] pub fn ends_run(&self) -> bool { *self == Self::TimeLimit }
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::NoImprovement => "no improvement",
+            Self::TargetScore => "target score reached",
+            Self::TimeLimit => "time limit reached",
+            Self::Plateau => "metric plateau",
+        })
+    }
+}
+
+/// Tracks a run against its stopping rules.
+pub(super) struct Stopper {
+    rules: StoppingRules,
+    deadline: Option<Instant>,
+    best: (f64, usize),              // Best score this phase, and the step it was reached
+    plateau: Option<(f64, usize)>,   // Plateau metric score, and the step it was first seen
+}
+
+impl Stopper {
+    pub(super) fn new(rules: &StoppingRules) -> Self {
+        // Only read the clock if there is a time limit, since `Instant` is unavailable in WASM.
+        let deadline = rules.time_limit_secs.map(|secs| Instant::now() + Duration::from_secs_f64(secs.max(0.0)));
+        Self { rules: rules.clone(), deadline, best: (f64::NEG_INFINITY, 0), plateau: None }
+    }
+
+    /// Reset the convergence rules at the start of a phase.
+    pub(super) fn start_phase(&mut self, iter: usize) {
+        self.best = (f64::NEG_INFINITY, iter);
+        self.plateau = None;
+    }
+
+    /// Check every rule at step `iter`, given the best score so far in this phase.
+    pub(super) fn check(&mut self, partition: &Partition, iter: usize, best_score: f64) -> Option<StopReason> {
+        let plateau_score = self.rules.plateau.as_ref().map(|rule| rule.metric.compute_score(partition));
+        self.check_scores(iter, best_score, plateau_score)
+    }
+
+    fn check_scores(&mut self, iter: usize, best_score: f64, plateau_score: Option<f64>) -> Option<StopReason> {
+        if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) { return Some(StopReason::TimeLimit) }
+        if self.rules.target_score.is_some_and(|target| best_score >= target) { return Some(StopReason::TargetScore) }
+
+        if best_score > self.best.0 { self.best = (best_score, iter) }
+        if self.rules.no_improvement_iters.is_some_and(|n| iter - self.best.1 >= n) {
+            return Some(StopReason::NoImprovement)
+        }
+
+        if let (Some(rule), Some(score)) = (&self.rules.plateau, plateau_score) {
+            match self.plateau {
+                Some((value, since)) if (score - value).abs() <= rule.tolerance => {
+                    if iter - since >= rule.window { return Some(StopReason::Plateau) }
+                }
+                _ => self.plateau = Some((score, iter)),
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convergence_rules_fire_and_reset_per_phase() {
+        let rules: StoppingRules = serde_json::from_str(r#"{
+            "no_improvement_iters": 3000,
+            "plateau": { "metric": { "type": "compactness_polsby_popper" }, "window": 2000 }
+        }"#).unwrap();
+        assert_eq!(rules.plateau.as_ref().unwrap().tolerance, 1e-6);
+
+        let mut stopper = Stopper::new(&rules);
+        assert_eq!(stopper.check_scores(1000, 1.0, Some(0.5)), None);
+        assert_eq!(stopper.check_scores(2000, 1.1, Some(0.6)), None);
+        assert_eq!(stopper.check_scores(3000, 1.2, Some(0.6)), None);
+        assert_eq!(stopper.check_scores(4000, 1.3, Some(0.6)), Some(StopReason::Plateau));
+
+        stopper.start_phase(4000);
+        assert_eq!(stopper.check_scores(5000, 1.0, Some(0.1)), None);
+        assert_eq!(stopper.check_scores(6000, 2.0, Some(0.2)), None);
+        assert_eq!(stopper.check_scores(8000, 2.0, Some(0.3)), None);
+        assert_eq!(stopper.check_scores(9000, 2.0, Some(0.4)), Some(StopReason::NoImprovement));
+    }
+
+    #[test]
+    fn time_limit_ends_the_run() {
+        let rules = StoppingRules { time_limit_secs: Some(0.0), target_score: Some(1.0), ..Default::default() };
+        let reason = Stopper::new(&rules).check_scores(0, 2.0, None).unwrap();
+        assert!(reason == StopReason::TimeLimit && reason.ends_run());
+        assert!(StoppingRules::default().is_empty() && !rules.is_empty());
+    }
+}
@@ -21,7 +21,7 @@ impl Partition {
         boundary_factor: f64,
         candidates_per_iter: usize,
     ) {
-        assert!(self.parts.get(0).is_empty(), "part 0 (unassigned) must be empty");
+        assert!(self.only_at_large_unassigned(), "part 0 (unassigned) must hold only the at-large area");
         assert!(self.num_parts() > 2, "need at least two parts for tabu_balance");
         assert!(
             self.part_weights().contains(series),
@@ -72,7 +72,7 @@ impl Partition {
             let mut best_move_node: Option<usize> = None;
             let mut best_move_src: u32 = 0;
             let mut best_move_dest: u32 = 0;
-            let mut best_move_bundle: Vec<usize> = Vec::new();
+            let mut best_move_subgraph: Vec<usize> = Vec::new();
             let mut best_move_new_cost: f64 = f64::INFINITY;
 
             // Sample a subset of the neighborhood (for speed).
@@ -104,11 +104,11 @@ impl Partition {
 
                 for &dest in &dest_parts {
                     // Maintain contiguity; compute dangling bundle if needed.
-                    let bundle = if self.check_node_contiguity(node, dest) {
-                        Vec::new()
-                    } else {
-                        self.cut_subgraph_within_part(node)
+                    // Skip moves that would violate group or anchor constraints.
+                    let Some(subgraph) = self.move_proposal(node, dest) else {
+                        continue;
                     };
+                    let bundle = &subgraph[..subgraph.len() - 1];
 
                     // Skip moves that would empty the source district.
                     if bundle.len() + 1 >= self.parts.get(src as usize).len() {
@@ -180,7 +180,7 @@ impl Partition {
                         best_move_node = Some(node);
                         best_move_src = src;
                         best_move_dest = dest;
-                        best_move_bundle = bundle;
+                        best_move_subgraph = subgraph;
                     }
                 }
             }
@@ -191,13 +191,7 @@ impl Partition {
             };
 
             // --- 4. Apply the chosen move ---
-            if best_move_bundle.is_empty() {
-                self.move_node(node, best_move_dest, false);
-            } else {
-                let mut subgraph = best_move_bundle.clone();
-                subgraph.push(node);
-                self.move_subgraph(&subgraph, best_move_dest, false);
-            }
+            self.apply_move(&best_move_subgraph, best_move_dest);
 
             current_cost = best_move_new_cost;
 
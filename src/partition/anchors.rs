@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use crate::partition::Partition;
+
+/// Anchor value of a node that is not pinned to any part.
+const FREE: u32 = u32::MAX;
+
+impl Partition {
+    /// Pin a set of nodes to a part, moving them there. Optimization moves never take them out of it.
+    pub(crate) fn set_anchors(&mut self, nodes: &[usize], part: u32) {
+        assert!(part > 0 && part < self.num_parts(), "anchor part must be in range [1, {})", self.num_parts());
+        self.pin_nodes(nodes, part);
+    }
+
+    /// Remove all district anchors (the at-large area, if any, stays pinned).
+    pub(crate) fn clear_anchors(&mut self) {
+        match self.has_at_large() {
+            true => Arc::make_mut(&mut self.anchors).iter_mut()
+                .filter(|part| **part != 0)
+                .for_each(|part| *part = FREE),
+            false => self.anchors = Arc::default(),
+        }
+    }
+
+    /// Get the part a node is pinned to, if any (0 for the at-large area).
+    #[inline]
+    pub(crate) fn anchor(&self, node: usize) -> Option<u32> {
+        self.anchors.get(node).copied().filter(|&part| part != FREE)
+    }
+
+    /// Whether any anchor constraints are set.
+    #[inline] pub(crate) fn has_anchors(&self) -> bool { !self.anchors.is_empty() }
+
+    /// Check if moving a node to `part` respects its anchor and cluster (if any).
+    /// Only the at-large area may be unassigned while one is set.
+    #[inline]
+    pub(crate) fn can_move_node(&self, node: usize, part: u32) -> bool {
+        let pinned = match self.anchor(node) {
+            Some(anchor) => anchor == part,
+            None => part != 0 || !self.has_at_large(),
+        };
+        pinned && self.in_cluster(node, part)
+    }
+
+    /// Check if moving a set of nodes to `part` respects all of their anchors and clusters.
+    pub(crate) fn can_move_nodes(&self, nodes: &[usize], part: u32) -> bool {
+        !(self.has_anchors() || self.has_clusters()) || nodes.iter().all(|&node| self.can_move_node(node, part))
+    }
+
+    /// Check that every anchored node is currently in its anchor part.
+    pub(crate) fn anchors_satisfied(&self) -> bool {
+        self.anchors.iter().enumerate()
+            .all(|(node, &part)| part == FREE || self.assignment(node) == part)
+    }
+
+    /// Move every anchored node into its anchor part.
+    pub(crate) fn restore_anchors(&mut self) {
+        for node in 0..self.anchors.len() {
+            if let Some(part) = self.anchor(node) { self.move_node(node, part, false) }
+        }
+    }
+
+    /// Hold a set of nodes unassigned as the at-large area of a hybrid plan, replacing any previous
+    /// one: they stay in part 0 and population targets only cover the districted remainder.
+    pub(crate) fn set_at_large(&mut self, nodes: &[usize]) {
+        self.clear_at_large();
+        if nodes.is_empty() { return }
+        self.pin_nodes(nodes, 0);
+
+        let mut districted = self.unit_weights().copy_of_size(1);
+        districted.set_row_to_sum_of(0, self.unit_weights());
+        districted.subtract_rows_from(0, self.unit_weights(), &self.at_large_nodes());
+        self.districted_weights = Some(Arc::new(districted));
+    }
+
+    /// Release the at-large area, leaving its nodes unassigned but free to join districts.
+    pub(crate) fn clear_at_large(&mut self) {
+        if !self.has_at_large() { return }
+        Arc::make_mut(&mut self.anchors).iter_mut()
+            .filter(|part| **part == 0)
+            .for_each(|part| *part = FREE);
+        self.districted_weights = None;
+    }
+
+    /// Whether an at-large area is set.
+    #[inline] pub(crate) fn has_at_large(&self) -> bool { self.districted_weights.is_some() }
+
+    /// Nodes of the at-large area.
+    pub(crate) fn at_large_nodes(&self) -> Vec<usize> {
+        (0..self.anchors.len()).filter(|&node| self.anchors[node] == 0).collect()
+    }
+
+    /// Check that every unassigned node belongs to the at-large area (i.e. the plan is complete).
+    pub(crate) fn only_at_large_unassigned(&self) -> bool {
+        self.parts.get(0).iter().all(|&node| self.anchor(node) == Some(0))
+    }
+
+    /// Pin nodes to a part (0 for the at-large area), moving them there.
+    fn pin_nodes(&mut self, nodes: &[usize], part: u32) {
+        if self.anchors.is_empty() { self.anchors = Arc::new(vec![FREE; self.num_nodes()]) }
+
+        for &node in nodes {
+            assert!(node < self.num_nodes(), "node {} out of range", node);
+            Arc::make_mut(&mut self.anchors)[node] = part;
+            self.move_node(node, part, false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AnnealConfig, Metric, Objective, Plan, test_maps};
+
+    fn row_partition(n: usize, num_parts: usize, assignments: &[u32]) -> Partition {
+        let map = test_maps::row(n, &[]);
+        let base = map.base().unwrap();
+        let mut partition = Partition::new(num_parts, base.get_unit_graph(), base.get_unit_weights(), map.region().unwrap().get_unit_weights());
+        partition.update_assignments(assignments);
+        partition
+    }
+
+    #[test]
+    fn anchors_pin_nodes_to_their_part() {
+        let mut partition = row_partition(4, 3, &[1, 1, 2, 2]);
+        partition.set_anchors(&[2], 1);
+        assert_eq!(partition.assignment(2), 1);
+        assert!(partition.can_move_nodes(&[2], 1) && !partition.can_move_nodes(&[1, 2], 2));
+        assert!(partition.anchors_satisfied());
+
+        partition.move_node(2, 2, false);
+        assert!(!partition.anchors_satisfied());
+        partition.restore_anchors();
+        assert_eq!(partition.assignment(2), 1);
+
+        // Clearing anchors keeps the at-large area pinned.
+        partition.set_at_large(&[3]);
+        partition.clear_anchors();
+        assert_eq!((partition.anchor(2), partition.anchor(3)), (None, Some(0)));
+        assert!(!partition.can_move_node(3, 2) && !partition.can_move_node(1, 0));
+        assert!(partition.only_at_large_unassigned());
+    }
+
+    #[test]
+    fn annealing_keeps_anchored_units() {
+        let map = test_maps::grid(4);
+        let corner = map.base().unwrap().geo_ids()[0].id().to_string();
+        let mut plan = Plan::new(map, 2).unwrap();
+        plan.randomize().unwrap();
+        plan.anchor_unit("block", &corner, 2).unwrap();
+        assert!(plan.anchor_unit("block", &corner, 1).unwrap_err().to_string().contains("already anchored to district 2"));
+
+        let objective = Objective::new(vec![Metric::population_deviation_smooth("pop".into())], None);
+        let config = AnnealConfig {
+            objectives: vec![objective],
+            max_iter: 2000,
+            phase_start_probs: vec![0.5],
+            phase_end_probs: vec![Some(0.01)],
+            phase_cooling_rates: vec![0.9],
+            init_temp: 1.0,
+            early_stop_iters: 1000,
+            temp_search_batch_size: 10,
+            batch_size: 100,
+            stop: Default::default(),
+            proposals: vec![],
+            constraints: Default::default(),
+            profile: false,
+        };
+        plan.anneal_with_config(&config).unwrap();
+        assert!(plan.anchors_satisfied());
+        assert_eq!(plan.get_assignments_vec().unwrap()[0], 2);
+    }
+}
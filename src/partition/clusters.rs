@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use crate::partition::Partition;
+
+/// Disjoint clusters of nodes (e.g. county clusters), each holding a fixed set of parts.
+/// Nodes may only be assigned to parts of their own cluster.
+#[derive(Debug)]
+pub(crate) struct NodeClusters {
+    cluster_of: Vec<u32>,       // Cluster index for each node
+    part_cluster: Vec<u32>,     // Cluster index for each part (entry 0, unassigned, is unused)
+}
+
+impl NodeClusters {
+    /// Heap memory held by the clusters, in bytes.
+    pub(super) fn heap_bytes(&self) -> usize {
+        (self.cluster_of.capacity() + self.part_cluster.capacity()) * size_of::<u32>()
+    }
+}
+
+impl Partition {
+    /// Confine nodes to the parts of their cluster, given the cluster index of each node and of each part.
+    /// The current assignment is left as is; call `randomize` to redraw parts within the clusters.
+    pub(crate) fn set_clusters(&mut self, cluster_of: Vec<u32>, part_cluster: Vec<u32>) {
+        assert_eq!(cluster_of.len(), self.num_nodes(), "cluster_of length must match number of nodes");
+        assert_eq!(part_cluster.len(), self.num_parts() as usize, "part_cluster length must match number of parts");
+        self.clusters = Some(Arc::new(NodeClusters { cluster_of, part_cluster }));
+    }
+
+    /// Remove all cluster constraints.
+    pub(crate) fn clear_clusters(&mut self) { self.clusters = None }
+
+    /// Whether cluster constraints are set.
+    #[inline] pub(crate) fn has_clusters(&self) -> bool { self.clusters.is_some() }
+
+    /// Check if `part` belongs to the cluster of `node` (always true without clusters).
+    #[inline]
+    pub(crate) fn in_cluster(&self, node: usize, part: u32) -> bool {
+        self.clusters.as_ref().is_none_or(|clusters| clusters.cluster_of[node] == clusters.part_cluster[part as usize])
+    }
+
+    /// Check that every assigned node is in a part of its cluster.
+    pub(crate) fn clusters_satisfied(&self) -> bool {
+        (0..self.num_nodes()).all(|node| self.assignment(node) == 0 || self.in_cluster(node, self.assignment(node)))
+    }
+}
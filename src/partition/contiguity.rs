@@ -1,6 +1,10 @@
 use std::collections::{HashMap, HashSet, VecDeque};
 
-use crate::partition::Partition;
+use crate::partition::{Partition, find_root};
+
+/// Number of BFS layers the local contiguity search explores around a moved node
+/// before falling back to the full search.
+const LOCAL_SEARCH_RADIUS: usize = 4;
 
 impl Partition {
     /// Check if a part is empty (has no assigned nodes).
@@ -45,140 +49,183 @@ impl Partition {
         // If currently unassigned, removing it can’t break any real district.
         if prev == 0 { return true }
 
-        // Collect neighbors in the same part as `node`.
-        let neighbors = self.unit_graph.edges(node)
-            .filter(|&v| self.parts.find(v) as u32 == prev)
-            .collect::<Vec<_>>();
-
-        // If fewer than 2 same-part neighbors, removing `node` cannot disconnect the part.
-        if neighbors.len() <= 1 { return true }
-
-        // Fast path: exactly two neighbors and they’re directly connected.
-        if neighbors.len() == 2
-            && self.unit_graph.edges(neighbors[0]).any(|v| v == neighbors[1])
-        { return true }
-
-        // Get the number of same-part neighbors for each neighbor.
-        let neighbor_degrees = neighbors.iter()
-            .map(|&u| self.unit_graph.edges(u).filter(|&v| self.parts.find(v) as u32 == prev).count())
-            .collect::<Vec<_>>();
-
-        // Heuristic: Start the BFS with the neighbor with fewest same-part neighbors.
-        let (start, &min_degree) = neighbor_degrees.iter().enumerate()
-            .min_by_key(|&(_, d)| d)
-            .unwrap();
-
-        // Fast path: If any neighbor has only a single same-part neighbor, removing `node` would disconnect.
-        if min_degree == 1 { return false }
-
-        // Advance the generation counter to get a fresh "all-false" state in scratch buffers.
-        // scratch_a[u] == gen means "is_neighbor"; scratch_b[u] == gen means "visited".
-        self.scratch_gen = self.scratch_gen.wrapping_add(1);
-        let stamp = self.scratch_gen;
-
-        // Mark which same-part neighbors are BFS targets.
-        for &u in &neighbors { self.scratch_a[u] = stamp; }
-
-        // BFS from one neighbor within `prev`, forbidding `node`.
-        self.scratch_b[node] = stamp;
-        self.scratch_b[neighbors[start]] = stamp;
-
-        let mut remaining = neighbors.len() - 1;
-        let mut queue = VecDeque::from([neighbors[start]]);
-        while let Some(u) = queue.pop_front() {
-            for v in self.unit_graph.edges(u) {
-                if self.parts.find(v) as u32 == prev && self.scratch_b[v] != stamp {
-                    self.scratch_b[v] = stamp;
-                    if self.scratch_a[v] == stamp {
-                        // Check for early exit: if all targets have been visited, contiguity is preserved.
-                        remaining -= 1; if remaining == 0 { return true }
-                        queue.push_front(v); // prioritize BFS from targets
-                    } else {
+        self.with_scratch(|this, scratch| {
+            // Collect neighbors in the same part as `node`.
+            let neighbors = &mut scratch.nodes;
+            neighbors.clear();
+            neighbors.extend(this.unit_graph.edges(node).filter(|&v| this.parts.find(v) as u32 == prev));
+
+            // If fewer than 2 same-part neighbors, removing `node` cannot disconnect the part.
+            if neighbors.len() <= 1 { return true }
+
+            // Fast path: exactly two neighbors and they’re directly connected.
+            if neighbors.len() == 2
+                && this.unit_graph.edges(neighbors[0]).any(|v| v == neighbors[1])
+            { return true }
+
+            // Fast path: If any neighbor has only a single same-part neighbor, removing `node` would disconnect.
+            if neighbors.iter().any(|&u| this.unit_graph.edges(u).filter(|&v| this.parts.find(v) as u32 == prev).count() == 1) { return false }
+
+            // Bounded search: walk out from one neighbor for at most `LOCAL_SEARCH_RADIUS` steps.
+            // Around a typical unit the others are met within a few steps, and a piece exhausted
+            // within the bound is cut off; otherwise fall back to the full search below.
+            let (targets, visited, queue) = (&mut scratch.targets, &mut scratch.visited, &mut scratch.queue);
+            targets.clear();
+            neighbors[1..].iter().for_each(|&u| { targets.insert(u); });
+            visited.clear();
+            visited.insert(node);
+            visited.insert(neighbors[0]);
+            queue.clear();
+            queue.push_back(neighbors[0]);
+            let mut remaining = neighbors.len() - 1;
+            for _ in 0..LOCAL_SEARCH_RADIUS {
+                for _ in 0..queue.len() {
+                    let u = queue.pop_front().unwrap();
+                    for v in this.unit_graph.edges(u).filter(|&v| this.parts.find(v) as u32 == prev) {
+                        if !visited.insert(v) { continue }
+                        if targets.contains(v) {
+                            remaining -= 1;
+                            if remaining == 0 { return true }
+                        }
                         queue.push_back(v);
                     }
                 }
+                if queue.is_empty() { return false }
             }
-        }
 
-        // If all same-part neighbors are reachable without `node`, contiguity is preserved.
-        remaining == 0
+            // Search from every neighbor at once within `prev`, forbidding `node`, merging searches
+            // that meet. Connected neighbors are found after about half the distance between them,
+            // and a cut-off piece as soon as its search is exhausted, without scanning the rest of
+            // the district.
+            let (visited, labels, roots, queues) = (&mut scratch.visited, &mut scratch.labels, &mut scratch.roots, &mut scratch.queues);
+            visited.clear();
+            visited.insert(node);
+            roots.clear();
+            roots.extend(0..neighbors.len());
+            queues.resize_with(queues.len().max(neighbors.len()), Default::default);
+            for (i, &u) in neighbors.iter().enumerate() {
+                visited.insert(u);
+                labels[u] = i;
+                queues[i].clear();
+                queues[i].push_back(u);
+            }
+
+            let mut searches = neighbors.len();
+            loop {
+                // Expand the active search with the smallest frontier by one BFS layer.
+                let Some(i) = (0..neighbors.len())
+                    .filter(|&i| !queues[i].is_empty())
+                    .min_by_key(|&i| queues[i].len())
+                else { return searches == 1 };
+
+                for _ in 0..queues[i].len() {
+                    let u = queues[i].pop_front().unwrap();
+                    for v in this.unit_graph.edges(u).filter(|&v| this.parts.find(v) as u32 == prev) {
+                        if visited.insert(v) {
+                            labels[v] = i;
+                            queues[i].push_back(v);
+                        } else if v != node {
+                            let (a, b) = (find_root(roots, i), find_root(roots, labels[v]));
+                            if a != b {
+                                // Two searches met: if all have, the neighbors remain connected.
+                                roots[b] = a;
+                                searches -= 1;
+                                if searches == 1 { return true }
+                            }
+                        }
+                    }
+                }
+
+                // A search exhausted with none of its merged searches still active has found a
+                // whole piece of `prev` without `node` that misses some neighbor.
+                if queues[i].is_empty() {
+                    let root = find_root(roots, i);
+                    if !(0..neighbors.len()).any(|j| !queues[j].is_empty() && find_root(roots, j) == root) { return false }
+                }
+            }
+        })
     }
 
     /// Check if a set of nodes forms a contiguous subgraph, and if moving them would violate contiguity.
-    pub(crate) fn check_subgraph_contiguity(&self, nodes: &[usize], part: u32) -> bool {
+    pub(crate) fn check_subgraph_contiguity(&mut self, nodes: &[usize], part: u32) -> bool {
         if nodes.is_empty() { return true }
 
-        // Deduplicate and validate indices.
-        let mut subgraph = Vec::with_capacity(nodes.len());
-        let mut in_subgraph = vec![false; self.graph().node_count()];
-        for &u in nodes {
-            assert!(u < self.graph().node_count(), "node {} out of range", u);
-            if !in_subgraph[u] { in_subgraph[u] = true; subgraph.push(u); }
-        }
+        self.with_scratch(|this, scratch| {
+            // Deduplicate and validate indices.
+            let (subgraph, in_subgraph) = (&mut scratch.nodes, &mut scratch.members);
+            subgraph.clear();
+            in_subgraph.clear();
+            for &u in nodes {
+                assert!(u < this.graph().node_count(), "node {} out of range", u);
+                if in_subgraph.insert(u) { subgraph.push(u) }
+            }
 
-        // Ensure that at least one node in the subgraph is adjacent to the new part.
-        if !(part == 0 || self.part_is_empty(part) || subgraph.iter().any(|&u| self.graph().edges(u).any(|v| self.assignment(v) == part))) { return false }
+            // Ensure that at least one node in the subgraph is adjacent to the new part.
+            if !(part == 0 || this.part_is_empty(part) || subgraph.iter().any(|&u| this.graph().edges(u).any(|v| this.assignment(v) == part))) { return false }
 
-        // Check if the subgraph itself is contiguous.
-        let mut seen: usize = 1;
-        let mut visited = vec![false; self.graph().node_count()];
-        let mut queue = VecDeque::from([subgraph[0]]);
-        visited[subgraph[0]] = true;
-        while let Some(u) = queue.pop_front() {
-            for v in self.graph().edges(u) {
-                if in_subgraph[v] && !visited[v] {
-                    seen += 1;
-                    visited[v] = true;
-                    queue.push_back(v);
+            // Check if the subgraph itself is contiguous.
+            let (visited, queue) = (&mut scratch.visited, &mut scratch.queue);
+            let mut seen: usize = 1;
+            visited.clear();
+            visited.insert(subgraph[0]);
+            queue.clear();
+            queue.push_back(subgraph[0]);
+            while let Some(u) = queue.pop_front() {
+                for v in this.graph().edges(u) {
+                    if in_subgraph.contains(v) && visited.insert(v) {
+                        seen += 1;
+                        queue.push_back(v);
+                    }
                 }
             }
-        }
-        if seen != subgraph.len() { return false }
-
-        // Collect unique non-zero parts appearing in the subgraph.
-        let mut parts = subgraph.iter()
-            .map(|&u| self.assignment(u))
-            .filter(|&p| p != 0)
-            .collect::<Vec<_>>();
-        parts.sort_unstable();
-        parts.dedup();
-
-        'by_part: for part in parts {
-            // Build boundary set in part: vertices in p adjacent to the subgraph.
-            let mut boundary = Vec::new();
-            let mut in_boundary = vec![false; self.graph().node_count()];
-            for &u in subgraph.iter().filter(|&&u| self.assignment(u) == part) {
-                for v in self.graph().edges(u).filter(|&v| !in_subgraph[v] && self.assignment(v) == part) {
-                    if !in_boundary[v] { in_boundary[v] = true; boundary.push(v) }
+            if seen != subgraph.len() { return false }
+
+            // Collect unique non-zero parts appearing in the subgraph.
+            let mut parts = subgraph.iter()
+                .map(|&u| this.assignment(u))
+                .filter(|&p| p != 0)
+                .collect::<Vec<_>>();
+            parts.sort_unstable();
+            parts.dedup();
+
+            'by_part: for part in parts {
+                // Build boundary set in part: vertices in p adjacent to the subgraph.
+                let (boundary, in_boundary) = (&mut scratch.boundary, &mut scratch.targets);
+                boundary.clear();
+                in_boundary.clear();
+                for &u in subgraph.iter().filter(|&&u| this.assignment(u) == part) {
+                    for v in this.graph().edges(u).filter(|&v| !in_subgraph.contains(v) && this.assignment(v) == part) {
+                        if in_boundary.insert(v) { boundary.push(v) }
+                    }
                 }
-            }
 
-            // If fewer than 2 boundary nodes, removal cannot disconnect the part.
-            if boundary.len() <= 1 { continue }
+                // If fewer than 2 boundary nodes, removal cannot disconnect the part.
+                if boundary.len() <= 1 { continue }
 
-            // BFS within part p, forbidding S, early exit once all targets seen.
-            let mut visited = vec![false; self.graph().node_count()];
-            visited[boundary[0]] = true;
+                // BFS within part p, forbidding S, early exit once all targets seen.
+                visited.clear();
+                visited.insert(boundary[0]);
 
-            let mut remaining = boundary.len() - 1;
-            let mut queue = VecDeque::from([boundary[0]]);
+                let mut remaining = boundary.len() - 1;
+                queue.clear();
+                queue.push_back(boundary[0]);
 
-            while let Some(u) = queue.pop_front() {
-                for v in self.graph().edges(u) {
-                    if !in_subgraph[v] && !visited[v] && self.assignment(v) == part {
-                        visited[v] = true;
-                        queue.push_back(v);
+                while let Some(u) = queue.pop_front() {
+                    for v in this.graph().edges(u) {
+                        if !in_subgraph.contains(v) && this.assignment(v) == part && visited.insert(v) {
+                            queue.push_back(v);
 
-                        // Check for early exit: if all targets have been visited, contiguity is preserved.
-                        if in_boundary[v] { remaining -= 1; if remaining == 0 { continue 'by_part } }
+                            // Check for early exit: if all targets have been visited, contiguity is preserved.
+                            if in_boundary.contains(v) { remaining -= 1; if remaining == 0 { continue 'by_part } }
+                        }
                     }
                 }
-            }
 
-            if remaining > 0 { return false }
-        }
+                if remaining > 0 { return false }
+            }
 
-        true
+            true
+        })
     }
 
     /// Find all connected components (as node lists) inside district `part`.
@@ -211,6 +258,11 @@ impl Partition {
         (1..self.num_parts()).all(|part| self.find_components(part).len() <= 1)
     }
 
+    /// First of `parts` (skipping unassigned 0) that is not contiguous, if any.
+    pub(crate) fn first_discontiguous(&self, parts: &[u32]) -> Option<u32> {
+        parts.iter().copied().find(|&part| part != 0 && self.find_components(part).len() > 1)
+    }
+
     /// Enforce contiguity of all parts by reassigning nodes as needed.
     ///
     /// Greedily fix contiguity: for any district with multiple components,
@@ -375,3 +427,59 @@ impl Partition {
             .collect::<Vec<_>>()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_maps;
+
+    fn grid_partition(n: usize, num_parts: usize) -> Partition {
+        let map = test_maps::grid(n);
+        let base = map.base().unwrap();
+        Partition::new(num_parts, base.get_unit_graph(), base.get_unit_weights(), map.region().unwrap().get_unit_weights())
+    }
+
+    /// Whether removing `node` leaves its part in one piece, by counting components from scratch.
+    fn removal_keeps_contiguity(partition: &Partition, node: usize) -> bool {
+        let mut removed = partition.clone();
+        let prev = removed.assignment(node);
+        removed.move_node(node, 0, false);
+        prev == 0 || removed.find_components(prev).len() <= 1
+    }
+
+    #[test]
+    fn node_contiguity_matches_component_count() {
+        let mut partition = grid_partition(8, 5);
+        for _ in 0..5 {
+            partition.randomize();
+            for node in 0..partition.num_nodes() {
+                assert_eq!(partition.check_node_contiguity(node, 0), removal_keeps_contiguity(&partition, node), "node {node}");
+            }
+        }
+
+        // A ring around an inner square stays connected the long way round, until it is cut.
+        let n = 6;
+        let ring = |k: usize| [k / n, k % n].iter().any(|&i| i == 0 || i == n - 1);
+        let mut partition = grid_partition(n, 3);
+        partition.update_assignments(&(0..n * n).map(|k| if ring(k) { 1 } else { 2 }).collect::<Vec<_>>());
+        assert!((0..n * n).filter(|&k| ring(k)).all(|k| partition.check_node_contiguity(k, 0)));
+
+        partition.move_node(5 * n + 3, 2, false);
+        assert!(!partition.check_node_contiguity(3, 0));
+        assert!(partition.check_node_contiguity(5 * n + 2, 0));
+    }
+
+    #[test]
+    fn node_contiguity_falls_back_beyond_the_local_search() {
+        // The ring's neighbors of a corner meet, or are found cut off, only far outside the radius.
+        let n = 12;
+        let ring = |k: usize| [k / n, k % n].iter().any(|&i| i == 0 || i == n - 1);
+        let mut partition = grid_partition(n, 3);
+        partition.update_assignments(&(0..n * n).map(|k| if ring(k) { 1 } else { 2 }).collect::<Vec<_>>());
+        assert!(partition.check_node_contiguity(0, 0));
+
+        partition.move_node((n - 1) * n + n / 2, 2, false);
+        assert!(!partition.check_node_contiguity(0, 0));
+        assert_eq!(partition.check_node_contiguity(0, 0), removal_keeps_contiguity(&partition, 0));
+    }
+}
@@ -0,0 +1,119 @@
+use std::sync::Arc;
+
+use crate::partition::Partition;
+
+/// Atomic groups of nodes (e.g. whole counties) that always move between parts together.
+#[derive(Debug)]
+pub(crate) struct UnitGroups {
+    group_of: Vec<u32>,         // Group index for each node
+    members: Vec<Vec<usize>>,   // Nodes in each group
+}
+
+impl UnitGroups {
+    /// Build groups from the group index of each node.
+    pub(crate) fn new(group_of: Vec<u32>) -> Self {
+        let num_groups = group_of.iter().max().map_or(0, |&g| g as usize + 1);
+        let mut members = vec![Vec::new(); num_groups];
+        for (node, &group) in group_of.iter().enumerate() { members[group as usize].push(node) }
+        Self { group_of, members }
+    }
+
+    /// Number of groups.
+    #[inline] pub(crate) fn len(&self) -> usize { self.members.len() }
+
+    /// Heap memory held by the groups, in bytes.
+    pub(super) fn heap_bytes(&self) -> usize {
+        self.group_of.capacity() * size_of::<u32>() + self.members.capacity() * size_of::<Vec<usize>>()
+            + self.members.iter().map(|members| members.capacity() * size_of::<usize>()).sum::<usize>()
+    }
+}
+
+impl Partition {
+    /// Require nodes to move in atomic groups, given the group index of each node.
+    ///
+    /// Groups that are currently split between parts are moved whole to the part holding most of
+    /// their nodes, or failing that to another part they touch, if the move keeps every part
+    /// contiguous and respects anchors. Returns the groups that could not be consolidated; their
+    /// nodes stay where they are, since moves only propose whole groups held by a single part.
+    pub(crate) fn set_groups(&mut self, group_of: Vec<u32>) -> Vec<u32> {
+        assert_eq!(group_of.len(), self.num_nodes(), "group_of length must match number of nodes");
+        let groups = Arc::new(UnitGroups::new(group_of));
+
+        let mut split = Vec::new();
+        for (group, members) in groups.members.iter().enumerate() {
+            let mut counts = vec![0usize; self.num_parts() as usize];
+            for &u in members { counts[self.assignment(u) as usize] += 1 }
+            if counts.iter().filter(|&&c| c > 0).count() <= 1 { continue }
+
+            let mut parts = (0..self.num_parts()).filter(|&part| counts[part as usize] > 0).collect::<Vec<_>>();
+            parts.sort_by_key(|&part| std::cmp::Reverse(counts[part as usize]));
+            match parts.into_iter().find(|&part| self.check_subgraph_contiguity(members, part) && self.can_move_nodes(members, part)) {
+                Some(part) => for source in (0..self.num_parts()).filter(|&source| source != part) {
+                    let nodes = members.iter().copied().filter(|&u| self.assignment(u) == source).collect::<Vec<_>>();
+                    self.move_subgraph(&nodes, part, false);
+                },
+                None => split.push(group as u32),
+            }
+        }
+
+        self.groups = Some(groups);
+        split
+    }
+
+    /// Remove all group constraints.
+    pub(crate) fn clear_groups(&mut self) { self.groups = None }
+
+    /// Number of atomic groups, if groups are set.
+    pub(crate) fn num_groups(&self) -> Option<usize> { self.groups.as_ref().map(|g| g.len()) }
+
+    /// Get all nodes in the same group as `node`, if groups are set.
+    #[inline]
+    pub(crate) fn group_members(&self, node: usize) -> Option<&[usize]> {
+        let groups = self.groups.as_ref()?;
+        Some(&groups.members[groups.group_of[node] as usize])
+    }
+
+    /// Check if a set of nodes contains every member of each group it touches.
+    pub(crate) fn covers_groups(&self, nodes: &[usize]) -> bool {
+        let Some(groups) = self.groups.as_ref() else { return true };
+        let mut counts = std::collections::HashMap::new();
+        for &u in nodes { *counts.entry(groups.group_of[u]).or_insert(0) += 1 }
+        counts.iter().all(|(&group, &count)| groups.members[group as usize].len() == count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_maps;
+
+    fn with_assignments(map: crate::Map, num_parts: usize, assignments: &[u32]) -> Partition {
+        let base = map.base().unwrap();
+        let mut partition = Partition::new(num_parts, base.get_unit_graph(), base.get_unit_weights(), map.region().unwrap().get_unit_weights());
+        partition.update_assignments(assignments);
+        partition
+    }
+
+    #[test]
+    fn split_groups_are_consolidated_when_contiguous() {
+        // Columns of a 4x4 grid as groups; district 1 holds the first column and the bottom block of the second.
+        let mut partition = with_assignments(test_maps::grid(4), 3, &(0..16).map(|k| if k < 5 { 1 } else { 2 }).collect::<Vec<_>>());
+        assert!(partition.set_groups((0..16).map(|k| k / 4).collect()).is_empty());
+        assert_eq!(partition.assignments(), (0..16).map(|k| if k < 4 { 1 } else { 2 }).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn groups_that_cant_move_whole_are_reported() {
+        // A row of four blocks in districts 1, 2, 2, 3, grouping the two ends: the group isn't
+        // contiguous, so it can't move whole to either end's district.
+        let mut partition = with_assignments(test_maps::row(4, &[]), 4, &[1, 2, 2, 3]);
+        assert_eq!(partition.set_groups(vec![0, 1, 2, 0]), vec![0]);
+        assert_eq!(partition.assignments(), vec![1, 2, 2, 3]);
+
+        // Anchors hold the blocks of a group in different districts.
+        let mut partition = with_assignments(test_maps::row(4, &[]), 3, &[1, 1, 2, 2]);
+        partition.set_anchors(&[1], 1);
+        partition.set_anchors(&[2], 2);
+        assert_eq!(partition.set_groups(vec![0, 1, 1, 2]), vec![1]);
+    }
+}
@@ -0,0 +1,236 @@
+use std::{fmt, sync::{Arc, Mutex, OnceLock}};
+
+use geo::{ConvexHull, Coord, GeodesicArea, LineString, MultiPoint, Polygon};
+use geograph::{Region, UnitId};
+
+use crate::partition::Partition;
+
+/// WGS84 semi-major axis (meters) and first eccentricity squared, matching the geodesic areas
+/// of units.
+const WGS84_A: f64 = 6_378_137.0;
+const WGS84_E2: f64 = 6.694_379_990_141_3e-3;
+
+/// Convex hull vertices (lon/lat) of each unit, computed from the unit geometry on first use and
+/// shared by every partition of a map.
+pub(crate) struct UnitHulls {
+    region: Arc<Region>,
+    hulls: Vec<OnceLock<Box<[Coord<f64>]>>>, // Hull vertices of each unit, without the closing vertex
+}
+
+impl UnitHulls {
+    pub(crate) fn new(region: Arc<Region>) -> Self {
+        let hulls = (0..region.num_units()).map(|_| OnceLock::new()).collect();
+        Self { region, hulls }
+    }
+
+    /// Heap memory held by the hulls computed so far, in bytes. The region is the map's.
+    pub(super) fn heap_bytes(&self) -> usize {
+        self.hulls.capacity() * size_of::<OnceLock<Box<[Coord<f64>]>>>()
+            + self.hulls.iter().filter_map(OnceLock::get).map(|hull| hull.len() * size_of::<Coord<f64>>()).sum::<usize>()
+    }
+
+    /// Hull vertices of a unit.
+    fn get(&self, node: usize) -> &[Coord<f64>] {
+        self.hulls[node].get_or_init(|| {
+            let mut ring = self.region.geometry(UnitId(node as u32)).convex_hull().exterior().0.clone();
+            ring.pop();
+            ring.into_boxed_slice()
+        })
+    }
+}
+
+impl fmt::Debug for UnitHulls {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UnitHulls").field("units", &self.hulls.len()).finish()
+    }
+}
+
+/// Convex hull vertices of each part, computed on first use and kept up to date as nodes move:
+/// a part gaining nodes extends its hull with theirs, and a part losing a node that touches its
+/// hull recomputes it from its frontier on next use.
+#[derive(Debug, Default)]
+pub(crate) struct PartHulls(Mutex<Vec<Option<Vec<Coord<f64>>>>>); // None if not computed or stale
+
+impl Clone for PartHulls {
+    fn clone(&self) -> Self { Self(Mutex::new(self.0.lock().expect("part hull lock poisoned").clone())) }
+}
+
+impl PartHulls {
+    /// Forget every part's hull.
+    pub(super) fn clear(&mut self) { self.0.get_mut().expect("part hull lock poisoned").clear() }
+
+    /// Forget the hull of `part`.
+    pub(super) fn invalidate(&mut self, part: u32) {
+        if let Some(hull) = self.0.get_mut().expect("part hull lock poisoned").get_mut(part as usize) { *hull = None }
+    }
+
+    /// Update the hulls of `prev` and `next` for `nodes` moving from one to the other.
+    pub(super) fn on_move(&mut self, units: &UnitHulls, nodes: &[usize], prev: u32, next: u32) {
+        let hulls = self.0.get_mut().expect("part hull lock poisoned");
+        if let Some(Some(hull)) = hulls.get_mut(next as usize) {
+            let points = hull.iter().chain(nodes.iter().flat_map(|&node| units.get(node))).copied().collect();
+            *hull = convex_hull(points);
+        }
+        if let Some(slot @ Some(_)) = hulls.get_mut(prev as usize) {
+            let hull = slot.as_ref().unwrap();
+            if nodes.iter().flat_map(|&node| units.get(node)).any(|point| hull.contains(point)) { *slot = None }
+        }
+    }
+}
+
+/// Convex hull vertices of a set of points, without the closing vertex.
+fn convex_hull(points: Vec<Coord<f64>>) -> Vec<Coord<f64>> {
+    let mut ring = MultiPoint::from(points).convex_hull().exterior().0.clone();
+    ring.pop();
+    ring
+}
+
+/// Smallest circle enclosing planar points, as (center, radius), by Welzl's algorithm.
+fn enclosing_circle(points: &[Coord<f64>]) -> (Coord<f64>, f64) {
+    fn circle_of(boundary: &[Coord<f64>]) -> (Coord<f64>, f64) {
+        match *boundary {
+            [] => (Coord { x: 0.0, y: 0.0 }, 0.0),
+            [a] => (a, 0.0),
+            [a, b] => {
+                let center = (a + b) / 2.0;
+                (center, (a - center).x.hypot((a - center).y))
+            }
+            [a, b, c, ..] => {
+                let (b, c) = (b - a, c - a);
+                let d = 2.0 * (b.x * c.y - b.y * c.x);
+                if d.abs() < f64::EPSILON {
+                    // Collinear: the circle on the two farthest points.
+                    return [circle_of(&[a, a + b]), circle_of(&[a, a + c]), circle_of(&[a + b, a + c])]
+                        .into_iter().fold((a, 0.0), |best, circle| if circle.1 > best.1 { circle } else { best });
+                }
+                let (b2, c2) = (b.x * b.x + b.y * b.y, c.x * c.x + c.y * c.y);
+                let center = Coord { x: (c.y * b2 - b.y * c2) / d, y: (b.x * c2 - c.x * b2) / d };
+                (a + center, center.x.hypot(center.y))
+            }
+        }
+    }
+
+    let contains = |(center, radius): (Coord<f64>, f64), point: Coord<f64>| {
+        (point - center).x.hypot((point - center).y) <= radius * (1.0 + 1e-12) + 1e-9
+    };
+
+    // Iterative form of Welzl's algorithm (expected linear time for points in random order;
+    // hull vertices are few, so their ring order is fine).
+    let mut circle = circle_of(&[]);
+    for (i, &p) in points.iter().enumerate() {
+        if contains(circle, p) { continue }
+        circle = circle_of(&[p]);
+        for (j, &q) in points[..i].iter().enumerate() {
+            if contains(circle, q) { continue }
+            circle = circle_of(&[p, q]);
+            for &r in &points[..j] {
+                if !contains(circle, r) { circle = circle_of(&[p, q, r]) }
+            }
+        }
+    }
+    circle
+}
+
+impl Partition {
+    /// Use the unit geometry of `region` for hull-based compactness scores.
+    pub(crate) fn set_unit_hulls(&mut self, region: Arc<Region>) {
+        self.unit_hulls = Some(Arc::new(UnitHulls::new(region)));
+        self.part_hulls.clear();
+    }
+
+    /// Convex hull vertices (lon/lat) of a part, or `None` if the partition has no unit geometry.
+    /// Computed from the part's frontier units, since its interior units cannot touch its hull.
+    pub(crate) fn part_hull(&self, part: u32) -> Option<Vec<Coord<f64>>> {
+        let units = self.unit_hulls.as_ref()?;
+        let mut hulls = self.part_hulls.0.lock().expect("part hull lock poisoned");
+        hulls.resize(self.num_parts() as usize, None);
+        let hull = hulls[part as usize].get_or_insert_with(|| {
+            convex_hull(self.frontier(part).iter().flat_map(|&node| units.get(node)).copied().collect())
+        });
+        Some(hull.clone())
+    }
+
+    /// Geodesic area of a part's convex hull, in square meters.
+    fn hull_area(&self, part: u32) -> f64 {
+        let hull = self.part_hull(part).expect("hull compactness requires unit geometry");
+        Polygon::new(LineString::from(hull), vec![]).geodesic_area_unsigned()
+    }
+
+    /// Area of the smallest circle enclosing a part, in square meters, measured in a local
+    /// equirectangular projection scaled to the ellipsoid at the part's mean latitude.
+    fn enclosing_circle_area(&self, part: u32) -> f64 {
+        let hull = self.part_hull(part).expect("Reock compactness requires unit geometry");
+        if hull.is_empty() { return 0.0 }
+        let lat0 = (hull.iter().map(|c| c.y).sum::<f64>() / hull.len() as f64).to_radians();
+        let w = 1.0 - WGS84_E2 * lat0.sin().powi(2);
+        let meridional = WGS84_A * (1.0 - WGS84_E2) / w.powf(1.5);
+        let parallel = WGS84_A / w.sqrt() * lat0.cos();
+        let projected = hull.iter()
+            .map(|c| Coord { x: parallel * c.x.to_radians(), y: meridional * c.y.to_radians() })
+            .collect::<Vec<_>>();
+        let (_, radius) = enclosing_circle(&projected);
+        std::f64::consts::PI * radius * radius
+    }
+
+    /// Compute the Reock compactness score for a part (0 to 1).
+    /// Formula: area(part) / area(minimum_bounding_circle(part))
+    /// If the minimum bounding circle area is zero, returns infinity.
+    pub(crate) fn reock(&self, part: u32) -> f64 {
+        let circle = self.enclosing_circle_area(part);
+        if circle == 0.0 { return f64::INFINITY }
+        self.area(part) / circle
+    }
+
+    /// Compute the convex hull compactness score for a part (0 to 1).
+    /// Formula: area(part) / area(convex_hull(part))
+    /// If the convex hull area is zero, returns infinity.
+    pub(crate) fn convex_hull(&self, part: u32) -> f64 {
+        let hull = self.hull_area(part);
+        if hull == 0.0 { return f64::INFINITY }
+        self.area(part) / hull
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Aggregation, Map, Metric, Plan, test_maps};
+
+    #[test]
+    fn enclosing_circle_of_square_touches_corners() {
+        let square = [(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0), (1.0, 1.0)].map(|(x, y)| Coord { x, y });
+        let (center, radius) = enclosing_circle(&square);
+        assert!((center.x - 1.0).abs() < 1e-12 && (center.y - 1.0).abs() < 1e-12);
+        assert!((radius - 2f64.sqrt()).abs() < 1e-12);
+
+        let line = [(0.0, 0.0), (1.0, 0.0), (3.0, 0.0)].map(|(x, y)| Coord { x, y });
+        assert!((enclosing_circle(&line).1 - 1.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn hull_scores_follow_moves() {
+        // An 8x8 grid of 0.1 degree cells at the equator, split into four 4x4 squares.
+        let geoms = test_maps::grid_geoms(8, 0.1);
+        let areas = geoms.iter().map(|geom| geom.geodesic_area_unsigned()).collect();
+        let map = Arc::new(Map::from_parts(geoms, None, vec![("pop".into(), vec![1.0; 64]), ("area_m2".into(), areas)], &[]).unwrap());
+        let mut plan = Plan::new(map.clone(), 4).unwrap();
+        plan.set_assignments_vec((0..64).map(|k| k / 32 * 2 + (k % 8) / 4 + 1).collect()).unwrap();
+
+        // Each district is a square: its own hull, enclosed by a circle of area pi/2 times its own.
+        let convex = plan.compute_metric(&Metric::compactness_convex_hull());
+        let reock = plan.compute_metric(&Metric::compactness_reock());
+        assert!(convex.iter().all(|score| (score - 1.0).abs() < 1e-3), "{convex:?}");
+        assert!(reock.iter().all(|score| (score - 2.0 / std::f64::consts::PI).abs() < 1e-3), "{reock:?}");
+
+        // Hulls kept up to date along a chain match hulls computed from scratch.
+        assert!(plan.recom("pop", 0.0, 10, Some(3)).unwrap() > 0);
+        let mut fresh = Plan::new(map, 4).unwrap();
+        fresh.set_assignments_vec(plan.get_assignments_vec().unwrap()).unwrap();
+        for metric in [Metric::compactness_convex_hull(), Metric::compactness_reock()] {
+            assert_eq!(plan.compute_metric(&metric), fresh.compute_metric(&metric));
+        }
+        let worst = Metric::compactness_reock().with_aggregation(Aggregation::Min).unwrap();
+        assert_eq!(plan.compute_metric_score(&worst), fresh.compute_metric(&worst).into_iter().fold(f64::INFINITY, f64::min));
+        assert!(Metric::population_deviation("pop".into()).with_aggregation(Aggregation::Min).is_err());
+    }
+}
@@ -2,15 +2,19 @@ use crate::partition::Partition;
 
 impl Partition {
     /// Compute the population deviation for a given partition.
+    /// Integer series are compared exactly, so a part at the ideal population has deviation 0.
     pub(crate) fn population_deviation(&self, part: u32, pop_series: &str) -> f64 {
-        let average = self.region_total(pop_series) / (self.num_parts() - 1) as f64;
+        let districts = self.num_parts() - 1;
+        if let Some((total, region)) = self.exact_totals(pop_series, part) {
+            return (total as i128 * districts as i128 - region as i128) as f64 / region as f64
+        }
+        let average = self.region_total(pop_series) / districts as f64;
         self.part_total(pop_series, part) / average - 1.0
     }
 
     /// Compute the absolute population deviation for a given partition.
     pub(crate) fn absolute_population_deviation(&self, part: u32, pop_series: &str) -> f64 {
-        let average = self.region_total(pop_series) / (self.num_parts() - 1) as f64;
-        (self.part_total(pop_series, part) / average - 1.0).abs()
+        self.population_deviation(part, pop_series).abs()
     }
 
     /// Compute a smooth population deviation metric for a given partition.
@@ -28,6 +28,20 @@ impl Partition {
         (- (lean * lean) / (2.0 * sigma * sigma)).exp()
     }
 
+    /// Efficiency gap: wasted Republican votes minus wasted Democratic votes, as a share of all votes.
+    /// A vote is wasted if it is cast for the loser or for the winner beyond half of the district's
+    /// votes. Positive values favor Democrats, matching `partisan_lean`.
+    pub(crate) fn efficiency_gap(&self, dem_series: &str, rep_series: &str) -> f64 {
+        let (mut wasted_dem, mut wasted_rep, mut total) = (0.0, 0.0, 0.0);
+        for part in 1..self.num_parts() {
+            let (dem, rep) = (self.part_total(dem_series, part), self.part_total(rep_series, part));
+            let half = (dem + rep) / 2.0;
+            if dem > rep { wasted_dem += dem - half; wasted_rep += rep } else { wasted_rep += rep - half; wasted_dem += dem }
+            total += dem + rep;
+        }
+        if total == 0.0 { 0.0 } else { (wasted_rep - wasted_dem) / total }
+    }
+
     /// Seats–votes proportionality / partisan fairness metric.
     #[allow(unused_variables)]
     pub(crate) fn proportionality(&self, dem_series: &str, rep_series: &str) -> Vec<f64> { todo!() }
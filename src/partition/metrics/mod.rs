@@ -1,4 +1,5 @@
 mod compactness;
 mod demographic;
 mod electoral;
+mod partisan;
 mod splits;
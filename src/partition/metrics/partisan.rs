@@ -0,0 +1,168 @@
+use rand::Rng;
+
+use crate::partition::Partition;
+
+impl Partition {
+    /// Democratic share of the two-party vote in each district (0.5 for a district with no votes).
+    pub(crate) fn dem_shares(&self, dem_series: &str, rep_series: &str) -> Vec<f64> {
+        (1..self.num_parts())
+            .map(|part| (self.partisan_lean(part, dem_series, rep_series) + 1.0) / 2.0)
+            .collect()
+    }
+
+    /// Mean-median difference: median minus mean of the districts' Democratic vote shares.
+    /// Positive values favor Democrats, matching `efficiency_gap`.
+    pub(crate) fn mean_median(&self, dem_series: &str, rep_series: &str) -> f64 {
+        mean_median(&self.dem_shares(dem_series, rep_series))
+    }
+
+    /// Lopsided-wins test: Welch's t-test comparing the Republican share in Republican wins with
+    /// the Democratic share in Democratic wins. Returns the t statistic and its two-sided p-value,
+    /// or `None` if either party wins fewer than two districts. Positive t means Republicans win
+    /// by larger margins (Democrats are spread more efficiently), favoring Democrats.
+    pub(crate) fn lopsided_wins(&self, dem_series: &str, rep_series: &str) -> Option<(f64, f64)> {
+        let shares = self.dem_shares(dem_series, rep_series);
+        let dem_wins = shares.iter().copied().filter(|&share| share > 0.5).collect::<Vec<_>>();
+        let rep_wins = shares.iter().copied().filter(|&share| share < 0.5).map(|share| 1.0 - share).collect::<Vec<_>>();
+        welch_t_test(&rep_wins, &dem_wins)
+    }
+
+    /// Mean-median difference of `samples` bootstrap replicates, each resampling every district's
+    /// units with replacement before re-aggregating its votes.
+    pub(crate) fn bootstrap_mean_median(&self, dem_series: &str, rep_series: &str, samples: usize, rng: &mut impl Rng) -> Vec<f64> {
+        let weights = self.unit_weights();
+        let votes = (1..self.num_parts())
+            .map(|part| self.part_nodes(part).into_iter()
+                .map(|u| (weights.get_as_f64(dem_series, u).unwrap(), weights.get_as_f64(rep_series, u).unwrap()))
+                .collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        (0..samples).map(|_| {
+            let shares = votes.iter().map(|units| {
+                let (mut dem, mut rep) = (0.0, 0.0);
+                for _ in 0..units.len() {
+                    let (d, r) = units[rng.random_range(0..units.len())];
+                    dem += d;
+                    rep += r;
+                }
+                if dem + rep == 0.0 { 0.5 } else { dem / (dem + rep) }
+            }).collect::<Vec<_>>();
+            mean_median(&shares)
+        }).collect()
+    }
+}
+
+/// Median minus mean of `values` (0 if empty).
+fn mean_median(values: &[f64]) -> f64 {
+    if values.is_empty() { return 0.0 }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let n = sorted.len();
+    let median = if n % 2 == 1 { sorted[n / 2] } else { (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0 };
+    median - sorted.iter().sum::<f64>() / n as f64
+}
+
+/// Welch's unequal-variance t-test of mean(a) - mean(b): the t statistic and two-sided p-value.
+/// `None` if either sample has fewer than two values, or both have zero variance.
+fn welch_t_test(a: &[f64], b: &[f64]) -> Option<(f64, f64)> {
+    if a.len() < 2 || b.len() < 2 { return None }
+    let stats = |x: &[f64]| {
+        let n = x.len() as f64;
+        let mean = x.iter().sum::<f64>() / n;
+        let var = x.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        (mean, var / n)
+    };
+    let ((mean_a, se_a), (mean_b, se_b)) = (stats(a), stats(b));
+    let se = se_a + se_b;
+    if se == 0.0 { return None }
+
+    let t = (mean_a - mean_b) / se.sqrt();
+    let df = se * se / (se_a * se_a / (a.len() - 1) as f64 + se_b * se_b / (b.len() - 1) as f64);
+    Some((t, student_t_two_sided(t, df)))
+}
+
+/// Two-sided p-value of Student's t distribution with `df` degrees of freedom.
+fn student_t_two_sided(t: f64, df: f64) -> f64 {
+    regularized_beta(df / (df + t * t), df / 2.0, 0.5)
+}
+
+/// Regularized incomplete beta function I_x(a, b), by Lentz's continued fraction.
+fn regularized_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 { return 0.0 }
+    if x >= 1.0 { return 1.0 }
+    // The continued fraction converges quickly only below the mean; use the symmetry otherwise.
+    if x > (a + 1.0) / (a + b + 2.0) { return 1.0 - regularized_beta(1.0 - x, b, a) }
+
+    let front = (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp() / a;
+    const TINY: f64 = 1e-300;
+    let (mut c, mut d) = (1.0, 1.0 - (a + b) * x / (a + 1.0));
+    if d.abs() < TINY { d = TINY }
+    d = 1.0 / d;
+    let mut f = d;
+    for m in 1..300 {
+        let m = m as f64;
+        for numerator in [
+            m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m)),
+            -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0)),
+        ] {
+            d = 1.0 + numerator * d;
+            if d.abs() < TINY { d = TINY }
+            c = 1.0 + numerator / c;
+            if c.abs() < TINY { c = TINY }
+            d = 1.0 / d;
+            f *= c * d;
+        }
+        if (c * d - 1.0).abs() < 1e-14 { break }
+    }
+    front * f
+}
+
+/// Natural log of the gamma function (Lanczos approximation, g = 7).
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFS: [f64; 9] = [
+        0.999_999_999_999_809_9, 676.520_368_121_885_1, -1_259.139_216_722_402_8, 771.323_428_777_653_1,
+        -176.615_029_162_140_6, 12.507_343_278_686_905, -0.138_571_095_265_720_12, 9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        return (std::f64::consts::PI / (std::f64::consts::PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let sum = COEFFS[1..].iter().enumerate().fold(COEFFS[0], |sum, (i, &c)| sum + c / (x + i as f64 + 1.0));
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mean_median_of_skewed_shares() {
+        // Median 0.55, mean 0.6.
+        assert!((mean_median(&[0.45, 0.55, 0.8]) - (-0.05)).abs() < 1e-12);
+        assert_eq!(mean_median(&[]), 0.0);
+    }
+
+    #[test]
+    fn ln_gamma_matches_factorials() {
+        assert!((ln_gamma(5.0) - 24f64.ln()).abs() < 1e-10);
+        assert!((ln_gamma(0.5) - std::f64::consts::PI.sqrt().ln()).abs() < 1e-10);
+    }
+
+    #[test]
+    fn student_t_p_values() {
+        // Critical values of the t distribution at two-sided p = 0.05.
+        assert!((student_t_two_sided(2.228, 10.0) - 0.05).abs() < 1e-3);
+        assert!((student_t_two_sided(1.96, 1e6) - 0.05).abs() < 1e-3);
+        assert!((student_t_two_sided(0.0, 5.0) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn welch_t_test_detects_separated_samples() {
+        let (t, p) = welch_t_test(&[0.80, 0.82, 0.85, 0.78], &[0.55, 0.57, 0.52, 0.56]).unwrap();
+        assert!(t > 0.0);
+        assert!(p < 0.001);
+        assert!(welch_t_test(&[0.6], &[0.55, 0.57]).is_none());
+    }
+}
@@ -1,9 +1,20 @@
 mod algorithm;
+mod anchors;
+mod clusters;
 mod contiguity;
+mod groups;
+mod hulls;
 mod metrics;
 mod ops;
 mod partition;
 mod structures;
 
+pub use algorithm::{
+    ALGORITHMS, AlgorithmInfo, AlgorithmKind, AnnealProgress, BalanceBound, CsvStepWriter, EveryNth, JsonlStepWriter, PlateauRule, Proposal,
+    RunProfile, Step, StepBuffer, StepObserver, StepRecord, StopReason, StoppingRules, WeightedProposal, algorithm_info,
+};
+pub(crate) use algorithm::{Kernel, KernelMix, MoveConstraints};
 pub(crate) use partition::Partition;
+pub use structures::HullKind;
+pub(crate) use structures::HullSet;
 use structures::*;
@@ -141,6 +141,35 @@ impl Partition {
         self.update_on_subgraph_move(&subgraph, prev, part);
     }
 
+    /// Collect the nodes that must move together when moving `node` to `part`, with `node` last:
+    /// its whole group if groups are set, otherwise the articulation bundle needed to keep the
+    /// source part contiguous. Returns `None` if the move would violate contiguity or an anchor,
+    /// or if the group is split between parts.
+    pub(crate) fn move_proposal(&mut self, node: usize, part: u32) -> Option<Vec<usize>> {
+        let subgraph = if let Some(group) = self.group_members(node) {
+            let source = self.assignment(node);
+            if group.iter().any(|&u| self.assignment(u) != source) { return None }
+            let mut subgraph = group.iter().copied().filter(|&u| u != node).collect::<Vec<_>>();
+            subgraph.push(node);
+            if !self.check_subgraph_contiguity(&subgraph, part) { return None }
+            subgraph
+        } else if self.check_node_contiguity(node, part) {
+            vec![node]
+        } else {
+            let mut subgraph = self.cut_subgraph_within_part(node);
+            subgraph.push(node);
+            subgraph
+        };
+
+        self.can_move_nodes(&subgraph, part).then_some(subgraph)
+    }
+
+    /// Move a set of nodes (as returned by `move_proposal`) to a part, without checks.
+    pub(crate) fn apply_move(&mut self, subgraph: &[usize], part: u32) {
+        if subgraph.len() == 1 { self.move_node(subgraph[0], part, false) }
+        else { self.move_subgraph(subgraph, part, false) }
+    }
+
     /// Articulation-aware move: move `u` and (if needed) the minimal "dangling" component
     /// that would be cut off by removing `u`, so the source stays contiguous.
     pub(crate) fn move_node_with_articulation(&mut self, node: usize, part: u32) {
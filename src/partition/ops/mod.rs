@@ -1,3 +1,4 @@
 mod flip;
 mod merge;
 mod recombine;
+mod suggest;
@@ -112,24 +112,30 @@ impl Partition {
             }
         }
 
-        eprintln!("Part size: {}", nodes.len());
-        eprintln!("Spanning tree size: {}", order.len());
-
         SpanningTree { root, parent, order, index, size }
     }
 
-    /// Find the child `u` that yields the most balanced split when cutting (parent[u], u).
-    fn balanced_cut(&self, tree: &SpanningTree, series: &str) -> Option<usize> {
-        // 1) Pull node weights for the order (release the &self borrow quickly).
-        let weights = tree.order.iter()
-            .map(|&u| self.unit_weights().get_as_f64(series, u).unwrap())
-            .collect::<Vec<_>>();
-
-        // 2) Prefix sums over preorder.
+    /// Prefix sums of `series` over the preorder of a tree, so each subtree's total is the
+    /// difference of two entries. Integer series are summed exactly.
+    fn preorder_prefix(&self, tree: &SpanningTree, series: &str) -> Vec<f64> {
+        let weights = self.unit_weights();
         let mut prefix = Vec::with_capacity(tree.order.len() + 1);
         prefix.push(0.0);
-        for &w in &weights { prefix.push(prefix.last().unwrap() + w) }
+        if weights.get_as_i64(series, tree.root).is_some() {
+            let mut total = 0i64;
+            for &u in &tree.order {
+                total += weights.get_as_i64(series, u).unwrap();
+                prefix.push(total as f64);
+            }
+        } else {
+            for &u in &tree.order { prefix.push(prefix.last().unwrap() + weights.get_as_f64(series, u).unwrap()) }
+        }
+        prefix
+    }
 
+    /// Find the child `u` that yields the most balanced split when cutting (parent[u], u).
+    fn balanced_cut(&self, tree: &SpanningTree, series: &str) -> Option<usize> {
+        let prefix = self.preorder_prefix(tree, series);
         let total = *prefix.last().unwrap();
         let target = total * 0.5;
 
@@ -148,16 +154,17 @@ impl Partition {
             }
         }
 
-        eprintln!("total: {total}, target: {target}, best_err: {best_err}");
-
         best_cut
     }
 
-    /// Recombine two parts by merging them into one and then repartitioning.
+    /// Recombine two parts by merging them into one and then repartitioning, splitting the
+    /// merged part as evenly as possible by `series` (e.g. total population or VAP).
     /// If the two parts are not contiguous, does nothing.
-    pub(crate) fn recombine_parts(&mut self, a: u32, b: u32) {
+    pub(crate) fn recombine_parts(&mut self, a: u32, b: u32, series: &str) {
         let rng = &mut rand::rng();
 
+        let (a_nodes, b_nodes) = (self.part_nodes(a), self.part_nodes(b));
+
         // If the two part are not contiguous, do nothing.
         let Some(other) = self.merge_parts(a, b, true) else { return };
         let merged = if other == a { b } else { a };
@@ -166,12 +173,67 @@ impl Partition {
         let tree = self.random_spanning_tree(merged, rng);
 
         // Select a random edge of the spanning tree to cut the subgraph
-        let edge = self.balanced_cut(&tree, "T_20_CENS_Total").unwrap();
+        let edge = self.balanced_cut(&tree, series).unwrap();
+        self.split_merged(&tree, edge, merged, other, if other == a { &a_nodes } else { &b_nodes });
+    }
+
+    /// Recombine two adjacent parts, cutting a random spanning tree of their union at an edge
+    /// chosen uniformly among those leaving both halves' `series` totals within `bounds`.
+    /// Returns `false`, leaving both parts unchanged, if the parts are not adjacent, no edge of
+    /// the drawn tree gives a cut within bounds, or the cut would break a group or anchor.
+    pub(crate) fn recombine_parts_bounded(&mut self, a: u32, b: u32, series: &str, bounds: (f64, f64), rng: &mut impl rand::Rng) -> bool {
+        use rand::seq::IndexedRandom;
+
+        let (a_nodes, b_nodes) = (self.part_nodes(a), self.part_nodes(b));
+        let Some(other) = self.merge_parts(a, b, true) else { return false };
+        let merged = if other == a { b } else { a };
+        let restore = if other == a { &a_nodes } else { &b_nodes };
+
+        let tree = self.random_spanning_tree(merged, rng);
+        let prefix = self.preorder_prefix(&tree, series);
+        let total = *prefix.last().unwrap();
+        let within = |value: f64| value >= bounds.0 && value <= bounds.1;
+
+        let cuts = tree.order[1..].iter().copied().filter(|&u| {
+            let (index, size) = (tree.index[u].unwrap(), tree.size[u].unwrap());
+            let sub = prefix[index + size] - prefix[index];
+            within(sub) && within(total - sub)
+        }).collect::<Vec<_>>();
+
+        match cuts.choose(rng) {
+            Some(&edge) => self.split_merged(&tree, edge, merged, other, restore),
+            None => { self.move_subgraph(restore, other, false); false }
+        }
+    }
+
+    /// Split a merged part along tree edge (parent[edge], edge), moving one side into the empty part `other`.
+    /// If the cut would break a group or separate anchored or clustered nodes from their parts, moves `restore` back to
+    /// `other` instead (undoing the merge) and returns `false`.
+    fn split_merged(&mut self, tree: &SpanningTree, edge: usize, merged: u32, other: u32, restore: &[usize]) -> bool {
         let subtree = tree.subtree_slice(edge).unwrap();
 
-        self.move_subgraph(subtree, other, false);
+        if !self.covers_groups(subtree) {
+            // The cut would split an atomic group: undo the merge.
+            self.move_subgraph(restore, other, false);
+            return false;
+        }
 
-        println!("a population: {}", self.part_weights().get_as_f64("T_20_CENS_Total", a as usize).unwrap());
-        println!("b population: {}", self.part_weights().get_as_f64("T_20_CENS_Total", b as usize).unwrap());
+        // Either side may go to `other`, as long as anchors and clusters allow both to land.
+        let index = tree.index[edge].unwrap();
+        let complement = tree.order[..index].iter()
+            .chain(&tree.order[index + subtree.len()..])
+            .copied()
+            .collect::<Vec<_>>();
+        let fits = |moved: &[usize], kept: &[usize]| self.can_move_nodes(moved, other) && self.can_move_nodes(kept, merged);
+        if fits(subtree, &complement) {
+            self.move_subgraph(subtree, other, false);
+        } else if fits(&complement, subtree) {
+            self.move_subgraph(&complement, other, false);
+        } else {
+            // The cut would separate anchors or clusters from their parts: undo the merge.
+            self.move_subgraph(restore, other, false);
+            return false;
+        }
+        true
     }
 }
@@ -0,0 +1,29 @@
+use crate::partition::Partition;
+
+impl Partition {
+    /// Score every single-node move of a district's frontier node into a neighboring district,
+    /// returning `(node, from, to, score)` with the score after the move. Moves that would break
+    /// contiguity, an anchor or a cluster, or that must carry other nodes along (a group, or an
+    /// articulation bundle) are skipped. The partition is left as it was.
+    pub(crate) fn single_move_scores(&mut self, score: impl Fn(&Partition) -> f64) -> Vec<(usize, u32, u32, f64)> {
+        let mut scores = Vec::new();
+        for src in 1..self.num_parts() {
+            for node in self.frontier(src).to_vec() {
+                let mut dests = self.graph().edges(node)
+                    .map(|v| self.assignment(v))
+                    .filter(|&p| p != 0 && p != src)
+                    .collect::<Vec<_>>();
+                dests.sort_unstable();
+                dests.dedup();
+
+                for dest in dests {
+                    if self.move_proposal(node, dest).is_none_or(|subgraph| subgraph.len() != 1) { continue }
+                    self.move_node(node, dest, false);
+                    scores.push((node, src, dest, score(self)));
+                    self.move_node(node, src, false);
+                }
+            }
+        }
+        scores
+    }
+}
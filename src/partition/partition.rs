@@ -2,9 +2,13 @@ use std::{collections::HashSet, sync::Arc};
 
 use crate::{
     graph::{UnitGraph, WeightMatrix},
-    partition::{FrontierEdgeList, MultiSet, PartGraph, PartitionSet},
+    map::SharedBuffers,
+    partition::{FrontierEdgeList, MultiSet, PartGraph, PartitionSet, Scratch, clusters::NodeClusters, groups::UnitGroups, hulls::{PartHulls, UnitHulls}},
 };
 
+/// `update_assignments` rebuilds every cache when more than 1/`REBUILD_FRACTION` of the nodes move.
+const REBUILD_FRACTION: usize = 8;
+
 /// A partition of a graph into contiguous parts (districts).
 #[derive(Clone, Debug)]
 pub(crate) struct Partition {
@@ -12,9 +16,16 @@ pub(crate) struct Partition {
     pub(super) frontiers: MultiSet,          // Nodes on the boundary of each part
     pub(super) frontier_edges: FrontierEdgeList, // Half-edges on the boundary of each part
     pub(super) part_graph: PartGraph,        // Aggregated weights and perimeters for each part
-    unit_graph: UnitGraph,                   // Graph topology for basic units (census block)
+    pub(super) unit_graph: UnitGraph,        // Graph topology for basic units (census block)
     unit_weights: Arc<WeightMatrix>,         // Demographic/election weights for basic units
     region_weights: Arc<WeightMatrix>,       // Summed weights for the entire region (state totals)
+    pub(super) districted_weights: Option<Arc<WeightMatrix>>, // Region weights less the at-large area, if any
+    pub(super) anchors: Arc<Vec<u32>>,       // Part each node is pinned to (`FREE` if none), empty if no anchors
+    pub(super) groups: Option<Arc<UnitGroups>>, // Atomic groups of nodes that move together (e.g. counties)
+    pub(super) clusters: Option<Arc<NodeClusters>>, // Clusters confining nodes to a subset of parts
+    pub(super) unit_hulls: Option<Arc<UnitHulls>>,  // Unit convex hulls for hull compactness, if the map has geometry
+    pub(super) part_hulls: PartHulls,               // Cached convex hull of each part
+    pub(super) scratch: Scratch,             // Buffers reused by the contiguity checks of each move
 }
 
 impl Partition {
@@ -33,6 +44,7 @@ impl Partition {
 
         // edge_count() returns total directed edges (each undirected edge counted twice)
         let num_directed_edges = unit_graph.edge_count();
+        let scratch = Scratch::new(unit_graph.node_count());
 
         Self {
             parts: PartitionSet::new(num_parts, unit_graph.node_count()),
@@ -42,6 +54,13 @@ impl Partition {
             unit_graph,
             unit_weights,
             region_weights,
+            districted_weights: None,
+            anchors: Arc::default(),
+            groups: None,
+            clusters: None,
+            unit_hulls: None,
+            part_hulls: PartHulls::default(),
+            scratch,
         }
     }
 
@@ -57,6 +76,14 @@ impl Partition {
     /// Get a reference to the underlying unit graph.
     pub(super) fn graph(&self) -> &UnitGraph { &self.unit_graph }
 
+    /// Run `f` with the scratch buffers lent out, so that it can read the partition meanwhile.
+    pub(super) fn with_scratch<T>(&mut self, f: impl FnOnce(&Self, &mut Scratch) -> T) -> T {
+        let mut scratch = std::mem::take(&mut self.scratch);
+        let result = f(self, &mut scratch);
+        self.scratch = scratch;
+        result
+    }
+
     /// Get a reference to the unit weights.
     pub(super) fn unit_weights(&self) -> &WeightMatrix { &self.unit_weights }
 
@@ -89,6 +116,7 @@ impl Partition {
         self.part_graph.node_weights_mut().clear_all_rows();
         self.part_graph.node_weights_mut().set_row_to_sum_of(0, &self.unit_weights);
         self.part_graph.clear_perimeters();
+        self.part_hulls.clear();
     }
 
     /// Whether a node is on the frontier of its part: it has a graph
@@ -120,6 +148,20 @@ impl Partition {
             .collect()
     }
 
+    /// Sorted list of the districts bordering each part (index 0 is unassigned and left empty).
+    pub(crate) fn part_adjacency(&self) -> Vec<Vec<u32>> {
+        (0..self.num_parts()).map(|part| {
+            if part == 0 { return vec![] }
+            let mut neighbors = self.frontier_edge_endpoints(part).into_iter()
+                .map(|(_, v)| self.assignment(v))
+                .filter(|&other| other != 0 && other != part)
+                .collect::<Vec<_>>();
+            neighbors.sort_unstable();
+            neighbors.dedup();
+            neighbors
+        }).collect()
+    }
+
     /// Verify that frontier edges are consistent with current assignments.
     /// Returns true if all frontier edges are correctly tracked.
     #[cfg(debug_assertions)]
@@ -222,6 +264,27 @@ impl Partition {
         cycle
     }
 
+    /// Set assignments, moving only the nodes whose part changed, or rebuilding every cache
+    /// if more than 1/`REBUILD_FRACTION` of the nodes did. Returns the changed parts, sorted.
+    pub(crate) fn update_assignments(&mut self, assignments: &[u32]) -> Vec<u32> {
+        assert!(assignments.len() == self.num_nodes(), "assignments.len() must equal number of nodes");
+        assert!(assignments.iter().all(|&p| p < self.num_parts()), "all assignments must be in range [0, {})", self.num_parts());
+
+        let changed = (0..self.num_nodes()).filter(|&u| self.assignment(u) != assignments[u]).collect::<Vec<_>>();
+        let mut dirty = vec![false; self.num_parts() as usize];
+        for &u in &changed {
+            dirty[self.assignment(u) as usize] = true;
+            dirty[assignments[u] as usize] = true;
+        }
+
+        if changed.len() * REBUILD_FRACTION > self.num_nodes() {
+            self.set_assignments(assignments.to_vec());
+        } else {
+            for &u in &changed { self.move_node(u, assignments[u], false) }
+        }
+        (0..self.num_parts()).filter(|&part| dirty[part as usize]).collect()
+    }
+
     /// Generate assignments map from GeoId to district.
     pub(crate) fn set_assignments(&mut self, assignments: Vec<u32>) {
         assert!(assignments.len() == self.num_nodes(), "assignments.len() must equal number of nodes");
@@ -286,6 +349,7 @@ impl Partition {
         }
 
         self.part_graph = part_graph;
+        self.part_hulls.clear();
     }
 
     /// Sum of a given series for a specific part.
@@ -300,9 +364,36 @@ impl Partition {
             .collect()
     }
 
-    /// Get the total weight of the entire region for a given series.
+    /// Get the total weight of the districted region (the entire region, less any at-large area) for a given series.
     pub(crate) fn region_total(&self, series: &str) -> f64 {
-        self.region_weights.get_as_f64(series, 0).unwrap()
+        self.districted_region().get_as_f64(series, 0).unwrap()
+    }
+
+    /// Exact sums of an integer series for a part and for the whole region (None for float series).
+    pub(crate) fn exact_totals(&self, series: &str, part: u32) -> Option<(i64, i64)> {
+        Some((self.part_weights().get_as_i64(series, part as usize)?, self.districted_region().get_as_i64(series, 0)?))
+    }
+
+    /// Summed weights of the region that districts divide: the whole region, less the at-large area.
+    fn districted_region(&self) -> &WeightMatrix {
+        self.districted_weights.as_deref().unwrap_or(&self.region_weights)
+    }
+
+    /// Heap memory held by the partition, in bytes. The unit graph, weights, and constraints are
+    /// shared between copies of a partition (and the graph and weights with the map), so they
+    /// are only counted if not already in `shared`.
+    pub(crate) fn heap_bytes(&self, shared: &mut SharedBuffers) -> usize {
+        let region = &self.unit_graph.0;
+        self.parts.heap_bytes() + self.frontiers.heap_bytes() + self.frontier_edges.heap_bytes() + self.part_graph.heap_bytes()
+            + self.scratch.heap_bytes()
+            + shared.once(Arc::as_ptr(region), || region.heap_bytes_breakdown().into_iter().map(|(_, bytes)| bytes).sum())
+            + [Some(&self.unit_weights), Some(&self.region_weights), self.districted_weights.as_ref()].into_iter().flatten()
+                .map(|weights| shared.once(Arc::as_ptr(weights), || weights.heap_bytes()))
+                .sum::<usize>()
+            + shared.once(Arc::as_ptr(&self.anchors), || self.anchors.capacity() * size_of::<u32>())
+            + self.groups.as_ref().map_or(0, |groups| shared.once(Arc::as_ptr(groups), || groups.heap_bytes()))
+            + self.clusters.as_ref().map_or(0, |clusters| shared.once(Arc::as_ptr(clusters), || clusters.heap_bytes()))
+            + self.unit_hulls.as_ref().map_or(0, |hulls| shared.once(Arc::as_ptr(hulls), || hulls.heap_bytes()))
     }
 
     /// Get a reference to the part weights matrix.
@@ -313,6 +404,8 @@ impl Partition {
 
     /// Update part weight totals for a single node move (from prev to next part).
     pub(super) fn update_on_node_move(&mut self, node: usize, prev: u32, next: u32) {
+        if let Some(units) = &self.unit_hulls { self.part_hulls.on_move(units, &[node], prev, next) }
+
         // Update node weights between part totals.
         self.part_graph.node_weights_mut().subtract_row_from(
             prev as usize,
@@ -354,6 +447,8 @@ impl Partition {
 
     /// Update part weight totals for a subgraph move (from prev to next part).
     pub(super) fn update_on_subgraph_move(&mut self, subgraph: &[usize], prev: u32, next: u32) {
+        if let Some(units) = &self.unit_hulls { self.part_hulls.on_move(units, subgraph, prev, next) }
+
         // Add/subtract node weights from part totals.
         self.part_graph.node_weights_mut().subtract_rows_from(
             prev as usize,
@@ -398,5 +493,7 @@ impl Partition {
 
     pub(super) fn update_on_merge_parts(&mut self, target: u32, source: u32) {
         self.part_graph.merge_into(target as usize, source as usize);
+        self.part_hulls.invalidate(target);
+        self.part_hulls.invalidate(source);
     }
 }
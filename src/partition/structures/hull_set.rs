@@ -1,9 +1,100 @@
+use geo::{ConcaveHull, ConvexHull, MultiPolygon};
 
-// Maintains relationship between convex hull points and units.
-pub(crate) struct HullSet {
+/// How a district outline is drawn for hull-based compactness scores and exports.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HullKind {
+    /// Convex hull of the district.
+    Convex,
+    /// Concave hull (alpha shape) of the district's boundary vertices.
+    /// Lower `concavity` hugs the boundary more tightly; very high values approach the convex hull.
+    Concave { concavity: f64 },
+    /// The district itself, dissolved exactly from its units.
+    Dissolve,
+}
+
+impl HullKind {
+    /// Concavity used when none is given.
+    pub const DEFAULT_CONCAVITY: f64 = 2.0;
+
+    /// Get the string representation of the HullKind.
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            HullKind::Convex => "convex",
+            HullKind::Concave { .. } => "concave",
+            HullKind::Dissolve => "dissolve",
+        }
+    }
 
+    /// Create a HullKind from a string representation, using the default concavity.
+    pub fn from_name(string: &str) -> Option<HullKind> {
+        match string.to_lowercase().as_str() {
+            "convex" => Some(HullKind::Convex),
+            "concave" | "alpha" => Some(HullKind::Concave { concavity: Self::DEFAULT_CONCAVITY }),
+            "dissolve" | "exact" => Some(HullKind::Dissolve),
+            _ => None,
+        }
+    }
+
+    /// Outline of a district from its exact dissolved boundary.
+    pub(crate) fn outline(&self, boundary: MultiPolygon<f64>) -> MultiPolygon<f64> {
+        if boundary.0.is_empty() { return boundary }
+        match *self {
+            HullKind::Convex => MultiPolygon(vec![boundary.convex_hull()]),
+            HullKind::Concave { concavity } => MultiPolygon(vec![boundary.concave_hull(concavity)]),
+            HullKind::Dissolve => boundary,
+        }
+    }
+}
+
+/// Outline of each part under a given `HullKind`, indexed by part (part 0, unassigned, is left empty).
+pub(crate) struct HullSet {
+    hulls: Vec<MultiPolygon<f64>>,
 }
 
 impl HullSet {
+    /// Build the outline of each part from the exact dissolved boundary of parts 1, 2, ...
+    pub(crate) fn new(kind: HullKind, boundaries: impl IntoIterator<Item = MultiPolygon<f64>>) -> Self {
+        let hulls = std::iter::once(MultiPolygon(vec![]))
+            .chain(boundaries.into_iter().map(|boundary| kind.outline(boundary)))
+            .collect();
+        Self { hulls }
+    }
+
+    /// Get the outline of a part.
+    #[inline] pub(crate) fn get(&self, part: u32) -> &MultiPolygon<f64> { &self.hulls[part as usize] }
+
+    /// Consume the set, returning the outline of each part (including unassigned 0).
+    #[inline] pub(crate) fn into_hulls(self) -> Vec<MultiPolygon<f64>> { self.hulls }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::{Area, polygon};
+
+    /// An L-shaped district: a 2x2 square with its top-right quadrant missing.
+    fn l_shape() -> MultiPolygon<f64> {
+        MultiPolygon(vec![polygon![
+            (x: 0., y: 0.), (x: 2., y: 0.), (x: 2., y: 1.), (x: 1., y: 1.), (x: 1., y: 2.), (x: 0., y: 2.), (x: 0., y: 0.),
+        ]])
+    }
+
+    #[test]
+    fn outlines_nest_by_kind() {
+        let hulls = [HullKind::Dissolve, HullKind::Concave { concavity: 1.0 }, HullKind::Convex]
+            .map(|kind| HullSet::new(kind, [l_shape()]));
+        let areas = hulls.iter().map(|set| set.get(1).unsigned_area()).collect::<Vec<_>>();
+
+        assert_eq!(areas[0], 3.0);
+        assert_eq!(areas[2], 3.5);
+        assert!(areas[0] <= areas[1] && areas[1] <= areas[2]);
+        assert!(hulls[0].get(0).0.is_empty());
+    }
 
+    #[test]
+    fn parses_names() {
+        assert_eq!(HullKind::from_name("Convex"), Some(HullKind::Convex));
+        assert_eq!(HullKind::from_name("concave").map(|kind| kind.to_str()), Some("concave"));
+        assert_eq!(HullKind::from_name("circle"), None);
+    }
 }
@@ -1,10 +1,9 @@
-mod edge_list;
 mod hull_set;
-mod multi_set;
 mod part_graph;
-mod partition_set;
+mod scratch;
 
-pub(crate) use edge_list::FrontierEdgeList;
-pub(crate) use multi_set::MultiSet;
+pub use hull_set::HullKind;
+pub(crate) use hull_set::HullSet;
 pub(crate) use part_graph::PartGraph;
-pub(crate) use partition_set::PartitionSet;
+pub(crate) use scratch::{Scratch, find_root};
+pub(crate) use partset::{FrontierEdgeList, MultiSet, PartitionSet};
@@ -1,15 +1,17 @@
 use ndarray::Array2;
 
-use crate::graph::WeightMatrix;
+use crate::graph::{WeightMatrix, compensated_add};
 
 /// A dense graph over the parts (districts) of a partition.
 ///
 /// Stores per-district aggregated node weights and a symmetric `num_parts × num_parts`
-/// matrix of shared perimeter lengths between every pair of districts.
+/// matrix of shared perimeter lengths between every pair of districts. Perimeters are
+/// compensated sums, so they do not drift with the number or order of moves.
 #[derive(Clone, Debug)]
 pub(crate) struct PartGraph {
     weights: WeightMatrix,
     perimeters: Array2<f64>,
+    perimeter_errors: Array2<f64>, // Neumaier compensation for `perimeters`
 }
 
 impl PartGraph {
@@ -17,28 +19,37 @@ impl PartGraph {
         Self {
             weights,
             perimeters: Array2::zeros((num_parts, num_parts)),
+            perimeter_errors: Array2::zeros((num_parts, num_parts)),
         }
     }
 
     pub(crate) fn node_weights(&self) -> &WeightMatrix { &self.weights }
     pub(crate) fn node_weights_mut(&mut self) -> &mut WeightMatrix { &mut self.weights }
 
+    /// Heap memory held by the part weights and perimeters, in bytes.
+    pub(crate) fn heap_bytes(&self) -> usize {
+        self.weights.heap_bytes() + (self.perimeters.len() + self.perimeter_errors.len()) * size_of::<f64>()
+    }
+
     /// Add `delta` to the shared perimeter between parts `a` and `b`.
     pub(crate) fn add_perimeter(&mut self, a: usize, b: usize, delta: f64) {
-        self.perimeters[[a, b]] += delta;
+        compensated_add(&mut self.perimeters[[a, b]], &mut self.perimeter_errors[[a, b]], delta);
     }
 
     /// Total perimeter of a part: sum of shared perimeters with all other parts.
     pub(crate) fn total_perimeter(&self, part: usize) -> f64 {
-        self.perimeters.row(part).iter().enumerate()
-            .filter(|&(j, _)| j != part)
-            .map(|(_, &w)| w)
-            .sum()
+        let (mut sum, mut error) = (0.0, 0.0);
+        for j in (0..self.perimeters.ncols()).filter(|&j| j != part) {
+            compensated_add(&mut sum, &mut error, self.perimeters[[part, j]]);
+            compensated_add(&mut sum, &mut error, self.perimeter_errors[[part, j]]);
+        }
+        sum + error
     }
 
     /// Zero out all perimeter values.
     pub(crate) fn clear_perimeters(&mut self) {
         self.perimeters.fill(0.0);
+        self.perimeter_errors.fill(0.0);
     }
 
     /// Merge `source` into `target`: accumulate weights and perimeters, then zero out `source`.
@@ -46,11 +57,16 @@ impl PartGraph {
         let n = self.perimeters.nrows();
         for part in 0..n {
             if part != target && part != source {
-                self.perimeters[[target, part]] += self.perimeters[[source, part]];
-                self.perimeters[[part, target]] += self.perimeters[[part, source]];
+                for (from, to) in [([source, part], [target, part]), ([part, source], [part, target])] {
+                    let (value, error) = (self.perimeters[from], self.perimeter_errors[from]);
+                    compensated_add(&mut self.perimeters[to], &mut self.perimeter_errors[to], value);
+                    compensated_add(&mut self.perimeters[to], &mut self.perimeter_errors[to], error);
+                }
+            }
+            for index in [[source, part], [part, source]] {
+                self.perimeters[index] = 0.0;
+                self.perimeter_errors[index] = 0.0;
             }
-            self.perimeters[[source, part]] = 0.0;
-            self.perimeters[[part, source]] = 0.0;
         }
         self.weights.add_row(target, source);
         self.weights.clear_row(source);
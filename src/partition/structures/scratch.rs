@@ -0,0 +1,119 @@
+use std::collections::VecDeque;
+
+/// A set of nodes that is cleared in O(1) by advancing a generation stamp,
+/// so it can be reused by every step of a chain without refilling.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Marks {
+    stamps: Vec<u32>, // Generation in which each node was last marked
+    current: u32,     // Generation of the live set
+}
+
+impl Marks {
+    fn new(num_nodes: usize) -> Self {
+        Self { stamps: vec![0; num_nodes], current: 1 }
+    }
+
+    /// Unmark every node.
+    pub(crate) fn clear(&mut self) {
+        self.current = self.current.wrapping_add(1);
+        if self.current == 0 { self.stamps.fill(0); self.current = 1 }
+    }
+
+    /// Mark `node`, returning true if it was not already marked.
+    #[inline]
+    pub(crate) fn insert(&mut self, node: usize) -> bool {
+        let fresh = self.stamps[node] != self.current;
+        self.stamps[node] = self.current;
+        fresh
+    }
+
+    #[inline]
+    pub(crate) fn contains(&self, node: usize) -> bool { self.stamps[node] == self.current }
+}
+
+/// Buffers for the temporaries of each move proposal (BFS queues, visited marks, node lists),
+/// allocated once per partition and reset between uses rather than reallocated at every step.
+#[derive(Debug, Default)]
+pub(crate) struct Scratch {
+    pub(crate) members: Marks,         // Nodes of the subgraph being moved
+    pub(crate) targets: Marks,         // Nodes a BFS must reach
+    pub(crate) visited: Marks,         // Nodes reached by a BFS
+    pub(crate) queue: VecDeque<usize>, // BFS queue
+    pub(crate) queues: Vec<VecDeque<usize>>, // One BFS queue per search of a multi-source BFS
+    pub(crate) labels: Vec<usize>,     // Search that reached each visited node
+    pub(crate) roots: Vec<usize>,      // Union-find parent of each search, merged when searches meet
+    pub(crate) nodes: Vec<usize>,      // Deduplicated subgraph or same-part neighbors of a node
+    pub(crate) boundary: Vec<usize>,   // Nodes bordering a subgraph, or move candidates
+}
+
+impl Scratch {
+    pub(crate) fn new(num_nodes: usize) -> Self {
+        Self {
+            members: Marks::new(num_nodes),
+            targets: Marks::new(num_nodes),
+            visited: Marks::new(num_nodes),
+            queue: VecDeque::new(),
+            queues: Vec::new(),
+            labels: vec![0; num_nodes],
+            roots: Vec::new(),
+            nodes: Vec::new(),
+            boundary: Vec::new(),
+        }
+    }
+
+    /// Heap memory held by the buffers, in bytes.
+    pub(crate) fn heap_bytes(&self) -> usize {
+        let marks = self.members.stamps.capacity() + self.targets.stamps.capacity() + self.visited.stamps.capacity();
+        let queues = self.queue.capacity() + self.queues.iter().map(VecDeque::capacity).sum::<usize>();
+        let lists = self.labels.capacity() + self.roots.capacity() + self.nodes.capacity() + self.boundary.capacity();
+        marks * size_of::<u32>() + (queues + lists) * size_of::<usize>()
+    }
+}
+
+/// A clone gets fresh buffers for the same number of nodes; contents are never carried over.
+impl Clone for Scratch {
+    fn clone(&self) -> Self { Self::new(self.labels.len()) }
+}
+
+/// Representative of `search` among the searches merged with it, given union-find parents `roots`.
+pub(crate) fn find_root(roots: &mut [usize], mut search: usize) -> usize {
+    while roots[search] != search {
+        roots[search] = roots[roots[search]]; // path halving
+        search = roots[search];
+    }
+    search
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marks_clear_between_uses() {
+        let mut marks = Marks::new(4);
+        assert!(marks.insert(2) && !marks.insert(2));
+        assert!(marks.contains(2) && !marks.contains(1));
+        marks.clear();
+        assert!(!marks.contains(2));
+
+        // Wrapping the generation counter must not resurrect stale marks.
+        marks.insert(3);
+        marks.current = u32::MAX;
+        marks.insert(1);
+        marks.clear();
+        assert!((0..4).all(|node| !marks.contains(node)));
+    }
+
+    #[test]
+    fn clones_start_empty() {
+        let mut scratch = Scratch::new(4);
+        scratch.members.insert(1);
+        scratch.nodes.extend([0, 1, 2]);
+        scratch.queue.push_back(3);
+
+        let clone = scratch.clone();
+        assert_eq!(clone.labels.len(), 4);
+        assert!(!clone.members.contains(1));
+        assert!(clone.nodes.is_empty() && clone.queue.is_empty());
+    }
+}
@@ -0,0 +1,156 @@
+use std::{collections::BTreeMap, path::Path};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::plan::{Plan, metadata::now_unix};
+
+/// What initiated an edit to a plan.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EditSource {
+    /// A direct assignment by the user (assigning units, loading assignments).
+    Manual,
+    /// An optimization or sampling step (randomize, equalize, anneal, recombine, ...).
+    Chain,
+}
+
+/// A single recorded operation on a plan, listing the blocks it moved into each district.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub seq: u64,
+    pub timestamp: Option<u64>,            // Seconds since the Unix epoch
+    pub operation: String,                 // Name of the `Plan` method, e.g. "assign_unit"
+    pub source: EditSource,
+    pub moves: BTreeMap<u32, Vec<String>>, // District -> block GeoIDs moved into it
+}
+
+/// Append-only log of the operations applied to a plan.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AuditLog {
+    entries: Vec<AuditEntry>,
+}
+
+impl AuditLog {
+    /// Get the recorded entries, oldest first.
+    #[inline] pub fn entries(&self) -> &[AuditEntry] { &self.entries }
+
+    /// Number of recorded entries.
+    #[inline] pub fn len(&self) -> usize { self.entries.len() }
+
+    /// Check if the log has no entries.
+    #[inline] pub fn is_empty(&self) -> bool { self.entries.is_empty() }
+
+    /// Serialize the log as JSON Lines, one entry per line.
+    pub fn to_jsonl(&self) -> Result<String> {
+        self.entries.iter()
+            .map(|entry| Ok(serde_json::to_string(entry)? + "\n"))
+            .collect()
+    }
+}
+
+impl Plan {
+    /// Start recording every assignment operation on this plan. No-op if already recording.
+    pub fn enable_audit_log(&mut self) {
+        if self.audit.is_none() { self.audit = Some(AuditLog::default()) }
+    }
+
+    /// Stop recording and discard the audit log.
+    pub fn disable_audit_log(&mut self) { self.audit = None }
+
+    /// Get the audit log, if recording is enabled.
+    #[inline] pub fn audit_log(&self) -> Option<&AuditLog> { self.audit.as_ref() }
+
+    /// Write the audit log to a JSON Lines file.
+    pub fn write_audit_log(&self, path: &Path) -> Result<()> {
+        let log = self.audit.as_ref().context("[Plan] Audit log is not enabled")?;
+        std::fs::write(path, log.to_jsonl()?)
+            .with_context(|| format!("[Plan] Failed to write audit log {}", path.display()))
+    }
+
+    /// Append an entry for an operation that moved each block in `nodes` to the matching district in `parts`.
+    pub(super) fn record_audit(&mut self, operation: &str, source: EditSource, nodes: &[u32], parts: &[u32]) -> Result<()> {
+        if self.audit.is_none() { return Ok(()) }
+
+        let geo_ids = self.map().base()?.geo_ids();
+        let mut moves: BTreeMap<u32, Vec<String>> = BTreeMap::new();
+        for (&node, &part) in nodes.iter().zip(parts) {
+            moves.entry(part).or_default().push(geo_ids[node as usize].id().to_string());
+        }
+
+        if let Some(log) = self.audit.as_mut() {
+            log.entries.push(AuditEntry {
+                seq: log.entries.len() as u64,
+                timestamp: now_unix(),
+                operation: operation.to_string(),
+                source,
+                moves,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_maps;
+
+    fn moves(entries: &[(u32, &[&str])]) -> BTreeMap<u32, Vec<String>> {
+        entries.iter().map(|&(part, ids)| (part, ids.iter().map(|id| id.to_string()).collect())).collect()
+    }
+
+    #[test]
+    fn edits_undo_and_redo_are_recorded() {
+        let mut plan = Plan::new(test_maps::row(3, &["000000000000000", "000000000000001", "000000000000002"]), 2).unwrap();
+        plan.enable_audit_log();
+        plan.enable_history(100);
+        plan.set_assignments_vec(vec![1, 2, 2]).unwrap();
+        plan.set_assignments_vec(vec![1, 2, 2]).unwrap();
+        plan.assign_unit("block", "000000000000001", 1).unwrap();
+        plan.undo().unwrap();
+        plan.redo().unwrap();
+
+        let log = plan.audit_log().unwrap();
+        let summary = log.entries().iter().map(|entry| (entry.seq, entry.operation.as_str(), entry.source)).collect::<Vec<_>>();
+        assert_eq!(summary, [
+            (0, "set_assignments", EditSource::Manual),
+            (1, "assign_unit", EditSource::Manual),
+            (2, "undo", EditSource::Manual),
+            (3, "redo", EditSource::Manual),
+        ]);
+        assert_eq!(log.entries()[0].moves, moves(&[(1, &["000000000000000"]), (2, &["000000000000001", "000000000000002"])]));
+        assert_eq!(log.entries()[2].moves, moves(&[(2, &["000000000000001"])]));
+        assert_eq!(log.entries()[3].moves, moves(&[(1, &["000000000000001"])]));
+    }
+
+    #[test]
+    fn chain_steps_are_recorded_as_chain() {
+        let mut plan = Plan::new(test_maps::row(4, &[]), 2).unwrap();
+        plan.enable_audit_log();
+        plan.randomize().unwrap();
+
+        let entry = &plan.audit_log().unwrap().entries()[0];
+        assert_eq!((entry.operation.as_str(), entry.source), ("randomize", EditSource::Chain));
+        assert_eq!(entry.moves.values().map(Vec::len).sum::<usize>(), 4);
+        assert!(entry.moves.keys().all(|&part| part == 1 || part == 2));
+    }
+
+    #[test]
+    fn logs_are_written_as_json_lines() {
+        let mut plan = Plan::new(test_maps::row(2, &[]), 2).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        assert!(plan.write_audit_log(&path).is_err());
+
+        plan.enable_audit_log();
+        plan.set_assignments_vec(vec![1, 2]).unwrap();
+        plan.set_assignments_vec(vec![2, 1]).unwrap();
+        plan.write_audit_log(&path).unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let entries = text.lines().map(|line| serde_json::from_str::<AuditEntry>(line).unwrap()).collect::<Vec<_>>();
+        assert_eq!(entries, plan.audit_log().unwrap().entries());
+        assert!(text.lines().all(|line| line.contains("\"source\":\"manual\"")));
+    }
+}
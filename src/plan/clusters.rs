@@ -0,0 +1,256 @@
+use std::collections::{BTreeSet, HashMap};
+
+use anyhow::{Result, bail, ensure};
+use serde::{Deserialize, Serialize};
+
+use crate::{map::GeoType, plan::{EditSource, Plan}};
+
+/// A connected group of whole counties whose population fits a whole number of districts.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CountyCluster {
+    pub counties: Vec<String>,  // County GEOIDs (block GEOIDs for blocks outside any county)
+    pub districts: u32,         // Number of districts drawn within the cluster
+    pub total: f64,             // Cluster total of the population series
+}
+
+impl Plan {
+    /// Enumerate county clusterings, as required by county-grouping rules (e.g. North Carolina's
+    /// or Virginia's): ways to split the state into connected clusters of whole counties whose
+    /// `pop_series` totals are each within `tolerance` (relative) of a whole number of ideal
+    /// districts, adding up to the plan's number of districts.
+    ///
+    /// Stops after `limit` clusterings. Clusters are grown smallest first, so finer clusterings
+    /// tend to be found first; results are sorted by number of clusters, most first. The search
+    /// is exhaustive up to `limit`, so it can take long on states with many counties and loose
+    /// tolerances.
+    pub fn county_clusters(&self, pop_series: &str, tolerance: f64, limit: usize) -> Result<Vec<Vec<CountyCluster>>> {
+        ensure!(self.series().contains(pop_series), "[Plan.county_clusters] Unknown series '{pop_series}'");
+        ensure!(tolerance >= 0.0, "[Plan.county_clusters] Tolerance must be non-negative");
+
+        let (keys, county_of) = self.county_keys()?;
+        let base = self.map().base()?;
+        let (graph, weights) = (base.get_unit_graph(), base.get_unit_weights());
+        let mut totals = vec![0.0; keys.len()];
+        let mut neighbors = vec![BTreeSet::new(); keys.len()];
+        for (u, &county) in county_of.iter().enumerate() {
+            totals[county as usize] += weights.get_as_f64(pop_series, u).unwrap();
+            for v in graph.edges(u) {
+                if county_of[v] != county { neighbors[county as usize].insert(county_of[v] as usize); }
+            }
+        }
+        let adjacency = neighbors.into_iter().map(|set| set.into_iter().collect()).collect::<Vec<Vec<_>>>();
+
+        let ideal = totals.iter().sum::<f64>() / self.num_districts() as f64;
+        let mut clusterings = enumerate_clusterings(&totals, &adjacency, ideal, tolerance, self.num_districts(), limit);
+        clusterings.sort_by_key(|clustering| std::cmp::Reverse(clustering.len()));
+
+        Ok(clusterings.into_iter().map(|clustering| clustering.into_iter().map(|(counties, districts)| CountyCluster {
+            total: counties.iter().map(|&c| totals[c]).sum(),
+            counties: counties.into_iter().map(|c| keys[c].clone()).collect(),
+            districts,
+        }).collect()).collect())
+    }
+
+    /// Confine districts to a county clustering (e.g. one from `county_clusters`): the first
+    /// cluster's `districts` districts are numbered first, and so on, and every block may only be
+    /// assigned to a district of its cluster. Optimizers and samplers respect the constraint.
+    ///
+    /// The plan is redrawn at random within the clusters.
+    pub fn set_county_clusters(&mut self, clusters: &[CountyCluster]) -> Result<()> {
+        let cluster_of = self.cluster_of_units(clusters, "[Plan.set_county_clusters]")?;
+        let part_cluster = part_clusters(clusters);
+        self.tracked("set_county_clusters", EditSource::Manual, |plan| {
+            plan.partition.set_clusters(cluster_of, part_cluster);
+            plan.partition.randomize();
+            Ok(())
+        })
+    }
+
+    /// Remove the county clustering constraint, leaving the current assignment as is.
+    pub fn clear_county_clusters(&mut self) { self.partition.clear_clusters() }
+
+    /// Check that every block is in a district of its county cluster (true without clusters).
+    pub fn county_clusters_satisfied(&self) -> bool { self.partition.clusters_satisfied() }
+
+    /// Check that `clusters` cover every county once, with districts adding up to the plan's,
+    /// and get the cluster index of each base unit.
+    pub(super) fn cluster_of_units(&self, clusters: &[CountyCluster], context: &str) -> Result<Vec<u32>> {
+        let districts = clusters.iter().map(|cluster| cluster.districts).sum::<u32>();
+        ensure!(districts == self.num_districts(),
+            "{context} Clusters hold {districts} districts, but the plan has {}", self.num_districts());
+        ensure!(clusters.iter().all(|cluster| cluster.districts > 0), "{context} Every cluster needs a district");
+
+        let (keys, county_of) = self.county_keys()?;
+        let index = keys.iter().enumerate().map(|(i, key)| (key.as_str(), i)).collect::<HashMap<_, _>>();
+        let mut county_cluster = vec![None; keys.len()];
+        for (c, cluster) in clusters.iter().enumerate() {
+            for county in &cluster.counties {
+                let Some(&i) = index.get(county.as_str()) else { bail!("{context} Unknown county '{county}'") };
+                ensure!(county_cluster[i].replace(c as u32).is_none(), "{context} County '{county}' is in two clusters");
+            }
+        }
+        if let Some(i) = county_cluster.iter().position(Option::is_none) {
+            bail!("{context} County '{}' is in no cluster", keys[i])
+        }
+
+        Ok(county_of.iter().map(|&county| county_cluster[county as usize].unwrap()).collect())
+    }
+
+    /// GEOID of each county, and the county index of each base unit.
+    /// Units outside any county count as counties of their own.
+    pub(super) fn county_keys(&self) -> Result<(Vec<String>, Vec<u32>)> {
+        let base = self.map().base()?;
+        let mut keys = Vec::new();
+        let mut index = HashMap::new();
+        let county_of = base.parents().iter().enumerate()
+            .map(|(i, refs)| {
+                let key = refs.get(GeoType::County).unwrap_or(&base.geo_ids()[i]).id();
+                *index.entry(key).or_insert_with(|| { keys.push(key.to_string()); keys.len() as u32 - 1 })
+            })
+            .collect();
+        Ok((keys, county_of))
+    }
+}
+
+/// Cluster index of each part, numbering each cluster's districts in turn (part 0 is in none).
+pub(super) fn part_clusters(clusters: &[CountyCluster]) -> Vec<u32> {
+    std::iter::once(u32::MAX)
+        .chain(clusters.iter().enumerate().flat_map(|(c, cluster)| std::iter::repeat_n(c as u32, cluster.districts as usize)))
+        .collect()
+}
+
+/// Enumerate partitions of a county graph into connected clusters whose totals are each within
+/// `tolerance` of a whole number of `ideal` districts, `num_districts` in all. Each clustering is
+/// a list of clusters, as county indices and district counts. Stops after `limit` clusterings.
+fn enumerate_clusterings(
+    totals: &[f64],
+    adjacency: &[Vec<usize>],
+    ideal: f64,
+    tolerance: f64,
+    num_districts: u32,
+    limit: usize,
+) -> Vec<Vec<(Vec<usize>, u32)>> {
+    let mut search = ClusterSearch {
+        totals, adjacency, ideal, tolerance, num_districts, limit,
+        taken: vec![false; totals.len()],
+        forbidden: vec![false; totals.len()],
+        current: vec![],
+        found: vec![],
+    };
+    search.cover();
+    search.found
+}
+
+struct ClusterSearch<'a> {
+    totals: &'a [f64],
+    adjacency: &'a [Vec<usize>],
+    ideal: f64,
+    tolerance: f64,
+    num_districts: u32,
+    limit: usize,
+    taken: Vec<bool>,       // Counties in a cluster so far (including the one being grown)
+    forbidden: Vec<bool>,   // Counties excluded from the cluster being grown
+    current: Vec<(Vec<usize>, u32)>,
+    found: Vec<Vec<(Vec<usize>, u32)>>,
+}
+
+impl ClusterSearch<'_> {
+    /// Number of districts a cluster with this total holds, if it fits a whole number of them.
+    fn districts(&self, total: f64) -> Option<u32> {
+        let k = (total / self.ideal).round().max(1.0);
+        ((total - k * self.ideal).abs() <= k * self.ideal * self.tolerance).then_some(k as u32)
+    }
+
+    /// Cluster the counties not yet taken, starting from the first of them.
+    fn cover(&mut self) {
+        if self.found.len() >= self.limit { return }
+        let Some(start) = self.taken.iter().position(|&taken| !taken) else {
+            if self.current.iter().map(|&(_, k)| k).sum::<u32>() == self.num_districts { self.found.push(self.current.clone()) }
+            return;
+        };
+
+        self.taken[start] = true;
+        let candidates = self.adjacency[start].iter().copied().filter(|&c| !self.taken[c]).collect();
+        self.grow(&mut vec![start], self.totals[start], candidates);
+        self.taken[start] = false;
+    }
+
+    /// Try cluster `members` (totalling `total`), then every connected superset adding counties
+    /// from `candidates` but none that are forbidden.
+    fn grow(&mut self, members: &mut Vec<usize>, total: f64, candidates: Vec<usize>) {
+        if !self.rest_feasible(&candidates) { return }
+
+        if let Some(k) = self.districts(total) && self.rest_feasible(&[]) {
+            // Counties excluded from this cluster are free to join the next ones.
+            let forbidden = std::mem::replace(&mut self.forbidden, vec![false; self.totals.len()]);
+            self.current.push((members.clone(), k));
+            self.cover();
+            self.current.pop();
+            self.forbidden = forbidden;
+        }
+
+        let max_total = self.ideal * self.num_districts as f64 * (1.0 + self.tolerance);
+        for (i, &county) in candidates.iter().enumerate() {
+            if self.found.len() >= self.limit { break }
+            if total + self.totals[county] <= max_total {
+                let mut next = candidates[i + 1..].to_vec();
+                next.extend(self.adjacency[county].iter().copied()
+                    .filter(|&c| !self.taken[c] && !self.forbidden[c] && c != county && !candidates.contains(&c)));
+                self.taken[county] = true;
+                members.push(county);
+                self.grow(members, total + self.totals[county], next);
+                members.pop();
+                self.taken[county] = false;
+            }
+            self.forbidden[county] = true;
+        }
+        for &county in &candidates { self.forbidden[county] = false }
+    }
+
+    /// Check that every connected piece of the untaken counties that the current cluster can no
+    /// longer reach (through `candidates`) fits a whole number of districts.
+    fn rest_feasible(&self, candidates: &[usize]) -> bool {
+        let mut seen = self.taken.clone();
+        for start in 0..self.totals.len() {
+            if seen[start] { continue }
+            seen[start] = true;
+            let (mut stack, mut total, mut reachable) = (vec![start], 0.0, false);
+            while let Some(c) = stack.pop() {
+                total += self.totals[c];
+                reachable |= candidates.contains(&c);
+                for &d in &self.adjacency[c] {
+                    if !seen[d] { seen[d] = true; stack.push(d) }
+                }
+            }
+            if !reachable && self.districts(total).is_none() { return false }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enumerates_clusterings_of_a_county_path() {
+        // Counties 0 - 1 - 2 - 3 with totals 1, 1, 2, 2: three districts of 2.
+        let adjacency = vec![vec![1], vec![0, 2], vec![1, 3], vec![2]];
+        let mut clusterings = enumerate_clusterings(&[1.0, 1.0, 2.0, 2.0], &adjacency, 2.0, 0.0, 3, 10);
+        clusterings.sort();
+        assert_eq!(clusterings, vec![
+            vec![(vec![0, 1], 1), (vec![2], 1), (vec![3], 1)],
+            vec![(vec![0, 1], 1), (vec![2, 3], 2)],
+            vec![(vec![0, 1, 2], 2), (vec![3], 1)],
+            vec![(vec![0, 1, 2, 3], 3)],
+        ]);
+
+        assert_eq!(enumerate_clusterings(&[1.0, 1.0, 2.0, 2.0], &adjacency, 2.0, 0.0, 3, 2).len(), 2);
+
+        // Counties passed over by the first cluster can still join later ones.
+        let adjacency = vec![vec![1, 2, 3], vec![0, 2], vec![0, 1], vec![0]];
+        let mut clusterings = enumerate_clusterings(&[1.0; 4], &adjacency, 2.0, 0.0, 2, 10);
+        clusterings.sort();
+        assert_eq!(clusterings, vec![vec![(vec![0, 1, 2, 3], 2)], vec![(vec![0, 3], 1), (vec![1, 2], 1)]]);
+    }
+}
@@ -0,0 +1,181 @@
+use anyhow::{Result, bail, ensure};
+
+use crate::{map::Map, plan::Plan};
+
+const MAGIC: &[u8; 4] = b"OMAC";
+const VERSION: u8 = 1;
+
+/// Compact binary encoding of block assignments for storing many plans of one map.
+///
+/// Blocks are visited along a Hilbert curve, so a contiguous district becomes a few long runs.
+/// District ids are renumbered in order of first appearance and stored, with run lengths, as
+/// varints. A typical plan takes a few KB instead of several bytes per block.
+///
+/// Layout: `"OMAC"`, version byte, then varints `num_units`, `num_districts`, the district table
+/// (length, then original ids), and the runs (count, then `(table index, length)` pairs).
+/// Block order depends only on the map's geometry bounds, so plans encoded with one codec decode
+/// with any codec built from the same map.
+#[derive(Clone, Debug)]
+pub struct AssignmentCodec {
+    order: Vec<u32>,
+}
+
+impl AssignmentCodec {
+    /// Build a codec for the blocks of `map`.
+    pub fn new(map: &Map) -> Result<Self> {
+        let order = map.base()?.region().hilbert_order().into_iter().map(|unit| unit.0).collect();
+        Ok(Self { order })
+    }
+
+    /// Number of blocks this codec encodes.
+    #[inline] pub fn len(&self) -> usize { self.order.len() }
+
+    /// Check whether the codec's map has no blocks.
+    #[inline] pub fn is_empty(&self) -> bool { self.order.is_empty() }
+
+    /// Encode one district per block (0 for unassigned) as compact bytes.
+    pub fn encode(&self, assignments: &[u32], num_districts: u32) -> Result<Vec<u8>> {
+        ensure!(assignments.len() == self.order.len(),
+            "[AssignmentCodec] Got {} assignments, expected {}", assignments.len(), self.order.len());
+        ensure!(assignments.iter().all(|&district| district <= num_districts),
+            "[AssignmentCodec] District out of range for {} districts", num_districts);
+        let ordered = self.order.iter().map(|&u| assignments[u as usize]).collect::<Vec<_>>();
+        Ok(encode_runs(&ordered, num_districts))
+    }
+
+    /// Decode bytes from [`AssignmentCodec::encode`], returning `(assignments, num_districts)`.
+    pub fn decode(&self, bytes: &[u8]) -> Result<(Vec<u32>, u32)> {
+        let (ordered, num_districts) = decode_runs(bytes)?;
+        ensure!(ordered.len() == self.order.len(),
+            "[AssignmentCodec] Encoded plan has {} blocks, expected {}", ordered.len(), self.order.len());
+        let mut assignments = vec![0; ordered.len()];
+        for (&u, district) in self.order.iter().zip(ordered) {
+            assignments[u as usize] = district;
+        }
+        Ok((assignments, num_districts))
+    }
+}
+
+impl Plan {
+    /// Build an [`AssignmentCodec`] for this plan's map, to share across many plans of that map.
+    pub fn assignment_codec(&self) -> Result<AssignmentCodec> {
+        AssignmentCodec::new(self.map())
+    }
+
+    /// Encode this plan's assignments compactly (see [`AssignmentCodec`]).
+    pub fn to_compact_bytes(&self, codec: &AssignmentCodec) -> Result<Vec<u8>> {
+        codec.encode(&self.partition.assignments(), self.num_districts())
+    }
+
+    /// Replace this plan's assignments with bytes from [`Plan::to_compact_bytes`] for a plan with the
+    /// same district count.
+    pub fn load_compact_bytes(&mut self, codec: &AssignmentCodec, bytes: &[u8]) -> Result<()> {
+        let (assignments, num_districts) = codec.decode(bytes)?;
+        ensure!(num_districts == self.num_districts(),
+            "[Plan] Encoded plan has {} districts, expected {}", num_districts, self.num_districts());
+        self.set_assignments_vec(assignments)
+    }
+}
+
+/// Run-length encode a label sequence, renumbering labels in order of first appearance.
+fn encode_runs(labels: &[u32], num_districts: u32) -> Vec<u8> {
+    let mut table = Vec::new();
+    let mut remap = vec![u32::MAX; num_districts as usize + 1];
+    let mut runs: Vec<(u32, u32)> = Vec::new();
+    for &label in labels {
+        if remap[label as usize] == u32::MAX {
+            remap[label as usize] = table.len() as u32;
+            table.push(label);
+        }
+        match runs.last_mut() {
+            Some((last, length)) if *last == remap[label as usize] => *length += 1,
+            _ => runs.push((remap[label as usize], 1)),
+        }
+    }
+
+    let mut bytes = MAGIC.to_vec();
+    bytes.push(VERSION);
+    write_varint(&mut bytes, labels.len() as u64);
+    write_varint(&mut bytes, num_districts as u64);
+    write_varint(&mut bytes, table.len() as u64);
+    for &label in &table { write_varint(&mut bytes, label as u64) }
+    write_varint(&mut bytes, runs.len() as u64);
+    for &(index, length) in &runs {
+        write_varint(&mut bytes, index as u64);
+        write_varint(&mut bytes, length as u64);
+    }
+    bytes
+}
+
+/// Inverse of [`encode_runs`], returning `(labels, num_districts)`.
+fn decode_runs(bytes: &[u8]) -> Result<(Vec<u32>, u32)> {
+    ensure!(bytes.len() > MAGIC.len() && bytes.starts_with(MAGIC), "[AssignmentCodec] Not an encoded plan");
+    let version = bytes[MAGIC.len()];
+    ensure!(version == VERSION, "[AssignmentCodec] Unsupported version {}", version);
+
+    let mut pos = MAGIC.len() + 1;
+    let num_units = read_varint(bytes, &mut pos)? as usize;
+    let num_districts = u32::try_from(read_varint(bytes, &mut pos)?)?;
+    let table = (0..read_varint(bytes, &mut pos)?)
+        .map(|_| {
+            let label = read_varint(bytes, &mut pos)?;
+            ensure!(label <= num_districts as u64, "[AssignmentCodec] District {} out of range", label);
+            Ok(label as u32)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut labels = Vec::new();
+    for _ in 0..read_varint(bytes, &mut pos)? {
+        let index = read_varint(bytes, &mut pos)? as usize;
+        let length = read_varint(bytes, &mut pos)? as usize;
+        let Some(&label) = table.get(index) else { bail!("[AssignmentCodec] Run refers to unknown district {}", index) };
+        ensure!(labels.len() + length <= num_units, "[AssignmentCodec] Runs cover more than {} blocks", num_units);
+        labels.resize(labels.len() + length, label);
+    }
+    ensure!(labels.len() == num_units, "[AssignmentCodec] Runs cover {} of {} blocks", labels.len(), num_units);
+    ensure!(pos == bytes.len(), "[AssignmentCodec] Trailing bytes after encoded plan");
+    Ok((labels, num_districts))
+}
+
+/// Append `value` as an unsigned LEB128 varint.
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+/// Read an unsigned LEB128 varint at `pos`, advancing it.
+fn read_varint(bytes: &[u8], pos: &mut usize) -> Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let Some(&byte) = bytes.get(*pos) else { bail!("[AssignmentCodec] Truncated encoded plan") };
+        *pos += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 { return Ok(value) }
+    }
+    bail!("[AssignmentCodec] Varint too long")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_round_trip_and_stay_small() {
+        let labels = [vec![2; 1000], vec![0; 3], vec![1; 500], vec![2; 10]].concat();
+        let bytes = encode_runs(&labels, 3);
+        assert!(bytes.len() < 32);
+        assert_eq!(decode_runs(&bytes).unwrap(), (labels, 3));
+        assert_eq!(decode_runs(&encode_runs(&[], 4)).unwrap(), (vec![], 4));
+    }
+
+    #[test]
+    fn corrupt_bytes_are_rejected() {
+        let bytes = encode_runs(&[1, 1, 2, 2, 2], 2);
+        assert!(decode_runs(&bytes[..bytes.len() - 1]).is_err());
+        assert!(decode_runs(&[bytes.as_slice(), &[0]].concat()).is_err());
+        assert!(decode_runs(b"nope").is_err());
+    }
+}
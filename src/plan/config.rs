@@ -0,0 +1,249 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Constraints, Objective, RelaxedConstraint,
+    map::GeoType,
+    partition::{AnnealProgress, BalanceBound, Kernel, KernelMix, MoveConstraints, Proposal, RunProfile, StepObserver, StoppingRules, WeightedProposal},
+    plan::{EditSource, Plan},
+};
+
+/// Full description of a multi-phase annealing run (see [`Plan::anneal`]), one objective per phase.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AnnealConfig {
+    pub objectives: Vec<Objective>,
+    pub max_iter: usize,
+    pub phase_start_probs: Vec<f64>,
+    pub phase_end_probs: Vec<Option<f64>>,
+    pub phase_cooling_rates: Vec<f64>,
+    #[serde(default = "AnnealConfig::default_init_temp")]
+    pub init_temp: f64,
+    #[serde(default = "AnnealConfig::default_early_stop_iters")]
+    pub early_stop_iters: usize,
+    #[serde(default = "AnnealConfig::default_batch_size")]
+    pub temp_search_batch_size: usize,
+    #[serde(default = "AnnealConfig::default_batch_size")]
+    pub batch_size: usize,
+    #[serde(default, skip_serializing_if = "StoppingRules::is_empty")]
+    pub stop: StoppingRules,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub proposals: Vec<WeightedProposal>, // Mix of move kinds (single flips if empty)
+    #[serde(default, skip_serializing_if = "Constraints::is_empty")]
+    pub constraints: Constraints,         // Hard constraints enforced on every move
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub profile: bool,                    // Time each stage of the run (see `Plan::run_profile`)
+}
+
+impl AnnealConfig {
+    fn default_init_temp() -> f64 { 1.0 }
+    fn default_early_stop_iters() -> usize { 100_000 }
+    fn default_batch_size() -> usize { 1000 }
+}
+
+/// Full description of a relaxed-constraint annealing run (see [`Plan::anneal_relaxed`]).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RelaxedAnnealConfig {
+    pub objective: Objective,
+    pub constraints: Vec<RelaxedConstraint>,
+    pub max_iter: usize,
+    #[serde(default = "RelaxedAnnealConfig::default_initial_temp")]
+    pub initial_temp: f64,
+    #[serde(default = "RelaxedAnnealConfig::default_final_temp")]
+    pub final_temp: f64,
+    #[serde(default = "RelaxedAnnealConfig::default_initial_penalty")]
+    pub initial_penalty: f64,
+    #[serde(default = "RelaxedAnnealConfig::default_final_penalty")]
+    pub final_penalty: f64,
+    #[serde(default, skip_serializing_if = "StoppingRules::is_empty")]
+    pub stop: StoppingRules,
+}
+
+impl RelaxedAnnealConfig {
+    fn default_initial_temp() -> f64 { 1.0 }
+    fn default_final_temp() -> f64 { 1e-4 }
+    fn default_initial_penalty() -> f64 { 1.0 }
+    fn default_final_penalty() -> f64 { 1000.0 }
+}
+
+impl Plan {
+    /// Run multi-phase simulated annealing as described by a config.
+    pub fn anneal_with_config(&mut self, config: &AnnealConfig) -> Result<()> {
+        self.anneal_with_observer(config, &mut ())
+    }
+
+    /// Run multi-phase simulated annealing as described by a config,
+    /// calling `on_progress` after each batch of moves (e.g. to stream a live run to a dashboard).
+    pub fn anneal_with_progress(&mut self, config: &AnnealConfig, mut on_progress: impl FnMut(&AnnealProgress)) -> Result<()> {
+        self.check_complete("[Plan.anneal]")?;
+        let proposals = self.kernel_mix(&config.proposals)?;
+        let constraints = self.move_constraints(config)?;
+        self.tracked("anneal", EditSource::Chain, |plan| {
+            plan.profile = plan.partition.anneal(
+                &config.objectives, config.max_iter, config.init_temp,
+                &config.phase_start_probs, &config.phase_end_probs, &config.phase_cooling_rates,
+                config.early_stop_iters, config.temp_search_batch_size, config.batch_size, &mut on_progress, &mut (),
+                &config.stop, &proposals, &constraints, config.profile,
+            );
+            Ok(())
+        })
+    }
+
+    /// Run multi-phase simulated annealing as described by a config, passing each step that
+    /// `steps` wants to it (e.g. a [`StepBuffer`](crate::StepBuffer) or [`CsvStepWriter`](crate::CsvStepWriter)).
+    pub fn anneal_with_observer(&mut self, config: &AnnealConfig, steps: &mut dyn StepObserver) -> Result<()> {
+        self.check_complete("[Plan.anneal]")?;
+        let proposals = self.kernel_mix(&config.proposals)?;
+        let constraints = self.move_constraints(config)?;
+        self.tracked("anneal", EditSource::Chain, |plan| {
+            plan.profile = plan.partition.anneal(
+                &config.objectives, config.max_iter, config.init_temp,
+                &config.phase_start_probs, &config.phase_end_probs, &config.phase_cooling_rates,
+                config.early_stop_iters, config.temp_search_batch_size, config.batch_size, &mut |_| {}, steps,
+                &config.stop, &proposals, &constraints, config.profile,
+            );
+            Ok(())
+        })
+    }
+
+    /// Time spent in each stage of the last annealing run, if its config set `profile`.
+    pub fn run_profile(&self) -> Option<&RunProfile> { self.profile.as_ref() }
+
+    /// Hard constraints of a run, resolved against the map (see [`Plan::resolve_constraints`]).
+    fn move_constraints(&self, config: &AnnealConfig) -> Result<MoveConstraints> {
+        self.check_balance(&config.constraints.balance)?;
+        self.resolve_constraints(&config.constraints)
+    }
+
+    /// Check that every block outside the at-large area is assigned, as optimization requires.
+    pub(super) fn check_complete(&self, context: &str) -> Result<()> {
+        anyhow::ensure!(self.partition.only_at_large_unassigned(),
+            "{context} Unassigned blocks outside the at-large area; assign every block first");
+        Ok(())
+    }
+
+    /// Check that every balance bound names a series of the map.
+    fn check_balance(&self, balance: &[BalanceBound]) -> Result<()> {
+        match balance.iter().find(|bound| !self.series().contains(&bound.series)) {
+            Some(bound) => anyhow::bail!("[Plan.anneal] Unknown balance series '{}'", bound.series),
+            None => Ok(()),
+        }
+    }
+
+    /// Check that a relaxed-constraint run is well formed for this plan.
+    fn check_relaxed(&self, config: &RelaxedAnnealConfig) -> Result<()> {
+        anyhow::ensure!(self.num_districts() > 1, "[Plan.anneal_relaxed] Need at least two districts");
+        self.check_complete("[Plan.anneal_relaxed]")?;
+        anyhow::ensure!(config.max_iter > 0, "[Plan.anneal_relaxed] max_iter must be positive");
+        anyhow::ensure!(config.initial_temp > 0.0 && config.final_temp > 0.0, "[Plan.anneal_relaxed] Temperatures must be positive");
+        anyhow::ensure!(config.initial_penalty > 0.0 && config.final_penalty > 0.0, "[Plan.anneal_relaxed] Penalties must be positive");
+        for constraint in &config.constraints {
+            anyhow::ensure!(constraint.target() >= 0.0, "[Plan.anneal_relaxed] Target of {constraint} must be non-negative");
+            if let Some(series) = constraint.pop_series() {
+                anyhow::ensure!(self.series().contains(series), "[Plan.anneal_relaxed] Unknown series '{series}'");
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolve proposal kinds against the map, labeling each block with its unit in a chunk's layer
+    /// (blocks without a unit in that layer form chunks on their own).
+    fn kernel_mix(&self, proposals: &[WeightedProposal]) -> Result<KernelMix> {
+        let kernels = proposals.iter().map(|weighted| {
+            let kernel = match &weighted.proposal {
+                Proposal::Flip => Kernel::Flip,
+                Proposal::Swap => Kernel::Swap,
+                Proposal::Chunk { layer } => {
+                    let ty = GeoType::from_str(layer)
+                        .with_context(|| format!("[Plan.anneal] Unknown chunk layer '{layer}'"))?;
+                    let base = self.map().base()?;
+                    let mut index = HashMap::new();
+                    let labels = base.parents().iter().zip(base.geo_ids())
+                        .map(|(refs, geo_id)| {
+                            let next = index.len() as u32;
+                            *index.entry(refs.get(ty).unwrap_or(geo_id)).or_insert(next)
+                        })
+                        .collect::<Vec<u32>>();
+                    Kernel::Chunk(labels.into())
+                }
+            };
+            Ok((kernel, weighted.weight))
+        }).collect::<Result<_>>()?;
+        KernelMix::new(kernels)
+    }
+
+    /// Run relaxed-constraint annealing as described by a config.
+    /// Returns `true` if a plan satisfying every target bound was found.
+    pub fn anneal_relaxed_with_config(&mut self, config: &RelaxedAnnealConfig) -> Result<bool> {
+        self.anneal_relaxed_with_observer(config, &mut ())
+    }
+
+    /// Run relaxed-constraint annealing as described by a config, passing each step that `steps` wants to it.
+    /// Returns `true` if a plan satisfying every target bound was found.
+    pub fn anneal_relaxed_with_observer(&mut self, config: &RelaxedAnnealConfig, steps: &mut dyn StepObserver) -> Result<bool> {
+        self.check_relaxed(config)?;
+        let counties = if config.constraints.iter().any(|c| c.needs_counties()) {
+            Some(self.county_indices()?)
+        } else { None };
+
+        self.tracked("anneal_relaxed", EditSource::Chain, |plan| Ok(plan.partition.anneal_relaxed(
+            &config.objective, &config.constraints, counties.as_deref(), config.max_iter,
+            config.initial_temp, config.final_temp, config.initial_penalty, config.final_penalty, steps, &config.stop,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Metric, test_maps};
+
+    #[test]
+    fn configs_round_trip_through_json() {
+        let spec = r#"{
+            "objectives": [{"metrics": [{"type": "population_deviation", "pop_series": "pop"}]}],
+            "max_iter": 100, "phase_start_probs": [0.5], "phase_end_probs": [null], "phase_cooling_rates": [0.9]
+        }"#;
+        let config: AnnealConfig = serde_json::from_str(spec).unwrap();
+        assert_eq!((config.init_temp, config.batch_size), (1.0, 1000));
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(serde_json::to_string(&serde_json::from_str::<AnnealConfig>(&json).unwrap()).unwrap(), json);
+
+        let spec = r#"{
+            "objective": {"metrics": [{"type": "compactness_reock"}]},
+            "constraints": [{"type": "county_splits", "target": 3.0}], "max_iter": 10
+        }"#;
+        let config: RelaxedAnnealConfig = serde_json::from_str(spec).unwrap();
+        assert_eq!((config.final_temp, config.final_penalty), (1e-4, 1000.0));
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(serde_json::to_string(&serde_json::from_str::<RelaxedAnnealConfig>(&json).unwrap()).unwrap(), json);
+    }
+
+    #[test]
+    fn malformed_relaxed_runs_are_rejected() {
+        let mut plan = Plan::new(test_maps::grid(4), 2).unwrap();
+        plan.randomize().unwrap();
+        let config = RelaxedAnnealConfig {
+            objective: Objective::new(vec![Metric::population_deviation_smooth("pop".into())], None),
+            constraints: vec![RelaxedConstraint::population_deviation("pop".into(), 0.05)],
+            max_iter: 100,
+            initial_temp: 1.0,
+            final_temp: 1e-4,
+            initial_penalty: 1.0,
+            final_penalty: 1000.0,
+            stop: StoppingRules::default(),
+        };
+        assert!(plan.anneal_relaxed_with_config(&config).is_ok());
+
+        let cases = [
+            RelaxedAnnealConfig { max_iter: 0, ..config.clone() },
+            RelaxedAnnealConfig { final_penalty: 0.0, ..config.clone() },
+            RelaxedAnnealConfig { constraints: vec![RelaxedConstraint::total_deviation("pop".into(), -0.1)], ..config.clone() },
+            RelaxedAnnealConfig { constraints: vec![RelaxedConstraint::population_deviation("vap".into(), 0.05)], ..config.clone() },
+        ];
+        for case in &cases {
+            assert!(plan.anneal_relaxed_with_config(case).unwrap_err().to_string().starts_with("[Plan.anneal_relaxed]"));
+        }
+    }
+}
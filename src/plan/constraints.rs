@@ -0,0 +1,126 @@
+use std::collections::{BTreeSet, HashMap};
+
+use anyhow::{Context, Result, bail};
+
+use crate::{Constraints, PreservedUnits, map::GeoType, partition::MoveConstraints, plan::{Plan, io::unit_ids}};
+
+impl Plan {
+    /// Blocks of each preserved unit, labeled e.g. "county 17031".
+    fn preserved_units(&self, preserve: &[PreservedUnits]) -> Result<Vec<(String, Vec<usize>)>> {
+        let base = self.map.base()?;
+        let mut units = Vec::new();
+        for PreservedUnits { layer, geo_ids } in preserve {
+            let ty = GeoType::from_str(layer)
+                .with_context(|| format!("[Plan.constraints] Unknown layer '{layer}'"))?;
+            let index = geo_ids.iter().enumerate().map(|(i, id)| (id.as_str(), i)).collect::<HashMap<_, _>>();
+            let mut nodes = vec![Vec::new(); geo_ids.len()];
+            for (node, unit) in unit_ids(base, ty).into_iter().enumerate() {
+                if let Some(&i) = unit.and_then(|unit| index.get(unit)) { nodes[i].push(node) }
+            }
+            if let Some(i) = nodes.iter().position(Vec::is_empty) {
+                bail!("[Plan.constraints] No blocks found for {layer} '{}'", geo_ids[i])
+            }
+            units.extend(geo_ids.iter().map(|id| format!("{layer} {id}")).zip(nodes));
+        }
+        Ok(units)
+    }
+
+    /// Districts holding the blocks of a unit, if more than one.
+    fn splitting_districts(&self, nodes: &[usize]) -> Option<BTreeSet<u32>> {
+        let districts = nodes.iter().map(|&node| self.partition.assignment(node)).collect::<BTreeSet<_>>();
+        (districts.len() > 1).then_some(districts)
+    }
+
+    /// Resolve `constraints` against the map's blocks for an optimization run, failing if the plan
+    /// already breaks one that moves cannot repair: a discontiguous district or a split preserved
+    /// unit. Balance bounds may start violated (see [`crate::BalanceBound`]).
+    pub(super) fn resolve_constraints(&self, constraints: &Constraints) -> Result<MoveConstraints> {
+        if constraints.contiguous && let Some(district) = self.partition.first_discontiguous(&(1..=self.num_districts()).collect::<Vec<_>>()) {
+            bail!("[Plan.constraints] District {district} is not contiguous, but the run requires contiguous districts")
+        }
+        let units = self.preserved_units(&constraints.preserve)?;
+        if let Some((name, districts)) = units.iter().find_map(|(name, nodes)| Some((name, self.splitting_districts(nodes)?))) {
+            bail!("[Plan.constraints] Preserved {name} is already split between districts {districts:?}")
+        }
+        let nodes = units.into_iter().map(|(_, nodes)| nodes).collect::<Vec<_>>();
+        Ok(MoveConstraints::new(constraints.balance.clone(), &nodes, self.partition.num_nodes()))
+    }
+
+    /// Describe each way the plan breaks `constraints`, e.g. "District 3 is not contiguous" (empty
+    /// if the plan satisfies them all).
+    pub fn constraint_violations(&self, constraints: &Constraints) -> Result<Vec<String>> {
+        let mut violations = Vec::new();
+        for bound in &constraints.balance {
+            anyhow::ensure!(self.series().contains(&bound.series), "[Plan.constraint_violations] Unknown series '{}'", bound.series);
+            for district in 1..=self.num_districts() {
+                let deviation = self.partition.population_deviation(district, &bound.series);
+                if bound.excess(deviation) > 0.0 {
+                    let limit = |value: Option<f64>| value.map_or("none".to_string(), |value| format!("{:+.2}%", value * 100.0));
+                    violations.push(format!("District {district} deviates {:+.2}% from the ideal {} (bounds {} to {})",
+                        deviation * 100.0, bound.series, limit(bound.min), limit(bound.max)));
+                }
+            }
+        }
+        if constraints.contiguous {
+            for district in 1..=self.num_districts() {
+                let pieces = self.partition.find_components(district).len();
+                if pieces > 1 { violations.push(format!("District {district} is not contiguous ({pieces} pieces)")) }
+            }
+        }
+        for (name, nodes) in self.preserved_units(&constraints.preserve)? {
+            if let Some(districts) = self.splitting_districts(&nodes) {
+                violations.push(format!("Preserved {name} is split between districts {districts:?}"));
+            }
+        }
+        Ok(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AnnealConfig, Metric, Objective, test_maps};
+
+    #[test]
+    fn runs_keep_hard_constraints() {
+        // A row of eight unit squares in two tracts of four.
+        let ids = (0..8).map(|k| format!("17019000{}001{k:03}", 1 + k / 4)).collect::<Vec<_>>();
+        let map = test_maps::row(8, &ids.iter().map(String::as_str).collect::<Vec<_>>());
+        let mut plan = Plan::new(map, 2).unwrap();
+        plan.set_assignments_vec(vec![1, 1, 1, 2, 2, 2, 2, 2]).unwrap();
+
+        let constraints = Constraints::new().max_population_deviation("pop", 0.3).contiguous().preserve("block", &["170190002001007"]);
+        assert_eq!(plan.constraint_violations(&constraints).unwrap().len(), 0);
+        let split = Constraints::new().max_population_deviation("pop", 0.1).preserve("tract", &["17019000100"]);
+        assert_eq!(plan.constraint_violations(&split).unwrap(), vec![
+            "District 1 deviates -25.00% from the ideal pop (bounds -10.00% to +10.00%)",
+            "District 2 deviates +25.00% from the ideal pop (bounds -10.00% to +10.00%)",
+            "Preserved tract 17019000100 is split between districts {1, 2}",
+        ]);
+
+        // Runs refuse to start from a plan that splits a preserved unit...
+        let config = AnnealConfig {
+            objectives: vec![Objective::new(vec![Metric::population_deviation("pop".into())], None)],
+            max_iter: 200,
+            phase_start_probs: vec![0.5],
+            phase_end_probs: vec![Some(0.01)],
+            phase_cooling_rates: vec![0.1],
+            init_temp: 1.0,
+            early_stop_iters: 100,
+            temp_search_batch_size: 10,
+            batch_size: 10,
+            stop: Default::default(),
+            proposals: vec![],
+            constraints: split,
+            profile: false,
+        };
+        assert!(plan.anneal_with_config(&config).unwrap_err().to_string().contains("already split"));
+
+        // ...and never split one once running, while districts outside the population bounds move into them.
+        let constraints = Constraints::new().max_population_deviation("pop", 0.3).preserve("tract", &["17019000200"]);
+        plan.set_assignments_vec(vec![1, 1, 2, 2, 2, 2, 2, 2]).unwrap();
+        plan.anneal_with_config(&AnnealConfig { constraints: constraints.clone(), ..config }).unwrap();
+        assert!(plan.constraint_violations(&constraints).unwrap().is_empty());
+        assert!(plan.get_assignments_vec().unwrap()[4..].iter().all(|&district| district == 2));
+    }
+}
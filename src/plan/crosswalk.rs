@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use anyhow::{Result, ensure};
+
+use crate::{Map, VintageCrosswalk, plan::Plan};
+
+impl Plan {
+    /// Translate the plan onto `map`, a map of the crosswalk's target vintage (e.g. an enacted
+    /// 2010 plan onto 2020 blocks). Each target block joins the district holding most of the
+    /// `pop_series` population that flows into it, falling back on crosswalk weight for blocks with
+    /// no population; blocks the crosswalk does not reach are left unassigned.
+    pub fn crosswalk_vintage(&self, crosswalk: &VintageCrosswalk, map: impl Into<Arc<Map>>, pop_series: &str) -> Result<Plan> {
+        ensure!(self.series().contains(pop_series), "[Plan.crosswalk_vintage] Unknown series '{pop_series}'");
+        let mut plan = Plan::new(map, self.num_districts())?;
+        plan.set_at_large_seats(self.at_large_seats);
+
+        let source_base = self.map.base()?;
+        let entries = crosswalk.resolve(source_base, plan.map.base()?)?;
+        let weights = source_base.get_unit_weights();
+        let assignments = self.partition.assignments();
+
+        // Population and crosswalk weight flowing into each target block from each district.
+        let mut inflows = vec![vec![(0.0, 0.0); self.num_districts() as usize + 1]; plan.partition.num_nodes()];
+        for (source, target, weight) in entries {
+            let inflow = &mut inflows[target][assignments[source] as usize];
+            inflow.0 += weight * weights.get_as_f64(pop_series, source).unwrap();
+            inflow.1 += weight;
+        }
+
+        let districts = inflows.iter()
+            .map(|inflow| (1..inflow.len())
+                .filter(|&district| inflow[district].1 > 0.0)
+                .max_by(|&a, &b| inflow[a].0.total_cmp(&inflow[b].0).then(inflow[a].1.total_cmp(&inflow[b].1)).then(b.cmp(&a)))
+                .map_or(0, |district| district as u32))
+            .collect();
+        plan.set_assignments_vec(districts)?;
+        Ok(plan)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CrosswalkEntry, test_maps};
+
+    fn strip_map(ids: &[&str], pop: Vec<f64>) -> Map {
+        test_maps::row_with(ids.len(), ids, vec![("pop".into(), pop)])
+    }
+
+    #[test]
+    fn plan_and_population_follow_crosswalk() {
+        // Four 2010 blocks become three 2020 blocks: the middle one straddles both districts.
+        let old = strip_map(&["010010000001000", "010010000001001", "010010000001002", "010010000001003"], vec![10.0, 30.0, 20.0, 40.0]);
+        let new = strip_map(&["010010000002000", "010010000002001", "010010000002002"], vec![0.0; 3]);
+        let entry = |source: &str, target: &str, weight| CrosswalkEntry { source: source.into(), target: target.into(), weight };
+        let crosswalk = VintageCrosswalk::new(2010, 2020, vec![
+            entry("010010000001000", "010010000002000", 1.0),
+            entry("010010000001001", "010010000002001", 1.0),
+            entry("010010000001002", "010010000002001", 0.5),
+            entry("010010000001002", "010010000002002", 0.5),
+            entry("010010000001003", "010010000002002", 1.0),
+        ]).unwrap();
+
+        let translated = old.crosswalk_vintage(&crosswalk, &new, &["pop"]).unwrap();
+        let mut plan = Plan::new(old, 2).unwrap();
+        plan.set_assignments_vec(vec![1, 1, 2, 2]).unwrap();
+
+        let plan = plan.crosswalk_vintage(&crosswalk, translated, "pop").unwrap();
+        assert_eq!(plan.get_assignments_vec().unwrap(), vec![1, 1, 2]);
+        assert_eq!((1..=2).map(|part| plan.partition.part_total("pop", part)).collect::<Vec<_>>(), vec![50.0, 50.0]);
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::BTreeSet;
+
+use anyhow::{Result, ensure};
+use serde::{Deserialize, Serialize};
+
+use crate::plan::Plan;
+
+/// A two-party contest stored in the map as a pair of Democratic and Republican vote series.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Contest {
+    pub name: String, // e.g. "20_PRES"
+    pub dem: String,  // Democratic vote series, e.g. "E_20_PRES_Dem"
+    pub rep: String,  // Republican vote series, e.g. "E_20_PRES_Rep"
+}
+
+impl Contest {
+    /// The contest `name` with the pack's `E_<name>_Dem` and `E_<name>_Rep` series.
+    pub fn named(name: &str) -> Self {
+        Self { name: name.into(), dem: format!("E_{name}_Dem"), rep: format!("E_{name}_Rep") }
+    }
+}
+
+/// A statewide vote swing towards Democrats (negative towards Republicans), applied to every
+/// block's two-party vote share before re-aggregating its votes into districts.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "swing", rename_all = "lowercase")]
+pub enum Swing {
+    /// Every block's Democratic share moves by the same amount, e.g. 0.03 for a D+3 year.
+    Uniform(f64),
+    /// Every block's Democratic share moves in proportion to it, so the statewide share moves by
+    /// the given amount and strongholds swing most.
+    Proportional(f64),
+}
+
+impl Swing {
+    /// Democratic share of a block with share `share` after the swing, where `statewide` is the
+    /// statewide Democratic share before it.
+    fn apply(self, share: f64, statewide: f64) -> f64 {
+        let swung = match self {
+            Swing::Uniform(swing) => share + swing,
+            Swing::Proportional(swing) => if statewide > 0.0 { share + swing * share / statewide } else { share },
+        };
+        swung.clamp(0.0, 1.0)
+    }
+}
+
+/// Two-party votes of one district in a (possibly swung) contest.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DistrictResult {
+    pub district: u32,
+    pub dem: f64,
+    pub rep: f64,
+}
+
+impl DistrictResult {
+    /// Democratic share of the two-party vote (0.5 with no votes).
+    pub fn dem_share(&self) -> f64 {
+        if self.dem + self.rep == 0.0 { 0.5 } else { self.dem / (self.dem + self.rep) }
+    }
+}
+
+/// District-level results of a contest under a swing scenario.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ElectionResults {
+    pub contest: Contest,
+    pub swing: Swing,
+    pub dem_share: f64,                  // Statewide Democratic share after the swing
+    pub dem_seats: u32,                  // Districts the Democrat carries
+    pub districts: Vec<DistrictResult>,  // One per district, in order
+}
+
+impl Plan {
+    /// Contests stored in the map: names `<name>` with both `E_<name>_Dem` and `E_<name>_Rep`
+    /// series, sorted by name.
+    pub fn contests(&self) -> Vec<Contest> {
+        let series = self.series();
+        series.iter()
+            .filter_map(|name| name.strip_prefix("E_")?.strip_suffix("_Dem"))
+            .filter(|name| series.contains(&format!("E_{name}_Rep")))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(Contest::named)
+            .collect()
+    }
+
+    /// District-level results of `contest` in a year that swings by `swing` from it.
+    pub fn election_results(&self, contest: &Contest, swing: Swing) -> Result<ElectionResults> {
+        Ok(self.swung_results(contest, &[swing])?.remove(0))
+    }
+
+    /// Democratic seats won in `contest` under each of `swings`, e.g. uniform swings from -0.1 to
+    /// 0.1 to trace a seats-votes curve, or the same scenario across the plans of an ensemble.
+    pub fn seats_under_swings(&self, contest: &Contest, swings: &[Swing]) -> Result<Vec<u32>> {
+        Ok(self.swung_results(contest, swings)?.into_iter().map(|results| results.dem_seats).collect())
+    }
+
+    /// Re-aggregate `contest` into districts once per swing, reading each block's votes once.
+    fn swung_results(&self, contest: &Contest, swings: &[Swing]) -> Result<Vec<ElectionResults>> {
+        ensure!(self.series().contains(&contest.dem), "[Plan.election_results] Unknown series '{}'", contest.dem);
+        ensure!(self.series().contains(&contest.rep), "[Plan.election_results] Unknown series '{}'", contest.rep);
+
+        let weights = self.map.base()?.get_unit_weights();
+        let assignments = self.partition.assignments();
+        let votes = (0..assignments.len())
+            .map(|u| (weights.get_as_f64(&contest.dem, u).unwrap(), weights.get_as_f64(&contest.rep, u).unwrap()))
+            .collect::<Vec<_>>();
+        let (dem, total) = votes.iter().fold((0.0, 0.0), |(dem, total), &(d, r)| (dem + d, total + d + r));
+        let statewide = if total == 0.0 { 0.5 } else { dem / total };
+
+        Ok(swings.iter().map(|&swing| {
+            let mut districts = (1..=self.num_districts())
+                .map(|district| DistrictResult { district, dem: 0.0, rep: 0.0 })
+                .collect::<Vec<_>>();
+            let mut swung_dem = 0.0;
+            for (&(d, r), &part) in votes.iter().zip(&assignments) {
+                let total = d + r;
+                if total == 0.0 { continue }
+                let d = total * swing.apply(d / total, statewide);
+                swung_dem += d;
+                if part == 0 { continue }
+                let result = &mut districts[part as usize - 1];
+                result.dem += d;
+                result.rep += total - d;
+            }
+
+            ElectionResults {
+                contest: contest.clone(),
+                swing,
+                dem_share: if total == 0.0 { 0.5 } else { swung_dem / total },
+                dem_seats: districts.iter().filter(|result| result.dem > result.rep).count() as u32,
+                districts,
+            }
+        }).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uniform_swing_shifts_and_clamps() {
+        assert!((Swing::Uniform(0.03).apply(0.48, 0.5) - 0.51).abs() < 1e-12);
+        assert_eq!(Swing::Uniform(0.1).apply(0.95, 0.5), 1.0);
+        assert_eq!(Swing::Uniform(-0.1).apply(0.05, 0.5), 0.0);
+    }
+
+    #[test]
+    fn proportional_swing_scales_with_share() {
+        // At the statewide share, a block moves by the full swing; at half of it, by half.
+        assert!((Swing::Proportional(0.04).apply(0.4, 0.4) - 0.44).abs() < 1e-12);
+        assert!((Swing::Proportional(0.04).apply(0.2, 0.4) - 0.22).abs() < 1e-12);
+        assert_eq!(Swing::Proportional(0.04).apply(0.0, 0.4), 0.0);
+    }
+
+    #[test]
+    fn contest_uses_pack_series_names() {
+        let contest = Contest::named("20_PRES");
+        assert_eq!(contest.dem, "E_20_PRES_Dem");
+        assert_eq!(contest.rep, "E_20_PRES_Rep");
+    }
+
+    #[test]
+    fn swing_serializes_with_kind() {
+        let json = serde_json::to_string(&Swing::Proportional(0.03)).unwrap();
+        assert_eq!(json, r#"{"kind":"proportional","swing":0.03}"#);
+    }
+}
@@ -0,0 +1,223 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, bail, ensure};
+use polars::{frame::DataFrame, prelude::NamedFrom, series::Series};
+use serde::{Deserialize, Serialize};
+
+use crate::{map::quantile, plan::{Contest, Plan}};
+
+/// The `rank`-th least Democratic district across an ensemble (see [`Plan::ensemble_boxplot`]):
+/// quartiles of its Democratic two-party share over the plans, and the enacted plan's share.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BoxplotRank {
+    pub rank: u32, // 1 for each plan's least Democratic district
+    pub min: f64,
+    pub q1: f64,
+    pub median: f64,
+    pub q3: f64,
+    pub max: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enacted: Option<f64>,
+}
+
+/// Sorted-district boxplot data of an ensemble, the classic "ensemble vs. enacted" chart.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EnsembleBoxplot {
+    pub contest: String,
+    pub num_plans: usize,
+    pub ranks: Vec<BoxplotRank>,
+}
+
+impl EnsembleBoxplot {
+    /// One row per rank: `rank`, `min`, `q1`, `median`, `q3`, `max` and, if an enacted plan was
+    /// given, `enacted`.
+    pub fn to_dataframe(&self) -> Result<DataFrame> {
+        let column = |name: &str, value: fn(&BoxplotRank) -> f64| {
+            Series::new(name.into(), self.ranks.iter().map(value).collect::<Vec<_>>())
+        };
+        let mut columns = vec![
+            Series::new("rank".into(), self.ranks.iter().map(|rank| rank.rank).collect::<Vec<_>>()),
+            column("min", |rank| rank.min),
+            column("q1", |rank| rank.q1),
+            column("median", |rank| rank.median),
+            column("q3", |rank| rank.q3),
+            column("max", |rank| rank.max),
+        ];
+        if self.ranks.iter().all(|rank| rank.enacted.is_some()) {
+            columns.push(column("enacted", |rank| rank.enacted.unwrap()));
+        }
+        Ok(DataFrame::new(columns.into_iter().map(Into::into).collect())?)
+    }
+
+    /// Write the boxplot to a `.json`, `.csv` or `.parquet` file, by its extension.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => std::fs::write(path, serde_json::to_string_pretty(self)?)
+                .with_context(|| format!("[EnsembleBoxplot.write] Failed to write {}", path.display())),
+            _ => crate::io::write_table(&mut self.to_dataframe()?, path),
+        }
+    }
+}
+
+impl Plan {
+    /// How contested each block is across an ensemble of plans of the same map: the entropy of the
+    /// districts it is assigned to, scaled to [0, 1] by ln(num_districts). A block always in the same
+    /// district scores 0; one spread evenly over every district scores 1. District labels must be
+    /// comparable across plans, e.g. successive samples of one chain started from a common plan.
+    pub fn unit_flippability(plans: &[&Plan]) -> Result<Vec<f64>> {
+        let assignments = ensemble_assignments(plans, "[Plan.unit_flippability]")?;
+        Ok(assignment_entropy(&assignments, plans[0].num_districts()))
+    }
+
+    /// Per-block district stability across an ensemble of plans of the same map, one row per block:
+    /// `geo_id`, `entropy` (see [`Plan::unit_flippability`]), the `modal_district` the block is most
+    /// often in and its `modal_share` of plans, `unassigned` (share of plans leaving it unassigned),
+    /// and `district_<d>`, the share of plans placing it in district `d`. District labels must be
+    /// comparable across plans. To map a column, attach it with [`crate::Map::set_unit_column`].
+    pub fn unit_stability(plans: &[&Plan]) -> Result<DataFrame> {
+        let assignments = ensemble_assignments(plans, "[Plan.unit_stability]")?;
+        let num_districts = plans[0].num_districts();
+        let shares = district_shares(&assignments, num_districts);
+        let modal = shares.iter()
+            .map(|shares| (1..shares.len()).fold((0, 0.0), |best, d| if shares[d] > best.1 { (d as u32, shares[d]) } else { best }))
+            .collect::<Vec<_>>();
+
+        let base = plans[0].map().base()?;
+        let mut columns = vec![
+            Series::new("geo_id".into(), base.geo_ids().iter().map(|geo_id| geo_id.id().to_string()).collect::<Vec<_>>()),
+            Series::new("entropy".into(), assignment_entropy(&assignments, num_districts)),
+            Series::new("modal_district".into(), modal.iter().map(|&(district, _)| district).collect::<Vec<_>>()),
+            Series::new("modal_share".into(), modal.iter().map(|&(_, share)| share).collect::<Vec<_>>()),
+            Series::new("unassigned".into(), shares.iter().map(|shares| shares[0]).collect::<Vec<_>>()),
+        ];
+        columns.extend((1..=num_districts as usize).map(|d| {
+            Series::new(format!("district_{d}").into(), shares.iter().map(|shares| shares[d]).collect::<Vec<_>>())
+        }));
+        Ok(DataFrame::new(columns.into_iter().map(Into::into).collect())?)
+    }
+
+    /// Write [`Plan::unit_stability`] to a `.csv` or `.parquet` file, by its extension.
+    pub fn write_unit_stability(plans: &[&Plan], path: &Path) -> Result<()> {
+        crate::io::write_table(&mut Self::unit_stability(plans)?, path)
+    }
+
+    /// Sorted-district boxplot of `contest` across an ensemble: each plan's districts are sorted
+    /// by Democratic two-party share, and the shares at each rank are summarized by their
+    /// quartiles, with the `enacted` plan's share at that rank overlaid if given. District labels
+    /// need not be comparable across plans.
+    pub fn ensemble_boxplot(plans: &[&Plan], contest: &Contest, enacted: Option<&Plan>) -> Result<EnsembleBoxplot> {
+        check_ensemble(plans, "[Plan.ensemble_boxplot]")?;
+        ensure!(plans[0].series().contains(&contest.dem), "[Plan.ensemble_boxplot] Unknown series '{}'", contest.dem);
+        ensure!(plans[0].series().contains(&contest.rep), "[Plan.ensemble_boxplot] Unknown series '{}'", contest.rep);
+        if let Some(enacted) = enacted {
+            ensure!(enacted.num_districts() == plans[0].num_districts(),
+                "[Plan.ensemble_boxplot] The enacted plan must have the same number of districts as the ensemble");
+        }
+
+        let sorted_shares = |plan: &Plan| {
+            let mut shares = plan.partition.dem_shares(&contest.dem, &contest.rep);
+            shares.sort_by(f64::total_cmp);
+            shares
+        };
+        let shares = plans.iter().map(|plan| sorted_shares(plan)).collect::<Vec<_>>();
+        let enacted = enacted.map(sorted_shares);
+
+        let ranks = (0..plans[0].num_districts() as usize).map(|rank| {
+            let mut values = shares.iter().map(|shares| shares[rank]).collect::<Vec<_>>();
+            values.sort_by(f64::total_cmp);
+            BoxplotRank {
+                rank: rank as u32 + 1,
+                min: values[0],
+                q1: quantile(&values, 0.25),
+                median: quantile(&values, 0.5),
+                q3: quantile(&values, 0.75),
+                max: values[values.len() - 1],
+                enacted: enacted.as_ref().map(|shares| shares[rank]),
+            }
+        }).collect();
+
+        Ok(EnsembleBoxplot { contest: contest.name.clone(), num_plans: plans.len(), ranks })
+    }
+}
+
+/// Check that the plans form an ensemble of one map and district count.
+fn check_ensemble(plans: &[&Plan], context: &str) -> Result<()> {
+    let Some(first) = plans.first() else { bail!("{context} No plans given") };
+    ensure!(plans.iter().all(|plan| std::ptr::eq(plan.map(), first.map())),
+        "{context} All plans must share the same map");
+    ensure!(plans.iter().all(|plan| plan.num_districts() == first.num_districts()),
+        "{context} All plans must have the same number of districts");
+    Ok(())
+}
+
+/// Assignments of every plan, checking that the plans form an ensemble of one map and district count.
+fn ensemble_assignments(plans: &[&Plan], context: &str) -> Result<Vec<Vec<u32>>> {
+    check_ensemble(plans, context)?;
+    Ok(plans.iter().map(|plan| plan.partition.assignments()).collect())
+}
+
+/// Share of assignment vectors placing each node in each label (0..=num_districts).
+fn district_shares<A: AsRef<[u32]>>(assignments: &[A], num_districts: u32) -> Vec<Vec<f64>> {
+    let Some(first) = assignments.first() else { return vec![] };
+    let samples = assignments.len() as f64;
+    (0..first.as_ref().len()).map(|node| {
+        let mut shares = vec![0.0; num_districts as usize + 1];
+        for assignment in assignments {
+            shares[assignment.as_ref()[node] as usize] += 1.0 / samples;
+        }
+        shares
+    }).collect()
+}
+
+/// Normalized entropy of each node's labels (0..=num_districts, with 0 for unassigned) across
+/// equal-length assignment vectors.
+fn assignment_entropy<A: AsRef<[u32]>>(assignments: &[A], num_districts: u32) -> Vec<f64> {
+    let Some(first) = assignments.first() else { return vec![] };
+    let samples = assignments.len() as f64;
+    let scale = (num_districts.max(2) as f64).ln();
+    let mut counts = vec![0u32; num_districts as usize + 1];
+
+    (0..first.as_ref().len()).map(|node| {
+        counts.fill(0);
+        for assignment in assignments {
+            counts[assignment.as_ref()[node] as usize] += 1;
+        }
+        let entropy = counts.iter().filter(|&&count| count > 0)
+            .map(|&count| { let p = count as f64 / samples; p * (1.0 / p).ln() })
+            .sum::<f64>();
+        (entropy / scale).clamp(0.0, 1.0)
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entropy_is_zero_for_fixed_and_one_for_even_splits() {
+        let assignments = [vec![1, 1, 2], vec![1, 2, 2], vec![1, 1, 2], vec![1, 2, 2]];
+        let entropy = assignment_entropy(&assignments, 2);
+        assert_eq!(entropy[0], 0.0);
+        assert!((entropy[1] - 1.0).abs() < 1e-12);
+        assert_eq!(entropy[2], 0.0);
+        assert!(assignment_entropy::<Vec<u32>>(&[], 2).is_empty());
+    }
+
+    #[test]
+    fn district_shares_count_each_label() {
+        let assignments = [vec![1, 0], vec![2, 2], vec![1, 2], vec![1, 2]];
+        let shares = district_shares(&assignments, 2);
+        assert_eq!(shares[0], vec![0.0, 0.75, 0.25]);
+        assert_eq!(shares[1], vec![0.25, 0.0, 0.75]);
+    }
+
+    #[test]
+    fn quantiles_interpolate_between_values() {
+        let values = [0.1, 0.2, 0.4, 0.8];
+        assert_eq!(quantile(&values, 0.0), 0.1);
+        assert_eq!(quantile(&values, 1.0), 0.8);
+        assert!((quantile(&values, 0.5) - 0.3).abs() < 1e-12);
+        assert!((quantile(&values, 0.25) - 0.175).abs() < 1e-12);
+        assert_eq!(quantile(&[0.5], 0.75), 0.5);
+    }
+}
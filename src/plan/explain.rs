@@ -0,0 +1,137 @@
+use std::collections::BTreeSet;
+
+use anyhow::{Result, ensure};
+use serde::{Deserialize, Serialize};
+
+use crate::{Contest, Metric, plan::Plan};
+
+/// One district's or county's share of a score (see [`ScoreExplanation`]).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Contribution {
+    pub label: String,       // "District <n>", or a county GEOID
+    pub districts: Vec<u32>, // Districts involved
+    pub value: f64,          // The district's own value, or the number of times the county is split
+    pub contribution: f64,   // Its part of the score; the contributions sum to the score
+}
+
+/// A score broken down into the districts or counties that produce it, e.g. for UI tooltips and
+/// reports. Each explanation documents how its contributions are ordered.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScoreExplanation {
+    pub score: f64,
+    pub contributions: Vec<Contribution>,
+}
+
+impl Plan {
+    /// Break [`Plan::compute_metric_score`] down by district: each district's value of `metric`
+    /// and its part of the mean. Contributions are ordered worst first (lowest value), since
+    /// higher scores are better.
+    pub fn explain_metric(&self, metric: &Metric) -> ScoreExplanation {
+        let values = metric.compute(&self.partition);
+        let count = values.len().max(1) as f64;
+        let mut contributions = values.iter().enumerate()
+            .map(|(i, &value)| Contribution {
+                label: format!("District {}", i + 1),
+                districts: vec![i as u32 + 1],
+                value,
+                contribution: value / count,
+            })
+            .collect::<Vec<_>>();
+        contributions.sort_by(|a, b| a.value.total_cmp(&b.value).then(a.districts.cmp(&b.districts)));
+
+        ScoreExplanation { score: contributions.iter().map(|c| c.contribution).sum(), contributions }
+    }
+
+    /// Break the number of county splits (the districts a county touches beyond the first, summed
+    /// over counties) down by county. Only split counties are listed, most splits first; each
+    /// contribution is the county's number of splits.
+    pub fn explain_county_splits(&self) -> Result<ScoreExplanation> {
+        let (keys, county_of) = self.county_keys()?;
+        let mut districts = vec![BTreeSet::new(); keys.len()];
+        for (&part, &county) in self.partition.assignments().iter().zip(&county_of) {
+            if part != 0 { districts[county as usize].insert(part); }
+        }
+
+        let mut contributions = keys.into_iter().zip(districts)
+            .filter(|(_, parts)| parts.len() > 1)
+            .map(|(county, parts)| {
+                let splits = (parts.len() - 1) as f64;
+                Contribution { label: county, districts: parts.into_iter().collect(), value: splits, contribution: splits }
+            })
+            .collect::<Vec<_>>();
+        contributions.sort_by(|a, b| b.value.total_cmp(&a.value).then_with(|| a.label.cmp(&b.label)));
+
+        Ok(ScoreExplanation { score: contributions.iter().map(|c| c.contribution).sum(), contributions })
+    }
+
+    /// Break the efficiency gap of `contest` down by district: each district's value is its
+    /// Democratic two-party share, and its contribution is its wasted Republican votes minus its
+    /// wasted Democratic votes, as a share of all votes (positive favors Democrats). Contributions
+    /// are ordered by magnitude, largest first.
+    pub fn explain_efficiency_gap(&self, contest: &Contest) -> Result<ScoreExplanation> {
+        ensure!(self.series().contains(&contest.dem), "[Plan.explain_efficiency_gap] Unknown series '{}'", contest.dem);
+        ensure!(self.series().contains(&contest.rep), "[Plan.explain_efficiency_gap] Unknown series '{}'", contest.rep);
+
+        let votes = (1..=self.num_districts())
+            .map(|part| (part, self.partition.part_total(&contest.dem, part), self.partition.part_total(&contest.rep, part)))
+            .collect::<Vec<_>>();
+        let total = votes.iter().map(|&(_, dem, rep)| dem + rep).sum::<f64>();
+
+        let mut contributions = votes.into_iter()
+            .map(|(part, dem, rep)| {
+                let half = (dem + rep) / 2.0;
+                let (wasted_dem, wasted_rep) = if dem > rep { (dem - half, rep) } else { (dem, rep - half) };
+                Contribution {
+                    label: format!("District {part}"),
+                    districts: vec![part],
+                    value: if dem + rep == 0.0 { 0.0 } else { dem / (dem + rep) },
+                    contribution: if total == 0.0 { 0.0 } else { (wasted_rep - wasted_dem) / total },
+                }
+            })
+            .collect::<Vec<_>>();
+        contributions.sort_by(|a, b| b.contribution.abs().total_cmp(&a.contribution.abs()).then(a.districts.cmp(&b.districts)));
+
+        Ok(ScoreExplanation { score: contributions.iter().map(|c| c.contribution).sum(), contributions })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_maps;
+
+    #[test]
+    fn explanations_sum_to_scores() {
+        // District 1 holds the first three columns of a 4x4 grid, district 2 the last.
+        let mut plan = Plan::new(test_maps::grid(4), 2).unwrap();
+        plan.set_assignments_vec((0..16).map(|k| if k < 12 { 1 } else { 2 }).collect()).unwrap();
+
+        let metric = Metric::population_deviation_sharp("pop".into());
+        let explanation = plan.explain_metric(&metric);
+        assert!((explanation.score - plan.compute_metric_score(&metric)).abs() < 1e-12);
+        assert_eq!(explanation.contributions.iter().map(|c| c.districts[0]).collect::<Vec<_>>(), vec![1, 2]);
+
+        // Blocks without a county are counties of their own, so nothing is split.
+        let splits = plan.explain_county_splits().unwrap();
+        assert_eq!(splits.score, 0.0);
+        assert!(splits.contributions.is_empty());
+    }
+
+    #[test]
+    fn efficiency_gap_attributed_to_districts() {
+        // Democrats pack district 1 (15-1 per column) and narrowly lose district 2 (7-9).
+        let series = vec![
+            ("E_20_X_Dem".into(), vec![15.0, 15.0, 7.0, 7.0]),
+            ("E_20_X_Rep".into(), vec![1.0, 1.0, 9.0, 9.0]),
+        ];
+        let mut plan = Plan::new(test_maps::row_with(4, &[], series), 2).unwrap();
+        plan.set_assignments_vec(vec![1, 1, 2, 2]).unwrap();
+
+        let contest = Contest::named("20_X");
+        let explanation = plan.explain_efficiency_gap(&contest).unwrap();
+        assert!((explanation.score - plan.partition.efficiency_gap(&contest.dem, &contest.rep)).abs() < 1e-12);
+        // District 1 wastes 14 Democratic votes and 2 Republican ones, out of 64 in all.
+        assert_eq!(explanation.contributions[0].districts, vec![1]);
+        assert!((explanation.contributions[0].contribution + 12.0 / 64.0).abs() < 1e-12);
+    }
+}
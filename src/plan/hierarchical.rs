@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use crate::{
+    map::{GeoType, Map},
+    plan::{AnnealConfig, CountyCluster, EditSource, Plan, clusters::part_clusters},
+};
+
+impl Plan {
+    /// Divide-and-conquer annealing for large states. The state is split into super-regions of
+    /// whole counties with fixed district quotas (e.g. a metro area and the rest of the state, or
+    /// clusters from [`Plan::county_clusters`]); each region's districts are drawn and annealed
+    /// with `config` on a sub-map of their own, all regions in parallel, and the regional plans are
+    /// stitched together, numbering each region's districts in turn.
+    ///
+    /// If `seam` is given, the stitched plan is then annealed with it while the core is frozen:
+    /// only districts bordering another region's districts may trade units, with each other and
+    /// across the seams. Any county clustering constraint is cleared. Requires map geometry.
+    pub fn anneal_by_region(&mut self, regions: &[CountyCluster], config: &AnnealConfig, seam: Option<&AnnealConfig>) -> Result<()> {
+        let region_of = self.cluster_of_units(regions, "[Plan.anneal_by_region]")?;
+        let county_region = regions.iter().enumerate()
+            .flat_map(|(r, region)| region.counties.iter().map(move |county| (county.as_str(), r)))
+            .collect::<HashMap<_, _>>();
+        let maps = (0..regions.len())
+            .map(|r| self.map().subset(|geo_id, parents| county_region[parents.get(GeoType::County).unwrap_or(geo_id).id()] == r))
+            .collect::<Result<Vec<_>>>()?;
+
+        let anneal_region = |(map, region): (Map, &CountyCluster)| -> Result<Vec<u32>> {
+            let mut plan = Plan::new(map, region.districts)?;
+            plan.randomize()?;
+            // A region with a single district is drawn whole; there is nothing to anneal.
+            if region.districts > 1 { plan.anneal_with_config(config)? }
+            plan.get_assignments_vec()
+        };
+
+        // Threads are unavailable on wasm32, so regions run one after another there.
+        #[cfg(not(target_arch = "wasm32"))]
+        let assignments = std::thread::scope(|scope| {
+            let anneal_region = &anneal_region;
+            let handles = maps.into_iter().zip(regions)
+                .map(|job| scope.spawn(move || anneal_region(job)))
+                .collect::<Vec<_>>();
+            handles.into_iter().map(|handle| handle.join().expect("regional annealing panicked")).collect::<Result<Vec<_>>>()
+        })?;
+        #[cfg(target_arch = "wasm32")]
+        let assignments = maps.into_iter().zip(regions).map(anneal_region).collect::<Result<Vec<_>>>()?;
+
+        // Sub-map units keep the base layer's order, so each region's assignments are read in turn.
+        let offsets = regions.iter()
+            .scan(0, |offset, region| { *offset += region.districts; Some(*offset - region.districts) })
+            .collect::<Vec<_>>();
+        let mut next = vec![0; regions.len()];
+        let stitched = region_of.iter().map(|&r| {
+            let r = r as usize;
+            let local = assignments[r][next[r]];
+            next[r] += 1;
+            if local == 0 { 0 } else { offsets[r] + local }
+        }).collect();
+
+        self.partition.clear_clusters();
+        self.tracked("anneal_by_region", EditSource::Chain, |plan| {
+            plan.partition.set_assignments(stitched);
+            Ok(())
+        })?;
+
+        let Some(seam) = seam else { return Ok(()) };
+
+        let part_cluster = self.seam_clusters(regions);
+        let cluster_of = self.partition.assignments().iter().map(|&part| part_cluster[part as usize]).collect();
+        self.partition.set_clusters(cluster_of, part_cluster);
+        let result = self.anneal_with_config(seam);
+        self.partition.clear_clusters();
+        result
+    }
+
+    /// Cluster of each part for the seam pass of [`Plan::anneal_by_region`]: districts bordering
+    /// another region's districts share cluster 0, and every other district is frozen in a
+    /// cluster of its own. Bordering the unassigned (at-large) area does not make a seam.
+    fn seam_clusters(&self, regions: &[CountyCluster]) -> Vec<u32> {
+        let part_region = part_clusters(regions);
+        self.partition.part_adjacency().iter().enumerate()
+            .map(|(part, neighbors)| match part {
+                0 => u32::MAX,
+                _ if neighbors.iter().any(|&other| other != 0 && part_region[other as usize] != part_region[part]) => 0,
+                _ => part as u32,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Metric, Objective, test_maps};
+
+    /// Clusters of the given block indices, with their district counts.
+    fn clusters_of_blocks(plan: &Plan, clusters: &[(&[usize], u32)]) -> Vec<CountyCluster> {
+        let geo_ids = plan.map().base().unwrap().geo_ids();
+        clusters.iter().map(|&(blocks, districts)| CountyCluster {
+            counties: blocks.iter().map(|&block| geo_ids[block].id().to_string()).collect(),
+            districts,
+            total: blocks.len() as f64,
+        }).collect()
+    }
+
+    fn config() -> AnnealConfig {
+        AnnealConfig {
+            objectives: vec![Objective::new(vec![Metric::population_deviation_smooth("pop".into())], None)],
+            max_iter: 500,
+            phase_start_probs: vec![0.5],
+            phase_end_probs: vec![Some(0.01)],
+            phase_cooling_rates: vec![0.9],
+            init_temp: 1.0,
+            early_stop_iters: 200,
+            temp_search_batch_size: 10,
+            batch_size: 50,
+            stop: Default::default(),
+            proposals: vec![],
+            constraints: Default::default(),
+            profile: false,
+        }
+    }
+
+    #[test]
+    fn regions_are_numbered_in_turn() {
+        // The right half of a 4x4 grid is listed first, so its two districts come first.
+        let mut plan = Plan::new(test_maps::grid(4), 3).unwrap();
+        let regions = clusters_of_blocks(&plan, &[(&[8, 9, 10, 11, 12, 13, 14, 15], 2), (&[0, 1, 2, 3, 4, 5, 6, 7], 1)]);
+        plan.anneal_by_region(&regions, &config(), None).unwrap();
+
+        let assignments = plan.get_assignments_vec().unwrap();
+        assert!(assignments[..8].iter().all(|&part| part == 3));
+        assert!(assignments[8..].iter().all(|&part| part == 1 || part == 2));
+        assert!(assignments[8..].contains(&1) && assignments[8..].contains(&2));
+        assert!(!plan.partition.has_clusters());
+    }
+
+    #[test]
+    fn only_districts_on_seams_are_annealed() {
+        // A row of six blocks: region A holds districts 1 | 2 2, region B districts 3 | 4 and an unassigned block.
+        let mut plan = Plan::new(test_maps::row(6, &[]), 4).unwrap();
+        let regions = clusters_of_blocks(&plan, &[(&[0, 1, 2], 2), (&[3, 4, 5], 2)]);
+        plan.partition.update_assignments(&[1, 2, 2, 3, 4, 0]);
+        assert_eq!(plan.seam_clusters(&regions), [u32::MAX, 1, 0, 0, 4]);
+
+        // Across a full run, blocks only move between districts on the seam after stitching.
+        let mut plan = Plan::new(test_maps::grid(4), 4).unwrap();
+        let regions = clusters_of_blocks(&plan, &[(&[0, 1, 2, 3, 4, 5, 6, 7], 2), (&[8, 9, 10, 11, 12, 13, 14, 15], 2)]);
+        plan.enable_history(1000);
+        plan.anneal_by_region(&regions, &config(), Some(&config())).unwrap();
+        let annealed = plan.get_assignments_vec().unwrap();
+        if plan.undo().unwrap() && plan.can_undo() {
+            let seams = plan.seam_clusters(&regions);
+            let stitched = plan.get_assignments_vec().unwrap();
+            for (before, after) in stitched.into_iter().zip(annealed) {
+                if before != after { assert!(seams[before as usize] == 0 && seams[after as usize] == 0) }
+            }
+        }
+    }
+
+    #[test]
+    fn regions_must_cover_every_county_once() {
+        let mut plan = Plan::new(test_maps::row(4, &[]), 2).unwrap();
+        let mut check = |clusters: &[(&[usize], u32)]| {
+            let regions = clusters_of_blocks(&plan, clusters);
+            plan.anneal_by_region(&regions, &config(), None).unwrap_err().to_string()
+        };
+        assert_eq!(check(&[(&[0, 1], 1), (&[2, 3], 2)]), "[Plan.anneal_by_region] Clusters hold 3 districts, but the plan has 2");
+        assert_eq!(check(&[(&[0, 1, 2], 1), (&[2, 3], 1)]), "[Plan.anneal_by_region] County '000000000000002' is in two clusters");
+        assert_eq!(check(&[(&[0, 1], 1), (&[2], 1)]), "[Plan.anneal_by_region] County '000000000000003' is in no cluster");
+    }
+}
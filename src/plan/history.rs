@@ -0,0 +1,162 @@
+use std::collections::VecDeque;
+
+use anyhow::Result;
+
+use crate::plan::{EditSource, Plan};
+
+/// Blocks moved by one operation, with the district each was in before and after.
+#[derive(Clone, Debug, Default)]
+pub(super) struct Delta {
+    pub(super) nodes: Vec<u32>,
+    pub(super) before: Vec<u32>,
+    pub(super) after: Vec<u32>,
+}
+
+impl Delta {
+    /// Compute the delta between two full assignment vectors.
+    pub(super) fn between(before: &[u32], after: &[u32]) -> Self {
+        let mut delta = Self::default();
+        for (node, (&old, &new)) in before.iter().zip(after).enumerate() {
+            if old != new {
+                delta.nodes.push(node as u32);
+                delta.before.push(old);
+                delta.after.push(new);
+            }
+        }
+        delta
+    }
+
+    #[inline] pub(super) fn len(&self) -> usize { self.nodes.len() }
+
+    #[inline] pub(super) fn is_empty(&self) -> bool { self.nodes.is_empty() }
+}
+
+/// Undo/redo stacks of plan edits, bounded by the total number of block moves stored.
+#[derive(Clone, Debug)]
+pub(super) struct History {
+    undo: VecDeque<Delta>,
+    redo: Vec<Delta>,
+    capacity: usize, // maximum number of block moves across both stacks
+    size: usize,     // current number of block moves across both stacks
+}
+
+impl History {
+    pub(super) fn new(capacity: usize) -> Self {
+        Self { undo: VecDeque::new(), redo: Vec::new(), capacity, size: 0 }
+    }
+
+    /// Record a new edit, clearing the redo stack and evicting the oldest edits if over capacity.
+    pub(super) fn push(&mut self, delta: Delta) {
+        self.size -= self.redo.drain(..).map(|d| d.len()).sum::<usize>();
+        self.size += delta.len();
+        self.undo.push_back(delta);
+        while self.size > self.capacity && let Some(oldest) = self.undo.pop_front() {
+            self.size -= oldest.len();
+        }
+    }
+
+    #[inline] fn pop_undo(&mut self) -> Option<Delta> { self.undo.pop_back() }
+
+    #[inline] fn pop_redo(&mut self) -> Option<Delta> { self.redo.pop() }
+}
+
+/// Default bound on the number of block moves kept in the undo/redo history.
+pub const DEFAULT_HISTORY_CAPACITY: usize = 1_000_000;
+
+impl Plan {
+    /// Start keeping an undo/redo history of edits, storing at most `capacity` block moves.
+    /// Oldest edits are forgotten first once the bound is reached.
+    pub fn enable_history(&mut self, capacity: usize) {
+        self.history = Some(History::new(capacity));
+    }
+
+    /// Stop keeping history and discard it.
+    pub fn disable_history(&mut self) { self.history = None }
+
+    /// Check if there is an edit to undo.
+    pub fn can_undo(&self) -> bool { self.history.as_ref().is_some_and(|h| !h.undo.is_empty()) }
+
+    /// Check if there is an undone edit to redo.
+    pub fn can_redo(&self) -> bool { self.history.as_ref().is_some_and(|h| !h.redo.is_empty()) }
+
+    /// Revert the most recent edit. Returns `false` if there was nothing to undo.
+    /// Only block assignments are restored; anchors and whole-county mode are left as they are.
+    pub fn undo(&mut self) -> Result<bool> {
+        let Some(delta) = self.history.as_mut().and_then(History::pop_undo) else { return Ok(false) };
+        self.apply_moves(&delta.nodes, &delta.before);
+        // The delta is kept for redo even if auditing fails, since the blocks have already moved.
+        let audited = self.record_audit("undo", EditSource::Manual, &delta.nodes, &delta.before);
+        if let Some(history) = self.history.as_mut() { history.redo.push(delta) }
+        audited.map(|_| true)
+    }
+
+    /// Reapply the most recently undone edit. Returns `false` if there was nothing to redo.
+    pub fn redo(&mut self) -> Result<bool> {
+        let Some(delta) = self.history.as_mut().and_then(History::pop_redo) else { return Ok(false) };
+        self.apply_moves(&delta.nodes, &delta.after);
+        let audited = self.record_audit("redo", EditSource::Manual, &delta.nodes, &delta.after);
+        if let Some(history) = self.history.as_mut() { history.undo.push_back(delta) }
+        audited.map(|_| true)
+    }
+
+    /// Move each block in `nodes` to the matching district in `parts`.
+    fn apply_moves(&mut self, nodes: &[u32], parts: &[u32]) {
+        for (&node, &part) in nodes.iter().zip(parts) {
+            self.partition.move_node(node as usize, part, false);
+        }
+    }
+
+    /// Run an operation that may change assignments, recording the blocks it moved
+    /// in the audit log and undo history (if enabled).
+    pub(super) fn tracked<T>(&mut self, operation: &str, source: EditSource, op: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        if self.audit.is_none() && self.history.is_none() { return op(self) }
+
+        let before = self.partition.assignments();
+        let result = op(self)?;
+        let delta = Delta::between(&before, &self.partition.assignments());
+        if delta.is_empty() { return Ok(result) }
+
+        let audited = self.record_audit(operation, source, &delta.nodes, &delta.after);
+        if let Some(history) = self.history.as_mut() { history.push(delta) }
+        audited.map(|_| result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delta(nodes: &[u32]) -> Delta {
+        Delta { nodes: nodes.to_vec(), before: vec![0; nodes.len()], after: vec![1; nodes.len()] }
+    }
+
+    #[test]
+    fn between_lists_changed_nodes() {
+        let delta = Delta::between(&[0, 1, 2, 2], &[0, 2, 2, 1]);
+        assert_eq!(delta.nodes, vec![1, 3]);
+        assert_eq!(delta.before, vec![1, 2]);
+        assert_eq!(delta.after, vec![2, 1]);
+    }
+
+    #[test]
+    fn push_evicts_oldest_over_capacity() {
+        let mut history = History::new(4);
+        history.push(delta(&[0, 1]));
+        history.push(delta(&[2]));
+        history.push(delta(&[3, 4]));
+        assert_eq!(history.undo.len(), 2);
+        assert_eq!(history.size, 3);
+        assert_eq!(history.undo[0].nodes, vec![2]);
+    }
+
+    #[test]
+    fn push_clears_redo() {
+        let mut history = History::new(10);
+        history.push(delta(&[0, 1]));
+        let undone = history.pop_undo().unwrap();
+        history.redo.push(undone);
+        history.push(delta(&[2]));
+        assert!(history.redo.is_empty());
+        assert_eq!(history.size, 1);
+    }
+}
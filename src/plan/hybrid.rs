@@ -0,0 +1,91 @@
+use anyhow::{Result, ensure};
+
+use crate::{map::GeoId, plan::{EditSource, Plan}};
+
+impl Plan {
+    /// Get the number of seats elected at large, besides the plan's districts.
+    #[inline] pub fn at_large_seats(&self) -> u32 { self.at_large_seats }
+
+    /// Set the number of seats elected at large, e.g. 2 for a school board of 5 districts and 2
+    /// at-large members. District metrics are unaffected; see [`Plan::set_at_large_area`].
+    pub fn set_at_large_seats(&mut self, seats: u32) { self.at_large_seats = seats }
+
+    /// Get the total number of seats: districts plus at-large seats.
+    #[inline] pub fn total_seats(&self) -> u32 { self.num_districts() + self.at_large_seats }
+
+    /// Hold the blocks of the given units (e.g. the part of the jurisdiction that only votes
+    /// at large) unassigned and whole, replacing any previous at-large area. Optimization never
+    /// moves blocks into or out of it, and population targets are computed over the districted
+    /// remainder, so district metrics only cover districted seats.
+    pub fn set_at_large_area(&mut self, layer: &str, geo_ids: &[&str]) -> Result<()> {
+        let nodes = self.units_nodes(layer, geo_ids)?;
+        ensure!(!nodes.is_empty(), "[Plan.set_at_large_area] No blocks found for the given {layer} units");
+        ensure!(nodes.len() < self.partition.num_nodes(), "[Plan.set_at_large_area] The at-large area cannot cover every block");
+        if let Some(&node) = nodes.iter().find(|&&node| self.partition.anchor(node).is_some_and(|part| part != 0)) {
+            anyhow::bail!("[Plan.set_at_large_area] Block {} is anchored to a district", self.map.base()?.geo_ids()[node].id());
+        }
+
+        self.tracked("set_at_large_area", EditSource::Manual, |plan| {
+            plan.partition.set_at_large(&nodes);
+            Ok(())
+        })
+    }
+
+    /// Release the at-large area: its blocks stay unassigned until the plan is redrawn or edited,
+    /// and optimization refuses to run until they are assigned.
+    pub fn clear_at_large_area(&mut self) -> Result<()> {
+        self.tracked("clear_at_large_area", EditSource::Manual, |plan| {
+            plan.partition.clear_at_large();
+            Ok(())
+        })
+    }
+
+    /// Get the blocks of the at-large area (empty if none is set).
+    pub fn at_large_area(&self) -> Result<Vec<GeoId>> {
+        let geo_ids = self.map.base()?.geo_ids();
+        Ok(self.partition.at_large_nodes().into_iter().map(|node| geo_ids[node].clone()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Metric, Objective, RelaxedConstraint, test_maps};
+
+    #[test]
+    fn at_large_area_stays_whole() {
+        let mut plan = Plan::new(test_maps::grid(4), 2).unwrap();
+        plan.set_at_large_seats(1);
+        assert_eq!(plan.total_seats(), 3);
+
+        // Hold the first column of the grid (blocks 0-3) at large.
+        let geo_ids = plan.map.base().unwrap().geo_ids()[..4].iter().map(|geo_id| geo_id.id().to_string()).collect::<Vec<_>>();
+        plan.set_at_large_area("block", &geo_ids.iter().map(String::as_str).collect::<Vec<_>>()).unwrap();
+        assert_eq!(plan.at_large_area().unwrap().len(), 4);
+
+        plan.randomize().unwrap();
+        let objective = Objective::new(vec![Metric::population_deviation_smooth("pop".into())], None);
+        let constraints = [RelaxedConstraint::population_deviation("pop".into(), 0.0)];
+        assert!(plan.anneal_relaxed(&objective, &constraints, 5000, 1.0, 1e-4, 1.0, 1000.0).unwrap());
+        let assignments = plan.get_assignments_vec().unwrap();
+        assert!(assignments[..4].iter().all(|&part| part == 0));
+        assert!(assignments[4..].iter().all(|&part| part != 0));
+
+        // Deviations are measured against the 12 districted blocks, so both districts hit the ideal of 6.
+        assert_eq!(plan.partition.region_total("pop"), 12.0);
+        assert_eq!((1..=2).map(|part| plan.partition.part_total("pop", part)).collect::<Vec<_>>(), vec![6.0, 6.0]);
+
+        plan.clear_at_large_area().unwrap();
+        assert!(plan.at_large_area().unwrap().is_empty());
+
+        // The released blocks are unassigned, so optimizing refuses to run until they are assigned.
+        let objectives = [objective];
+        let anneal = |plan: &mut Plan| plan.anneal(&objectives, 100, 1.0, &[0.5], &[Some(0.1)], &[0.01], 100, 10, 10);
+        let err = anneal(&mut plan).unwrap_err();
+        assert_eq!(err.to_string(), "[Plan.anneal] Unassigned blocks outside the at-large area; assign every block first");
+        assert!(plan.tabu_balance("pop", 10, 2, 1.0, 4).is_err());
+        assert!(plan.anneal_balance("pop", 10, 1.0, 0.01, 1.0).is_err());
+        plan.randomize().unwrap();
+        anneal(&mut plan).unwrap();
+    }
+}
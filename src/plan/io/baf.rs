@@ -0,0 +1,187 @@
+use std::{collections::{BTreeMap, HashMap, HashSet}, path::Path};
+
+use anyhow::{Context, Result, anyhow, bail, ensure};
+
+use crate::{map::{GeoType, MapLayer}, plan::Plan};
+
+use super::StampStyle;
+
+/// District value marking a unit as unassigned in Census block assignment files.
+const UNASSIGNED: &str = "ZZ";
+
+/// Parse the rows of a block assignment file: `GEOID,District` pairs, optionally under a header
+/// line, separated by commas, pipes (as in Census BAFs) or tabs. Unassigned units (`ZZ` or an
+/// empty district) get district 0.
+fn parse_baf(text: &str) -> Result<Vec<(String, u32)>> {
+    let mut lines = text.lines().enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#')) // Skip export stamps
+        .peekable();
+    let delimiter = match lines.peek() {
+        Some((_, line)) if line.contains('|') => '|',
+        Some((_, line)) if line.contains('\t') => '\t',
+        _ => ',',
+    };
+    let field = |field: &str| field.trim().trim_matches('"').to_string();
+
+    let mut rows = Vec::new();
+    for (n, (i, line)) in lines.enumerate() {
+        let mut fields = line.split(delimiter).map(field);
+        let (Some(geo_id), Some(district)) = (fields.next(), fields.next()) else {
+            bail!("[Plan.load_baf] Line {}: expected GEOID{delimiter}District", i + 1)
+        };
+        if !geo_id.bytes().all(|b| b.is_ascii_digit()) {
+            if n == 0 { continue } // Header
+            bail!("[Plan.load_baf] Line {}: invalid GEOID '{geo_id}'", i + 1)
+        }
+        let district = match district.as_str() {
+            "" => 0,
+            district if district.eq_ignore_ascii_case(UNASSIGNED) => 0,
+            district => district.parse()
+                .map_err(|_| anyhow!("[Plan.load_baf] Line {}: invalid district '{district}'", i + 1))?,
+        };
+        rows.push((geo_id, district));
+    }
+    Ok(rows)
+}
+
+/// GEOID of the unit of layer `ty` holding each block (the block itself for blocks). Counties,
+/// tracts and block groups are prefixes of block GEOIDs, so they are found even for blocks without
+/// a parent reference; VTDs are not.
+pub(in crate::plan) fn unit_ids(base: &MapLayer, ty: GeoType) -> Vec<Option<&str>> {
+    base.geo_ids().iter().zip(base.parents())
+        .map(|(block, refs)| match (ty, refs.get(ty)) {
+            (GeoType::Block, _) => Some(block.id()),
+            (_, Some(unit)) => Some(unit.id()),
+            (GeoType::VTD, None) => None,
+            (_, None) => Some(&block.id()[..ty.id_len()]),
+        })
+        .collect()
+}
+
+impl Plan {
+    /// Load a plan from a block assignment file (BAF), the `GEOID20,District` CSV that the Census
+    /// Bureau, Dave's Redistricting, Districtr and state redistricting portals exchange. Pipe- or
+    /// tab-separated files and files without a header also work. Rows marked `ZZ` and blocks the
+    /// file does not list are left unassigned.
+    ///
+    /// Files may assign units of a coarser layer than blocks (e.g. VTDs or tracts), detected from
+    /// the GEOIDs; each block then takes the district of the unit holding it.
+    pub fn read_baf(&mut self, path: &Path) -> Result<()> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("[Plan.read_baf] Failed to read {}", path.display()))?;
+        self.load_baf(&text)?;
+        self.metadata_mut().source = Some(path.display().to_string());
+        Ok(())
+    }
+
+    /// Load a plan from the text of a block assignment file (see [`Plan::read_baf`]).
+    pub fn load_baf(&mut self, text: &str) -> Result<()> {
+        let rows = parse_baf(text)?;
+        let len = rows.first().map(|(geo_id, _)| geo_id.len())
+            .ok_or_else(|| anyhow!("[Plan.load_baf] The file has no assignments"))?;
+        if let Some((geo_id, _)) = rows.iter().find(|(geo_id, _)| geo_id.len() != len) {
+            bail!("[Plan.load_baf] GEOID '{geo_id}' has {} digits, but the file starts with {len}-digit GEOIDs", geo_id.len())
+        }
+
+        let mut districts = HashMap::with_capacity(rows.len());
+        for (geo_id, district) in &rows {
+            if let Some(previous) = districts.insert(geo_id.as_str(), *district) && previous != *district {
+                bail!("[Plan.load_baf] GEOID '{geo_id}' is assigned to both district {previous} and {district}")
+            }
+        }
+
+        // Tracts and VTDs share a GEOID length, so pick the layer whose units the file names.
+        let base = self.map.base()?;
+        let (ty, units) = GeoType::ALL.into_iter().rev()
+            .filter(|ty| ty.id_len() == len)
+            .map(|ty| (ty, unit_ids(base, ty)))
+            .max_by_key(|(_, units)| units.iter().flatten().filter(|id| districts.contains_key(*id)).collect::<HashSet<_>>().len())
+            .ok_or_else(|| anyhow!("[Plan.load_baf] No layer has {len}-digit GEOIDs"))?;
+
+        let known = units.iter().flatten().copied().collect::<HashSet<_>>();
+        if let Some((geo_id, _)) = rows.iter().find(|(geo_id, _)| !known.contains(geo_id.as_str())) {
+            bail!("[Plan.load_baf] GEOID '{geo_id}' not found in the map's {} layer", ty.to_str())
+        }
+
+        let assignments = units.iter()
+            .map(|unit| unit.and_then(|id| districts.get(id)).copied().unwrap_or(0))
+            .collect::<Vec<_>>();
+        self.set_assignment_bulk(&assignments, false).map(|_| ())
+    }
+
+    /// Write the plan as a block assignment file (see [`Plan::read_baf`]) at `layer` (e.g.
+    /// "block" or "vtd"). Unassigned units are omitted.
+    ///
+    /// A unit of a coarser layer than blocks whose blocks lie in several districts is assigned to
+    /// the district holding most of its `pop_series` (or most of its blocks, without a series).
+    pub fn write_baf(&self, path: &Path, layer: &str, pop_series: Option<&str>) -> Result<()> {
+        std::fs::write(path, self.to_baf(layer, pop_series)?)
+            .with_context(|| format!("[Plan.write_baf] Failed to write {}", path.display()))
+    }
+
+    /// Get the plan as the text of a block assignment file (see [`Plan::write_baf`]).
+    pub fn to_baf(&self, layer: &str, pop_series: Option<&str>) -> Result<String> {
+        let ty = GeoType::from_str(layer)
+            .ok_or_else(|| anyhow!("[Plan.to_baf] Unknown layer '{layer}'"))?;
+        ensure!(ty != GeoType::State, "[Plan.to_baf] Cannot assign whole states to districts");
+        if let Some(series) = pop_series {
+            ensure!(self.series().contains(series), "[Plan.to_baf] Unknown series '{series}'");
+        }
+
+        let base = self.map.base()?;
+        let weights = base.get_unit_weights();
+        let assignments = self.partition.assignments();
+
+        // Weight of each unit in each district.
+        let mut shares = BTreeMap::<&str, BTreeMap<u32, f64>>::new();
+        for (block, unit) in unit_ids(base, ty).into_iter().enumerate() {
+            let Some(unit) = unit else { continue };
+            if assignments[block] == 0 { continue }
+            let weight = pop_series.map_or(1.0, |series| weights.get_as_f64(series, block).unwrap());
+            *shares.entry(unit).or_default().entry(assignments[block]).or_default() += weight;
+        }
+
+        // Census BAFs name the GEOID column after the vintage, e.g. GEOID20.
+        let vintage = self.map.build_record()
+            .and_then(|build| build.parameters.get("vintage")?.as_u64())
+            .unwrap_or(2020);
+        let mut baf = format!("GEOID{:02},District\n", vintage % 100);
+        for (unit, districts) in shares {
+            let district = districts.into_iter()
+                .max_by(|a, b| a.1.total_cmp(&b.1).then(b.0.cmp(&a.0)))
+                .map(|(district, _)| district)
+                .expect("units are listed with at least one district");
+            baf.push_str(&format!("{unit},{district}\n"));
+        }
+        self.stamp_text(StampStyle::CsvComment, baf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_maps;
+
+    #[test]
+    fn baf_round_trips_and_disaggregates() {
+        let ids = ["170190001001000", "170190001001001", "170190002001000", "170190002001001"];
+        let map = test_maps::row_with(4, &ids, vec![("pop".into(), vec![1.0, 3.0, 2.0, 2.0])]);
+        let mut plan = Plan::new(map, 2).unwrap();
+
+        plan.load_baf("GEOID20|DISTRICT\n170190001001000|1\n170190001001001|2\n170190002001000|ZZ\n").unwrap();
+        assert_eq!(plan.get_assignments_vec().unwrap(), vec![1, 2, 0, 0]);
+        assert_eq!(plan.to_baf("block", None).unwrap(), "GEOID20,District\n170190001001000,1\n170190001001001,2\n");
+
+        // Tract GEOIDs are prefixes of block GEOIDs, so a tract-level file needs no tract layer.
+        plan.load_baf("17019000100,1\n17019000200,2\n").unwrap();
+        assert_eq!(plan.get_assignments_vec().unwrap(), vec![1, 1, 2, 2]);
+
+        // Split tracts go to the district holding most of their population, ties to the lower district.
+        plan.set_assignments_vec(vec![1, 2, 2, 1]).unwrap();
+        assert_eq!(plan.to_baf("tract", Some("pop")).unwrap(), "GEOID20,District\n17019000100,2\n17019000200,1\n");
+        assert_eq!(plan.to_baf("tract", None).unwrap(), "GEOID20,District\n17019000100,1\n17019000200,1\n");
+
+        assert!(plan.load_baf("170190001001000,3\n").is_err());
+        assert!(plan.load_baf("170190009001000,1\n").is_err());
+    }
+}
@@ -1,24 +1,44 @@
-use std::{collections::HashMap, path::Path};
+use std::{collections::HashMap, path::{Path, PathBuf}};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 
-use crate::{map::GeoId, plan::Plan};
+use crate::{map::GeoId, plan::{Plan, PlanMetadata}};
+
+use super::{StampStyle, strip_stamp};
+
+/// Path of the JSON metadata sidecar for a CSV plan file (`plan.csv` -> `plan.meta.json`).
+fn metadata_sidecar(csv_path: &Path) -> PathBuf {
+    csv_path.with_extension("meta.json")
+}
 
 impl Plan {
-    /// Load a plan from a CSV block assignment file.
+    /// Load a plan from a CSV block assignment file, along with its metadata sidecar if present.
     pub fn read_from_csv(&mut self, csv_path: &Path) -> Result<()> {
-        // Read the CSV file into a Polars DataFrame
-        let df = crate::io::csv::read_csv(csv_path)?;
+        // Read the CSV file, without any export stamp, into a Polars DataFrame
+        let csv = std::fs::read_to_string(csv_path)
+            .with_context(|| format!("[Plan] Failed to read {}", csv_path.display()))?;
+        let df = crate::io::csv::read_csv_string(&strip_stamp(&csv))?;
         let block_layer = self.map().base()?;
         let assignments_vec = crate::io::csv::read_plan_assignments(df, block_layer)?;
         // Convert Vec to HashMap
         let assignments: HashMap<GeoId, u32> = assignments_vec.into_iter().collect();
-        self.set_assignments(assignments)
+        self.set_assignments(assignments)?;
+
+        let sidecar = metadata_sidecar(csv_path);
+        if sidecar.exists() {
+            let json = std::fs::read_to_string(&sidecar)
+                .with_context(|| format!("[Plan] Failed to read metadata file {}", sidecar.display()))?;
+            let metadata: PlanMetadata = serde_json::from_str(&json)
+                .with_context(|| format!("[Plan] Failed to parse metadata file {}", sidecar.display()))?;
+            self.set_metadata(metadata);
+        }
+        self.metadata_mut().source = Some(csv_path.display().to_string());
+        Ok(())
     }
 
     /// Load a plan from CSV text (for browser/WASM use).
     pub fn load_csv(&mut self, csv: &str) -> Result<()> {
-        let df = crate::io::csv::read_csv_string(csv)?;
+        let df = crate::io::csv::read_csv_string(&strip_stamp(csv))?;
         let block_layer = self.map().base()?;
         let assignments_vec = crate::io::csv::read_plan_assignments(df, block_layer)?;
         // Convert Vec to HashMap
@@ -26,12 +46,14 @@ impl Plan {
         self.set_assignments(assignments)
     }
 
-    /// Generate a CSV block assignment
+    /// Generate a CSV block assignment, with the plan's metadata in a JSON sidecar file.
     pub fn write_to_csv(&self, path: &Path) -> Result<()> {
-        let assignments_map = self.get_assignments()?;
-        // Convert HashMap to Vec
-        let assignments: Vec<(GeoId, u32)> = assignments_map.into_iter().collect();
-        crate::io::csv::write_plan_assignments(&assignments, path)
+        std::fs::write(path, self.to_csv()?)
+            .with_context(|| format!("[Plan] Failed to write {}", path.display()))?;
+
+        let sidecar = metadata_sidecar(path);
+        std::fs::write(&sidecar, serde_json::to_string_pretty(self.metadata())?)
+            .with_context(|| format!("[Plan] Failed to write metadata file {}", sidecar.display()))
     }
 
     /// Generate a CSV block assignment as a string (for browser/WASM use).
@@ -39,6 +61,6 @@ impl Plan {
         let assignments_map = self.get_assignments()?;
         // Convert HashMap to Vec
         let assignments: Vec<(GeoId, u32)> = assignments_map.into_iter().collect();
-        crate::io::csv::write_plan_assignments_string(&assignments)
+        self.stamp_text(StampStyle::CsvComment, crate::io::csv::write_plan_assignments_string(&assignments)?)
     }
 }
@@ -0,0 +1,124 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, bail, ensure};
+use serde_json::{Value, json};
+
+use crate::{map::{multilinestring_to_geojson, multipolygon_to_geojson}, objective::Metric, partition::HullKind, plan::Plan};
+
+use super::StampStyle;
+
+/// Properties every district feature of [`Plan::to_geojson`] has, which metric names cannot reuse.
+const DISTRICT_PROPERTIES: [&str; 3] = ["district", "population", "deviation"];
+
+impl Plan {
+    /// Export the plan as a GeoJSON FeatureCollection with one MultiPolygon feature per district,
+    /// dissolved from its units, e.g. to drop into a web map. Each feature's properties hold its
+    /// `district` number, `population` (total of `pop_series`), `deviation` from the ideal
+    /// population, and its score on each of the named `metrics`. The plan's metadata is kept in
+    /// the collection's `metadata` member.
+    pub fn to_geojson(&self, pop_series: &str, metrics: &[(String, Metric)]) -> Result<Value> {
+        ensure!(self.series().contains(pop_series), "[Plan.to_geojson] Unknown series '{pop_series}'");
+        if let Some((name, _)) = metrics.iter().find(|(name, _)| DISTRICT_PROPERTIES.contains(&name.as_str())) {
+            bail!("[Plan.to_geojson] Metric name '{name}' clashes with a district property")
+        }
+        let scores = metrics.iter().map(|(name, metric)| (name, self.compute_metric(metric))).collect::<Vec<_>>();
+
+        let features = self.district_outlines(HullKind::Dissolve)?.into_iter()
+            .map(|(district, outline)| {
+                let mut properties = serde_json::Map::new();
+                properties.insert("district".into(), json!(district));
+                properties.insert("population".into(), json!(self.partition.part_total(pop_series, district)));
+                properties.insert("deviation".into(), json!(self.partition.population_deviation(district, pop_series)));
+                for (name, values) in &scores {
+                    properties.insert(name.to_string(), json!(values[district as usize - 1]));
+                }
+                Ok(json!({
+                    "type": "Feature",
+                    "geometry": multipolygon_to_geojson(&outline)?,
+                    "properties": properties,
+                }))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(json!({
+            "type": "FeatureCollection",
+            "features": features,
+            "metadata": self.metadata(),
+        }))
+    }
+
+    /// Write [`Plan::to_geojson`] to a `.geojson` file, stamped if exports are stamped
+    /// (see [`Plan::set_stamp_exports`]).
+    pub fn write_geojson(&self, path: &Path, pop_series: &str, metrics: &[(String, Metric)]) -> Result<()> {
+        std::fs::write(path, self.stamp_text(StampStyle::JsonMember, self.to_geojson(pop_series, metrics)?.to_string())?)
+            .with_context(|| format!("[Plan.write_geojson] Failed to write {}", path.display()))
+    }
+
+    /// Export the outline of each district (see [`Plan::district_outlines`]) as a GeoJSON
+    /// FeatureCollection of MultiPolygons, with the district number in its `district` property.
+    pub fn district_outlines_geojson(&self, kind: HullKind) -> Result<Value> {
+        let features = self.district_outlines(kind)?.into_iter()
+            .map(|(district, outline)| Ok(json!({
+                "type": "Feature",
+                "geometry": multipolygon_to_geojson(&outline)?,
+                "properties": { "district": district },
+            })))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(json!({
+            "type": "FeatureCollection",
+            "features": features,
+        }))
+    }
+
+    /// Export the lines where districts meet (see [`Plan::district_border_lines`]) as a GeoJSON
+    /// FeatureCollection of MultiLineStrings, one feature per pair of bordering districts with
+    /// the pair in its `districts` property.
+    pub fn district_borders_geojson(&self) -> Result<Value> {
+        let features = self.district_border_lines()?.into_iter()
+            .map(|((a, b), lines)| json!({
+                "type": "Feature",
+                "geometry": multilinestring_to_geojson(&lines),
+                "properties": { "districts": [a, b] },
+            }))
+            .collect::<Vec<_>>();
+
+        Ok(json!({
+            "type": "FeatureCollection",
+            "features": features,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::GeodesicArea;
+
+    use super::*;
+    use crate::{Map, test_maps};
+
+    #[test]
+    fn plan_geojson_dissolves_districts() {
+        // A row of four unit squares, two per district.
+        let geoms = test_maps::row_geoms(4);
+        let areas = geoms.iter().map(|geom| geom.geodesic_area_unsigned()).collect();
+        let map = Map::from_parts(geoms, None, vec![("pop".into(), vec![1.0, 2.0, 3.0, 4.0]), ("area_m2".into(), areas)], &[]).unwrap();
+        let mut plan = Plan::new(map, 2).unwrap();
+        plan.set_assignments_vec(vec![1, 1, 2, 2]).unwrap();
+
+        let geojson = plan.to_geojson("pop", &[("reock".into(), Metric::compactness_reock())]).unwrap();
+        let features = geojson["features"].as_array().unwrap();
+        assert_eq!(features.len(), 2);
+        for (feature, population) in features.iter().zip([3.0, 7.0]) {
+            assert_eq!(feature["geometry"]["type"], "MultiPolygon");
+            // Dissolved into one polygon per district, not one per unit.
+            assert_eq!(feature["geometry"]["coordinates"].as_array().unwrap().len(), 1);
+            assert_eq!(feature["properties"]["population"], population);
+            assert!(feature["properties"]["reock"].as_f64().unwrap() > 0.0);
+        }
+        assert_eq!(features[1]["properties"]["district"], 2);
+
+        assert!(plan.to_geojson("votes", &[]).is_err());
+        assert!(plan.to_geojson("pop", &[("district".into(), Metric::compactness_reock())]).is_err());
+    }
+}
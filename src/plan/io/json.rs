@@ -0,0 +1,115 @@
+use std::{collections::{BTreeMap, HashMap}, sync::Arc};
+
+use anyhow::{Context, Result, ensure};
+use serde::{Deserialize, Serialize};
+
+use crate::{map::{GeoId, GeoType, Map}, plan::{Plan, PlanMetadata}};
+
+/// Serialized form of a plan: the district count, each block's assignment, and metadata.
+/// Blocks missing from `assignments` are unassigned; with at-large seats, they form the at-large area.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PlanData {
+    pub num_districts: u32,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub at_large_seats: u32,
+    pub assignments: BTreeMap<String, u32>,
+    #[serde(default)]
+    pub metadata: PlanMetadata,
+}
+
+impl Serialize for Plan {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_data().map_err(serde::ser::Error::custom)?.serialize(serializer)
+    }
+}
+
+impl Plan {
+    /// Get the serializable form of this plan (non-zero assignments only).
+    pub fn to_data(&self) -> Result<PlanData> {
+        Ok(PlanData {
+            num_districts: self.num_districts(),
+            at_large_seats: self.at_large_seats(),
+            assignments: self.get_assignments()?.into_iter()
+                .filter(|&(_, district)| district != 0)
+                .map(|(geo_id, district)| (geo_id.id().to_string(), district))
+                .collect(),
+            metadata: self.metadata().clone(),
+        })
+    }
+
+    /// Create a plan on `map` from its serialized form.
+    pub fn from_data(map: impl Into<Arc<Map>>, data: &PlanData) -> Result<Self> {
+        let mut plan = Self::new(map, data.num_districts)?;
+        plan.set_data(data)?;
+        Ok(plan)
+    }
+
+    /// Replace this plan's assignments and metadata with those of a serialized plan with the same district count.
+    pub fn set_data(&mut self, data: &PlanData) -> Result<()> {
+        ensure!(data.num_districts == self.num_districts(),
+            "[Plan] Serialized plan has {} districts, expected {}", data.num_districts, self.num_districts());
+
+        let index = self.map().base()?.index();
+        let mut assignments = HashMap::with_capacity(data.assignments.len());
+        for (id, &district) in &data.assignments {
+            ensure!(district <= self.num_districts(), "[Plan] District {} out of range for block {}", district, id);
+            ensure!(id.len() == GeoType::BOTTOM.id_len(), "[Plan] Unknown block {}", id);
+            let geo_id = GeoId::new(GeoType::BOTTOM, id);
+            ensure!(index.contains_key(&geo_id), "[Plan] Unknown block {}", id);
+            assignments.insert(geo_id, district);
+        }
+        self.set_assignments(assignments)?;
+        self.set_metadata(data.metadata.clone());
+
+        self.set_at_large_seats(data.at_large_seats);
+        match data.at_large_seats > 0 && !self.partition.part_is_empty(0) {
+            true => self.partition.set_at_large(&self.partition.part_nodes(0)),
+            false => self.partition.clear_at_large(),
+        }
+        Ok(())
+    }
+
+    /// Serialize this plan to a JSON string.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(&self.to_data()?).context("[Plan] Failed to serialize plan")
+    }
+
+    /// Load assignments from a JSON string produced by [`Plan::to_json`].
+    pub fn load_json(&mut self, json: &str) -> Result<()> {
+        let data: PlanData = serde_json::from_str(json).context("[Plan] Failed to parse plan JSON")?;
+        self.set_data(&data)
+    }
+}
+
+fn is_zero(value: &u32) -> bool { *value == 0 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_maps;
+
+    #[test]
+    fn plans_round_trip_through_json() {
+        // Hold the first column of a 4x4 grid at large and district the rest.
+        let map = Arc::new(test_maps::grid(4));
+        let mut plan = Plan::new(map.clone(), 2).unwrap();
+        plan.set_at_large_seats(1);
+        let at_large = map.base().unwrap().geo_ids()[..4].iter().map(|geo_id| geo_id.id().to_string()).collect::<Vec<_>>();
+        plan.set_at_large_area("block", &at_large.iter().map(String::as_str).collect::<Vec<_>>()).unwrap();
+        plan.randomize().unwrap();
+        plan.metadata_mut().name = Some("Test plan".into());
+
+        let json = plan.to_json().unwrap();
+        let data: PlanData = serde_json::from_str(&json).unwrap();
+        assert_eq!((data.assignments.len(), data.at_large_seats), (12, 1));
+        assert_eq!(serde_json::to_string(&data).unwrap(), json);
+
+        let loaded = Plan::from_data(map.clone(), &data).unwrap();
+        assert_eq!(loaded.get_assignments_vec().unwrap(), plan.get_assignments_vec().unwrap());
+        assert_eq!(loaded.at_large_area().unwrap().len(), 4);
+        assert_eq!(loaded.metadata().name.as_deref(), Some("Test plan"));
+
+        let mut three = Plan::new(map, 3).unwrap();
+        assert!(three.load_json(&json).is_err());
+    }
+}
@@ -1,2 +1,13 @@
+mod baf;
 mod csv;
+mod geojson;
+mod json;
+#[cfg(feature = "download")]
+mod shp;
+mod stamp;
 mod svg;
+
+pub use json::PlanData;
+pub use stamp::ExportStamp;
+pub(crate) use stamp::{StampStyle, strip_stamp};
+pub(super) use baf::unit_ids;
@@ -0,0 +1,80 @@
+use std::path::Path;
+
+use anyhow::{Result, anyhow};
+
+use crate::{io::shp::{ShapefileColumn, ShapefileSchema, write_shapefile}, objective::Metric, partition::HullKind, plan::Plan};
+
+impl Plan {
+    /// Export the plan as an ESRI shapefile at `path` (`.shp`, with `.shx`, `.dbf` and `.prj`
+    /// files next to it), with one polygon per district dissolved from its units, as many state
+    /// legislatures require for plan submissions. Each record has a `DISTRICT` number, followed by
+    /// the fields of `schema`, whose columns name either one of `metrics` (the district's score) or
+    /// a series (the district's total).
+    pub fn write_shapefile(&self, path: &Path, schema: &ShapefileSchema, metrics: &[(String, Metric)]) -> Result<()> {
+        let outlines = self.district_outlines(HullKind::Dissolve)?;
+        let districts = outlines.iter().map(|(district, _)| *district).collect::<Vec<_>>();
+        let series = self.series();
+
+        let mut fields = vec![("DISTRICT".to_string(), ShapefileColumn::Number(districts.iter().map(|&d| d as f64).collect()))];
+        for (name, column) in &schema.fields {
+            let values = match metrics.iter().find(|(metric, _)| metric == column) {
+                Some((_, metric)) => {
+                    let scores = self.compute_metric(metric);
+                    districts.iter().map(|&d| scores[d as usize - 1]).collect()
+                }
+                None if series.contains(column) => districts.iter().map(|&d| self.partition.part_total(column, d)).collect(),
+                None => return Err(anyhow!("[Plan.write_shapefile] '{column}' is neither a metric nor a series")),
+            };
+            fields.push((name.clone(), ShapefileColumn::Number(values)));
+        }
+
+        let shapes = outlines.into_iter().map(|(_, outline)| outline).collect::<Vec<_>>();
+        write_shapefile(path, &shapes, &fields)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use shapefile::dbase::FieldValue;
+
+    use super::*;
+    use crate::{io::shp::{read_shapefile, shape_to_multipolygon}, test_maps};
+
+    #[test]
+    fn plan_and_layer_shapefiles_round_trip() {
+        let ids = ["170190001001000", "170190001001001", "170190002001000", "170190002001001"];
+        let map = test_maps::row_with(4, &ids, vec![("T_20_CENS_Total".into(), vec![1.0, 2.0, 3.0, 4.0])]);
+        let mut plan = Plan::new(map, 2).unwrap();
+        plan.set_assignments_vec(vec![1, 1, 2, 2]).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("plan.shp");
+        let schema = ShapefileSchema::default().field("POP", "T_20_CENS_Total").field("DEV", "deviation");
+        let metrics = [("deviation".to_string(), Metric::population_deviation("T_20_CENS_Total".into()))];
+        plan.write_shapefile(&path, &schema, &metrics).unwrap();
+        for ext in ["shx", "dbf", "prj"] {
+            assert!(path.with_extension(ext).exists());
+        }
+
+        let (shapes, records) = read_shapefile(&path).unwrap();
+        assert_eq!(shapes.len(), 2);
+        for ((shape, record), (district, pop)) in shapes.into_iter().zip(&records).zip([(1.0, 3.0), (2.0, 7.0)]) {
+            // Dissolved into one polygon per district, not one per unit.
+            assert_eq!(shape_to_multipolygon(shape).unwrap().0.len(), 1);
+            assert_eq!(record.get("DISTRICT"), Some(&FieldValue::Numeric(Some(district))));
+            assert_eq!(record.get("POP"), Some(&FieldValue::Numeric(Some(pop))));
+            assert!(matches!(record.get("DEV"), Some(FieldValue::Numeric(Some(_)))));
+        }
+
+        // Layers export one shape per unit, keyed by GEOID.
+        let layer_path = dir.path().join("block.shp");
+        plan.map().base().unwrap().write_shapefile(&layer_path, &ShapefileSchema::default().field("POP", "T_20_CENS_Total")).unwrap();
+        let (shapes, records) = read_shapefile(&layer_path).unwrap();
+        assert_eq!(shapes.len(), 4);
+        assert_eq!(records[3].get("GEOID"), Some(&FieldValue::Character(Some(ids[3].into()))));
+        assert_eq!(records[3].get("POP"), Some(&FieldValue::Numeric(Some(4.0))));
+
+        assert!(plan.write_shapefile(&path, &ShapefileSchema::from_columns(&["T_20_CENS_Total"]), &[]).is_err());
+        assert!(plan.write_shapefile(&path, &ShapefileSchema::default().field("X", "missing"), &[]).is_err());
+    }
+}
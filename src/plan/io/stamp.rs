@@ -0,0 +1,189 @@
+use std::{fmt, fs::{File, OpenOptions}, io::Write, path::Path};
+
+use anyhow::{Context, Result, anyhow, ensure};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::{map::pack::sha256_hex, plan::Plan};
+
+/// Word opening every export stamp.
+const MARKER: &str = "openmander-stamp";
+
+/// Stamp embedded in an exported plan artifact: the plan it was exported from, the library
+/// version that wrote it, and a hash of the rest of the artifact, so that an exhibit edited after
+/// export can be detected with [`Plan::verify_export`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExportStamp {
+    pub plan_id: String, // Plan::plan_id of the exported plan
+    pub tool: String,    // Library that wrote the artifact, e.g. "openmander/0.1.0"
+    pub sha256: String,  // SHA-256 of the artifact without its stamp
+}
+
+impl fmt::Display for ExportStamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{MARKER} plan={} tool={} sha256={}", self.plan_id, self.tool, self.sha256)
+    }
+}
+
+impl ExportStamp {
+    /// Parse a stamp written by its `Display` impl.
+    fn parse(text: &str) -> Option<Self> {
+        let mut words = text.split_whitespace();
+        if words.next()? != MARKER { return None }
+        let mut field = |key: &str| words.next()?.strip_prefix(key)?.strip_prefix('=').map(str::to_string);
+        Some(Self { plan_id: field("plan")?, tool: field("tool")?, sha256: field("sha256")? })
+    }
+}
+
+/// Where a format carries its stamp, so readers of the format skip it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StampStyle {
+    CsvComment, // A leading `# ...` line
+    JsonMember, // A leading `"openmander_stamp"` member of the top-level object
+    XmlComment, // A trailing `<!-- ... -->` comment
+}
+
+impl StampStyle {
+    /// Embed `stamp` in `body`.
+    fn embed(self, stamp: &ExportStamp, body: &str) -> String {
+        match self {
+            Self::CsvComment => format!("# {stamp}\n{body}"),
+            Self::JsonMember => format!("{{\"openmander_stamp\":\"{stamp}\",{}", body.strip_prefix('{').unwrap_or(body)),
+            Self::XmlComment => format!("{body}<!-- {stamp} -->\n"),
+        }
+    }
+}
+
+/// Split a stamped export into its stamp and the text it covers, or `None` if it has no stamp.
+fn split_stamp(text: &str) -> Option<(ExportStamp, String)> {
+    if let Some(rest) = text.strip_prefix("# ") {
+        let (line, body) = rest.split_once('\n').unwrap_or((rest, ""));
+        return Some((ExportStamp::parse(line)?, body.to_string()))
+    }
+    if let Some(rest) = text.strip_prefix("{\"openmander_stamp\":\"") {
+        let (stamp, body) = rest.split_once("\",")?;
+        return Some((ExportStamp::parse(stamp)?, format!("{{{body}")))
+    }
+    let start = text.rfind(&format!("<!-- {MARKER} "))?;
+    let stamp = text[start + 5..].trim_end().strip_suffix("-->")?;
+    Some((ExportStamp::parse(stamp)?, text[..start].to_string()))
+}
+
+/// Text of an export without its stamp, for readers of plan files.
+pub(crate) fn strip_stamp(text: &str) -> String {
+    split_stamp(text).map_or_else(|| text.to_string(), |(_, body)| body)
+}
+
+impl Plan {
+    /// Content identifier of the plan's assignments: the SHA-256 (as lowercase hex) of the district
+    /// count on one line, followed by a `GEOID,district` line for every block in GEOID order.
+    /// Identical assignments on the same blocks always get the same ID.
+    pub fn plan_id(&self) -> Result<String> {
+        let base = self.map.base()?;
+        let assignments = self.partition.assignments();
+        let mut blocks = base.geo_ids().iter().zip(assignments.iter()).collect::<Vec<_>>();
+        blocks.sort_by_key(|(geo_id, _)| geo_id.id());
+
+        let mut hasher = Sha256::new();
+        hasher.update(format!("{}\n", self.num_districts()));
+        for (geo_id, district) in blocks {
+            hasher.update(format!("{},{district}\n", geo_id.id()));
+        }
+        Ok(hex::encode(hasher.finalize()))
+    }
+
+    /// Check whether exports (CSV, block assignment files, GeoJSON, SVG) are stamped.
+    #[inline] pub fn stamp_exports(&self) -> bool { self.stamp_exports }
+
+    /// Stamp every export written from now on with the plan's ID, the library version, and a hash
+    /// of its contents (see [`ExportStamp`]), as a comment or an extra member that readers of the
+    /// format ignore. Check an artifact with [`Plan::verify_export`].
+    #[inline] pub fn set_stamp_exports(&mut self, enabled: bool) { self.stamp_exports = enabled }
+
+    /// Stamp for an export whose contents (without the stamp) hash to `sha256`.
+    fn export_stamp(&self, sha256: String) -> Result<ExportStamp> {
+        Ok(ExportStamp { plan_id: self.plan_id()?, tool: format!("openmander/{}", env!("CARGO_PKG_VERSION")), sha256 })
+    }
+
+    /// Embed a stamp in the export `body`, if exports are stamped.
+    pub(crate) fn stamp_text(&self, style: StampStyle, body: String) -> Result<String> {
+        if !self.stamp_exports { return Ok(body) }
+        let stamp = self.export_stamp(sha256_hex(body.as_bytes()))?;
+        Ok(style.embed(&stamp, &body))
+    }
+
+    /// Append a stamp to the SVG file streamed to `path`, if exports are stamped.
+    pub(crate) fn stamp_svg_file(&self, path: &Path) -> Result<()> {
+        if !self.stamp_exports { return Ok(()) }
+        let mut hasher = Sha256::new();
+        let mut file = File::open(path).with_context(|| format!("[Plan] Failed to read {}", path.display()))?;
+        std::io::copy(&mut file, &mut hasher).with_context(|| format!("[Plan] Failed to read {}", path.display()))?;
+        let stamp = self.export_stamp(hex::encode(hasher.finalize()))?;
+
+        let mut file = OpenOptions::new().append(true).open(path)
+            .with_context(|| format!("[Plan] Failed to stamp {}", path.display()))?;
+        file.write_all(StampStyle::XmlComment.embed(&stamp, "").as_bytes())
+            .with_context(|| format!("[Plan] Failed to stamp {}", path.display()))
+    }
+
+    /// Check that the text of an exported artifact is unmodified and was exported from this plan:
+    /// its contents must match the hash in its stamp, and the stamp's plan ID must be this plan's.
+    /// Returns the stamp, e.g. to report the library version that wrote the artifact.
+    pub fn verify_export(&self, text: &str) -> Result<ExportStamp> {
+        let (stamp, body) = split_stamp(text)
+            .ok_or_else(|| anyhow!("[Plan.verify_export] The export has no {MARKER} (was it written with stamped exports?)"))?;
+        let actual = sha256_hex(body.as_bytes());
+        ensure!(actual == stamp.sha256,
+            "[Plan.verify_export] The export was modified after it was written (contents hash to {actual}, stamp records {})", stamp.sha256);
+        let plan_id = self.plan_id()?;
+        ensure!(plan_id == stamp.plan_id,
+            "[Plan.verify_export] The export is of a different plan (stamp records plan {}, this plan is {plan_id})", stamp.plan_id);
+        Ok(stamp)
+    }
+
+    /// Check an exported artifact at `path` (see [`Plan::verify_export`]).
+    pub fn verify_export_file(&self, path: &Path) -> Result<ExportStamp> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("[Plan.verify_export] Failed to read {}", path.display()))?;
+        self.verify_export(&text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_maps;
+
+    #[test]
+    fn stamped_exports_verify_until_modified() {
+        let ids = ["170190001001000", "170190001001001", "170190002001000", "170190002001001"];
+        let map = test_maps::row(4, &ids);
+        let mut plan = Plan::new(map, 2).unwrap();
+        plan.set_assignments_vec(vec![1, 1, 2, 2]).unwrap();
+        assert!(plan.to_baf("block", None).unwrap().starts_with("GEOID20,District\n"));
+
+        plan.set_stamp_exports(true);
+        let dir = tempfile::tempdir().unwrap();
+        let [baf, geojson, svg] = ["plan.csv", "plan.geojson", "plan.svg"].map(|name| dir.path().join(name));
+        plan.write_baf(&baf, "block", None).unwrap();
+        plan.write_geojson(&geojson, "pop", &[]).unwrap();
+        plan.to_svg(&svg, false).unwrap();
+
+        for path in [&baf, &geojson, &svg] {
+            let export = std::fs::read_to_string(path).unwrap();
+            assert_eq!(plan.verify_export(&export).unwrap().plan_id, plan.plan_id().unwrap());
+            let edited = if path == &baf { export.replacen(",1\n", ",2\n", 1) } else { export.replacen("1.0", "1.5", 1) };
+            assert!(plan.verify_export(&edited).unwrap_err().to_string().contains("modified"));
+        }
+        assert!(serde_json::from_str::<serde_json::Value>(&std::fs::read_to_string(&geojson).unwrap()).is_ok());
+
+        // Stamped files still load, and stop verifying once the plan changes.
+        let mut copy = plan.clone();
+        copy.set_assignments_vec(vec![0; 4]).unwrap();
+        copy.read_baf(&baf).unwrap();
+        assert_eq!(copy.get_assignments_vec().unwrap(), vec![1, 1, 2, 2]);
+        plan.set_assignments_vec(vec![1, 2, 2, 2]).unwrap();
+        assert!(plan.verify_export_file(&baf).unwrap_err().to_string().contains("different plan"));
+        assert!(plan.verify_export("GEOID20,District\n").is_err());
+    }
+}
@@ -1,74 +1,323 @@
 use std::{collections::{HashMap, HashSet}, io::Write, path::Path};
 
-use anyhow::Result;
+use anyhow::{Context, Result, bail, ensure};
 use geo::Coord;
+use geograph::UnitId;
 
-use crate::{io::svg::{Projection, SegmentSet, Viewport}, plan::Plan};
+use crate::{io::svg::{Annotations, Palette, PanelLayout, SegmentSet, SvgOptions, Viewport}, plan::Plan};
+
+use super::StampStyle;
+
+/// Dissolved boundary rings of one district, or `None` if it is empty.
+type DistrictRings = Option<Vec<Vec<Coord<f64>>>>;
+
+/// Labelled values drawn by an annotation: (label, fill) legend entries or (name, value) metric lines.
+type Entries = Vec<(String, String)>;
 
 impl Plan {
     /// Small wrapper with defaults.
     pub fn to_svg(&self, path: &Path, color_partisan: bool) -> Result<()> {
-        self.to_svg_with_size(path, color_partisan, 1200, 10)
+        self.to_svg_with_options(path, color_partisan, None, &SvgOptions::default())
     }
 
-    /// Draw dissolved districts using only frontier blocks + state boundary.
-    fn to_svg_with_size(&self, path: &Path, color_partisan: bool, width: i32, margin: i32) -> Result<()> {
-        let bounds = self.map().base()?.region().bounds_all();
+    /// Like `to_svg`, but fill districts from `palette` so that bordering districts never share a color.
+    pub fn to_svg_with_palette(&self, path: &Path, palette: Palette) -> Result<()> {
+        self.to_svg_with_options(path, false, Some(palette), &SvgOptions::default())
+    }
 
-        let vp = Viewport::new(bounds, width as f64, margin as f64);
+    /// Draw dissolved districts with the given projection and size. Districts are filled from
+    /// `palette` if given, otherwise by partisan lean if `color_partisan`, otherwise by a distinct hue.
+    pub fn to_svg_with_options(&self, path: &Path, color_partisan: bool, palette: Option<Palette>, options: &SvgOptions) -> Result<()> {
+        let mut writer = crate::io::svg::SvgWriter::new(path)?;
+        self.render_district_svg(&mut writer, &self.district_fills(color_partisan, palette)?, options)?;
+        writer.flush()?;
+        self.stamp_svg_file(path)
+    }
 
-        // --- Precompute state outer boundary as a segment set ---
-        // Build a set of undirected segments for the *outer* state boundary (all exteriors).
-        let state_outline = {
-            let outline = self.map().region()?.union();
+    /// Return dissolved districts (see [`Plan::to_svg_with_options`]) as an SVG string.
+    pub fn to_svg_string_with_options(&self, color_partisan: bool, palette: Option<Palette>, options: &SvgOptions) -> Result<String> {
+        let mut writer = crate::io::svg::SvgStringWriter::new();
+        self.render_district_svg(&mut writer, &self.district_fills(color_partisan, palette)?, options)?;
+        self.stamp_text(StampStyle::XmlComment, writer.into_string()?)
+    }
 
-            let mut ptmap: HashMap<crate::io::svg::QuantizedPoint, Coord<f64>> = HashMap::new();
-            let mut set = crate::io::svg::SegmentSet::default();
+    /// Draw every block, streamed to disk, with one `<g id="district-{n}">` group per district
+    /// carrying the district fill. Unassigned blocks go in `district-0` with the default style.
+    pub fn to_block_svg(&self, path: &Path, color_partisan: bool) -> Result<()> {
+        self.to_block_svg_with_options(path, color_partisan, None, &SvgOptions::default())
+    }
 
-            for polygon in outline {
-                crate::io::svg::collect_ring_segments(polygon.exterior(), &mut set, &mut ptmap);
+    /// Like `to_block_svg`, but fill districts from `palette` so that bordering districts never share a color.
+    pub fn to_block_svg_with_palette(&self, path: &Path, palette: Palette) -> Result<()> {
+        self.to_block_svg_with_options(path, false, Some(palette), &SvgOptions::default())
+    }
+
+    /// Draw every block (see `to_block_svg`) with the given projection, size, and district fills.
+    pub fn to_block_svg_with_options(&self, path: &Path, color_partisan: bool, palette: Option<Palette>, options: &SvgOptions) -> Result<()> {
+        self.write_block_svg(path, &self.district_fills(color_partisan, palette)?, options)
+    }
+
+    /// Hex color of each district 1..=num_districts from `palette`, with no two bordering districts
+    /// sharing a color where the palette allows. Depends only on the assignment, so the same plan
+    /// always gets the same colors.
+    pub fn district_colors(&self, palette: Palette) -> Vec<String> {
+        crate::io::svg::color_graph(&self.partition.part_adjacency(), palette)
+            .into_iter().skip(1).map(|color| color.to_hex()).collect()
+    }
+
+    fn write_block_svg(&self, path: &Path, colors: &[String], options: &SvgOptions) -> Result<()> {
+        self.map().require_geometry("[Plan.to_block_svg]")?;
+        let region = self.map().base()?.region();
+        let fills = shown_fills(colors, options);
+        let (legend, metrics) = self.annotation_entries(&options.annotations, &fills)?;
+        let footer = crate::io::svg::annotation_height(&options.annotations, options.width, legend.len(), metrics.len());
+        let vp = Viewport::for_region(region, options).with_footer(footer);
+        let project = move |coord: &Coord<f64>| vp.project(coord);
+
+        let mut members = vec![Vec::new(); self.num_districts() as usize + 1];
+        for (u, &part) in self.partition.assignments().iter().enumerate() {
+            members[part as usize].push(u);
+        }
+
+        let mut writer = crate::io::svg::SvgWriter::new(path)?;
+        writer.write_header(&vp, &options.accessibility)?;
+        writer.write_styles()?;
+        self.write_patterns(&mut writer, colors, options)?;
+
+        let district = options.annotations.locale.text("District");
+        for (part, units) in members.iter().enumerate() {
+            if units.is_empty() { continue }
+            let (style, label) = match part {
+                0 => (None, None),
+                part => (Some(format!("fill:{}", fills[part])), Some(format!("{district} {part}"))),
+            };
+            crate::io::svg::write_group_start(&mut writer, "dist", &format!("district-{part}"), style.as_deref(), label.as_deref())?;
+            let inherit = if style.is_some() { r#" style="fill:inherit""# } else { "" };
+            for &u in units {
+                write!(writer, r#"<path class="blk" fill-rule="evenodd"{inherit} d=""#)?;
+                crate::io::svg::write_path_data(&mut writer, &region.geometry(UnitId(u as u32)), &project)?;
+                writeln!(writer, r#""/>"#)?;
             }
+            crate::io::svg::write_group_end(&mut writer)?;
+        }
 
-            set
+        self.write_symbols(&mut writer, options, &vp)?;
+        crate::io::svg::write_annotations(&mut writer, &vp, &options.annotations, &legend, &metrics)?;
+        writer.write_footer()?;
+        writer.flush()?;
+        self.stamp_svg_file(path)
+    }
+
+    /// Draw the symbols in `options`, if any, over the districts.
+    fn write_symbols(&self, writer: &mut impl Write, options: &SvgOptions, vp: &Viewport) -> Result<()> {
+        let Some(symbols) = &options.symbols else { return Ok(()) };
+        let layer = self.map().layer(symbols.layer)
+            .with_context(|| format!("[Plan.to_svg] Layer {:?} is not present in this map", symbols.layer.to_str()))?;
+        layer.write_symbols(writer, &symbols.style, vp)
+    }
+
+    /// Define the hatch patterns over district `colors` drawn if `options` ask for them (see [`shown_fills`]).
+    fn write_patterns(&self, writer: &mut impl Write, colors: &[String], options: &SvgOptions) -> Result<()> {
+        if !options.accessibility.patterns { return Ok(()) }
+        crate::io::svg::write_fill_patterns(writer, colors, &self.partition.part_adjacency())
+    }
+
+    /// Legend entries (label, fill) and metrics box lines (name, value) for `annotations`.
+    fn annotation_entries(&self, annotations: &Annotations, fills: &[String]) -> Result<(Entries, Entries)> {
+        let legend = if annotations.legend {
+            (1..=self.num_districts()).map(|part| (part.to_string(), fills[part as usize].clone())).collect()
+        } else { vec![] };
+
+        let Some(series) = &annotations.metrics else { return Ok((legend, vec![])) };
+        let available = self.partition.series();
+        for name in [&series.pop, &series.dem, &series.rep] {
+            ensure!(available.contains(name), "[Plan.to_svg] missing series {name:?} for metrics box");
+        }
+        let max_deviation = (1..=self.num_districts())
+            .map(|part| self.partition.absolute_population_deviation(part, &series.pop))
+            .fold(0.0, f64::max);
+        let gap = self.partition.efficiency_gap(&series.dem, &series.rep);
+        let locale = &annotations.locale;
+        let metrics = vec![
+            (locale.text("Districts").into(), self.num_districts().to_string()),
+            (locale.text("Max population deviation").into(), locale.format_percent(max_deviation, 2)),
+            (locale.text("Efficiency gap").into(), format!("{} {}", locale.format_percent(gap.abs(), 1), if gap > 0.0 { "D" } else if gap < 0.0 { "R" } else { "" }).trim_end().to_string()),
+        ];
+        Ok((legend, metrics))
+    }
+
+    /// Fill color of each part (index 0, unassigned, is unused): from `palette` if given, otherwise
+    /// partisan lean if `color_partisan`, otherwise a distinct hue per district.
+    fn district_fills(&self, color_partisan: bool, palette: Option<Palette>) -> Result<Vec<String>> {
+        if let Some(palette) = palette {
+            return Ok(crate::io::svg::color_graph(&self.partition.part_adjacency(), palette)
+                .into_iter().map(|color| color.to_string()).collect());
+        }
+        let state_id = if color_partisan { 0 } else {
+            self.map().region()?.geo_ids()[0].id().parse::<usize>().expect("[Plan.to_svg] Couldn't determine state id.")
         };
+        Ok(std::iter::once(String::new()).chain((1..=self.num_districts()).map(|part| if color_partisan {
+            crate::io::svg::partisan_color(self.partition.partisan_lean(part, "E_20_PRES_Dem", "E_20_PRES_Rep")).to_string()
+        } else {
+            crate::io::svg::golden_angle_color((state_id + 1) * 100 + part as usize).to_string()
+        })).collect())
+    }
+
+    /// Draw dissolved districts using only frontier blocks + state boundary.
+    fn render_district_svg(&self, writer: &mut impl Write, colors: &[String], options: &SvgOptions) -> Result<()> {
+        self.map().require_geometry("[Plan.to_svg]")?;
+        let fills = shown_fills(colors, options);
+        let (legend, metrics) = self.annotation_entries(&options.annotations, &fills)?;
+        let footer = crate::io::svg::annotation_height(&options.annotations, options.width, legend.len(), metrics.len());
+        let vp = Viewport::for_region(self.map().base()?.region(), options).with_footer(footer);
+        let outlines = self.dissolved_rings()?;
 
-        // --- Write SVG ---
+        crate::io::svg::write_svg_header(writer, &vp, &options.accessibility)?;
+        crate::io::svg::write_svg_styles(writer)?;
+        self.write_patterns(writer, colors, options)?;
+        write_districts(writer, &outlines, &fills, &vp, options.annotations.locale.text("District"))?;
+        self.write_symbols(writer, options, &vp)?;
+        crate::io::svg::write_annotations(writer, &vp, &options.annotations, &legend, &metrics)?;
+        crate::io::svg::write_svg_footer(writer)
+    }
+
+    /// Draw several plans of the same map side by side, one titled panel each, with any zoom
+    /// insets in `layout` repeated in every panel. Districts are filled as in `to_svg_with_options`.
+    pub fn to_panels_svg(path: &Path, plans: &[(&str, &Plan)], color_partisan: bool, palette: Option<Palette>, layout: &PanelLayout) -> Result<()> {
         let mut writer = crate::io::svg::SvgWriter::new(path)?;
-        writer.write_header(&vp)?;
-        writer.write_styles()?;
+        Self::render_panels(&mut writer, plans, color_partisan, palette, layout)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Return a multi-panel figure (see [`Plan::to_panels_svg`]) as an SVG string.
+    pub fn to_panels_svg_string(plans: &[(&str, &Plan)], color_partisan: bool, palette: Option<Palette>, layout: &PanelLayout) -> Result<String> {
+        let mut writer = crate::io::svg::SvgStringWriter::new();
+        Self::render_panels(&mut writer, plans, color_partisan, palette, layout)?;
+        writer.into_string()
+    }
+
+    fn render_panels(writer: &mut impl Write, plans: &[(&str, &Plan)], color_partisan: bool, palette: Option<Palette>, layout: &PanelLayout) -> Result<()> {
+        let Some(&(_, first)) = plans.first() else { bail!("[Plan.to_panels_svg] No plans to draw") };
+        ensure!(plans.iter().all(|(_, plan)| std::ptr::eq(plan.map(), first.map())),
+            "[Plan.to_panels_svg] All plans must share the same map");
+        first.map().require_geometry("[Plan.to_panels_svg]")?;
+
+        let vp = Viewport::for_region(first.map().base()?.region(), &layout.panel);
+        let panels = plans.iter()
+            .map(|(_, plan)| Ok((plan.dissolved_rings()?, plan.district_fills(color_partisan, palette)?)))
+            .collect::<Result<Vec<_>>>()?;
+        let titles = plans.iter().map(|&(title, _)| title).collect::<Vec<_>>();
+
+        let district = layout.panel.annotations.locale.text("District");
+        layout.write(writer, &vp, &titles, |writer, i, vp| {
+            let (outlines, fills) = &panels[i];
+            write_districts(writer, outlines, fills, vp, district)
+        })
+    }
 
+    /// Heat map of [`Plan::unit_flippability`] over `plans`: every block filled on a yellow-to-red
+    /// ramp by how often it changes district, with the districts of the first plan outlined on top.
+    /// The legend shows the ramp; the metrics box is not drawn.
+    pub fn to_flippability_svg(path: &Path, plans: &[&Plan], options: &SvgOptions) -> Result<()> {
+        let flippability = Self::unit_flippability(plans)?;
+        let first = plans[0];
+        first.map().require_geometry("[Plan.to_flippability_svg]")?;
+        let region = first.map().base()?.region();
+        let annotations = Annotations { metrics: None, ..options.annotations.clone() };
+        let legend = if annotations.legend {
+            [0.0, 0.25, 0.5, 0.75, 1.0].into_iter()
+                .map(|t| (t.to_string(), crate::io::svg::heat_color(t).to_string())).collect()
+        } else { vec![] };
+        let footer = crate::io::svg::annotation_height(&annotations, options.width, legend.len(), 0);
+        let vp = Viewport::for_region(region, options).with_footer(footer);
         let project = move |coord: &Coord<f64>| vp.project(coord);
 
-        // Draw each district as a single dissolved path (holes supported via even-odd fill).
-        for part in 1..=self.num_districts() as usize {
-            if let Some(path) = self.build_district_path_string(part as u32, &state_outline, &project)? {
-                // Determine fill color based on boolean variable.
-                let fill: String = if color_partisan {
-                    crate::io::svg::partisan_color(self.partition.partisan_lean(part as u32, "E_20_PRES_Dem", "E_20_PRES_Rep")).to_string()
-                } else {
-                    let state_id = self.map().region()?.geo_ids()[0].id().parse::<usize>().expect("[Plan.to_svg] Couldn't determine state id.");
-                    crate::io::svg::golden_angle_color((state_id + 1) * 100 + part).to_string()
-                };
-
-                writeln!(
-                    writer,
-                    r#"<path class="dist" fill-rule="evenodd" style="fill:{fill};stroke:#111827;stroke-width:0.6;fill-opacity:0.85" d="{path}"/>"#,
-                )?;
-            }
+        let mut writer = crate::io::svg::SvgWriter::new(path)?;
+        writer.write_header(&vp, &options.accessibility)?;
+        writer.write_styles()?;
+        crate::io::svg::write_group_start(&mut writer, "heat", "flippability", None, None)?;
+        for (u, &value) in flippability.iter().enumerate() {
+            write!(writer, r#"<path class="blk" fill-rule="evenodd" style="fill:{};stroke:none" data-flippability="{value:.4}" d=""#, crate::io::svg::heat_color(value))?;
+            crate::io::svg::write_path_data(&mut writer, &region.geometry(UnitId(u as u32)), &project)?;
+            writeln!(writer, r#""/>"#)?;
         }
+        crate::io::svg::write_group_end(&mut writer)?;
 
+        let outlines = first.dissolved_rings()?;
+        let fills = vec!["none".to_string(); outlines.len() + 1];
+        write_districts(&mut writer, &outlines, &fills, &vp, options.annotations.locale.text("District"))?;
+        first.write_symbols(&mut writer, options, &vp)?;
+        crate::io::svg::write_annotations(&mut writer, &vp, &annotations, &legend, &[])?;
         writer.write_footer()?;
         writer.flush()?;
         Ok(())
     }
 
-    /// Build dissolved boundary for district `d` using frontier blocks, immediate same-district neighbors,
-    /// and segments on the state outer boundary.
-    fn build_district_path_string(&self, d: u32, state_outline: &SegmentSet, project: &Projection) -> Result<Option<String>> {
+    /// Draw only the lines where districts meet (see [`Plan::district_border_lines`]), with one
+    /// `<path class="border">` per pair of districts, for overlay on a basemap. The legend and
+    /// metrics box are not drawn.
+    pub fn to_border_svg(&self, path: &Path, options: &SvgOptions) -> Result<()> {
+        let mut writer = crate::io::svg::SvgWriter::new(path)?;
+        self.render_borders(&mut writer, options)?;
+        writer.flush()?;
+        self.stamp_svg_file(path)
+    }
+
+    /// Return the district border lines (see [`Plan::to_border_svg`]) as an SVG string.
+    pub fn to_border_svg_string(&self, options: &SvgOptions) -> Result<String> {
+        let mut writer = crate::io::svg::SvgStringWriter::new();
+        self.render_borders(&mut writer, options)?;
+        self.stamp_text(StampStyle::XmlComment, writer.into_string()?)
+    }
+
+    fn render_borders(&self, writer: &mut impl Write, options: &SvgOptions) -> Result<()> {
+        self.map().require_geometry("[Plan.to_border_svg]")?;
+        let annotations = Annotations { legend: false, metrics: None, ..options.annotations.clone() };
+        let footer = crate::io::svg::annotation_height(&annotations, options.width, 0, 0);
+        let vp = Viewport::for_region(self.map().base()?.region(), options).with_footer(footer);
+        let project = move |coord: &Coord<f64>| vp.project(coord);
+
+        crate::io::svg::write_svg_header(writer, &vp, &options.accessibility)?;
+        crate::io::svg::write_svg_styles(writer)?;
+        writeln!(writer, r#"<g class="borders" style="fill:none;stroke:#111827;stroke-width:1.2;stroke-linejoin:round">"#)?;
+        for ((a, b), lines) in self.district_border_lines()? {
+            write!(writer, r#"<path class="border" data-districts="{a}-{b}" d=""#)?;
+            for line in &lines.0 {
+                crate::io::svg::write_line_data(writer, &line.0, &project)?;
+            }
+            writeln!(writer, r#""/>"#)?;
+        }
+        writeln!(writer, "</g>")?;
+        self.write_symbols(writer, options, &vp)?;
+        crate::io::svg::write_annotations(writer, &vp, &annotations, &[], &[])?;
+        crate::io::svg::write_svg_footer(writer)
+    }
+
+    /// Dissolved boundary rings of each district 1..=num_districts.
+    fn dissolved_rings(&self) -> Result<Vec<DistrictRings>> {
+        // Build a set of undirected segments for the *outer* state boundary (all exteriors).
+        let state_outline = {
+            let outline = self.map().region()?.union();
+
+            let mut ptmap: HashMap<crate::io::svg::QuantizedPoint, Coord<f64>> = HashMap::new();
+            let mut set = crate::io::svg::SegmentSet::default();
+
+            for polygon in outline {
+                crate::io::svg::collect_ring_segments(polygon.exterior(), &mut set, &mut ptmap);
+            }
+
+            set
+        };
+
+        (1..=self.num_districts()).map(|part| self.district_rings(part, &state_outline)).collect()
+    }
+
+    /// Build dissolved boundary rings for district `d` using frontier blocks, immediate same-district
+    /// neighbors, and segments on the state outer boundary.
+    fn district_rings(&self, d: u32, state_outline: &SegmentSet) -> Result<DistrictRings> {
         let base_region = self.map().base()?.region();
-        let shapes: Vec<geo::MultiPolygon<f64>> = base_region.unit_ids()
-            .map(|u| base_region.geometry(u).clone())
-            .collect();
+        let shape = |i: usize| base_region.geometry(UnitId(i as u32));
 
         let base_adj = base_region.adjacency();
 
@@ -96,7 +345,7 @@ impl Plan {
             // Iterate all blocks in district d; for each, intersect its segments with the state outline
             // (This is linear in vertices of blocks in d; if you want faster, filter via an R-tree.)
             let mut ptmap: HashMap<crate::io::svg::QuantizedPoint, Coord<f64>> = HashMap::new();
-            for (i, _poly) in shapes.iter().enumerate() {
+            for i in 0..base_region.num_units() {
                 if self.partition.assignment(i) != d {
                     continue;
                 }
@@ -104,7 +353,7 @@ impl Plan {
                 if include.contains(&i) {
                     continue;
                 }
-                let si = crate::io::svg::collect_segments(&shapes[i], &mut ptmap);
+                let si = crate::io::svg::collect_segments(&shape(i), &mut ptmap);
                 if !si.0.is_disjoint(&state_outline.0) {
                     include.insert(i);
                 }
@@ -119,7 +368,7 @@ impl Plan {
         let mut ptmap: HashMap<crate::io::svg::QuantizedPoint, Coord<f64>> = HashMap::new();
         let mut segs_cache: HashMap<usize, crate::io::svg::SegmentSet> = HashMap::new();
         for &i in &include {
-            let set = crate::io::svg::collect_segments(&shapes[i], &mut ptmap);
+            let set = crate::io::svg::collect_segments(&shape(i), &mut ptmap);
             segs_cache.insert(i, set);
         }
 
@@ -133,11 +382,11 @@ impl Plan {
             let sa = segs_cache
                 .get(&ia)
                 .cloned()
-                .unwrap_or_else(|| crate::io::svg::collect_segments(&shapes[ia], &mut ptmap));
+                .unwrap_or_else(|| crate::io::svg::collect_segments(&shape(ia), &mut ptmap));
             let sb = segs_cache
                 .get(&ib)
                 .cloned()
-                .unwrap_or_else(|| crate::io::svg::collect_segments(&shapes[ib], &mut ptmap));
+                .unwrap_or_else(|| crate::io::svg::collect_segments(&shape(ib), &mut ptmap));
 
             for seg in sa.0.intersection(&sb.0) {
                 boundary.0.insert(*seg);
@@ -169,12 +418,31 @@ impl Plan {
             return Ok(None);
         }
 
-        // 4) Stitch boundary segments into rings and emit a single path (multiple 'M…Z')
-        let rings = crate::io::svg::polygonize_rings(&boundary, &ptmap)?;
-        let mut path = String::new();
+        // 4) Stitch boundary segments into rings (written as a single path, multiple 'M…Z')
+        Ok(Some(crate::io::svg::polygonize_rings(&boundary, &ptmap)?))
+    }
+}
+
+/// Fill drawn for each district: its color in `colors`, or the hatch pattern over it if `options`
+/// ask for patterns.
+fn shown_fills(colors: &[String], options: &SvgOptions) -> Vec<String> {
+    if options.accessibility.patterns { crate::io::svg::pattern_fills(colors) } else { colors.to_vec() }
+}
+
+/// Draw each district as a single dissolved path (holes supported via even-odd fill), labelled
+/// e.g. "{district} 3" for screen readers.
+fn write_districts(writer: &mut impl Write, outlines: &[DistrictRings], fills: &[String], vp: &Viewport, district: &str) -> Result<()> {
+    let vp = *vp;
+    let project = move |coord: &Coord<f64>| vp.project(coord);
+    for (i, rings) in outlines.iter().enumerate() {
+        let Some(rings) = rings else { continue };
+        let fill = &fills[i + 1];
+        let label = crate::io::svg::aria_label("img", &format!("{district} {}", i + 1));
+        write!(writer, r#"<path class="dist" fill-rule="evenodd" style="fill:{fill};stroke:#111827;stroke-width:0.6;fill-opacity:0.85"{label} d=""#)?;
         for ring in rings {
-            crate::io::svg::ring_to_path(&ring, project, &mut path);
+            crate::io::svg::write_ring_data(writer, ring, &project)?;
         }
-        Ok(Some(path))
+        writeln!(writer, r#""/>"#)?;
     }
+    Ok(())
 }
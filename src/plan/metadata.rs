@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+/// Descriptive and provenance information attached to a plan.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlanMetadata {
+    pub name: Option<String>,
+    pub author: Option<String>,
+    pub description: Option<String>,
+    pub source: Option<String>,       // File the plan was loaded from
+    pub parent: Option<String>,       // Plan this one was derived from
+    pub created: Option<u64>,         // Creation time (seconds since the Unix epoch)
+    pub tool_version: Option<String>, // Version of this library that created the plan
+}
+
+impl PlanMetadata {
+    /// Metadata for a newly created plan, stamped with the current time and library version.
+    pub fn new() -> Self {
+        Self {
+            created: now_unix(),
+            tool_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            ..Self::default()
+        }
+    }
+
+    /// Metadata for a plan derived from this one: descriptive fields are kept,
+    /// `parent` points at this plan's name, and creation is re-stamped.
+    pub fn derived(&self) -> Self {
+        Self {
+            author: self.author.clone(),
+            description: self.description.clone(),
+            parent: self.name.clone().or_else(|| self.source.clone()),
+            ..Self::new()
+        }
+    }
+}
+
+/// Current time in seconds since the Unix epoch, if the platform has a clock.
+pub(crate) fn now_unix() -> Option<u64> {
+    #[cfg(not(target_arch = "wasm32"))]
+    { std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).ok().map(|d| d.as_secs()) }
+    #[cfg(target_arch = "wasm32")]
+    { None }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derived_metadata_points_at_the_parent() {
+        let loaded = PlanMetadata { author: Some("a".into()), source: Some("enacted.csv".into()), ..PlanMetadata::new() };
+        let child = loaded.derived();
+        assert_eq!((child.author.as_deref(), child.parent.as_deref(), child.source), (Some("a"), Some("enacted.csv"), None));
+
+        let named = PlanMetadata { name: Some("enacted".into()), ..loaded };
+        assert_eq!(named.derived().parent.as_deref(), Some("enacted"));
+    }
+}
@@ -1,4 +1,43 @@
+mod audit;
+mod clusters;
+mod codec;
+mod config;
+mod constraints;
+mod crosswalk;
+mod elections;
+mod explain;
+mod ensemble;
+mod hierarchical;
+mod history;
+mod hybrid;
 mod io;
+mod metadata;
+mod outlines;
+mod partisan;
 mod plan;
+mod plan_ensemble;
+mod profiles;
+mod recom;
+mod report;
+mod suggest;
+mod sweep;
+mod wards;
+mod warm;
 
+pub use audit::{AuditEntry, AuditLog, EditSource};
+pub use clusters::CountyCluster;
+pub use codec::AssignmentCodec;
+pub use config::{AnnealConfig, RelaxedAnnealConfig};
+pub use elections::{Contest, DistrictResult, ElectionResults, Swing};
+pub use ensemble::{BoxplotRank, EnsembleBoxplot};
+pub use explain::{Contribution, ScoreExplanation};
+pub use history::DEFAULT_HISTORY_CAPACITY;
+pub use io::{ExportStamp, PlanData};
+pub use metadata::PlanMetadata;
+pub(crate) use metadata::now_unix;
+pub use partisan::PartisanTests;
 pub use plan::Plan;
+pub use plan_ensemble::{EnsembleConfig, PlanEnsemble};
+pub use suggest::SuggestedMove;
+pub use sweep::SweepConfig;
+pub use wards::{ExportPreset, WardRules};
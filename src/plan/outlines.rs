@@ -0,0 +1,69 @@
+use anyhow::Result;
+use geo::MultiPolygon;
+use geograph::{LabelOutlines, UnitId};
+
+use crate::{io::wkb::multipolygon_to_wkb, plan::Plan};
+
+/// District boundaries kept in step with the plan's assignments, for redrawing after each edit.
+#[derive(Clone, Debug)]
+pub(super) struct LiveOutlines {
+    outlines: LabelOutlines,
+    assignments: Vec<u32>, // assignments the outlines were last brought up to date with
+}
+
+impl LiveOutlines {
+    /// Approximate heap memory of the tracked boundaries and assignments, in bytes.
+    pub(super) fn heap_bytes(&self) -> usize {
+        self.outlines.heap_bytes() + self.assignments.capacity() * std::mem::size_of::<u32>()
+    }
+}
+
+impl Plan {
+    /// Outlines of the districts that changed since the last call, as `(district, outline)`.
+    ///
+    /// The first call dissolves every district; after that only the boundary edges of moved
+    /// blocks are updated, and only districts that gained or lost blocks are traced again.
+    /// Districts that became empty are returned with an empty outline.
+    pub fn changed_district_outlines(&mut self) -> Result<Vec<(u32, MultiPolygon<f64>)>> {
+        let num_districts = self.num_districts();
+        let base = self.map.base()?;
+        base.require_geometry("[Plan.changed_district_outlines]")?;
+        let region = base.region();
+        let assignments = self.partition.assignments();
+        let label = |u: UnitId| assignments[u.0 as usize];
+
+        let live = match self.outlines.as_mut() {
+            Some(live) => live,
+            None => {
+                let outlines = region.label_outlines(num_districts as usize + 1, label);
+                let live = self.outlines.insert(LiveOutlines { outlines, assignments: assignments.clone() });
+                return Ok((1..=num_districts)
+                    .map(|district| (district, region.label_outline(&live.outlines, district)))
+                    .collect())
+            }
+        };
+
+        let mut dirty = vec![false; num_districts as usize + 1];
+        for (unit, (&before, &after)) in live.assignments.iter().zip(&assignments).enumerate() {
+            if before == after { continue }
+            region.relabel_unit(&mut live.outlines, UnitId(unit as u32), before, label);
+            dirty[before as usize] = true;
+            dirty[after as usize] = true;
+        }
+        live.assignments = assignments;
+        Ok((1..=num_districts)
+            .filter(|&district| dirty[district as usize])
+            .map(|district| (district, region.label_outline(&live.outlines, district)))
+            .collect())
+    }
+
+    /// Outlines of the districts that changed since the last call, as `(district, wkb)`.
+    pub fn changed_district_outlines_wkb(&mut self) -> Result<Vec<(u32, Vec<u8>)>> {
+        self.changed_district_outlines()?.into_iter()
+            .map(|(district, outline)| Ok((district, multipolygon_to_wkb(&outline)?)))
+            .collect()
+    }
+
+    /// Stop tracking district outlines; the next [`Plan::changed_district_outlines`] returns all of them.
+    pub fn reset_district_outlines(&mut self) { self.outlines = None }
+}
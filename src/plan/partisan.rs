@@ -0,0 +1,51 @@
+use anyhow::{Result, ensure};
+use rand::{SeedableRng, rngs::StdRng};
+use serde::{Deserialize, Serialize};
+
+use crate::plan::Plan;
+
+/// Partisan fairness tests with their significance, for reporting rather than optimization.
+/// Positive statistics favor Democrats, matching the efficiency gap.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PartisanTests {
+    pub mean_median: f64,                   // Median minus mean of the districts' Democratic vote shares
+    pub mean_median_ci: (f64, f64),         // 95% bootstrap confidence interval of the mean-median difference
+    pub mean_median_p_value: f64,           // Two-sided bootstrap p-value against a difference of zero
+    pub lopsided_wins: Option<f64>,         // Welch t statistic (None if a party wins fewer than two districts)
+    pub lopsided_wins_p_value: Option<f64>, // Two-sided p-value of the t statistic
+}
+
+impl Plan {
+    /// Run the mean-median and lopsided-wins tests on the `dem_series` and `rep_series` votes.
+    ///
+    /// The mean-median difference is bootstrapped over `samples` replicates, each resampling the
+    /// blocks of every district with replacement; the confidence interval is the 2.5th to 97.5th
+    /// percentile of the replicates. The lopsided-wins test compares the winning party's vote
+    /// share in Republican and Democratic wins with Welch's t-test. With a `seed`, the bootstrap
+    /// is reproducible.
+    pub fn partisan_tests(&self, dem_series: &str, rep_series: &str, samples: usize, seed: Option<u64>) -> Result<PartisanTests> {
+        ensure!(self.series().contains(dem_series), "[Plan.partisan_tests] Unknown series '{dem_series}'");
+        ensure!(self.series().contains(rep_series), "[Plan.partisan_tests] Unknown series '{rep_series}'");
+        ensure!(samples > 0, "[Plan.partisan_tests] Need at least one bootstrap sample");
+
+        let mut rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_rng(&mut rand::rng()),
+        };
+        let mut replicates = self.partition.bootstrap_mean_median(dem_series, rep_series, samples, &mut rng);
+        replicates.sort_by(f64::total_cmp);
+        let percentile = |q: f64| replicates[((q * (samples - 1) as f64).round() as usize).min(samples - 1)];
+
+        let below = replicates.iter().filter(|&&value| value <= 0.0).count() as f64 / samples as f64;
+        let above = replicates.iter().filter(|&&value| value >= 0.0).count() as f64 / samples as f64;
+        let lopsided = self.partition.lopsided_wins(dem_series, rep_series);
+
+        Ok(PartisanTests {
+            mean_median: self.partition.mean_median(dem_series, rep_series),
+            mean_median_ci: (percentile(0.025), percentile(0.975)),
+            mean_median_p_value: (2.0 * below.min(above)).min(1.0),
+            lopsided_wins: lopsided.map(|(t, _)| t),
+            lopsided_wins_p_value: lopsided.map(|(_, p)| p),
+        })
+    }
+}
@@ -1,23 +1,45 @@
 use std::{collections::{HashMap, HashSet}, sync::Arc};
 
-use anyhow::{Result};
+use anyhow::{Context, Result};
+use polars::prelude::DataType;
 
 use crate::{
-    Metric, Objective,
+    Metric, Objective, RelaxedAnnealConfig, RelaxedConstraint,
     io::wkb::multipolygon_to_wkb,
-    map::{GeoId, GeoType, Map},
-    partition::Partition,
+    map::{GeoId, GeoType, Map, SharedBuffers},
+    partition::{HullKind, HullSet, KernelMix, MoveConstraints, Partition, RunProfile, StoppingRules},
+    plan::{AuditLog, EditSource, PlanMetadata, history::History, outlines::LiveOutlines},
 };
+use geo::{Area, MultiLineString, MultiPolygon};
 use geograph::UnitId;
 
+/// Lines where each pair of districts `(a, b)`, with `a < b`, meet.
+type BorderLines = Vec<((u32, u32), MultiLineString<f64>)>;
+
 /// A districting plan, assigning blocks to districts.
+///
+/// The map is shared, so cloning a plan only copies its assignment state.
 #[derive(Clone, Debug)]
 pub struct Plan {
-    map: Arc<Map>,
+    pub(super) map: Arc<Map>,
     num_districts: u32, // number of districts (excluding unassigned 0)
+    pub(super) at_large_seats: u32, // seats elected at large, besides the districts
     pub(super) partition: Partition,
+    metadata: PlanMetadata,
+    pub(super) audit: Option<AuditLog>,
+    pub(super) history: Option<History>,
+    pub(super) profile: Option<RunProfile>, // Stage timings of the last profiled annealing run
+    pub(super) outlines: Option<LiveOutlines>, // District boundaries tracked for live redrawing
+    pub(super) stamp_exports: bool, // Whether exports carry an ExportStamp
 }
 
+// Maps and plans are shared or sent across worker threads.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Map>();
+    assert_send_sync::<Plan>();
+};
+
 impl Plan {
     /// Create a new empty plan with a set number of districts.
     pub fn new(map: impl Into<Arc<Map>>, num_districts: u32) -> Result<Self> {
@@ -26,34 +48,64 @@ impl Plan {
         let unit_graph = base.get_unit_graph();
         let unit_weights = base.get_unit_weights();
         let region_weights = map.region()?.get_unit_weights();
-        let partition = Partition::new(
+        let mut partition = Partition::new(
             num_districts as usize + 1,
             unit_graph,
             unit_weights,
             region_weights,
         );
+        if base.has_geometry() { partition.set_unit_hulls(base.shared_region()) }
 
-        Ok(Self { map, num_districts, partition })
+        Ok(Self { map, num_districts, at_large_seats: 0, partition, metadata: PlanMetadata::new(), audit: None, history: None, profile: None, outlines: None, stamp_exports: false })
     }
 
     /// Get an immutable reference to the map.
     #[inline] pub(super) fn map(&self) -> &Map { &self.map }
 
+    /// Get the plan's descriptive and provenance metadata.
+    #[inline] pub fn metadata(&self) -> &PlanMetadata { &self.metadata }
+
+    /// Get a mutable reference to the plan's metadata.
+    #[inline] pub fn metadata_mut(&mut self) -> &mut PlanMetadata { &mut self.metadata }
+
+    /// Replace the plan's metadata.
+    #[inline] pub fn set_metadata(&mut self, metadata: PlanMetadata) { self.metadata = metadata }
+
+    /// Copy this plan to edit separately, with metadata naming this plan as its parent (see [`PlanMetadata::derived`]).
+    pub fn fork(&self) -> Self {
+        Self { metadata: self.metadata.derived(), ..self.clone() }
+    }
+
     /// Get the number of districts in this plan (excluding unassigned 0).
     #[inline] pub fn num_districts(&self) -> u32 { self.num_districts }
 
+    /// Estimated heap memory of the plan's own state (assignments, district totals and borders,
+    /// constraints), in bytes. The map is shared between plans and counted by [`Map::memory_footprint`].
+    pub fn memory_footprint(&self) -> usize {
+        let mut shared = SharedBuffers::default();
+        self.map.memory_footprint_shared(&mut shared);
+        self.memory_footprint_shared(&mut shared)
+    }
+
+    /// Estimated heap memory of the plan, in bytes, leaving out buffers already in `shared`, such
+    /// as the map's or constraints shared with copies of this plan.
+    pub fn memory_footprint_shared(&self, shared: &mut SharedBuffers) -> usize {
+        self.partition.heap_bytes(shared) + self.outlines.as_ref().map_or(0, LiveOutlines::heap_bytes)
+    }
+
     /// Get the list of weight series available in the map's node weights.
     #[inline] pub fn series(&self) -> HashSet<String> { self.partition.series() }
 
     /// Set the block assignments for the plan.
     #[inline]
     pub fn set_assignments(&mut self, assignments: HashMap<GeoId, u32>) -> Result<()> {
-        self.partition.set_assignments(
-            self.map.base()?.geo_ids().iter()
-                .map(|geo_id| assignments.get(geo_id).copied().unwrap_or(0))
-                .collect()
-        );
-        Ok(())
+        let assignments = self.map.base()?.geo_ids().iter()
+            .map(|geo_id| assignments.get(geo_id).copied().unwrap_or(0))
+            .collect();
+        self.tracked("set_assignments", EditSource::Manual, |plan| {
+            plan.partition.set_assignments(assignments);
+            Ok(())
+        })
     }
 
     /// Get raw block assignments as a flat `Vec<u32>` (index-aligned with units).
@@ -63,8 +115,55 @@ impl Plan {
 
     /// Set assignments directly from a flat `Vec<u32>` (index-aligned with units).
     pub fn set_assignments_vec(&mut self, assignments: Vec<u32>) -> Result<()> {
-        self.partition.set_assignments(assignments);
-        Ok(())
+        self.set_assignment_bulk(&assignments, false).map(|_| ())
+    }
+
+    /// Set every block's district from a flat array (index-aligned with units, 0 for unassigned),
+    /// checking its length and district range, and optionally that every changed district is
+    /// contiguous (the plan is left unchanged otherwise).
+    ///
+    /// Only blocks whose district changed are moved, unless many did, so small edits are cheap.
+    /// Returns the districts whose blocks changed (including 0 if any block was or became
+    /// unassigned), so metrics cached per district only need recomputing for those.
+    pub fn set_assignment_bulk(&mut self, assignments: &[u32], check_contiguity: bool) -> Result<Vec<u32>> {
+        anyhow::ensure!(assignments.len() == self.partition.num_nodes(),
+            "[Plan.set_assignment_bulk] Expected {} assignments, got {}", self.partition.num_nodes(), assignments.len());
+        if let Some((block, &district)) = assignments.iter().enumerate().find(|&(_, &district)| district > self.num_districts) {
+            anyhow::bail!("[Plan.set_assignment_bulk] District {district} of block {block} is out of range [0, {}]", self.num_districts)
+        }
+
+        let previous = check_contiguity.then(|| self.partition.assignments());
+        self.tracked("set_assignments", EditSource::Manual, |plan| {
+            let dirty = plan.partition.update_assignments(assignments);
+            if let Some(previous) = previous && let Some(district) = plan.partition.first_discontiguous(&dirty) {
+                plan.partition.update_assignments(&previous);
+                anyhow::bail!("[Plan.set_assignment_bulk] District {district} would not be contiguous")
+            }
+            Ok(dirty)
+        })
+    }
+
+    /// Create a plan from a block-level column of district numbers stored in the map
+    /// (e.g. a historical plan column such as "cd114"). The district count is the largest value.
+    pub fn from_assignment_column(map: impl Into<Arc<Map>>, column: &str) -> Result<Self> {
+        let map: Arc<Map> = map.into();
+        let values = map.base()?.data().column(column)
+            .with_context(|| format!("[Plan] Missing assignment column '{}'", column))?
+            .cast(&DataType::Float64)?;
+        let assignments = values.f64()?.into_iter()
+            .map(|value| match value {
+                Some(v) if v >= 0.0 && v.fract() == 0.0 => Ok(v as u32),
+                None => Ok(0),
+                Some(v) => Err(anyhow::anyhow!("[Plan] Invalid district {} in column '{}'", v, column)),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let num_districts = assignments.iter().copied().max().unwrap_or(0);
+        anyhow::ensure!(num_districts > 0, "[Plan] Column '{}' assigns no blocks to a district", column);
+
+        let mut plan = Self::new(map, num_districts)?;
+        plan.set_assignments_vec(assignments)?;
+        plan.metadata.source = Some(format!("column:{column}"));
+        Ok(plan)
     }
 
     /// Get block assignments as `Vec<(GeoId, u32)>`.
@@ -106,24 +205,57 @@ impl Plan {
 
     /// Randomize partition into contiguous districts.
     pub fn randomize(&mut self) -> Result<()> {
-        self.partition.randomize();
-        Ok(())
+        self.tracked("randomize", EditSource::Chain, |plan| {
+            plan.partition.randomize();
+            Ok(())
+        })
     }
 
     /// Run one outer iteration of equalization. Returns `true` if all districts are within tolerance.
     pub fn equalize_step(&mut self, series: &str, tolerance: f64) -> Result<bool> {
-        Ok(self.partition.equalize_step(series, tolerance))
+        self.tracked("equalize_step", EditSource::Chain, |plan| Ok(plan.partition.equalize_step(series, tolerance)))
     }
 
     /// Equalize a weight series across districts using greedy swaps.
     pub fn equalize(&mut self, series: &str, tolerance: f64, max_iter: usize) -> Result<()> {
-        self.partition.equalize(series, tolerance, max_iter);
-        Ok(())
+        self.tracked("equalize", EditSource::Chain, |plan| {
+            plan.partition.equalize(series, tolerance, max_iter);
+            Ok(())
+        })
+    }
+
+    /// Deterministically repair balance of a weight series after manual edits or layer translation,
+    /// routing frontier units along chains of adjacent districts from over- to under-populated ones.
+    /// Returns `true` if every district ends within `tolerance` (a fraction) of the ideal total.
+    pub fn rebalance(&mut self, series: &str, tolerance: f64) -> Result<bool> {
+        anyhow::ensure!(self.num_districts > 1, "[Plan.rebalance] Need at least two districts");
+        anyhow::ensure!(self.series().contains(series), "[Plan.rebalance] Unknown series '{series}'");
+        anyhow::ensure!(tolerance >= 0.0, "[Plan.rebalance] Tolerance must be non-negative");
+        let max_rounds = 100 * self.num_districts as usize;
+        self.tracked("rebalance", EditSource::Chain, |plan| Ok(plan.partition.rebalance(series, tolerance, max_rounds)))
+    }
+
+    /// Re-split districts `a` and `b` exactly, giving them the shortest shared boundary with both
+    /// `series` totals within `tolerance` of the plan-wide ideal. Only for small pairs: returns `false`,
+    /// leaving the plan unchanged, if the districts are not adjacent, too large, or cannot be balanced.
+    pub fn refine_pair_exact(&mut self, a: u32, b: u32, series: &str, tolerance: f64) -> Result<bool> {
+        let districts = 1..=self.num_districts;
+        anyhow::ensure!(a != b && districts.contains(&a) && districts.contains(&b),
+            "[Plan.refine_pair_exact] Districts must be two distinct values in 1..={}", self.num_districts);
+        anyhow::ensure!(self.series().contains(series), "[Plan.refine_pair_exact] Unknown series '{series}'");
+        anyhow::ensure!(tolerance >= 0.0, "[Plan.refine_pair_exact] Tolerance must be non-negative");
+        let totals = self.partition.part_totals(series);
+        let ideal = totals[1..].iter().sum::<f64>() / self.num_districts as f64;
+        let bounds = (ideal * (1.0 - tolerance), ideal * (1.0 + tolerance));
+        self.tracked("refine_pair_exact", EditSource::Chain, |plan| Ok(plan.partition.refine_pair_exact(a, b, series, bounds)))
     }
 
     pub fn anneal_balance(&mut self, series: &str, max_iter: usize, initial_temp: f64, final_temp: f64, boundary_factor: f64) -> Result<()> {
-        self.partition.anneal_balance(series, max_iter, initial_temp, final_temp, boundary_factor);
-        Ok(())
+        self.check_complete("[Plan.anneal_balance]")?;
+        self.tracked("anneal_balance", EditSource::Chain, |plan| {
+            plan.partition.anneal_balance(series, max_iter, initial_temp, final_temp, boundary_factor);
+            Ok(())
+        })
     }
 
     pub fn anneal(
@@ -138,12 +270,101 @@ impl Plan {
         temp_search_batch_size: usize,
         batch_size: usize,
     ) -> Result<()> {
-        self.partition.anneal(
-            objectives, max_iter, init_temp,
-            phase_start_probs, phase_end_probs, phase_cooling_rates,
-            early_stop_iters, temp_search_batch_size, batch_size,
-        );
-        Ok(())
+        self.check_complete("[Plan.anneal]")?;
+        self.tracked("anneal", EditSource::Chain, |plan| {
+            plan.partition.anneal(
+                objectives, max_iter, init_temp,
+                phase_start_probs, phase_end_probs, phase_cooling_rates,
+                early_stop_iters, temp_search_batch_size, batch_size, &mut |_| {}, &mut (),
+                &StoppingRules::default(), &KernelMix::flip(), &MoveConstraints::default(), false,
+            );
+            Ok(())
+        })
+    }
+
+    /// Run simulated annealing from a possibly infeasible plan, with hard constraints that start
+    /// relaxed and tighten to their targets over the run (see `RelaxedConstraint`).
+    ///
+    /// Returns `true` if a plan satisfying every target bound was found; the plan is then set to
+    /// the best such plan. Otherwise the plan is left at the final state of the run.
+    #[allow(clippy::too_many_arguments)]
+    pub fn anneal_relaxed(
+        &mut self,
+        objective: &Objective,
+        constraints: &[RelaxedConstraint],
+        max_iter: usize,
+        initial_temp: f64,
+        final_temp: f64,
+        initial_penalty: f64,
+        final_penalty: f64,
+    ) -> Result<bool> {
+        self.anneal_relaxed_with_config(&RelaxedAnnealConfig {
+            objective: objective.clone(),
+            constraints: constraints.to_vec(),
+            max_iter, initial_temp, final_temp, initial_penalty, final_penalty,
+            stop: StoppingRules::default(),
+        })
+    }
+
+    /// Switch to whole-county districting: each county moves between districts as a single unit,
+    /// except counties whose `pop_series` total exceeds one district's ideal total, which are
+    /// subdivided along VTD lines (or into blocks, if the map has no VTD layer).
+    ///
+    /// Counties currently split between districts are moved whole to the district holding most of
+    /// their blocks, where that keeps districts contiguous and respects anchors. Returns the number
+    /// of atomic units, and the GEOIDs of the units (counties, VTDs or blocks) left split.
+    pub fn set_whole_county_mode(&mut self, pop_series: &str) -> Result<(usize, Vec<String>)> {
+        let map = self.map.clone();
+        let base = map.base()?;
+        let weights = base.get_unit_weights();
+        anyhow::ensure!(weights.contains(pop_series), "series '{}' not found in node weights", pop_series);
+
+        let ideal = self.partition.region_total(pop_series) / self.num_districts as f64;
+
+        let mut county_totals: HashMap<&GeoId, f64> = HashMap::new();
+        for (i, refs) in base.parents().iter().enumerate() {
+            if let Some(county) = refs.get(GeoType::County) {
+                *county_totals.entry(county).or_insert(0.0) += weights.get_as_f64(pop_series, i).unwrap();
+            }
+        }
+
+        let mut index: HashMap<&GeoId, u32> = HashMap::new();
+        let group_of: Vec<u32> = base.parents().iter().enumerate()
+            .map(|(i, refs)| {
+                let key = match refs.get(GeoType::County) {
+                    Some(county) if county_totals[county] <= ideal => Some(county),
+                    _ => refs.get(GeoType::VTD),
+                }.unwrap_or(&base.geo_ids()[i]);
+                let next = index.len() as u32;
+                *index.entry(key).or_insert(next)
+            })
+            .collect();
+
+        let mut keys = vec![""; index.len()];
+        index.iter().for_each(|(geo_id, &group)| keys[group as usize] = geo_id.id());
+
+        self.tracked("set_whole_county_mode", EditSource::Manual, |plan| {
+            let split = plan.partition.set_groups(group_of);
+            Ok((keys.len(), split.into_iter().map(|group| keys[group as usize].to_string()).collect()))
+        })
+    }
+
+    /// Leave whole-county mode, allowing individual blocks to move again.
+    pub fn clear_whole_county_mode(&mut self) { self.partition.clear_groups() }
+
+    /// Index of the parent county of each base unit (units without a county share index 0).
+    pub(super) fn county_indices(&self) -> Result<Vec<u32>> {
+        let base = self.map.base()?;
+        let mut index = HashMap::new();
+        Ok(base.parents().iter()
+            .map(|refs| match refs.get(GeoType::County) {
+                Some(county) => {
+                    let next = index.len() as u32 + 1;
+                    *index.entry(county.clone()).or_insert(next)
+                }
+                None => 0,
+            })
+            .collect())
     }
 
     pub fn tabu_balance(
@@ -154,26 +375,65 @@ impl Plan {
         boundary_factor: f64,
         candidates_per_iter: usize,
     ) -> Result<()> {
-        self.partition.tabu_balance(series, max_iter, tabu_tenure, boundary_factor, candidates_per_iter);
-        Ok(())
+        self.check_complete("[Plan.tabu_balance]")?;
+        self.tracked("tabu_balance", EditSource::Chain, |plan| {
+            plan.partition.tabu_balance(series, max_iter, tabu_tenure, boundary_factor, candidates_per_iter);
+            Ok(())
+        })
     }
 
-    pub fn recombine(&mut self, a: u32, b: u32) -> Result<()> {
-        self.partition.recombine_parts(a, b);
-        Ok(())
+    /// Merge districts `a` and `b` and split them again along a random spanning tree, balancing
+    /// the two halves by `pop_series` (any numeric column, e.g. total population or VAP).
+    pub fn recombine(&mut self, a: u32, b: u32, pop_series: &str) -> Result<()> {
+        anyhow::ensure!(self.series().contains(pop_series), "[Plan.recombine] Unknown series '{pop_series}'");
+        self.tracked("recombine", EditSource::Chain, |plan| {
+            plan.partition.recombine_parts(a, b, pop_series);
+            Ok(())
+        })
     }
 
-    /// Assign all blocks belonging to a geographic unit to a given district.
-    ///
-    /// `layer` is the geographic level ("block", "vtd", "group", "tract", "county", "state").
-    /// `geo_id` is the FIPS identifier for the unit at that level.
-    /// `district` is the target district (1-indexed; 0 = unassigned).
-    /// Contiguity is not enforced.
-    pub fn assign_unit(&mut self, layer: &str, geo_id: &str, district: u32) -> Result<()> {
-        anyhow::ensure!(
-            district <= self.num_districts,
-            "district {} out of range [0, {}]", district, self.num_districts
-        );
+    /// Recombine a pair of adjacent districts chosen uniformly at random, balancing by `pop_series`,
+    /// and return the pair.
+    pub fn recombine_random(&mut self, pop_series: &str) -> Result<(u32, u32)> {
+        use rand::seq::IndexedRandom;
+
+        let pairs = self.adjacent_district_pairs();
+        let &(a, b) = pairs.choose(&mut rand::rng())
+            .ok_or_else(|| anyhow::anyhow!("[Plan.recombine_random] No adjacent districts to recombine"))?;
+        self.recombine(a, b, pop_series)?;
+        Ok((a, b))
+    }
+
+    /// Every pair `(a, b)` of adjacent districts, with `a < b`.
+    pub(super) fn adjacent_district_pairs(&self) -> Vec<(u32, u32)> {
+        self.partition.adjacent_part_pairs()
+    }
+
+    /// Get the block indices belonging to any of several geographic units, in one pass over the blocks.
+    pub(super) fn units_nodes(&self, layer: &str, geo_ids: &[&str]) -> Result<Vec<usize>> {
+        let ty = GeoType::from_str(layer)
+            .ok_or_else(|| anyhow::anyhow!("unknown layer '{}'", layer))?;
+
+        let id_set: std::collections::HashSet<&str> = geo_ids.iter().copied().collect();
+        let base = self.map.base()?;
+
+        Ok(if ty == GeoType::Block {
+            base.geo_ids().iter()
+                .enumerate()
+                .filter(|(_, gid)| id_set.contains(gid.id()))
+                .map(|(i, _)| i)
+                .collect()
+        } else {
+            base.parents().iter()
+                .enumerate()
+                .filter(|(_, refs)| refs.get(ty).map(|id| id_set.contains(id.id())).unwrap_or(false))
+                .map(|(i, _)| i)
+                .collect()
+        })
+    }
+
+    /// Get the indices of all base units belonging to a geographic unit.
+    fn unit_nodes(&self, layer: &str, geo_id: &str) -> Result<Vec<usize>> {
         let ty = GeoType::from_str(layer)
             .ok_or_else(|| anyhow::anyhow!("unknown layer '{}'", layer))?;
 
@@ -194,14 +454,56 @@ impl Plan {
         };
 
         anyhow::ensure!(!nodes.is_empty(), "no blocks found for {} geo_id '{}'", layer, geo_id);
+        Ok(nodes)
+    }
 
-        for node in nodes {
-            self.partition.move_node(node, district, false);
+    /// Assign all blocks belonging to a geographic unit to a given district.
+    ///
+    /// `layer` is the geographic level ("block", "vtd", "group", "tract", "county", "state").
+    /// `geo_id` is the FIPS identifier for the unit at that level.
+    /// `district` is the target district (1-indexed; 0 = unassigned).
+    /// Contiguity is not enforced.
+    pub fn assign_unit(&mut self, layer: &str, geo_id: &str, district: u32) -> Result<()> {
+        anyhow::ensure!(
+            district <= self.num_districts,
+            "district {} out of range [0, {}]", district, self.num_districts
+        );
+
+        let nodes = self.unit_nodes(layer, geo_id)?;
+        self.tracked("assign_unit", EditSource::Manual, |plan| {
+            for node in nodes { plan.partition.move_node(node, district, false) }
+            Ok(())
+        })
+    }
+
+    /// Require that a district always contains a geographic unit (e.g. "district 3 must contain
+    /// Travis County"). The unit's blocks are assigned to the district immediately, and
+    /// optimization moves will never take them out of it.
+    pub fn anchor_unit(&mut self, layer: &str, geo_id: &str, district: u32) -> Result<()> {
+        anyhow::ensure!(
+            district >= 1 && district <= self.num_districts,
+            "district {} out of range [1, {}]", district, self.num_districts
+        );
+
+        let nodes = self.unit_nodes(layer, geo_id)?;
+        if let Some((&node, part)) = nodes.iter()
+            .find_map(|node| self.partition.anchor(*node).filter(|&p| p != district).map(|p| (node, p)))
+        {
+            anyhow::bail!("block {} is already anchored to district {}", self.map.base()?.geo_ids()[node].id(), part);
         }
 
-        Ok(())
+        self.tracked("anchor_unit", EditSource::Manual, |plan| {
+            plan.partition.set_anchors(&nodes, district);
+            Ok(())
+        })
     }
 
+    /// Remove all district anchors.
+    pub fn clear_anchors(&mut self) { self.partition.clear_anchors() }
+
+    /// Check that every anchored unit is in its district.
+    pub fn anchors_satisfied(&self) -> bool { self.partition.anchors_satisfied() }
+
     /// Assign all blocks belonging to multiple geographic units to a given district in one pass.
     ///
     /// Equivalent to calling `assign_unit` for each geo_id, but iterates the block table only
@@ -212,31 +514,12 @@ impl Plan {
             "district {} out of range [0, {}]", district, self.num_districts
         );
         if geo_ids.is_empty() { return Ok(()); }
-        let ty = GeoType::from_str(layer)
-            .ok_or_else(|| anyhow::anyhow!("unknown layer '{}'", layer))?;
-
-        let id_set: std::collections::HashSet<&str> = geo_ids.iter().copied().collect();
-        let base = self.map.base()?;
+        let nodes = self.units_nodes(layer, geo_ids)?;
 
-        let nodes: Vec<usize> = if ty == GeoType::Block {
-            base.geo_ids().iter()
-                .enumerate()
-                .filter(|(_, gid)| id_set.contains(gid.id()))
-                .map(|(i, _)| i)
-                .collect()
-        } else {
-            base.parents().iter()
-                .enumerate()
-                .filter(|(_, refs)| refs.get(ty).map(|id| id_set.contains(id.id())).unwrap_or(false))
-                .map(|(i, _)| i)
-                .collect()
-        };
-
-        for node in nodes {
-            self.partition.move_node(node, district, false);
-        }
-
-        Ok(())
+        self.tracked("assign_units_batch", EditSource::Manual, |plan| {
+            for node in nodes { plan.partition.move_node(node, district, false) }
+            Ok(())
+        })
     }
 
     /// Extract district boundaries as WKB using the DCEL Region.
@@ -245,23 +528,124 @@ impl Plan {
     /// to produce the exact merged polygon via DCEL boundary tracing — no stitching
     /// or coordinate matching required.
     pub fn district_geometries_wkb(&self) -> Result<Vec<(u32, Vec<u8>)>> {
+        self.district_outlines_wkb(HullKind::Dissolve)
+    }
+
+    /// Extract district outlines as WKB, either exact or as a convex/concave hull.
+    pub fn district_outlines_wkb(&self, kind: HullKind) -> Result<Vec<(u32, Vec<u8>)>> {
+        self.district_outlines(kind)?.into_iter()
+            .map(|(district, outline)| Ok((district, multipolygon_to_wkb(&outline)?)))
+            .collect()
+    }
+
+    /// Get the outline of each district, either exact or as a convex/concave hull.
+    pub fn district_outlines(&self, kind: HullKind) -> Result<Vec<(u32, MultiPolygon<f64>)>> {
+        Ok(HullSet::new(kind, self.district_boundaries()?).into_hulls().into_iter()
+            .enumerate()
+            .skip(1)
+            .map(|(district, outline)| (district as u32, outline))
+            .collect())
+    }
+
+    /// Lines where two districts meet, as `((a, b), lines)` with `a < b`, without the state outline.
+    /// Part 0 is unassigned. Much lighter than district polygons for drawing over a basemap.
+    pub fn district_border_lines(&self) -> Result<BorderLines> {
         let base = self.map.base()?;
+        base.require_geometry("[Plan.district_border_lines]")?;
         let region = base.region();
+        let frontier = (1..=self.num_districts)
+            .flat_map(|district| self.partition.frontier(district))
+            .map(|&i| UnitId(i as u32));
+        Ok(region.label_boundaries(frontier, |u| self.partition.assignment(u.0 as usize)))
+    }
 
-        let mut results = Vec::with_capacity(self.num_districts as usize);
+    /// Compute the ratio of each district's area to the area of its outline (0 to 1), for hull-based
+    /// compactness. Index 0 holds the unassigned part. Returns 0 for a district with no area.
+    pub fn hull_compactness(&self, kind: HullKind) -> Result<Vec<f64>> {
+        let boundaries = self.district_boundaries()?;
+        let areas = boundaries.iter().map(|boundary| boundary.unsigned_area()).collect::<Vec<_>>();
+        let hulls = HullSet::new(kind, boundaries);
+        Ok(std::iter::once(0.0)
+            .chain(areas.into_iter().enumerate().map(|(i, area)| {
+                let hull = hulls.get(i as u32 + 1).unsigned_area();
+                if hull == 0.0 { 0.0 } else { area / hull }
+            }))
+            .collect())
+    }
 
-        for district in 1..=self.num_districts {
-            let frontier = self.partition.frontier(district)
-                .iter()
-                .map(|&i| UnitId(i as u32));
-            let boundary = region.union_of_frontier(
-                frontier,
+    /// Exact dissolved boundary of each district (1..=num_districts), traced from its frontier units.
+    fn district_boundaries(&self) -> Result<Vec<MultiPolygon<f64>>> {
+        let base = self.map.base()?;
+        base.require_geometry("[Plan.district_outlines]")?;
+        let region = base.region();
+        Ok((1..=self.num_districts)
+            .map(|district| region.union_of_frontier(
+                self.partition.frontier(district).iter().map(|&i| UnitId(i as u32)),
                 |u| self.partition.assignment(u.0 as usize) == district,
-            );
-            let wkb = multipolygon_to_wkb(&boundary)?;
-            results.push((district, wkb));
-        }
+            ))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_maps;
+
+    #[test]
+    fn footprints_leave_out_the_map_and_shared_constraints() {
+        let mut plan = Plan::new(test_maps::row(4, &[]), 2).unwrap();
+        plan.set_assignments_vec(vec![1, 1, 2, 2]).unwrap();
+        plan.anchor_unit("block", "000000000000000", 1).unwrap();
+        assert!(plan.memory_footprint() > 0);
+
+        let mut shared = SharedBuffers::default();
+        assert!(plan.memory_footprint_shared(&mut shared) > plan.memory_footprint());
+        let mut shared = SharedBuffers::default();
+        plan.map.memory_footprint_shared(&mut shared);
+        assert_eq!(plan.memory_footprint_shared(&mut shared), plan.memory_footprint());
+        // A copy shares the anchors, and the map, with the original.
+        assert!(plan.clone().memory_footprint_shared(&mut shared) < plan.memory_footprint());
+    }
+
+    #[test]
+    fn bulk_assignments_are_checked() {
+        let mut plan = Plan::new(test_maps::row(4, &[]), 2).unwrap();
+        let err = plan.set_assignment_bulk(&[1, 1, 2], false).unwrap_err();
+        assert_eq!(err.to_string(), "[Plan.set_assignment_bulk] Expected 4 assignments, got 3");
+        let err = plan.set_assignment_bulk(&[1, 1, 3, 2], false).unwrap_err();
+        assert_eq!(err.to_string(), "[Plan.set_assignment_bulk] District 3 of block 2 is out of range [0, 2]");
+        assert_eq!(plan.get_assignments_vec().unwrap(), [0; 4]);
+    }
+
+    #[test]
+    fn bulk_assignments_report_changed_districts() {
+        let mut plan = Plan::new(test_maps::row(4, &[]), 3).unwrap();
+        assert_eq!(plan.set_assignment_bulk(&[1, 1, 2, 2], true).unwrap(), [0, 1, 2]);
+        assert_eq!(plan.set_assignment_bulk(&[1, 2, 2, 2], true).unwrap(), [1, 2]);
+        assert_eq!(plan.set_assignment_bulk(&[1, 2, 2, 2], true).unwrap(), Vec::<u32>::new());
+
+        // District 1 would be split around district 2, so nothing moves.
+        let err = plan.set_assignment_bulk(&[1, 2, 1, 3], true).unwrap_err();
+        assert_eq!(err.to_string(), "[Plan.set_assignment_bulk] District 1 would not be contiguous");
+        assert_eq!(plan.get_assignments_vec().unwrap(), [1, 2, 2, 2]);
+        assert_eq!(plan.set_assignment_bulk(&[1, 2, 1, 3], false).unwrap(), [1, 2, 3]);
+    }
 
-        Ok(results)
+    #[test]
+    fn bulk_assignments_are_one_history_entry() {
+        let mut plan = Plan::new(test_maps::row(4, &[]), 2).unwrap();
+        plan.enable_history(100);
+        plan.set_assignment_bulk(&[1, 1, 2, 2], false).unwrap();
+        plan.set_assignment_bulk(&[1, 2, 2, 2], false).unwrap();
+        assert!(plan.set_assignment_bulk(&[1, 2, 1, 2], true).is_err());
+
+        assert!(plan.undo().unwrap());
+        assert_eq!(plan.get_assignments_vec().unwrap(), [1, 1, 2, 2]);
+        assert!(plan.undo().unwrap());
+        assert_eq!(plan.get_assignments_vec().unwrap(), [0; 4]);
+        assert!(!plan.can_undo());
+        assert!(plan.redo().unwrap());
+        assert_eq!(plan.get_assignments_vec().unwrap(), [1, 1, 2, 2]);
     }
 }
@@ -0,0 +1,259 @@
+use std::{collections::BTreeMap, path::Path, sync::Arc};
+
+use anyhow::{Context, Result, ensure};
+use polars::{frame::DataFrame, prelude::NamedFrom, series::Series};
+use serde::{Deserialize, Serialize};
+
+use crate::{Metric, map::{ColumnStats, quantile}, plan::{AssignmentCodec, Plan, recom::seeded_rng}};
+
+/// How a [`PlanEnsemble`] is sampled: `chains` independent ReCom chains (see [`Plan::recom`]),
+/// each started from the same plan and run for `steps` steps, keeping a plan every
+/// `sample_every` steps. Use many short chains with `sample_every == steps` for independent
+/// draws, or one long chain with a smaller `sample_every` for a thinned chain.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EnsembleConfig {
+    pub pop_series: String,
+    pub tolerance: f64,         // Relative population tolerance of each ReCom split, e.g. 0.02
+    pub chains: usize,
+    pub steps: usize,           // ReCom steps per chain
+    pub sample_every: usize,    // Thinning: steps between kept plans
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,      // Chain `i` uses seed `seed + i`
+}
+
+/// A collection of sampled plans and each plan's metric scores, for comparing a plan against
+/// the distribution of plans a neutral process draws (outlier analysis).
+///
+/// Plans are kept compact-encoded (see [`AssignmentCodec`]), a few KB each, and rebuilt on demand
+/// by [`PlanEnsemble::plan`], without their metadata, edit history or audit log.
+#[derive(Clone)]
+pub struct PlanEnsemble {
+    template: Option<Arc<(Plan, AssignmentCodec)>>, // Plan that samples are decoded into, and its codec
+    assignments: Vec<Vec<u8>>, // Per plan, compact-encoded assignments
+    samples: Vec<(u32, u32)>,  // (chain, step) each plan was kept at
+    metrics: Vec<String>,      // Metric names, in column order
+    scores: Vec<Vec<f64>>,     // Per plan, the score of each metric
+}
+
+impl PlanEnsemble {
+    /// Sample an ensemble from `start` following `config`, scoring each plan with the named
+    /// `metrics`. Chains run in parallel on at most one thread per core; with a seed, the same
+    /// seed gives the same ensemble.
+    pub fn sample(start: &Plan, config: &EnsembleConfig, metrics: &[(String, Metric)]) -> Result<Self> {
+        ensure!(config.chains > 0, "[PlanEnsemble.sample] chains must be positive");
+        ensure!(config.sample_every > 0 && config.steps >= config.sample_every,
+            "[PlanEnsemble.sample] sample_every must be positive and at most steps");
+
+        let mut ensemble = Self::empty(metrics)?;
+        let template = ensemble.set_template(start)?;
+        let (start, codec) = &*template;
+        let bounds = start.recom_bounds("[PlanEnsemble.sample]", &config.pop_series, config.tolerance)?;
+
+        // Each chain keeps only the compact assignments and scores of its samples.
+        let chain = |i: usize| -> Result<Vec<(Vec<u8>, Vec<f64>)>> {
+            let mut rng = seeded_rng(config.seed.map(|seed| seed.wrapping_add(i as u64)));
+            let mut plan = start.clone();
+            (0..config.steps / config.sample_every).map(|_| {
+                plan.partition.recom_chain(&config.pop_series, bounds, config.sample_every, &mut rng);
+                Ok((plan.to_compact_bytes(codec)?, score(&plan, metrics)))
+            }).collect()
+        };
+
+        for (i, samples) in run_pooled(config.chains, chain).into_iter().enumerate() {
+            for (k, (assignments, scores)) in samples?.into_iter().enumerate() {
+                ensemble.assignments.push(assignments);
+                ensemble.scores.push(scores);
+                ensemble.samples.push((i as u32, ((k + 1) * config.sample_every) as u32));
+            }
+        }
+        Ok(ensemble)
+    }
+
+    /// An ensemble of existing plans (e.g. loaded from a plan store), scored with the named
+    /// `metrics`. Each plan is recorded as its own chain. The plans must share one map and
+    /// district count.
+    pub fn from_plans(plans: Vec<Plan>, metrics: &[(String, Metric)]) -> Result<Self> {
+        let mut ensemble = Self::empty(metrics)?;
+        let Some(first) = plans.first() else { return Ok(ensemble) };
+        let template = ensemble.set_template(first)?;
+        let (first, codec) = &*template;
+        for (i, plan) in plans.iter().enumerate() {
+            ensure!(Arc::ptr_eq(&plan.map, &first.map) && plan.num_districts() == first.num_districts(),
+                "[PlanEnsemble.from_plans] Plans must share one map and district count");
+            ensemble.assignments.push(plan.to_compact_bytes(codec)?);
+            ensemble.scores.push(score(plan, metrics));
+            ensemble.samples.push((i as u32, 0));
+        }
+        Ok(ensemble)
+    }
+
+    /// An ensemble with no plans, scored with the named `metrics`.
+    fn empty(metrics: &[(String, Metric)]) -> Result<Self> {
+        let mut names = metrics.iter().map(|(name, _)| name.clone()).collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+        ensure!(names.len() == metrics.len(), "[PlanEnsemble] Metric names must be unique");
+        ensure!(!names.iter().any(|name| name == "chain" || name == "step"), "[PlanEnsemble] Metric names 'chain' and 'step' are reserved");
+
+        Ok(Self {
+            template: None,
+            assignments: Vec::new(),
+            samples: Vec::new(),
+            metrics: metrics.iter().map(|(name, _)| name.clone()).collect(),
+            scores: Vec::new(),
+        })
+    }
+
+    /// Decode plans into copies of `plan`, stripped of its edit history and audit log.
+    fn set_template(&mut self, plan: &Plan) -> Result<Arc<(Plan, AssignmentCodec)>> {
+        let mut template = plan.clone();
+        template.disable_history();
+        template.disable_audit_log();
+        let codec = template.assignment_codec()?;
+        Ok(self.template.insert(Arc::new((template, codec))).clone())
+    }
+
+    #[inline] pub fn len(&self) -> usize { self.assignments.len() }
+
+    #[inline] pub fn is_empty(&self) -> bool { self.assignments.is_empty() }
+
+    /// The plan at `index`, in chain order, rebuilt from its compact assignments.
+    pub fn plan(&self, index: usize) -> Result<Plan> {
+        let bytes = self.assignments.get(index)
+            .with_context(|| format!("[PlanEnsemble.plan] No plan {index} in an ensemble of {}", self.len()))?;
+        let (template, codec) = &**self.template.as_ref().expect("non-empty ensembles have a template");
+        let mut plan = template.clone();
+        plan.load_compact_bytes(codec, bytes)?;
+        Ok(plan)
+    }
+
+    /// Every plan, in chain order (see [`PlanEnsemble::plan`]).
+    pub fn plans(&self) -> Result<Vec<Plan>> {
+        (0..self.len()).map(|index| self.plan(index)).collect()
+    }
+
+    /// Names of the metrics each plan is scored with.
+    #[inline] pub fn metric_names(&self) -> &[String] { &self.metrics }
+
+    /// Score of every plan on the metric `name`, in plan order.
+    pub fn scores(&self, name: &str) -> Result<Vec<f64>> {
+        let column = self.metrics.iter().position(|metric| metric == name)
+            .with_context(|| format!("[PlanEnsemble.scores] Unknown metric '{name}'"))?;
+        Ok(self.scores.iter().map(|scores| scores[column]).collect())
+    }
+
+    /// The `q`-quantile (0 to 1) of the ensemble's scores on the metric `name`.
+    pub fn percentile(&self, name: &str, q: f64) -> Result<f64> {
+        ensure!((0.0..=1.0).contains(&q), "[PlanEnsemble.percentile] q must be between 0 and 1");
+        ensure!(!self.is_empty(), "[PlanEnsemble.percentile] The ensemble is empty");
+        let mut scores = self.scores(name)?;
+        scores.sort_by(f64::total_cmp);
+        Ok(quantile(&scores, q))
+    }
+
+    /// Share of the ensemble's plans scoring at most `value` on the metric `name`, e.g. where
+    /// an enacted plan's score falls; values near 0 or 1 mark it as an outlier.
+    pub fn percentile_rank(&self, name: &str, value: f64) -> Result<f64> {
+        ensure!(!self.is_empty(), "[PlanEnsemble.percentile_rank] The ensemble is empty");
+        let scores = self.scores(name)?;
+        Ok(scores.iter().filter(|&&score| score <= value).count() as f64 / scores.len() as f64)
+    }
+
+    /// Range, quartiles and histogram of the ensemble's scores on each metric, by name.
+    pub fn summary(&self) -> BTreeMap<String, ColumnStats> {
+        self.metrics.iter().enumerate()
+            .filter_map(|(column, name)| {
+                let scores = self.scores.iter().map(|scores| scores[column]).collect::<Vec<_>>();
+                Some((name.clone(), ColumnStats::from_values(&scores)?))
+            })
+            .collect()
+    }
+
+    /// One row per plan: the `chain` and `step` it was kept at, and its score on each metric.
+    pub fn to_dataframe(&self) -> Result<DataFrame> {
+        let mut columns = vec![
+            Series::new("chain".into(), self.samples.iter().map(|&(chain, _)| chain).collect::<Vec<_>>()),
+            Series::new("step".into(), self.samples.iter().map(|&(_, step)| step).collect::<Vec<_>>()),
+        ];
+        columns.extend(self.metrics.iter().enumerate().map(|(column, name)| {
+            Series::new(name.into(), self.scores.iter().map(|scores| scores[column]).collect::<Vec<_>>())
+        }));
+        Ok(DataFrame::new(columns.into_iter().map(Into::into).collect())?)
+    }
+
+    /// Write [`PlanEnsemble::to_dataframe`] to a `.csv` or `.parquet` file, by its extension.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        crate::io::write_table(&mut self.to_dataframe()?, path)
+    }
+}
+
+/// Score of `plan` on each of `metrics`.
+fn score(plan: &Plan, metrics: &[(String, Metric)]) -> Vec<f64> {
+    metrics.iter().map(|(_, metric)| plan.compute_metric_score(metric)).collect()
+}
+
+/// Run `task(0..count)` on a pool of at most one thread per core, returning the results in order.
+/// Threads are unavailable on wasm32, so tasks run one after another there.
+fn run_pooled<T: Send>(count: usize, task: impl Fn(usize) -> T + Sync) -> Vec<T> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let threads = std::thread::available_parallelism().map_or(1, usize::from).min(count);
+        let next = AtomicUsize::new(0);
+        let mut results = std::thread::scope(|scope| {
+            let handles = (0..threads).map(|_| scope.spawn(|| {
+                let mut done = Vec::new();
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    if index >= count { return done }
+                    done.push((index, task(index)));
+                }
+            })).collect::<Vec<_>>();
+            handles.into_iter().flat_map(|handle| handle.join().expect("ensemble chain panicked")).collect::<Vec<_>>()
+        });
+        results.sort_by_key(|&(index, _)| index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+    #[cfg(target_arch = "wasm32")]
+    (0..count).map(task).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_maps;
+
+    #[test]
+    fn chains_are_scored_and_summarized() {
+        let map = test_maps::grid(6);
+        let mut start = Plan::new(map, 3).unwrap();
+        start.set_assignments_vec((0..36).map(|k| k / 12 + 1).collect()).unwrap();
+
+        let config = EnsembleConfig {
+            pop_series: "pop".into(), tolerance: 0.0, chains: 3, steps: 10, sample_every: 5, seed: Some(1),
+        };
+        let metrics = [("pop_dev".to_string(), Metric::population_deviation_sharp("pop".into()))];
+        let ensemble = PlanEnsemble::sample(&start, &config, &metrics).unwrap();
+        assert_eq!(ensemble.len(), 6);
+        assert!(ensemble.scores("pop_dev").unwrap().iter().all(|&score| (score - 1.0).abs() < 1e-12));
+        assert_eq!(ensemble.percentile_rank("pop_dev", 0.5).unwrap(), 0.0);
+        assert_eq!(ensemble.summary()["pop_dev"].histogram[0], 6);
+        assert!(ensemble.scores("missing").is_err());
+
+        let df = ensemble.to_dataframe().unwrap();
+        assert_eq!(df.get_column_names(), ["chain", "step", "pop_dev"]);
+        assert_eq!(df.column("step").unwrap().u32().unwrap().get(1), Some(10));
+
+        // Plans decode from their compact assignments, and the same seed gives the same ensemble.
+        let plans = ensemble.plans().unwrap();
+        assert!(plans.iter().all(|plan| plan.get_assignments_vec().unwrap().iter().filter(|&&district| district == 1).count() == 12));
+        let again = PlanEnsemble::sample(&start, &config, &metrics).unwrap();
+        assert_eq!(again.assignments, ensemble.assignments);
+        assert!(ensemble.plan(6).is_err());
+
+        let rescored = PlanEnsemble::from_plans(plans, &metrics).unwrap();
+        assert_eq!(rescored.assignments, ensemble.assignments);
+        assert!(PlanEnsemble::from_plans(vec![start.clone(), Plan::new(start.map.clone(), 2).unwrap()], &metrics).is_err());
+    }
+}
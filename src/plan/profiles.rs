@@ -0,0 +1,122 @@
+use std::{collections::BTreeSet, path::Path};
+
+use anyhow::{Result, ensure};
+use polars::{frame::DataFrame, prelude::NamedFrom, series::Series};
+
+use crate::plan::Plan;
+
+impl Plan {
+    /// A tidy table describing each district, one row per district:
+    ///
+    /// - `district`, `population` (total of `pop_series`) and `deviation` from the ideal population;
+    /// - `<group>_share` for every other series sharing `pop_series`'s prefix, e.g. `Black_share`
+    ///   for `T_20_CENS_Black` when `pop_series` is `T_20_CENS_Total`;
+    /// - `<contest>_dem_share`, the two-party Democratic share of each of the map's [`Plan::contests`];
+    /// - `polsby_popper` and `schwartzberg` compactness, if the map has geometry and areas;
+    /// - `num_counties` and `counties`, the `;`-separated GEOIDs of the counties it touches.
+    pub fn district_profiles(&self, pop_series: &str) -> Result<DataFrame> {
+        ensure!(self.series().contains(pop_series), "[Plan.district_profiles] Unknown series '{pop_series}'");
+        let districts = (1..=self.num_districts()).collect::<Vec<_>>();
+        let population = districts.iter().map(|&part| self.partition.part_total(pop_series, part)).collect::<Vec<_>>();
+
+        let mut columns = vec![
+            Series::new("district".into(), &districts),
+            Series::new("population".into(), &population),
+            Series::new("deviation".into(), districts.iter()
+                .map(|&part| self.partition.population_deviation(part, pop_series))
+                .collect::<Vec<_>>()),
+        ];
+
+        if let Some(prefix) = pop_series.strip_suffix("Total") {
+            let groups = self.series().into_iter()
+                .filter(|series| series.starts_with(prefix) && series != pop_series)
+                .collect::<BTreeSet<_>>();
+            for series in groups {
+                columns.push(Series::new(format!("{}_share", &series[prefix.len()..]).into(), districts.iter().zip(&population)
+                    .map(|(&part, &total)| if total == 0.0 { 0.0 } else { self.partition.part_total(&series, part) / total })
+                    .collect::<Vec<_>>()));
+            }
+        }
+
+        for contest in self.contests() {
+            columns.push(Series::new(format!("{}_dem_share", contest.name).into(),
+                self.partition.dem_shares(&contest.dem, &contest.rep)));
+        }
+
+        if self.map.has_geometry() && self.series().contains("area_m2") {
+            columns.push(Series::new("polsby_popper".into(),
+                districts.iter().map(|&part| self.partition.polsby_pobber(part)).collect::<Vec<_>>()));
+            columns.push(Series::new("schwartzberg".into(),
+                districts.iter().map(|&part| self.partition.schwartzberg(part)).collect::<Vec<_>>()));
+        }
+
+        let (keys, county_of) = self.county_keys()?;
+        let mut counties = vec![BTreeSet::new(); districts.len()];
+        for (&part, &county) in self.partition.assignments().iter().zip(&county_of) {
+            if part != 0 { counties[part as usize - 1].insert(keys[county as usize].as_str()); }
+        }
+        columns.push(Series::new("num_counties".into(), counties.iter().map(|set| set.len() as u32).collect::<Vec<_>>()));
+        columns.push(Series::new("counties".into(), counties.iter()
+            .map(|set| set.iter().copied().collect::<Vec<_>>().join(";"))
+            .collect::<Vec<_>>()));
+
+        Ok(DataFrame::new(columns.into_iter().map(Into::into).collect())?)
+    }
+
+    /// Write [`Plan::district_profiles`] to a `.csv` or `.parquet` file, by its extension.
+    pub fn write_district_profiles(&self, path: &Path, pop_series: &str) -> Result<()> {
+        crate::io::write_table(&mut self.district_profiles(pop_series)?, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_maps;
+
+    fn plan() -> Plan {
+        let columns = [
+            ("T_20_CENS_Total", [1.0, 1.0, 2.0, 2.0]),
+            ("T_20_CENS_Black", [1.0, 0.0, 1.0, 0.0]),
+            ("E_20_PRES_Dem", [1.0, 1.0, 0.0, 1.0]),
+            ("E_20_PRES_Rep", [0.0, 2.0, 1.0, 2.0]),
+        ];
+        let map = test_maps::row_with(4, &[], columns.iter().map(|(name, values)| (name.to_string(), values.to_vec())).collect());
+        let mut plan = Plan::new(map, 2).unwrap();
+        plan.set_assignments_vec(vec![1, 1, 2, 2]).unwrap();
+        plan
+    }
+
+    #[test]
+    fn one_row_per_district() {
+        let df = plan().district_profiles("T_20_CENS_Total").unwrap();
+        let names = df.get_column_names().iter().map(|name| name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["district", "population", "deviation", "Black_share", "20_PRES_dem_share", "num_counties", "counties"]);
+        assert_eq!(df.height(), 2);
+
+        let column = |name: &str| df.column(name).unwrap().f64().unwrap().into_no_null_iter().collect::<Vec<_>>();
+        assert_eq!(column("population"), [2.0, 4.0]);
+        assert_eq!(column("Black_share"), [0.5, 0.25]);
+        assert_eq!(column("20_PRES_dem_share"), [0.5, 0.25]);
+        // Blocks of these maps have no county, so each stands for its own.
+        let counties = df.column("counties").unwrap().str().unwrap().get(0).unwrap();
+        assert_eq!(counties, "000000000000000;000000000000001");
+
+        assert!(plan().district_profiles("T_20_CENS_Asian").is_err());
+    }
+
+    #[test]
+    fn profiles_round_trip_through_csv() {
+        let plan = plan();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profiles.csv");
+        plan.write_district_profiles(&path, "T_20_CENS_Total").unwrap();
+
+        let read = crate::io::csv::read_csv_string(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let written = plan.district_profiles("T_20_CENS_Total").unwrap();
+        assert_eq!(read.get_column_names(), written.get_column_names());
+        assert_eq!(read.column("num_counties").unwrap().cast(&polars::prelude::DataType::UInt32).unwrap(), *written.column("num_counties").unwrap());
+        assert_eq!(read.column("population").unwrap(), written.column("population").unwrap());
+        assert!(plan.write_district_profiles(&dir.path().join("profiles.txt"), "T_20_CENS_Total").is_err());
+    }
+}
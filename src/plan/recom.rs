@@ -0,0 +1,81 @@
+use anyhow::{Result, ensure};
+use rand::{SeedableRng, rngs::StdRng};
+
+use crate::plan::{EditSource, Plan};
+
+impl Plan {
+    /// Run a ReCom (recombination) chain from this plan for `steps` steps, the proposal GerryChain
+    /// uses to sample ensembles. Each step merges a random pair of adjacent districts and re-splits
+    /// them along a random spanning tree, at a cut leaving both districts' `pop_series` totals
+    /// within `tolerance` (relative) of the ideal district total; steps with no such cut leave the
+    /// plan unchanged. Districts stay contiguous. The same `seed` gives the same chain.
+    ///
+    /// Returns the number of steps that changed the plan.
+    pub fn recom(&mut self, pop_series: &str, tolerance: f64, steps: usize, seed: Option<u64>) -> Result<usize> {
+        let bounds = self.recom_bounds("[Plan.recom]", pop_series, tolerance)?;
+        let mut rng = seeded_rng(seed);
+        self.tracked("recom", EditSource::Chain, |plan| Ok(plan.partition.recom_chain(pop_series, bounds, steps, &mut rng)))
+    }
+
+    /// Sample an ensemble with a ReCom chain (see [`Plan::recom`]) started from this plan: runs
+    /// `steps` steps and keeps a copy of the plan every `sample_every` steps. This plan is unchanged,
+    /// and is the parent of every sample.
+    pub fn recom_ensemble(&self, pop_series: &str, tolerance: f64, steps: usize, sample_every: usize, seed: Option<u64>) -> Result<Vec<Plan>> {
+        ensure!(sample_every > 0, "[Plan.recom_ensemble] sample_every must be positive");
+        let bounds = self.recom_bounds("[Plan.recom_ensemble]", pop_series, tolerance)?;
+        let mut rng = seeded_rng(seed);
+
+        let mut chain = self.fork();
+        let mut samples = Vec::with_capacity(steps / sample_every);
+        for _ in 0..steps / sample_every {
+            chain.tracked("recom", EditSource::Chain, |plan| Ok(plan.partition.recom_chain(pop_series, bounds, sample_every, &mut rng)))?;
+            samples.push(chain.clone());
+        }
+        Ok(samples)
+    }
+
+    /// Check that a ReCom chain can run, and get the bounds on district totals it keeps.
+    pub(super) fn recom_bounds(&self, context: &str, pop_series: &str, tolerance: f64) -> Result<(f64, f64)> {
+        ensure!(self.num_districts() > 1, "{context} Need at least two districts to recombine");
+        ensure!(self.partition.part_nodes(0).is_empty(), "{context} Every block must be assigned to a district");
+        ensure!(self.series().contains(pop_series), "{context} Unknown series '{pop_series}'");
+        ensure!(tolerance >= 0.0, "{context} Tolerance must be non-negative");
+
+        let ideal = self.partition.part_totals(pop_series).iter().sum::<f64>() / self.num_districts() as f64;
+        Ok((ideal * (1.0 - tolerance), ideal * (1.0 + tolerance)))
+    }
+}
+
+/// A random number generator seeded with `seed`, or from entropy.
+pub(super) fn seeded_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_rng(&mut rand::rng()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_maps;
+
+    #[test]
+    fn chain_keeps_districts_balanced() {
+        // Four districts of two columns each on an 8x8 grid.
+        let mut plan = Plan::new(test_maps::grid(8), 4).unwrap();
+        plan.metadata_mut().name = Some("enacted".into());
+        plan.set_assignments_vec((0..64).map(|k| k / 16 + 1).collect()).unwrap();
+
+        let samples = plan.recom_ensemble("pop", 0.0, 20, 5, Some(7)).unwrap();
+        assert_eq!(samples.len(), 4);
+        assert!(samples.iter().all(|sample| sample.metadata().parent.as_deref() == Some("enacted")));
+        for sample in &samples {
+            assert!(sample.partition.part_totals("pop")[1..].iter().all(|&total| total == 16.0));
+        }
+        assert_eq!(plan.get_assignments_vec().unwrap(), (0..64).map(|k| k / 16 + 1).collect::<Vec<_>>());
+
+        let again = plan.recom_ensemble("pop", 0.0, 20, 5, Some(7)).unwrap();
+        assert_eq!(again.last().unwrap().get_assignments_vec().unwrap(), samples.last().unwrap().get_assignments_vec().unwrap());
+        assert!(plan.recom("pop", -1.0, 1, None).is_err());
+    }
+}
@@ -0,0 +1,108 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use polars::prelude::{DataFrame, DataType};
+
+use crate::{io::locale::Locale, plan::Plan};
+
+impl Plan {
+    /// [`Plan::district_profiles`] with each column renamed to its label in `locale`'s language
+    /// (see [`Locale::column_label`]), for tables shown to the public.
+    pub fn localized_district_profiles(&self, pop_series: &str, locale: &Locale) -> Result<DataFrame> {
+        let mut profiles = self.district_profiles(pop_series)?;
+        let labels = profiles.get_column_names_owned().into_iter()
+            .map(|name| locale.column_label(&name))
+            .collect::<Vec<_>>();
+        profiles.set_column_names(labels)?;
+        Ok(profiles)
+    }
+
+    /// Report of the plan's districts as a Markdown document in `locale`'s language and number
+    /// format: a summary of its population balance, then a table of [`Plan::district_profiles`]
+    /// with translated column labels.
+    pub fn district_report(&self, pop_series: &str, locale: &Locale) -> Result<String> {
+        let profiles = self.district_profiles(pop_series)?;
+        let total = self.partition.region_total(pop_series);
+        let max_deviation = (1..=self.num_districts())
+            .map(|part| self.partition.absolute_population_deviation(part, pop_series))
+            .fold(0.0, f64::max);
+
+        let mut report = format!("# {}", locale.text("District report"));
+        if let Some(name) = &self.metadata().name { report.push_str(&format!(": {name}")) }
+        report.push_str("\n\n");
+        for (label, value) in [
+            ("Districts", self.num_districts().to_string()),
+            ("Total population", locale.format_number(total, 0)),
+            ("Ideal population", locale.format_number(total / self.num_districts() as f64, 0)),
+            ("Max population deviation", locale.format_percent(max_deviation, 2)),
+        ] {
+            report.push_str(&format!("- {}: {value}\n", locale.text(label)));
+        }
+
+        // Format every column as text, right-aligning numbers.
+        let mut columns = Vec::with_capacity(profiles.width());
+        for column in profiles.get_columns() {
+            let name = column.name().as_str();
+            let cells = if column.dtype().is_primitive_numeric() {
+                let values = column.cast(&DataType::Float64)?;
+                values.f64()?.into_iter()
+                    .map(|value| value.map_or_else(String::new, |value| match name {
+                        "district" | "num_counties" => value.to_string(),
+                        "population" => locale.format_number(value, 0),
+                        "deviation" => locale.format_percent(value, 2),
+                        name if name.ends_with("_share") => locale.format_percent(value, 1),
+                        _ => locale.format_number(value, 3),
+                    }))
+                    .collect::<Vec<_>>()
+            } else {
+                let values = column.cast(&DataType::String)?;
+                values.str()?.into_iter()
+                    .map(|value| value.unwrap_or_default().replace(';', ", ").replace('|', "\\|"))
+                    .collect()
+            };
+            columns.push((locale.column_label(name), column.dtype().is_primitive_numeric(), cells));
+        }
+
+        report.push('\n');
+        report.push_str(&format!("| {} |\n", columns.iter().map(|(label, _, _)| label.as_str()).collect::<Vec<_>>().join(" | ")));
+        report.push_str(&format!("|{}|\n", columns.iter().map(|(_, numeric, _)| if *numeric { "---:" } else { "---" }).collect::<Vec<_>>().join("|")));
+        for row in 0..profiles.height() {
+            report.push_str(&format!("| {} |\n", columns.iter().map(|(_, _, cells)| cells[row].as_str()).collect::<Vec<_>>().join(" | ")));
+        }
+        Ok(report)
+    }
+
+    /// Write [`Plan::district_report`] to a Markdown file.
+    pub fn write_district_report(&self, path: &Path, pop_series: &str, locale: &Locale) -> Result<()> {
+        std::fs::write(path, self.district_report(pop_series, locale)?)
+            .with_context(|| format!("[Plan.write_district_report] Failed to write {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_maps;
+
+    #[test]
+    fn spanish_reports_translate_labels_and_numbers() {
+        let ids = ["170190001001000", "170190001001001", "170190002001000", "170190002001001"];
+        let columns = vec![("T_20_CENS_Total".into(), vec![1000.0, 1500.0, 1200.0, 1300.0]), ("T_20_CENS_Hispanic".into(), vec![500.0, 0.0, 600.0, 0.0])];
+        let map = test_maps::row_with(4, &ids, columns);
+        let mut plan = Plan::new(map, 2).unwrap();
+        plan.set_assignments_vec(vec![1, 1, 2, 2]).unwrap();
+
+        let report = plan.district_report("T_20_CENS_Total", &Locale::ES_ES).unwrap();
+        assert!(report.starts_with("# Informe de distritos\n"));
+        assert!(report.contains("- Población total: 5.000\n"));
+        assert!(report.contains("- Desviación máxima de población: 0,00%\n"));
+        assert!(report.contains("| Distrito | Población | Desviación | Proporción Hispanic |"));
+        assert!(report.contains("| 1 | 2.500 | 0,00% | 20,0% |"));
+
+        let english = plan.district_report("T_20_CENS_Total", &Locale::EN_US).unwrap();
+        assert!(english.contains("| 2 | 2,500 | 0.00% | 24.0% |"));
+
+        let profiles = plan.localized_district_profiles("T_20_CENS_Total", &Locale::ES_US).unwrap();
+        assert_eq!(profiles.get_column_names()[..2], ["Distrito", "Población"]);
+    }
+}
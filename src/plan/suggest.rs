@@ -0,0 +1,68 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{Metric, plan::Plan};
+
+/// A single-block reassignment and its effect on a metric (see [`Plan::best_single_moves`]).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SuggestedMove {
+    pub block: String,    // GEOID of the block to move
+    pub from: u32,        // Its current district
+    pub to: u32,          // The neighboring district to move it into
+    pub score: f64,       // Metric score after the move
+    pub improvement: f64, // Score after the move minus the current score (positive)
+}
+
+impl Plan {
+    /// The `k` single-block reassignments that most improve `metric` (higher scores are better),
+    /// best first, e.g. to suggest an edit in an interactive editor.
+    ///
+    /// Every block on a district's frontier is tried in each neighboring district; moves that
+    /// would break contiguity or an anchor, or that must carry other blocks along, are skipped,
+    /// as are moves that do not improve the score. The plan itself is not changed.
+    pub fn best_single_moves(&self, metric: &Metric, k: usize) -> Result<Vec<SuggestedMove>> {
+        let current = metric.compute_score(&self.partition);
+        let mut partition = self.partition.clone();
+        let mut moves = partition.single_move_scores(|partition| metric.compute_score(partition)).into_iter()
+            .filter(|&(_, _, _, score)| score > current)
+            .collect::<Vec<_>>();
+        moves.sort_by(|a, b| b.3.total_cmp(&a.3).then(a.0.cmp(&b.0)).then(a.2.cmp(&b.2)));
+        moves.truncate(k);
+
+        let geo_ids = self.map.base()?.geo_ids();
+        Ok(moves.into_iter()
+            .map(|(node, from, to, score)| SuggestedMove {
+                block: geo_ids[node].id().to_string(),
+                from,
+                to,
+                score,
+                improvement: score - current,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_maps;
+
+    #[test]
+    fn moves_rebalance_uneven_districts() {
+        // District 1 holds the first three columns of a 4x4 grid, district 2 the last.
+        let mut plan = Plan::new(test_maps::grid(4), 2).unwrap();
+        plan.set_assignments_vec((0..16).map(|k| if k < 12 { 1 } else { 2 }).collect()).unwrap();
+        let before = plan.get_assignments_vec().unwrap();
+
+        let metric = Metric::population_deviation_sharp("pop".into());
+        let moves = plan.best_single_moves(&metric, 3).unwrap();
+        assert_eq!(moves.len(), 3);
+        assert!(moves.iter().all(|m| m.from == 1 && m.to == 2 && m.improvement > 0.0));
+        assert!(moves.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        assert_eq!(plan.get_assignments_vec().unwrap(), before);
+
+        // Once balanced, no single move improves the deviation.
+        plan.set_assignments_vec((0..16).map(|k| if k < 8 { 1 } else { 2 }).collect()).unwrap();
+        assert!(plan.best_single_moves(&metric, 3).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,131 @@
+use std::{collections::HashSet, ops::RangeInclusive, path::Path, sync::Arc};
+
+use anyhow::{Result, ensure};
+use polars::{frame::DataFrame, prelude::NamedFrom, series::Series};
+use serde::{Deserialize, Serialize};
+
+use crate::{map::Map, plan::{AnnealConfig, Plan, Swing}};
+
+/// How each plan of a [`Plan::district_count_sweep`] is drawn: randomized, equalized to
+/// `tolerance` on `pop_series`, then optionally annealed with `anneal`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SweepConfig {
+    pub pop_series: String,
+    pub tolerance: f64,                // Relative population tolerance for equalization, e.g. 0.05
+    #[serde(default = "SweepConfig::default_equalize_iter")]
+    pub equalize_iter: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anneal: Option<AnnealConfig>,  // Annealing run after equalization (none if None)
+}
+
+impl SweepConfig {
+    fn default_equalize_iter() -> usize { 1000 }
+}
+
+impl Plan {
+    /// Draw one plan of `map` for each district count in `counts` (e.g. `3..=15`, when choosing a
+    /// council size), following `config`. Plans are drawn in parallel; compare them with
+    /// [`Plan::compare_plans`].
+    pub fn district_count_sweep(map: impl Into<Arc<Map>>, counts: RangeInclusive<u32>, config: &SweepConfig) -> Result<Vec<Plan>> {
+        ensure!(!counts.is_empty() && *counts.start() > 0, "[Plan.district_count_sweep] District counts must be a non-empty range of positive counts");
+        let map = map.into();
+        ensure!(map.base()?.get_unit_weights().contains(&config.pop_series),
+            "[Plan.district_count_sweep] Unknown series '{}'", config.pop_series);
+
+        let draw = |num_districts: u32| -> Result<Plan> {
+            let mut plan = Plan::new(map.clone(), num_districts)?;
+            plan.randomize()?;
+            plan.equalize(&config.pop_series, config.tolerance, config.equalize_iter)?;
+            if let Some(anneal) = &config.anneal { plan.anneal_with_config(anneal)?; }
+            Ok(plan)
+        };
+
+        // Threads are unavailable on wasm32, so plans are drawn one after another there.
+        #[cfg(not(target_arch = "wasm32"))]
+        let plans = std::thread::scope(|scope| {
+            let draw = &draw;
+            let handles = counts.map(|count| scope.spawn(move || draw(count))).collect::<Vec<_>>();
+            handles.into_iter().map(|handle| handle.join().expect("district count sweep panicked")).collect()
+        });
+        #[cfg(target_arch = "wasm32")]
+        let plans = counts.map(draw).collect();
+        plans
+    }
+
+    /// A comparative table of plans of one map, one row per plan: `num_districts`, the
+    /// `ideal_population` of `pop_series` and the `max_deviation` from it, `split_counties`
+    /// (counties touching more than one district), `mean_polsby_popper` and `min_polsby_popper`
+    /// if the map has geometry and areas, and `<contest>_dem_seats` for each of the map's contests.
+    pub fn compare_plans(plans: &[&Plan], pop_series: &str) -> Result<DataFrame> {
+        ensure!(!plans.is_empty(), "[Plan.compare_plans] No plans given");
+        ensure!(plans.iter().all(|plan| plan.series().contains(pop_series)), "[Plan.compare_plans] Unknown series '{pop_series}'");
+
+        let districts = |plan: &Plan| 1..=plan.num_districts();
+        let mut columns = vec![
+            Series::new("num_districts".into(), plans.iter().map(|plan| plan.num_districts()).collect::<Vec<_>>()),
+            Series::new("ideal_population".into(), plans.iter()
+                .map(|plan| plan.partition.region_total(pop_series) / plan.num_districts() as f64)
+                .collect::<Vec<_>>()),
+            Series::new("max_deviation".into(), plans.iter()
+                .map(|plan| districts(plan).map(|part| plan.partition.population_deviation(part, pop_series).abs()).fold(0.0, f64::max))
+                .collect::<Vec<_>>()),
+            Series::new("split_counties".into(), plans.iter()
+                .map(|plan| plan.split_counties())
+                .collect::<Result<Vec<_>>>()?),
+        ];
+
+        if plans.iter().all(|plan| plan.map.has_geometry() && plan.series().contains("area_m2")) {
+            let scores = plans.iter()
+                .map(|plan| districts(plan).map(|part| plan.partition.polsby_pobber(part)).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            columns.push(Series::new("mean_polsby_popper".into(), scores.iter()
+                .map(|scores| scores.iter().sum::<f64>() / scores.len() as f64)
+                .collect::<Vec<_>>()));
+            columns.push(Series::new("min_polsby_popper".into(), scores.iter()
+                .map(|scores| scores.iter().copied().fold(f64::INFINITY, f64::min))
+                .collect::<Vec<_>>()));
+        }
+
+        for contest in plans[0].contests() {
+            columns.push(Series::new(format!("{}_dem_seats", contest.name).into(), plans.iter()
+                .map(|plan| Ok(plan.seats_under_swings(&contest, &[Swing::Uniform(0.0)])?[0]))
+                .collect::<Result<Vec<_>>>()?));
+        }
+
+        Ok(DataFrame::new(columns.into_iter().map(Into::into).collect())?)
+    }
+
+    /// Write [`Plan::compare_plans`] to a `.csv` or `.parquet` file, by its extension.
+    pub fn write_plan_comparison(plans: &[&Plan], pop_series: &str, path: &Path) -> Result<()> {
+        crate::io::write_table(&mut Self::compare_plans(plans, pop_series)?, path)
+    }
+
+    /// Number of counties whose blocks are assigned to more than one district.
+    fn split_counties(&self) -> Result<u32> {
+        let (keys, county_of) = self.county_keys()?;
+        let mut districts = vec![HashSet::new(); keys.len()];
+        for (&part, &county) in self.partition.assignments().iter().zip(&county_of) {
+            if part != 0 { districts[county as usize].insert(part); }
+        }
+        Ok(districts.iter().filter(|parts| parts.len() > 1).count() as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_maps;
+
+    #[test]
+    fn sweep_draws_one_plan_per_count() {
+        let config = SweepConfig { pop_series: "pop".into(), tolerance: 0.5, equalize_iter: 100, anneal: None };
+        let plans = Plan::district_count_sweep(test_maps::grid(4), 2..=4, &config).unwrap();
+        assert_eq!(plans.iter().map(Plan::num_districts).collect::<Vec<_>>(), vec![2, 3, 4]);
+
+        let table = Plan::compare_plans(&plans.iter().collect::<Vec<_>>(), "pop").unwrap();
+        assert_eq!(table.height(), 3);
+        let ideal = table.column("ideal_population").unwrap().f64().unwrap().into_no_null_iter().collect::<Vec<_>>();
+        assert_eq!(ideal, vec![8.0, 16.0 / 3.0, 4.0]);
+        assert!(Plan::district_count_sweep(test_maps::grid(2), 2..=3, &SweepConfig { pop_series: "none".into(), ..config }).is_err());
+    }
+}
@@ -0,0 +1,130 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, ensure};
+use serde::{Deserialize, Serialize};
+
+use crate::{Metric, Objective, RelaxedConstraint, partition::{HullKind, StoppingRules}, plan::{Plan, RelaxedAnnealConfig}};
+
+/// Rules for drawing municipal wards, e.g. from a map clipped to a city with
+/// [`Map::clip_to_geojson`](crate::Map::clip_to_geojson).
+///
+/// Local plans are usually held to a total deviation (largest minus smallest ward, relative to
+/// the ideal) rather than a per-district one: 0.10 for the common ±5% standard, 0.05 for ±2.5%.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WardRules {
+    pub pop_series: String,
+    pub max_total_deviation: f64,
+    #[serde(default = "WardRules::default_max_iter")]
+    pub max_iter: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub objective: Option<Objective>, // Polsby-Popper compactness if None (smooth deviation without areas)
+}
+
+impl WardRules {
+    fn default_max_iter() -> usize { 100_000 }
+
+    /// Wards within `max_total_deviation` of each other on `pop_series`, with default settings.
+    pub fn new(pop_series: &str, max_total_deviation: f64) -> Self {
+        Self { pop_series: pop_series.into(), max_total_deviation, max_iter: Self::default_max_iter(), objective: None }
+    }
+}
+
+/// Which files [`Plan::export_wards`] writes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportPreset {
+    /// What a city clerk files: `assignments.csv` (block equivalency, with metadata sidecar),
+    /// `wards.csv` (population and deviation per ward) and `wards.svg` (a printable map).
+    Clerk,
+    /// What a GIS department loads: `assignments.csv` and `wards.geojson` (dissolved ward outlines).
+    Gis,
+    /// Every file of both presets.
+    All,
+}
+
+impl Plan {
+    /// Draw wards from a random plan under `rules`: anneal with the total deviation constraint
+    /// tightening to `max_total_deviation`. Returns `true` if the wards satisfy it.
+    pub fn draw_wards(&mut self, rules: &WardRules) -> Result<bool> {
+        ensure!(self.series().contains(&rules.pop_series), "[Plan.draw_wards] Unknown series '{}'", rules.pop_series);
+        ensure!(rules.max_total_deviation >= 0.0, "[Plan.draw_wards] Total deviation must be non-negative");
+
+        let objective = rules.objective.clone().unwrap_or_else(|| match self.map.has_geometry() && self.series().contains("area_m2") {
+            true => Objective::new(vec![Metric::compactness_polsby_popper()], None),
+            false => Objective::new(vec![Metric::population_deviation_smooth(rules.pop_series.clone())], None),
+        });
+
+        self.randomize()?;
+        self.anneal_relaxed_with_config(&RelaxedAnnealConfig {
+            objective,
+            constraints: vec![RelaxedConstraint::total_deviation(rules.pop_series.clone(), rules.max_total_deviation)],
+            max_iter: rules.max_iter,
+            initial_temp: 1.0,
+            final_temp: 1e-4,
+            initial_penalty: 1.0,
+            final_penalty: 1000.0,
+            stop: StoppingRules::default(),
+        })
+    }
+
+    /// Write the files of `preset` into the directory `dir` (created if missing), with ward
+    /// populations taken from `pop_series`. Returns the paths written.
+    pub fn export_wards(&self, dir: &Path, pop_series: &str, preset: ExportPreset) -> Result<Vec<PathBuf>> {
+        ensure!(self.series().contains(pop_series), "[Plan.export_wards] Unknown series '{pop_series}'");
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("[Plan.export_wards] Failed to create directory {}", dir.display()))?;
+
+        let mut written = vec![dir.join("assignments.csv")];
+        self.write_to_csv(&written[0])?;
+
+        if matches!(preset, ExportPreset::Clerk | ExportPreset::All) {
+            let profiles = dir.join("wards.csv");
+            self.write_district_profiles(&profiles, pop_series)?;
+            written.push(profiles);
+
+            let svg = dir.join("wards.svg");
+            self.to_svg(&svg, false)?;
+            written.push(svg);
+        }
+
+        if matches!(preset, ExportPreset::Gis | ExportPreset::All) {
+            let geojson = dir.join("wards.geojson");
+            std::fs::write(&geojson, serde_json::to_string(&self.district_outlines_geojson(HullKind::Dissolve)?)?)
+                .with_context(|| format!("[Plan.export_wards] Failed to write {}", geojson.display()))?;
+            written.push(geojson);
+        }
+
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_maps;
+
+    #[test]
+    fn wards_drawn_within_clipped_city() {
+        let dir = std::env::temp_dir().join(format!("openmander-wards-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let boundary = dir.join("city.geojson");
+        let city = json!({ "type": "Feature", "geometry": {
+            "type": "Polygon", "coordinates": [[[0.0, 0.0], [4.0, 0.0], [4.0, 3.0], [0.0, 3.0], [0.0, 0.0]]],
+        }});
+        std::fs::write(&boundary, city.to_string()).unwrap();
+
+        let map = test_maps::grid(6).clip_to_geojson(&boundary).unwrap();
+        assert_eq!(map.base().unwrap().len(), 12);
+
+        let mut plan = Plan::new(map, 3).unwrap();
+        assert!(plan.draw_wards(&WardRules { max_iter: 20_000, ..WardRules::new("pop", 0.0) }).unwrap());
+        let totals = (1..=3).map(|part| plan.partition.part_total("pop", part)).collect::<Vec<_>>();
+        assert_eq!(totals, vec![4.0; 3]);
+
+        let written = plan.export_wards(&dir.join("export"), "pop", ExportPreset::Gis).unwrap();
+        assert!(written.iter().all(|path| path.exists()));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,58 @@
+use anyhow::Result;
+
+use crate::plan::{EditSource, Plan, recom::seeded_rng};
+
+impl Plan {
+    /// Perturb this plan into `k` diverse starting plans, e.g. to seed multi-start optimization
+    /// or to spread parallel chains over the space of plans.
+    ///
+    /// Each start is a copy of this plan after `steps` bounded ReCom steps: two adjacent districts
+    /// are merged and re-split along a random spanning tree, at a cut leaving both districts'
+    /// `pop_series` totals within `tolerance` (relative) of the ideal district total. Steps with no
+    /// such cut are skipped, so every start stays contiguous and no district leaves the bounds
+    /// unless it was already outside them. Starts are drawn independently; with a `seed`, start
+    /// `i` uses seed `seed + i`, so the same seed gives the same starts.
+    pub fn warm_starts(&self, k: usize, steps: usize, pop_series: &str, tolerance: f64, seed: Option<u64>) -> Result<Vec<Plan>> {
+        let bounds = self.recom_bounds("[Plan.warm_starts]", pop_series, tolerance)?;
+
+        (0..k).map(|i| {
+            let mut rng = seeded_rng(seed.map(|seed| seed.wrapping_add(i as u64)));
+            let mut start = self.fork();
+            start.tracked("warm_start", EditSource::Chain, |plan| {
+                plan.partition.recom_chain(pop_series, bounds, steps, &mut rng);
+                Ok(())
+            })?;
+            Ok(start)
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_maps;
+
+    #[test]
+    fn starts_are_balanced_distinct_and_reproducible() {
+        // Four districts of two columns each on an 8x8 grid.
+        let mut plan = Plan::new(test_maps::grid(8), 4).unwrap();
+        plan.set_assignments_vec((0..64).map(|k| k / 16 + 1).collect()).unwrap();
+        plan.metadata_mut().name = Some("enacted".into());
+
+        let starts = plan.warm_starts(3, 10, "pop", 0.125, Some(11)).unwrap();
+        assert_eq!(starts.len(), 3);
+        for start in &starts {
+            assert!(start.partition.part_totals("pop")[1..].iter().all(|&total| (14.0..=18.0).contains(&total)));
+            assert!(start.partition.first_discontiguous(&[1, 2, 3, 4]).is_none());
+            assert_eq!(start.metadata().parent.as_deref(), Some("enacted"));
+        }
+
+        let assignments = starts.iter().map(|start| start.get_assignments_vec().unwrap()).collect::<Vec<_>>();
+        assert!(assignments.iter().all(|a| *a != plan.get_assignments_vec().unwrap()));
+        assert!(assignments[0] != assignments[1] && assignments[1] != assignments[2] && assignments[0] != assignments[2]);
+
+        let again = plan.warm_starts(3, 10, "pop", 0.125, Some(11)).unwrap();
+        assert_eq!(again.iter().map(|start| start.get_assignments_vec().unwrap()).collect::<Vec<_>>(), assignments);
+        assert!(plan.warm_starts(1, 1, "pop", -0.1, None).is_err());
+    }
+}
@@ -0,0 +1,53 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::plan::PlanMetadata;
+
+pub(super) const INDEX_VERSION: u32 = 2;
+
+/// Index entry for a named plan in a [`crate::PlanStore`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StoredPlan {
+    pub hash: String,                  // SHA-256 of the encoded assignments (object file name)
+    pub num_districts: u32,
+    #[serde(default)]
+    pub metadata: PlanMetadata,
+    #[serde(default)]
+    pub scores: BTreeMap<String, f64>, // Score snapshot taken when the plan was stored
+}
+
+/// A maintenance operation applied to a [`crate::PlanStore`] (merge, thin, dedup), recorded in
+/// its index so an ensemble's history can be traced.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StoreOperation {
+    pub timestamp: Option<u64>, // Seconds since the Unix epoch
+    pub operation: String,      // "merge", "thin" or "dedup"
+    pub details: String,        // Arguments, e.g. "prefix=\"seed1/\" burn_in=1000 every=10"
+    pub plans_before: usize,
+    pub plans_after: usize,
+}
+
+/// On-disk index of a plan store (`index.json`), keyed by plan name.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(super) struct StoreIndex {
+    pub(super) version: u32,
+    pub(super) num_units: usize,       // Block count of the map the store was created for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) blocks_hash: Option<String>, // SHA-256 of that map's block GEOIDs, in order (since version 2)
+    #[serde(default)]
+    pub(super) plans: BTreeMap<String, StoredPlan>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(super) provenance: Vec<StoreOperation>,
+}
+
+impl StoreIndex {
+    pub(super) fn new(num_units: usize, blocks_hash: String) -> Self {
+        Self { version: INDEX_VERSION, num_units, blocks_hash: Some(blocks_hash), plans: BTreeMap::new(), provenance: Vec::new() }
+    }
+
+    /// Check whether any plan refers to the object `hash`.
+    pub(super) fn references(&self, hash: &str) -> bool {
+        self.plans.values().any(|entry| entry.hash == hash)
+    }
+}
@@ -0,0 +1,7 @@
+mod index;
+mod reweight;
+mod store;
+
+pub use index::{StoreOperation, StoredPlan};
+pub use reweight::{ImportanceWeights, WeightedDistribution};
+pub use store::PlanStore;
@@ -0,0 +1,167 @@
+use anyhow::{Result, anyhow, ensure};
+
+use super::PlanStore;
+
+/// Normalized importance weights over the plans of a [`PlanStore`], reweighting an ensemble
+/// sampled from one distribution towards another without sampling it again.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportanceWeights {
+    names: Vec<String>,
+    weights: Vec<f64>, // Non-negative, summing to 1
+}
+
+impl ImportanceWeights {
+    /// Weights proportional to `exp(log_ratio)` for each `(name, log_ratio)`, where `log_ratio`
+    /// is the log of the target density over the sampling density (up to a constant).
+    pub fn from_log_ratios(log_ratios: Vec<(String, f64)>) -> Result<Self> {
+        ensure!(!log_ratios.is_empty(), "[ImportanceWeights] No plans to weight");
+        ensure!(log_ratios.iter().all(|(_, ratio)| !ratio.is_nan()), "[ImportanceWeights] Log ratios must not be NaN");
+        let max = log_ratios.iter().map(|&(_, ratio)| ratio).fold(f64::NEG_INFINITY, f64::max);
+        ensure!(max.is_finite(), "[ImportanceWeights] Every plan has zero weight");
+
+        // Subtract the largest log ratio so the exponentials cannot overflow.
+        let (names, mut weights): (Vec<_>, Vec<_>) = log_ratios.into_iter().map(|(name, ratio)| (name, (ratio - max).exp())).unzip();
+        let total = weights.iter().sum::<f64>();
+        weights.iter_mut().for_each(|weight| *weight /= total);
+        Ok(Self { names, weights })
+    }
+
+    /// Uniform weights over `names`, i.e. the ensemble as sampled.
+    pub fn uniform(names: Vec<String>) -> Result<Self> {
+        Self::from_log_ratios(names.into_iter().map(|name| (name, 0.0)).collect())
+    }
+
+    /// Get the number of weighted plans.
+    #[inline] pub fn len(&self) -> usize { self.names.len() }
+
+    /// Check if no plans are weighted.
+    #[inline] pub fn is_empty(&self) -> bool { self.names.is_empty() }
+
+    /// Get `(name, weight)` for every weighted plan.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, f64)> + '_ {
+        self.names.iter().map(String::as_str).zip(self.weights.iter().copied())
+    }
+
+    /// Get the weight of the named plan (0 if it is not weighted).
+    pub fn weight(&self, name: &str) -> f64 {
+        self.names.iter().position(|n| n == name).map_or(0.0, |i| self.weights[i])
+    }
+
+    /// Kish effective sample size, `1 / sum(w^2)`: how many independent plans the reweighted
+    /// ensemble is worth. Much smaller than `len()` means the target is poorly covered.
+    pub fn effective_sample_size(&self) -> f64 {
+        1.0 / self.weights.iter().map(|weight| weight * weight).sum::<f64>()
+    }
+}
+
+/// A weighted sample of one metric over an ensemble, sorted by value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WeightedDistribution {
+    samples: Vec<(f64, f64)>, // (value, weight), weights summing to 1
+}
+
+impl WeightedDistribution {
+    /// Build a distribution from `(value, weight)` pairs; weights are normalized to sum to 1.
+    pub fn new(mut samples: Vec<(f64, f64)>) -> Result<Self> {
+        ensure!(samples.iter().all(|&(value, weight)| !value.is_nan() && weight >= 0.0),
+            "[WeightedDistribution] Values must not be NaN and weights must be non-negative");
+        let total = samples.iter().map(|&(_, weight)| weight).sum::<f64>();
+        ensure!(total > 0.0, "[WeightedDistribution] Total weight must be positive");
+        samples.iter_mut().for_each(|(_, weight)| *weight /= total);
+        samples.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Ok(Self { samples })
+    }
+
+    /// Get the `(value, weight)` samples, sorted by value.
+    #[inline] pub fn samples(&self) -> &[(f64, f64)] { &self.samples }
+
+    /// Weighted mean.
+    pub fn mean(&self) -> f64 {
+        self.samples.iter().map(|&(value, weight)| value * weight).sum()
+    }
+
+    /// Weighted standard deviation.
+    pub fn std_dev(&self) -> f64 {
+        let mean = self.mean();
+        self.samples.iter().map(|&(value, weight)| weight * (value - mean).powi(2)).sum::<f64>().sqrt()
+    }
+
+    /// Smallest value whose cumulative weight reaches `q` (in [0, 1]).
+    pub fn quantile(&self, q: f64) -> f64 {
+        let mut cumulative = 0.0;
+        for &(value, weight) in &self.samples {
+            cumulative += weight;
+            if cumulative >= q - 1e-12 { return value }
+        }
+        self.samples.last().unwrap().0
+    }
+
+    /// Weight of samples at or below `value`, e.g. where an enacted plan falls in the ensemble.
+    pub fn cdf(&self, value: f64) -> f64 {
+        self.samples.iter().take_while(|&&(v, _)| v <= value).map(|&(_, weight)| weight).sum()
+    }
+}
+
+impl PlanStore {
+    /// Importance weights tempering the stored ensemble by a score snapshot: plan `i` gets weight
+    /// proportional to `exp(beta * score_i)`, e.g. a positive `beta` on a compactness score favors
+    /// compact plans. Plans without a snapshot of `score` are left out.
+    pub fn tempered_weights(&self, score: &str, beta: f64) -> Result<ImportanceWeights> {
+        let log_ratios = self.names()
+            .filter_map(|name| self.entry(name)?.scores.get(score).map(|&value| (name.to_string(), beta * value)))
+            .collect::<Vec<_>>();
+        ensure!(!log_ratios.is_empty(), "[PlanStore] No plans have a snapshot of {:?}", score);
+        ImportanceWeights::from_log_ratios(log_ratios)
+    }
+
+    /// Distribution of the score snapshot `metric` over the plans of `weights`.
+    pub fn weighted_distribution(&self, metric: &str, weights: &ImportanceWeights) -> Result<WeightedDistribution> {
+        let samples = weights.iter()
+            .map(|(name, weight)| {
+                let entry = self.entry(name).ok_or_else(|| anyhow!("[PlanStore] No plan named {:?}", name))?;
+                let value = entry.scores.get(metric)
+                    .ok_or_else(|| anyhow!("[PlanStore] Plan {:?} has no snapshot of {:?}", name, metric))?;
+                Ok((*value, weight))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        WeightedDistribution::new(samples)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weights_follow_log_ratios() {
+        let weights = ImportanceWeights::from_log_ratios(vec![("a".into(), 0.0), ("b".into(), 2f64.ln())]).unwrap();
+        assert!((weights.weight("a") - 1.0 / 3.0).abs() < 1e-12);
+        assert!((weights.weight("b") - 2.0 / 3.0).abs() < 1e-12);
+        assert_eq!(weights.weight("c"), 0.0);
+    }
+
+    #[test]
+    fn large_log_ratios_do_not_overflow() {
+        let weights = ImportanceWeights::from_log_ratios(vec![("a".into(), 1000.0), ("b".into(), 1000.0)]).unwrap();
+        assert!((weights.weight("a") - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn effective_sample_size_shrinks_with_skew() {
+        let uniform = ImportanceWeights::uniform(vec!["a".into(), "b".into(), "c".into(), "d".into()]).unwrap();
+        assert!((uniform.effective_sample_size() - 4.0).abs() < 1e-12);
+        let skewed = ImportanceWeights::from_log_ratios(vec![("a".into(), 10.0), ("b".into(), 0.0)]).unwrap();
+        assert!(skewed.effective_sample_size() < 1.001);
+    }
+
+    #[test]
+    fn weighted_distribution_summaries() {
+        let dist = WeightedDistribution::new(vec![(3.0, 1.0), (1.0, 1.0), (2.0, 2.0)]).unwrap();
+        assert!((dist.mean() - 2.0).abs() < 1e-12);
+        assert!((dist.std_dev() - 0.5f64.sqrt()).abs() < 1e-12);
+        assert_eq!(dist.quantile(0.5), 2.0);
+        assert_eq!(dist.quantile(0.0), 1.0);
+        assert!((dist.cdf(2.0) - 0.75).abs() < 1e-12);
+        assert!(WeightedDistribution::new(vec![(1.0, 0.0)]).is_err());
+    }
+}
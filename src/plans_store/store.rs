@@ -0,0 +1,352 @@
+use std::{io::Write, path::{Path, PathBuf}, sync::Arc};
+
+use anyhow::{Context, Result, anyhow, ensure};
+use sha2::{Digest, Sha256};
+
+use crate::{map::Map, plan::{AssignmentCodec, Plan, now_unix}};
+
+use super::index::{INDEX_VERSION, StoreIndex, StoreOperation, StoredPlan};
+
+const INDEX_FILE: &str = "index.json";
+const OBJECTS_DIR: &str = "objects";
+
+/// A directory of named plans for one map, stored content-addressed by assignment hash.
+///
+/// Each distinct assignment is written once to `objects/<hash>.omac` (see [`AssignmentCodec`]),
+/// however many names refer to it. `index.json` maps each name to its hash, metadata, and a
+/// snapshot of scores, so plans can be listed and ranked without decoding them.
+pub struct PlanStore {
+    root: PathBuf,
+    codec: AssignmentCodec,
+    index: StoreIndex,
+}
+
+impl PlanStore {
+    /// Open the store at `root` for plans of `map`, creating it if it does not exist.
+    pub fn open(root: &Path, map: &Map) -> Result<Self> {
+        std::fs::create_dir_all(root.join(OBJECTS_DIR))
+            .with_context(|| format!("[PlanStore] Failed to create store at {}", root.display()))?;
+        let codec = AssignmentCodec::new(map)?;
+        let blocks_hash = blocks_hash(map)?;
+
+        let index_path = root.join(INDEX_FILE);
+        let index = if index_path.exists() {
+            let bytes = std::fs::read(&index_path)
+                .with_context(|| format!("[PlanStore] Failed to read {}", index_path.display()))?;
+            let mut index: StoreIndex = serde_json::from_slice(&bytes)
+                .with_context(|| format!("[PlanStore] Failed to parse {}", index_path.display()))?;
+            ensure!(index.version <= INDEX_VERSION, "[PlanStore] Unsupported store version {}", index.version);
+            ensure!(index.num_units == codec.len(),
+                "[PlanStore] Store holds plans for {} blocks, but the map has {}", index.num_units, codec.len());
+            // Stores from before version 2 only recorded the block count; they adopt this map's hash.
+            let recorded = index.blocks_hash.get_or_insert_with(|| blocks_hash.clone());
+            ensure!(*recorded == blocks_hash, "[PlanStore] Store holds plans for a map with different blocks");
+            index.version = INDEX_VERSION;
+            index
+        } else {
+            StoreIndex::new(codec.len(), blocks_hash)
+        };
+
+        Ok(Self { root: root.to_path_buf(), codec, index })
+    }
+
+    /// Get the number of named plans in the store.
+    #[inline] pub fn len(&self) -> usize { self.index.plans.len() }
+
+    /// Check if the store holds no plans.
+    #[inline] pub fn is_empty(&self) -> bool { self.index.plans.is_empty() }
+
+    /// Get the names of all stored plans, in sorted order.
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.index.plans.keys().map(String::as_str)
+    }
+
+    /// Get the index entry for a named plan.
+    pub fn entry(&self, name: &str) -> Option<&StoredPlan> { self.index.plans.get(name) }
+
+    /// Check if a plan with the given name exists.
+    pub fn contains(&self, name: &str) -> bool { self.index.plans.contains_key(name) }
+
+    /// Get the number of distinct assignments stored, after deduplication.
+    pub fn num_objects(&self) -> usize {
+        self.index.plans.values().map(|entry| &entry.hash).collect::<std::collections::HashSet<_>>().len()
+    }
+
+    /// Store `plan` under `name` with a snapshot of `scores`, replacing any plan of that name.
+    /// Returns the assignment hash; plans with identical assignments share one object.
+    pub fn put(&mut self, name: &str, plan: &Plan, scores: &[(&str, f64)]) -> Result<String> {
+        ensure!(!name.is_empty(), "[PlanStore] Plan name must not be empty");
+        let bytes = plan.to_compact_bytes(&self.codec)?;
+        let hash = hex::encode(Sha256::digest(&bytes));
+
+        let object = self.object_path(&hash);
+        if !object.exists() {
+            write_atomic(&object, &bytes)?;
+        }
+
+        let entry = StoredPlan {
+            hash: hash.clone(),
+            num_districts: plan.num_districts(),
+            metadata: plan.metadata().clone(),
+            scores: scores.iter().map(|&(key, value)| (key.to_string(), value)).collect(),
+        };
+        let replaced = self.index.plans.insert(name.to_string(), entry);
+        self.save_index()?;
+        if let Some(old) = replaced { self.collect_object(&old.hash)?; }
+        Ok(hash)
+    }
+
+    /// Load the named plan onto `map`, which must be the map the store was opened with.
+    pub fn get(&self, name: &str, map: impl Into<Arc<Map>>) -> Result<Plan> {
+        let entry = self.index.plans.get(name).ok_or_else(|| anyhow!("[PlanStore] No plan named {:?}", name))?;
+        let bytes = std::fs::read(self.object_path(&entry.hash))
+            .with_context(|| format!("[PlanStore] Missing object for plan {:?}", name))?;
+        ensure!(hex::encode(Sha256::digest(&bytes)) == entry.hash, "[PlanStore] Object for plan {:?} is corrupt", name);
+
+        let mut plan = Plan::new(map, entry.num_districts)?;
+        plan.load_compact_bytes(&self.codec, &bytes)?;
+        plan.set_metadata(entry.metadata.clone());
+        Ok(plan)
+    }
+
+    /// Remove the named plan, deleting its object if no other plan shares it.
+    /// Returns `false` if there was no such plan.
+    pub fn remove(&mut self, name: &str) -> Result<bool> {
+        let Some(entry) = self.index.plans.remove(name) else { return Ok(false) };
+        self.save_index()?;
+        self.collect_object(&entry.hash)?;
+        Ok(true)
+    }
+
+    /// Rename a stored plan. Fails if `to` is already taken.
+    pub fn rename(&mut self, from: &str, to: &str) -> Result<()> {
+        ensure!(!to.is_empty(), "[PlanStore] Plan name must not be empty");
+        ensure!(!self.contains(to), "[PlanStore] A plan named {:?} already exists", to);
+        let entry = self.index.plans.remove(from).ok_or_else(|| anyhow!("[PlanStore] No plan named {:?}", from))?;
+        self.index.plans.insert(to.to_string(), entry);
+        self.save_index()
+    }
+
+    /// Get the names of all plans with the given assignment hash (duplicates of one another).
+    pub fn names_with_hash(&self, hash: &str) -> Vec<&str> {
+        self.index.plans.iter()
+            .filter(|(_, entry)| entry.hash == hash)
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// Get `(name, score)` for every plan with a snapshot of `score`, ordered from lowest to highest.
+    pub fn ranked_by(&self, score: &str) -> Vec<(&str, f64)> {
+        let mut ranked = self.index.plans.iter()
+            .filter_map(|(name, entry)| entry.scores.get(score).map(|&value| (name.as_str(), value)))
+            .collect::<Vec<_>>();
+        ranked.sort_by(|a, b| a.1.total_cmp(&b.1));
+        ranked
+    }
+
+    /// Get the maintenance operations applied to the store, oldest first.
+    pub fn provenance(&self) -> &[StoreOperation] { &self.index.provenance }
+
+    /// Copy every plan of `other` into this store as `<prefix><name>`, e.g. to combine chains run
+    /// from several seeds. Both stores must be for the same map; fails without copying anything if
+    /// a prefixed name is already taken. Returns the number of plans copied.
+    pub fn merge(&mut self, other: &PlanStore, prefix: &str) -> Result<usize> {
+        ensure!(other.index.num_units == self.index.num_units,
+            "[PlanStore] Cannot merge a store for {} blocks into one for {}", other.index.num_units, self.index.num_units);
+        ensure!(other.index.blocks_hash == self.index.blocks_hash,
+            "[PlanStore] Cannot merge a store for a map with different blocks");
+        let taken = other.names().map(|name| format!("{prefix}{name}")).filter(|name| self.contains(name)).collect::<Vec<_>>();
+        ensure!(taken.is_empty(), "[PlanStore] Plans already exist: {}", taken.join(", "));
+
+        let before = self.len();
+        for (name, entry) in &other.index.plans {
+            let object = self.object_path(&entry.hash);
+            if !object.exists() {
+                let bytes = std::fs::read(other.object_path(&entry.hash))
+                    .with_context(|| format!("[PlanStore] Missing object for plan {:?}", name))?;
+                ensure!(hex::encode(Sha256::digest(&bytes)) == entry.hash, "[PlanStore] Object for plan {:?} is corrupt", name);
+                write_atomic(&object, &bytes)?;
+            }
+            self.index.plans.insert(format!("{prefix}{name}"), entry.clone());
+        }
+        self.record("merge", format!("source={:?} prefix={prefix:?}", other.root.display().to_string()), before)?;
+        Ok(other.len())
+    }
+
+    /// Drop burn-in and thin one chain: of the plans whose names start with `prefix`, in name
+    /// order (so sample names should be zero-padded), remove the first `burn_in`, then keep every
+    /// `every`-th of the rest. Returns the names removed.
+    pub fn thin(&mut self, prefix: &str, burn_in: usize, every: usize) -> Result<Vec<String>> {
+        ensure!(every > 0, "[PlanStore] Thinning interval must be positive");
+        let removed = self.names()
+            .filter(|name| name.starts_with(prefix))
+            .enumerate()
+            .filter(|&(i, _)| i < burn_in || !(i - burn_in).is_multiple_of(every))
+            .map(|(_, name)| name.to_string())
+            .collect::<Vec<_>>();
+        self.remove_all(&removed, "thin", format!("prefix={prefix:?} burn_in={burn_in} every={every}"))?;
+        Ok(removed)
+    }
+
+    /// Remove plans with the same assignments as an earlier plan in name order, keeping the first
+    /// name of each distinct assignment. Returns the names removed.
+    pub fn dedup(&mut self) -> Result<Vec<String>> {
+        let mut seen = std::collections::HashSet::new();
+        let removed = self.index.plans.iter()
+            .filter(|(_, entry)| !seen.insert(entry.hash.as_str()))
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>();
+        self.remove_all(&removed, "dedup", String::new())?;
+        Ok(removed)
+    }
+
+    /// Remove the named plans in one index update, recording the operation.
+    fn remove_all(&mut self, names: &[String], operation: &str, details: String) -> Result<()> {
+        let before = self.len();
+        let hashes = names.iter()
+            .filter_map(|name| self.index.plans.remove(name))
+            .map(|entry| entry.hash)
+            .collect::<Vec<_>>();
+        self.record(operation, details, before)?;
+        hashes.iter().try_for_each(|hash| self.collect_object(hash))
+    }
+
+    /// Append an operation to the store's provenance and save the index.
+    fn record(&mut self, operation: &str, details: String, plans_before: usize) -> Result<()> {
+        self.index.provenance.push(StoreOperation {
+            timestamp: now_unix(),
+            operation: operation.to_string(),
+            details,
+            plans_before,
+            plans_after: self.len(),
+        });
+        self.save_index()
+    }
+
+    fn object_path(&self, hash: &str) -> PathBuf {
+        self.root.join(OBJECTS_DIR).join(format!("{hash}.omac"))
+    }
+
+    /// Delete the object `hash` if no plan refers to it any more.
+    fn collect_object(&self, hash: &str) -> Result<()> {
+        let path = self.object_path(hash);
+        if !self.index.references(hash) && path.exists() {
+            std::fs::remove_file(&path).with_context(|| format!("[PlanStore] Failed to delete {}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    fn save_index(&self) -> Result<()> {
+        let bytes = serde_json::to_vec_pretty(&self.index).context("[PlanStore] Failed to serialize index")?;
+        write_atomic(&self.root.join(INDEX_FILE), &bytes)
+    }
+}
+
+/// SHA-256 of the map's block GEOIDs in block order, identifying the map a store's plans belong to.
+fn blocks_hash(map: &Map) -> Result<String> {
+    let mut hasher = Sha256::new();
+    for geo_id in map.base()?.geo_ids() {
+        hasher.update(geo_id.id().as_bytes());
+        hasher.update(b"\n");
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Write `bytes` to `path` through a temporary file, so readers never see a partial file.
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let mut file = tempfile::NamedTempFile::new_in(dir)
+        .with_context(|| format!("[PlanStore] Failed to create temporary file in {}", dir.display()))?;
+    file.write_all(bytes)?;
+    file.persist(path).with_context(|| format!("[PlanStore] Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_maps;
+
+    #[test]
+    fn store_dedups_and_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let map = Arc::new(test_maps::grid(4));
+        let mut plan = Plan::new(map.clone(), 2).unwrap();
+        plan.set_assignments_vec((0..16).map(|u| if u < 8 { 1 } else { 2 }).collect()).unwrap();
+
+        let mut store = PlanStore::open(dir.path(), &map).unwrap();
+        let hash = store.put("a", &plan, &[("score", 2.0)]).unwrap();
+        assert_eq!(store.put("b", &plan, &[("score", 1.0)]).unwrap(), hash);
+        assert_eq!(store.num_objects(), 1);
+        assert_eq!(store.ranked_by("score"), vec![("b", 1.0), ("a", 2.0)]);
+
+        let store = PlanStore::open(dir.path(), &map).unwrap();
+        assert_eq!(store.names_with_hash(&hash), vec!["a", "b"]);
+        assert_eq!(store.get("a", map.clone()).unwrap().get_assignments_vec().unwrap(), plan.get_assignments_vec().unwrap());
+
+        let mut store = store;
+        assert!(store.remove("a").unwrap());
+        assert!(dir.path().join(OBJECTS_DIR).join(format!("{hash}.omac")).exists());
+        assert!(store.remove("b").unwrap());
+        assert!(!dir.path().join(OBJECTS_DIR).join(format!("{hash}.omac")).exists());
+        assert!(PlanStore::open(dir.path(), &Arc::new(test_maps::grid(3))).is_err());
+    }
+
+    #[test]
+    fn stores_check_block_geoids() {
+        let (a, b) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let ids = ["170190001001000", "170190001001001", "170190001001002", "170190001001003"];
+        let (map, renamed) = (test_maps::row(4, &ids), test_maps::row(4, &[]));
+        let mut plan = Plan::new(map.clone(), 2).unwrap();
+        plan.set_assignments_vec(vec![1, 1, 2, 2]).unwrap();
+        PlanStore::open(a.path(), &map).unwrap().put("a", &plan, &[]).unwrap();
+
+        // Same block count, different blocks.
+        let error = PlanStore::open(a.path(), &renamed).err().unwrap();
+        assert!(error.to_string().contains("different blocks"), "{error}");
+        let other = PlanStore::open(b.path(), &renamed).unwrap();
+        assert!(PlanStore::open(a.path(), &map).unwrap().merge(&other, "b/").is_err());
+
+        // Version 1 indexes, without a hash, adopt the map they are next opened with.
+        let index_path = a.path().join(INDEX_FILE);
+        let mut index: serde_json::Value = serde_json::from_slice(&std::fs::read(&index_path).unwrap()).unwrap();
+        index.as_object_mut().unwrap().remove("blocks_hash");
+        index["version"] = 1.into();
+        std::fs::write(&index_path, serde_json::to_vec(&index).unwrap()).unwrap();
+        let mut store = PlanStore::open(a.path(), &map).unwrap();
+        store.put("b", &plan, &[]).unwrap();
+        assert!(PlanStore::open(a.path(), &renamed).is_err());
+    }
+
+    #[test]
+    fn merge_thin_and_dedup_record_provenance() {
+        let (a, b) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let map = Arc::new(test_maps::grid(4));
+        let plans = (0..4).map(|k| {
+            let mut plan = Plan::new(map.clone(), 2).unwrap();
+            plan.set_assignments_vec((0..16).map(|u| if u < 4 * (k % 2 + 1) { 1 } else { 2 }).collect()).unwrap();
+            plan
+        }).collect::<Vec<_>>();
+
+        let mut store = PlanStore::open(a.path(), &map).unwrap();
+        let mut other = PlanStore::open(b.path(), &map).unwrap();
+        for (k, plan) in plans.iter().enumerate() {
+            store.put(&format!("s{k}"), plan, &[]).unwrap();
+            other.put(&format!("s{k}"), plan, &[]).unwrap();
+        }
+
+        assert_eq!(store.merge(&other, "b/").unwrap(), 4);
+        assert!(store.merge(&other, "b/").is_err());
+        assert_eq!(store.len(), 8);
+
+        // Plans alternate between two assignments, so only the first of each survives dedup.
+        assert_eq!(store.thin("b/", 1, 2).unwrap(), vec!["b/s0", "b/s2"]);
+        assert_eq!(store.dedup().unwrap(), vec!["b/s3", "s1", "s2", "s3"]);
+        assert_eq!(store.names().collect::<Vec<_>>(), vec!["b/s1", "s0"]);
+        assert!(store.dedup().unwrap().is_empty());
+
+        let store = PlanStore::open(a.path(), &map).unwrap();
+        let operations = store.provenance().iter().map(|op| (op.operation.as_str(), op.plans_before, op.plans_after)).collect::<Vec<_>>();
+        assert_eq!(operations, vec![("merge", 4, 8), ("thin", 8, 6), ("dedup", 6, 2), ("dedup", 2, 2)]);
+    }
+}
@@ -0,0 +1,254 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    path::PathBuf,
+    sync::{Arc, Mutex, RwLock, atomic::{AtomicU64, Ordering}},
+};
+
+use axum::{Json, http::StatusCode, response::{IntoResponse, Response}};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use tokio::sync::broadcast;
+
+use crate::{AnnealProgress, map::Map, objective::Metric, plan::{AnnealConfig, Plan}};
+
+/// Number of finished runs whose final event is kept for late subscribers; older ones are dropped.
+const FINISHED_RUN_CAPACITY: usize = 64;
+
+/// Maps, plans, and annealing runs held by the server, keyed by the ids handed out on creation.
+pub(super) struct ServerState {
+    pub(super) pack_root: PathBuf, // Directory that map paths are resolved against
+    pub(super) maps: RwLock<HashMap<String, Arc<Map>>>,
+    pub(super) plans: RwLock<HashMap<String, Arc<Mutex<Plan>>>>,
+    pub(super) runs: RwLock<HashMap<String, Arc<Run>>>,
+    running: Mutex<HashSet<String>>,   // Ids of plans with a run in progress
+    finished: Mutex<VecDeque<String>>, // Ids of finished runs, oldest first
+    next_id: AtomicU64,
+}
+
+impl ServerState {
+    pub(super) fn new(pack_root: PathBuf) -> Self {
+        Self {
+            pack_root,
+            maps: RwLock::default(),
+            plans: RwLock::default(),
+            runs: RwLock::default(),
+            running: Mutex::default(),
+            finished: Mutex::default(),
+            next_id: AtomicU64::default(),
+        }
+    }
+
+    /// A fresh id with the given prefix, e.g. `"plan-3"`.
+    pub(super) fn new_id(&self, prefix: &str) -> String {
+        format!("{prefix}-{}", self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    pub(super) fn map(&self, id: &str) -> Result<Arc<Map>, ApiError> {
+        self.maps.read().unwrap().get(id).cloned()
+            .ok_or_else(|| ApiError::not_found(format!("No map with id {id:?}")))
+    }
+
+    pub(super) fn plan(&self, id: &str) -> Result<Arc<Mutex<Plan>>, ApiError> {
+        self.plans.read().unwrap().get(id).cloned()
+            .ok_or_else(|| ApiError::not_found(format!("No plan with id {id:?}")))
+    }
+
+    pub(super) fn run(&self, id: &str) -> Result<Arc<Run>, ApiError> {
+        self.runs.read().unwrap().get(id).cloned()
+            .ok_or_else(|| ApiError::not_found(format!("No run with id {id:?}")))
+    }
+
+    /// Fail with 409 Conflict if plan `id` has a run in progress, whose result would overwrite an edit.
+    pub(super) fn check_idle(&self, id: &str) -> Result<(), ApiError> {
+        if !self.running.lock().unwrap().contains(id) { return Ok(()) }
+        Err(ApiError::conflict(format!("Plan {id:?} has a run in progress")))
+    }
+
+    /// Mark plan `id` as having a run in progress, failing with 409 Conflict if it already has one.
+    pub(super) fn start_running(&self, id: &str) -> Result<(), ApiError> {
+        if self.running.lock().unwrap().insert(id.to_string()) { return Ok(()) }
+        Err(ApiError::conflict(format!("Plan {id:?} has a run in progress")))
+    }
+
+    pub(super) fn stop_running(&self, id: &str) {
+        self.running.lock().unwrap().remove(id);
+    }
+
+    /// Record that run `id` has ended, forgetting the oldest finished runs beyond the capacity.
+    pub(super) fn finish_run(&self, id: String) {
+        let mut finished = self.finished.lock().unwrap();
+        finished.push_back(id);
+        while finished.len() > FINISHED_RUN_CAPACITY {
+            let oldest = finished.pop_front().unwrap();
+            self.runs.write().unwrap().remove(&oldest);
+        }
+    }
+}
+
+/// Number of undelivered events kept per run; slower subscribers skip ahead.
+const RUN_EVENT_CAPACITY: usize = 256;
+
+/// A [`RunEvent`] serialized to JSON text.
+#[derive(Clone)]
+pub(super) struct RunMessage {
+    pub(super) text: String,
+    pub(super) is_final: bool,
+}
+
+/// A background annealing run, broadcasting its events to every subscriber.
+pub(super) struct Run {
+    events: broadcast::Sender<RunMessage>,
+    last: Mutex<Option<RunMessage>>, // Final event, for subscribers that arrive after the run ends
+}
+
+impl Run {
+    pub(super) fn new() -> Self {
+        Self { events: broadcast::channel(RUN_EVENT_CAPACITY).0, last: Mutex::new(None) }
+    }
+
+    /// Subscribe to the run's events. Returns the final event instead if the run has already ended.
+    pub(super) fn subscribe(&self) -> Result<broadcast::Receiver<RunMessage>, RunMessage> {
+        let receiver = self.events.subscribe();
+        match self.last.lock().unwrap().clone() {
+            Some(last) => Err(last),
+            None => Ok(receiver),
+        }
+    }
+
+    pub(super) fn publish(&self, event: &RunEvent) {
+        let text = serde_json::to_string(event).expect("run events are always serializable");
+        let message = RunMessage { text, is_final: event.is_final() };
+        if message.is_final { *self.last.lock().unwrap() = Some(message.clone()) }
+        let _ = self.events.send(message); // No subscribers is fine
+    }
+}
+
+/// An event streamed from `GET /runs/{id}/events`, tagged by `"event"`.
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub(super) enum RunEvent {
+    Progress {
+        phase: String,
+        iter: usize,
+        score: f64,
+        best_score: f64,
+        temperature: f64,
+        accept_prob: f64,
+        metrics: BTreeMap<String, f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        assignments: Option<Vec<u32>>, // Snapshot in base-layer block order (see `GET /maps/{id}/blocks`)
+    },
+    Done { assignments: Vec<u32> },
+    Failed { error: String },
+}
+
+impl RunEvent {
+    pub(super) fn progress(progress: &AnnealProgress, snapshot: bool) -> Self {
+        Self::Progress {
+            phase: progress.phase.to_string(),
+            iter: progress.iter,
+            score: progress.score,
+            best_score: progress.best_score,
+            temperature: progress.temperature,
+            accept_prob: progress.accept_prob,
+            metrics: progress.metrics.iter().map(|&(name, score)| (name.to_string(), score)).collect(),
+            assignments: snapshot.then(|| progress.assignments()),
+        }
+    }
+
+    pub(super) fn is_final(&self) -> bool { !matches!(self, Self::Progress { .. }) }
+}
+
+/// An error response: `{"error": message}` with a status code.
+#[derive(Debug)]
+pub(super) struct ApiError {
+    status: StatusCode,
+    pub(super) message: String,
+}
+
+impl ApiError {
+    pub(super) fn not_found(message: String) -> Self { Self { status: StatusCode::NOT_FOUND, message } }
+
+    pub(super) fn conflict(message: String) -> Self { Self { status: StatusCode::CONFLICT, message } }
+
+    pub(super) fn internal(message: String) -> Self { Self { status: StatusCode::INTERNAL_SERVER_ERROR, message } }
+}
+
+/// Library errors are caused by the request (unknown blocks, bad metric series, ...).
+impl From<anyhow::Error> for ApiError {
+    fn from(error: anyhow::Error) -> Self {
+        Self { status: StatusCode::BAD_REQUEST, message: format!("{error:#}") }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(json!({ "error": self.message }))).into_response()
+    }
+}
+
+#[derive(Deserialize)]
+pub(super) struct LoadMap {
+    pub(super) path: String,
+}
+
+#[derive(Serialize)]
+pub(super) struct MapInfo {
+    pub(super) id: String,
+    pub(super) layers: BTreeMap<String, usize>, // Unit count per layer
+}
+
+#[derive(Deserialize)]
+pub(super) struct CreatePlan {
+    pub(super) map: String,
+    pub(super) num_districts: u32,
+}
+
+#[derive(Serialize)]
+pub(super) struct Created {
+    pub(super) id: String,
+}
+
+#[derive(Deserialize)]
+pub(super) struct SetAssignments {
+    pub(super) assignments: BTreeMap<String, u32>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct MetricsRequest {
+    pub(super) metrics: Vec<Metric>,
+}
+
+#[derive(Serialize)]
+pub(super) struct MetricResult {
+    pub(super) metric: Metric,
+    pub(super) score: f64,
+    pub(super) districts: Vec<f64>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct RecomRequest {
+    pub(super) a: u32,
+    pub(super) b: u32,
+    pub(super) pop_series: String, // Column the two new districts are balanced by
+    #[serde(default)]
+    pub(super) apply: bool, // Keep the proposal instead of only reporting it
+}
+
+#[derive(Serialize)]
+pub(super) struct RecomProposal {
+    pub(super) changes: BTreeMap<String, u32>, // New district of each block that moved
+    pub(super) applied: bool,
+}
+
+#[derive(Deserialize)]
+pub(super) struct StartRun {
+    pub(super) config: AnnealConfig,
+    #[serde(default = "StartRun::default_snapshot_every")]
+    pub(super) snapshot_every: usize, // Attach assignments to every n-th progress event (0 for never)
+}
+
+impl StartRun {
+    fn default_snapshot_every() -> usize { 10 }
+}
@@ -0,0 +1,50 @@
+//! HTTP JSON API for loading packs, editing plans, and scoring them (feature `server`).
+//!
+//! Lets a thin web frontend drive large states without running the library in the browser.
+//! Maps and plans live in server memory and are referred to by the ids returned on creation:
+//!
+//! | Method   | Path                          | Body                               | Returns                          |
+//! |----------|-------------------------------|------------------------------------|----------------------------------|
+//! | `POST`   | `/maps`                       | `{"path"}` (relative to pack root) | `{"id", "layers"}`               |
+//! | `GET`    | `/maps/{id}/blocks`           |                                    | base-layer geo ids, in order     |
+//! | `POST`   | `/plans`                      | `{"map", "num_districts"}`         | `{"id"}`                         |
+//! | `GET`    | `/plans/{id}`                 |                                    | [`crate::PlanData`]              |
+//! | `PUT`    | `/plans/{id}/assignments`     | `{"assignments": {block: district}}` | `{"id"}`                       |
+//! | `POST`   | `/plans/{id}/metrics`         | `{"metrics": [Metric]}`            | score and per-district values    |
+//! | `POST`   | `/plans/{id}/recom`           | `{"a", "b", "pop_series", "apply"}` | changed block assignments       |
+//! | `GET`    | `/plans/{id}/districts`       |                                    | dissolved district GeoJSON       |
+//! | `DELETE` | `/plans/{id}`                 |                                    | `{"id"}`                         |
+//! | `POST`   | `/plans/{id}/runs`            | `{"config": AnnealConfig, "snapshot_every"}` | `{"id"}`               |
+//! | `GET`    | `/runs/{id}/events`           | WebSocket upgrade                  | stream of run events             |
+//!
+//! Errors are returned as `{"error": message}` with a 4xx or 5xx status.
+//!
+//! A run anneals a copy of the plan in the background and replaces the plan with the result when it
+//! finishes. Until then, edits to the plan and new runs on it fail with 409 Conflict. Its event stream sends one JSON message per batch of moves,
+//! `{"event": "progress", "phase", "iter", "score", "best_score", "temperature", "accept_prob", "metrics"}`,
+//! with an `"assignments"` snapshot (in `/maps/{id}/blocks` order) on every `snapshot_every`-th message,
+//! and ends with `{"event": "done", "assignments"}` or `{"event": "failed", "error"}`.
+//! Only the 64 most recently finished runs can still be subscribed to.
+
+mod api;
+mod routes;
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+pub use routes::router;
+
+/// Serve the API on `addr` (e.g. `"127.0.0.1:8080"`), blocking until the server stops.
+/// Map paths sent by clients are resolved against `pack_root`; paths leading outside it are rejected.
+pub fn serve(addr: &str, pack_root: impl Into<PathBuf>) -> Result<()> {
+    let app = router(pack_root);
+    let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build()
+        .context("[server] Failed to start async runtime")?;
+    runtime.block_on(async {
+        let listener = tokio::net::TcpListener::bind(addr).await
+            .with_context(|| format!("[server] Failed to bind {addr}"))?;
+        axum::serve(listener, app).await
+            .context("[server] Server stopped with an error")
+    })
+}
@@ -0,0 +1,397 @@
+use std::{path::PathBuf, sync::{Arc, Mutex, MutexGuard}};
+
+use axum::{
+    Json, Router,
+    extract::{Path as UrlPath, State, ws::{Message, WebSocket, WebSocketUpgrade}},
+    response::Response,
+    routing::{get, post, put},
+};
+use serde_json::Value;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::{map::{Map, resolve_pack_path}, partition::HullKind, plan::{Plan, PlanData}};
+
+use super::api::{
+    ApiError, Created, CreatePlan, LoadMap, MapInfo, MetricResult, MetricsRequest, RecomProposal, RecomRequest,
+    Run, RunEvent, ServerState, SetAssignments, StartRun,
+};
+
+type AppState = State<Arc<ServerState>>;
+
+/// Build the API router (see the [`crate::server`] module docs), with its own empty state, loading
+/// maps only from packs under `pack_root`. Use this to mount the API inside a larger axum application.
+pub fn router(pack_root: impl Into<PathBuf>) -> Router {
+    routes(Arc::new(ServerState::new(pack_root.into())))
+}
+
+fn routes(state: Arc<ServerState>) -> Router {
+    Router::new()
+        .route("/maps", post(load_map))
+        .route("/maps/{id}/blocks", get(blocks))
+        .route("/plans", post(create_plan))
+        .route("/plans/{id}", get(get_plan).delete(delete_plan))
+        .route("/plans/{id}/assignments", put(set_assignments))
+        .route("/plans/{id}/metrics", post(metrics))
+        .route("/plans/{id}/recom", post(recom))
+        .route("/plans/{id}/districts", get(districts))
+        .route("/plans/{id}/runs", post(start_run))
+        .route("/runs/{id}/events", get(run_events))
+        .with_state(state)
+}
+
+/// Run CPU-heavy work off the async executor.
+async fn blocking<T: Send + 'static>(work: impl FnOnce() -> Result<T, ApiError> + Send + 'static) -> Result<T, ApiError> {
+    tokio::task::spawn_blocking(work).await
+        .map_err(|e| ApiError::internal(format!("Request failed: {e}")))?
+}
+
+fn lock(plan: &Mutex<Plan>) -> Result<MutexGuard<'_, Plan>, ApiError> {
+    plan.lock().map_err(|_| ApiError::internal("Plan is unusable after a failed request".to_string()))
+}
+
+async fn load_map(State(state): AppState, Json(request): Json<LoadMap>) -> Result<Json<MapInfo>, ApiError> {
+    let path = resolve_pack_path(&state.pack_root, &request.path)?;
+    let map = blocking(move || Ok(Map::read_from_pack(&path)?)).await?;
+    let layers = map.layers_iter().map(|layer| (layer.ty().to_str().to_string(), layer.len())).collect();
+    let id = state.new_id("map");
+    state.maps.write().unwrap().insert(id.clone(), Arc::new(map));
+    Ok(Json(MapInfo { id, layers }))
+}
+
+/// Geo ids of the base layer, in the block order used by run snapshots.
+async fn blocks(State(state): AppState, UrlPath(id): UrlPath<String>) -> Result<Json<Vec<String>>, ApiError> {
+    let map = state.map(&id)?;
+    Ok(Json(map.base()?.geo_ids().iter().map(|geo_id| geo_id.id().to_string()).collect()))
+}
+
+async fn create_plan(State(state): AppState, Json(request): Json<CreatePlan>) -> Result<Json<Created>, ApiError> {
+    let plan = Plan::new(state.map(&request.map)?, request.num_districts)?;
+    let id = state.new_id("plan");
+    state.plans.write().unwrap().insert(id.clone(), Arc::new(Mutex::new(plan)));
+    Ok(Json(Created { id }))
+}
+
+async fn get_plan(State(state): AppState, UrlPath(id): UrlPath<String>) -> Result<Json<PlanData>, ApiError> {
+    let plan = state.plan(&id)?;
+    let data = lock(&plan)?.to_data()?;
+    Ok(Json(data))
+}
+
+async fn delete_plan(State(state): AppState, UrlPath(id): UrlPath<String>) -> Result<Json<Created>, ApiError> {
+    state.plans.write().unwrap().remove(&id)
+        .ok_or_else(|| ApiError::not_found(format!("No plan with id {id:?}")))?;
+    Ok(Json(Created { id }))
+}
+
+async fn set_assignments(
+    State(state): AppState,
+    UrlPath(id): UrlPath<String>,
+    Json(request): Json<SetAssignments>,
+) -> Result<Json<Created>, ApiError> {
+    let plan = state.plan(&id)?;
+    let plan_id = id.clone();
+    blocking(move || {
+        let mut plan = lock(&plan)?;
+        state.check_idle(&plan_id)?;
+        let data = PlanData {
+            num_districts: plan.num_districts(),
+            at_large_seats: plan.at_large_seats(),
+            assignments: request.assignments,
+            metadata: plan.metadata().clone(),
+        };
+        Ok(plan.set_data(&data)?)
+    }).await?;
+    Ok(Json(Created { id }))
+}
+
+async fn metrics(
+    State(state): AppState,
+    UrlPath(id): UrlPath<String>,
+    Json(request): Json<MetricsRequest>,
+) -> Result<Json<Vec<MetricResult>>, ApiError> {
+    let plan = state.plan(&id)?;
+    let results = blocking(move || {
+        let plan = lock(&plan)?;
+        Ok(request.metrics.into_iter()
+            .map(|metric| MetricResult {
+                score: plan.compute_metric_score(&metric),
+                districts: plan.compute_metric(&metric),
+                metric,
+            })
+            .collect())
+    }).await?;
+    Ok(Json(results))
+}
+
+/// Propose a ReCom step merging and resplitting districts `a` and `b`, reporting the blocks that move.
+async fn recom(
+    State(state): AppState,
+    UrlPath(id): UrlPath<String>,
+    Json(request): Json<RecomRequest>,
+) -> Result<Json<RecomProposal>, ApiError> {
+    let plan = state.plan(&id)?;
+    let proposal = blocking(move || {
+        let mut plan = lock(&plan)?;
+        if request.apply { state.check_idle(&id)? }
+        let (a, b) = (request.a, request.b);
+        let valid = 1..=plan.num_districts();
+        if a == b || !valid.contains(&a) || !valid.contains(&b) {
+            return Err(anyhow::anyhow!("[server] Invalid district pair ({a}, {b})").into())
+        }
+
+        let mut proposal = plan.clone();
+        proposal.recombine(a, b, &request.pop_series)?;
+        let changes = plan.get_assignments()?.into_iter()
+            .zip(proposal.get_assignments()?)
+            .filter(|((_, old), (_, new))| old != new)
+            .map(|(_, (geo_id, new))| (geo_id.id().to_string(), new))
+            .collect();
+        if request.apply { *plan = proposal; }
+        Ok(RecomProposal { changes, applied: request.apply })
+    }).await?;
+    Ok(Json(proposal))
+}
+
+async fn districts(State(state): AppState, UrlPath(id): UrlPath<String>) -> Result<Json<Value>, ApiError> {
+    let plan = state.plan(&id)?;
+    let geojson = blocking(move || Ok(lock(&plan)?.district_outlines_geojson(HullKind::Dissolve)?)).await?;
+    Ok(Json(geojson))
+}
+
+/// Start annealing a copy of the plan in the background. The plan is replaced by the result when
+/// the run finishes, and edits and other runs are refused until then; progress can be followed
+/// from `GET /runs/{id}/events`.
+async fn start_run(
+    State(state): AppState,
+    UrlPath(id): UrlPath<String>,
+    Json(request): Json<StartRun>,
+) -> Result<Json<Created>, ApiError> {
+    let plan = state.plan(&id)?;
+    let mut working = {
+        let plan = lock(&plan)?;
+        state.start_running(&id)?;
+        plan.clone()
+    };
+    let run = Arc::new(Run::new());
+    let run_id = state.new_id("run");
+    state.runs.write().unwrap().insert(run_id.clone(), run.clone());
+
+    let finished = run_id.clone();
+    tokio::spawn(async move {
+        let events = run.clone();
+        let result = blocking(move || {
+            let mut reports = 0;
+            working.anneal_with_progress(&request.config, |progress| {
+                let snapshot = request.snapshot_every > 0 && reports % request.snapshot_every == 0;
+                events.publish(&RunEvent::progress(progress, snapshot));
+                reports += 1;
+            })?;
+            let assignments = working.get_assignments_vec()?;
+            *lock(&plan)? = working;
+            Ok(assignments)
+        }).await;
+        state.stop_running(&id);
+        run.publish(&match result {
+            Ok(assignments) => RunEvent::Done { assignments },
+            Err(error) => RunEvent::Failed { error: error.message },
+        });
+        state.finish_run(finished);
+    });
+    Ok(Json(Created { id: run_id }))
+}
+
+/// Stream a run's events over a WebSocket, closing it after the `done` or `failed` event.
+async fn run_events(State(state): AppState, UrlPath(id): UrlPath<String>, upgrade: WebSocketUpgrade) -> Result<Response, ApiError> {
+    let run = state.run(&id)?;
+    Ok(upgrade.on_upgrade(move |socket| forward_events(socket, run)))
+}
+
+async fn forward_events(mut socket: WebSocket, run: Arc<Run>) {
+    match run.subscribe() {
+        Ok(mut events) => loop {
+            let message = match events.recv().await {
+                Ok(message) => message,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            };
+            if socket.send(Message::Text(message.text.into())).await.is_err() || message.is_final { break }
+        },
+        Err(last) => { let _ = socket.send(Message::Text(last.text.into())).await; }
+    }
+    // Wait for the client's reply, so the connection ends with a complete closing handshake.
+    if socket.send(Message::Close(None)).await.is_ok() {
+        while let Some(Ok(message)) = socket.recv().await {
+            if matches!(message, Message::Close(_)) { break }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use axum::{body::Body, http::{Method, Request, StatusCode}};
+    use futures_util::StreamExt;
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::{objective::{Metric, Objective}, plan::AnnealConfig, test_maps};
+
+    /// Send one request to the router, returning the status and the JSON body.
+    async fn call(app: &Router, method: Method, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+        let request = Request::builder().method(method).uri(uri).header("content-type", "application/json")
+            .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string()))).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn plans_are_created_assigned_and_scored() {
+        let root = tempfile::tempdir().unwrap();
+        test_maps::row(4, &[]).write_to_pack(&root.path().join("row")).unwrap();
+        let app = router(root.path());
+
+        let (status, map) = call(&app, Method::POST, "/maps", Some(json!({ "path": "row" }))).await;
+        assert_eq!(status, StatusCode::OK, "{map}");
+        assert_eq!(map["layers"]["block"], 4);
+        let map_id = map["id"].as_str().unwrap();
+        let (_, blocks) = call(&app, Method::GET, &format!("/maps/{map_id}/blocks"), None).await;
+        let blocks = blocks.as_array().unwrap().iter().map(|id| id.as_str().unwrap().to_string()).collect::<Vec<_>>();
+
+        let (status, plan) = call(&app, Method::POST, "/plans", Some(json!({ "map": map_id, "num_districts": 2 }))).await;
+        assert_eq!(status, StatusCode::OK, "{plan}");
+        let plan_id = plan["id"].as_str().unwrap();
+        let assignments = blocks.iter().enumerate().map(|(i, id)| (id.clone(), 1 + i as u32 / 2)).collect::<BTreeMap<_, _>>();
+        let (status, _) = call(&app, Method::PUT, &format!("/plans/{plan_id}/assignments"), Some(json!({ "assignments": assignments }))).await;
+        assert_eq!(status, StatusCode::OK);
+        let (_, data) = call(&app, Method::GET, &format!("/plans/{plan_id}"), None).await;
+        assert_eq!(serde_json::from_value::<PlanData>(data).unwrap().assignments, assignments);
+
+        let metrics = json!({ "metrics": [Metric::population_deviation("pop".into())] });
+        let (status, results) = call(&app, Method::POST, &format!("/plans/{plan_id}/metrics"), Some(metrics)).await;
+        assert_eq!(status, StatusCode::OK, "{results}");
+        assert_eq!(results[0]["districts"], json!([0.0, 0.0]));
+
+        let (status, _) = call(&app, Method::DELETE, &format!("/plans/{plan_id}"), None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, error) = call(&app, Method::GET, &format!("/plans/{plan_id}"), None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(error["error"].as_str().unwrap().contains(plan_id));
+    }
+
+    #[tokio::test]
+    async fn bad_requests_are_rejected() {
+        let root = tempfile::tempdir().unwrap();
+        let packs = root.path().join("packs");
+        test_maps::row(4, &[]).write_to_pack(&packs.join("row")).unwrap();
+        test_maps::row(4, &[]).write_to_pack(&root.path().join("outside")).unwrap();
+        let app = router(&packs);
+
+        // Map paths may not leave the pack root.
+        let outside = root.path().join("outside").display().to_string();
+        for path in ["../outside", outside.as_str(), "missing"] {
+            let (status, error) = call(&app, Method::POST, "/maps", Some(json!({ "path": path }))).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{path}");
+            assert!(error["error"].is_string());
+        }
+
+        let (status, _) = call(&app, Method::POST, "/plans", Some(json!({ "map": "map-9", "num_districts": 2 }))).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (_, map) = call(&app, Method::POST, "/maps", Some(json!({ "path": "./row" }))).await;
+        let (_, plan) = call(&app, Method::POST, "/plans", Some(json!({ "map": map["id"], "num_districts": 2 }))).await;
+        let plan_id = plan["id"].as_str().unwrap();
+        let unknown = json!({ "assignments": { "999999999999999": 1 } });
+        let (status, _) = call(&app, Method::PUT, &format!("/plans/{plan_id}/assignments"), Some(unknown)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let pair = json!({ "a": 1, "b": 1, "pop_series": "pop" });
+        let (status, _) = call(&app, Method::POST, &format!("/plans/{plan_id}/recom"), Some(pair)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn runs_stream_events_and_hold_off_edits() {
+        let root = tempfile::tempdir().unwrap();
+        test_maps::row(4, &[]).write_to_pack(&root.path().join("row")).unwrap();
+        let state = Arc::new(ServerState::new(root.path().into()));
+        let app = routes(state.clone());
+
+        let (_, map) = call(&app, Method::POST, "/maps", Some(json!({ "path": "row" }))).await;
+        let (_, plan) = call(&app, Method::POST, "/plans", Some(json!({ "map": map["id"], "num_districts": 2 }))).await;
+        let plan_id = plan["id"].as_str().unwrap();
+        let (_, blocks) = call(&app, Method::GET, &format!("/maps/{}/blocks", map["id"].as_str().unwrap()), None).await;
+        let assignments = blocks.as_array().unwrap().iter().zip([1, 2, 2, 2]).map(|(id, district)| (id.as_str().unwrap().to_string(), district)).collect::<BTreeMap<_, _>>();
+        let (status, _) = call(&app, Method::PUT, &format!("/plans/{plan_id}/assignments"), Some(json!({ "assignments": assignments }))).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let config = AnnealConfig {
+            objectives: vec![Objective::new(vec![Metric::population_deviation("pop".into())], None)],
+            max_iter: 200,
+            phase_start_probs: vec![0.5],
+            phase_end_probs: vec![Some(0.01)],
+            phase_cooling_rates: vec![0.1],
+            init_temp: 1.0,
+            early_stop_iters: 100,
+            temp_search_batch_size: 10,
+            batch_size: 10,
+            stop: Default::default(),
+            proposals: vec![],
+            constraints: Default::default(),
+            profile: false,
+        };
+        let run = json!({ "config": config, "snapshot_every": 1 });
+
+        // While a run is in progress, its result would overwrite edits, so they are refused.
+        state.start_running(plan_id).unwrap();
+        let (status, error) = call(&app, Method::PUT, &format!("/plans/{plan_id}/assignments"), Some(json!({ "assignments": assignments }))).await;
+        assert_eq!(status, StatusCode::CONFLICT, "{error}");
+        let (status, _) = call(&app, Method::POST, &format!("/plans/{plan_id}/runs"), Some(run.clone())).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let pair = json!({ "a": 1, "b": 2, "pop_series": "pop", "apply": true });
+        let (status, _) = call(&app, Method::POST, &format!("/plans/{plan_id}/recom"), Some(pair)).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        state.stop_running(plan_id);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = app.clone();
+        tokio::spawn(async move { axum::serve(listener, server).await });
+
+        let (status, run) = call(&app, Method::POST, &format!("/plans/{plan_id}/runs"), Some(run)).await;
+        assert_eq!(status, StatusCode::OK, "{run}");
+        let url = format!("ws://{addr}/runs/{}/events", run["id"].as_str().unwrap());
+        let (mut socket, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+
+        // Late subscribers get only the final event, so the stream ends with it either way.
+        let mut events = vec![];
+        while let Some(message) = socket.next().await {
+            let message = message.unwrap();
+            if message.is_text() { events.push(serde_json::from_str::<Value>(message.to_text().unwrap()).unwrap()) }
+        }
+        let done = events.pop().unwrap();
+        assert_eq!(done["event"], "done", "{done}");
+        assert!(events.iter().all(|event| event["event"] == "progress" && event["assignments"].as_array().unwrap().len() == 4));
+
+        // The result replaced the plan, which can be edited again.
+        let (_, data) = call(&app, Method::GET, &format!("/plans/{plan_id}"), None).await;
+        let data = serde_json::from_value::<PlanData>(data).unwrap();
+        assert_eq!(data.assignments.values().copied().collect::<Vec<_>>(), serde_json::from_value::<Vec<u32>>(done["assignments"].clone()).unwrap());
+        let (status, _) = call(&app, Method::PUT, &format!("/plans/{plan_id}/assignments"), Some(json!({ "assignments": assignments }))).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[test]
+    fn finished_runs_are_pruned() {
+        let state = ServerState::new(PathBuf::new());
+        let ids = (0..100).map(|_| state.new_id("run")).collect::<Vec<_>>();
+        for id in &ids {
+            state.runs.write().unwrap().insert(id.clone(), Arc::new(Run::new()));
+            state.finish_run(id.clone());
+        }
+        assert_eq!(state.runs.read().unwrap().len(), 64);
+        assert!(state.run(&ids[35]).is_err() && state.run(&ids[36]).is_ok());
+    }
+}
@@ -0,0 +1,42 @@
+//! Small synthetic maps of unit squares shared by unit tests.
+
+use geo::{MultiPolygon, polygon};
+
+use crate::Map;
+
+/// Axis-aligned rectangle spanning `x0..x1` by `y0..y1`.
+pub(crate) fn rect(x0: f64, x1: f64, y0: f64, y1: f64) -> MultiPolygon<f64> {
+    MultiPolygon(vec![polygon![(x: x0, y: y0), (x: x1, y: y0), (x: x1, y: y1), (x: x0, y: y1)]])
+}
+
+/// An `n`×`n` grid of squares of side `size`, in columns: unit `k` sits in column `k / n`, row `k % n`.
+pub(crate) fn grid_geoms(n: usize, size: f64) -> Vec<MultiPolygon<f64>> {
+    (0..n * n).map(|k| {
+        // Scale integer corners so that neighbors share bit-identical edges.
+        let [x0, x1, y0, y1] = [k / n, k / n + 1, k % n, k % n + 1].map(|i| i as f64 * size);
+        rect(x0, x1, y0, y1)
+    }).collect()
+}
+
+/// A row of `n` unit squares, left to right.
+pub(crate) fn row_geoms(n: usize) -> Vec<MultiPolygon<f64>> {
+    (0..n).map(|k| rect(k as f64, k as f64 + 1.0, 0.0, 1.0)).collect()
+}
+
+/// An `n`×`n` grid of unit squares (see [`grid_geoms`]) with one person in each.
+pub(crate) fn grid(n: usize) -> Map {
+    Map::from_parts(grid_geoms(n, 1.0), None, vec![("pop".into(), vec![1.0; n * n])], &[]).unwrap()
+}
+
+/// A row of `n` unit squares with one person in each, with the given block GEOIDs (numbered from
+/// zero if `ids` is empty).
+pub(crate) fn row(n: usize, ids: &[&str]) -> Map {
+    row_with(n, ids, vec![("pop".into(), vec![1.0; n])])
+}
+
+/// A row of `n` unit squares with the given block GEOIDs (numbered from zero if `ids` is empty)
+/// and data columns.
+pub(crate) fn row_with(n: usize, ids: &[&str], columns: Vec<(String, Vec<f64>)>) -> Map {
+    let ids = (!ids.is_empty()).then(|| ids.iter().map(|id| id.to_string()).collect());
+    Map::from_parts(row_geoms(n), ids, columns, &[]).unwrap()
+}